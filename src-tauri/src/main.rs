@@ -0,0 +1,157 @@
+// Desktop shell for the playground
+// Shows the Trunk build in a native window and runs the Bash, Read, Write,
+// Edit, Glob, Grep and LS tools for real through the `run_tool` command.
+// Nothing the webview sends is trusted: tool settings that allow more than
+// before and every call that changes the machine are confirmed by the user in
+// a native dialog, and each call is checked against the settings in force.
+
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+
+mod policy;
+mod tools;
+
+use policy::Policy;
+use serde_json::Value;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::{Mutex, MutexGuard};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+/// Saved in the app's config directory once the user allows it
+const POLICY_FILE: &str = "tool_policy.json";
+/// Characters of a call's arguments shown in its approval dialog
+const MAX_PREVIEW_CHARS: usize = 2000;
+
+/// The tool policy in force, and approved calls waiting to run by token
+#[derive(Default)]
+struct ToolState {
+    policy: Mutex<Policy>,
+    approvals: Mutex<HashMap<String, (String, Value)>>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Ask the user in a native dialog, which scripts in the webview can't answer
+async fn confirm(app: &AppHandle, title: &str, message: String) -> Result<bool, String> {
+    let app = app.clone();
+    let title = title.to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        app.dialog()
+            .message(message)
+            .title(title)
+            .kind(MessageDialogKind::Warning)
+            .buttons(MessageDialogButtons::OkCancelCustom("Allow".to_string(), "Deny".to_string()))
+            .blocking_show()
+    })
+    .await
+    .map_err(|e| format!("Approval dialog failed: {}", e))
+}
+
+/// A single-use token that can't be guessed from the webview
+fn new_token() -> String {
+    (0..2)
+        .map(|_| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u128(std::time::UNIX_EPOCH.elapsed().map_or(0, |d| d.as_nanos()));
+            format!("{:016x}", hasher.finish())
+        })
+        .collect()
+}
+
+/// Put the desktop tool settings in force. Settings that allow more than the
+/// ones already allowed need the user's confirmation first.
+#[tauri::command]
+async fn set_tool_policy(app: AppHandle, state: State<'_, ToolState>, policy: String) -> Result<(), String> {
+    let policy: Policy = serde_json::from_str(&policy).map_err(|e| format!("Invalid tool settings: {}", e))?;
+    let current = lock(&state.policy).clone();
+    if policy == current {
+        return Ok(());
+    }
+    if !policy.within(&current) && !confirm(&app, "Allow desktop tools?", policy.describe()).await? {
+        return Err("The user didn't allow these desktop tool settings".to_string());
+    }
+    if let Ok(dir) = app.path().app_config_dir() {
+        let saved = std::fs::create_dir_all(&dir)
+            .and_then(|_| std::fs::write(dir.join(POLICY_FILE), serde_json::to_string(&policy).unwrap_or_default()));
+        if let Err(e) = saved {
+            eprintln!("Couldn't save the tool settings: {}", e);
+        }
+    }
+    *lock(&state.policy) = policy;
+    Ok(())
+}
+
+/// Ask the user about one call; returns the token `run_tool` needs to run it
+#[tauri::command]
+async fn approve_tool(
+    app: AppHandle,
+    state: State<'_, ToolState>,
+    tool: String,
+    arguments: String,
+) -> Result<String, String> {
+    let arguments: Value =
+        serde_json::from_str(&arguments).map_err(|e| format!("Invalid arguments: {}", e))?;
+    // Calls that would be refused anyway aren't worth asking about
+    tools::check(&lock(&state.policy).clone(), &tool, &arguments)?;
+    let mut preview = serde_json::to_string_pretty(&arguments).unwrap_or_default();
+    if preview.chars().count() > MAX_PREVIEW_CHARS {
+        preview = format!("{}…", preview.chars().take(MAX_PREVIEW_CHARS).collect::<String>());
+    }
+    let message = format!("The model wants to run {} with:\n\n{}", tool, preview);
+    if !confirm(&app, &format!("Allow {}?", tool), message).await? {
+        return Err("The user denied this tool call".to_string());
+    }
+    let token = new_token();
+    lock(&state.approvals).insert(token.clone(), (tool, arguments));
+    Ok(token)
+}
+
+/// Run one tool call; the arguments and the result travel as JSON text. Calls
+/// other than auto-approved reads need the token their approval returned.
+#[tauri::command]
+async fn run_tool(
+    state: State<'_, ToolState>,
+    tool: String,
+    arguments: String,
+    approval: Option<String>,
+) -> Result<String, String> {
+    let arguments: Value =
+        serde_json::from_str(&arguments).map_err(|e| format!("Invalid arguments: {}", e))?;
+    let policy = lock(&state.policy).clone();
+    if !(policy.auto_approve_reads && tools::is_read_only(&tool)) {
+        let approved = approval.and_then(|token| lock(&state.approvals).remove(&token));
+        if approved.as_ref() != Some(&(tool.clone(), arguments.clone())) {
+            return Err("This tool call wasn't approved".to_string());
+        }
+    }
+    let result = tauri::async_runtime::spawn_blocking(move || tools::run(&policy, &tool, &arguments))
+        .await
+        .map_err(|e| format!("Tool task failed: {}", e))??;
+    Ok(result.to_string())
+}
+
+fn main() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_dialog::init())
+        .manage(ToolState::default())
+        .setup(|app| {
+            // Settings the user allowed in an earlier run
+            let saved = app
+                .path()
+                .app_config_dir()
+                .ok()
+                .and_then(|dir| std::fs::read_to_string(dir.join(POLICY_FILE)).ok())
+                .and_then(|text| serde_json::from_str::<Policy>(&text).ok());
+            if let Some(policy) = saved {
+                *lock(&app.state::<ToolState>().policy) = policy;
+            }
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![set_tool_policy, approve_tool, run_tool])
+        .run(tauri::generate_context!())
+        .expect("error while running the desktop app");
+}
@@ -0,0 +1,222 @@
+// Tool policy
+// The directories the file tools may touch, the programs Bash may start and
+// whether read-only tools run without asking. The webview proposes a policy,
+// but one allowing more than the user has already allowed only takes effect
+// once they confirm it in a native dialog. Every call is checked against the
+// policy in force here, whatever the frontend already checked.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+/// Options that make an allowed program start other programs. This is a best
+/// effort: a program can still run what its own configuration tells it to.
+const PROGRAM_RUNNING_OPTIONS: [(&str, &[&str]); 3] = [
+    ("find", &["-exec", "-execdir", "-ok", "-okdir"]),
+    ("rg", &["--pre"]),
+    (
+        "git",
+        &[
+            "-c",
+            "--config-env",
+            "--exec-path",
+            "-O",
+            "--open-files-in-pager",
+            "--ext-diff",
+            "--upload-pack",
+            "--receive-pack",
+            "-x",
+            "--exec",
+            "config",
+            "difftool",
+            "mergetool",
+            "filter-branch",
+            "submodule",
+            "bisect",
+        ],
+    ),
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    /// Directories the file tools may touch, subdirectories included
+    pub allowed_roots: Vec<String>,
+    /// Programs Bash may start; "*" allows any
+    pub allowed_commands: Vec<String>,
+    /// Run Read, Glob, Grep and LS without a token from `approve_tool`
+    pub auto_approve_reads: bool,
+}
+
+impl Policy {
+    fn allows_any_command(&self) -> bool {
+        self.allowed_commands.iter().any(|c| c.trim() == "*")
+    }
+
+    /// The allowed roots with symlinks resolved; roots that don't exist are
+    /// left out. LLM_PLAYGROUND_ROOTS, when set, caps them further.
+    pub fn roots(&self) -> Vec<PathBuf> {
+        let cap: Option<Vec<PathBuf>> = std::env::var_os("LLM_PLAYGROUND_ROOTS")
+            .map(|roots| std::env::split_paths(&roots).filter_map(|root| root.canonicalize().ok()).collect());
+        self.allowed_roots
+            .iter()
+            .filter_map(|root| Path::new(root.trim()).canonicalize().ok())
+            .filter(|root| cap.as_ref().is_none_or(|cap| cap.iter().any(|dir| root.starts_with(dir))))
+            .collect()
+    }
+
+    /// Whether everything this policy allows is also allowed by `other`
+    pub fn within(&self, other: &Policy) -> bool {
+        let other_roots = other.roots();
+        let roots_within = self
+            .roots()
+            .iter()
+            .all(|root| other_roots.iter().any(|allowed| root.starts_with(allowed)));
+        let commands_within = other.allows_any_command()
+            || self
+                .allowed_commands
+                .iter()
+                .all(|command| other.allowed_commands.iter().any(|c| c.trim() == command.trim()));
+        roots_within && commands_within && (other.auto_approve_reads || !self.auto_approve_reads)
+    }
+
+    /// What the native dialog shows before the policy takes effect
+    pub fn describe(&self) -> String {
+        let roots = self.roots();
+        let roots = if roots.is_empty() {
+            "none".to_string()
+        } else {
+            roots.iter().map(|root| root.display().to_string()).collect::<Vec<_>>().join("\n")
+        };
+        let commands = if self.allows_any_command() {
+            "any program".to_string()
+        } else {
+            self.allowed_commands.join(", ")
+        };
+        format!(
+            "Directories the tools may read and change:\n{}\n\nPrograms Bash may start, after asking:\n{}\n\nRead, Glob, Grep and LS ask first: {}",
+            roots,
+            commands,
+            if self.auto_approve_reads { "no" } else { "yes" }
+        )
+    }
+
+    /// `path` with symlinks resolved, which must be absolute, free of `..`
+    /// and inside one of the allowed roots
+    pub fn check_path(&self, path: &Path) -> Result<PathBuf, String> {
+        if !path.is_absolute() {
+            return Err(format!("{} is not an absolute path", path.display()));
+        }
+        if path.components().any(|c| c == Component::ParentDir) {
+            return Err(format!("{} must not contain ..", path.display()));
+        }
+        let roots = self.roots();
+        if roots.is_empty() {
+            return Err("No allowed directories are configured".to_string());
+        }
+        let resolved = resolve(path);
+        if roots.iter().any(|root| resolved.starts_with(root)) {
+            Ok(resolved)
+        } else {
+            Err(format!("{} is outside the allowed directories", path.display()))
+        }
+    }
+
+    /// Every program the command starts must be allowed, by name and without
+    /// options that start other programs. Command substitution, environment
+    /// assignments and paths to programs can start anything, so they need "*".
+    pub fn check_command(&self, command: &str) -> Result<(), String> {
+        if self.allows_any_command() {
+            return Ok(());
+        }
+        if command.contains('`') || command.contains("$(") {
+            return Err("Command substitution needs every program to be allowed".to_string());
+        }
+        // `2>&1` style redirections are not command separators
+        let command = command.replace(">&", ">").replace("<&", "<");
+        let separators: &[char] = &[';', '|', '&', '\n', '(', ')'];
+        for segment in command.split(separators) {
+            let mut words = segment.split_whitespace();
+            let Some(program) = words.next() else {
+                continue;
+            };
+            if program.contains('=') {
+                return Err("Setting environment variables needs every program to be allowed".to_string());
+            }
+            if program.contains('/') || program.contains('\\') {
+                return Err(format!("{} must be named without a path", program));
+            }
+            if !self.allowed_commands.iter().any(|c| c.trim() == program) {
+                return Err(format!("{} is not an allowed program", program));
+            }
+            let blocked = PROGRAM_RUNNING_OPTIONS
+                .iter()
+                .find(|(name, _)| *name == program)
+                .map_or(&[][..], |(_, options)| options);
+            for word in words {
+                let word = word.trim_matches(|c| c == '"' || c == '\'');
+                let option = word.split('=').next().unwrap_or(word);
+                // Short options can carry their value, as in `-Osh`
+                let matches = |blocked: &&str| {
+                    *blocked == option || (blocked.len() == 2 && blocked.starts_with('-') && option.starts_with(*blocked))
+                };
+                if blocked.iter().any(matches) {
+                    return Err(format!("{} {} can start other programs", program, word));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Canonical path with symlinks resolved; for a path that doesn't exist yet,
+/// its closest existing ancestor is resolved instead
+pub fn resolve(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => resolve(parent).join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(commands: &[&str]) -> Policy {
+        Policy {
+            allowed_roots: vec![std::env::temp_dir().display().to_string()],
+            allowed_commands: commands.iter().map(|c| c.to_string()).collect(),
+            auto_approve_reads: true,
+        }
+    }
+
+    #[test]
+    fn commands_must_not_start_other_programs() {
+        let policy = policy(&["ls", "cat", "grep", "find", "git", "rg"]);
+        assert!(policy.check_command("git status && ls | grep src 2>&1").is_ok());
+        assert!(policy.check_command("find . -name '*.rs'").is_ok());
+        assert!(policy.check_command("find . -exec sh -c id \\;").is_err());
+        assert!(policy.check_command("git -c core.pager=sh log").is_err());
+        assert!(policy.check_command("git log --open-files-in-pager=sh").is_err());
+        assert!(policy.check_command("git grep -Osh main").is_err());
+        assert!(policy.check_command("git diff --cached").is_ok());
+        assert!(policy.check_command("rg --pre=./run pattern").is_err());
+        assert!(policy.check_command("LD_PRELOAD=/tmp/x.so ls").is_err());
+        assert!(policy.check_command("/tmp/bin/ls").is_err());
+        assert!(policy.check_command("ls; rm -rf /").is_err());
+        assert!(policy.check_command("cat $(which curl)").is_err());
+        assert!(Policy { allowed_commands: vec!["*".to_string()], ..policy }.check_command("rm -rf build").is_ok());
+    }
+
+    #[test]
+    fn wider_policies_are_not_within_narrower_ones() {
+        let approved = policy(&["ls", "cat"]);
+        assert!(policy(&["ls"]).within(&approved));
+        assert!(!policy(&["ls", "sh"]).within(&approved));
+        assert!(!Policy { allowed_roots: vec!["/".to_string()], ..policy(&["ls"]) }.within(&approved));
+        assert!(!policy(&["ls"]).within(&Policy { auto_approve_reads: false, ..approved.clone() }));
+        assert!(policy(&["sh"]).within(&Policy { allowed_commands: vec!["*".to_string()], ..approved }));
+    }
+}
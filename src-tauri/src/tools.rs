@@ -0,0 +1,613 @@
+// Tool implementations
+// Every call is checked against the policy in force: file tools only reach
+// paths inside its roots once symlinks are resolved, and Bash only starts the
+// programs it allows. Results have the same shape as the tools' mock responses.
+
+use crate::policy::Policy;
+use regex::RegexBuilder;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+const DEFAULT_TIMEOUT_MS: u64 = 120_000;
+const MAX_TIMEOUT_MS: u64 = 600_000;
+const DEFAULT_READ_LIMIT: usize = 2000;
+/// Upper bound on Glob and Grep results
+const MAX_RESULTS: usize = 1000;
+/// Directories Glob and Grep don't descend into
+const SKIPPED_DIRS: [&str; 3] = [".git", "target", "node_modules"];
+/// Longest wait for a finished command's output; a program it left running in
+/// the background can hold the pipes open
+const OUTPUT_GRACE: Duration = Duration::from_secs(2);
+
+/// Tools that only look at the filesystem
+pub fn is_read_only(tool: &str) -> bool {
+    matches!(tool, "Read" | "Glob" | "Grep" | "LS")
+}
+
+/// Check a call against `policy` without running it
+pub fn check(policy: &Policy, tool: &str, args: &Value) -> Result<(), String> {
+    match tool {
+        "Bash" => policy.check_command(text(args, "command")?),
+        "Read" | "Write" | "Edit" => checked_path(policy, args, "file_path").map(|_| ()),
+        "Glob" | "Grep" | "LS" => checked_path(policy, args, "path").map(|_| ()),
+        _ => Err(format!("Unknown tool: {}", tool)),
+    }
+}
+
+pub fn run(policy: &Policy, tool: &str, args: &Value) -> Result<Value, String> {
+    check(policy, tool, args)?;
+    match tool {
+        "Bash" => bash(args),
+        "Read" => read(policy, args),
+        "Write" => write(policy, args),
+        "Edit" => edit(policy, args),
+        "Glob" => glob_files(policy, args),
+        "Grep" => grep(policy, args),
+        "LS" => list(policy, args),
+        _ => Err(format!("Unknown tool: {}", tool)),
+    }
+}
+
+fn text<'a>(args: &'a Value, key: &str) -> Result<&'a str, String> {
+    args.get(key)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| format!("Missing {}", key))
+}
+
+fn number(args: &Value, key: &str) -> Option<u64> {
+    args.get(key)
+        .and_then(|v| v.as_f64())
+        .filter(|n| *n >= 0.0)
+        .map(|n| n as u64)
+}
+
+fn flag(args: &Value, key: &str) -> bool {
+    args.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+}
+
+/// The path under `key` with symlinks resolved, inside the policy's roots
+fn checked_path(policy: &Policy, args: &Value, key: &str) -> Result<PathBuf, String> {
+    policy.check_path(Path::new(text(args, key)?))
+}
+
+fn bash(args: &Value) -> Result<Value, String> {
+    let command = text(args, "command")?;
+    let timeout = Duration::from_millis(
+        number(args, "timeout")
+            .unwrap_or(DEFAULT_TIMEOUT_MS)
+            .min(MAX_TIMEOUT_MS),
+    );
+
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    // Its own process group, so a timeout stops everything it started
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut shell, 0);
+    let mut child = shell
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start the shell: {}", e))?;
+
+    // Read both pipes while waiting so a chatty command can't fill them and block
+    let stdout = PipeReader::start(child.stdout.take());
+    let stderr = PipeReader::start(child.stderr.take());
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break Some(status);
+        }
+        if started.elapsed() >= timeout {
+            kill_tree(&mut child);
+            break None;
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+
+    let mut result = json!({
+        "stdout": stdout.finish(),
+        "stderr": stderr.finish(),
+        "exit_code": status.and_then(|s| s.code()),
+    });
+    if status.is_none() {
+        result["timed_out"] = json!(true);
+    }
+    Ok(result)
+}
+
+/// Stop the shell and, on Unix, every process in its group
+fn kill_tree(child: &mut Child) {
+    #[cfg(unix)]
+    // SAFETY: kill only sends a signal; the group was made for this shell
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Reads a pipe on its own thread, keeping what it got so far
+struct PipeReader {
+    bytes: Arc<Mutex<Vec<u8>>>,
+    /// Disconnects when the pipe closes
+    closed: mpsc::Receiver<()>,
+}
+
+impl PipeReader {
+    fn start(pipe: Option<impl Read + Send + 'static>) -> Self {
+        let bytes = Arc::new(Mutex::new(Vec::new()));
+        let (done, closed) = mpsc::channel();
+        let buffer = bytes.clone();
+        thread::spawn(move || {
+            let _done = done;
+            let Some(mut pipe) = pipe else {
+                return;
+            };
+            let mut chunk = [0; 8192];
+            while let Ok(read) = pipe.read(&mut chunk) {
+                if read == 0 {
+                    break;
+                }
+                buffer.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(&chunk[..read]);
+            }
+        });
+        Self { bytes, closed }
+    }
+
+    /// The output, once the pipe closes or OUTPUT_GRACE has passed
+    fn finish(self) -> String {
+        let _ = self.closed.recv_timeout(OUTPUT_GRACE);
+        let bytes = self.bytes.lock().unwrap_or_else(|e| e.into_inner());
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+fn read(policy: &Policy, args: &Value) -> Result<Value, String> {
+    let path = checked_path(policy, args, "file_path")?;
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    // `offset` is the 1-based line to start from
+    let offset = number(args, "offset").unwrap_or(1).max(1) as usize;
+    let limit = number(args, "limit")
+        .map(|n| n as usize)
+        .unwrap_or(DEFAULT_READ_LIMIT);
+
+    let total_lines = content.lines().count();
+    let lines: Vec<&str> = content.lines().skip(offset - 1).take(limit).collect();
+    Ok(json!({
+        "content": lines.join("\n"),
+        "lines": lines.len(),
+        "total_lines": total_lines,
+        "truncated": offset - 1 + lines.len() < total_lines,
+    }))
+}
+
+fn write(policy: &Policy, args: &Value) -> Result<Value, String> {
+    let path = checked_path(policy, args, "file_path")?;
+    let content = text(args, "content")?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(json!({
+        "success": true,
+        "bytes_written": content.len(),
+        "file": path.display().to_string(),
+    }))
+}
+
+fn edit(policy: &Policy, args: &Value) -> Result<Value, String> {
+    let path = checked_path(policy, args, "file_path")?;
+    let old_string = text(args, "old_string")?;
+    let new_string = text(args, "new_string")?;
+    let replace_all = flag(args, "replace_all");
+    if old_string.is_empty() {
+        return Err("old_string must not be empty".to_string());
+    }
+    if old_string == new_string {
+        return Err("old_string and new_string are the same".to_string());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let replacements = match content.matches(old_string).count() {
+        0 => return Err(format!("old_string was not found in {}", path.display())),
+        n if n > 1 && !replace_all => {
+            return Err(format!(
+                "old_string appears {} times in {}; add more context to make it unique or set replace_all",
+                n,
+                path.display()
+            ))
+        }
+        n => n,
+    };
+    let updated = if replace_all {
+        content.replace(old_string, new_string)
+    } else {
+        content.replacen(old_string, new_string, 1)
+    };
+    fs::write(&path, updated).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(json!({
+        "success": true,
+        "replacements": replacements,
+        "file": path.display().to_string(),
+    }))
+}
+
+fn is_skipped(path: &Path) -> bool {
+    path.components().any(|c| match c {
+        Component::Normal(name) => SKIPPED_DIRS.iter().any(|skipped| name == *skipped),
+        _ => false,
+    })
+}
+
+fn glob_files(policy: &Policy, args: &Value) -> Result<Value, String> {
+    let base = checked_path(policy, args, "path")?;
+    let pattern = text(args, "pattern")?;
+    if Path::new(pattern).is_absolute() || pattern.split(['/', '\\']).any(|part| part == "..") {
+        return Err("The pattern must be relative to path".to_string());
+    }
+
+    let full_pattern = format!(
+        "{}/{}",
+        glob::Pattern::escape(&base.to_string_lossy()),
+        pattern
+    );
+    let entries = glob::glob(&full_pattern).map_err(|e| format!("Invalid pattern: {}", e))?;
+    let mut files: Vec<(PathBuf, u64)> = entries
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file())
+        .filter(|path| !is_skipped(path.strip_prefix(&base).unwrap_or(path)))
+        // Symlinks may point out of the allowed directories
+        .filter(|path| policy.check_path(path).is_ok())
+        .map(|path| {
+            let modified = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            (path, modified)
+        })
+        .collect();
+    // Most recently modified first
+    files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let count = files.len();
+    let files: Vec<String> = files
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(path, _)| path.display().to_string())
+        .collect();
+    Ok(json!({ "files": files, "count": count, "truncated": count > MAX_RESULTS }))
+}
+
+/// File extensions for Grep's `type` filter
+fn type_extensions(file_type: &str) -> Vec<&str> {
+    match file_type {
+        "rust" => vec!["rs"],
+        "js" => vec!["js", "jsx", "mjs", "cjs"],
+        "ts" => vec!["ts", "tsx"],
+        "py" => vec!["py"],
+        "c" => vec!["c", "h"],
+        "cpp" => vec!["cpp", "cc", "cxx", "hpp", "h"],
+        "md" => vec!["md", "markdown"],
+        "yaml" => vec!["yaml", "yml"],
+        other => vec![other],
+    }
+}
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+        return;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.filter_map(Result::ok) {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let entry_path = entry.path();
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS
+                .iter()
+                .any(|skipped| entry.file_name() == *skipped)
+            {
+                collect_files(&entry_path, files);
+            }
+        } else if file_type.is_file() {
+            files.push(entry_path);
+        }
+    }
+}
+
+fn grep(policy: &Policy, args: &Value) -> Result<Value, String> {
+    let root = checked_path(policy, args, "path")?;
+    let multiline = flag(args, "multiline");
+    let regex = RegexBuilder::new(text(args, "pattern")?)
+        .case_insensitive(flag(args, "-i"))
+        .multi_line(true)
+        .dot_matches_new_line(multiline)
+        .build()
+        .map_err(|e| format!("Invalid pattern: {}", e))?;
+    let file_filter = match args.get("glob").and_then(|v| v.as_str()) {
+        Some(pattern) => {
+            Some(glob::Pattern::new(pattern).map_err(|e| format!("Invalid glob: {}", e))?)
+        }
+        None => None,
+    };
+    let extensions = args
+        .get("type")
+        .and_then(|v| v.as_str())
+        .map(type_extensions);
+    let output_mode = args
+        .get("output_mode")
+        .and_then(|v| v.as_str())
+        .unwrap_or("files_with_matches");
+    let context = number(args, "-C").unwrap_or(0) as usize;
+    let before = number(args, "-B").map_or(context, |n| n as usize);
+    let after = number(args, "-A").map_or(context, |n| n as usize);
+    let head_limit = number(args, "head_limit").map_or(usize::MAX, |n| n as usize);
+
+    let mut files = Vec::new();
+    collect_files(&root, &mut files);
+    files.sort();
+
+    let mut entries = Vec::new();
+    let mut total_matches = 0;
+    for file in files {
+        let relative = file.strip_prefix(&root).unwrap_or(&file);
+        if let Some(filter) = &file_filter {
+            let name_matches = file
+                .file_name()
+                .is_some_and(|name| filter.matches(&name.to_string_lossy()));
+            if !name_matches && !filter.matches_path(relative) {
+                continue;
+            }
+        }
+        if let Some(extensions) = &extensions {
+            let extension = file.extension().map(|e| e.to_string_lossy().into_owned());
+            if !extension.is_some_and(|e| extensions.contains(&e.as_str())) {
+                continue;
+            }
+        }
+        // Binary and unreadable files are skipped
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+
+        let lines: Vec<&str> = content.lines().collect();
+        let mut matched: Vec<usize> = if multiline {
+            regex
+                .find_iter(&content)
+                .map(|m| content[..m.start()].matches('\n').count())
+                .collect()
+        } else {
+            (0..lines.len())
+                .filter(|&i| regex.is_match(lines[i]))
+                .collect()
+        };
+        matched.dedup();
+        if matched.is_empty() {
+            continue;
+        }
+        total_matches += matched.len();
+
+        let file_name = file.display().to_string();
+        match output_mode {
+            "content" => {
+                for index in matched {
+                    let mut entry = json!({
+                        "file": file_name,
+                        "line": index + 1,
+                        "content": lines.get(index).copied().unwrap_or_default(),
+                    });
+                    if before > 0 {
+                        entry["before"] = json!(lines[index.saturating_sub(before)..index]);
+                    }
+                    if after > 0 {
+                        let end = (index + 1 + after).min(lines.len());
+                        entry["after"] = json!(lines[(index + 1).min(end)..end]);
+                    }
+                    entries.push(entry);
+                }
+            }
+            "count" => entries.push(json!({ "file": file_name, "count": matched.len() })),
+            _ => entries.push(json!(file_name)),
+        }
+        if entries.len() >= head_limit.min(MAX_RESULTS) {
+            break;
+        }
+    }
+    entries.truncate(head_limit.min(MAX_RESULTS));
+
+    Ok(match output_mode {
+        "content" => json!({ "matches": entries, "total_matches": total_matches }),
+        "count" => json!({ "counts": entries, "total_matches": total_matches }),
+        _ => json!({ "files": entries, "count": entries.len() }),
+    })
+}
+
+fn list(policy: &Policy, args: &Value) -> Result<Value, String> {
+    let path = checked_path(policy, args, "path")?;
+    let ignore: Vec<glob::Pattern> = args
+        .get("ignore")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|p| p.as_str())
+        .filter_map(|p| glob::Pattern::new(p).ok())
+        .collect();
+
+    let mut entries: Vec<Value> = fs::read_dir(&path)
+        .map_err(|e| format!("Failed to list {}: {}", path.display(), e))?
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            !ignore
+                .iter()
+                .any(|p| p.matches(&name) || p.matches_path(&entry.path()))
+        })
+        .map(|entry| {
+            let metadata = entry.metadata().ok();
+            let kind = match entry.file_type() {
+                Ok(t) if t.is_symlink() => "symlink",
+                Ok(t) if t.is_dir() => "directory",
+                _ => "file",
+            };
+            json!({
+                "name": entry.file_name().to_string_lossy(),
+                "type": kind,
+                "size": metadata.map(|m| m.len()).unwrap_or(0),
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| a["name"].as_str().cmp(&b["name"].as_str()));
+    Ok(json!({ "entries": entries }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(root: &Path) -> Policy {
+        Policy {
+            allowed_roots: vec![root.display().to_string()],
+            allowed_commands: vec!["*".to_string()],
+            auto_approve_reads: true,
+        }
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "llm-playground-tools-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn edit_needs_a_unique_match_unless_replacing_all() {
+        let dir = scratch_dir("edit");
+        let policy = policy(&dir);
+        let file = dir.join("src/lib.rs");
+        fs::write(&file, "let a = 1;\nlet b = 1;\n").unwrap();
+        let path = file.display().to_string();
+
+        let args = json!({ "file_path": path, "old_string": "= 1", "new_string": "= 2" });
+        assert!(run(&policy, "Edit", &args).unwrap_err().contains("2 times"));
+
+        let args = json!({ "file_path": path, "old_string": "b = 1", "new_string": "b = 2" });
+        assert_eq!(run(&policy, "Edit", &args).unwrap()["replacements"], 1);
+
+        let args = json!({
+            "file_path": path,
+            "old_string": "let",
+            "new_string": "const",
+            "replace_all": true
+        });
+        assert_eq!(run(&policy, "Edit", &args).unwrap()["replacements"], 2);
+        let read = run(&policy, "Read", &json!({ "file_path": path, "offset": 2 })).unwrap();
+        assert_eq!(read["content"], "const b = 2;");
+
+        let escaped = format!("{}/src/../../etc/passwd", dir.display());
+        assert!(run(&policy, "Read", &json!({ "file_path": escaped })).is_err());
+        assert!(run(&policy, "Read", &json!({ "file_path": "src/lib.rs" })).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn grep_and_glob_search_the_tree() {
+        let dir = scratch_dir("search");
+        let policy = policy(&dir);
+        fs::write(dir.join("src/main.rs"), "fn main() {\n    run();\n}\n").unwrap();
+        fs::write(dir.join("notes.md"), "fn main is the entry point\n").unwrap();
+        fs::create_dir_all(dir.join("target")).unwrap();
+        fs::write(dir.join("target/gen.rs"), "fn main() {}\n").unwrap();
+        let root = dir.display().to_string();
+
+        let found = run(
+            &policy,
+            "Grep",
+            &json!({ "pattern": "fn main", "path": root, "type": "rust", "output_mode": "content", "-A": 1 }),
+        )
+        .unwrap();
+        assert_eq!(found["total_matches"], 1);
+        assert_eq!(found["matches"][0]["line"], 1);
+        assert_eq!(found["matches"][0]["after"][0], "    run();");
+
+        let files = run(
+            &policy,
+            "Grep",
+            &json!({ "pattern": "MAIN", "path": root, "-i": true }),
+        )
+        .unwrap();
+        assert_eq!(files["count"], 2);
+
+        let globbed = run(&policy, "Glob", &json!({ "pattern": "**/*.rs", "path": root })).unwrap();
+        assert_eq!(globbed["count"], 1);
+        assert!(run(&policy, "Glob", &json!({ "pattern": "../*", "path": root })).is_err());
+
+        let listed = run(&policy, "LS", &json!({ "path": root, "ignore": ["target"] })).unwrap();
+        let names: Vec<&str> = listed["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["notes.md", "src"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_out_of_the_roots_are_refused() {
+        let dir = scratch_dir("symlink");
+        let policy = policy(&dir.join("src"));
+        let outside = dir.join("secret.txt");
+        fs::write(&outside, "token").unwrap();
+        std::os::unix::fs::symlink(&outside, dir.join("src/link.txt")).unwrap();
+
+        let link = dir.join("src/link.txt").display().to_string();
+        assert!(run(&policy, "Read", &json!({ "file_path": link })).unwrap_err().contains("outside"));
+        let globbed = run(&policy, "Glob", &json!({ "pattern": "*", "path": dir.join("src").display().to_string() }));
+        assert_eq!(globbed.unwrap()["count"], 0);
+        assert!(run(&Policy::default(), "LS", &json!({ "path": dir.display().to_string() })).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn bash_does_not_wait_on_leftover_processes() {
+        let dir = scratch_dir("bash");
+        let policy = policy(&dir);
+
+        let started = Instant::now();
+        let result = run(&policy, "Bash", &json!({ "command": "echo hi; sleep 30 &" })).unwrap();
+        assert_eq!(result["stdout"], "hi\n");
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        let started = Instant::now();
+        let result = run(&policy, "Bash", &json!({ "command": "sleep 30 | cat", "timeout": 200 })).unwrap();
+        assert_eq!(result["timed_out"], true);
+        assert!(started.elapsed() < Duration::from_secs(10));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
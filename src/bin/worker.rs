@@ -0,0 +1,7 @@
+// Entry point of the background worker, built by Trunk as worker.js
+use gloo::worker::Registrable;
+use llm_playground_rs::llm_playground::offload::OffloadWorker;
+
+fn main() {
+    OffloadWorker::registrar().register();
+}
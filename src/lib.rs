@@ -0,0 +1,2 @@
+// Library root, shared by the app and the background worker binaries
+pub mod llm_playground;
@@ -0,0 +1,156 @@
+// A/B system-prompt experiments
+// Runs one user prompt against two system prompt variants on the same model,
+// several times each, and optionally asks a judge model which reply of each pair
+// is better.
+
+use crate::llm_playground::{
+    flexible_client::FlexibleLLMClient, provider_config::FlexibleApiConfig, Message, MessageRole,
+};
+
+pub const MAX_EXPERIMENT_RUNS: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    A,
+    B,
+}
+
+impl Variant {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Variant::A => "A",
+            Variant::B => "B",
+        }
+    }
+}
+
+/// One reply to the experiment prompt
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    pub output: Result<String, String>,
+    pub latency_ms: f64,
+}
+
+/// The judge's verdict on one A/B pair
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preference {
+    Prefers(Variant),
+    Tie,
+}
+
+/// Send the experiment prompt with the given system prompt
+pub async fn run_sample(
+    system_prompt: &str,
+    user_prompt: &str,
+    llm_client: &FlexibleLLMClient,
+    config: &FlexibleApiConfig,
+) -> Sample {
+    let mut variant_config = config.clone();
+    variant_config.system_prompt = system_prompt.to_string();
+
+    let started = js_sys::Date::now();
+    let output = llm_client
+        .send_message(&[user_message(user_prompt)], &variant_config)
+        .await
+        .map(|response| response.content.unwrap_or_default());
+    Sample {
+        output,
+        latency_ms: js_sys::Date::now() - started,
+    }
+}
+
+/// Ask the judge which of two replies is better. The replies are shown in a
+/// random order so the judge's position bias doesn't favour one variant.
+pub async fn judge_pair(
+    user_prompt: &str,
+    output_a: &str,
+    output_b: &str,
+    criteria: &str,
+    llm_client: &FlexibleLLMClient,
+    judge_config: &FlexibleApiConfig,
+) -> Result<Preference, String> {
+    let swapped = js_sys::Math::random() < 0.5;
+    let (first, second) = if swapped {
+        (output_b, output_a)
+    } else {
+        (output_a, output_b)
+    };
+
+    let mut config = judge_config.clone();
+    config.system_prompt = "You compare two AI assistant replies to the same user message. \
+        Decide which reply is better, or whether they are equally good. \
+        Answer with the line \"WINNER: 1\", \"WINNER: 2\" or \"WINNER: TIE\" followed by a one-sentence reason."
+        .to_string();
+    config.function_tools.clear();
+
+    let criteria = if criteria.trim().is_empty() {
+        "Overall helpfulness, accuracy and clarity".to_string()
+    } else {
+        criteria.trim().to_string()
+    };
+    let prompt = format!(
+        "Criteria: {}\n\nUser message:\n{}\n\nReply 1:\n{}\n\nReply 2:\n{}",
+        criteria, user_prompt, first, second
+    );
+
+    let verdict = llm_client
+        .send_message(&[user_message(&prompt)], &config)
+        .await?
+        .content
+        .unwrap_or_default();
+    match parse_winner(&verdict) {
+        Some(0) => Ok(Preference::Tie),
+        Some(1) => Ok(Preference::Prefers(if swapped {
+            Variant::B
+        } else {
+            Variant::A
+        })),
+        Some(_) => Ok(Preference::Prefers(if swapped {
+            Variant::A
+        } else {
+            Variant::B
+        })),
+        None => Err(format!("Judge gave no verdict: {}", verdict.trim())),
+    }
+}
+
+/// Read "WINNER: 1|2|TIE" from a judge reply; a tie is returned as 0
+pub fn parse_winner(verdict: &str) -> Option<u8> {
+    let upper = verdict.to_uppercase();
+    let after = &upper[upper.find("WINNER")? + "WINNER".len()..];
+    let answer = after.trim_start_matches(|c: char| c == ':' || c == '*' || c.is_whitespace());
+    if answer.starts_with("TIE") {
+        Some(0)
+    } else if answer.starts_with('1') {
+        Some(1)
+    } else if answer.starts_with('2') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn user_message(content: &str) -> Message {
+    Message {
+        id: format!("experiment_{}", js_sys::Date::now() as u64),
+        timestamp: js_sys::Date::now(),
+        ..Message::new(MessageRole::User, content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_judge_verdicts() {
+        assert_eq!(parse_winner("WINNER: 1\nMore concise."), Some(1));
+        assert_eq!(
+            parse_winner("**Winner:** 2 because it cites sources"),
+            Some(2)
+        );
+        assert_eq!(parse_winner("Winner: tie"), Some(0));
+        assert_eq!(parse_winner("Both are fine"), None);
+        assert_eq!(parse_winner("WINNER: neither"), None);
+    }
+}
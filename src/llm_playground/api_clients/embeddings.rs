@@ -0,0 +1,232 @@
+// Embeddings clients
+// Turn texts into vectors through a provider's embeddings endpoint. Used by the
+// knowledge base and by semantic session search.
+
+use crate::llm_playground::{cors_proxy, provider_config::FlexibleApiConfig};
+use serde_json::Value;
+use std::future::Future;
+use std::pin::Pin;
+
+pub type EmbeddingsFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<Vec<f32>>, String>> + 'a>>;
+
+// Represents a client that can embed texts with one embeddings model
+pub trait EmbeddingsClient {
+    /// Embed one batch of texts; vectors are returned in input order
+    fn embed(&self, texts: &[String]) -> EmbeddingsFuture<'_>;
+
+    /// "provider,model" identifying the vector space, so vectors from
+    /// different models are never compared
+    fn model_id(&self) -> &str;
+
+    /// Most texts the endpoint accepts in one request
+    fn max_batch_size(&self) -> usize {
+        64
+    }
+}
+
+/// OpenAI-compatible `/embeddings` endpoint
+pub struct OpenAIEmbeddingsClient {
+    pub base_url: String,
+    pub api_key: String,
+    pub model: String,
+    pub model_id: String,
+    pub cors_proxy: Option<String>,
+}
+
+impl EmbeddingsClient for OpenAIEmbeddingsClient {
+    fn embed(&self, texts: &[String]) -> EmbeddingsFuture<'_> {
+        let body = serde_json::json!({ "model": self.model, "input": texts });
+        Box::pin(async move {
+            let url = format!("{}/embeddings", self.base_url.trim_end_matches('/'));
+            let response = post_json(
+                cors_proxy::post(&url, self.cors_proxy.as_deref())
+                    .header("Authorization", &format!("Bearer {}", self.api_key)),
+                &body,
+            )
+            .await?;
+            let mut data: Vec<(usize, Vec<f32>)> = response
+                .get("data")
+                .and_then(|d| d.as_array())
+                .ok_or("Embeddings response has no data")?
+                .iter()
+                .enumerate()
+                .map(|(position, item)| {
+                    let index = item
+                        .get("index")
+                        .and_then(|i| i.as_u64())
+                        .map_or(position, |i| i as usize);
+                    (index, parse_vector(item.get("embedding")))
+                })
+                .collect();
+            data.sort_by_key(|(index, _)| *index);
+            Ok(data.into_iter().map(|(_, vector)| vector).collect())
+        })
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+}
+
+/// Gemini `batchEmbedContents` endpoint
+pub struct GeminiEmbeddingsClient {
+    pub base_url: String,
+    pub api_key: String,
+    pub model: String,
+    pub model_id: String,
+    pub cors_proxy: Option<String>,
+}
+
+impl EmbeddingsClient for GeminiEmbeddingsClient {
+    fn embed(&self, texts: &[String]) -> EmbeddingsFuture<'_> {
+        let requests: Vec<Value> = texts
+            .iter()
+            .map(|text| {
+                serde_json::json!({
+                    "model": format!("models/{}", self.model),
+                    "content": { "parts": [{ "text": text }] }
+                })
+            })
+            .collect();
+        let body = serde_json::json!({ "requests": requests });
+        Box::pin(async move {
+            let url = format!(
+                "{}/{}:batchEmbedContents?key={}",
+                self.base_url.trim_end_matches('/'),
+                self.model,
+                self.api_key
+            );
+            let response =
+                post_json(cors_proxy::post(&url, self.cors_proxy.as_deref()), &body).await?;
+            Ok(response
+                .get("embeddings")
+                .and_then(|e| e.as_array())
+                .ok_or("Embeddings response has no embeddings")?
+                .iter()
+                .map(|item| parse_vector(item.get("values")))
+                .collect())
+        })
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn max_batch_size(&self) -> usize {
+        100
+    }
+}
+
+/// Build the client for the config's embeddings model. Fails when no model is
+/// chosen or its provider has no API key.
+pub fn create_embeddings_client(
+    config: &FlexibleApiConfig,
+) -> Result<Box<dyn EmbeddingsClient>, String> {
+    let (provider_name, model) = config
+        .embedding_model
+        .as_deref()
+        .and_then(|value| value.split_once(','))
+        .filter(|(_, model)| !model.trim().is_empty())
+        .ok_or("No embeddings model configured. Choose one in the Knowledge view.")?;
+    let provider = config
+        .get_provider(provider_name)
+        .ok_or_else(|| format!("Embeddings provider '{}' not found", provider_name))?;
+    if provider.api_key.trim().is_empty() && provider.name != "ollama" {
+        return Err(format!("Provider '{}' has no API key", provider.name));
+    }
+
+    let model = model.trim().to_string();
+    let model_id = format!("{},{}", provider.name, model);
+    let base_url = provider.api_base_url.clone();
+    let api_key = provider.api_key.clone();
+    let cors_proxy = config.shared_settings.cors_proxy.clone();
+    if provider.transformer.r#use.contains(&"gemini".to_string()) {
+        if provider.gemini_auth.is_vertex() {
+            return Err(format!(
+                "Provider '{}' uses Vertex AI, which has no batch embeddings endpoint. Choose an AI Studio provider for embeddings.",
+                provider.name
+            ));
+        }
+        Ok(Box::new(GeminiEmbeddingsClient {
+            base_url,
+            api_key,
+            model,
+            model_id,
+            cors_proxy,
+        }))
+    } else {
+        Ok(Box::new(OpenAIEmbeddingsClient {
+            base_url,
+            api_key,
+            model,
+            model_id,
+            cors_proxy,
+        }))
+    }
+}
+
+/// Embed any number of texts, split into batches the endpoint accepts
+pub async fn embed_all(
+    client: &dyn EmbeddingsClient,
+    texts: &[String],
+) -> Result<Vec<Vec<f32>>, String> {
+    let mut embeddings = Vec::with_capacity(texts.len());
+    for batch in texts.chunks(client.max_batch_size().max(1)) {
+        let batch_embeddings = client.embed(batch).await?;
+        if batch_embeddings.len() != batch.len() {
+            return Err(format!(
+                "Expected {} embeddings, got {}",
+                batch.len(),
+                batch_embeddings.len()
+            ));
+        }
+        embeddings.extend(batch_embeddings);
+    }
+    Ok(embeddings)
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+async fn post_json(builder: gloo_net::http::RequestBuilder, body: &Value) -> Result<Value, String> {
+    let response = builder
+        .header("Content-Type", "application/json")
+        .json(body)
+        .map_err(|e| format!("Failed to create request: {}", e))?
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+    if !response.ok() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("Embeddings API error {}: {}", status, text));
+    }
+    response
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Failed to parse embeddings response: {}", e))
+}
+
+fn parse_vector(value: Option<&Value>) -> Vec<f32> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|x| x.as_f64())
+                .map(|x| x as f32)
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -1,90 +1,86 @@
-// Factory setup and initialization for the refactored system
-use super::client_service::{ClientService, ClientServiceBuilder};
+// Factory setup: the registry every request picks its client from
 use super::gemini_factory::GeminiProviderFactory;
-use super::message_service::MessageConversionService;
+use super::huggingface_client::HuggingFaceProviderFactory;
+use super::mock_provider::MockProviderFactory;
 use super::openai_factory::OpenAIProviderFactory;
 use super::provider_factory::ProviderRegistry;
+use crate::llm_playground::provider_config::ProviderConfig;
 use std::sync::Arc;
 
-/// Initialize the provider system with all available factories
-/// This demonstrates how to set up the system following SOLID principles
-pub fn initialize_provider_system() -> ClientService {
+/// A registry with the built-in factories, plus one for each of `providers`
+/// that is defined at runtime by a JSON descriptor in the settings
+pub fn provider_registry(providers: &[ProviderConfig]) -> ProviderRegistry {
     let mut registry = ProviderRegistry::new();
-    
-    // Register all available provider factories (OCP compliance)
     registry.register_factory(Arc::new(OpenAIProviderFactory::new()));
     registry.register_factory(Arc::new(GeminiProviderFactory::new()));
-    
-    // You can easily add new providers here without modifying existing code:
-    // registry.register_factory(Arc::new(AnthropicProviderFactory::new()));
-    // registry.register_factory(Arc::new(OllamaProviderFactory::new()));
-    
-    let message_converter = Arc::new(MessageConversionService::new());
-    
-    // Build the client service with dependency injection (DIP compliance)
-    ClientServiceBuilder::new()
-        .with_provider_registry(Arc::new(registry))
-        .with_message_converter(message_converter)
-        .build()
-}
-
-/// Create a minimal provider system for testing
-pub fn create_test_provider_system() -> ClientService {
-    let mut registry = ProviderRegistry::new();
-    registry.register_factory(Arc::new(OpenAIProviderFactory::new()));
-    
-    ClientServiceBuilder::new()
-        .with_provider_registry(Arc::new(registry))
-        .build()
-}
-
-/// Example of how to add a new provider without modifying existing code
-/// This demonstrates the Open/Closed Principle in action
-#[cfg(feature = "example_extension")]
-pub fn add_custom_provider(service: &mut ProviderRegistry) {
-    use super::provider_factory::ProviderFactory;
-    
-    // Example custom provider factory
-    struct CustomProviderFactory;
-    
-    impl ProviderFactory for CustomProviderFactory {
-        fn supports_provider(&self, provider_type: &str) -> bool {
-            provider_type == "custom"
-        }
-        
-        fn create_client(&self, _config: &crate::llm_playground::provider_config::ProviderConfig) -> Result<Box<dyn super::traits::LLMClient>, String> {
-            // Return a custom client implementation
-            Err("Custom provider not implemented".to_string())
-        }
-        
-        fn provider_type(&self) -> &str {
-            "custom"
-        }
-    }
-    
-    service.register_factory(Arc::new(CustomProviderFactory));
+    registry.register_factory(Arc::new(MockProviderFactory::new()));
+    registry.register_factory(Arc::new(HuggingFaceProviderFactory::new()));
+    registry.register_plugins(providers);
+    registry
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::llm_playground::provider_config::TransformerConfig;
+
+    fn provider(name: &str, transformer: &str) -> ProviderConfig {
+        ProviderConfig {
+            name: name.to_string(),
+            api_base_url: "https://example.com/v1".to_string(),
+            api_key: "key".to_string(),
+            models: vec!["model".to_string()],
+            transformer: TransformerConfig {
+                r#use: vec![transformer.to_string()],
+            },
+            model_defaults: Default::default(),
+            mock_script: None,
+            plugin: None,
+            gemini_auth: Default::default(),
+        }
+    }
 
     #[test]
     fn test_provider_system_initialization() {
-        let service = initialize_provider_system();
-        let providers = service.get_supported_providers();
-        
+        let providers = provider_registry(&[]).get_supported_providers();
+
         assert!(providers.contains(&"openai".to_string()));
         assert!(providers.contains(&"gemini".to_string()));
-        assert_eq!(providers.len(), 2);
+        assert!(providers.contains(&"mock".to_string()));
+        assert!(providers.contains(&"huggingface".to_string()));
+        assert_eq!(providers.len(), 4);
     }
 
     #[test]
-    fn test_minimal_provider_system() {
-        let service = create_test_provider_system();
-        let providers = service.get_supported_providers();
-        
-        assert!(providers.contains(&"openai".to_string()));
-        assert_eq!(providers.len(), 1);
+    fn transformers_pick_their_client() {
+        let registry = provider_registry(&[]);
+        for (transformer, client) in [
+            ("openai", "OpenAI"),
+            ("gemini", "Gemini"),
+            ("mock", "Mock"),
+            ("huggingface", "Hugging Face"),
+        ] {
+            let created = registry.create_client(&provider(transformer, transformer)).unwrap();
+            assert_eq!(created.client_name(), client);
+        }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_provider_system_with_plugins() {
+        let plugin = crate::llm_playground::api_clients::provider_plugin::ProviderDescriptor {
+            name: "gateway".to_string(),
+            transformer: vec!["openai".to_string()],
+            api_base_url: "https://gateway.example.com/v1".to_string(),
+            models: vec!["model".to_string()],
+            auth: Default::default(),
+            endpoints: Default::default(),
+        }
+        .into_provider_config();
+        let registry = provider_registry(&[plugin.clone()]);
+        let providers = registry.get_supported_providers();
+
+        assert!(providers.contains(&"gateway".to_string()));
+        assert_eq!(providers.len(), 5);
+        assert!(registry.create_client(&plugin).is_ok());
+    }
+}
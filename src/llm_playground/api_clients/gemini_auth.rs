@@ -0,0 +1,174 @@
+// How a Gemini provider is reached
+// AI Studio takes an API key in the query string under the provider's base
+// URL. Vertex AI, which enterprise projects use instead, builds its URLs from
+// a Google Cloud project and location and takes an OAuth access token, e.g.
+// the output of `gcloud auth print-access-token`, as a bearer token. The
+// token is kept in the provider's API key field.
+use serde::{Deserialize, Serialize};
+
+/// Region picked when a provider is switched to Vertex AI
+pub const DEFAULT_VERTEX_LOCATION: &str = "us-central1";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum GeminiAuth {
+    /// AI Studio API key
+    #[default]
+    ApiKey,
+    /// Vertex AI with an OAuth access token
+    Vertex { project: String, location: String },
+}
+
+impl GeminiAuth {
+    pub fn is_api_key(&self) -> bool {
+        *self == GeminiAuth::ApiKey
+    }
+
+    pub fn is_vertex(&self) -> bool {
+        matches!(self, GeminiAuth::Vertex { .. })
+    }
+
+    /// URL of a model method such as "generateContent"; `method` may carry a
+    /// query string of its own
+    pub fn model_url(&self, base_url: &str, model: &str, method: &str, api_key: &str) -> String {
+        match self {
+            GeminiAuth::ApiKey => {
+                let separator = if method.contains('?') { '&' } else { '?' };
+                format!(
+                    "{}/{}:{}{}key={}",
+                    base_url.trim_end_matches('/'),
+                    model,
+                    method,
+                    separator,
+                    api_key
+                )
+            }
+            GeminiAuth::Vertex { project, location } => format!(
+                "{}/v1/projects/{}/locations/{}/publishers/google/models/{}:{}",
+                vertex_host(location),
+                project,
+                location,
+                model,
+                method
+            ),
+        }
+    }
+
+    /// URL listing the models the key or token can use
+    pub fn models_url(&self, base_url: &str, api_key: &str) -> String {
+        match self {
+            GeminiAuth::ApiKey => format!("{}?key={}", base_url.trim_end_matches('/'), api_key),
+            GeminiAuth::Vertex { location, .. } => {
+                format!("{}/v1beta1/publishers/google/models", vertex_host(location))
+            }
+        }
+    }
+
+    pub fn auth_headers(&self, api_key: &str) -> Vec<(String, String)> {
+        match self {
+            GeminiAuth::ApiKey => Vec::new(),
+            GeminiAuth::Vertex { project, .. } => vec![
+                ("Authorization".to_string(), format!("Bearer {}", api_key)),
+                // Bills quota to the project rather than to the token's owner
+                ("x-goog-user-project".to_string(), project.clone()),
+            ],
+        }
+    }
+
+    /// Message for a request without a key or token
+    pub fn missing_credentials(&self) -> &'static str {
+        match self {
+            GeminiAuth::ApiKey => "Please configure your Gemini API key in Settings",
+            GeminiAuth::Vertex { .. } => {
+                "Please paste a Vertex AI access token (gcloud auth print-access-token) as the provider's API key in Settings"
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            GeminiAuth::ApiKey => Ok(()),
+            GeminiAuth::Vertex { project, .. } if project.trim().is_empty() => {
+                Err("The Google Cloud project cannot be empty".to_string())
+            }
+            GeminiAuth::Vertex { location, .. }
+                if location.trim().is_empty()
+                    || !location
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-') =>
+            {
+                Err("The location must be a region such as us-central1, or global".to_string())
+            }
+            GeminiAuth::Vertex { .. } => Ok(()),
+        }
+    }
+}
+
+/// Model name from a list entry such as "models/gemini-pro" or
+/// "publishers/google/models/gemini-2.0-flash-001"
+pub fn model_name(entry: &str) -> &str {
+    entry.rsplit('/').next().unwrap_or(entry)
+}
+
+fn vertex_host(location: &str) -> String {
+    if location == "global" {
+        "https://aiplatform.googleapis.com".to_string()
+    } else {
+        format!("https://{}-aiplatform.googleapis.com", location)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models/";
+
+    #[test]
+    fn api_key_mode_keeps_the_ai_studio_urls() {
+        let auth = GeminiAuth::default();
+        assert_eq!(
+            auth.model_url(BASE_URL, "gemini-2.5-flash", "generateContent", "k"),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-2.5-flash:generateContent?key=k"
+        );
+        assert!(auth
+            .model_url(BASE_URL, "m", "streamGenerateContent?alt=sse", "k")
+            .ends_with(":streamGenerateContent?alt=sse&key=k"));
+        assert!(auth.auth_headers("k").is_empty());
+        assert_eq!(model_name("models/gemini-pro"), "gemini-pro");
+    }
+
+    #[test]
+    fn vertex_mode_uses_project_urls_and_a_bearer_token() {
+        let auth = GeminiAuth::Vertex {
+            project: "acme".to_string(),
+            location: "europe-west4".to_string(),
+        };
+        assert_eq!(
+            auth.model_url(BASE_URL, "gemini-2.5-pro", "generateContent", "ya29.token"),
+            "https://europe-west4-aiplatform.googleapis.com/v1/projects/acme/locations/europe-west4/publishers/google/models/gemini-2.5-pro:generateContent"
+        );
+        assert_eq!(
+            auth.auth_headers("ya29.token")[0],
+            ("Authorization".to_string(), "Bearer ya29.token".to_string())
+        );
+        assert_eq!(
+            model_name("publishers/google/models/gemini-2.0-flash-001"),
+            "gemini-2.0-flash-001"
+        );
+
+        let global = GeminiAuth::Vertex {
+            project: "acme".to_string(),
+            location: "global".to_string(),
+        };
+        assert!(global
+            .models_url(BASE_URL, "t")
+            .starts_with("https://aiplatform.googleapis.com/"));
+        assert!(global.validate().is_ok());
+        let bad = GeminiAuth::Vertex {
+            project: "acme".to_string(),
+            location: "us-central1/../x".to_string(),
+        };
+        assert!(bad.validate().is_err());
+    }
+}
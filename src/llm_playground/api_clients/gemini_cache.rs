@@ -0,0 +1,334 @@
+// Gemini context caching
+// The fixed part of a request, i.e. the system prompt with any documents
+// pasted into it plus the tool declarations, is uploaded once as cachedContent
+// and referenced by name in later requests, which Gemini bills at a discount.
+// Caches are remembered in local storage by a fingerprint of model and
+// content, reused until shortly before they expire, and can be extended or
+// deleted from the settings.
+
+use super::traits::ProviderRequest;
+use crate::llm_playground::{cors_proxy, logging, offload::estimate_tokens};
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::hash::{Hash, Hasher};
+
+const CACHE_REGISTRY_KEY: &str = "llm_playground_gemini_caches";
+/// Smaller contexts are sent as they are; Gemini rejects small caches
+pub const MIN_CACHE_TOKENS: usize = 4_096;
+pub const DEFAULT_CACHE_TTL_MINUTES: u32 = 60;
+/// Share of the input price saved on cached tokens
+pub const CACHED_TOKEN_DISCOUNT: f64 = 0.75;
+/// Caches this close to expiry are replaced rather than used
+const EXPIRY_MARGIN_MS: f64 = 60_000.0;
+/// Request fields moved into the cache; Gemini rejects them next to cachedContent
+const CACHED_KEYS: [&str; 3] = ["systemInstruction", "tools", "toolConfig"];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CachedContext {
+    /// "cachedContents/..." as returned by the API
+    pub name: String,
+    /// API root the cache lives under, to find the key that manages it
+    pub api_root: String,
+    pub model: String,
+    pub fingerprint: String,
+    pub token_count: u32,
+    pub created_at: f64,
+    pub expires_at: f64,
+}
+
+impl CachedContext {
+    pub fn is_usable(&self, now: f64) -> bool {
+        now + EXPIRY_MARGIN_MS < self.expires_at
+    }
+}
+
+/// The system instruction, tools and tool config of a generateContent body,
+/// if they are large enough to be worth caching
+pub fn cacheable_context(body: &Value) -> Option<Value> {
+    let mut context = serde_json::Map::new();
+    for key in CACHED_KEYS {
+        if let Some(value) = body.get(key).filter(|value| !value.is_null()) {
+            context.insert(key.to_string(), value.clone());
+        }
+    }
+    let context = Value::Object(context);
+    (estimate_tokens(&context.to_string()) >= MIN_CACHE_TOKENS).then_some(context)
+}
+
+pub fn fingerprint(model: &str, context: &Value) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    model.hash(&mut hasher);
+    context.to_string().hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Replace the cached part of a request body with a reference to the cache
+pub fn use_cache(body: &mut Value, cache_name: &str) {
+    if let Some(object) = body.as_object_mut() {
+        for key in CACHED_KEYS {
+            object.remove(key);
+        }
+        object.insert("cachedContent".to_string(), json!(cache_name));
+    }
+}
+
+/// "https://host/v1beta/models/" -> "https://host/v1beta"
+pub fn api_root(base_url: &str) -> &str {
+    let trimmed = base_url.trim_end_matches('/');
+    trimmed.strip_suffix("/models").unwrap_or(trimmed)
+}
+
+/// Share of the input cost saved by reading `cached_tokens` from a cache
+pub fn saved_input_share(input_tokens: u32, cached_tokens: u32) -> Option<f64> {
+    (input_tokens > 0).then(|| {
+        cached_tokens.min(input_tokens) as f64 * CACHED_TOKEN_DISCOUNT / input_tokens as f64
+    })
+}
+
+pub fn load_caches() -> Vec<CachedContext> {
+    LocalStorage::get(CACHE_REGISTRY_KEY).unwrap_or_default()
+}
+
+fn save_caches(caches: &[CachedContext]) {
+    if let Err(e) = LocalStorage::set(CACHE_REGISTRY_KEY, caches) {
+        logging::warn!("Failed to save context caches: {}", e);
+    }
+}
+
+/// Add or update a cache, dropping the ones that have expired
+fn remember(cache: CachedContext) {
+    let now = js_sys::Date::now();
+    let mut caches: Vec<CachedContext> = load_caches()
+        .into_iter()
+        .filter(|c| c.name != cache.name && c.expires_at > now)
+        .collect();
+    caches.push(cache);
+    save_caches(&caches);
+}
+
+/// Stop using a cache, e.g. once the API no longer knows it
+pub fn forget(name: &str) {
+    let caches: Vec<CachedContext> = load_caches()
+        .into_iter()
+        .filter(|c| c.name != name)
+        .collect();
+    save_caches(&caches);
+}
+
+/// Where and with which key cache requests are made
+pub struct CacheEndpoint<'a> {
+    pub base_url: &'a str,
+    pub api_key: &'a str,
+    pub cors_proxy: Option<&'a str>,
+}
+
+impl CacheEndpoint<'_> {
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}?key={}", api_root(self.base_url), path, self.api_key)
+    }
+
+    /// Point `request` at a cache of its fixed context, creating the cache
+    /// when there is none. Returns the cache used; on failure the request is
+    /// left as it was.
+    pub async fn apply(
+        &self,
+        request: &mut ProviderRequest,
+        model: &str,
+        ttl_minutes: u32,
+    ) -> Option<String> {
+        let context = cacheable_context(&request.body)?;
+        let fingerprint = fingerprint(model, &context);
+        let now = js_sys::Date::now();
+        let existing = load_caches()
+            .into_iter()
+            .find(|c| c.fingerprint == fingerprint && c.is_usable(now));
+        let cache = match existing {
+            Some(cache) => cache,
+            None => match self.create(model, &fingerprint, context, ttl_minutes).await {
+                Ok(cache) => {
+                    logging::info!(
+                        "Created context cache {} with {} tokens",
+                        cache.name,
+                        cache.token_count
+                    );
+                    remember(cache.clone());
+                    cache
+                }
+                Err(error) => {
+                    logging::warn!("Sending the full context, caching failed: {}", error);
+                    return None;
+                }
+            },
+        };
+        use_cache(&mut request.body, &cache.name);
+        Some(cache.name)
+    }
+
+    async fn create(
+        &self,
+        model: &str,
+        fingerprint: &str,
+        context: Value,
+        ttl_minutes: u32,
+    ) -> Result<CachedContext, String> {
+        let mut body = context;
+        body["model"] = json!(if model.starts_with("models/") {
+            model.to_string()
+        } else {
+            format!("models/{}", model)
+        });
+        body["ttl"] = json!(format!("{}s", ttl_minutes * 60));
+
+        let response = cors_proxy::post(&self.url("cachedContents"), self.cors_proxy)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .map_err(|e| format!("Failed to create request: {}", e))?
+            .send()
+            .await
+            .map_err(|e| format!("Network error: {}", e))?;
+        let response = read_cache_response(response).await?;
+
+        Ok(CachedContext {
+            name: response.name,
+            api_root: api_root(self.base_url).to_string(),
+            model: model.to_string(),
+            fingerprint: fingerprint.to_string(),
+            token_count: response
+                .usage_metadata
+                .map(|usage| usage.total_token_count)
+                .unwrap_or_default(),
+            created_at: js_sys::Date::now(),
+            expires_at: js_sys::Date::parse(&response.expire_time),
+        })
+    }
+
+    /// Give a cache a new lifetime from now
+    pub async fn extend(
+        &self,
+        cache: &CachedContext,
+        ttl_minutes: u32,
+    ) -> Result<CachedContext, String> {
+        let url = format!("{}&updateMask=ttl", self.url(&cache.name));
+        let response = cors_proxy::patch(&url, self.cors_proxy)
+            .header("Content-Type", "application/json")
+            .json(&json!({ "ttl": format!("{}s", ttl_minutes * 60) }))
+            .map_err(|e| format!("Failed to create request: {}", e))?
+            .send()
+            .await
+            .map_err(|e| format!("Network error: {}", e))?;
+        let response = read_cache_response(response).await?;
+
+        let extended = CachedContext {
+            expires_at: js_sys::Date::parse(&response.expire_time),
+            ..cache.clone()
+        };
+        remember(extended.clone());
+        Ok(extended)
+    }
+
+    pub async fn delete(&self, cache: &CachedContext) -> Result<(), String> {
+        let response = cors_proxy::delete(&self.url(&cache.name), self.cors_proxy)
+            .send()
+            .await
+            .map_err(|e| format!("Network error: {}", e))?;
+        // A cache that already expired is gone as well
+        if !response.ok() && response.status() != 404 {
+            return Err(format!(
+                "Failed to delete cache, status: {}",
+                response.status()
+            ));
+        }
+        forget(&cache.name);
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheResponse {
+    name: String,
+    expire_time: String,
+    #[serde(default)]
+    usage_metadata: Option<CacheUsage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheUsage {
+    #[serde(default)]
+    total_token_count: u32,
+}
+
+async fn read_cache_response(response: gloo_net::http::Response) -> Result<CacheResponse, String> {
+    if !response.ok() {
+        let status = response.status();
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, error_text));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse cache response: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_large_fixed_context_is_cached() {
+        let small = json!({
+            "contents": [],
+            "systemInstruction": { "parts": [{ "text": "Be brief." }] },
+        });
+        assert_eq!(cacheable_context(&small), None);
+
+        let document = "word ".repeat(MIN_CACHE_TOKENS);
+        let mut body = json!({
+            "contents": [{ "role": "user", "parts": [{ "text": "Hi" }] }],
+            "systemInstruction": { "parts": [{ "text": document }] },
+            "tools": null,
+        });
+        let context = cacheable_context(&body).unwrap();
+        assert!(context.get("systemInstruction").is_some());
+        assert!(context.get("tools").is_none());
+        assert_ne!(
+            fingerprint("gemini-2.5-flash", &context),
+            fingerprint("gemini-2.5-pro", &context)
+        );
+
+        use_cache(&mut body, "cachedContents/abc");
+        assert_eq!(body["cachedContent"], "cachedContents/abc");
+        assert!(body.get("systemInstruction").is_none());
+        assert!(body.get("contents").is_some());
+    }
+
+    #[test]
+    fn api_root_and_savings() {
+        assert_eq!(
+            api_root("https://generativelanguage.googleapis.com/v1beta/models/"),
+            "https://generativelanguage.googleapis.com/v1beta"
+        );
+        assert_eq!(
+            api_root("https://proxy.example/v1beta"),
+            "https://proxy.example/v1beta"
+        );
+
+        assert_eq!(saved_input_share(0, 0), None);
+        assert_eq!(saved_input_share(1_000, 0), Some(0.0));
+        assert_eq!(saved_input_share(1_000, 800), Some(0.6));
+
+        let cache = CachedContext {
+            name: "cachedContents/abc".to_string(),
+            api_root: String::new(),
+            model: "gemini-2.5-flash".to_string(),
+            fingerprint: String::new(),
+            token_count: 5_000,
+            created_at: 0.0,
+            expires_at: 120_000.0,
+        };
+        assert!(cache.is_usable(0.0));
+        assert!(!cache.is_usable(90_000.0));
+    }
+}
@@ -1,12 +1,15 @@
 // Gemini API client for WASM
 use crate::llm_playground::api_clients::{
-    FunctionCallRequest, FunctionResponse, LLMClient,
-    LLMResponse, MessageConverter, MessageSender, ModelProvider, NamedClient, StreamCallback,
-    StreamingSender, UnifiedMessage, UnifiedMessageRole,
+    FunctionCallRequest, LLMClient,
+    LLMResponse, MessageSender, ModelProvider, NamedClient, ProviderRequest,
+    RequestBuilder, ResponseTiming, StreamCallback, StreamingSender, TokenUsage, UnifiedMessage,
+    UnifiedMessageRole,
 };
-use crate::llm_playground::{ApiConfig, Message, MessageRole};
-use gloo_console::log;
-use gloo_net::http::Request;
+use crate::llm_playground::attachments;
+use crate::llm_playground::image_generation::{self, GeneratedImage, MASK_INSTRUCTION};
+use crate::llm_playground::{ApiConfig, ToolChoice};
+use crate::llm_playground::{cors_proxy, logging};
+use super::{gemini_auth, gemini_cache};
 use serde::{Deserialize, Serialize};
 use std::future::Future;
 use std::pin::Pin;
@@ -20,6 +23,8 @@ struct GeminiRequest {
     system_instruction: Option<SystemInstruction>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<Tool>>,
+    #[serde(rename = "toolConfig", skip_serializing_if = "Option::is_none")]
+    tool_config: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -36,6 +41,12 @@ struct Part {
     function_call: Option<serde_json::Value>,
     #[serde(rename = "functionResponse", skip_serializing_if = "Option::is_none")]
     function_response: Option<serde_json::Value>,
+    // Set on the thought summaries returned with includeThoughts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    thought: Option<bool>,
+    // An attached file, as { mimeType, data }
+    #[serde(rename = "inlineData", default, skip_serializing_if = "Option::is_none")]
+    inline_data: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -65,6 +76,17 @@ struct GenerationConfig {
     top_k: i32,
     #[serde(rename = "maxOutputTokens")]
     max_output_tokens: i32,
+    #[serde(rename = "responseMimeType", skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
+    #[serde(rename = "responseSchema", skip_serializing_if = "Option::is_none")]
+    response_schema: Option<serde_json::Value>,
+    #[serde(rename = "stopSequences", skip_serializing_if = "Vec::is_empty")]
+    stop_sequences: Vec<String>,
+    #[serde(rename = "thinkingConfig", skip_serializing_if = "Option::is_none")]
+    thinking_config: Option<serde_json::Value>,
+    // ["TEXT", "IMAGE"] for image models
+    #[serde(rename = "responseModalities", skip_serializing_if = "Option::is_none")]
+    response_modalities: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -81,6 +103,19 @@ struct Candidate {
     finish_reason: Option<String>,
 }
 
+/// Gemini reports usage as camelCase counts under usageMetadata
+fn token_usage(metadata: &serde_json::Value) -> TokenUsage {
+    let count = |key: &str| metadata.get(key).and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+    TokenUsage {
+        prompt_tokens: count("promptTokenCount"),
+        completion_tokens: count("candidatesTokenCount"),
+        total_tokens: count("totalTokenCount"),
+        cached_tokens: count("cachedContentTokenCount"),
+        queue_time: None,
+        completion_time: None,
+    }
+}
+
 pub struct GeminiClient;
 
 impl GeminiClient {
@@ -88,49 +123,95 @@ impl GeminiClient {
         Self {}
     }
 
+    /// Attached PDFs go in as files when `native_pdfs`, else as their text
     fn convert_unified_messages_to_contents(
         &self,
         messages: &[UnifiedMessage],
         system_prompt: Option<&str>,
+        native_pdfs: bool,
     ) -> (Vec<Content>, Option<SystemInstruction>) {
         let mut contents = Vec::new();
-        let mut system_instruction = None;
-
-        // Use system prompt if provided
-        if let Some(prompt) = system_prompt {
-            system_instruction = Some(SystemInstruction {
-                parts: vec![Part {
-                    text: Some(prompt.to_string()),
-                    function_call: None,
-                    function_response: None,
-                }],
-            });
-        }
+        // Gemini has a single system instruction, so the system prompt and
+        // every system or developer message become parts of it, in order
+        let mut system_parts: Vec<Part> = system_prompt
+            .map(|prompt| Part {
+                text: Some(prompt.to_string()),
+                function_call: None,
+                function_response: None,
+                thought: None,
+                inline_data: None,
+            })
+            .into_iter()
+            .collect();
 
         // Add new unified messages
         for message in messages {
             match message.role {
-                UnifiedMessageRole::System => {
-                    if system_instruction.is_none() && message.content.is_some() {
-                        system_instruction = Some(SystemInstruction {
-                            parts: vec![Part {
-                                text: message.content.clone(),
-                                function_call: None,
-                                function_response: None,
-                            }],
+                UnifiedMessageRole::System | UnifiedMessageRole::Developer => {
+                    if let Some(text) = message.content.as_ref().filter(|t| !t.trim().is_empty()) {
+                        system_parts.push(Part {
+                            text: Some(text.clone()),
+                            function_call: None,
+                            function_response: None,
+                            thought: None,
+                            inline_data: None,
                         });
                     }
                 }
                 UnifiedMessageRole::User => {
                     let mut parts = Vec::new();
+                    let content = if native_pdfs {
+                        message.content.clone()
+                    } else {
+                        attachments::content_with_text(message.content.as_deref(), &message.attachments)
+                    };
 
                     // Add text content if present
-                    if let Some(content) = &message.content {
+                    if let Some(content) = &content {
                         if !content.trim().is_empty() {
                             parts.push(Part {
                                 text: Some(content.clone()),
                                 function_call: None,
                                 function_response: None,
+                                thought: None,
+                                inline_data: None,
+                            });
+                        }
+                    }
+
+                    if native_pdfs {
+                        for attachment in &message.attachments {
+                            parts.push(Part {
+                                text: None,
+                                function_call: None,
+                                function_response: None,
+                                thought: None,
+                                inline_data: Some(serde_json::json!({
+                                    "mimeType": attachment.mime_type,
+                                    "data": attachment.data,
+                                })),
+                            });
+                        }
+                    }
+
+                    // The image to edit, then its mask as a second image
+                    if let Some(edit) = &message.image_edit {
+                        let image = |mime_type: &str, data: &str| Part {
+                            text: None,
+                            function_call: None,
+                            function_response: None,
+                            thought: None,
+                            inline_data: Some(serde_json::json!({ "mimeType": mime_type, "data": data })),
+                        };
+                        parts.push(image(&edit.image.mime_type, &edit.image.data));
+                        if let Some(mask) = &edit.mask {
+                            parts.push(image("image/png", mask));
+                            parts.push(Part {
+                                text: Some(MASK_INSTRUCTION.to_string()),
+                                function_call: None,
+                                function_response: None,
+                                thought: None,
+                                inline_data: None,
                             });
                         }
                     }
@@ -147,6 +228,8 @@ impl GeminiClient {
                             text: None,
                             function_call: None,
                             function_response: Some(response_json),
+                            thought: None,
+                            inline_data: None,
                         });
                     }
 
@@ -167,6 +250,8 @@ impl GeminiClient {
                                 text: Some(content.clone()),
                                 function_call: None,
                                 function_response: None,
+                                thought: None,
+                                inline_data: None,
                             });
                         }
                     }
@@ -181,6 +266,8 @@ impl GeminiClient {
                             text: None,
                             function_call: Some(call_json),
                             function_response: None,
+                            thought: None,
+                            inline_data: None,
                         });
                     }
 
@@ -194,6 +281,8 @@ impl GeminiClient {
             }
         }
 
+        let system_instruction =
+            (!system_parts.is_empty()).then_some(SystemInstruction { parts: system_parts });
         (contents, system_instruction)
     }
 
@@ -215,6 +304,48 @@ impl GeminiClient {
         }])
     }
 
+    /// Ask for or against thought summaries, per the reasoning setting
+    fn build_thinking_config(config: &ApiConfig) -> Option<serde_json::Value> {
+        config
+            .shared_settings
+            .reasoning
+            .include_reasoning()
+            .map(|include| serde_json::json!({ "includeThoughts": include }))
+    }
+
+    /// Function calling mode, sent only when tools are and it isn't AUTO. In
+    /// ANY mode the allowed functions that are being sent narrow the choice.
+    fn build_tool_config(config: &ApiConfig, tools: &Option<Vec<Tool>>) -> Option<serde_json::Value> {
+        let choice = config.shared_settings.tool_choice;
+        let tools = tools.as_ref().filter(|_| choice != ToolChoice::Auto)?;
+        let mut function_calling = serde_json::json!({ "mode": choice.gemini_mode() });
+        if choice == ToolChoice::Required {
+            let allowed: Vec<&String> = config
+                .shared_settings
+                .allowed_functions
+                .iter()
+                .filter(|name| {
+                    tools
+                        .iter()
+                        .flat_map(|tool| &tool.function_declarations)
+                        .any(|declaration| &declaration.name == *name)
+                })
+                .collect();
+            if !allowed.is_empty() {
+                function_calling["allowedFunctionNames"] = serde_json::json!(allowed);
+            }
+        }
+        Some(serde_json::json!({ "functionCallingConfig": function_calling }))
+    }
+
+    /// Response schema for the active structured output
+    fn build_response_schema(&self, config: &ApiConfig) -> Option<serde_json::Value> {
+        config
+            .structured_outputs
+            .first()
+            .map(|output| Self::clean_schema_for_gemini(&output.schema))
+    }
+
     /// Clean JSON schema for Gemini API compatibility
     /// Removes unsupported properties like $schema and additionalProperties
     fn clean_schema_for_gemini(schema: &serde_json::Value) -> serde_json::Value {
@@ -248,41 +379,57 @@ impl MessageSender for GeminiClient {
         config: &ApiConfig,
         system_prompt: Option<&str>,
     ) -> Pin<Box<dyn Future<Output = Result<LLMResponse, String>> + '_>> {
-        let (contents, system_instruction) = self.convert_unified_messages_to_contents(messages, system_prompt);
-        let tools = self.build_tools(config);
+        let request = self.build_request(messages, config, system_prompt);
         let api_key = config.gemini.api_key.clone();
-        let model = config.gemini.model.clone();
         let base_url = config.gemini.base_url.clone();
-        let temperature = config.shared_settings.temperature;
-        let max_tokens = config.shared_settings.max_tokens;
+        let model = config.gemini.model.clone();
+        let cors_proxy = config.shared_settings.cors_proxy.clone();
+        // Vertex AI has its own caching API
+        let cache_ttl = config
+            .shared_settings
+            .context_cache_ttl()
+            .filter(|_| !config.gemini.auth.is_vertex());
+        let auth = config.gemini.auth.clone();
+        let reasoning_visibility = config.shared_settings.reasoning;
+        // What images in the reply are recorded as made from
+        let last_user = messages
+            .iter()
+            .rev()
+            .find(|message| message.role == UnifiedMessageRole::User);
+        let image_prompt = last_user.and_then(|message| message.content.clone()).unwrap_or_default();
+        let edited_from = last_user
+            .and_then(|message| message.image_edit.as_ref())
+            .map(|edit| edit.image.id.clone());
 
         Box::pin(async move {
-            log!("Gemini API call started");
+            logging::debug!("Gemini API call started");
 
             if api_key.trim().is_empty() {
-                return Err("Please configure your Gemini API key in Settings".to_string());
+                return Err(auth.missing_credentials().to_string());
             }
-
-            let request_body = GeminiRequest {
-                contents,
-                generation_config: Some(GenerationConfig {
-                    temperature,
-                    top_p: 0.95,
-                    top_k: 40,
-                    max_output_tokens: max_tokens as i32,
-                }),
-                system_instruction,
-                tools,
+            auth.validate()?;
+            let mut request = request?;
+            let cache_name = match cache_ttl {
+                Some(ttl_minutes) => {
+                    let endpoint = gemini_cache::CacheEndpoint {
+                        base_url: &base_url,
+                        api_key: &api_key,
+                        cors_proxy: cors_proxy.as_deref(),
+                    };
+                    endpoint.apply(&mut request, &model, ttl_minutes).await
+                }
+                None => None,
             };
 
-            let url = format!(
-                "{}/{}:generateContent?key={}",
-                base_url.trim_end_matches('/'), model, api_key
-            );
-
-            let response = Request::post(&url)
-                .header("Content-Type", "application/json")
-                .json(&request_body)
+            let request_started = js_sys::Date::now();
+            let response = request
+                .headers
+                .iter()
+                .fold(
+                    cors_proxy::post(&request.url, cors_proxy.as_deref()),
+                    |builder, (name, value)| builder.header(name, value),
+                )
+                .json(&request.body)
                 .map_err(|e| format!("Failed to create request: {}", e))?
                 .send()
                 .await
@@ -292,6 +439,7 @@ impl MessageSender for GeminiClient {
                         e
                     )
                 })?;
+            let headers_ms = js_sys::Date::now() - request_started;
 
             if !response.ok() {
                 let status = response.status();
@@ -300,6 +448,13 @@ impl MessageSender for GeminiClient {
                     .await
                     .unwrap_or_else(|_| "Unknown error".to_string());
 
+                // A cache deleted or expired on the server is recreated next time
+                if let Some(cache_name) = &cache_name {
+                    if error_text.contains("CachedContent") || error_text.contains(cache_name.as_str()) {
+                        gemini_cache::forget(cache_name);
+                    }
+                }
+
                 let error_message = if status == 400 {
                     if error_text.contains("API_KEY_INVALID") {
                         "Invalid Gemini API key. Please check your API key in Settings."
@@ -308,6 +463,8 @@ impl MessageSender for GeminiClient {
                     } else {
                         "Bad request to Gemini API. Please check your configuration."
                     }
+                } else if status == 401 && auth.is_vertex() {
+                    "Vertex AI rejected the access token. Tokens expire after an hour; paste a new one from gcloud auth print-access-token."
                 } else if status == 403 {
                     "Access denied. Please verify your Gemini API key has proper permissions."
                 } else if status == 429 {
@@ -337,10 +494,33 @@ impl MessageSender for GeminiClient {
             }
 
             let mut content = None;
+            let mut reasoning = None;
             let mut function_calls = Vec::new();
+            let mut images = Vec::new();
+            let image_id = format!("img_{}", js_sys::Date::now() as u64);
 
             // Process all parts to extract text content and function calls
             for part in &candidate.content.parts {
+                if let Some(inline_data) = &part.inline_data {
+                    if let (Some(mime_type), Some(data)) =
+                        (inline_data["mimeType"].as_str(), inline_data["data"].as_str())
+                    {
+                        images.push(GeneratedImage {
+                            id: format!("{}_{}", image_id, images.len()),
+                            mime_type: mime_type.to_string(),
+                            data: data.to_string(),
+                            prompt: image_prompt.clone(),
+                            edited_from: edited_from.clone(),
+                        });
+                    }
+                    continue;
+                }
+                if part.thought == Some(true) {
+                    if let Some(text) = &part.text {
+                        reasoning.get_or_insert_with(String::new).push_str(text);
+                    }
+                    continue;
+                }
                 if let Some(text) = &part.text {
                     if content.is_none() {
                         content = Some(text.clone());
@@ -370,11 +550,28 @@ impl MessageSender for GeminiClient {
                 }
             }
 
+            if content.as_deref().is_none_or(|text| text.trim().is_empty()) && !images.is_empty() {
+                content = Some(image_generation::caption(&images));
+            }
+
+            let usage = gemini_response.usage_metadata.as_ref().map(token_usage);
+
             // Return structured response that UI layer can handle
             Ok(LLMResponse {
                 content,
                 function_calls,
                 finish_reason: candidate.finish_reason.clone(),
+                reasoning: reasoning_visibility.keep(reasoning),
+                citations: Vec::new(),
+                alternatives: Vec::new(),
+                native_tool_calls: Vec::new(),
+                audio: None,
+                images,
+                usage,
+                timing: Some(ResponseTiming {
+                    headers_ms,
+                    latency_ms: js_sys::Date::now() - request_started,
+                }),
             })
         })
     }
@@ -388,20 +585,27 @@ impl StreamingSender for GeminiClient {
         system_prompt: Option<&str>,
         callback: StreamCallback,
     ) -> Pin<Box<dyn Future<Output = Result<(), String>> + '_>> {
-        let (contents, system_instruction) = self.convert_unified_messages_to_contents(messages, system_prompt);
+        let native_pdfs = attachments::reads_pdfs_natively(true, &config.gemini.model);
+        let (contents, system_instruction) =
+            self.convert_unified_messages_to_contents(messages, system_prompt, native_pdfs);
         let tools = self.build_tools(config);
+        let tool_config = Self::build_tool_config(config, &tools);
+        let response_schema = self.build_response_schema(config);
         let api_key = config.gemini.api_key.clone();
         let model = config.gemini.model.clone();
         let base_url = config.gemini.base_url.clone();
+        let auth = config.gemini.auth.clone();
+        let cors_proxy = config.shared_settings.cors_proxy.clone();
         let temperature = config.shared_settings.temperature;
         let max_tokens = config.shared_settings.max_tokens;
+        let stop_sequences = config.shared_settings.stop_sequences();
         let _config_clone = config.clone();
 
         Box::pin(async move {
-            log!("Gemini streaming API call started");
+            logging::debug!("Gemini streaming API call started");
 
             if api_key.trim().is_empty() {
-                return Err("Please configure your Gemini API key in Settings".to_string());
+                return Err(auth.missing_credentials().to_string());
             }
 
             let request_body = GeminiRequest {
@@ -411,19 +615,31 @@ impl StreamingSender for GeminiClient {
                     top_p: 0.95,
                     top_k: 40,
                     max_output_tokens: max_tokens as i32,
+                    response_mime_type: response_schema
+                        .is_some()
+                        .then(|| "application/json".to_string()),
+                    response_schema,
+                    stop_sequences,
+                    thinking_config: Self::build_thinking_config(&_config_clone),
+                    // Streamed replies carry text only
+                    response_modalities: None,
                 }),
                 system_instruction,
                 tools,
+                tool_config,
             };
 
-            let url = format!(
-                "{}/{}:streamGenerateContent?alt=sse&key={}",
-                base_url.trim_end_matches('/'), model, api_key
-            );
+            let url = auth.model_url(&base_url, &model, "streamGenerateContent?alt=sse", &api_key);
 
             // For WASM, we'll use a simpler approach since we can't do proper SSE streaming
             // We'll make a regular request and simulate streaming by sending the response in chunks
-            let response = Request::post(&url)
+            let response = auth
+                .auth_headers(&api_key)
+                .iter()
+                .fold(
+                    cors_proxy::post(&url, cors_proxy.as_deref()),
+                    |builder, (name, value)| builder.header(name, value),
+                )
                 .header("Content-Type", "application/json")
                 .json(&request_body)
                 .map_err(|e| format!("Failed to create request: {}", e))?
@@ -467,18 +683,23 @@ impl ModelProvider for GeminiClient {
     ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, String>> + '_>> {
         let api_key = config.gemini.api_key.clone();
         let base_url = config.gemini.base_url.clone();
+        let auth = config.gemini.auth.clone();
+        let cors_proxy = config.shared_settings.cors_proxy.clone();
 
         Box::pin(async move {
             if api_key.trim().is_empty() {
-                return Err("Please configure your Gemini API key to fetch models".to_string());
+                return Err(auth.missing_credentials().to_string());
             }
 
-            let url = format!(
-                "{}?key={}",
-                base_url.trim_end_matches('/'), api_key
-            );
+            let url = auth.models_url(&base_url, &api_key);
 
-            let response = Request::get(&url)
+            let response = auth
+                .auth_headers(&api_key)
+                .iter()
+                .fold(
+                    cors_proxy::get(&url, cors_proxy.as_deref()),
+                    |builder, (name, value)| builder.header(name, value),
+                )
                 .send()
                 .await
                 .map_err(|e| format!("Failed to fetch models: {}", e))?;
@@ -490,7 +711,11 @@ impl ModelProvider for GeminiClient {
 
             #[derive(Deserialize)]
             struct ModelsResponse {
+                #[serde(default)]
                 models: Vec<ModelInfo>,
+                // Vertex AI lists publisher models instead
+                #[serde(default, rename = "publisherModels")]
+                publisher_models: Vec<ModelInfo>,
             }
 
             #[derive(Deserialize)]
@@ -509,15 +734,13 @@ impl ModelProvider for GeminiClient {
             let model_names: Vec<String> = models_response
                 .models
                 .into_iter()
+                .chain(models_response.publisher_models)
                 .filter_map(|model| {
                     // Extract the model name from the full path (e.g., "models/gemini-pro" -> "gemini-pro")
-                    if let Some(model_name) = model.name.strip_prefix("models/") {
-                        // Filter for generative models (exclude embedding models, etc.)
-                        if model_name.contains("gemini") && !model_name.contains("embedding") {
-                            Some(model_name.to_string())
-                        } else {
-                            None
-                        }
+                    let model_name = gemini_auth::model_name(&model.name);
+                    // Filter for generative models (exclude embedding models, etc.)
+                    if model_name.contains("gemini") && !model_name.contains("embedding") {
+                        Some(model_name.to_string())
                     } else {
                         None
                     }
@@ -529,73 +752,55 @@ impl ModelProvider for GeminiClient {
     }
 }
 
-impl MessageConverter for GeminiClient {
-    fn convert_legacy_messages(&self, messages: &[Message]) -> Vec<UnifiedMessage> {
-        let mut unified_messages = Vec::new();
-
-        for message in messages {
-            let role = match message.role {
-                MessageRole::System => UnifiedMessageRole::System,
-                MessageRole::User => UnifiedMessageRole::User,
-                MessageRole::Assistant => UnifiedMessageRole::Assistant,
-                MessageRole::Function => UnifiedMessageRole::User, // Function responses become user messages
-            };
-
-            let mut function_calls = Vec::new();
-            let mut function_responses = Vec::new();
-
-            // Convert legacy function calls
-            if let Some(fc) = &message.function_call {
-                if let Ok(func_calls) = serde_json::from_value::<Vec<serde_json::Value>>(fc.clone()) {
-                    for func_call in func_calls {
-                        if let (Some(name), Some(args)) = (
-                            func_call.get("name").and_then(|v| v.as_str()),
-                            func_call.get("args").or_else(|| func_call.get("arguments")),
-                        ) {
-                            let id = func_call
-                                .get("id")
-                                .and_then(|v| v.as_str())
-                                .map(|s| s.to_string())
-                                .unwrap_or_else(|| {
-                                    format!("gemini-fc-{}-{}", name, js_sys::Date::now() as u64)
-                                });
-
-                            function_calls.push(FunctionCallRequest {
-                                id,
-                                name: name.to_string(),
-                                arguments: args.clone(),
-                            });
-                        }
-                    }
-                }
-            }
-
-            // Convert legacy function responses
-            if let Some(fr) = &message.function_response {
-                if let (Some(id), Some(name), Some(response)) = (
-                    fr.get("id").and_then(|v| v.as_str()),
-                    fr.get("name").and_then(|v| v.as_str()),
-                    fr.get("response"),
-                ) {
-                    function_responses.push(FunctionResponse {
-                        id: id.to_string(),
-                        name: name.to_string(),
-                        response: response.clone(),
-                    });
-                }
-            }
-
-            unified_messages.push(UnifiedMessage {
-                id: message.id.clone(),
-                role,
-                content: if message.content.is_empty() { None } else { Some(message.content.clone()) },
-                timestamp: message.timestamp,
-                function_calls,
-                function_responses,
-            });
-        }
-
-        unified_messages
+impl RequestBuilder for GeminiClient {
+    fn build_request(
+        &self,
+        messages: &[UnifiedMessage],
+        config: &ApiConfig,
+        system_prompt: Option<&str>,
+    ) -> Result<ProviderRequest, String> {
+        let native_pdfs = attachments::reads_pdfs_natively(true, &config.gemini.model);
+        let (contents, system_instruction) =
+            self.convert_unified_messages_to_contents(messages, system_prompt, native_pdfs);
+        let response_schema = self.build_response_schema(config);
+        // Image models take no tools
+        let image_model = image_generation::is_image_model(true, &config.gemini.model);
+        let tools = self.build_tools(config).filter(|_| !image_model);
+        let request_body = GeminiRequest {
+            contents,
+            generation_config: Some(GenerationConfig {
+                temperature: config.shared_settings.temperature,
+                top_p: 0.95,
+                top_k: 40,
+                max_output_tokens: config.shared_settings.max_tokens as i32,
+                response_mime_type: response_schema
+                    .is_some()
+                    .then(|| "application/json".to_string()),
+                response_schema,
+                stop_sequences: config.shared_settings.stop_sequences(),
+                thinking_config: Self::build_thinking_config(config),
+                response_modalities: image_model
+                    .then(|| vec!["TEXT".to_string(), "IMAGE".to_string()]),
+            }),
+            system_instruction,
+            tool_config: Self::build_tool_config(config, &tools),
+            tools,
+        };
+
+        let auth = &config.gemini.auth;
+        let mut headers = auth.auth_headers(&config.gemini.api_key);
+        headers.push(("Content-Type".to_string(), "application/json".to_string()));
+        Ok(ProviderRequest {
+            url: auth.model_url(
+                &config.gemini.base_url,
+                &config.gemini.model,
+                "generateContent",
+                &config.gemini.api_key,
+            ),
+            headers,
+            body: serde_json::to_value(&request_body)
+                .map_err(|e| format!("Failed to serialize request: {}", e))?,
+        })
     }
 }
 
@@ -606,70 +811,27 @@ impl LLMClient for GeminiClient {}
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::llm_playground::{ApiConfig, FunctionTool, Message, MessageRole};
+    use crate::llm_playground::{ApiConfig, FunctionTool, ReasoningVisibility};
     use serde_json::json;
 
-    fn create_test_client() -> GeminiClient {
-        GeminiClient::new()
-    }
-
-    fn create_test_message(role: MessageRole, content: &str) -> Message {
-        Message {
-            id: "test_id".to_string(),
+    fn message(role: UnifiedMessageRole, content: &str) -> UnifiedMessage {
+        UnifiedMessage {
+            id: String::new(),
             role,
-            content: content.to_string(),
+            content: Some(content.to_string()),
             timestamp: 0.0,
-            function_call: None,
-            function_response: None,
+            function_calls: Vec::new(),
+            function_responses: Vec::new(),
+            attachments: Vec::new(),
+            image_edit: None,
         }
     }
 
-    #[test]
-    fn test_new_gemini_client() {
-        let client = create_test_client();
-        assert!(client.conversation_history.is_empty());
-        assert!(client.system_prompt.is_none());
-    }
-
-    #[test]
-    fn test_set_system_prompt() {
-        let mut client = create_test_client();
-        let prompt = "You are a helpful assistant.";
-        client.set_system_prompt(prompt);
-        assert_eq!(client.system_prompt, Some(prompt.to_string()));
-    }
-
-    #[test]
-    fn test_add_user_message() {
-        let mut client = create_test_client();
-        client.add_user_message("Hello");
-        assert_eq!(client.conversation_history.len(), 1);
-        assert_eq!(client.conversation_history[0].role, "user");
-        assert_eq!(client.conversation_history[0].content, "Hello");
-    }
-
-    #[test]
-    fn test_add_assistant_message() {
-        let mut client = create_test_client();
-        client.add_assistant_message("Hi there!", None);
-        assert_eq!(client.conversation_history.len(), 1);
-        assert_eq!(client.conversation_history[0].role, "model");
-        assert_eq!(client.conversation_history[0].content, "Hi there!");
-    }
-
-    #[test]
-    fn test_clear_conversation() {
-        let mut client = create_test_client();
-        client.add_user_message("Hello");
-        client.clear_conversation();
-        assert!(client.conversation_history.is_empty());
-    }
-
     #[test]
     fn test_convert_messages_to_contents_simple() {
-        let client = create_test_client();
-        let messages = vec![create_test_message(MessageRole::User, "Hello")];
-        let (contents, system_instruction) = client.convert_messages_to_contents(&messages);
+        let client = GeminiClient::new();
+        let messages = [message(UnifiedMessageRole::User, "Hello")];
+        let (contents, system_instruction) = client.convert_unified_messages_to_contents(&messages, None, false);
 
         assert!(system_instruction.is_none());
         assert_eq!(contents.len(), 1);
@@ -679,13 +841,12 @@ mod tests {
     }
 
     #[test]
-    fn test_convert_messages_with_system_prompt_from_client() {
-        let mut client = create_test_client();
-        client.set_system_prompt("Be concise.");
-        let messages = vec![create_test_message(MessageRole::User, "Hello")];
-        let (contents, system_instruction) = client.convert_messages_to_contents(&messages);
+    fn test_convert_messages_with_system_prompt() {
+        let client = GeminiClient::new();
+        let messages = [message(UnifiedMessageRole::User, "Hello")];
+        let (contents, system_instruction) =
+            client.convert_unified_messages_to_contents(&messages, Some("Be concise."), false);
 
-        assert!(system_instruction.is_some());
         let instruction = system_instruction.unwrap();
         assert_eq!(instruction.parts[0].text, Some("Be concise.".to_string()));
         assert_eq!(contents.len(), 1);
@@ -693,48 +854,45 @@ mod tests {
 
     #[test]
     fn test_convert_messages_with_system_prompt_from_message() {
-        let client = create_test_client();
-        let messages = vec![
-            create_test_message(MessageRole::System, "Be verbose."),
-            create_test_message(MessageRole::User, "Hello"),
+        let client = GeminiClient::new();
+        let messages = [
+            message(UnifiedMessageRole::System, "Be verbose."),
+            message(UnifiedMessageRole::User, "Hello"),
         ];
-        let (contents, system_instruction) = client.convert_messages_to_contents(&messages);
+        let (contents, system_instruction) =
+            client.convert_unified_messages_to_contents(&messages, Some("Be concise."), false);
 
-        assert!(system_instruction.is_some());
+        // The prompt and the system message share the one system instruction
         let instruction = system_instruction.unwrap();
-        assert_eq!(instruction.parts[0].text, Some("Be verbose.".to_string()));
+        assert_eq!(instruction.parts.len(), 2);
+        assert_eq!(instruction.parts[1].text, Some("Be verbose.".to_string()));
         assert_eq!(contents.len(), 1);
         assert_eq!(contents[0].role, "user");
     }
 
     #[test]
     fn test_convert_messages_with_history() {
-        let mut client = create_test_client();
-        client.add_user_message("First message");
-        client.add_assistant_message("First response", None);
-
-        let messages = vec![create_test_message(MessageRole::User, "Second message")];
-
-        let (contents, _) = client.convert_messages_to_contents(&messages);
+        // Earlier turns come from the session, not from the client
+        let client = GeminiClient::new();
+        let messages = [
+            message(UnifiedMessageRole::User, "First message"),
+            message(UnifiedMessageRole::Assistant, "First response"),
+            message(UnifiedMessageRole::User, "Second message"),
+        ];
+        let (contents, _) = client.convert_unified_messages_to_contents(&messages, None, false);
 
         assert_eq!(contents.len(), 3);
         assert_eq!(contents[0].role, "user");
         assert_eq!(contents[0].parts[0].text, Some("First message".to_string()));
         assert_eq!(contents[1].role, "model");
-        assert_eq!(
-            contents[1].parts[0].text,
-            Some("First response".to_string())
-        );
+        assert_eq!(contents[1].parts[0].text, Some("First response".to_string()));
         assert_eq!(contents[2].role, "user");
-        assert_eq!(
-            contents[2].parts[0].text,
-            Some("Second message".to_string())
-        );
+        assert_eq!(contents[2].parts[0].text, Some("Second message".to_string()));
     }
 
     #[test]
     fn test_build_tools_empty() {
-        let client = create_test_client();
+        let client = GeminiClient::new();
         let mut config = ApiConfig::default();
         config.function_tools = vec![];
         let tools = client.build_tools(&config);
@@ -743,7 +901,7 @@ mod tests {
 
     #[test]
     fn test_build_tools_with_one_tool() {
-        let client = create_test_client();
+        let client = GeminiClient::new();
         let mut config = ApiConfig::default();
         config.function_tools.clear();
         config.function_tools.push(FunctionTool {
@@ -761,6 +919,8 @@ mod tests {
             mock_response: "".to_string(),
             enabled: true,
             category: "Weather".to_string(),
+            is_builtin: false,
+            requires: None,
         });
 
         let tools = client.build_tools(&config);
@@ -770,4 +930,93 @@ mod tests {
         assert_eq!(tool_vec[0].function_declarations.len(), 1);
         assert_eq!(tool_vec[0].function_declarations[0].name, "get_weather");
     }
+
+    #[test]
+    fn test_build_tool_config_any_mode_filters_allowed_functions() {
+        let tools = Some(vec![Tool {
+            function_declarations: ["Read", "Grep"]
+                .iter()
+                .map(|name| FunctionDeclaration {
+                    name: name.to_string(),
+                    description: String::new(),
+                    parameters: json!({}),
+                })
+                .collect(),
+        }]);
+        let mut config = ApiConfig::default();
+
+        // AUTO is Gemini's default, so nothing is sent
+        assert!(GeminiClient::build_tool_config(&config, &tools).is_none());
+
+        config.shared_settings.tool_choice = ToolChoice::None;
+        assert_eq!(
+            GeminiClient::build_tool_config(&config, &tools),
+            Some(json!({ "functionCallingConfig": { "mode": "NONE" } }))
+        );
+        assert!(GeminiClient::build_tool_config(&config, &None).is_none());
+
+        // Functions that aren't being sent are left out of the allowed names
+        config.shared_settings.tool_choice = ToolChoice::Required;
+        config.shared_settings.allowed_functions = vec!["Grep".to_string(), "Write".to_string()];
+        assert_eq!(
+            GeminiClient::build_tool_config(&config, &tools),
+            Some(json!({ "functionCallingConfig": { "mode": "ANY", "allowedFunctionNames": ["Grep"] } }))
+        );
+
+        config.shared_settings.allowed_functions = vec!["Write".to_string()];
+        assert_eq!(
+            GeminiClient::build_tool_config(&config, &tools),
+            Some(json!({ "functionCallingConfig": { "mode": "ANY" } }))
+        );
+    }
+
+    #[test]
+    fn test_token_usage_from_usage_metadata() {
+        let usage = token_usage(&json!({
+            "promptTokenCount": 1200,
+            "candidatesTokenCount": 300,
+            "totalTokenCount": 1500,
+            "cachedContentTokenCount": 1024
+        }));
+        assert_eq!((usage.prompt_tokens, usage.completion_tokens, usage.total_tokens), (1200, 300, 1500));
+        assert_eq!(usage.cached_tokens, 1024);
+
+        // Counts Gemini leaves out are zero
+        assert_eq!(token_usage(&json!({ "promptTokenCount": 7 })).total_tokens, 0);
+    }
+
+    #[test]
+    fn test_system_and_developer_messages_join_the_system_instruction_in_order() {
+        let client = GeminiClient::new();
+        let messages = [
+            message(UnifiedMessageRole::User, "Hello"),
+            message(UnifiedMessageRole::Developer, "Answer in French."),
+            message(UnifiedMessageRole::System, "  "),
+            message(UnifiedMessageRole::User, "How are you?"),
+            message(UnifiedMessageRole::System, "Be brief."),
+        ];
+        let (contents, system_instruction) =
+            client.convert_unified_messages_to_contents(&messages, Some("Be kind."), false);
+
+        let parts: Vec<_> = system_instruction
+            .unwrap()
+            .parts
+            .into_iter()
+            .map(|part| part.text.unwrap())
+            .collect();
+        assert_eq!(parts, ["Be kind.", "Answer in French.", "Be brief."]);
+        assert_eq!(contents.len(), 2);
+        assert!(contents.iter().all(|content| content.role == "user"));
+    }
+
+    #[test]
+    fn test_build_thinking_config_follows_the_reasoning_setting() {
+        let mut config = ApiConfig::default();
+        assert!(GeminiClient::build_thinking_config(&config).is_none());
+
+        config.shared_settings.reasoning = ReasoningVisibility::Visible;
+        assert_eq!(GeminiClient::build_thinking_config(&config), Some(json!({ "includeThoughts": true })));
+        config.shared_settings.reasoning = ReasoningVisibility::Hidden;
+        assert_eq!(GeminiClient::build_thinking_config(&config), Some(json!({ "includeThoughts": false })));
+    }
 }
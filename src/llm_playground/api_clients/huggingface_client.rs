@@ -0,0 +1,456 @@
+// Hugging Face text generation
+// Talks to the serverless Hugging Face Inference API and to
+// text-generation-inference (TGI) servers, including Inference Endpoints.
+// Both take the conversation flattened into one prompt as `inputs` with the
+// sampling `parameters`, and answer with `generated_text`. A base URL ending
+// in "/models" is the Inference API and gets the model appended; any other
+// base URL is a TGI server and gets "/generate". The access token (hf_...) is
+// sent as a bearer token; self-hosted servers may run without one. Tool
+// calls are not supported by this request format.
+use super::provider_factory::ProviderFactory;
+use super::traits::{
+    LLMClient, LLMResponse, MessageSender, ModelProvider, NamedClient,
+    ProviderRequest, RequestBuilder, ResponseTiming, StreamCallback, StreamingSender, TokenUsage,
+    UnifiedMessage, UnifiedMessageRole,
+};
+use crate::llm_playground::{
+    attachments, cors_proxy, logging, offload::estimate_tokens, provider_config::ProviderConfig, ApiConfig,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::future::Future;
+use std::pin::Pin;
+use web_sys::js_sys;
+
+pub const HUGGINGFACE_TRANSFORMER: &str = "huggingface";
+pub const INFERENCE_API_URL: &str = "https://api-inference.huggingface.co/models";
+/// Ends the reply where the model starts writing the user's next turn
+const STOP_SEQUENCE: &str = "\nUser:";
+
+/// Where generation requests for `model` go
+pub fn generate_url(base_url: &str, model: &str) -> String {
+    let base_url = base_url.trim_end_matches('/');
+    if base_url.ends_with("/models") {
+        format!("{}/{}", base_url, model)
+    } else {
+        format!("{}/generate", base_url)
+    }
+}
+
+/// Hosted by Hugging Face, which always needs a token
+pub fn is_hosted(base_url: &str) -> bool {
+    base_url.contains("huggingface.co") || base_url.contains("huggingface.cloud")
+}
+
+/// The conversation as a plain transcript ending with the assistant's turn
+pub fn build_prompt(messages: &[UnifiedMessage], system_prompt: Option<&str>) -> String {
+    let mut instructions: Vec<&str> = system_prompt
+        .filter(|prompt| !prompt.trim().is_empty())
+        .into_iter()
+        .collect();
+    let mut turns = Vec::new();
+    for message in messages {
+        let content = message.content.as_deref().unwrap_or_default();
+        match message.role {
+            UnifiedMessageRole::System | UnifiedMessageRole::Developer => {
+                instructions.push(content);
+            }
+            UnifiedMessageRole::User if !message.function_responses.is_empty() => {
+                for response in &message.function_responses {
+                    turns.push(format!(
+                        "User: Result of {}: {}",
+                        response.name, response.response
+                    ));
+                }
+            }
+            UnifiedMessageRole::User => turns.push(format!(
+                "User: {}",
+                attachments::content_with_text(Some(content), &message.attachments).unwrap_or_default()
+            )),
+            UnifiedMessageRole::Assistant => turns.push(format!("Assistant: {}", content)),
+        }
+    }
+
+    let mut prompt = String::new();
+    if !instructions.is_empty() {
+        prompt.push_str(&format!("System: {}\n\n", instructions.join("\n\n")));
+    }
+    for turn in turns {
+        prompt.push_str(&turn);
+        prompt.push_str("\n\n");
+    }
+    prompt.push_str("Assistant:");
+    prompt
+}
+
+pub fn build_body(prompt: &str, temperature: f32, max_tokens: u32, stop: &[String]) -> Value {
+    let stop: Vec<&str> = std::iter::once(STOP_SEQUENCE)
+        .chain(stop.iter().map(String::as_str))
+        .collect();
+    let mut parameters = json!({
+        "max_new_tokens": max_tokens,
+        "return_full_text": false,
+        "stop": stop,
+        "details": true,
+    });
+    // TGI rejects a temperature of 0; greedy decoding is asked for instead
+    if temperature > 0.0 {
+        parameters["temperature"] = json!(temperature);
+        parameters["do_sample"] = json!(true);
+    } else {
+        parameters["do_sample"] = json!(false);
+    }
+    json!({ "inputs": prompt, "parameters": parameters })
+}
+
+#[derive(Debug, Deserialize)]
+struct Generation {
+    generated_text: String,
+    #[serde(default)]
+    details: Option<GenerationDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerationDetails {
+    #[serde(default)]
+    finish_reason: Option<String>,
+    #[serde(default)]
+    generated_tokens: Option<u32>,
+}
+
+/// Text, finish reason and generated token count of a reply; the Inference
+/// API wraps the generation in an array, TGI does not
+pub fn parse_generation(value: Value) -> Result<(String, Option<String>, Option<u32>), String> {
+    let value = match value {
+        Value::Array(mut items) if !items.is_empty() => items.remove(0),
+        value => value,
+    };
+    let generation: Generation =
+        serde_json::from_value(value).map_err(|e| format!("Failed to parse response: {}", e))?;
+    let text = generation
+        .generated_text
+        .strip_suffix(STOP_SEQUENCE)
+        .unwrap_or(&generation.generated_text)
+        .trim()
+        .to_string();
+    let (finish_reason, generated_tokens) = generation
+        .details
+        .map(|details| (details.finish_reason, details.generated_tokens))
+        .unwrap_or_default();
+    Ok((text, finish_reason, generated_tokens))
+}
+
+/// Client for the Hugging Face text generation format
+#[derive(Clone, Debug, Default)]
+pub struct HuggingFaceClient;
+
+impl HuggingFaceClient {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn auth_headers(api_key: &str) -> Vec<(String, String)> {
+        if api_key.trim().is_empty() {
+            Vec::new()
+        } else {
+            vec![("Authorization".to_string(), format!("Bearer {}", api_key))]
+        }
+    }
+}
+
+impl MessageSender for HuggingFaceClient {
+    fn send_message(
+        &self,
+        messages: &[UnifiedMessage],
+        config: &ApiConfig,
+        system_prompt: Option<&str>,
+    ) -> Pin<Box<dyn Future<Output = Result<LLMResponse, String>> + '_>> {
+        let request = self.build_request(messages, config, system_prompt);
+        let api_key = config.openai.api_key.clone();
+        let hosted = is_hosted(&config.openai.base_url);
+        let cors_proxy = config.shared_settings.cors_proxy.clone();
+
+        Box::pin(async move {
+            if hosted && api_key.trim().is_empty() {
+                return Err(
+                    "Please configure your Hugging Face access token in Settings".to_string(),
+                );
+            }
+            let request = request?;
+            let prompt_tokens = request.body["inputs"]
+                .as_str()
+                .map(estimate_tokens)
+                .unwrap_or_default() as u32;
+
+            let request_started = js_sys::Date::now();
+            let response = request
+                .headers
+                .iter()
+                .fold(
+                    cors_proxy::post(&request.url, cors_proxy.as_deref()),
+                    |builder, (name, value)| builder.header(name, value),
+                )
+                .json(&request.body)
+                .map_err(|e| format!("Failed to create request: {}", e))?
+                .send()
+                .await
+                .map_err(|e| format!("Network error: {}", e))?;
+            let headers_ms = js_sys::Date::now() - request_started;
+
+            if !response.ok() {
+                let status = response.status();
+                let error_text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| "Unknown error".to_string());
+                let error_message = match status {
+                    401 => "Invalid Hugging Face access token. Please check it in Settings.",
+                    404 => "Model not found. Check the model name and that it is served for text generation.",
+                    422 => "The server rejected the request parameters, e.g. max tokens above the model's limit.",
+                    429 => "Rate limit exceeded. Please wait a moment before trying again.",
+                    503 => "The model is loading or unavailable. Please try again in a moment.",
+                    _ => "Hugging Face API error occurred. Please try again.",
+                };
+                return Err(format!(
+                    "{}\n\nDetailed error: {}",
+                    error_message, error_text
+                ));
+            }
+
+            let value: Value = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+            logging::debug!("Hugging Face response: {}", value);
+            let (text, finish_reason, generated_tokens) = parse_generation(value)?;
+            let completion_tokens =
+                generated_tokens.unwrap_or_else(|| estimate_tokens(&text) as u32);
+
+            Ok(LLMResponse {
+                content: (!text.is_empty()).then_some(text),
+                function_calls: Vec::new(),
+                finish_reason,
+                reasoning: None,
+                citations: Vec::new(),
+                alternatives: Vec::new(),
+                native_tool_calls: Vec::new(),
+                audio: None,
+                images: Vec::new(),
+                usage: Some(TokenUsage {
+                    prompt_tokens,
+                    completion_tokens,
+                    total_tokens: prompt_tokens + completion_tokens,
+                    cached_tokens: 0,
+                    queue_time: None,
+                    completion_time: None,
+                }),
+                timing: Some(ResponseTiming {
+                    headers_ms,
+                    latency_ms: js_sys::Date::now() - request_started,
+                }),
+            })
+        })
+    }
+}
+
+impl StreamingSender for HuggingFaceClient {
+    fn send_message_stream(
+        &self,
+        messages: &[UnifiedMessage],
+        config: &ApiConfig,
+        system_prompt: Option<&str>,
+        callback: StreamCallback,
+    ) -> Pin<Box<dyn Future<Output = Result<(), String>> + '_>> {
+        let response = self.send_message(messages, config, system_prompt);
+        Box::pin(async move {
+            // Sent as a single chunk, like the other clients
+            let response = response.await?;
+            callback(response.content.unwrap_or_default(), None);
+            Ok(())
+        })
+    }
+}
+
+impl ModelProvider for HuggingFaceClient {
+    fn get_available_models(
+        &self,
+        config: &ApiConfig,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, String>> + '_>> {
+        let base_url = config.openai.base_url.trim_end_matches('/').to_string();
+        let auth_headers = Self::auth_headers(&config.openai.api_key);
+        let cors_proxy = config.shared_settings.cors_proxy.clone();
+
+        Box::pin(async move {
+            if base_url.ends_with("/models") {
+                return Err(
+                    "The Inference API cannot list its models; add them by name, e.g. mistralai/Mistral-7B-Instruct-v0.3"
+                        .to_string(),
+                );
+            }
+
+            // A TGI server serves one model and names it in /info
+            #[derive(Deserialize)]
+            struct Info {
+                model_id: String,
+            }
+
+            let response = auth_headers
+                .iter()
+                .fold(
+                    cors_proxy::get(&format!("{}/info", base_url), cors_proxy.as_deref()),
+                    |builder, (name, value)| builder.header(name, value),
+                )
+                .send()
+                .await
+                .map_err(|e| format!("Failed to fetch models: {}", e))?;
+            if !response.ok() {
+                return Err(format!(
+                    "Failed to fetch models, status: {}",
+                    response.status()
+                ));
+            }
+            let info: Info = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse server info: {}", e))?;
+            Ok(vec![info.model_id])
+        })
+    }
+}
+
+impl NamedClient for HuggingFaceClient {
+    fn client_name(&self) -> &str {
+        "Hugging Face"
+    }
+}
+
+impl RequestBuilder for HuggingFaceClient {
+    fn build_request(
+        &self,
+        messages: &[UnifiedMessage],
+        config: &ApiConfig,
+        system_prompt: Option<&str>,
+    ) -> Result<ProviderRequest, String> {
+        let prompt = build_prompt(messages, system_prompt);
+        let mut headers = vec![("Content-Type".to_string(), "application/json".to_string())];
+        headers.extend(Self::auth_headers(&config.openai.api_key));
+        Ok(ProviderRequest {
+            url: generate_url(&config.openai.base_url, &config.openai.model),
+            headers,
+            body: build_body(
+                &prompt,
+                config.shared_settings.temperature,
+                config.shared_settings.max_tokens,
+                &config.shared_settings.stop_sequences(),
+            ),
+        })
+    }
+}
+
+impl LLMClient for HuggingFaceClient {}
+
+/// Factory for the Hugging Face client, keyed on the "huggingface" transformer
+pub struct HuggingFaceProviderFactory;
+
+impl HuggingFaceProviderFactory {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ProviderFactory for HuggingFaceProviderFactory {
+    fn supports_provider(&self, provider_type: &str) -> bool {
+        provider_type == HUGGINGFACE_TRANSFORMER
+    }
+
+    fn create_client(&self, _config: &ProviderConfig) -> Result<Box<dyn LLMClient>, String> {
+        Ok(Box::new(HuggingFaceClient::new()))
+    }
+
+    fn provider_type(&self) -> &str {
+        HUGGINGFACE_TRANSFORMER
+    }
+}
+
+impl Default for HuggingFaceProviderFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::api_clients::FunctionResponse;
+
+    fn message(role: UnifiedMessageRole, content: &str) -> UnifiedMessage {
+        UnifiedMessage {
+            id: String::new(),
+            role,
+            content: Some(content.to_string()),
+            timestamp: 0.0,
+            function_calls: Vec::new(),
+            function_responses: Vec::new(),
+            attachments: Vec::new(),
+            image_edit: None,
+        }
+    }
+
+    #[test]
+    fn requests_carry_a_transcript_prompt_to_the_right_route() {
+        let mut result = message(UnifiedMessageRole::User, "");
+        result.function_responses.push(FunctionResponse {
+            id: "call_1".to_string(),
+            name: "get_time".to_string(),
+            response: json!({ "time": "12:00" }),
+        });
+        let messages = vec![
+            message(UnifiedMessageRole::Developer, "Answer in French."),
+            message(UnifiedMessageRole::User, "Hi"),
+            message(UnifiedMessageRole::Assistant, "Bonjour"),
+            result,
+        ];
+        assert_eq!(
+            build_prompt(&messages, Some("Be brief.")),
+            "System: Be brief.\n\nAnswer in French.\n\nUser: Hi\n\nAssistant: Bonjour\n\nUser: Result of get_time: {\"time\":\"12:00\"}\n\nAssistant:"
+        );
+
+        assert_eq!(
+            generate_url(INFERENCE_API_URL, "HuggingFaceH4/zephyr-7b-beta"),
+            "https://api-inference.huggingface.co/models/HuggingFaceH4/zephyr-7b-beta"
+        );
+        assert_eq!(
+            generate_url("http://localhost:8080/", "ignored"),
+            "http://localhost:8080/generate"
+        );
+        assert!(is_hosted("https://x.endpoints.huggingface.cloud"));
+        assert!(!is_hosted("http://localhost:8080"));
+
+        let greedy = build_body("p", 0.0, 64, &[]);
+        assert_eq!(greedy["parameters"]["do_sample"], false);
+        assert!(greedy["parameters"].get("temperature").is_none());
+        let sampled = build_body("p", 0.7, 64, &["###".to_string()]);
+        assert_eq!(sampled["parameters"]["max_new_tokens"], 64);
+        assert_eq!(sampled["parameters"]["stop"], json!([STOP_SEQUENCE, "###"]));
+    }
+
+    #[test]
+    fn inference_api_and_tgi_replies_are_parsed() {
+        let (text, finish_reason, tokens) =
+            parse_generation(json!([{ "generated_text": " Hello there.\nUser:" }])).unwrap();
+        assert_eq!(text, "Hello there.");
+        assert_eq!(finish_reason, None);
+        assert_eq!(tokens, None);
+
+        let (text, finish_reason, tokens) = parse_generation(json!({
+            "generated_text": "Cut off",
+            "details": { "finish_reason": "length", "generated_tokens": 64 }
+        }))
+        .unwrap();
+        assert_eq!(text, "Cut off");
+        assert_eq!(finish_reason.as_deref(), Some("length"));
+        assert_eq!(tokens, Some(64));
+
+        assert!(parse_generation(json!({ "error": "Model is loading" })).is_err());
+    }
+}
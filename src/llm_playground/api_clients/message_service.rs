@@ -1,5 +1,5 @@
 // Message conversion service (SRP compliance)
-use super::traits::{UnifiedMessage, UnifiedMessageRole};
+use super::traits::{FunctionCallRequest, FunctionResponse, UnifiedMessage, UnifiedMessageRole};
 use crate::llm_playground::{Message, MessageRole};
 
 /// Service responsible for message format conversion
@@ -11,25 +11,68 @@ impl MessageConversionService {
         Self
     }
 
-    /// Convert legacy messages to unified format
+    /// Convert stored messages to the unified format every client sends.
+    /// Tool calls are read with `arguments` or Gemini's `args`; calls saved
+    /// without an id get `call_{n}`. Tool results go out as user turns.
     pub fn convert_legacy_to_unified(&self, messages: &[Message]) -> Vec<UnifiedMessage> {
+        let mut function_call_id_counter = 0u32;
         messages
             .iter()
-            .map(|msg| {
-                let role = match msg.role {
+            .map(|message| {
+                let role = match message.role {
+                    MessageRole::System => UnifiedMessageRole::System,
+                    MessageRole::Developer => UnifiedMessageRole::Developer,
                     MessageRole::User => UnifiedMessageRole::User,
                     MessageRole::Assistant => UnifiedMessageRole::Assistant,
-                    MessageRole::System => UnifiedMessageRole::System,
-                    MessageRole::Function => UnifiedMessageRole::Assistant, // Map function to assistant
+                    MessageRole::Function => UnifiedMessageRole::User,
                 };
 
+                let function_calls = message
+                    .function_call
+                    .clone()
+                    .and_then(|calls| serde_json::from_value::<Vec<serde_json::Value>>(calls).ok())
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|call| {
+                        let name = call.get("name").and_then(|v| v.as_str())?;
+                        let arguments = call.get("arguments").or_else(|| call.get("args"))?;
+                        let id = match call.get("id").and_then(|v| v.as_str()) {
+                            Some(id) => id.to_string(),
+                            None => {
+                                function_call_id_counter += 1;
+                                format!("call_{}", function_call_id_counter)
+                            }
+                        };
+                        Some(FunctionCallRequest {
+                            id,
+                            name: name.to_string(),
+                            arguments: arguments.clone(),
+                        })
+                    })
+                    .collect();
+
+                let function_responses = message
+                    .function_response
+                    .as_ref()
+                    .and_then(|response| {
+                        Some(FunctionResponse {
+                            id: response.get("id")?.as_str()?.to_string(),
+                            name: response.get("name")?.as_str()?.to_string(),
+                            response: response.get("response")?.clone(),
+                        })
+                    })
+                    .into_iter()
+                    .collect();
+
                 UnifiedMessage {
-                    id: msg.id.clone(),
+                    id: message.id.clone(),
                     role,
-                    content: Some(msg.content.clone()),
-                    timestamp: msg.timestamp,
-                    function_calls: vec![], // Legacy messages don't have function calls
-                    function_responses: vec![],
+                    content: Some(message.content.clone()).filter(|content| !content.is_empty()),
+                    timestamp: message.timestamp,
+                    function_calls,
+                    function_responses,
+                    attachments: message.attachments.clone(),
+                    image_edit: message.image_edit.clone(),
                 }
             })
             .collect()
@@ -44,15 +87,13 @@ impl MessageConversionService {
                     UnifiedMessageRole::User => MessageRole::User,
                     UnifiedMessageRole::Assistant => MessageRole::Assistant,
                     UnifiedMessageRole::System => MessageRole::System,
+                    UnifiedMessageRole::Developer => MessageRole::Developer,
                 };
 
                 msg.content.as_ref().map(|content| Message {
                     id: msg.id.clone(),
-                    role,
-                    content: content.clone(),
                     timestamp: msg.timestamp,
-                    function_call: None,
-                    function_response: None,
+                    ..Message::new(role, content.clone())
                 })
             })
             .collect()
@@ -68,22 +109,54 @@ impl Default for MessageConversionService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
+
+    fn message(role: MessageRole, content: &str) -> Message {
+        Message {
+            id: "1".to_string(),
+            timestamp: 123.0,
+            ..Message::new(role, content)
+        }
+    }
 
     #[test]
     fn test_legacy_to_unified_conversion() {
         let service = MessageConversionService::new();
-        let legacy_messages = vec![
-            Message {
-                id: "1".to_string(),
-                role: MessageRole::User,
-                content: "Hello".to_string(),
-                timestamp: 123.0,
-            }
-        ];
-
-        let unified = service.convert_legacy_to_unified(&legacy_messages);
+        let unified = service.convert_legacy_to_unified(&[message(MessageRole::User, "Hello")]);
         assert_eq!(unified.len(), 1);
         assert_eq!(unified[0].content, Some("Hello".to_string()));
         assert!(matches!(unified[0].role, UnifiedMessageRole::User));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn tool_calls_of_both_providers_are_read() {
+        let service = MessageConversionService::new();
+        let mut call = message(MessageRole::Assistant, "");
+        call.function_call = Some(json!([
+            {"id": "call_abc", "name": "get_weather", "arguments": {"city": "Paris"}},
+            {"name": "get_time", "args": {"zone": "UTC"}},
+        ]));
+        let mut result = message(MessageRole::Function, "Function get_weather executed");
+        result.function_response = Some(json!({"id": "call_abc", "name": "get_weather", "response": "Sunny"}));
+
+        let unified = service.convert_legacy_to_unified(&[call, result]);
+        assert_eq!(unified[0].content, None);
+        assert_eq!(
+            unified[0].function_calls,
+            [
+                FunctionCallRequest {
+                    id: "call_abc".to_string(),
+                    name: "get_weather".to_string(),
+                    arguments: json!({"city": "Paris"}),
+                },
+                FunctionCallRequest {
+                    id: "call_1".to_string(),
+                    name: "get_time".to_string(),
+                    arguments: json!({"zone": "UTC"}),
+                },
+            ]
+        );
+        assert!(matches!(unified[1].role, UnifiedMessageRole::User));
+        assert_eq!(unified[1].function_responses[0].response, json!("Sunny"));
+    }
+}
@@ -0,0 +1,342 @@
+// Mock LLM provider for tests and demos
+// Answers from a script instead of calling an API: the reply for each turn is
+// picked by how many assistant turns the conversation already has, so the
+// same conversation always gets the same answer. Scripted replies can call
+// tools, and `{{user}}` / `{{tool_result}}` in their text are filled in from
+// the conversation. The "echo" model ignores the script and repeats the user.
+use super::provider_factory::ProviderFactory;
+use super::traits::{
+    FunctionCallRequest, LLMClient, LLMResponse, MessageSender, ModelProvider,
+    NamedClient, ProviderRequest, RequestBuilder, ResponseTiming, StreamCallback, StreamingSender,
+    TokenUsage, UnifiedMessage, UnifiedMessageRole,
+};
+use crate::llm_playground::{provider_config::ProviderConfig, ApiConfig};
+use gloo_timers::future::TimeoutFuture;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::future::Future;
+use std::pin::Pin;
+
+pub const MOCK_TRANSFORMER: &str = "mock";
+pub const ECHO_MODEL: &str = "echo";
+
+/// Scripted replies of a mock provider
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MockScript {
+    /// Milliseconds to wait before answering
+    pub delay_ms: u32,
+    /// One reply per assistant turn, starting over once all were used
+    pub responses: Vec<MockResponse>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MockResponse {
+    pub content: String,
+    pub function_calls: Vec<MockFunctionCall>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MockFunctionCall {
+    pub name: String,
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+impl MockScript {
+    /// A weather lookup followed by a summary of the tool result
+    pub fn demo() -> Self {
+        Self {
+            delay_ms: 600,
+            responses: vec![
+                MockResponse {
+                    content: "Let me check the weather for that.".to_string(),
+                    function_calls: vec![MockFunctionCall {
+                        name: "get_weather".to_string(),
+                        arguments: serde_json::json!({ "location": "Tokyo", "unit": "celsius" }),
+                    }],
+                },
+                MockResponse {
+                    content: "Here is what the weather tool returned:\n\n```json\n{{tool_result}}\n```\n\nThis reply comes from the mock provider, so no API key was needed.".to_string(),
+                    function_calls: Vec::new(),
+                },
+            ],
+        }
+    }
+
+    /// The reply for the next assistant turn of `messages`
+    pub fn reply(&self, model: &str, messages: &[UnifiedMessage]) -> LLMResponse {
+        let turn = messages
+            .iter()
+            .filter(|m| m.role == UnifiedMessageRole::Assistant)
+            .count();
+        let last_user = messages
+            .iter()
+            .rev()
+            .find(|m| m.role == UnifiedMessageRole::User && m.function_responses.is_empty())
+            .and_then(|m| m.content.clone())
+            .unwrap_or_default();
+        let tool_result = messages
+            .iter()
+            .rev()
+            .find_map(|m| m.function_responses.last())
+            .map(|r| serde_json::to_string_pretty(&r.response).unwrap_or_default())
+            .unwrap_or_default();
+
+        let scripted = if model == ECHO_MODEL || self.responses.is_empty() {
+            None
+        } else {
+            self.responses.get(turn % self.responses.len())
+        };
+        let (content, function_calls) = match scripted {
+            Some(response) => (
+                response
+                    .content
+                    .replace("{{user}}", &last_user)
+                    .replace("{{tool_result}}", &tool_result),
+                response
+                    .function_calls
+                    .iter()
+                    .enumerate()
+                    .map(|(index, call)| FunctionCallRequest {
+                        id: format!("mock_call_{}_{}", turn, index),
+                        name: call.name.clone(),
+                        arguments: call.arguments.clone(),
+                    })
+                    .collect(),
+            ),
+            None => (format!("You said: {}", last_user), Vec::new()),
+        };
+
+        // Rough counts, about four characters per token
+        let prompt_chars: usize = messages
+            .iter()
+            .filter_map(|m| m.content.as_ref())
+            .map(|c| c.chars().count())
+            .sum();
+        let prompt_tokens = prompt_chars.div_ceil(4) as u32;
+        let completion_tokens = content.chars().count().div_ceil(4) as u32;
+
+        LLMResponse {
+            finish_reason: Some(
+                if function_calls.is_empty() {
+                    "stop"
+                } else {
+                    "tool_calls"
+                }
+                .to_string(),
+            ),
+            reasoning: None,
+            citations: Vec::new(),
+            alternatives: Vec::new(),
+            native_tool_calls: Vec::new(),
+            audio: None,
+            images: Vec::new(),
+            content: (!content.is_empty()).then_some(content),
+            function_calls,
+            usage: Some(TokenUsage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+                cached_tokens: 0,
+                queue_time: None,
+                completion_time: None,
+            }),
+            timing: Some(ResponseTiming {
+                headers_ms: self.delay_ms as f64,
+                latency_ms: self.delay_ms as f64,
+            }),
+        }
+    }
+}
+
+/// Client answering from a `MockScript`
+#[derive(Clone, Debug, Default)]
+pub struct MockProvider {
+    script: MockScript,
+}
+
+impl MockProvider {
+    pub fn new(script: MockScript) -> Self {
+        Self { script }
+    }
+}
+
+impl MessageSender for MockProvider {
+    fn send_message(
+        &self,
+        messages: &[UnifiedMessage],
+        config: &ApiConfig,
+        _system_prompt: Option<&str>,
+    ) -> Pin<Box<dyn Future<Output = Result<LLMResponse, String>> + '_>> {
+        let response = self.script.reply(&config.openai.model, messages);
+        let delay_ms = self.script.delay_ms;
+        Box::pin(async move {
+            if delay_ms > 0 {
+                TimeoutFuture::new(delay_ms).await;
+            }
+            Ok(response)
+        })
+    }
+}
+
+impl StreamingSender for MockProvider {
+    fn send_message_stream(
+        &self,
+        messages: &[UnifiedMessage],
+        config: &ApiConfig,
+        _system_prompt: Option<&str>,
+        callback: StreamCallback,
+    ) -> Pin<Box<dyn Future<Output = Result<(), String>> + '_>> {
+        let response = self.script.reply(&config.openai.model, messages);
+        let delay_ms = self.script.delay_ms;
+        Box::pin(async move {
+            // Word by word, spreading the delay over the chunks
+            let content = response.content.unwrap_or_default();
+            let words: Vec<&str> = content.split_inclusive(' ').collect();
+            let chunk_delay = delay_ms / words.len().max(1) as u32;
+            for word in words {
+                if chunk_delay > 0 {
+                    TimeoutFuture::new(chunk_delay).await;
+                }
+                callback(word.to_string(), None);
+            }
+            if !response.function_calls.is_empty() {
+                callback(
+                    String::new(),
+                    serde_json::to_value(&response.function_calls).ok(),
+                );
+            }
+            Ok(())
+        })
+    }
+}
+
+impl ModelProvider for MockProvider {
+    fn get_available_models(
+        &self,
+        _config: &ApiConfig,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, String>> + '_>> {
+        Box::pin(async { Ok(vec!["scripted".to_string(), ECHO_MODEL.to_string()]) })
+    }
+}
+
+impl NamedClient for MockProvider {
+    fn client_name(&self) -> &str {
+        "Mock"
+    }
+}
+
+impl RequestBuilder for MockProvider {
+    fn build_request(
+        &self,
+        _messages: &[UnifiedMessage],
+        _config: &ApiConfig,
+        _system_prompt: Option<&str>,
+    ) -> Result<ProviderRequest, String> {
+        Err("The mock provider answers locally and sends no request".to_string())
+    }
+}
+
+impl LLMClient for MockProvider {}
+
+/// Factory for the mock provider, keyed on the "mock" transformer
+pub struct MockProviderFactory;
+
+impl MockProviderFactory {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ProviderFactory for MockProviderFactory {
+    fn supports_provider(&self, provider_type: &str) -> bool {
+        provider_type == MOCK_TRANSFORMER
+    }
+
+    fn create_client(&self, config: &ProviderConfig) -> Result<Box<dyn LLMClient>, String> {
+        Ok(Box::new(MockProvider::new(
+            config.mock_script.clone().unwrap_or_default(),
+        )))
+    }
+
+    fn provider_type(&self) -> &str {
+        MOCK_TRANSFORMER
+    }
+}
+
+impl Default for MockProviderFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::api_clients::FunctionResponse;
+
+    fn message(role: UnifiedMessageRole, content: &str) -> UnifiedMessage {
+        UnifiedMessage {
+            id: String::new(),
+            role,
+            content: Some(content.to_string()),
+            timestamp: 0.0,
+            function_calls: Vec::new(),
+            function_responses: Vec::new(),
+            attachments: Vec::new(),
+            image_edit: None,
+        }
+    }
+
+    #[test]
+    fn script_replies_follow_the_assistant_turns() {
+        let script = MockScript::demo();
+        let mut messages = vec![message(UnifiedMessageRole::User, "Weather in Tokyo?")];
+
+        let first = script.reply("scripted", &messages);
+        assert_eq!(first.function_calls.len(), 1);
+        assert_eq!(first.function_calls[0].name, "get_weather");
+        assert_eq!(first.function_calls[0].id, "mock_call_0_0");
+        assert_eq!(first.finish_reason.as_deref(), Some("tool_calls"));
+        // The same conversation gets the same reply
+        assert_eq!(script.reply("scripted", &messages), first);
+
+        messages.push(message(UnifiedMessageRole::Assistant, "Let me check."));
+        let mut result = message(UnifiedMessageRole::User, "");
+        result.function_responses.push(FunctionResponse {
+            id: "mock_call_0_0".to_string(),
+            name: "get_weather".to_string(),
+            response: serde_json::json!({ "temperature": 22 }),
+        });
+        messages.push(result);
+
+        let second = script.reply("scripted", &messages);
+        assert!(second.function_calls.is_empty());
+        assert!(second.content.unwrap().contains("\"temperature\": 22"));
+        assert_eq!(second.finish_reason.as_deref(), Some("stop"));
+    }
+
+    #[test]
+    fn echo_model_and_empty_script_repeat_the_user() {
+        let messages = vec![
+            message(UnifiedMessageRole::User, "First"),
+            message(UnifiedMessageRole::Assistant, "Reply"),
+            message(UnifiedMessageRole::User, "Second"),
+        ];
+        let echo = MockScript::demo().reply(ECHO_MODEL, &messages);
+        assert_eq!(echo.content.as_deref(), Some("You said: Second"));
+        assert_eq!(
+            MockScript::default().reply("scripted", &messages).content,
+            echo.content
+        );
+
+        let usage = echo.usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 4);
+        assert_eq!(
+            usage.total_tokens,
+            usage.prompt_tokens + usage.completion_tokens
+        );
+    }
+}
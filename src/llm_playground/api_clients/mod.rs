@@ -1,36 +1,36 @@
-// API client modules - Legacy modules (for backward compatibility)
-pub mod conversation;
-pub mod gemini_client;
-pub mod openai_client;
-pub mod traits;
-
-// New SOLID-compliant modules
-pub mod client_service;
+// API clients
+// Every request goes through one pipeline: the MessageConversionService turns
+// stored messages into unified ones, and the ProviderRegistry picks the client
+// for the provider's transformer (OpenAI-compatible, Gemini, Hugging Face,
+// the mock provider, or a plugin descriptor).
+pub mod embeddings;
 pub mod factory_setup;
+pub mod gemini_auth;
+pub mod gemini_cache;
+pub mod gemini_client;
 pub mod gemini_factory;
-pub mod interfaces;
+pub mod huggingface_client;
 pub mod message_service;
+pub mod mock_provider;
+pub mod openai_client;
 pub mod openai_factory;
+pub mod openai_responses;
 pub mod provider_factory;
-pub mod refactored_openai_client;
-pub mod refactored_flexible_client;
-pub mod example_usage;
+pub mod provider_plugin;
+pub mod traits;
 
-// Legacy exports (for backward compatibility)
 pub use gemini_client::GeminiClient;
+pub use huggingface_client::HuggingFaceClient;
 pub use openai_client::OpenAIClient;
 pub use traits::{
     FunctionCallRequest, FunctionResponse, LLMClient,
-    LLMResponse, MessageConverter, MessageSender, ModelProvider, NamedClient, StreamCallback,
-    StreamingSender, UnifiedMessage, UnifiedMessageRole,
-};
-
-// New SOLID-compliant exports
-pub use client_service::{ClientService, ClientServiceBuilder};
-pub use factory_setup::{initialize_provider_system, create_test_provider_system};
-pub use interfaces::{
-    FunctionCaller,
+    LLMResponse, MessageSender, ModelProvider, NamedClient, ProviderRequest,
+    RequestBuilder, ResponseTiming, StreamCallback, StreamingSender, TokenUsage, UnifiedMessage,
+    UnifiedMessageRole,
 };
+pub use factory_setup::provider_registry;
 pub use message_service::MessageConversionService;
+pub use mock_provider::{MockProvider, MockScript};
 pub use provider_factory::{ProviderFactory, ProviderRegistry};
-pub use refactored_flexible_client::RefactoredFlexibleLLMClient;
+pub use gemini_auth::GeminiAuth;
+pub use provider_plugin::{AuthScheme, ProviderDescriptor, ProviderPlugin};
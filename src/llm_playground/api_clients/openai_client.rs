@@ -1,12 +1,15 @@
 // OpenAI-compatible API client for WASM
 use crate::llm_playground::api_clients::{
-    FunctionCallRequest, FunctionResponse, LLMClient,
-    LLMResponse, MessageConverter, MessageSender, ModelProvider, NamedClient, StreamCallback,
-    StreamingSender, UnifiedMessage, UnifiedMessageRole,
+    openai_responses, provider_plugin::ProviderPlugin, FunctionCallRequest, LLMClient,
+    LLMResponse, MessageSender, ModelProvider, NamedClient, ProviderRequest,
+    RequestBuilder, ResponseTiming, StreamCallback, StreamingSender, TokenUsage, UnifiedMessage,
+    UnifiedMessageRole,
 };
-use crate::llm_playground::{ApiConfig, Message, MessageRole};
-use gloo_console::log;
-use gloo_net::http::Request;
+use crate::llm_playground::attachments;
+use crate::llm_playground::audio_output::MessageAudio;
+use crate::llm_playground::image_generation;
+use crate::llm_playground::{ApiConfig, SharedSettings};
+use crate::llm_playground::{cors_proxy, logging, sampling_controls};
 use js_sys::Promise;
 use serde::{Deserialize, Serialize};
 use std::future::Future;
@@ -24,19 +27,66 @@ struct OpenAIRequest {
     tools: Option<Vec<serde_json::Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logit_bias: Option<serde_json::Value>,
+    // Completions to return, when several candidates are wanted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    n: Option<u32>,
+    // OpenRouter-style switch for returning the model's thinking
+    #[serde(skip_serializing_if = "Option::is_none")]
+    include_reasoning: Option<bool>,
+    // ["text", "audio"] and the voice, for models that can speak the reply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modalities: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    audio: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct OpenAIMessage {
     role: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    content: Option<String>,
+    content: Option<OpenAIContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_calls: Option<Vec<ToolCall>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_call_id: Option<String>,
+    // DeepSeek-R1 style thinking; never sent back, the API rejects it in input
+    #[serde(default, skip_serializing)]
+    reasoning_content: Option<String>,
+    // Spoken reply; sent back as its transcript in the content instead
+    #[serde(default, skip_serializing)]
+    audio: Option<OpenAIAudio>,
+}
+
+// Plain text, or text and file parts when PDFs are attached
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+enum OpenAIContent {
+    Text(String),
+    Parts(Vec<serde_json::Value>),
+}
+
+impl OpenAIContent {
+    fn text(&self) -> String {
+        match self {
+            OpenAIContent::Text(text) => text.clone(),
+            OpenAIContent::Parts(parts) => parts.iter().filter_map(|part| part["text"].as_str()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct OpenAIAudio {
+    data: String,
+    #[serde(default)]
+    transcript: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -56,18 +106,132 @@ struct FunctionCall {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct OpenAIResponse {
     choices: Vec<Choice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    usage: Option<TokenUsage>,
+    // Perplexity's sources, numbered [1], [2], ... in the answer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    citations: Vec<String>,
+    // Newer Perplexity responses list the sources here instead
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    search_results: Vec<SearchResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SearchResult {
+    url: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct Choice {
     message: OpenAIMessage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    finish_reason: Option<String>,
+}
+
+/// Add the configured stop sequences and logit bias to a request body
+fn apply_sampling_controls(request_body: &mut serde_json::Value, settings: &SharedSettings) {
+    let stop = settings.stop_sequences();
+    if !stop.is_empty() {
+        request_body["stop"] = serde_json::json!(stop);
+    }
+    if let Some(logit_bias) = sampling_controls::logit_bias_value(&settings.logit_bias) {
+        request_body["logit_bias"] = logit_bias;
+    }
+}
+
+/// Turn a chat completions response into the reply shown in the chat
+fn parse_chat_response(
+    openai_response: &OpenAIResponse,
+    settings: &SharedSettings,
+) -> Result<LLMResponse, String> {
+    if openai_response.choices.is_empty() {
+        return Err("No response from OpenAI API".to_string());
+    }
+
+    let choice = &openai_response.choices[0];
+    let message = &choice.message;
+
+    // A spoken reply's text comes as its transcript
+    let content = message.content.as_ref().map(OpenAIContent::text).or_else(|| {
+        message
+            .audio
+            .as_ref()
+            .map(|audio| audio.transcript.clone())
+            .filter(|transcript| !transcript.is_empty())
+    });
+
+    // Extract function calls
+    let mut function_calls = Vec::new();
+    if let Some(tool_calls) = &message.tool_calls {
+        for tool_call in tool_calls {
+            // Parse the arguments JSON string
+            let args = if tool_call.function.arguments.is_empty() {
+                serde_json::json!({})
+            } else {
+                // Unparseable arguments stay a raw string so they can be repaired
+                serde_json::from_str::<serde_json::Value>(&tool_call.function.arguments)
+                    .unwrap_or_else(|_| serde_json::Value::String(tool_call.function.arguments.clone()))
+            };
+
+            function_calls.push(FunctionCallRequest {
+                id: tool_call.id.clone(),
+                name: tool_call.function.name.clone(),
+                arguments: args,
+            });
+        }
+    }
+
+    Ok(LLMResponse {
+        content,
+        function_calls,
+        finish_reason: choice.finish_reason.clone(),
+        reasoning: settings.reasoning.keep(message.reasoning_content.clone()),
+        citations: if openai_response.citations.is_empty() {
+            openai_response
+                .search_results
+                .iter()
+                .map(|result| result.url.clone())
+                .collect()
+        } else {
+            openai_response.citations.clone()
+        },
+        alternatives: openai_response
+            .choices
+            .iter()
+            .skip(1)
+            .filter_map(|choice| choice.message.content.as_ref().map(OpenAIContent::text))
+            .collect(),
+        native_tool_calls: Vec::new(),
+        audio: message.audio.as_ref().map(|audio| MessageAudio {
+            data: audio.data.clone(),
+            format: settings.audio_output.format.clone(),
+            transcript: audio.transcript.clone(),
+        }),
+        images: Vec::new(),
+        usage: openai_response.usage.clone(),
+        timing: None,
+    })
 }
 
-pub struct OpenAIClient;
+pub struct OpenAIClient {
+    // Auth scheme and endpoint paths; plain OpenAI unless set by a plugin
+    plugin: ProviderPlugin,
+}
 
 impl OpenAIClient {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            plugin: ProviderPlugin::default(),
+        }
+    }
+
+    /// Client for a provider added from a JSON descriptor
+    pub fn with_plugin(plugin: ProviderPlugin) -> Self {
+        Self { plugin }
+    }
+
+    fn missing_api_key(&self, api_key: &str) -> bool {
+        self.plugin.needs_api_key() && api_key.trim().is_empty()
     }
 
     // Helper function to sleep for a specified duration
@@ -79,34 +243,169 @@ impl OpenAIClient {
         let _ = JsFuture::from(promise).await;
     }
 
-    fn convert_unified_messages_to_openai(&self, messages: &[UnifiedMessage], system_prompt: Option<&str>) -> Vec<OpenAIMessage> {
+    /// Generate an image, or edit the one the last user message sent back,
+    /// through the Images API; image models have no chat endpoint
+    async fn send_image_request(
+        &self,
+        messages: &[UnifiedMessage],
+        config: &ApiConfig,
+    ) -> Result<LLMResponse, String> {
+        let last_user = messages
+            .iter()
+            .rev()
+            .find(|message| message.role == UnifiedMessageRole::User)
+            .ok_or_else(|| "Describe the image to generate".to_string())?;
+        let prompt = last_user.content.clone().unwrap_or_default();
+        let edit = last_user.image_edit.as_ref();
+        let operation = if edit.is_some() { "edits" } else { "generations" };
+        let url = self
+            .plugin
+            .images_url(&config.openai.base_url, operation, &config.openai.api_key);
+        let builder = self.plugin.auth_headers(&config.openai.api_key).iter().fold(
+            cors_proxy::post(&url, config.shared_settings.cors_proxy()),
+            |builder, (name, value)| builder.header(name, value),
+        );
+
+        let request = match edit {
+            // Edits are uploaded as a form; the browser sets its content type
+            Some(edit) => {
+                let form_error = |e: wasm_bindgen::JsValue| format!("Failed to create request: {:?}", e);
+                let blob = |data: &str, mime_type: &str| -> Result<web_sys::Blob, String> {
+                    let bytes = js_sys::Uint8Array::from(image_generation::decode(data)?.as_slice());
+                    let options = web_sys::BlobPropertyBag::new();
+                    options.set_type(mime_type);
+                    web_sys::Blob::new_with_u8_array_sequence_and_options(&js_sys::Array::of1(&bytes), &options)
+                        .map_err(form_error)
+                };
+                let form = web_sys::FormData::new().map_err(form_error)?;
+                form.append_with_str("model", &config.openai.model).map_err(form_error)?;
+                form.append_with_str("prompt", &prompt).map_err(form_error)?;
+                form.append_with_blob_and_filename(
+                    "image",
+                    &blob(&edit.image.data, &edit.image.mime_type)?,
+                    &edit.image.file_name(),
+                )
+                .map_err(form_error)?;
+                if let Some(mask) = &edit.mask {
+                    form.append_with_blob_and_filename("mask", &blob(mask, "image/png")?, "mask.png")
+                        .map_err(form_error)?;
+                }
+                builder.body(form)
+            }
+            None => builder
+                .header("Content-Type", "application/json")
+                .json(&image_generation::openai_generation_body(&config.openai.model, &prompt)),
+        }
+        .map_err(|e| format!("Failed to create request: {}", e))?;
+
+        let request_started = js_sys::Date::now();
+        let response = request.send().await.map_err(|e| {
+            format!(
+                "Network error - Check your internet connection and API key: {}",
+                e
+            )
+        })?;
+        let headers_ms = js_sys::Date::now() - request_started;
+        if !response.ok() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!(
+                "OpenAI image request failed ({}).\n\nDetailed error: {}",
+                status, error_text
+            ));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+        let images = image_generation::parse_openai_images(
+            &body,
+            &prompt,
+            edit.map(|edit| edit.image.id.as_str()),
+            &format!("img_{}", js_sys::Date::now() as u64),
+        )?;
+        let usage = &body["usage"];
+        let count = |value: &serde_json::Value| value.as_u64().unwrap_or(0) as u32;
+        Ok(LLMResponse {
+            content: Some(image_generation::caption(&images)),
+            function_calls: Vec::new(),
+            finish_reason: Some("stop".to_string()),
+            reasoning: None,
+            citations: Vec::new(),
+            alternatives: Vec::new(),
+            native_tool_calls: Vec::new(),
+            audio: None,
+            images,
+            usage: usage.is_object().then(|| TokenUsage {
+                prompt_tokens: count(&usage["input_tokens"]),
+                completion_tokens: count(&usage["output_tokens"]),
+                total_tokens: count(&usage["total_tokens"]),
+                ..Default::default()
+            }),
+            timing: Some(ResponseTiming {
+                headers_ms,
+                latency_ms: js_sys::Date::now() - request_started,
+            }),
+        })
+    }
+
+    /// A message's content; attached PDFs go in as file parts when
+    /// `native_pdfs`, else their text is appended
+    fn message_content(message: &UnifiedMessage, native_pdfs: bool) -> Option<OpenAIContent> {
+        if message.attachments.is_empty() {
+            return message.content.clone().map(OpenAIContent::Text);
+        }
+        if !native_pdfs {
+            return attachments::content_with_text(message.content.as_deref(), &message.attachments)
+                .map(OpenAIContent::Text);
+        }
+        let text = message
+            .content
+            .iter()
+            .filter(|content| !content.is_empty())
+            .map(|content| serde_json::json!({ "type": "text", "text": content }));
+        let files = message.attachments.iter().map(|attachment| {
+            serde_json::json!({
+                "type": "file",
+                "file": { "filename": attachment.name, "file_data": attachment.data_url() }
+            })
+        });
+        Some(OpenAIContent::Parts(text.chain(files).collect()))
+    }
+
+    fn convert_unified_messages_to_openai(
+        &self,
+        messages: &[UnifiedMessage],
+        system_prompt: Option<&str>,
+        native_pdfs: bool,
+    ) -> Vec<OpenAIMessage> {
         let mut openai_messages = Vec::new();
 
         // Add system message if provided
         if let Some(prompt) = system_prompt {
             openai_messages.push(OpenAIMessage {
                 role: "system".to_string(),
-                content: Some(prompt.to_string()),
+                content: Some(OpenAIContent::Text(prompt.to_string())),
                 name: None,
                 tool_calls: None,
                 tool_call_id: None,
+                reasoning_content: None,
+                audio: None,
             });
         }
 
         // Add new unified messages
         for message in messages {
-            log!("unified_message_json");
-            let message_json = serde_json::json!(message.clone());
-            log!(message_json.to_string());
+            logging::debug!("Unified message: {}", serde_json::json!(message));
 
+            // System and developer messages keep their place in the conversation
             let role = match message.role {
-                UnifiedMessageRole::System => {
-                    if openai_messages.is_empty() || openai_messages[0].role != "system" {
-                        "system"
-                    } else {
-                        continue; // Skip if we already have a system message
-                    }
-                }
+                UnifiedMessageRole::System => "system",
+                UnifiedMessageRole::Developer => "developer",
                 UnifiedMessageRole::User => "user",
                 UnifiedMessageRole::Assistant => "assistant",
             };
@@ -115,10 +414,12 @@ impl OpenAIClient {
             if message.role == UnifiedMessageRole::Assistant && !message.function_calls.is_empty() {
                 let mut openai_msg = OpenAIMessage {
                     role: "assistant".to_string(),
-                    content: message.content.clone(),
+                    content: message.content.clone().map(OpenAIContent::Text),
                     name: None,
                     tool_calls: None,
                     tool_call_id: None,
+                    reasoning_content: None,
+                    audio: None,
                 };
 
                 // Convert function calls to OpenAI format
@@ -129,7 +430,10 @@ impl OpenAIClient {
                         call_type: "function".to_string(),
                         function: FunctionCall {
                             name: func_call.name.clone(),
-                            arguments: serde_json::to_string(&func_call.arguments).unwrap_or_default(),
+                            arguments: match &func_call.arguments {
+                                serde_json::Value::String(raw) => raw.clone(),
+                                arguments => serde_json::to_string(arguments).unwrap_or_default(),
+                            },
                         },
                     });
                 }
@@ -142,10 +446,12 @@ impl OpenAIClient {
                 // Regular message
                 let openai_msg = OpenAIMessage {
                     role: role.to_string(),
-                    content: message.content.clone(),
+                    content: Self::message_content(message, native_pdfs),
                     name: None,
                     tool_calls: None,
                     tool_call_id: None,
+                    reasoning_content: None,
+                    audio: None,
                 };
 
                 openai_messages.push(openai_msg);
@@ -155,10 +461,14 @@ impl OpenAIClient {
             for func_response in &message.function_responses {
                 let tool_msg = OpenAIMessage {
                     role: "tool".to_string(),
-                    content: Some(serde_json::to_string(&func_response.response).unwrap_or_default()),
+                    content: Some(OpenAIContent::Text(
+                        serde_json::to_string(&func_response.response).unwrap_or_default(),
+                    )),
                     name: Some(func_response.name.clone()),
                     tool_calls: None,
                     tool_call_id: Some(func_response.id.clone()),
+                    reasoning_content: None,
+                    audio: None,
                 };
                 openai_messages.push(tool_msg);
             }
@@ -190,19 +500,36 @@ impl OpenAIClient {
         )
     }
 
+    /// JSON schema response format for the active structured output
+    fn build_response_format(&self, config: &ApiConfig) -> Option<serde_json::Value> {
+        config.structured_outputs.first().map(|output| {
+            serde_json::json!({
+                "type": "json_schema",
+                "json_schema": {
+                    "name": output.name,
+                    "schema": output.schema
+                }
+            })
+        })
+    }
+
     async fn send_message_internal(
         &self,
         messages: &[UnifiedMessage],
         config: &ApiConfig,
         system_prompt: Option<&str>,
     ) -> Result<String, String> {
-        log!("OpenAI API call started");
+        logging::debug!("OpenAI API call started");
 
-        if config.openai.api_key.trim().is_empty() {
+        if self.missing_api_key(&config.openai.api_key) {
             return Err("Please configure your OpenAI API key in Settings".to_string());
         }
 
-        let openai_messages = self.convert_unified_messages_to_openai(messages, system_prompt);
+        let openai_messages = self.convert_unified_messages_to_openai(
+            messages,
+            system_prompt,
+            attachments::reads_pdfs_natively(false, &config.openai.model),
+        );
         let tools = self.build_tools(config);
 
         let mut request_body = serde_json::json!({
@@ -215,14 +542,23 @@ impl OpenAIClient {
         if let Some(tools_array) = tools {
             request_body["tools"] = serde_json::Value::Array(tools_array);
         }
-
-        let url = format!("{}/chat/completions", config.openai.base_url);
-
-        let response = Request::post(&url)
-            .header("Content-Type", "application/json")
-            .header(
-                "Authorization",
-                &format!("Bearer {}", config.openai.api_key),
+        if let Some(response_format) = self.build_response_format(config) {
+            request_body["response_format"] = response_format;
+        }
+        apply_sampling_controls(&mut request_body, &config.shared_settings);
+
+        let url = self
+            .plugin
+            .chat_url(&config.openai.base_url, &config.openai.api_key);
+
+        let response = self
+            .plugin
+            .auth_headers(&config.openai.api_key)
+            .iter()
+            .fold(
+                cors_proxy::post(&url, config.shared_settings.cors_proxy())
+                    .header("Content-Type", "application/json"),
+                |builder, (name, value)| builder.header(name, value),
             )
             .json(&request_body)
             .map_err(|e| format!("Failed to create request: {}", e))?
@@ -274,7 +610,8 @@ impl OpenAIClient {
         Ok(openai_response.choices[0]
             .message
             .content
-            .clone()
+            .as_ref()
+            .map(OpenAIContent::text)
             .unwrap_or_default())
     }
 }
@@ -290,51 +627,38 @@ impl MessageSender for OpenAIClient {
         let config_clone = config.clone();
         let system_prompt_clone = system_prompt.map(|s| s.to_string());
 
-        log!("unified_messages_json");
-        let messages_json = serde_json::json!(messages_clone.clone());
-        log!(messages_json.to_string());
+        logging::debug!("Unified messages: {}", serde_json::json!(messages_clone));
 
         Box::pin(async move {
             // Use the full response parsing instead of just the internal method
-            if config_clone.openai.api_key.trim().is_empty() {
+            if self.missing_api_key(&config_clone.openai.api_key) {
                 return Err("Please configure your OpenAI API key in Settings".to_string());
             }
+            if image_generation::is_image_model(false, &config_clone.openai.model) {
+                return self.send_image_request(&messages_clone, &config_clone).await;
+            }
 
-            let openai_messages = self.convert_unified_messages_to_openai(&messages_clone, system_prompt_clone.as_deref());
-
-            log!("openai_messages_json");
-            let openai_messages_json = serde_json::json!(openai_messages.clone());
-            log!(openai_messages_json.to_string());
-
-            let tools = self.build_tools(&config_clone);
-
-            let request_body = OpenAIRequest {
-                model: config_clone.openai.model,
-                messages: openai_messages,
-                temperature: config_clone.shared_settings.temperature,
-                max_tokens: config_clone.shared_settings.max_tokens,
-                tools: tools.clone(),
-                tool_choice: if tools.is_some() {
-                    Some("auto".to_string())
-                } else {
-                    None
-                },
-            };
+            let request = self.build_request(
+                &messages_clone,
+                &config_clone,
+                system_prompt_clone.as_deref(),
+            )?;
 
-            let url = format!("{}/chat/completions", config_clone.openai.base_url);
+            logging::debug!("OpenAI request: {}", request.body);
 
             // Add sleep/delay before sending the request (500ms)
-            log!("Sleeping for 500ms before sending OpenAI request...");
+            logging::debug!("Waiting 500ms before sending the OpenAI request");
             OpenAIClient::sleep(500).await;
-            log!("Sleep completed, sending request now");
 
-            let response = Request::post(&url)
-                .header("Content-Type", "application/json")
-                .header(
-                    "Authorization",
-                    &format!("Bearer {}", config_clone.openai.api_key),
+            let request_started = js_sys::Date::now();
+            let response = request
+                .headers
+                .iter()
+                .fold(
+                    cors_proxy::post(&request.url, config_clone.shared_settings.cors_proxy()),
+                    |builder, (name, value)| builder.header(name, value),
                 )
-                .json(&request_body)
+                .json(&request.body)
                 .map_err(|e| format!("Failed to create request: {}", e))?
                 .send()
                 .await
@@ -344,6 +668,7 @@ impl MessageSender for OpenAIClient {
                         e
                     )
                 })?;
+            let headers_ms = js_sys::Date::now() - request_started;
 
             if !response.ok() {
                 let status = response.status();
@@ -372,54 +697,33 @@ impl MessageSender for OpenAIClient {
                 ));
             }
 
+            if config_clone.shared_settings.native_tools.any() {
+                let body: serde_json::Value = response
+                    .json()
+                    .await
+                    .map_err(|e| format!("Failed to parse response: {}", e))?;
+                logging::debug!("OpenAI Responses API response: {}", body);
+                let mut parsed = openai_responses::parse_response(&body, &config_clone)?;
+                parsed.timing = Some(ResponseTiming {
+                    headers_ms,
+                    latency_ms: js_sys::Date::now() - request_started,
+                });
+                return Ok(parsed);
+            }
+
             let openai_response: OpenAIResponse = response
                 .json()
                 .await
                 .map_err(|e| format!("Failed to parse response: {}", e))?;
 
-            log!("response_json");
-            let response_json = serde_json::json!(openai_response.clone());
-            log!(response_json.to_string());
+            logging::debug!("OpenAI response: {}", serde_json::json!(openai_response));
 
-            if openai_response.choices.is_empty() {
-                return Err("No response from OpenAI API".to_string());
-            }
-
-            let choice = &openai_response.choices[0];
-            let message = &choice.message;
-
-            // Extract content
-            let content = message.content.clone();
-
-            // Extract function calls
-            let mut function_calls = Vec::new();
-            if let Some(tool_calls) = &message.tool_calls {
-                for tool_call in tool_calls {
-                    // Parse the arguments JSON string
-                    let args = if tool_call.function.arguments.is_empty() {
-                        serde_json::json!({})
-                    } else {
-                        match serde_json::from_str::<serde_json::Value>(
-                            &tool_call.function.arguments,
-                        ) {
-                            Ok(parsed) => parsed,
-                            Err(_) => serde_json::json!({}),
-                        }
-                    };
-
-                    function_calls.push(FunctionCallRequest {
-                        id: tool_call.id.clone(),
-                        name: tool_call.function.name.clone(),
-                        arguments: args,
-                    });
-                }
-            }
-
-            Ok(LLMResponse {
-                content,
-                function_calls,
-                finish_reason: Some("stop".to_string()),
-            })
+            let mut parsed = parse_chat_response(&openai_response, &config_clone.shared_settings)?;
+            parsed.timing = Some(ResponseTiming {
+                headers_ms,
+                latency_ms: js_sys::Date::now() - request_started,
+            });
+            Ok(parsed)
         })
     }
 }
@@ -432,16 +736,24 @@ impl StreamingSender for OpenAIClient {
         system_prompt: Option<&str>,
         callback: StreamCallback,
     ) -> Pin<Box<dyn Future<Output = Result<(), String>> + '_>> {
-        let openai_messages = self.convert_unified_messages_to_openai(messages, system_prompt);
+        let openai_messages = self.convert_unified_messages_to_openai(
+            messages,
+            system_prompt,
+            attachments::reads_pdfs_natively(false, &config.openai.model),
+        );
         let tools = self.build_tools(config);
+        let response_format = self.build_response_format(config);
         let api_key = config.openai.api_key.clone();
-        let base_url = config.openai.base_url.clone();
+        let url = self.plugin.chat_url(&config.openai.base_url, &api_key);
+        let auth_headers = self.plugin.auth_headers(&api_key);
+        let cors_proxy = config.shared_settings.cors_proxy.clone();
         let model = config.openai.model.clone();
         let temperature = config.shared_settings.temperature;
         let max_tokens = config.shared_settings.max_tokens;
+        let shared_settings = config.shared_settings.clone();
 
         Box::pin(async move {
-            if api_key.trim().is_empty() {
+            if self.missing_api_key(&api_key) {
                 return Err("Please configure your OpenAI API key in Settings".to_string());
             }
 
@@ -455,20 +767,26 @@ impl StreamingSender for OpenAIClient {
 
             if let Some(tools_array) = tools {
                 request_body["tools"] = serde_json::Value::Array(tools_array);
-                request_body["tool_choice"] = serde_json::Value::String("auto".to_string());
+                request_body["tool_choice"] =
+                    serde_json::Value::String(shared_settings.tool_choice.openai_value().to_string());
             }
-
-            let url = format!("{}/chat/completions", base_url);
+            if let Some(response_format) = response_format {
+                request_body["response_format"] = response_format;
+            }
+            apply_sampling_controls(&mut request_body, &shared_settings);
 
             // Add sleep/delay before sending the streaming request (500ms)
-            log!("Sleeping for 500ms before sending OpenAI streaming request...");
+            logging::debug!("Waiting 500ms before sending the OpenAI streaming request");
             OpenAIClient::sleep(500).await;
-            log!("Sleep completed, sending streaming request now");
 
             // For WASM, we'll simulate streaming like we did with Gemini
-            let response = Request::post(&url)
-                .header("Content-Type", "application/json")
-                .header("Authorization", &format!("Bearer {}", api_key))
+            let response = auth_headers
+                .iter()
+                .fold(
+                    cors_proxy::post(&url, cors_proxy.as_deref())
+                        .header("Content-Type", "application/json"),
+                    |builder, (name, value)| builder.header(name, value),
+                )
                 .json(&request_body)
                 .map_err(|e| format!("Failed to create request: {}", e))?
                 .send()
@@ -509,17 +827,22 @@ impl ModelProvider for OpenAIClient {
         config: &ApiConfig,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, String>> + '_>> {
         let api_key = config.openai.api_key.clone();
-        let base_url = config.openai.base_url.clone();
+        let url = self.plugin.models_url(&config.openai.base_url, &api_key);
+        let auth_headers = self.plugin.auth_headers(&api_key);
+        let cors_proxy = config.shared_settings.cors_proxy.clone();
 
         Box::pin(async move {
-            if api_key.trim().is_empty() {
+            if self.missing_api_key(&api_key) {
                 return Err("Please configure your OpenAI API key to fetch models".to_string());
             }
+            let url = url.ok_or_else(|| "This provider cannot list its models".to_string())?;
 
-            let url = format!("{}/models", base_url);
-
-            let response = Request::get(&url)
-                .header("Authorization", &format!("Bearer {}", api_key))
+            let response = auth_headers
+                .iter()
+                .fold(
+                    cors_proxy::get(&url, cors_proxy.as_deref()),
+                    |builder, (name, value)| builder.header(name, value),
+                )
                 .send()
                 .await
                 .map_err(|e| format!("Failed to fetch models: {}", e))?;
@@ -568,75 +891,58 @@ impl ModelProvider for OpenAIClient {
     }
 }
 
-impl MessageConverter for OpenAIClient {
-    fn convert_legacy_messages(&self, messages: &[Message]) -> Vec<UnifiedMessage> {
-        let mut unified_messages = Vec::new();
-        let mut function_call_id_counter = 0u32;
-
-        for message in messages {
-            let role = match message.role {
-                MessageRole::System => UnifiedMessageRole::System,
-                MessageRole::User => UnifiedMessageRole::User,
-                MessageRole::Assistant => UnifiedMessageRole::Assistant,
-                MessageRole::Function => UnifiedMessageRole::User, // Function responses become user messages
-            };
-
-            let mut function_calls = Vec::new();
-            let mut function_responses = Vec::new();
-
-            // Convert legacy function calls
-            if let Some(fc) = &message.function_call {
-                if let Ok(func_calls) = serde_json::from_value::<Vec<serde_json::Value>>(fc.clone()) {
-                    for func_call in func_calls {
-                        if let (Some(name), Some(args)) = (
-                            func_call.get("name").and_then(|v| v.as_str()),
-                            func_call.get("arguments"),
-                        ) {
-                            let id = func_call
-                                .get("id")
-                                .and_then(|v| v.as_str())
-                                .map(|s| s.to_string())
-                                .unwrap_or_else(|| {
-                                    function_call_id_counter += 1;
-                                    format!("call_{}", function_call_id_counter)
-                                });
-
-                            function_calls.push(FunctionCallRequest {
-                                id,
-                                name: name.to_string(),
-                                arguments: args.clone(),
-                            });
-                        }
-                    }
-                }
-            }
-
-            // Convert legacy function responses
-            if let Some(fr) = &message.function_response {
-                if let (Some(id), Some(name), Some(response)) = (
-                    fr.get("id").and_then(|v| v.as_str()),
-                    fr.get("name").and_then(|v| v.as_str()),
-                    fr.get("response"),
-                ) {
-                    function_responses.push(FunctionResponse {
-                        id: id.to_string(),
-                        name: name.to_string(),
-                        response: response.clone(),
-                    });
-                }
-            }
-
-            unified_messages.push(UnifiedMessage {
-                id: message.id.clone(),
-                role,
-                content: if message.content.is_empty() { None } else { Some(message.content.clone()) },
-                timestamp: message.timestamp,
-                function_calls,
-                function_responses,
+impl RequestBuilder for OpenAIClient {
+    fn build_request(
+        &self,
+        messages: &[UnifiedMessage],
+        config: &ApiConfig,
+        system_prompt: Option<&str>,
+    ) -> Result<ProviderRequest, String> {
+        let mut headers = vec![("Content-Type".to_string(), "application/json".to_string())];
+        headers.extend(self.plugin.auth_headers(&config.openai.api_key));
+
+        if config.shared_settings.native_tools.any() {
+            return Ok(ProviderRequest {
+                url: self
+                    .plugin
+                    .responses_url(&config.openai.base_url, &config.openai.api_key),
+                headers,
+                body: openai_responses::build_body(messages, config, system_prompt),
             });
         }
 
-        unified_messages
+        let tools = self.build_tools(config);
+        let (modalities, audio) = config.shared_settings.audio_output.request_params().unzip();
+        let request_body = OpenAIRequest {
+            model: config.openai.model.clone(),
+            messages: self.convert_unified_messages_to_openai(
+                messages,
+                system_prompt,
+                attachments::reads_pdfs_natively(false, &config.openai.model),
+            ),
+            temperature: config.shared_settings.temperature,
+            max_tokens: config.shared_settings.max_tokens,
+            tool_choice: tools
+                .is_some()
+                .then(|| config.shared_settings.tool_choice.openai_value().to_string()),
+            tools,
+            response_format: self.build_response_format(config),
+            stop: config.shared_settings.stop_sequences(),
+            logit_bias: sampling_controls::logit_bias_value(&config.shared_settings.logit_bias),
+            n: Some(config.shared_settings.candidate_count()).filter(|n| *n > 1),
+            include_reasoning: config.shared_settings.reasoning.include_reasoning(),
+            modalities,
+            audio,
+        };
+
+        Ok(ProviderRequest {
+            url: self
+                .plugin
+                .chat_url(&config.openai.base_url, &config.openai.api_key),
+            headers,
+            body: serde_json::to_value(&request_body)
+                .map_err(|e| format!("Failed to serialize request: {}", e))?,
+        })
     }
 }
 
@@ -649,7 +955,7 @@ impl LLMClient for OpenAIClient {}
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::llm_playground::{ApiConfig, FunctionTool, Message, MessageRole, OpenAIConfig};
+    use crate::llm_playground::{ApiConfig, FunctionTool, OpenAIConfig, ReasoningVisibility};
     use serde_json::json;
 
     // Helper to create a default config for tests
@@ -664,123 +970,79 @@ mod tests {
         }
     }
 
-    fn create_test_message(role: MessageRole, content: &str) -> Message {
-        Message {
-            id: "test_id".to_string(),
+    fn message(role: UnifiedMessageRole, content: &str) -> UnifiedMessage {
+        UnifiedMessage {
+            id: String::new(),
             role,
-            content: content.to_string(),
+            content: Some(content.to_string()),
             timestamp: 0.0,
-            function_call: None,
-            function_response: None,
+            function_calls: Vec::new(),
+            function_responses: Vec::new(),
+            attachments: Vec::new(),
+            image_edit: None,
         }
     }
 
-    #[test]
-    fn test_new_openai_client() {
-        let client = OpenAIClient::new();
-        assert!(client.conversation_history.is_empty());
-        assert!(client.system_prompt.is_none());
-    }
-
-    #[test]
-    fn test_set_system_prompt() {
-        let mut client = OpenAIClient::new();
-        let prompt = "You are a helpful assistant.";
-        client.set_system_prompt(prompt);
-        assert_eq!(client.system_prompt, Some(prompt.to_string()));
-    }
-
-    #[test]
-    fn test_add_user_message() {
-        let mut client = OpenAIClient::new();
-        client.add_user_message("Hello");
-        assert_eq!(client.conversation_history.len(), 1);
-        assert_eq!(client.conversation_history[0].role, "user");
-        assert_eq!(client.conversation_history[0].content, "Hello");
-    }
-
-    #[test]
-    fn test_add_assistant_message() {
-        let mut client = OpenAIClient::new();
-        client.add_assistant_message("Hi there!", None);
-        assert_eq!(client.conversation_history.len(), 1);
-        assert_eq!(client.conversation_history[0].role, "assistant");
-        assert_eq!(client.conversation_history[0].content, "Hi there!");
-    }
-
-    #[test]
-    fn test_clear_conversation() {
-        let mut client = OpenAIClient::new();
-        client.add_user_message("Hello");
-        client.clear_conversation();
-        assert!(client.conversation_history.is_empty());
+    fn text(message: &OpenAIMessage) -> Option<String> {
+        message.content.as_ref().map(OpenAIContent::text)
     }
 
     #[test]
     fn test_convert_messages_to_openai_simple() {
         let client = OpenAIClient::new();
-        let messages = vec![create_test_message(MessageRole::User, "Hello")];
-        let openai_messages = client.convert_messages_to_openai(&messages);
+        let messages = [message(UnifiedMessageRole::User, "Hello")];
+        let openai_messages = client.convert_unified_messages_to_openai(&messages, None, false);
 
         assert_eq!(openai_messages.len(), 1);
         assert_eq!(openai_messages[0].role, "user");
-        assert_eq!(openai_messages[0].content, Some("Hello".to_string()));
+        assert_eq!(text(&openai_messages[0]), Some("Hello".to_string()));
     }
 
     #[test]
-    fn test_convert_messages_with_system_prompt_from_client() {
-        let mut client = OpenAIClient::new();
-        client.set_system_prompt("Be concise.");
-        let messages = vec![create_test_message(MessageRole::User, "Hello")];
-        let openai_messages = client.convert_messages_to_openai(&messages);
+    fn test_convert_messages_with_system_prompt() {
+        let client = OpenAIClient::new();
+        let messages = [message(UnifiedMessageRole::User, "Hello")];
+        let openai_messages = client.convert_unified_messages_to_openai(&messages, Some("Be concise."), false);
 
         assert_eq!(openai_messages.len(), 2);
         assert_eq!(openai_messages[0].role, "system");
-        assert_eq!(openai_messages[0].content, Some("Be concise.".to_string()));
+        assert_eq!(text(&openai_messages[0]), Some("Be concise.".to_string()));
         assert_eq!(openai_messages[1].role, "user");
     }
 
     #[test]
     fn test_convert_messages_with_system_prompt_from_message() {
         let client = OpenAIClient::new();
-        let messages = vec![
-            create_test_message(MessageRole::System, "Be verbose."),
-            create_test_message(MessageRole::User, "Hello"),
+        let messages = [
+            message(UnifiedMessageRole::System, "Be verbose."),
+            message(UnifiedMessageRole::User, "Hello"),
         ];
-        let openai_messages = client.convert_messages_to_openai(&messages);
+        let openai_messages = client.convert_unified_messages_to_openai(&messages, None, false);
 
         assert_eq!(openai_messages.len(), 2);
         assert_eq!(openai_messages[0].role, "system");
-        assert_eq!(openai_messages[0].content, Some("Be verbose.".to_string()));
+        assert_eq!(text(&openai_messages[0]), Some("Be verbose.".to_string()));
         assert_eq!(openai_messages[1].role, "user");
     }
 
     #[test]
     fn test_convert_messages_with_history() {
-        let mut client = OpenAIClient::new();
-        client.add_user_message("First message");
-        client.add_assistant_message("First response", None);
-
-        let messages = vec![create_test_message(MessageRole::User, "Second message")];
-
-        let openai_messages = client.convert_messages_to_openai(&messages);
+        // Earlier turns come from the session, not from the client
+        let client = OpenAIClient::new();
+        let messages = [
+            message(UnifiedMessageRole::User, "First message"),
+            message(UnifiedMessageRole::Assistant, "First response"),
+            message(UnifiedMessageRole::User, "Second message"),
+        ];
+        let openai_messages = client.convert_unified_messages_to_openai(&messages, None, false);
 
         assert_eq!(openai_messages.len(), 3);
         assert_eq!(openai_messages[0].role, "user");
-        assert_eq!(
-            openai_messages[0].content,
-            Some("First message".to_string())
-        );
+        assert_eq!(text(&openai_messages[0]), Some("First message".to_string()));
         assert_eq!(openai_messages[1].role, "assistant");
-        assert_eq!(
-            openai_messages[1].content,
-            Some("First response".to_string())
-        );
+        assert_eq!(text(&openai_messages[1]), Some("First response".to_string()));
         assert_eq!(openai_messages[2].role, "user");
-        assert_eq!(
-            openai_messages[2].content,
-            Some("Second message".to_string())
-        );
+        assert_eq!(text(&openai_messages[2]), Some("Second message".to_string()));
     }
 
     #[test]
@@ -812,6 +1074,8 @@ mod tests {
             mock_response: "".to_string(),
             enabled: true,
             category: "Weather".to_string(),
+            is_builtin: false,
+            requires: None,
         });
 
         let tools = client.build_tools(&config);
@@ -822,4 +1086,121 @@ mod tests {
         assert_eq!(tool_json["type"], "function");
         assert_eq!(tool_json["function"]["name"], "get_weather");
     }
+
+    fn chat_response(body: serde_json::Value) -> LLMResponse {
+        let response: OpenAIResponse = serde_json::from_value(body).unwrap();
+        parse_chat_response(&response, &ApiConfig::default().shared_settings).unwrap()
+    }
+
+    #[test]
+    fn test_parse_chat_response_keeps_usage() {
+        let response = chat_response(json!({
+            "choices": [{ "message": { "role": "assistant", "content": "Hi" } }],
+            "usage": { "prompt_tokens": 12, "completion_tokens": 3, "total_tokens": 15 }
+        }));
+        assert_eq!(response.content.as_deref(), Some("Hi"));
+        let usage = response.usage.unwrap();
+        assert_eq!((usage.prompt_tokens, usage.completion_tokens, usage.total_tokens), (12, 3, 15));
+
+        let response = chat_response(json!({
+            "choices": [{ "message": { "role": "assistant", "content": "Hi" } }]
+        }));
+        assert!(response.usage.is_none());
+
+        let empty: OpenAIResponse = serde_json::from_value(json!({ "choices": [] })).unwrap();
+        assert!(parse_chat_response(&empty, &ApiConfig::default().shared_settings).is_err());
+    }
+
+    #[test]
+    fn test_parse_chat_response_keeps_finish_reason() {
+        let response = chat_response(json!({
+            "choices": [{ "message": { "role": "assistant", "content": "Once upon" }, "finish_reason": "length" }]
+        }));
+        assert_eq!(response.finish_reason.as_deref(), Some("length"));
+    }
+
+    #[test]
+    fn test_system_and_developer_messages_keep_their_place() {
+        let client = OpenAIClient::new();
+        let messages = [
+            message(UnifiedMessageRole::User, "Hello"),
+            message(UnifiedMessageRole::Developer, "Answer in French."),
+            message(UnifiedMessageRole::User, "How are you?"),
+            message(UnifiedMessageRole::System, "Be brief."),
+        ];
+        let openai_messages = client.convert_unified_messages_to_openai(&messages, Some("Be kind."), false);
+
+        let roles: Vec<_> = openai_messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["system", "user", "developer", "user", "system"]);
+        assert_eq!(text(&openai_messages[2]), Some("Answer in French.".to_string()));
+        assert_eq!(text(&openai_messages[4]), Some("Be brief.".to_string()));
+    }
+
+    #[test]
+    fn test_parse_chat_response_keeps_groq_timings() {
+        let response = chat_response(json!({
+            "choices": [{ "message": { "role": "assistant", "content": "Hi" } }],
+            "usage": {
+                "prompt_tokens": 40,
+                "completion_tokens": 250,
+                "total_tokens": 290,
+                "queue_time": 0.02,
+                "completion_time": 0.5
+            }
+        }));
+        let usage = response.usage.unwrap();
+        assert_eq!(usage.queue_time, Some(0.02));
+        assert_eq!(usage.completion_time, Some(0.5));
+    }
+
+    #[test]
+    fn test_reasoning_content_is_shown_but_never_sent_back() {
+        let body = |reasoning: &str| {
+            json!({
+                "choices": [{
+                    "message": { "role": "assistant", "content": "42", "reasoning_content": reasoning }
+                }]
+            })
+        };
+        let response = chat_response(body("Six times seven."));
+        assert_eq!(response.reasoning.as_deref(), Some("Six times seven."));
+        assert_eq!(response.content.as_deref(), Some("42"));
+        assert!(chat_response(body("  ")).reasoning.is_none());
+
+        let parsed: OpenAIResponse = serde_json::from_value(body("Six times seven.")).unwrap();
+        let resent = serde_json::to_value(&parsed.choices[0].message).unwrap();
+        assert!(resent.get("reasoning_content").is_none());
+    }
+
+    #[test]
+    fn test_perplexity_citations_come_from_either_field() {
+        let choices = json!([{ "message": { "role": "assistant", "content": "See [1]." } }]);
+        let response = chat_response(json!({
+            "choices": choices,
+            "citations": ["https://a.example/1", "https://b.example/2"]
+        }));
+        assert_eq!(response.citations, ["https://a.example/1", "https://b.example/2"]);
+
+        let response = chat_response(json!({
+            "choices": choices,
+            "search_results": [{ "title": "A", "url": "https://a.example/1" }]
+        }));
+        assert_eq!(response.citations, ["https://a.example/1"]);
+        assert!(chat_response(json!({ "choices": choices })).citations.is_empty());
+    }
+
+    #[test]
+    fn test_hidden_reasoning_is_dropped_from_the_reply() {
+        let response: OpenAIResponse = serde_json::from_value(json!({
+            "choices": [{
+                "message": { "role": "assistant", "content": "42", "reasoning_content": "Six times seven." }
+            }]
+        }))
+        .unwrap();
+        let mut settings = ApiConfig::default().shared_settings;
+        settings.reasoning = ReasoningVisibility::Hidden;
+        let reply = parse_chat_response(&response, &settings).unwrap();
+        assert!(reply.reasoning.is_none());
+        assert_eq!(reply.content.as_deref(), Some("42"));
+    }
 }
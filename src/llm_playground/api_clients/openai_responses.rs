@@ -0,0 +1,243 @@
+// OpenAI Responses API
+// Requests that use provider-native tools go to /responses instead of
+// /chat/completions. Its input is a list of items rather than messages:
+// function calls and their results are items of their own, and the output
+// lists the native tool calls next to the reply.
+use crate::llm_playground::api_clients::{
+    FunctionCallRequest, LLMResponse, TokenUsage, UnifiedMessage, UnifiedMessageRole,
+};
+use crate::llm_playground::attachments;
+use crate::llm_playground::native_tools::NativeToolCall;
+use crate::llm_playground::ApiConfig;
+use serde_json::{json, Value};
+
+/// Attached PDFs go in as files when `native_pdfs`, else as their text
+fn input_items(messages: &[UnifiedMessage], native_pdfs: bool) -> Vec<Value> {
+    let mut items = Vec::new();
+    for message in messages {
+        let role = match message.role {
+            UnifiedMessageRole::System => "system",
+            UnifiedMessageRole::Developer => "developer",
+            UnifiedMessageRole::User => "user",
+            UnifiedMessageRole::Assistant => "assistant",
+        };
+        if native_pdfs && !message.attachments.is_empty() {
+            let text = message
+                .content
+                .iter()
+                .filter(|content| !content.is_empty())
+                .map(|content| json!({ "type": "input_text", "text": content }));
+            let files = message.attachments.iter().map(|attachment| {
+                json!({ "type": "input_file", "filename": attachment.name, "file_data": attachment.data_url() })
+            });
+            items.push(json!({ "role": role, "content": text.chain(files).collect::<Vec<_>>() }));
+        } else if let Some(content) =
+            attachments::content_with_text(message.content.as_deref(), &message.attachments)
+        {
+            items.push(json!({ "role": role, "content": content }));
+        }
+        for call in &message.function_calls {
+            items.push(json!({
+                "type": "function_call",
+                "call_id": call.id,
+                "name": call.name,
+                "arguments": match &call.arguments {
+                    Value::String(raw) => raw.clone(),
+                    arguments => arguments.to_string(),
+                },
+            }));
+        }
+        for response in &message.function_responses {
+            items.push(json!({
+                "type": "function_call_output",
+                "call_id": response.id,
+                "output": response.response.to_string(),
+            }));
+        }
+    }
+    items
+}
+
+/// Body of a Responses API request with the native and function tools
+pub fn build_body(messages: &[UnifiedMessage], config: &ApiConfig, system_prompt: Option<&str>) -> Value {
+    let settings = &config.shared_settings;
+    let mut tools = settings.native_tools.request_tools();
+    tools.extend(config.get_enabled_function_tools().iter().map(|tool| {
+        json!({
+            "type": "function",
+            "name": tool.name,
+            "description": tool.description,
+            "parameters": tool.parameters,
+        })
+    }));
+
+    let mut body = json!({
+        "model": config.openai.model,
+        "input": input_items(messages, attachments::reads_pdfs_natively(false, &config.openai.model)),
+        "temperature": settings.temperature,
+        "max_output_tokens": settings.max_tokens,
+        "tools": tools,
+        "tool_choice": settings.tool_choice.openai_value(),
+    });
+    if let Some(prompt) = system_prompt {
+        body["instructions"] = json!(prompt);
+    }
+    if settings.native_tools.code_interpreter {
+        // Logs and images are left out of the output unless asked for
+        body["include"] = json!(["code_interpreter_call.outputs"]);
+    }
+    if let Some(output) = config.structured_outputs.first() {
+        body["text"] = json!({
+            "format": { "type": "json_schema", "name": output.name, "schema": output.schema }
+        });
+    }
+    body
+}
+
+/// Read a Responses API response; timing is left for the caller to fill in
+pub fn parse_response(response: &Value, config: &ApiConfig) -> Result<LLMResponse, String> {
+    if let Some(message) = response["error"]["message"].as_str() {
+        return Err(format!("OpenAI API error: {}", message));
+    }
+    let output = response["output"]
+        .as_array()
+        .ok_or_else(|| "No response from OpenAI API".to_string())?;
+
+    let mut content = String::new();
+    let mut citations: Vec<String> = Vec::new();
+    let mut reasoning = Vec::new();
+    let mut function_calls = Vec::new();
+    let mut native_tool_calls = Vec::new();
+    for item in output {
+        match item["type"].as_str() {
+            Some("message") => {
+                for part in item["content"].as_array().into_iter().flatten() {
+                    content.push_str(part["text"].as_str().or(part["refusal"].as_str()).unwrap_or_default());
+                    for annotation in part["annotations"].as_array().into_iter().flatten() {
+                        if let Some(url) = annotation["url"].as_str() {
+                            if !citations.iter().any(|cited| cited == url) {
+                                citations.push(url.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+            Some("function_call") => {
+                let raw = item["arguments"].as_str().unwrap_or_default();
+                function_calls.push(FunctionCallRequest {
+                    id: item["call_id"].as_str().unwrap_or_default().to_string(),
+                    name: item["name"].as_str().unwrap_or_default().to_string(),
+                    // Unparseable arguments stay a raw string so they can be repaired
+                    arguments: if raw.is_empty() {
+                        json!({})
+                    } else {
+                        serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string()))
+                    },
+                });
+            }
+            Some("reasoning") => {
+                reasoning.extend(
+                    item["summary"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|summary| summary["text"].as_str()),
+                );
+            }
+            _ => native_tool_calls.extend(NativeToolCall::from_output_item(item)),
+        }
+    }
+
+    let finish_reason = match response["incomplete_details"]["reason"].as_str() {
+        Some("max_output_tokens") => "length",
+        Some(reason) => reason,
+        None if !function_calls.is_empty() => "tool_calls",
+        None => "stop",
+    };
+    let usage = &response["usage"];
+    let count = |value: &Value| value.as_u64().unwrap_or(0) as u32;
+    Ok(LLMResponse {
+        content: Some(content).filter(|content| !content.is_empty()),
+        function_calls,
+        finish_reason: Some(finish_reason.to_string()),
+        reasoning: config.shared_settings.reasoning.keep(Some(reasoning.join("\n\n"))),
+        citations,
+        alternatives: Vec::new(),
+        native_tool_calls,
+        audio: None,
+        images: Vec::new(),
+        usage: usage.is_object().then(|| TokenUsage {
+            prompt_tokens: count(&usage["input_tokens"]),
+            completion_tokens: count(&usage["output_tokens"]),
+            total_tokens: count(&usage["total_tokens"]),
+            cached_tokens: count(&usage["input_tokens_details"]["cached_tokens"]),
+            ..Default::default()
+        }),
+        timing: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::api_clients::FunctionResponse;
+
+    #[test]
+    fn function_calls_and_results_become_items() {
+        let messages = vec![
+            UnifiedMessage {
+                id: "a".to_string(),
+                role: UnifiedMessageRole::Assistant,
+                content: None,
+                timestamp: 0.0,
+                function_calls: vec![FunctionCallRequest {
+                    id: "call_1".to_string(),
+                    name: "get_weather".to_string(),
+                    arguments: json!({ "city": "Oslo" }),
+                }],
+                function_responses: Vec::new(),
+                attachments: Vec::new(),
+                image_edit: None,
+            },
+            UnifiedMessage {
+                id: "f".to_string(),
+                role: UnifiedMessageRole::User,
+                content: None,
+                timestamp: 0.0,
+                function_calls: Vec::new(),
+                function_responses: vec![FunctionResponse {
+                    id: "call_1".to_string(),
+                    name: "get_weather".to_string(),
+                    response: json!({ "temp": 4 }),
+                }],
+                attachments: Vec::new(),
+                image_edit: None,
+            },
+        ];
+        let items = input_items(&messages, true);
+        assert_eq!(items[0]["type"], "function_call");
+        assert_eq!(items[0]["arguments"], "{\"city\":\"Oslo\"}");
+        assert_eq!(items[1], json!({ "type": "function_call_output", "call_id": "call_1", "output": "{\"temp\":4}" }));
+    }
+
+    #[test]
+    fn output_items_are_read() {
+        let response = json!({
+            "output": [
+                { "type": "web_search_call", "status": "completed", "action": { "type": "search", "query": "oslo weather" } },
+                { "type": "message", "content": [{
+                    "type": "output_text",
+                    "text": "It is 4°C.",
+                    "annotations": [{ "type": "url_citation", "url": "https://yr.no" }]
+                }] }
+            ],
+            "usage": { "input_tokens": 20, "output_tokens": 5, "total_tokens": 25 }
+        });
+        let parsed = parse_response(&response, &ApiConfig::default()).unwrap();
+        assert_eq!(parsed.content.as_deref(), Some("It is 4°C."));
+        assert_eq!(parsed.citations, vec!["https://yr.no".to_string()]);
+        assert_eq!(parsed.native_tool_calls.len(), 1);
+        assert_eq!(parsed.finish_reason.as_deref(), Some("stop"));
+        assert_eq!(parsed.usage.map(|usage| usage.total_tokens), Some(25));
+    }
+}
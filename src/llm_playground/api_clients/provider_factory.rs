@@ -1,4 +1,7 @@
 // Provider factory pattern for extensible client creation (OCP compliance)
+use super::huggingface_client::HUGGINGFACE_TRANSFORMER;
+use super::mock_provider::MOCK_TRANSFORMER;
+use super::provider_plugin::PluginProviderFactory;
 use super::traits::LLMClient;
 use crate::llm_playground::provider_config::ProviderConfig;
 use std::collections::HashMap;
@@ -36,22 +39,42 @@ impl ProviderRegistry {
         self.factories.insert(provider_type, factory);
     }
 
+    /// Register a factory for every provider defined by a plugin descriptor
+    pub fn register_plugins(&mut self, providers: &[ProviderConfig]) {
+        for factory in providers.iter().filter_map(PluginProviderFactory::for_provider) {
+            self.register_factory(Arc::new(factory));
+        }
+    }
+
     /// Create a client for the given provider configuration
     pub fn create_client(&self, config: &ProviderConfig) -> Result<Box<dyn LLMClient>, String> {
-        // Try to find a factory that supports this provider
+        let provider_type = self.detect_provider_type(config);
+        // The factory registered under the type comes first, so a plugin
+        // named like one of the types another factory supports gets its own
+        if let Some(factory) = self.factories.get(&provider_type) {
+            return factory.create_client(config);
+        }
         for factory in self.factories.values() {
-            if factory.supports_provider(&self.detect_provider_type(config)) {
+            if factory.supports_provider(&provider_type) {
                 return factory.create_client(config);
             }
         }
+
         
         Err(format!("No factory found for provider configuration: {:?}", config.transformer.r#use))
     }
 
     /// Detect provider type from configuration
     fn detect_provider_type(&self, config: &ProviderConfig) -> String {
-        if config.transformer.r#use.contains(&"gemini".to_string()) {
+        // Plugin factories are registered under their provider's name
+        if config.plugin.is_some() {
+            config.name.clone()
+        } else if config.transformer.r#use.contains(&"gemini".to_string()) {
             "gemini".to_string()
+        } else if config.transformer.r#use.contains(&MOCK_TRANSFORMER.to_string()) {
+            MOCK_TRANSFORMER.to_string()
+        } else if config.transformer.r#use.contains(&HUGGINGFACE_TRANSFORMER.to_string()) {
+            HUGGINGFACE_TRANSFORMER.to_string()
         } else {
             "openai".to_string()
         }
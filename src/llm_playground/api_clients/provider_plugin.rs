@@ -0,0 +1,305 @@
+// Provider plugins defined at runtime
+// A JSON descriptor in the settings names a provider, its transformer
+// pipeline, how the API key is sent and which paths the chat and model list
+// requests go to. Plugins speak the OpenAI wire format, so new API flavors
+// such as Azure deployments or gateways with their own auth header need no
+// new client code:
+//
+//     {
+//       "name": "azure-gpt4o",
+//       "transformer": ["openai"],
+//       "api_base_url": "https://example.openai.azure.com/openai/deployments/gpt-4o",
+//       "models": ["gpt-4o"],
+//       "auth": { "scheme": "header", "name": "api-key" },
+//       "endpoints": { "chat": "/chat/completions?api-version=2024-06-01", "models": null }
+//     }
+use super::openai_client::OpenAIClient;
+use super::provider_factory::ProviderFactory;
+use super::traits::LLMClient;
+use crate::llm_playground::provider_config::{ProviderConfig, TransformerConfig};
+use serde::{Deserialize, Serialize};
+
+/// Transformer whose wire format plugins use
+pub const PLUGIN_WIRE_FORMAT: &str = "openai";
+
+/// How the API key is attached to requests
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "scheme", rename_all = "snake_case")]
+pub enum AuthScheme {
+    /// `Authorization: Bearer <key>`
+    #[default]
+    Bearer,
+    /// The key as the value of a custom header, e.g. `api-key`
+    Header { name: String },
+    /// The key as a query parameter, e.g. `?key=<key>`
+    Query { param: String },
+    /// No key at all, for local servers
+    None,
+}
+
+/// Paths appended to the provider's base URL
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PluginEndpoints {
+    pub chat: String,
+    /// None when the API cannot list its models
+    pub models: Option<String>,
+}
+
+impl Default for PluginEndpoints {
+    fn default() -> Self {
+        Self {
+            chat: "/chat/completions".to_string(),
+            models: Some("/models".to_string()),
+        }
+    }
+}
+
+/// Request shape of a provider; the default is plain OpenAI
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProviderPlugin {
+    pub auth: AuthScheme,
+    pub endpoints: PluginEndpoints,
+}
+
+impl ProviderPlugin {
+    pub fn needs_api_key(&self) -> bool {
+        self.auth != AuthScheme::None
+    }
+
+    pub fn chat_url(&self, base_url: &str, api_key: &str) -> String {
+        self.url(base_url, &self.endpoints.chat, api_key)
+    }
+
+    /// OpenAI's Responses API, used when native tools are on
+    pub fn responses_url(&self, base_url: &str, api_key: &str) -> String {
+        self.url(base_url, "/responses", api_key)
+    }
+
+    /// OpenAI's Images API, `operation` being "generations" or "edits"
+    pub fn images_url(&self, base_url: &str, operation: &str, api_key: &str) -> String {
+        self.url(base_url, &format!("/images/{}", operation), api_key)
+    }
+
+    pub fn models_url(&self, base_url: &str, api_key: &str) -> Option<String> {
+        self.endpoints
+            .models
+            .as_deref()
+            .map(|path| self.url(base_url, path, api_key))
+    }
+
+    fn url(&self, base_url: &str, path: &str, api_key: &str) -> String {
+        let mut url = format!(
+            "{}/{}",
+            base_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        );
+        if let AuthScheme::Query { param } = &self.auth {
+            let separator = if url.contains('?') { '&' } else { '?' };
+            url.push_str(&format!(
+                "{}{}={}",
+                separator,
+                param,
+                js_sys::encode_uri_component(api_key)
+            ));
+        }
+        url
+    }
+
+    /// Short description of the auth scheme and endpoints for the settings
+    pub fn summary(&self) -> String {
+        let auth = match &self.auth {
+            AuthScheme::Bearer => "bearer token".to_string(),
+            AuthScheme::Header { name } => format!("key in header {}", name),
+            AuthScheme::Query { param } => format!("key in query parameter {}", param),
+            AuthScheme::None => "no API key".to_string(),
+        };
+        let models = match &self.endpoints.models {
+            Some(path) => format!("models {}", path),
+            None => "no model list".to_string(),
+        };
+        format!("{} · chat {} · {}", auth, self.endpoints.chat, models)
+    }
+
+    /// Headers carrying the API key, if the scheme uses one
+    pub fn auth_headers(&self, api_key: &str) -> Vec<(String, String)> {
+        match &self.auth {
+            AuthScheme::Bearer => {
+                vec![("Authorization".to_string(), format!("Bearer {}", api_key))]
+            }
+            AuthScheme::Header { name } => vec![(name.clone(), api_key.to_string())],
+            AuthScheme::Query { .. } | AuthScheme::None => Vec::new(),
+        }
+    }
+}
+
+/// A provider definition pasted into the settings
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProviderDescriptor {
+    pub name: String,
+    pub transformer: Vec<String>,
+    pub api_base_url: String,
+    pub models: Vec<String>,
+    #[serde(default)]
+    pub auth: AuthScheme,
+    #[serde(default)]
+    pub endpoints: PluginEndpoints,
+}
+
+impl ProviderDescriptor {
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let descriptor: Self = serde_json::from_str(json)
+            .map_err(|e| format!("Invalid provider descriptor: {}", e))?;
+        descriptor.validate()?;
+        Ok(descriptor)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Provider name cannot be empty".to_string());
+        }
+        if !self.transformer.iter().any(|t| t == PLUGIN_WIRE_FORMAT) {
+            return Err(format!(
+                "The transformer pipeline must include \"{}\"; plugins use its wire format",
+                PLUGIN_WIRE_FORMAT
+            ));
+        }
+        if !self.api_base_url.starts_with("http://") && !self.api_base_url.starts_with("https://") {
+            return Err("API base URL must start with http:// or https://".to_string());
+        }
+        if self.models.is_empty() {
+            return Err("At least one model must be specified".to_string());
+        }
+        if self.endpoints.chat.trim().is_empty() {
+            return Err("The chat endpoint cannot be empty".to_string());
+        }
+        match &self.auth {
+            AuthScheme::Header { name } if name.trim().is_empty() => {
+                Err("The auth header name cannot be empty".to_string())
+            }
+            AuthScheme::Query { param } if param.trim().is_empty() => {
+                Err("The auth query parameter cannot be empty".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// A provider entry without an API key, ready to be added to the config
+    pub fn into_provider_config(self) -> ProviderConfig {
+        ProviderConfig {
+            name: self.name,
+            api_base_url: self.api_base_url,
+            api_key: String::new(),
+            models: self.models,
+            transformer: TransformerConfig {
+                r#use: self.transformer,
+            },
+            model_defaults: Default::default(),
+            mock_script: None,
+            plugin: Some(ProviderPlugin {
+                auth: self.auth,
+                endpoints: self.endpoints,
+            }),
+            gemini_auth: Default::default(),
+        }
+    }
+}
+
+/// Factory for one plugin provider, registered under the provider's name
+pub struct PluginProviderFactory {
+    name: String,
+    plugin: ProviderPlugin,
+}
+
+impl PluginProviderFactory {
+    /// None for providers that are not plugins
+    pub fn for_provider(provider: &ProviderConfig) -> Option<Self> {
+        provider.plugin.clone().map(|plugin| Self {
+            name: provider.name.clone(),
+            plugin,
+        })
+    }
+}
+
+impl ProviderFactory for PluginProviderFactory {
+    fn supports_provider(&self, provider_type: &str) -> bool {
+        provider_type == self.name
+    }
+
+    fn create_client(&self, _config: &ProviderConfig) -> Result<Box<dyn LLMClient>, String> {
+        Ok(Box::new(OpenAIClient::with_plugin(self.plugin.clone())))
+    }
+
+    fn provider_type(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn descriptor_becomes_a_provider_with_its_plugin() {
+        let descriptor = ProviderDescriptor::parse(
+            r#"{
+                "name": "azure",
+                "transformer": ["openai"],
+                "api_base_url": "https://example.azure.com/deployments/gpt-4o/",
+                "models": ["gpt-4o"],
+                "auth": { "scheme": "header", "name": "api-key" },
+                "endpoints": { "chat": "/chat/completions?api-version=1", "models": null }
+            }"#,
+        )
+        .unwrap();
+        let provider = descriptor.into_provider_config();
+        let plugin = provider.plugin.clone().unwrap();
+        assert_eq!(provider.transformer.r#use, ["openai"]);
+        assert_eq!(
+            plugin.chat_url(&provider.api_base_url, "k"),
+            "https://example.azure.com/deployments/gpt-4o/chat/completions?api-version=1"
+        );
+        assert_eq!(plugin.models_url(&provider.api_base_url, "k"), None);
+        assert_eq!(
+            plugin.auth_headers("k"),
+            [("api-key".to_string(), "k".to_string())]
+        );
+
+        let factory = PluginProviderFactory::for_provider(&provider).unwrap();
+        assert!(factory.supports_provider("azure"));
+        assert!(!factory.supports_provider("openai"));
+    }
+
+    #[test]
+    fn descriptors_are_validated() {
+        let base = r#""api_base_url": "https://api.example.com", "models": ["m"]"#;
+        assert!(ProviderDescriptor::parse("not json").is_err());
+        assert!(ProviderDescriptor::parse(&format!(
+            r#"{{ "name": "x", "transformer": ["gemini"], {} }}"#,
+            base
+        ))
+        .is_err());
+        assert!(ProviderDescriptor::parse(&format!(
+            r#"{{ "name": "x", "transformer": ["openai"], {}, "auth": {{ "scheme": "header", "name": "" }} }}"#,
+            base
+        ))
+        .is_err());
+
+        // Defaults are the plain OpenAI paths and bearer auth
+        let descriptor = ProviderDescriptor::parse(&format!(
+            r#"{{ "name": "x", "transformer": ["openai"], {} }}"#,
+            base
+        ))
+        .unwrap();
+        assert_eq!(descriptor.auth, AuthScheme::Bearer);
+        assert_eq!(descriptor.endpoints, PluginEndpoints::default());
+
+        let local = ProviderPlugin {
+            auth: AuthScheme::None,
+            ..Default::default()
+        };
+        assert!(!local.needs_api_key());
+        assert!(local.auth_headers("k").is_empty());
+    }
+}
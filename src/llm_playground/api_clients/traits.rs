@@ -1,5 +1,9 @@
 // Common traits for API clients
-use crate::llm_playground::{ApiConfig, Message};
+use crate::llm_playground::attachments::Attachment;
+use crate::llm_playground::audio_output::MessageAudio;
+use crate::llm_playground::image_generation::{GeneratedImage, ImageEdit};
+use crate::llm_playground::native_tools::NativeToolCall;
+use crate::llm_playground::ApiConfig;
 use serde::{Deserialize, Serialize};
 use std::future::Future;
 use std::pin::Pin;
@@ -25,6 +29,49 @@ pub struct LLMResponse {
     pub content: Option<String>,
     pub function_calls: Vec<FunctionCallRequest>,
     pub finish_reason: Option<String>,
+    // Thinking that reasoning models such as DeepSeek-R1 return next to the answer
+    pub reasoning: Option<String>,
+    // Source URLs of search-backed models such as Perplexity's
+    pub citations: Vec<String>,
+    // Content of the other choices when several completions were requested
+    pub alternatives: Vec<String>,
+    // Web searches and code runs the provider did itself, e.g. OpenAI's
+    // Responses API tools
+    pub native_tool_calls: Vec<NativeToolCall>,
+    // Speech returned by audio-capable models
+    pub audio: Option<MessageAudio>,
+    // Pictures returned by image models
+    pub images: Vec<GeneratedImage>,
+    pub usage: Option<TokenUsage>,
+    pub timing: Option<ResponseTiming>,
+}
+
+// Wall-clock timing of a single request, measured by the client
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResponseTiming {
+    // Until the response headers arrived; the body may still be generating
+    pub headers_ms: f64,
+    pub latency_ms: f64,
+}
+
+// Token counts reported by the provider for a single request
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    #[serde(default)]
+    pub prompt_tokens: u32,
+    #[serde(default)]
+    pub completion_tokens: u32,
+    #[serde(default)]
+    pub total_tokens: u32,
+    // Prompt tokens read from a context cache, billed at a discount
+    #[serde(default)]
+    pub cached_tokens: u32,
+    // Seconds spent waiting in the provider's queue and generating the
+    // completion, as Groq reports them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_time: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_time: Option<f64>,
 }
 
 // Unified message structure for internal LLM client communication
@@ -36,11 +83,18 @@ pub struct UnifiedMessage {
     pub timestamp: f64,
     pub function_calls: Vec<FunctionCallRequest>,
     pub function_responses: Vec<FunctionResponse>,
+    // PDFs sent with the message
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    // Earlier image to edit, for image models
+    #[serde(default)]
+    pub image_edit: Option<ImageEdit>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum UnifiedMessageRole {
     System,
+    Developer,
     User,
     Assistant,
 }
@@ -74,39 +128,35 @@ pub trait ModelProvider {
     ) -> Pin<Box<dyn Future<Output = Result<Vec<String>, String>> + '_>>;
 }
 
-// Represents a utility for converting message formats
-pub trait MessageConverter {
-    fn convert_legacy_messages(&self, messages: &[Message]) -> Vec<UnifiedMessage>;
-}
-
 // Represents a client that has a name
 pub trait NamedClient {
     fn client_name(&self) -> &str;
 }
 
-// Updated LLMClient trait composed of smaller, focused traits.
-// Any client that implements all the smaller traits automatically implements LLMClient.
-pub trait LLMClient:
-    MessageSender + StreamingSender + ModelProvider + MessageConverter + NamedClient
-{
+// The HTTP request a client sends for a non-streaming message
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderRequest {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: serde_json::Value,
 }
 
-// Trait for conversation management
-pub trait ConversationManager {
-    fn add_user_message(&mut self, message: &str);
-    fn add_assistant_message(&mut self, message: &str, function_call: Option<serde_json::Value>);
-    fn add_function_response(&mut self, function_response: &FunctionResponse);
-    fn clear_conversation(&mut self);
-    fn set_system_prompt(&mut self, prompt: &str);
-    fn get_conversation_history(&self) -> &[ConversationMessage];
+// Represents a client that can show the request it would send, e.g. for export
+pub trait RequestBuilder {
+    fn build_request(
+        &self,
+        messages: &[UnifiedMessage],
+        config: &ApiConfig,
+        system_prompt: Option<&str>,
+    ) -> Result<ProviderRequest, String>;
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
-pub struct ConversationMessage {
-    pub role: String,
-    pub content: String,
-    pub function_call: Option<serde_json::Value>,
-    pub function_response: Option<serde_json::Value>,
+// Updated LLMClient trait composed of smaller, focused traits.
+// Any client that implements all the smaller traits automatically implements LLMClient.
+// Clients hold no conversation state; requests are built from the messages given.
+pub trait LLMClient:
+    MessageSender + StreamingSender + ModelProvider + NamedClient + RequestBuilder
+{
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -0,0 +1,111 @@
+// Shared application state
+// The configuration, the clients and the appearance live in one reducer that
+// the playground exposes through a Yew context. Views read it with
+// `use_app_state()` instead of receiving it as props; an action that changes
+// nothing keeps the same state, so the views don't re-render for it.
+
+use crate::llm_playground::{
+    flexible_client::FlexibleLLMClient, mcp_client::McpClient, provider_config::FlexibleApiConfig,
+};
+use std::rc::Rc;
+use yew::prelude::*;
+
+#[derive(Clone, Default, PartialEq)]
+pub struct AppState {
+    pub config: FlexibleApiConfig,
+    pub llm_client: FlexibleLLMClient,
+    /// Connected MCP servers, None until one is enabled and initialized
+    pub mcp_client: Option<McpClient>,
+    pub dark_mode: bool,
+}
+
+#[derive(Clone, PartialEq)]
+pub enum AppAction {
+    SetConfig(FlexibleApiConfig),
+    /// Config loaded from storage; ignored once a session picked its provider
+    LoadConfig(FlexibleApiConfig),
+    /// Also replaces the MCP tools in the function tool list
+    SetMcpClient(Option<McpClient>),
+    SetDarkMode(bool),
+    ToggleDarkMode,
+}
+
+impl Reducible for AppState {
+    type Action = AppAction;
+
+    fn reduce(self: Rc<Self>, action: AppAction) -> Rc<Self> {
+        let mut next = (*self).clone();
+        match action {
+            AppAction::SetConfig(config) => next.config = config,
+            AppAction::LoadConfig(config) => {
+                if self.config.current_session_provider.is_none() {
+                    next.config = config;
+                }
+            }
+            AppAction::SetMcpClient(client) => {
+                if let Some(client) = &client {
+                    next.config.add_mcp_tools(client.get_function_tools());
+                }
+                next.mcp_client = client;
+            }
+            AppAction::SetDarkMode(dark_mode) => next.dark_mode = dark_mode,
+            AppAction::ToggleDarkMode => next.dark_mode = !self.dark_mode,
+        }
+        if next == *self {
+            self
+        } else {
+            Rc::new(next)
+        }
+    }
+}
+
+pub type AppStateHandle = UseReducerHandle<AppState>;
+
+/// The shared state from the playground's context provider
+#[hook]
+pub fn use_app_state() -> AppStateHandle {
+    use_context::<AppStateHandle>().expect("use_app_state needs an AppState context provider")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reduce(state: &Rc<AppState>, action: AppAction) -> Rc<AppState> {
+        state.clone().reduce(action)
+    }
+
+    #[test]
+    fn unchanged_state_is_kept() {
+        let state = Rc::new(AppState::default());
+
+        let same = reduce(&state, AppAction::SetConfig(state.config.clone()));
+        assert!(Rc::ptr_eq(&state, &same));
+        let same = reduce(&state, AppAction::SetDarkMode(false));
+        assert!(Rc::ptr_eq(&state, &same));
+
+        let dark = reduce(&state, AppAction::ToggleDarkMode);
+        assert!(!Rc::ptr_eq(&state, &dark));
+        assert!(dark.dark_mode);
+        assert!(!reduce(&dark, AppAction::ToggleDarkMode).dark_mode);
+    }
+
+    #[test]
+    fn loaded_config_does_not_override_a_session_provider() {
+        let mut stored = FlexibleApiConfig::default();
+        stored.system_prompt = "From storage".to_string();
+
+        let state = Rc::new(AppState::default());
+        let loaded = reduce(&state, AppAction::LoadConfig(stored.clone()));
+        assert_eq!(loaded.config.system_prompt, "From storage");
+
+        let mut config = FlexibleApiConfig::default();
+        config.current_session_provider = Some("openai,gpt-4o".to_string());
+        let state = Rc::new(AppState {
+            config,
+            ..AppState::default()
+        });
+        let kept = reduce(&state, AppAction::LoadConfig(stored));
+        assert!(Rc::ptr_eq(&state, &kept));
+    }
+}
@@ -0,0 +1,131 @@
+// Blind arena leaderboard
+// Keeps per-model win/loss/tie counts from blind comparison votes in its own
+// localStorage namespace, separate from sessions and configuration.
+
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const STORAGE_KEY_ARENA_LEADERBOARD: &str = "llm_playground_arena_leaderboard";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArenaRecord {
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+}
+
+impl ArenaRecord {
+    pub fn matches(&self) -> u32 {
+        self.wins + self.losses + self.ties
+    }
+
+    /// Win rate counting ties as half a win
+    pub fn win_rate(&self) -> f64 {
+        let matches = self.matches();
+        if matches == 0 {
+            0.0
+        } else {
+            (self.wins as f64 + self.ties as f64 * 0.5) / matches as f64
+        }
+    }
+}
+
+/// Win rates keyed by "provider,model"
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArenaLeaderboard {
+    pub records: HashMap<String, ArenaRecord>,
+}
+
+impl ArenaLeaderboard {
+    pub fn load() -> Self {
+        LocalStorage::get::<String>(STORAGE_KEY_ARENA_LEADERBOARD)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Ok(leaderboard_str) = serde_json::to_string(self) {
+            let _ = LocalStorage::set(STORAGE_KEY_ARENA_LEADERBOARD, leaderboard_str);
+        }
+    }
+
+    pub fn clear() {
+        LocalStorage::delete(STORAGE_KEY_ARENA_LEADERBOARD);
+    }
+
+    /// Record a vote: the winner gains a win and every other participant a loss.
+    /// With no winner the round is a tie for all participants.
+    pub fn record_vote(&mut self, participants: &[String], winner: Option<&str>) {
+        let mut seen = std::collections::HashSet::new();
+        for participant in participants {
+            if !seen.insert(participant.as_str()) {
+                continue;
+            }
+            let record = self.records.entry(participant.clone()).or_default();
+            match winner {
+                Some(winner) if winner == participant => record.wins += 1,
+                Some(_) => record.losses += 1,
+                None => record.ties += 1,
+            }
+        }
+    }
+
+    /// Records sorted by win rate, then by number of matches
+    pub fn ranked(&self) -> Vec<(&String, &ArenaRecord)> {
+        let mut ranked: Vec<_> = self.records.iter().collect();
+        ranked.sort_by(|a, b| {
+            b.1.win_rate()
+                .partial_cmp(&a.1.win_rate())
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(b.1.matches().cmp(&a.1.matches()))
+                .then(a.0.cmp(b.0))
+        });
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_vote_and_ranking() {
+        let mut leaderboard = ArenaLeaderboard::default();
+        let a = "openai,gpt-4o".to_string();
+        let b = "gemini,gemini-2.5-flash".to_string();
+        let participants = vec![a.clone(), b.clone()];
+
+        leaderboard.record_vote(&participants, Some(&a));
+        leaderboard.record_vote(&participants, Some(&a));
+        leaderboard.record_vote(&participants, None);
+
+        assert_eq!(
+            leaderboard.records[&a],
+            ArenaRecord {
+                wins: 2,
+                losses: 0,
+                ties: 1
+            }
+        );
+        assert_eq!(
+            leaderboard.records[&b],
+            ArenaRecord {
+                wins: 0,
+                losses: 2,
+                ties: 1
+            }
+        );
+        assert!((leaderboard.records[&a].win_rate() - 2.5 / 3.0).abs() < f64::EPSILON);
+        assert_eq!(leaderboard.ranked()[0].0, &a);
+    }
+
+    #[test]
+    fn test_duplicate_participants_counted_once() {
+        let mut leaderboard = ArenaLeaderboard::default();
+        let a = "openai,gpt-4o".to_string();
+        leaderboard.record_vote(&[a.clone(), a.clone()], None);
+        assert_eq!(leaderboard.records[&a].ties, 1);
+    }
+}
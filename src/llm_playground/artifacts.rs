@@ -0,0 +1,174 @@
+// Artifacts
+// Large code blocks and long documents in assistant replies can be opened in a
+// side panel instead of filling the conversation. The panel edits a copy; the
+// edits go back to the chat as a new draft.
+
+/// Code blocks with at least this many lines are collapsed into an artifact
+pub const MIN_CODE_LINES: usize = 20;
+/// Replies without code blocks and at least this long are treated as documents
+pub const MIN_DOCUMENT_CHARS: usize = 3000;
+/// Lines shown in the conversation for a collapsed artifact
+pub const PREVIEW_LINES: usize = 6;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Artifact {
+    /// Message id plus the block's position, stable across re-renders
+    pub id: String,
+    pub title: String,
+    /// Fence language, or "markdown" for documents
+    pub language: String,
+    pub content: String,
+}
+
+impl Artifact {
+    /// The `index`-th code block of a message
+    pub fn code_block(message_id: &str, index: usize, language: &str, content: &str) -> Self {
+        let label = if language.is_empty() {
+            "Code"
+        } else {
+            language
+        };
+        Self {
+            id: format!("{}_block_{}", message_id, index),
+            title: format!("{} #{}", label, index + 1),
+            language: language.to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    /// A whole reply opened as a document
+    pub fn document(message_id: &str, content: &str) -> Self {
+        let title = content
+            .lines()
+            .map(|line| line.trim().trim_start_matches('#').trim())
+            .find(|line| !line.is_empty())
+            .map(|line| line.chars().take(40).collect())
+            .unwrap_or_else(|| "Document".to_string());
+        Self {
+            id: format!("{}_document", message_id),
+            title,
+            language: "markdown".to_string(),
+            content: content.to_string(),
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        let extension = match self.language.to_lowercase().as_str() {
+            "rust" | "rs" => "rs",
+            "python" | "py" => "py",
+            "javascript" | "js" => "js",
+            "typescript" | "ts" => "ts",
+            "html" => "html",
+            "css" => "css",
+            "json" => "json",
+            "markdown" | "md" => "md",
+            "bash" | "sh" | "shell" | "zsh" => "sh",
+            "toml" => "toml",
+            "yaml" | "yml" => "yml",
+            "sql" => "sql",
+            "go" => "go",
+            "java" => "java",
+            "c" => "c",
+            "cpp" | "c++" => "cpp",
+            _ => "txt",
+        };
+        let stem: String = self
+            .title
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        let stem = if stem.is_empty() { "artifact" } else { &stem };
+        format!("{}.{}", stem, extension)
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self.language.to_lowercase().as_str() {
+            "markdown" | "md" => "text/markdown",
+            "html" => "text/html",
+            "json" => "application/json",
+            _ => "text/plain",
+        }
+    }
+}
+
+pub fn is_large_code_block(code: &str) -> bool {
+    code.lines().count() >= MIN_CODE_LINES
+}
+
+/// A reply long enough to open as a document; replies with code blocks offer
+/// their blocks instead
+pub fn document_artifact(message_id: &str, content: &str) -> Option<Artifact> {
+    if content.contains("```") || content.chars().count() < MIN_DOCUMENT_CHARS {
+        return None;
+    }
+    Some(Artifact::document(message_id, content))
+}
+
+/// The first lines of an artifact and how many were left out
+pub fn preview(content: &str) -> (String, usize) {
+    let total = content.lines().count();
+    let shown: Vec<&str> = content.lines().take(PREVIEW_LINES).collect();
+    (shown.join("\n"), total.saturating_sub(PREVIEW_LINES))
+}
+
+/// Chat draft carrying the user's edited version of an artifact
+pub fn edits_message(artifact: &Artifact, edited: &str) -> String {
+    if artifact.language == "markdown" && artifact.id.ends_with("_document") {
+        format!(
+            "Here is my edited version of \"{}\":\n\n{}\n",
+            artifact.title,
+            edited.trim_end()
+        )
+    } else {
+        format!(
+            "Here is my edited version of {}:\n\n```{}\n{}\n```\n",
+            artifact.title,
+            artifact.language,
+            edited.trim_end()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_files_after_title_and_language() {
+        let block = Artifact::code_block("msg_1", 0, "rust", "fn main() {}");
+        assert_eq!(block.id, "msg_1_block_0");
+        assert_eq!(block.file_name(), "rust-1.rs");
+        assert_eq!(
+            Artifact::code_block("m", 2, "", "x").file_name(),
+            "code-3.txt"
+        );
+
+        let doc = Artifact::document("msg_2", "\n# Release Notes: v2\n\nBody");
+        assert_eq!(doc.title, "Release Notes: v2");
+        assert_eq!(doc.file_name(), "release-notes-v2.md");
+        assert_eq!(doc.mime_type(), "text/markdown");
+    }
+
+    #[test]
+    fn detects_documents_and_builds_edit_drafts() {
+        let long = "word ".repeat(MIN_DOCUMENT_CHARS / 5);
+        assert!(document_artifact("m", &long).is_some());
+        assert!(document_artifact("m", "short").is_none());
+        assert!(document_artifact("m", &format!("{}```x```", long)).is_none());
+
+        let code = (0..MIN_CODE_LINES)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(is_large_code_block(&code));
+        assert_eq!(preview(&code).1, MIN_CODE_LINES - PREVIEW_LINES);
+
+        let block = Artifact::code_block("m", 0, "py", "print(1)");
+        assert_eq!(
+            edits_message(&block, "print(2)\n"),
+            "Here is my edited version of py #1:\n\n```py\nprint(2)\n```\n"
+        );
+    }
+}
@@ -0,0 +1,183 @@
+// PDF attachments
+// A PDF attached to a message goes to models that read documents natively as
+// the file itself: Gemini gets it as inlineData and OpenAI as a file input.
+// Other models get the text pulled out of it in the browser instead, which is
+// done when the file is attached so the fallback is ready whichever model the
+// message is sent to later.
+
+use crate::llm_playground::pdf_text;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+pub const PDF_MIME_TYPE: &str = "application/pdf";
+/// Largest PDF accepted; Gemini takes at most 20 MB of inline data per request
+pub const MAX_PDF_BYTES: usize = 20 * 1024 * 1024;
+/// Extracted text beyond this is cut off
+pub const MAX_TEXT_CHARS: usize = 200_000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub name: String,
+    pub mime_type: String,
+    /// Size of the file in bytes
+    pub size: usize,
+    /// The file, base64-encoded
+    pub data: String,
+    /// Text extracted for models that can't read the file
+    #[serde(default)]
+    pub text: String,
+}
+
+impl Attachment {
+    /// `data:` URL of the file, as OpenAI's file inputs take it
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type, self.data)
+    }
+
+    /// What a model without document support is sent in place of the file
+    pub fn as_text(&self) -> String {
+        if self.text.trim().is_empty() {
+            format!(
+                "[Attached {}: no text could be extracted; it may be scanned]",
+                self.name
+            )
+        } else {
+            format!("[Attached {}]\n{}\n[End of {}]", self.name, self.text, self.name)
+        }
+    }
+}
+
+/// Whether a model takes PDF files as they are; `gemini` is for models behind
+/// the Gemini API, the rest are called with the OpenAI format
+pub fn reads_pdfs_natively(gemini: bool, model: &str) -> bool {
+    // "openai/gpt-4o" on gateways such as OpenRouter
+    let model = model.rsplit('/').next().unwrap_or(model).to_lowercase();
+    if gemini {
+        model.starts_with("gemini-") && !model.starts_with("gemini-1.0") && model != "gemini-pro"
+    } else {
+        ["gpt-4o", "gpt-4.1", "gpt-5", "o1", "o3", "o4"]
+            .iter()
+            .any(|prefix| model.starts_with(prefix))
+            && !model.contains("audio")
+            && !model.contains("realtime")
+    }
+}
+
+/// Message content with the text of attachments the model can't read
+/// natively appended
+pub fn content_with_text(content: Option<&str>, attachments: &[Attachment]) -> Option<String> {
+    let texts = attachments.iter().map(Attachment::as_text);
+    let parts: Vec<String> = content
+        .filter(|content| !content.is_empty())
+        .map(str::to_string)
+        .into_iter()
+        .chain(texts)
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("\n\n"))
+}
+
+/// Human-readable file size, e.g. "2.4 MB"
+pub fn format_size(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
+}
+
+/// How far reading an attached file has got
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReadProgress {
+    Reading,
+    /// Streams done out of all of them
+    Extracting(usize, usize),
+}
+
+impl ReadProgress {
+    pub fn label(&self) -> String {
+        match self {
+            ReadProgress::Reading => "Reading…".to_string(),
+            ReadProgress::Extracting(_, 0) => "Extracting text…".to_string(),
+            ReadProgress::Extracting(done, total) => {
+                format!("Extracting text {}%", done * 100 / total)
+            }
+        }
+    }
+}
+
+/// Read a picked PDF and extract its text
+pub async fn read_pdf(
+    file: &web_sys::File,
+    on_progress: impl Fn(ReadProgress),
+) -> Result<Attachment, String> {
+    let name = file.name();
+    if file.type_() != PDF_MIME_TYPE && !name.to_lowercase().ends_with(".pdf") {
+        return Err(format!("{} is not a PDF", name));
+    }
+    let size = file.size() as usize;
+    if size > MAX_PDF_BYTES {
+        return Err(format!(
+            "{} is {}; PDFs can be at most {}",
+            name,
+            format_size(size),
+            format_size(MAX_PDF_BYTES)
+        ));
+    }
+
+    on_progress(ReadProgress::Reading);
+    let buffer = wasm_bindgen_futures::JsFuture::from(file.array_buffer())
+        .await
+        .map_err(|e| format!("Couldn't read {}: {:?}", name, e))?;
+    let bytes = js_sys::Uint8Array::new(&buffer).to_vec();
+    if !bytes.starts_with(b"%PDF") {
+        return Err(format!("{} is not a valid PDF", name));
+    }
+
+    let mut text = pdf_text::extract_text(&bytes, |done, total| {
+        on_progress(ReadProgress::Extracting(done, total))
+    })
+    .await;
+    if let Some((cut, _)) = text.char_indices().nth(MAX_TEXT_CHARS) {
+        text.truncate(cut);
+        text.push_str("\n[Text cut off]");
+    }
+
+    Ok(Attachment {
+        name,
+        mime_type: PDF_MIME_TYPE.to_string(),
+        size,
+        data: base64::engine::general_purpose::STANDARD.encode(&bytes),
+        text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn native_pdf_support_by_model() {
+        assert!(reads_pdfs_natively(true, "gemini-2.5-flash"));
+        assert!(!reads_pdfs_natively(true, "gemma-3-27b-it"));
+        assert!(reads_pdfs_natively(false, "gpt-4o-mini"));
+        assert!(reads_pdfs_natively(false, "openai/o3-mini"));
+        assert!(!reads_pdfs_natively(false, "gpt-4o-audio-preview"));
+        assert!(!reads_pdfs_natively(false, "deepseek-chat"));
+    }
+
+    #[test]
+    fn fallback_text_follows_the_message() {
+        let attachment = Attachment {
+            name: "report.pdf".to_string(),
+            mime_type: PDF_MIME_TYPE.to_string(),
+            size: 10,
+            data: String::new(),
+            text: "Revenue grew".to_string(),
+        };
+        assert_eq!(
+            content_with_text(Some("Summarize"), &[attachment]).as_deref(),
+            Some("Summarize\n\n[Attached report.pdf]\nRevenue grew\n[End of report.pdf]")
+        );
+        assert_eq!(content_with_text(None, &[]), None);
+    }
+}
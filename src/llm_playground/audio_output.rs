@@ -0,0 +1,98 @@
+// Spoken replies
+// Audio-capable OpenAI models such as gpt-4o-audio-preview can answer with
+// speech as well as text. When turned on, requests ask for the audio modality;
+// the returned clip is kept with the message and played inline, and its
+// transcript becomes the message's text.
+
+use serde::{Deserialize, Serialize};
+
+pub const VOICES: [&str; 8] = ["alloy", "ash", "ballad", "coral", "echo", "sage", "shimmer", "verse"];
+pub const FORMATS: [&str; 4] = ["mp3", "wav", "opus", "flac"];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioOutputSettings {
+    pub enabled: bool,
+    pub voice: String,
+    pub format: String,
+}
+
+impl Default for AudioOutputSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            voice: "alloy".to_string(),
+            format: "mp3".to_string(),
+        }
+    }
+}
+
+impl AudioOutputSettings {
+    /// The request's `modalities` and `audio` parameters, when audio is wanted
+    pub fn request_params(&self) -> Option<(Vec<String>, serde_json::Value)> {
+        self.enabled.then(|| {
+            (
+                vec!["text".to_string(), "audio".to_string()],
+                serde_json::json!({ "voice": self.voice, "format": self.format }),
+            )
+        })
+    }
+}
+
+/// A spoken reply, stored with its message
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MessageAudio {
+    /// Base64-encoded audio
+    pub data: String,
+    pub format: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub transcript: String,
+}
+
+impl MessageAudio {
+    pub fn mime_type(&self) -> &'static str {
+        match self.format.as_str() {
+            "wav" => "audio/wav",
+            "opus" => "audio/ogg",
+            "flac" => "audio/flac",
+            "pcm16" => "audio/L16",
+            _ => "audio/mpeg",
+        }
+    }
+
+    /// Source for an `<audio>` element
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type(), self.data)
+    }
+
+    /// Size of the decoded clip; base64 takes 4 characters per 3 bytes
+    pub fn size_bytes(&self) -> usize {
+        self.data.len() / 4 * 3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audio_is_only_requested_when_enabled() {
+        assert_eq!(AudioOutputSettings::default().request_params(), None);
+        let settings = AudioOutputSettings {
+            enabled: true,
+            voice: "coral".to_string(),
+            format: "wav".to_string(),
+        };
+        let (modalities, audio) = settings.request_params().unwrap();
+        assert_eq!(modalities, vec!["text", "audio"]);
+        assert_eq!(audio, serde_json::json!({ "voice": "coral", "format": "wav" }));
+
+        let clip = MessageAudio {
+            data: "AAAA".to_string(),
+            format: "wav".to_string(),
+            transcript: String::new(),
+        };
+        assert_eq!(clip.data_url(), "data:audio/wav;base64,AAAA");
+        assert_eq!(clip.size_bytes(), 3);
+    }
+}
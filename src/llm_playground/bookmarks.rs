@@ -0,0 +1,135 @@
+// Message bookmarks
+// Messages are pinned by id on their session, so a bookmark goes away with
+// the message or session it points at. The Bookmarks view lists the pinned
+// messages of every session, newest first.
+use crate::llm_playground::{ChatSession, MessageRole};
+use std::collections::HashMap;
+
+/// Characters of the message shown in the Bookmarks view
+const PREVIEW_CHARS: usize = 160;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bookmark {
+    pub session_id: String,
+    pub session_title: String,
+    pub message_id: String,
+    pub role: MessageRole,
+    pub preview: String,
+    pub timestamp: f64,
+}
+
+/// Pin the message, or unpin it if it was pinned. Returns whether it is
+/// pinned now.
+pub fn toggle_pin(session: &mut ChatSession, message_id: &str) -> bool {
+    if let Some(index) = session
+        .pinned_messages
+        .iter()
+        .position(|id| id == message_id)
+    {
+        session.pinned_messages.remove(index);
+        false
+    } else {
+        session.pinned_messages.push(message_id.to_string());
+        true
+    }
+}
+
+/// Pinned messages across all sessions, newest first
+pub fn collect(sessions: &HashMap<String, ChatSession>) -> Vec<Bookmark> {
+    let mut bookmarks: Vec<Bookmark> = sessions
+        .values()
+        .flat_map(|session| {
+            session
+                .messages
+                .iter()
+                .filter(|message| session.pinned_messages.contains(&message.id))
+                .map(|message| {
+                    let text = message
+                        .content
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let mut preview: String = text.chars().take(PREVIEW_CHARS).collect();
+                    if text.chars().count() > PREVIEW_CHARS {
+                        preview.push('…');
+                    }
+                    Bookmark {
+                        session_id: session.id.clone(),
+                        session_title: session.title.clone(),
+                        message_id: message.id.clone(),
+                        role: message.role.clone(),
+                        preview,
+                        timestamp: message.timestamp,
+                    }
+                })
+        })
+        .collect();
+    bookmarks.sort_by(|a, b| b.timestamp.total_cmp(&a.timestamp));
+    bookmarks
+}
+
+/// DOM id of a message in the conversation, scrolled to when a bookmark is
+/// opened
+pub fn message_element_id(message_id: &str) -> String {
+    format!("message-{}", message_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::Message;
+
+    fn session(id: &str, messages: &[(&str, f64)]) -> ChatSession {
+        ChatSession {
+            id: id.to_string(),
+            title: format!("Session {}", id),
+            messages: messages
+                .iter()
+                .map(|(message_id, timestamp)| Message {
+                    id: message_id.to_string(),
+                    timestamp: *timestamp,
+                    ..Message::new(MessageRole::Assistant, format!("Reply  {}\n", message_id))
+                })
+                .collect(),
+            created_at: 0.0,
+            updated_at: 0.0,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: false,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    #[test]
+    fn toggling_twice_unpins() {
+        let mut session = session("a", &[("m1", 1.0)]);
+        assert!(toggle_pin(&mut session, "m1"));
+        assert_eq!(session.pinned_messages, ["m1"]);
+        assert!(!toggle_pin(&mut session, "m1"));
+        assert!(session.pinned_messages.is_empty());
+    }
+
+    #[test]
+    fn bookmarks_span_sessions_newest_first() {
+        let mut first = session("a", &[("m1", 1.0), ("m2", 2.0)]);
+        let mut second = session("b", &[("m3", 3.0)]);
+        toggle_pin(&mut first, "m1");
+        toggle_pin(&mut second, "m3");
+        // Pins of deleted messages are skipped
+        toggle_pin(&mut second, "gone");
+        let sessions = HashMap::from([("a".to_string(), first), ("b".to_string(), second)]);
+
+        let bookmarks = collect(&sessions);
+        let ids: Vec<_> = bookmarks.iter().map(|b| b.message_id.as_str()).collect();
+        assert_eq!(ids, ["m3", "m1"]);
+        assert_eq!(bookmarks[0].session_title, "Session b");
+        assert_eq!(bookmarks[1].preview, "Reply m1");
+    }
+}
@@ -0,0 +1,111 @@
+// Browser notifications
+// When a reply or agent run that took a while finishes while the tab is in the
+// background, the browser shows a system notification. Opt-in in settings;
+// the browser asks for permission when it is turned on.
+
+use crate::llm_playground::tab_status;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+/// Characters of the reply shown in the notification
+const BODY_CHARS: usize = 160;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrowserNotificationSettings {
+    pub enabled: bool,
+    /// Runs shorter than this finish without a notification
+    pub min_seconds: u32,
+}
+
+impl Default for BrowserNotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_seconds: 10,
+        }
+    }
+}
+
+impl BrowserNotificationSettings {
+    /// Whether a run from `started_at` to `finished_at` (ms) is worth a
+    /// notification, given whether the tab is hidden
+    pub fn wants(&self, started_at: f64, finished_at: f64, hidden: bool) -> bool {
+        self.enabled && hidden && finished_at - started_at >= self.min_seconds as f64 * 1000.0
+    }
+}
+
+/// Whether the browser has the Notifications API at all
+pub fn supported() -> bool {
+    web_sys::window().is_some_and(|window| {
+        js_sys::Reflect::has(&window, &JsValue::from_str("Notification")).unwrap_or(false)
+    })
+}
+
+/// The user's answer so far: "granted", "denied" or "default" (not asked)
+pub fn permission() -> Option<NotificationPermission> {
+    supported().then(Notification::permission)
+}
+
+/// Ask for permission to show notifications
+pub async fn request_permission() -> Result<NotificationPermission, String> {
+    if !supported() {
+        return Err("This browser doesn't support notifications".to_string());
+    }
+    let promise = Notification::request_permission()
+        .map_err(|e| format!("Permission request failed: {:?}", e))?;
+    let answer = wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .map_err(|e| format!("Permission request failed: {:?}", e))?;
+    Ok(NotificationPermission::from_js_value(&answer).unwrap_or(NotificationPermission::Default))
+}
+
+/// Show a notification for a finished run if the settings want one; clicking
+/// it brings the tab back
+pub fn notify_finished(
+    settings: &BrowserNotificationSettings,
+    started_at: f64,
+    title: &str,
+    body: &str,
+) {
+    if !settings.wants(started_at, js_sys::Date::now(), tab_status::tab_hidden())
+        || permission() != Some(NotificationPermission::Granted)
+    {
+        return;
+    }
+    let options = NotificationOptions::new();
+    let mut body: String = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    if body.chars().count() > BODY_CHARS {
+        body = format!("{}…", body.chars().take(BODY_CHARS).collect::<String>());
+    }
+    options.set_body(&body);
+    options.set_tag("llm-playground-run");
+    if let Ok(notification) = Notification::new_with_options(title, &options) {
+        let shown = notification.clone();
+        let on_click = Closure::once_into_js(move || {
+            if let Some(window) = web_sys::window() {
+                let _ = window.focus();
+            }
+            shown.close();
+        });
+        notification.set_onclick(Some(on_click.unchecked_ref()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_long_runs_in_hidden_tabs_notify() {
+        let settings = BrowserNotificationSettings {
+            enabled: true,
+            min_seconds: 10,
+        };
+        assert!(settings.wants(0.0, 12_000.0, true));
+        assert!(!settings.wants(0.0, 12_000.0, false));
+        assert!(!settings.wants(0.0, 9_000.0, true));
+        assert!(!BrowserNotificationSettings::default().wants(0.0, 60_000.0, true));
+    }
+}
@@ -0,0 +1,178 @@
+// Session budget
+// Caps on what the agent loop may spend in one session: total tokens, cost
+// estimated from the per-model prices, and tool calls. When a reply asks for
+// tool calls after a cap was passed, the loop pauses before running them. The
+// user either lets it continue, which starts a fresh budget from that point,
+// or stops it, which answers the pending calls without running them.
+
+use crate::llm_playground::{ChatSession, Message, MessageRole};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BudgetSettings {
+    pub max_total_tokens: Option<u32>,
+    /// In the currency of the model prices
+    pub max_cost: Option<f64>,
+    pub max_tool_calls: Option<u32>,
+}
+
+impl BudgetSettings {
+    /// Why `usage` is over budget, if it is
+    pub fn exceeded(&self, usage: &BudgetUsage) -> Option<String> {
+        if let Some(max) = self.max_tool_calls.filter(|max| usage.tool_calls > *max) {
+            return Some(format!(
+                "{} tool calls requested, the limit is {}",
+                usage.tool_calls, max
+            ));
+        }
+        if let Some(max) = self.max_total_tokens.filter(|max| usage.tokens > *max) {
+            return Some(format!(
+                "{} tokens used, the limit is {}",
+                usage.tokens, max
+            ));
+        }
+        if let Some(max) = self.max_cost.filter(|max| usage.cost > *max) {
+            return Some(format!("{:.4} spent, the limit is {:.4}", usage.cost, max));
+        }
+        None
+    }
+}
+
+/// What a session used since its budget last started
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BudgetUsage {
+    pub tokens: u32,
+    pub cost: f64,
+    /// Including calls requested but not run yet
+    pub tool_calls: u32,
+}
+
+impl BudgetUsage {
+    pub fn of_session(session: &ChatSession) -> Self {
+        let mut usage = Self::default();
+        for message in session
+            .messages
+            .iter()
+            .filter(|m| m.timestamp >= session.budget_since)
+        {
+            if let Some(metrics) = &message.metrics {
+                usage.tokens += metrics.input_tokens.unwrap_or_default()
+                    + metrics.output_tokens.unwrap_or_default();
+                usage.cost += metrics.cost.unwrap_or_default();
+            }
+            if let Some(calls) = message.function_call.as_ref().and_then(Value::as_array) {
+                usage.tool_calls += calls.len() as u32;
+            }
+        }
+        usage
+    }
+}
+
+/// Answers to tool calls the user chose not to run, so the conversation
+/// stays valid for the next request
+pub fn stopped_responses(function_calls: &[Value]) -> Vec<Message> {
+    let now = js_sys::Date::now();
+    function_calls
+        .iter()
+        .enumerate()
+        .filter_map(|(index, call)| {
+            let id = call.get("id").and_then(Value::as_str)?;
+            let name = call.get("name").and_then(Value::as_str)?;
+            Some(Message {
+                id: format!("msg_fr_{}_{}", now as u64, index),
+                timestamp: now,
+                function_response: Some(json!({
+                    "id": id,
+                    "name": name,
+                    "response": { "error": "Not run: the session budget was reached and the user stopped the agent" }
+                })),
+                ..Message::new(MessageRole::Function, format!("Function {} not run", name))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::ResponseMetrics;
+
+    fn message(timestamp: f64, tokens: u32, cost: f64, calls: usize) -> Message {
+        Message {
+            timestamp,
+            function_call: (calls > 0).then(|| {
+                json!((0..calls)
+                    .map(|i| json!({ "id": i.to_string(), "name": "f" }))
+                    .collect::<Vec<_>>())
+            }),
+            metrics: Some(ResponseMetrics {
+                input_tokens: Some(tokens),
+                output_tokens: Some(0),
+                cost: Some(cost),
+                ..Default::default()
+            }),
+            ..Message::new(MessageRole::Assistant, "")
+        }
+    }
+
+    fn session(messages: Vec<Message>, budget_since: f64) -> ChatSession {
+        ChatSession {
+            id: String::new(),
+            title: String::new(),
+            messages,
+            created_at: 0.0,
+            updated_at: 0.0,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: false,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    #[test]
+    fn usage_counts_from_the_budget_start() {
+        let messages = vec![message(1.0, 500, 0.5, 2), message(2.0, 300, 0.25, 1)];
+        let all = BudgetUsage::of_session(&session(messages.clone(), 0.0));
+        assert_eq!(all.tokens, 800);
+        assert_eq!(all.cost, 0.75);
+        assert_eq!(all.tool_calls, 3);
+
+        let resumed = BudgetUsage::of_session(&session(messages, 2.0));
+        assert_eq!(resumed.tokens, 300);
+        assert_eq!(resumed.tool_calls, 1);
+    }
+
+    #[test]
+    fn limits_are_only_passed_above_the_cap() {
+        let budget = BudgetSettings {
+            max_tool_calls: Some(3),
+            max_total_tokens: Some(1_000),
+            ..Default::default()
+        };
+        assert_eq!(
+            BudgetSettings::default().exceeded(&BudgetUsage::default()),
+            None
+        );
+
+        let mut usage = BudgetUsage {
+            tokens: 1_000,
+            cost: 99.0,
+            tool_calls: 3,
+        };
+        assert_eq!(budget.exceeded(&usage), None);
+        usage.tool_calls = 4;
+        assert!(budget.exceeded(&usage).unwrap().contains("tool calls"));
+        usage.tool_calls = 0;
+        usage.tokens = 1_001;
+        assert!(budget.exceeded(&usage).unwrap().contains("tokens"));
+    }
+}
@@ -5,7 +5,19 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{Request, RequestInit, RequestMode, Response};
 
+use crate::llm_playground::chart;
+use crate::llm_playground::code_sandbox;
+use crate::llm_playground::cors_proxy;
+use crate::llm_playground::desktop_tools;
+use crate::llm_playground::event_bus::EventBus;
+use crate::llm_playground::fetch_tool::{self, FetchToolSettings};
+use crate::llm_playground::handoff_tools;
+use crate::llm_playground::knowledge;
 use crate::llm_playground::mcp_client::McpClient;
+use crate::llm_playground::provider_config::FlexibleApiConfig;
+use crate::llm_playground::session_database;
+use crate::llm_playground::ChatSession;
+use crate::llm_playground::{web_fetch, web_search};
 
 #[wasm_bindgen]
 extern "C" {
@@ -18,6 +30,8 @@ pub async fn execute_builtin_tool(
     tool_name: &str,
     arguments: &Value,
     mcp_client: Option<&McpClient>,
+    cors_proxy: Option<&str>,
+    fetch_settings: &FetchToolSettings,
 ) -> Result<Value, String> {
     log(&format!("execute_builtin_tool called with: {}", tool_name));
 
@@ -30,13 +44,72 @@ pub async fn execute_builtin_tool(
 
     // Handle built-in tools
     match tool_name {
-        "fetch" => execute_fetch(arguments).await,
+        "fetch" => execute_fetch(arguments, cors_proxy, fetch_settings).await,
         _ => Err(format!("Unknown built-in tool: {}", tool_name)),
     }
 }
 
+/// Execute a configured function tool: built-in tools, WebFetch and WebSearch
+/// run for real, as do the file and shell tools in the desktop build, and the
+/// rest return their mock response. `session` picks the database run_sql
+/// queries. Desktop and handoff tool approvals are published on `approvals`.
+/// Failures are reported to the model as an error object.
+pub async fn execute_function_tool(
+    config: &FlexibleApiConfig,
+    session: &ChatSession,
+    tool_name: &str,
+    arguments: &Value,
+    mcp_client: Option<&McpClient>,
+    approvals: Option<&EventBus>,
+) -> Value {
+    let result = match config.function_tools.iter().find(|tool| tool.name == tool_name) {
+        // Knowledge search needs the embeddings settings, not just the tool call
+        Some(tool) if tool.is_builtin && tool_name == knowledge::SEARCH_DOCUMENTS_TOOL_NAME => {
+            knowledge::search_documents(config, arguments).await
+        }
+        // WebFetch has a model read the page, so it needs the whole config
+        Some(_) if tool_name == web_fetch::WEB_FETCH_TOOL_NAME => web_fetch::run(config, arguments).await,
+        Some(_) if tool_name == web_search::WEB_SEARCH_TOOL_NAME => {
+            web_search::run(&config.web_search, config.shared_settings.cors_proxy(), arguments).await
+        }
+        Some(tool) if tool.is_builtin && tool_name == session_database::RUN_SQL_TOOL_NAME => {
+            session_database::run_sql(&session.id, session.incognito, arguments).await
+        }
+        Some(tool) if tool.is_builtin && tool_name == chart::RENDER_CHART_TOOL_NAME => chart::run(arguments),
+        Some(tool) if tool.is_builtin && handoff_tools::handles(tool_name) => {
+            handoff_tools::run(tool_name, arguments, approvals).await
+        }
+        Some(tool) if tool.is_builtin && code_sandbox::handles(tool_name) => {
+            code_sandbox::run(&config.code_sandbox, tool_name, arguments).await
+        }
+        Some(tool) if tool.is_builtin => {
+            execute_builtin_tool(
+                tool_name,
+                arguments,
+                mcp_client,
+                config.shared_settings.cors_proxy(),
+                &config.fetch_tool,
+            )
+            .await
+        }
+        Some(_) if desktop_tools::handles(&config.desktop_tools, tool_name) => {
+            desktop_tools::run(&config.desktop_tools, tool_name, arguments).await
+        }
+        Some(tool) => {
+            return serde_json::from_str(&tool.mock_response)
+                .unwrap_or_else(|_| serde_json::json!({ "result": tool.mock_response.clone() }))
+        }
+        None => return serde_json::json!({ "error": "Unknown function tool" }),
+    };
+    result.unwrap_or_else(|error| serde_json::json!({ "error": error }))
+}
+
 /// Execute the fetch tool with real HTTP requests
-async fn execute_fetch(arguments: &Value) -> Result<Value, String> {
+async fn execute_fetch(
+    arguments: &Value,
+    proxy: Option<&str>,
+    settings: &FetchToolSettings,
+) -> Result<Value, String> {
     // Extract parameters
     let url = arguments
         .get("url")
@@ -72,8 +145,9 @@ async fn execute_fetch(arguments: &Value) -> Result<Value, String> {
     }
 
     // Create the request
-    let request = Request::new_with_str_and_init(url, &opts)
+    let request = Request::new_with_str_and_init(&cors_proxy::proxied_url(proxy, url), &opts)
         .map_err(|e| format!("Failed to create request: {:?}", e))?;
+    cors_proxy::apply_proxy_headers(&request.headers(), proxy)?;
 
     // Add headers
     for (key, value) in headers {
@@ -132,13 +206,17 @@ async fn execute_fetch(arguments: &Value) -> Result<Value, String> {
         String::new()
     };
 
-    // Build response
-    let response = serde_json::json!({
+    // Build response, with the body in the format asked for
+    let (format, max_bytes) = settings.for_call(arguments);
+    let content_type = response_headers.get("content-type").cloned().unwrap_or_default();
+    let mut response = serde_json::json!({
         "status": status,
         "status_text": status_text,
         "headers": response_headers,
-        "body": body_text
     });
+    if let Some(fields) = response.as_object_mut() {
+        fields.extend(fetch_tool::process(url, &content_type, &body_text, format, max_bytes));
+    }
 
     log(&format!("Response received: status {}", status));
 
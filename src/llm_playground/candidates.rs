@@ -0,0 +1,88 @@
+// N-best sampling
+// With "Candidates" above one in settings, a prompt is answered several times:
+// in one request where the API takes `n` (OpenAI-compatible), otherwise by
+// asking again. The answers are kept on the reply and shown as tabs; the
+// chosen one is the reply's content, and so what later turns see.
+
+use crate::llm_playground::{
+    api_clients::LLMResponse,
+    guardrails::{self, GuardrailRule},
+    Message,
+};
+
+/// Texts of a response's choices, its content first; blank ones are dropped
+pub fn from_response(response: &LLMResponse) -> Vec<String> {
+    response
+        .content
+        .iter()
+        .chain(response.alternatives.iter())
+        .filter(|content| !content.trim().is_empty())
+        .cloned()
+        .collect()
+}
+
+/// Index of the candidate the message shows
+pub fn selected(message: &Message) -> Option<usize> {
+    message
+        .candidates
+        .iter()
+        .position(|candidate| *candidate == message.content)
+}
+
+/// Make candidate `index` the message's content, re-checking it against the
+/// guardrails. Returns whether the message changed.
+pub fn select(message: &mut Message, index: usize, rules: &[GuardrailRule]) -> bool {
+    let Some(candidate) = message.candidates.get(index) else {
+        return false;
+    };
+    if *candidate == message.content {
+        return false;
+    }
+    let (content, violations) = guardrails::apply(candidate, rules);
+    message.content = content;
+    message.guardrail_violations = violations;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::MessageRole;
+
+    fn reply(content: &str, candidates: &[&str]) -> Message {
+        Message {
+            id: "assistant_1".to_string(),
+            candidates: candidates.iter().map(|c| c.to_string()).collect(),
+            ..Message::new(MessageRole::Assistant, content)
+        }
+    }
+
+    #[test]
+    fn response_choices_become_candidates() {
+        let response = LLMResponse {
+            content: Some("first".to_string()),
+            function_calls: Vec::new(),
+            finish_reason: None,
+            reasoning: None,
+            citations: Vec::new(),
+            alternatives: vec![" ".to_string(), "second".to_string()],
+            native_tool_calls: Vec::new(),
+            audio: None,
+            images: Vec::new(),
+            usage: None,
+            timing: None,
+        };
+        assert_eq!(from_response(&response), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn selecting_swaps_the_content() {
+        let mut message = reply("a", &["a", "b", "c"]);
+        assert_eq!(selected(&message), Some(0));
+        assert!(select(&mut message, 2, &[]));
+        assert_eq!(message.content, "c");
+        assert_eq!(selected(&message), Some(2));
+        assert!(!select(&mut message, 2, &[]));
+        assert!(!select(&mut message, 5, &[]));
+    }
+}
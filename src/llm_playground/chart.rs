@@ -0,0 +1,402 @@
+// Chart tool
+// `render_chart` takes a chart spec (a type, category labels and numeric
+// series) and the chat draws it as an SVG chart under the tool's response, so a
+// model can show data it got from run_sql, fetch or a code run instead of
+// describing it. The spec is checked and normalized here; the model gets back
+// the chart it drew, or an error saying what to fix.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+pub const RENDER_CHART_TOOL_NAME: &str = "render_chart";
+
+/// Series one chart may have
+pub const MAX_SERIES: usize = 12;
+/// Values one series may have
+pub const MAX_POINTS: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChartType {
+    Bar,
+    Line,
+    Area,
+    Pie,
+}
+
+impl ChartType {
+    pub const ALL: [ChartType; 4] = [ChartType::Bar, ChartType::Line, ChartType::Area, ChartType::Pie];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            ChartType::Bar => "bar",
+            ChartType::Line => "line",
+            ChartType::Area => "area",
+            ChartType::Pie => "pie",
+        }
+    }
+
+    pub fn parse(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|chart_type| chart_type.id() == id.trim().to_lowercase())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Series {
+    #[serde(default)]
+    pub name: String,
+    /// One value per label; None leaves a gap
+    pub data: Vec<Option<f64>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChartSpec {
+    #[serde(rename = "type")]
+    pub chart_type: ChartType,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
+    pub labels: Vec<String>,
+    pub series: Vec<Series>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub x_label: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub y_label: String,
+}
+
+impl ChartSpec {
+    /// Read a render_chart call. Numbers given as strings are accepted and
+    /// labels are generated when left out.
+    pub fn from_arguments(arguments: &Value) -> Result<Self, String> {
+        let type_id = arguments
+            .get("type")
+            .and_then(Value::as_str)
+            .ok_or("Missing required parameter: type")?;
+        let chart_type = ChartType::parse(type_id)
+            .ok_or_else(|| format!("Unknown chart type '{}'; use bar, line, area or pie", type_id))?;
+
+        let series_values = arguments
+            .get("series")
+            .and_then(Value::as_array)
+            .filter(|series| !series.is_empty())
+            .ok_or("Missing required parameter: series")?;
+        // A bare list of numbers is a single unnamed series
+        let series: Vec<Series> = if series_values.iter().all(|value| !value.is_object()) {
+            vec![Series {
+                name: String::new(),
+                data: read_values("series", series_values)?,
+            }]
+        } else {
+            series_values
+                .iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    let name = value.get("name").map(label_text).unwrap_or_default();
+                    let data = value
+                        .get("data")
+                        .and_then(Value::as_array)
+                        .ok_or_else(|| format!("Series {} has no data array", index + 1))?;
+                    let what = if name.is_empty() { format!("Series {}", index + 1) } else { format!("Series '{}'", name) };
+                    Ok(Series {
+                        data: read_values(&what, data)?,
+                        name,
+                    })
+                })
+                .collect::<Result<_, String>>()?
+        };
+        if series.len() > MAX_SERIES {
+            return Err(format!("A chart can have at most {} series", MAX_SERIES));
+        }
+        if chart_type == ChartType::Pie && series.len() > 1 {
+            return Err("A pie chart takes a single series".to_string());
+        }
+
+        let longest = series.iter().map(|series| series.data.len()).max().unwrap_or(0);
+        if longest > MAX_POINTS {
+            return Err(format!("A series can have at most {} values", MAX_POINTS));
+        }
+        let labels: Vec<String> = match arguments.get("labels").and_then(Value::as_array) {
+            Some(labels) if !labels.is_empty() => labels.iter().map(label_text).collect(),
+            _ => (1..=longest).map(|index| index.to_string()).collect(),
+        };
+        for (index, series) in series.iter().enumerate() {
+            if series.data.len() != labels.len() {
+                let name = if series.name.is_empty() { format!("Series {}", index + 1) } else { format!("Series '{}'", series.name) };
+                return Err(format!(
+                    "{} has {} values but there are {} labels",
+                    name,
+                    series.data.len(),
+                    labels.len()
+                ));
+            }
+        }
+        if !series.iter().flat_map(|series| &series.data).any(Option::is_some) {
+            return Err("The chart has no values to draw".to_string());
+        }
+        if chart_type == ChartType::Pie && series[0].data.iter().flatten().any(|value| *value < 0.0) {
+            return Err("A pie chart can't show negative values".to_string());
+        }
+
+        let text = |key: &str| {
+            arguments
+                .get(key)
+                .and_then(Value::as_str)
+                .map(|text| text.trim().to_string())
+                .unwrap_or_default()
+        };
+        Ok(Self {
+            chart_type,
+            title: text("title"),
+            labels,
+            series,
+            x_label: text("x_label"),
+            y_label: text("y_label"),
+        })
+    }
+
+    /// Lowest and highest value the value axis covers. Bars and areas always
+    /// start from zero.
+    pub fn value_range(&self) -> (f64, f64) {
+        let values = self.series.iter().flat_map(|series| series.data.iter().flatten().copied());
+        let (mut min, mut max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+        if !min.is_finite() {
+            return (0.0, 1.0);
+        }
+        if matches!(self.chart_type, ChartType::Bar | ChartType::Area) {
+            min = min.min(0.0);
+            max = max.max(0.0);
+        }
+        if min == max {
+            // A flat line still needs some height
+            let pad = if min == 0.0 { 1.0 } else { min.abs() / 10.0 };
+            min -= pad;
+            max += pad;
+        }
+        (min, max)
+    }
+
+    /// The chart a render_chart call drew, read back from its function response
+    pub fn from_function_response(function_response: &Value) -> Option<Self> {
+        if function_response.get("name")?.as_str()? != RENDER_CHART_TOOL_NAME {
+            return None;
+        }
+        serde_json::from_value(function_response.pointer("/response/chart")?.clone()).ok()
+    }
+}
+
+fn label_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.trim().to_string(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+fn read_values(what: &str, values: &[Value]) -> Result<Vec<Option<f64>>, String> {
+    values
+        .iter()
+        .map(|value| match value {
+            Value::Null => Ok(None),
+            Value::Number(number) => Ok(number.as_f64()),
+            Value::String(text) => text
+                .trim()
+                .replace(',', "")
+                .parse::<f64>()
+                .ok()
+                .filter(|number| number.is_finite())
+                .map(Some)
+                .ok_or_else(|| format!("{} has a value that isn't a number: {}", what, text)),
+            value => Err(format!("{} has a value that isn't a number: {}", what, value)),
+        })
+        .collect()
+}
+
+/// Draw the call's chart; the model gets the normalized spec back
+pub fn run(arguments: &Value) -> Result<Value, String> {
+    let spec = ChartSpec::from_arguments(arguments)?;
+    Ok(json!({
+        "result": "The chart is shown to the user below this tool call",
+        "chart": spec,
+    }))
+}
+
+/// Round axis steps covering `min..=max` with about `target` ticks
+pub fn nice_ticks(min: f64, max: f64, target: usize) -> Vec<f64> {
+    let span = max - min;
+    if span.is_nan() || span <= 0.0 || target < 2 {
+        return vec![min];
+    }
+    let rough = span / (target - 1) as f64;
+    let magnitude = 10f64.powf(rough.log10().floor());
+    let step = [1.0, 2.0, 2.5, 5.0, 10.0]
+        .iter()
+        .map(|factor| factor * magnitude)
+        .find(|step| *step >= rough)
+        .unwrap_or(10.0 * magnitude);
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+    (first..=last).map(|index| index as f64 * step).collect()
+}
+
+/// A tick or value in a few characters: 1.5k, 2M, 0.25
+pub fn format_value(value: f64) -> String {
+    let abs = value.abs();
+    let (scaled, suffix) = if abs >= 1e9 {
+        (value / 1e9, "B")
+    } else if abs >= 1e6 {
+        (value / 1e6, "M")
+    } else if abs >= 1e4 {
+        (value / 1e3, "k")
+    } else {
+        (value, "")
+    };
+    let text = format!("{:.2}", scaled);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    let text = if text == "-0" { "0" } else { text };
+    format!("{}{}", text, suffix)
+}
+
+/// SVG path through the points, broken where a value is missing
+pub fn line_path(points: &[Option<(f64, f64)>]) -> String {
+    let mut path = String::new();
+    let mut pen_down = false;
+    for point in points {
+        match point {
+            Some((x, y)) => {
+                path.push_str(&format!("{}{:.1} {:.1} ", if pen_down { "L" } else { "M" }, x, y));
+                pen_down = true;
+            }
+            None => pen_down = false,
+        }
+    }
+    path.trim_end().to_string()
+}
+
+/// SVG path filling each unbroken run of points down to `baseline`
+pub fn area_path(points: &[Option<(f64, f64)>], baseline: f64) -> String {
+    points
+        .split(Option::is_none)
+        .filter(|run| !run.is_empty())
+        .map(|run| {
+            let line = line_path(run);
+            let (first_x, _) = run[0].unwrap_or_default();
+            let (last_x, _) = run[run.len() - 1].unwrap_or_default();
+            format!("{} L{:.1} {:.1} L{:.1} {:.1} Z", line, last_x, baseline, first_x, baseline)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// SVG path for a pie slice between two fractions of a full turn, starting at
+/// twelve o'clock and going clockwise
+pub fn pie_slice_path(cx: f64, cy: f64, radius: f64, start: f64, end: f64) -> String {
+    let point = |fraction: f64| {
+        let angle = fraction * std::f64::consts::TAU;
+        (cx + radius * angle.sin(), cy - radius * angle.cos())
+    };
+    if end - start >= 0.9999 {
+        // An arc can't end where it starts, so a whole pie is two halves
+        let (top_x, top_y) = point(0.0);
+        let (bottom_x, bottom_y) = point(0.5);
+        return format!(
+            "M{:.1} {:.1} A{r:.1} {r:.1} 0 1 1 {:.1} {:.1} A{r:.1} {r:.1} 0 1 1 {:.1} {:.1} Z",
+            top_x,
+            top_y,
+            bottom_x,
+            bottom_y,
+            top_x,
+            top_y,
+            r = radius
+        );
+    }
+    let (start_x, start_y) = point(start);
+    let (end_x, end_y) = point(end);
+    let large_arc = if end - start > 0.5 { 1 } else { 0 };
+    format!(
+        "M{:.1} {:.1} L{:.1} {:.1} A{r:.1} {r:.1} 0 {} 1 {:.1} {:.1} Z",
+        cx,
+        cy,
+        start_x,
+        start_y,
+        large_arc,
+        end_x,
+        end_y,
+        r = radius
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn specs_are_read_leniently_and_checked() {
+        let spec = ChartSpec::from_arguments(&json!({
+            "type": "Bar",
+            "title": " Sales ",
+            "labels": ["North", 2024],
+            "series": [{ "name": "Q1", "data": [120, "1,500"] }, { "data": [null, 3.5] }],
+        }))
+        .unwrap();
+        assert_eq!(spec.chart_type, ChartType::Bar);
+        assert_eq!(spec.title, "Sales");
+        assert_eq!(spec.labels, vec!["North", "2024"]);
+        assert_eq!(spec.series[0].data, vec![Some(120.0), Some(1500.0)]);
+        assert_eq!(spec.series[1].data, vec![None, Some(3.5)]);
+
+        let bare = ChartSpec::from_arguments(&json!({ "type": "line", "series": [3, 1, 2] })).unwrap();
+        assert_eq!(bare.labels, vec!["1", "2", "3"]);
+        assert_eq!(bare.series.len(), 1);
+
+        let error = |arguments: Value| ChartSpec::from_arguments(&arguments).unwrap_err();
+        assert_eq!(
+            error(json!({ "type": "donut", "series": [1] })),
+            "Unknown chart type 'donut'; use bar, line, area or pie"
+        );
+        assert_eq!(
+            error(json!({ "type": "bar", "labels": ["a", "b"], "series": [{ "name": "x", "data": [1] }] })),
+            "Series 'x' has 1 values but there are 2 labels"
+        );
+        assert_eq!(
+            error(json!({ "type": "pie", "series": [{ "data": [1] }, { "data": [2] }] })),
+            "A pie chart takes a single series"
+        );
+        assert_eq!(error(json!({ "type": "pie", "series": [1, -2] })), "A pie chart can't show negative values");
+        assert_eq!(error(json!({ "type": "bar", "series": [null] })), "The chart has no values to draw");
+    }
+
+    #[test]
+    fn drawn_charts_are_read_back_from_the_response() {
+        let result = run(&json!({ "type": "area", "labels": ["a", "b"], "series": [1, 2] })).unwrap();
+        let function_response = json!({ "name": RENDER_CHART_TOOL_NAME, "response": result });
+        let spec = ChartSpec::from_function_response(&function_response).unwrap();
+        assert_eq!(spec.chart_type, ChartType::Area);
+        assert_eq!(spec.value_range(), (0.0, 2.0));
+        assert!(ChartSpec::from_function_response(&json!({ "name": "run_sql", "response": result })).is_none());
+    }
+
+    #[test]
+    fn axes_get_round_ticks() {
+        assert_eq!(nice_ticks(0.0, 95.0, 5), vec![0.0, 25.0, 50.0, 75.0, 100.0]);
+        assert_eq!(nice_ticks(-3.0, 7.0, 6), vec![-4.0, -2.0, 0.0, 2.0, 4.0, 6.0, 8.0]);
+        assert_eq!(format_value(1500.0), "1500");
+        assert_eq!(format_value(25_000.0), "25k");
+        assert_eq!(format_value(-2_500_000.0), "-2.5M");
+        assert_eq!(format_value(1.0 / 3.0), "0.33");
+    }
+
+    #[test]
+    fn paths_break_at_gaps() {
+        let points = [Some((0.0, 10.0)), Some((5.0, 0.0)), None, Some((15.0, 5.0))];
+        assert_eq!(line_path(&points), "M0.0 10.0 L5.0 0.0 M15.0 5.0");
+        assert_eq!(
+            area_path(&points, 20.0),
+            "M0.0 10.0 L5.0 0.0 L5.0 20.0 L0.0 20.0 Z M15.0 5.0 L15.0 20.0 L15.0 20.0 Z"
+        );
+        assert_eq!(
+            pie_slice_path(50.0, 50.0, 10.0, 0.0, 0.25),
+            "M50.0 50.0 L50.0 40.0 A10.0 10.0 0 0 1 60.0 50.0 Z"
+        );
+    }
+}
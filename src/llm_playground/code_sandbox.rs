@@ -0,0 +1,391 @@
+// Code execution tools
+// `run_python` and `run_js` run the model's code in a Web Worker made for the
+// call: Python through Pyodide, JavaScript as the body of an async function.
+// The worker is started from a sandboxed iframe with an opaque origin, so it
+// can't reach the app's storage, and the iframe's Content-Security-Policy has
+// `connect-src 'none'` and only lets scripts load from the Pyodide host, so it
+// can't reach the network. Pyodide's own files are fetched by the page for it,
+// until the code starts. Printed output is streamed back to the page as it's
+// written, and the iframe is removed once the time limit passes.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+pub const RUN_PYTHON_TOOL_NAME: &str = "run_python";
+pub const RUN_JS_TOOL_NAME: &str = "run_js";
+
+/// Time Pyodide gets to download and start before the run is given up on
+const LOAD_TIMEOUT_MS: u32 = 60_000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CodeSandboxSettings {
+    /// Seconds a run may take once its runtime has loaded
+    pub timeout_secs: u32,
+    /// Characters of stdout and of stderr returned to the model
+    pub max_output_chars: usize,
+    /// pyodide.js to load Python from
+    pub pyodide_url: String,
+}
+
+impl Default for CodeSandboxSettings {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 10,
+            max_output_chars: 10_000,
+            pyodide_url: "https://cdn.jsdelivr.net/pyodide/v0.27.2/full/pyodide.js".to_string(),
+        }
+    }
+}
+
+/// Whether `tool_name` is one of the code execution tools
+pub fn handles(tool_name: &str) -> bool {
+    tool_name == RUN_PYTHON_TOOL_NAME || tool_name == RUN_JS_TOOL_NAME
+}
+
+/// Shared by both workers: forwards printed output, and stands in for `fetch`
+/// with requests the page makes
+const WORKER_PRELUDE: &str = r#"
+function show(value) {
+    if (typeof value === "string") return value;
+    try { return JSON.stringify(value) ?? String(value); } catch (e) { return String(value); }
+}
+const send = (stream) => (...args) => postMessage({ type: "output", stream, text: args.map(show).join(" ") });
+const fetches = new Map();
+let nextFetch = 0;
+function pageFetch(input, base) {
+    const url = new URL(typeof input === "string" ? input : input.url, base).href;
+    return new Promise((resolve, reject) => {
+        const id = nextFetch++;
+        fetches.set(id, { resolve, reject });
+        postMessage({ type: "fetch", id, url });
+    });
+}
+function settleFetch(data) {
+    const pending = fetches.get(data.id);
+    fetches.delete(data.id);
+    if (!pending) return;
+    if (data.error) pending.reject(new TypeError(data.error));
+    else pending.resolve(new Response(data.body, { status: data.status, headers: { "content-type": data.contentType } }));
+}
+"#;
+
+const JS_WORKER: &str = r#"
+self.onmessage = async (event) => {
+    if (event.data.type === "fetched") return settleFetch(event.data);
+    console.log = console.info = console.debug = send("stdout");
+    console.warn = console.error = send("stderr");
+    postMessage({ type: "started" });
+    try {
+        const AsyncFunction = (async () => {}).constructor;
+        const value = await new AsyncFunction(event.data.code)();
+        postMessage({ type: "done", ok: true, result: value === undefined ? null : show(value) });
+    } catch (error) {
+        const message = error instanceof Error ? `${error.name}: ${error.message}` : show(error);
+        postMessage({ type: "done", ok: false, error: message });
+    }
+};
+"#;
+
+const PYTHON_WORKER: &str = r#"
+self.onmessage = async (event) => {
+    if (event.data.type === "fetched") return settleFetch(event.data);
+    try {
+        const indexURL = event.data.pyodide_url.replace(/[^/]*$/, "");
+        self.fetch = (input) => pageFetch(input, indexURL);
+        importScripts(event.data.pyodide_url);
+        const pyodide = await loadPyodide({ indexURL });
+        pyodide.setStdout({ batched: send("stdout") });
+        pyodide.setStderr({ batched: send("stderr") });
+        await pyodide.loadPackagesFromImports(event.data.code);
+        postMessage({ type: "started" });
+        const value = await pyodide.runPythonAsync(event.data.code);
+        const result = value === undefined || value === null ? null : String(value);
+        if (value && typeof value.destroy === "function") value.destroy();
+        postMessage({ type: "done", ok: true, result });
+    } catch (error) {
+        postMessage({ type: "done", ok: false, error: String(error && error.message || error) });
+    }
+};
+"#;
+
+/// The sandboxed iframe's page: starts the worker and relays messages between
+/// it and the app. `{csp}` is replaced with the page's policy. The desktop
+/// app's CSP allows the script by its SHA-256 hash, in src-tauri/tauri.conf.json.
+const FRAME_PAGE: &str = r#"<!doctype html>
+<meta http-equiv="Content-Security-Policy" content="{csp}">
+<script>
+let worker;
+addEventListener("message", (event) => {
+    if (event.source !== parent) return;
+    const data = event.data;
+    if (data.type === "run") {
+        worker = new Worker(URL.createObjectURL(new Blob([data.source], { type: "text/javascript" })));
+        worker.onmessage = (event) => parent.postMessage(event.data, "*");
+        worker.onerror = (event) => {
+            event.preventDefault();
+            parent.postMessage({ type: "done", ok: false, error: event.message || "The sandbox failed to start" }, "*");
+        };
+        worker.postMessage(data.message);
+    } else if (worker) {
+        worker.postMessage(data, data.body ? [data.body] : []);
+    }
+});
+</script>
+"#;
+
+/// Runs on the page: starts the sandbox, collects its output, fetches the
+/// runtime's files under `fetchBase` until the code starts and enforces the
+/// time limits. Resolves with the run as JSON text.
+const RUNNER: &str = r#"
+return new Promise((resolve) => {
+    const frame = document.createElement("iframe");
+    frame.setAttribute("sandbox", "allow-scripts");
+    frame.style.display = "none";
+    frame.srcdoc = page;
+    const output = [];
+    let startedAt = 0;
+    let timer;
+    const post = (data, transfer) => frame.contentWindow && frame.contentWindow.postMessage(data, "*", transfer);
+    const finish = (result) => {
+        clearTimeout(timer);
+        window.removeEventListener("message", onMessage);
+        frame.remove();
+        const duration_ms = startedAt ? performance.now() - startedAt : 0;
+        resolve(JSON.stringify({ ...result, output, duration_ms }));
+    };
+    const onMessage = async (event) => {
+        if (event.source !== frame.contentWindow) return;
+        const data = event.data;
+        if (data.type === "fetch") {
+            try {
+                const url = new URL(data.url).href;
+                if (startedAt || !fetchBase || !url.startsWith(fetchBase)) {
+                    throw new Error(`Blocked request to ${url}`);
+                }
+                const response = await fetch(url);
+                const body = await response.arrayBuffer();
+                const contentType = response.headers.get("content-type") || "";
+                post({ type: "fetched", id: data.id, status: response.status, contentType, body }, [body]);
+            } catch (error) {
+                post({ type: "fetched", id: data.id, error: String(error && error.message || error) });
+            }
+        } else if (data.type === "output") {
+            output.push({ stream: data.stream, text: data.text });
+        } else if (data.type === "started") {
+            clearTimeout(timer);
+            startedAt = performance.now();
+            timer = setTimeout(
+                () => finish({ ok: false, timed_out: true, error: `Stopped after ${runMs / 1000} s` }),
+                runMs,
+            );
+        } else if (data.type === "done") {
+            finish(data);
+        }
+    };
+    window.addEventListener("message", onMessage);
+    timer = setTimeout(() => finish({ ok: false, error: "The runtime didn't load in time" }), loadMs);
+    frame.onload = () => post({ type: "run", source, message: JSON.parse(message) });
+    document.body.appendChild(frame);
+});
+"#;
+
+/// `scheme://host[:port]` of an http(s) URL; None for anything else, so the
+/// result is safe to put in a policy
+pub fn url_origin(url: &str) -> Option<String> {
+    let (scheme, rest) = url.split_once("://")?;
+    if scheme != "https" && scheme != "http" {
+        return None;
+    }
+    let host = rest.split(['/', '?', '#']).next()?;
+    let valid = !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '[' | ']'));
+    valid.then(|| format!("{}://{}", scheme, host))
+}
+
+/// The sandbox page's policy: no requests, and scripts only from itself, the
+/// worker's blob and `script_origin`
+pub fn content_security_policy(script_origin: Option<&str>) -> String {
+    format!(
+        "default-src 'none'; script-src 'unsafe-inline' 'unsafe-eval' 'wasm-unsafe-eval' blob:{}; worker-src blob:; connect-src 'none'",
+        script_origin.map(|origin| format!(" {}", origin)).unwrap_or_default()
+    )
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct OutputLine {
+    /// "stdout" or "stderr"
+    pub stream: String,
+    pub text: String,
+}
+
+/// What a run reported back
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct SandboxRun {
+    #[serde(default)]
+    pub ok: bool,
+    #[serde(default)]
+    pub output: Vec<OutputLine>,
+    /// The value the code evaluated to, as text
+    #[serde(default)]
+    pub result: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub timed_out: bool,
+    #[serde(default)]
+    pub duration_ms: f64,
+}
+
+impl SandboxRun {
+    /// The tool result: each stream's text cut to `max_chars`, then the
+    /// result or error
+    pub fn to_tool_result(&self, max_chars: usize) -> Value {
+        let stream = |name: &str| {
+            let lines: Vec<&str> = self
+                .output
+                .iter()
+                .filter(|line| line.stream == name)
+                .map(|line| line.text.as_str())
+                .collect();
+            truncate(&lines.join("\n"), max_chars)
+        };
+        let mut result = json!({
+            "ok": self.ok,
+            "stdout": stream("stdout"),
+            "stderr": stream("stderr"),
+            "duration_ms": self.duration_ms.round(),
+        });
+        if let Some(fields) = result.as_object_mut() {
+            if let Some(value) = &self.result {
+                fields.insert("result".to_string(), json!(truncate(value, max_chars)));
+            }
+            if let Some(error) = &self.error {
+                fields.insert("error".to_string(), json!(error));
+            }
+            if self.timed_out {
+                fields.insert("timed_out".to_string(), json!(true));
+            }
+        }
+        result
+    }
+}
+
+/// `text` cut to `max_chars` characters with a note of what was left out
+fn truncate(text: &str, max_chars: usize) -> String {
+    let total = text.chars().count();
+    if total <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars).collect();
+    format!("{}\n… ({} more characters)", kept, total - max_chars)
+}
+
+/// Run the call's code with the tool's runtime
+pub async fn run(settings: &CodeSandboxSettings, tool_name: &str, arguments: &Value) -> Result<Value, String> {
+    let code = arguments
+        .get("code")
+        .and_then(Value::as_str)
+        .filter(|code| !code.trim().is_empty())
+        .ok_or("Missing required parameter: code")?;
+    let python = tool_name == RUN_PYTHON_TOOL_NAME;
+    // Python may load scripts from Pyodide's host, and have the page fetch the
+    // files beside pyodide.js; JavaScript gets neither
+    let (worker, script_origin, fetch_base) = if python {
+        let origin = url_origin(&settings.pyodide_url).ok_or("The Pyodide URL must be an http(s) URL")?;
+        let base = settings.pyodide_url.rsplit_once('/').map(|(base, _)| format!("{}/", base));
+        (PYTHON_WORKER, Some(origin), base)
+    } else {
+        (JS_WORKER, None, None)
+    };
+    let source = format!("{}{}", WORKER_PRELUDE, worker);
+    let page = FRAME_PAGE.replace("{csp}", &content_security_policy(script_origin.as_deref()));
+    let message = json!({ "code": code, "pyodide_url": settings.pyodide_url }).to_string();
+    let run_ms = settings.timeout_secs.max(1) * 1000;
+
+    let runner = js_sys::Function::new_with_args("page, source, message, fetchBase, loadMs, runMs", RUNNER);
+    let args: js_sys::Array = [
+        page.into(),
+        source.into(),
+        message.into(),
+        fetch_base.map_or(JsValue::NULL, JsValue::from),
+        LOAD_TIMEOUT_MS.into(),
+        run_ms.into(),
+    ]
+    .iter()
+    .collect();
+    let promise: js_sys::Promise = runner
+        .apply(&JsValue::NULL, &args)
+        .map_err(|e| format!("Couldn't start the sandbox: {:?}", e))?
+        .into();
+    let text = JsFuture::from(promise)
+        .await
+        .map_err(|e| format!("The sandbox failed: {:?}", e))?
+        .as_string()
+        .unwrap_or_default();
+    let run: SandboxRun = serde_json::from_str(&text).map_err(|e| format!("Unreadable sandbox result: {}", e))?;
+    Ok(run.to_tool_result(settings.max_output_chars))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(stream: &str, text: &str) -> OutputLine {
+        OutputLine {
+            stream: stream.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn streams_are_split_and_cut() {
+        let run = SandboxRun {
+            ok: false,
+            output: vec![line("stdout", "1"), line("stderr", "warning"), line("stdout", "22345")],
+            result: None,
+            error: Some("Stopped after 10 s".to_string()),
+            timed_out: true,
+            duration_ms: 10_000.4,
+        };
+        assert_eq!(
+            run.to_tool_result(4),
+            json!({
+                "ok": false,
+                "stdout": "1\n22\n… (3 more characters)",
+                "stderr": "warn\n… (3 more characters)",
+                "duration_ms": 10_000.0,
+                "error": "Stopped after 10 s",
+                "timed_out": true,
+            })
+        );
+    }
+
+    #[test]
+    fn only_http_origins_reach_the_policy() {
+        assert_eq!(
+            url_origin("https://cdn.jsdelivr.net/pyodide/v0.27.2/full/pyodide.js").as_deref(),
+            Some("https://cdn.jsdelivr.net")
+        );
+        assert_eq!(url_origin("http://localhost:8000/pyodide.js").as_deref(), Some("http://localhost:8000"));
+        assert_eq!(url_origin("javascript:alert(1)"), None);
+        assert_eq!(url_origin("https://evil.example\"><script>"), None);
+        assert_eq!(
+            content_security_policy(None),
+            "default-src 'none'; script-src 'unsafe-inline' 'unsafe-eval' 'wasm-unsafe-eval' blob:; worker-src blob:; connect-src 'none'"
+        );
+        assert!(content_security_policy(Some("https://cdn.jsdelivr.net")).contains("blob: https://cdn.jsdelivr.net;"));
+    }
+
+    #[test]
+    fn worker_messages_read_as_a_run() {
+        let text = r#"{"type": "done", "ok": true, "result": "42", "output": [{"stream": "stdout", "text": "hi"}], "duration_ms": 3}"#;
+        let run: SandboxRun = serde_json::from_str(text).unwrap();
+        assert_eq!(run.to_tool_result(100)["result"], "42");
+        assert!(run.to_tool_result(100).get("timed_out").is_none());
+    }
+}
@@ -0,0 +1,506 @@
+// A/B system-prompt experiment: one user prompt, two system prompts, N runs each,
+// with an optional judge picking the better reply of every pair
+use crate::llm_playground::{
+    ab_experiment::{self, Preference, Sample, Variant, MAX_EXPERIMENT_RUNS},
+    app_state::use_app_state,
+};
+use std::rc::Rc;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct PairResult {
+    a: Option<Sample>,
+    b: Option<Sample>,
+    judgment: Option<Result<Preference, String>>,
+}
+
+impl PairResult {
+    fn both_succeeded(&self) -> bool {
+        matches!(
+            (&self.a, &self.b),
+            (
+                Some(Sample { output: Ok(_), .. }),
+                Some(Sample { output: Ok(_), .. })
+            )
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct ExperimentRun {
+    run_id: u64,
+    judged: bool,
+    pairs: Vec<PairResult>,
+}
+
+impl ExperimentRun {
+    fn is_running(&self) -> bool {
+        self.pairs.iter().any(|pair| {
+            pair.a.is_none()
+                || pair.b.is_none()
+                || (self.judged && pair.both_succeeded() && pair.judgment.is_none())
+        })
+    }
+}
+
+enum ExperimentAction {
+    Start {
+        run_id: u64,
+        runs: usize,
+        judged: bool,
+    },
+    Sample {
+        run_id: u64,
+        index: usize,
+        variant: Variant,
+        sample: Sample,
+    },
+    Judged {
+        run_id: u64,
+        index: usize,
+        result: Result<Preference, String>,
+    },
+}
+
+impl Reducible for ExperimentRun {
+    type Action = ExperimentAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            ExperimentAction::Start {
+                run_id,
+                runs,
+                judged,
+            } => Rc::new(Self {
+                run_id,
+                judged,
+                pairs: vec![PairResult::default(); runs],
+            }),
+            ExperimentAction::Sample {
+                run_id,
+                index,
+                variant,
+                sample,
+            } => {
+                // Ignore late results from a previous run
+                if run_id != self.run_id || index >= self.pairs.len() {
+                    return self;
+                }
+                let mut next = (*self).clone();
+                match variant {
+                    Variant::A => next.pairs[index].a = Some(sample),
+                    Variant::B => next.pairs[index].b = Some(sample),
+                }
+                Rc::new(next)
+            }
+            ExperimentAction::Judged {
+                run_id,
+                index,
+                result,
+            } => {
+                if run_id != self.run_id || index >= self.pairs.len() {
+                    return self;
+                }
+                let mut next = (*self).clone();
+                next.pairs[index].judgment = Some(result);
+                Rc::new(next)
+            }
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct AbExperimentViewProps {
+    pub on_close: Callback<()>,
+}
+
+#[function_component(AbExperimentView)]
+pub fn ab_experiment_view(props: &AbExperimentViewProps) -> Html {
+    let app_state = use_app_state();
+    let combos = app_state.config.get_all_provider_models();
+    let model = use_state(|| app_state.config.get_current_provider_and_model());
+    let judge_model = use_state(|| Option::<(String, String)>::None);
+    let judge_criteria = use_state(String::new);
+    let user_prompt = use_state(String::new);
+    let system_prompt_a = use_state(|| app_state.config.system_prompt.clone());
+    let system_prompt_b = use_state(String::new);
+    let runs = use_state(|| 3usize);
+    let run = use_reducer(ExperimentRun::default);
+
+    let is_running = run.is_running();
+
+    let text_setter = |state: &UseStateHandle<String>| {
+        let state = state.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            state.set(input.value());
+        })
+    };
+    let combo_setter = |state: &UseStateHandle<(String, String)>| {
+        let state = state.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some((provider, model)) = input.value().split_once(',') {
+                state.set((provider.to_string(), model.to_string()));
+            }
+        })
+    };
+
+    let on_judge_model_change = {
+        let judge_model = judge_model.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            judge_model.set(
+                input
+                    .value()
+                    .split_once(',')
+                    .map(|(provider, model)| (provider.to_string(), model.to_string())),
+            );
+        })
+    };
+
+    let on_criteria_input = {
+        let judge_criteria = judge_criteria.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            judge_criteria.set(input.value());
+        })
+    };
+
+    let on_runs_input = {
+        let runs = runs.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(value) = input.value().parse::<usize>() {
+                runs.set(value.clamp(1, MAX_EXPERIMENT_RUNS));
+            }
+        })
+    };
+
+    let on_run = {
+        let run = run.clone();
+        let model = model.clone();
+        let judge_model = judge_model.clone();
+        let judge_criteria = judge_criteria.clone();
+        let user_prompt = user_prompt.clone();
+        let system_prompt_a = system_prompt_a.clone();
+        let system_prompt_b = system_prompt_b.clone();
+        let runs = runs.clone();
+        let config = app_state.config.clone();
+        let llm_client = app_state.llm_client.clone();
+        Callback::from(move |_: MouseEvent| {
+            let prompt = user_prompt.trim().to_string();
+            if prompt.is_empty() {
+                return;
+            }
+            let pinned = |(provider, model): &(String, String)| {
+                let mut pinned = config.clone();
+                pinned.set_session_provider(provider, model);
+                pinned.apply_model_defaults(provider, model);
+                // Only the system prompt differs between variants
+                pinned.function_tools.clear();
+                pinned
+            };
+            let run_config = pinned(&model);
+            let judge_config = judge_model.as_ref().map(pinned);
+            let prompts = [(*system_prompt_a).clone(), (*system_prompt_b).clone()];
+            let criteria = (*judge_criteria).clone();
+            let total_runs = *runs;
+
+            let run_id = js_sys::Date::now() as u64;
+            run.dispatch(ExperimentAction::Start {
+                run_id,
+                runs: total_runs,
+                judged: judge_config.is_some(),
+            });
+
+            let run = run.clone();
+            let llm_client = llm_client.clone();
+            // Runs go one at a time, alternating variants, so rate limits and
+            // provider load affect both variants alike
+            wasm_bindgen_futures::spawn_local(async move {
+                for index in 0..total_runs {
+                    let mut outputs = Vec::new();
+                    for (variant, system_prompt) in [Variant::A, Variant::B].iter().zip(&prompts) {
+                        let sample = ab_experiment::run_sample(
+                            system_prompt,
+                            &prompt,
+                            &llm_client,
+                            &run_config,
+                        )
+                        .await;
+                        outputs.push(sample.output.clone().ok());
+                        run.dispatch(ExperimentAction::Sample {
+                            run_id,
+                            index,
+                            variant: *variant,
+                            sample,
+                        });
+                    }
+
+                    if let (Some(judge_config), [Some(output_a), Some(output_b)]) =
+                        (&judge_config, outputs.as_slice())
+                    {
+                        let result = ab_experiment::judge_pair(
+                            &prompt,
+                            output_a,
+                            output_b,
+                            &criteria,
+                            &llm_client,
+                            judge_config,
+                        )
+                        .await;
+                        run.dispatch(ExperimentAction::Judged {
+                            run_id,
+                            index,
+                            result,
+                        });
+                    }
+                }
+            });
+        })
+    };
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    let select_class = "p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 text-sm";
+    let textarea_class = "w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 text-sm";
+
+    html! {
+        <div class="flex-1 flex flex-col min-w-0 bg-white dark:bg-gray-800">
+            <header class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
+                <div>
+                    <h2 class="font-semibold text-gray-900 dark:text-gray-100">{"A/B System Prompts"}</h2>
+                    <div class="text-sm text-gray-600 dark:text-gray-300">
+                        {"Run one prompt against two system prompts on the same model and compare the replies."}
+                    </div>
+                </div>
+                <button
+                    onclick={on_close}
+                    class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                    aria-label="Close experiment"
+                    title="Back to chat"
+                >
+                    <i class="fas fa-times" aria-hidden="true"></i>
+                </button>
+            </header>
+
+            <div class="flex-1 overflow-y-auto custom-scrollbar p-4 space-y-4">
+                <div class="flex flex-wrap items-center gap-2">
+                    <label class="text-sm text-gray-700 dark:text-gray-300">{"Model"}</label>
+                    <select aria-label="Model" onchange={combo_setter(&model)} class={select_class} disabled={is_running}>
+                        {for combos.iter().map(|(provider, name)| html! {
+                            <option value={format!("{},{}", provider, name)} selected={*model == (provider.clone(), name.clone())}>
+                                {format!("{} / {}", provider, name)}
+                            </option>
+                        })}
+                    </select>
+                    <label class="text-sm text-gray-700 dark:text-gray-300" for="ab-runs">{"Runs per variant"}</label>
+                    <input
+                        id="ab-runs"
+                        type="number"
+                        min="1"
+                        max={MAX_EXPERIMENT_RUNS.to_string()}
+                        value={runs.to_string()}
+                        oninput={on_runs_input}
+                        disabled={is_running}
+                        class={classes!(select_class, "w-20")}
+                    />
+                    <label class="text-sm text-gray-700 dark:text-gray-300">{"Judge"}</label>
+                    <select aria-label="Judge model" onchange={on_judge_model_change} class={select_class} disabled={is_running}>
+                        <option value="" selected={judge_model.is_none()}>{"No judge"}</option>
+                        {for combos.iter().map(|(provider, name)| html! {
+                            <option value={format!("{},{}", provider, name)} selected={judge_model.as_ref() == Some(&(provider.clone(), name.clone()))}>
+                                {format!("{} / {}", provider, name)}
+                            </option>
+                        })}
+                    </select>
+                    {if judge_model.is_some() {
+                        html! {
+                            <input
+                                type="text"
+                                aria-label="Judge criteria"
+                                value={(*judge_criteria).clone()}
+                                oninput={on_criteria_input}
+                                disabled={is_running}
+                                class={classes!(select_class, "flex-1", "min-w-[12rem]")}
+                                placeholder="Criteria (default: helpfulness, accuracy, clarity)"
+                            />
+                        }
+                    } else {
+                        html! {}
+                    }}
+                </div>
+
+                <div>
+                    <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="ab-user-prompt">{"User prompt"}</label>
+                    <textarea
+                        id="ab-user-prompt"
+                        rows="3"
+                        value={(*user_prompt).clone()}
+                        oninput={text_setter(&user_prompt)}
+                        class={textarea_class}
+                    />
+                </div>
+
+                <div class="grid grid-cols-2 gap-4">
+                    {for [(Variant::A, &system_prompt_a), (Variant::B, &system_prompt_b)].into_iter().map(|(variant, state)| html! {
+                        <div>
+                            <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300">
+                                {format!("System prompt {}", variant.label())}
+                            </label>
+                            <textarea
+                                rows="5"
+                                aria-label={format!("System prompt {}", variant.label())}
+                                value={(**state).clone()}
+                                oninput={text_setter(state)}
+                                class={textarea_class}
+                            />
+                        </div>
+                    })}
+                </div>
+
+                <div class="flex justify-end">
+                    <button
+                        onclick={on_run}
+                        disabled={is_running || user_prompt.trim().is_empty()}
+                        class="text-sm px-4 py-2 bg-primary-600 text-white rounded-md hover:bg-primary-700 disabled:opacity-50"
+                    >
+                        {if is_running {
+                            html! { <><i class="fas fa-spinner fa-spin mr-1" aria-hidden="true"></i>{"Running..."}</> }
+                        } else {
+                            html! { <><i class="fas fa-play mr-1" aria-hidden="true"></i>{"Run experiment"}</> }
+                        }}
+                    </button>
+                </div>
+
+                {if run.pairs.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <>
+                            {render_summary(&run)}
+                            {for run.pairs.iter().enumerate().map(|(index, pair)| render_pair(index, pair))}
+                        </>
+                    }
+                }}
+            </div>
+        </div>
+    }
+}
+
+fn render_summary(run: &ExperimentRun) -> Html {
+    let stats = |pick: fn(&PairResult) -> &Option<Sample>| {
+        let outputs: Vec<(&String, f64)> = run
+            .pairs
+            .iter()
+            .filter_map(|pair| match pick(pair) {
+                Some(Sample {
+                    output: Ok(text),
+                    latency_ms,
+                }) => Some((text, *latency_ms)),
+                _ => None,
+            })
+            .collect();
+        if outputs.is_empty() {
+            return "—".to_string();
+        }
+        let count = outputs.len() as f64;
+        let avg_latency = outputs.iter().map(|(_, ms)| ms).sum::<f64>() / count;
+        let avg_chars = outputs
+            .iter()
+            .map(|(t, _)| t.chars().count())
+            .sum::<usize>() as f64
+            / count;
+        format!("{:.0} ms avg · {:.0} chars avg", avg_latency, avg_chars)
+    };
+    let verdicts = |preference: Preference| {
+        run.pairs
+            .iter()
+            .filter(|pair| pair.judgment == Some(Ok(preference)))
+            .count()
+    };
+
+    html! {
+        <div class="p-3 rounded-md bg-gray-50 dark:bg-gray-900/40 border border-gray-200 dark:border-gray-600 text-sm text-gray-700 dark:text-gray-300 space-y-1" role="status">
+            <div><span class="font-medium">{"A: "}</span>{stats(|pair| &pair.a)}</div>
+            <div><span class="font-medium">{"B: "}</span>{stats(|pair| &pair.b)}</div>
+            {if run.judged {
+                html! {
+                    <div class="font-medium">
+                        {format!(
+                            "Judge preferred A {} × · B {} × · tie {} ×",
+                            verdicts(Preference::Prefers(Variant::A)),
+                            verdicts(Preference::Prefers(Variant::B)),
+                            verdicts(Preference::Tie),
+                        )}
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
+
+fn render_pair(index: usize, pair: &PairResult) -> Html {
+    let verdict = match &pair.judgment {
+        Some(Ok(Preference::Prefers(variant))) => {
+            Some(format!("Judge prefers {}", variant.label()))
+        }
+        Some(Ok(Preference::Tie)) => Some("Judge: tie".to_string()),
+        Some(Err(error)) => Some(format!("Judge failed: {}", error)),
+        None => None,
+    };
+    let preferred = match &pair.judgment {
+        Some(Ok(Preference::Prefers(variant))) => Some(*variant),
+        _ => None,
+    };
+    let render_sample = |variant: Variant, sample: &Option<Sample>| {
+        let highlight = if preferred == Some(variant) {
+            "border-green-400 dark:border-green-600"
+        } else {
+            "border-gray-200 dark:border-gray-600"
+        };
+        html! {
+            <div class={classes!("p-3", "rounded-md", "border", "text-sm", highlight)}>
+                <div class="text-xs font-medium text-gray-500 dark:text-gray-400 mb-1">
+                    {match sample {
+                        Some(sample) => format!("{} · {:.0} ms", variant.label(), sample.latency_ms),
+                        None => variant.label().to_string(),
+                    }}
+                </div>
+                {match sample {
+                    None => html! { <i class="fas fa-spinner fa-spin text-gray-400" aria-hidden="true"></i> },
+                    Some(Sample { output: Ok(text), .. }) => html! {
+                        <div class="whitespace-pre-wrap text-gray-800 dark:text-gray-200">{text}</div>
+                    },
+                    Some(Sample { output: Err(error), .. }) => html! {
+                        <div class="text-red-600 dark:text-red-400">{format!("Error: {}", error)}</div>
+                    },
+                }}
+            </div>
+        }
+    };
+
+    html! {
+        <section class="space-y-2" aria-label={format!("Run {}", index + 1)}>
+            <div class="flex items-center justify-between text-sm">
+                <span class="font-medium text-gray-700 dark:text-gray-300">{format!("Run {}", index + 1)}</span>
+                {if let Some(verdict) = verdict {
+                    html! { <span class="text-xs text-gray-600 dark:text-gray-300">{verdict}</span> }
+                } else {
+                    html! {}
+                }}
+            </div>
+            <div class="grid grid-cols-2 gap-4">
+                {render_sample(Variant::A, &pair.a)}
+                {render_sample(Variant::B, &pair.b)}
+            </div>
+        </section>
+    }
+}
@@ -0,0 +1,208 @@
+use super::notification::{NotificationMessage, NotificationType};
+use crate::llm_playground::{
+    artifacts::{self, Artifact},
+    event_bus::PlaygroundEvent,
+    hooks::use_event_bus,
+    html_export,
+};
+use std::collections::HashMap;
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ArtifactPanelProps {
+    /// Open artifacts, one tab each
+    pub artifacts: Vec<Artifact>,
+    pub active_id: String,
+    pub on_select: Callback<String>,
+    /// Emits the id of the tab to close
+    pub on_close: Callback<String>,
+    /// Emits the chat draft carrying the edited artifact
+    pub on_send_edits: Callback<String>,
+}
+
+/// Side panel with long code blocks and documents taken out of the conversation
+#[function_component(ArtifactPanel)]
+pub fn artifact_panel(props: &ArtifactPanelProps) -> Html {
+    // Edited copies by artifact id; the original stays in the message
+    let edits = use_state(HashMap::<String, String>::new);
+    let on_notification = use_event_bus().callback(PlaygroundEvent::Notify);
+
+    let Some(active) = props
+        .artifacts
+        .iter()
+        .find(|a| a.id == props.active_id)
+        .or_else(|| props.artifacts.last())
+    else {
+        return html! {};
+    };
+    let current = edits
+        .get(&active.id)
+        .cloned()
+        .unwrap_or_else(|| active.content.clone());
+    let is_edited = current != active.content;
+
+    let on_input = {
+        let edits = edits.clone();
+        let id = active.id.clone();
+        Callback::from(move |e: InputEvent| {
+            let value = e.target_unchecked_into::<HtmlTextAreaElement>().value();
+            let mut next = (*edits).clone();
+            next.insert(id.clone(), value);
+            edits.set(next);
+        })
+    };
+
+    let on_copy = {
+        let text = current.clone();
+        let on_notification = on_notification.clone();
+        Callback::from(move |_: MouseEvent| {
+            let text = text.clone();
+            let on_notification = on_notification.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let notification = match html_export::copy_to_clipboard(&text).await {
+                    Ok(()) => NotificationMessage::new(
+                        "Copied to clipboard".to_string(),
+                        NotificationType::Success,
+                    ),
+                    Err(error) => NotificationMessage::new(error, NotificationType::Error),
+                };
+                on_notification.emit(notification);
+            });
+        })
+    };
+
+    let on_download = {
+        let file_name = active.file_name();
+        let mime_type = active.mime_type();
+        let text = current.clone();
+        let on_notification = on_notification.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Err(error) = html_export::download_file(&file_name, &text, mime_type) {
+                on_notification.emit(NotificationMessage::new(
+                    format!("Download failed: {}", error),
+                    NotificationType::Error,
+                ));
+            }
+        })
+    };
+
+    let on_reset = {
+        let edits = edits.clone();
+        let id = active.id.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut next = (*edits).clone();
+            next.remove(&id);
+            edits.set(next);
+        })
+    };
+
+    let on_send = {
+        let on_send_edits = props.on_send_edits.clone();
+        let message = artifacts::edits_message(active, &current);
+        Callback::from(move |_: MouseEvent| on_send_edits.emit(message.clone()))
+    };
+
+    let toolbar_button = "px-2 py-1 text-xs rounded text-gray-600 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700";
+
+    html! {
+        <aside
+            class="w-[32rem] max-w-[50%] flex-shrink-0 border-l border-gray-200 dark:border-gray-600 bg-white dark:bg-gray-800 flex flex-col"
+            aria-label="Artifacts"
+        >
+            <div class="flex items-center border-b border-gray-200 dark:border-gray-600 overflow-x-auto custom-scrollbar" role="tablist">
+                {for props.artifacts.iter().map(|artifact| {
+                    let selected = artifact.id == active.id;
+                    let on_select = {
+                        let on_select = props.on_select.clone();
+                        let id = artifact.id.clone();
+                        Callback::from(move |_: MouseEvent| on_select.emit(id.clone()))
+                    };
+                    let on_close = {
+                        let on_close = props.on_close.clone();
+                        let id = artifact.id.clone();
+                        Callback::from(move |e: MouseEvent| {
+                            e.stop_propagation();
+                            on_close.emit(id.clone())
+                        })
+                    };
+                    html! {
+                        <div
+                            key={artifact.id.clone()}
+                            class={classes!(
+                                "flex", "items-center", "flex-shrink-0", "px-3", "py-2", "text-sm", "border-r",
+                                "border-gray-200", "dark:border-gray-600",
+                                if selected {
+                                    "bg-primary-50 dark:bg-gray-700 text-primary-700 dark:text-primary-300"
+                                } else {
+                                    "text-gray-600 dark:text-gray-300 hover:bg-gray-50 dark:hover:bg-gray-700"
+                                }
+                            )}
+                        >
+                            <button onclick={on_select} role="tab" aria-selected={selected.to_string()} class="max-w-[10rem] truncate">
+                                {if edits.get(&artifact.id).is_some_and(|edited| *edited != artifact.content) {
+                                    format!("{} •", artifact.title)
+                                } else {
+                                    artifact.title.clone()
+                                }}
+                            </button>
+                            <button
+                                onclick={on_close}
+                                class="ml-2 text-gray-400 hover:text-gray-700 dark:hover:text-gray-200"
+                                aria-label={format!("Close {}", artifact.title)}
+                            >
+                                <i class="fas fa-times text-xs" aria-hidden="true"></i>
+                            </button>
+                        </div>
+                    }
+                })}
+            </div>
+            <div class="flex items-center justify-between px-3 py-2 border-b border-gray-200 dark:border-gray-600">
+                <span class="text-xs text-gray-500 dark:text-gray-400">
+                    {format!("{} · {} lines", active.file_name(), current.lines().count())}
+                </span>
+                <div class="flex items-center space-x-1">
+                    <button onclick={on_copy} class={toolbar_button} title="Copy to clipboard">
+                        <i class="fas fa-copy mr-1" aria-hidden="true"></i>{"Copy"}
+                    </button>
+                    <button onclick={on_download} class={toolbar_button} title="Download as a file">
+                        <i class="fas fa-download mr-1" aria-hidden="true"></i>{"Download"}
+                    </button>
+                    {if is_edited {
+                        html! {
+                            <button onclick={on_reset} class={toolbar_button} title="Discard edits">
+                                <i class="fas fa-undo mr-1" aria-hidden="true"></i>{"Reset"}
+                            </button>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                </div>
+            </div>
+            <textarea
+                class="flex-1 w-full p-3 font-mono text-sm resize-none border-0 focus:ring-0 bg-gray-50 dark:bg-gray-900 text-gray-800 dark:text-gray-100 custom-scrollbar"
+                aria-label={format!("Contents of {}", active.title)}
+                spellcheck="false"
+                value={current}
+                oninput={on_input}
+            />
+            <div class="p-3 border-t border-gray-200 dark:border-gray-600">
+                <button
+                    onclick={on_send}
+                    disabled={!is_edited}
+                    class={classes!(
+                        "w-full", "px-3", "py-2", "text-sm", "rounded-md",
+                        if is_edited {
+                            "bg-primary-600 hover:bg-primary-700 text-white"
+                        } else {
+                            "bg-gray-100 dark:bg-gray-700 text-gray-400 dark:text-gray-500 cursor-not-allowed"
+                        }
+                    )}
+                    title="Put the edited version in the message box"
+                >
+                    <i class="fas fa-reply mr-1" aria-hidden="true"></i>{"Send edits back to chat"}
+                </button>
+            </div>
+        </aside>
+    }
+}
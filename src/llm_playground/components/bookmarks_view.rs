@@ -0,0 +1,127 @@
+// Pinned messages of every session, each opening its session scrolled to the
+// message
+use super::sidebar::format_time_ago;
+use crate::llm_playground::{
+    bookmarks::{self, Bookmark},
+    event_bus::PlaygroundEvent,
+    hooks::use_event_bus,
+    ChatSession, MessageRole,
+};
+use std::collections::HashMap;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct BookmarksViewProps {
+    pub sessions: HashMap<String, ChatSession>,
+    /// Open a session at a message: (session id, message id)
+    pub on_open: Callback<(String, String)>,
+    pub on_close: Callback<()>,
+}
+
+fn role_label(role: &MessageRole) -> &'static str {
+    match role {
+        MessageRole::System => "System",
+        MessageRole::Developer => "Developer",
+        MessageRole::User => "You",
+        MessageRole::Assistant => "Assistant",
+        MessageRole::Function => "Function",
+    }
+}
+
+#[function_component(BookmarksView)]
+pub fn bookmarks_view(props: &BookmarksViewProps) -> Html {
+    let on_session_update = use_event_bus().callback(PlaygroundEvent::SessionUpdated);
+    let bookmarks = bookmarks::collect(&props.sessions);
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    let render_bookmark = |bookmark: &Bookmark| {
+        let on_open = {
+            let on_open = props.on_open.clone();
+            let target = (bookmark.session_id.clone(), bookmark.message_id.clone());
+            Callback::from(move |_: MouseEvent| on_open.emit(target.clone()))
+        };
+        let on_unpin = {
+            let session = props.sessions.get(&bookmark.session_id).cloned();
+            let message_id = bookmark.message_id.clone();
+            let on_session_update = on_session_update.clone();
+            Callback::from(move |_: MouseEvent| {
+                if let Some(mut session) = session.clone() {
+                    bookmarks::toggle_pin(&mut session, &message_id);
+                    on_session_update.emit(session);
+                }
+            })
+        };
+
+        html! {
+            <li key={format!("{}/{}", bookmark.session_id, bookmark.message_id)} class="group relative">
+                <button
+                    onclick={on_open}
+                    class="w-full text-left p-3 pr-10 rounded-md border border-gray-200 dark:border-gray-600 hover:bg-gray-50 dark:hover:bg-gray-700 focus:outline-none focus:ring-2 focus:ring-primary-500"
+                    title="Open the session at this message"
+                >
+                    <div class="flex items-center gap-2 text-xs text-gray-500 dark:text-gray-400 mb-1">
+                        <span class="font-medium text-gray-700 dark:text-gray-300 truncate">{&bookmark.session_title}</span>
+                        <span aria-hidden="true">{"·"}</span>
+                        <span>{role_label(&bookmark.role)}</span>
+                        <span aria-hidden="true">{"·"}</span>
+                        <span>{format_time_ago(bookmark.timestamp)}</span>
+                    </div>
+                    <div class="text-sm text-gray-800 dark:text-gray-200 line-clamp-3">
+                        {if bookmark.preview.is_empty() { "(no text)" } else { bookmark.preview.as_str() }}
+                    </div>
+                </button>
+                <button
+                    onclick={on_unpin}
+                    class="absolute right-2 top-2 p-1 text-yellow-500 hover:text-yellow-600"
+                    aria-label={format!("Unpin message from {}", bookmark.session_title)}
+                    title="Remove from bookmarks"
+                >
+                    <i class="fas fa-bookmark" aria-hidden="true"></i>
+                </button>
+            </li>
+        }
+    };
+
+    html! {
+        <div class="flex-1 flex flex-col min-w-0 bg-white dark:bg-gray-800">
+            <header class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
+                <div>
+                    <h2 class="font-semibold text-gray-900 dark:text-gray-100">{"Bookmarks"}</h2>
+                    <div class="text-sm text-gray-600 dark:text-gray-300">
+                        {format!("{} pinned message{} across all sessions", bookmarks.len(), if bookmarks.len() == 1 { "" } else { "s" })}
+                    </div>
+                </div>
+                <button
+                    onclick={on_close}
+                    class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                    aria-label="Close bookmarks"
+                    title="Back to chat"
+                >
+                    <i class="fas fa-times" aria-hidden="true"></i>
+                </button>
+            </header>
+
+            <div class="flex-1 overflow-y-auto custom-scrollbar p-4">
+                {if bookmarks.is_empty() {
+                    html! {
+                        <p class="text-center text-sm text-gray-600 dark:text-gray-300 mt-8">
+                            {"No bookmarks yet. Pin a message with its "}
+                            <i class="far fa-bookmark" aria-hidden="true"></i>
+                            {" button to find it here."}
+                        </p>
+                    }
+                } else {
+                    html! {
+                        <ul class="space-y-2 max-w-3xl mx-auto" aria-label="Pinned messages">
+                            {for bookmarks.iter().map(render_bookmark)}
+                        </ul>
+                    }
+                }}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,257 @@
+use crate::llm_playground::chart::{self, ChartSpec, ChartType};
+use yew::prelude::*;
+
+/// Series colors, in order
+const PALETTE: [&str; 8] = [
+    "#3b82f6", "#f97316", "#10b981", "#ef4444", "#8b5cf6", "#eab308", "#ec4899", "#14b8a6",
+];
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 320.0;
+const LEFT: f64 = 56.0;
+const RIGHT: f64 = 16.0;
+const TOP: f64 = 16.0;
+const BOTTOM: f64 = 44.0;
+/// Category labels drawn under the axis; the rest are left to the tooltips
+const MAX_AXIS_LABELS: usize = 12;
+
+fn color(index: usize) -> &'static str {
+    PALETTE[index % PALETTE.len()]
+}
+
+fn short_label(label: &str) -> String {
+    if label.chars().count() > 14 {
+        format!("{}…", label.chars().take(13).collect::<String>())
+    } else {
+        label.to_string()
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ChartViewProps {
+    pub spec: ChartSpec,
+}
+
+/// A render_chart call's chart, drawn as SVG with a legend and a tooltip per value
+#[function_component(ChartView)]
+pub fn chart_view(props: &ChartViewProps) -> Html {
+    let spec = &props.spec;
+    let chart = if spec.chart_type == ChartType::Pie {
+        render_pie(spec)
+    } else {
+        render_axes(spec)
+    };
+    let legend: Vec<(String, &'static str)> = if spec.chart_type == ChartType::Pie {
+        let total: f64 = spec.series[0].data.iter().flatten().sum();
+        spec.labels
+            .iter()
+            .zip(&spec.series[0].data)
+            .enumerate()
+            .filter_map(|(index, (label, value))| {
+                let value = (*value)?;
+                let share = if total > 0.0 { value / total * 100.0 } else { 0.0 };
+                Some((format!("{} · {:.1}%", label, share), color(index)))
+            })
+            .collect()
+    } else if spec.series.len() > 1 {
+        spec.series
+            .iter()
+            .enumerate()
+            .map(|(index, series)| {
+                let name = if series.name.is_empty() { format!("Series {}", index + 1) } else { series.name.clone() };
+                (name, color(index))
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let label = if spec.title.is_empty() { "Chart".to_string() } else { spec.title.clone() };
+
+    html! {
+        <figure class="mb-3 rounded-md border border-gray-200 dark:border-gray-600 bg-white dark:bg-gray-800 p-3">
+            {if spec.title.is_empty() {
+                html! {}
+            } else {
+                html! {
+                    <figcaption class="mb-2 text-sm font-medium text-gray-800 dark:text-gray-200">{&spec.title}</figcaption>
+                }
+            }}
+            <svg
+                viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)}
+                class="w-full h-auto text-gray-500 dark:text-gray-400"
+                role="img"
+                aria-label={label}
+            >
+                {chart}
+            </svg>
+            {if legend.is_empty() {
+                html! {}
+            } else {
+                html! {
+                    <ul class="mt-2 flex flex-wrap gap-x-4 gap-y-1 text-xs text-gray-700 dark:text-gray-300">
+                        {for legend.into_iter().map(|(name, color)| html! {
+                            <li class="flex items-center gap-1">
+                                <span class="inline-block w-3 h-3 rounded-sm" style={format!("background-color: {}", color)}></span>
+                                {name}
+                            </li>
+                        })}
+                    </ul>
+                }
+            }}
+        </figure>
+    }
+}
+
+/// Bar, line and area charts: a value axis on the left and a category per label
+fn render_axes(spec: &ChartSpec) -> Html {
+    let (min, max) = spec.value_range();
+    let ticks = chart::nice_ticks(min, max, 5);
+    let low = ticks.first().copied().unwrap_or(min).min(min);
+    let high = ticks.last().copied().unwrap_or(max).max(max);
+    let bottom = HEIGHT - BOTTOM;
+    let plot_width = WIDTH - LEFT - RIGHT;
+    let y = |value: f64| bottom - (value - low) / (high - low) * (bottom - TOP);
+    let count = spec.labels.len().max(1);
+    let band = plot_width / count as f64;
+    let center = |index: usize| LEFT + band * (index as f64 + 0.5);
+    let label_every = count.div_ceil(MAX_AXIS_LABELS);
+    let baseline = y(0.0_f64.clamp(low, high));
+    let tooltip = |index: usize, series_name: &str, value: f64| {
+        let label = spec.labels.get(index).map(String::as_str).unwrap_or_default();
+        if series_name.is_empty() {
+            format!("{}: {}", label, value)
+        } else {
+            format!("{} · {}: {}", label, series_name, value)
+        }
+    };
+
+    let series = spec.series.iter().enumerate().map(|(series_index, series)| {
+        let color = color(series_index);
+        match spec.chart_type {
+            ChartType::Bar => {
+                let bar_width = band * 0.8 / spec.series.len() as f64;
+                html! {
+                    <g fill={color}>
+                        {for series.data.iter().enumerate().filter_map(|(index, value)| {
+                            let value = (*value)?;
+                            let x = LEFT + band * index as f64 + band * 0.1 + bar_width * series_index as f64;
+                            let top = y(value).min(baseline);
+                            let height = (y(value) - baseline).abs();
+                            Some(html! {
+                                <rect x={format!("{:.1}", x)} y={format!("{:.1}", top)}
+                                    width={format!("{:.1}", bar_width.max(1.0))} height={format!("{:.1}", height)}>
+                                    <title>{tooltip(index, &series.name, value)}</title>
+                                </rect>
+                            })
+                        })}
+                    </g>
+                }
+            }
+            _ => {
+                let points: Vec<Option<(f64, f64)>> = series
+                    .data
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| value.map(|value| (center(index), y(value))))
+                    .collect();
+                html! {
+                    <g>
+                        {if spec.chart_type == ChartType::Area {
+                            html! { <path d={chart::area_path(&points, baseline)} fill={color} fill-opacity="0.2" /> }
+                        } else {
+                            html! {}
+                        }}
+                        <path d={chart::line_path(&points)} fill="none" stroke={color} stroke-width="2" stroke-linejoin="round" />
+                        {if count <= 60 {
+                            html! {
+                                {for series.data.iter().enumerate().filter_map(|(index, value)| {
+                                    let value = (*value)?;
+                                    Some(html! {
+                                        <circle cx={format!("{:.1}", center(index))} cy={format!("{:.1}", y(value))} r="3" fill={color}>
+                                            <title>{tooltip(index, &series.name, value)}</title>
+                                        </circle>
+                                    })
+                                })}
+                            }
+                        } else {
+                            html! {}
+                        }}
+                    </g>
+                }
+            }
+        }
+    });
+
+    html! {
+        <>
+            <g font-size="11" fill="currentColor">
+                {for ticks.iter().map(|tick| html! {
+                    <g>
+                        <line x1={LEFT.to_string()} x2={(WIDTH - RIGHT).to_string()} y1={format!("{:.1}", y(*tick))} y2={format!("{:.1}", y(*tick))}
+                            stroke="currentColor" stroke-opacity="0.2" />
+                        <text x={(LEFT - 6.0).to_string()} y={format!("{:.1}", y(*tick) + 4.0)} text-anchor="end">
+                            {chart::format_value(*tick)}
+                        </text>
+                    </g>
+                })}
+                {for spec.labels.iter().enumerate().filter(|(index, _)| index % label_every == 0).map(|(index, label)| html! {
+                    <text x={format!("{:.1}", center(index))} y={(bottom + 16.0).to_string()} text-anchor="middle">
+                        {short_label(label)}
+                    </text>
+                })}
+                {if spec.x_label.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <text x={(LEFT + plot_width / 2.0).to_string()} y={(HEIGHT - 4.0).to_string()} text-anchor="middle">
+                            {&spec.x_label}
+                        </text>
+                    }
+                }}
+                {if spec.y_label.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <text transform={format!("translate(12 {:.1}) rotate(-90)", TOP + (bottom - TOP) / 2.0)} text-anchor="middle">
+                            {&spec.y_label}
+                        </text>
+                    }
+                }}
+            </g>
+            {for series}
+            <line x1={LEFT.to_string()} x2={(WIDTH - RIGHT).to_string()} y1={format!("{:.1}", baseline)} y2={format!("{:.1}", baseline)}
+                stroke="currentColor" stroke-opacity="0.6" />
+        </>
+    }
+}
+
+/// A pie of the single series, a slice per label
+fn render_pie(spec: &ChartSpec) -> Html {
+    let values = &spec.series[0].data;
+    let total: f64 = values.iter().flatten().sum();
+    if total <= 0.0 {
+        return html! {
+            <text x={(WIDTH / 2.0).to_string()} y={(HEIGHT / 2.0).to_string()} text-anchor="middle" fill="currentColor">
+                {"Every value is zero"}
+            </text>
+        };
+    }
+    let radius = HEIGHT / 2.0 - TOP;
+    let mut start = 0.0;
+    html! {
+        <g stroke="white" stroke-width="1">
+            {for values.iter().enumerate().filter_map(|(index, value)| {
+                let value = (*value).filter(|value| *value > 0.0)?;
+                let end = start + value / total;
+                let path = chart::pie_slice_path(WIDTH / 2.0, HEIGHT / 2.0, radius, start, end);
+                start = end;
+                let label = spec.labels.get(index).map(String::as_str).unwrap_or_default();
+                Some(html! {
+                    <path d={path} fill={color(index)}>
+                        <title>{format!("{}: {} ({:.1}%)", label, value, value / total * 100.0)}</title>
+                    </path>
+                })
+            })}
+        </g>
+    }
+}
@@ -1,12 +1,40 @@
-use crate::llm_playground::{ApiConfig, ApiProvider, ChatSession};
+use crate::llm_playground::api_clients::gemini_cache;
+use super::SnapshotMenu;
+use crate::llm_playground::session_snapshots::SessionSnapshot;
+use crate::llm_playground::personas::Persona;
+use crate::llm_playground::response_length::LengthPreset;
+use crate::llm_playground::{app_state::use_app_state, event_bus::PlaygroundEvent, hooks::use_event_bus};
+use crate::llm_playground::{session_pages, session_recording, ChatSession, SessionStats};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct ChatHeaderProps {
     pub current_session: Option<ChatSession>,
-    pub api_config: ApiConfig,
     pub on_toggle_dark_mode: Callback<()>,
     pub dark_mode: bool,
+    /// Download the session as a standalone HTML page
+    #[prop_or_default]
+    pub on_share_html: Callback<()>,
+    /// Copy a link that opens the session in the read-only viewer
+    pub on_share_link: Callback<()>,
+    /// Open the replay of the session's recording
+    #[prop_or_default]
+    pub on_replay: Callback<()>,
+    /// Open the timeline of the session's tool calls
+    #[prop_or_default]
+    pub on_trace: Callback<()>,
+    /// Open the session's SQLite database
+    #[prop_or_default]
+    pub on_database: Callback<()>,
+    /// Lock or unlock the session against changes
+    #[prop_or_default]
+    pub on_toggle_lock: Callback<()>,
+    /// Put an earlier snapshot of the session back
+    #[prop_or_default]
+    pub on_restore_snapshot: Callback<SessionSnapshot>,
+    /// The persona the session was started from
+    #[prop_or_default]
+    pub persona: Option<Persona>,
 }
 
 #[function_component(ChatHeader)]
@@ -18,15 +46,120 @@ pub fn chat_header(props: &ChatHeaderProps) -> Html {
         })
     };
 
+    // The length preset is part of the config, changed through the bus
+    let app_state = use_app_state();
+    let on_config_change = use_event_bus().callback(|config| PlaygroundEvent::ConfigChanged(Box::new(config)));
+    let length_preset = app_state.config.response_length.preset;
+    let on_length_change = {
+        let config = app_state.config.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlInputElement = e.target_unchecked_into();
+            if let Some(preset) = LengthPreset::parse(&select.value()) {
+                let mut config = config.clone();
+                config.response_length.preset = preset;
+                on_config_change.emit(config);
+            }
+        })
+    };
+
+    let show_stats = use_state(|| false);
+    let on_toggle_stats = {
+        let show_stats = show_stats.clone();
+        Callback::from(move |_| {
+            show_stats.set(!*show_stats);
+        })
+    };
+    // Over the whole history, older pages included, and only while shown
+    let stats = props
+        .current_session
+        .as_ref()
+        .filter(|_| *show_stats)
+        .and_then(|s| session_pages::hydrated(s).stats());
+
+    let show_snapshots = use_state(|| false);
+    let on_toggle_snapshots = {
+        let show_snapshots = show_snapshots.clone();
+        Callback::from(move |_| {
+            show_snapshots.set(!*show_snapshots);
+        })
+    };
+    let on_restore_snapshot = {
+        let callback = props.on_restore_snapshot.clone();
+        let show_snapshots = show_snapshots.clone();
+        Callback::from(move |snapshot: SessionSnapshot| {
+            show_snapshots.set(false);
+            callback.emit(snapshot);
+        })
+    };
+
+    let on_share_html = {
+        let callback = props.on_share_html.clone();
+        Callback::from(move |_| {
+            callback.emit(());
+        })
+    };
+    let on_share_link = props.on_share_link.reform(|_: MouseEvent| ());
+
+    // (recording on, recorded steps) for the current session
+    let recording = use_state(|| (false, 0usize));
+    {
+        let recording = recording.clone();
+        let session = props
+            .current_session
+            .as_ref()
+            .map(|s| (s.id.clone(), s.messages.len()));
+        use_effect_with(session, move |session| {
+            let state = session
+                .as_ref()
+                .and_then(|(id, _)| session_recording::load_recording(id))
+                .map(|r| (r.active, r.steps.len()))
+                .unwrap_or_default();
+            recording.set(state);
+            || ()
+        });
+    }
+    let (is_recording, recorded_steps) = *recording;
+    let on_toggle_recording = {
+        let recording = recording.clone();
+        let session_id = props.current_session.as_ref().map(|s| s.id.clone());
+        Callback::from(move |_| {
+            if let Some(id) = &session_id {
+                let (active, steps) = *recording;
+                session_recording::set_recording(id, !active);
+                recording.set((!active, steps));
+            }
+        })
+    };
+    let on_replay = {
+        let callback = props.on_replay.clone();
+        Callback::from(move |_| {
+            callback.emit(());
+        })
+    };
+    let on_trace = {
+        let callback = props.on_trace.clone();
+        Callback::from(move |_| {
+            callback.emit(());
+        })
+    };
+    let on_database = props.on_database.reform(|_: MouseEvent| ());
+    let has_tool_calls = props
+        .current_session
+        .as_ref()
+        .is_some_and(|s| s.messages.iter().any(|m| m.function_call.is_some()));
+
+    let locked = props.current_session.as_ref().is_some_and(|s| s.locked);
+    // Snapshots and recordings are kept in storage, so incognito sessions get neither
+    let incognito = props.current_session.as_ref().is_some_and(|s| s.incognito);
+    let on_toggle_lock = {
+        let callback = props.on_toggle_lock.clone();
+        Callback::from(move |_| {
+            callback.emit(());
+        })
+    };
+
     let (session_title, model_info) = if let Some(session) = &props.current_session {
-        let model = match props.api_config.current_provider {
-            ApiProvider::Gemini => &props.api_config.gemini.model,
-            ApiProvider::OpenAI => &props.api_config.openai.model,
-        };
-        let provider = match props.api_config.current_provider {
-            ApiProvider::Gemini => "Gemini",
-            ApiProvider::OpenAI => "OpenAI",
-        };
+        let (provider, model) = app_state.config.get_current_provider_and_model();
         (
             session.title.clone(),
             format!("Using {} {}", provider, model),
@@ -39,27 +172,297 @@ pub fn chat_header(props: &ChatHeaderProps) -> Html {
     };
 
     html! {
-        <div class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
+        <header class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
             <div>
-                <h2 class="font-semibold text-gray-900 dark:text-gray-100">{session_title}</h2>
-                <div class="text-sm text-gray-600 dark:text-gray-300">{model_info}</div>
+                <h2 class="font-semibold text-gray-900 dark:text-gray-100">
+                    {session_title}
+                    {if locked {
+                        html! { <i class="fas fa-lock ml-2 text-sm text-gray-500 dark:text-gray-400" aria-label="Locked" title="Locked"></i> }
+                    } else {
+                        html! {}
+                    }}
+                    {if incognito {
+                        html! {
+                            <span
+                                class="ml-2 px-2 py-0.5 text-xs font-normal rounded-full bg-gray-800 text-white dark:bg-gray-200 dark:text-gray-900"
+                                title="Not saved; gone when the page is closed or reloaded"
+                            >
+                                <i class="fas fa-user-secret mr-1" aria-hidden="true"></i>
+                                {"Incognito"}
+                            </span>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                </h2>
+                <div class="text-sm text-gray-600 dark:text-gray-300 flex items-center gap-2">
+                    {if let Some(persona) = &props.persona {
+                        html! {
+                            <span
+                                class="px-2 py-0.5 text-xs rounded-full bg-primary-100 dark:bg-primary-900/30 text-primary-700 dark:text-primary-300"
+                                title="Persona"
+                            >
+                                {persona.label()}
+                            </span>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    <span>{model_info}</span>
+                </div>
             </div>
-            <div class="flex space-x-2">
+            <div class="flex space-x-2 relative">
+                {if props.current_session.is_some() {
+                    html! {
+                        <select
+                            onchange={on_length_change}
+                            class={classes!(
+                                "px-2", "py-1", "text-sm", "rounded-md", "border", "border-gray-300", "dark:border-gray-600", "bg-white", "dark:bg-gray-700",
+                                if length_preset == LengthPreset::Default { "text-gray-600 dark:text-gray-300" } else { "text-primary-700 dark:text-primary-300" }
+                            )}
+                            title="Response length"
+                            aria-label="Response length"
+                        >
+                            {for LengthPreset::ALL.iter().map(|preset| html! {
+                                <option value={preset.label()} selected={*preset == length_preset}>
+                                    {if *preset == LengthPreset::Default { "Any length" } else { preset.label() }}
+                                </option>
+                            })}
+                        </select>
+                    }
+                } else {
+                    html! {}
+                }}
+                {if props.current_session.is_some() {
+                    html! {
+                        <button
+                            onclick={on_toggle_stats}
+                            class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                            title="Session stats"
+                            aria-label="Session stats"
+                            aria-expanded={show_stats.to_string()}
+                        >
+                            <i class="fas fa-chart-simple" aria-hidden="true"></i>
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }}
+                {if *show_stats {
+                    render_stats(stats.as_ref())
+                } else {
+                    html! {}
+                }}
+                {if props.current_session.is_some() && !incognito {
+                    html! {
+                        <button
+                            onclick={on_toggle_snapshots}
+                            class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                            title="Snapshots"
+                            aria-label="Snapshots"
+                            aria-expanded={show_snapshots.to_string()}
+                        >
+                            <i class="fas fa-clock-rotate-left" aria-hidden="true"></i>
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }}
+                {match (&props.current_session, *show_snapshots) {
+                    (Some(session), true) => html! {
+                        <SnapshotMenu session={session.clone()} on_restore={on_restore_snapshot} />
+                    },
+                    _ => html! {},
+                }}
+                {if props.current_session.is_some() && !incognito {
+                    html! {
+                        <button
+                            onclick={on_toggle_recording}
+                            class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                            title={if is_recording { "Stop recording" } else { "Record requests and tool calls" }}
+                            aria-label="Record session"
+                            aria-pressed={is_recording.to_string()}
+                        >
+                            <i class={classes!("fas", "fa-circle", is_recording.then_some("text-red-500"))} aria-hidden="true"></i>
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }}
+                {if props.current_session.is_some() && recorded_steps > 0 {
+                    html! {
+                        <button
+                            onclick={on_replay}
+                            class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                            title="Replay recording"
+                            aria-label="Replay recording"
+                        >
+                            <i class="fas fa-film" aria-hidden="true"></i>
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }}
+                {if has_tool_calls {
+                    html! {
+                        <button
+                            onclick={on_trace}
+                            class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                            title="Tool call trace"
+                            aria-label="Tool call trace"
+                        >
+                            <i class="fas fa-timeline" aria-hidden="true"></i>
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }}
+                {if props.current_session.is_some() {
+                    html! {
+                        <button
+                            onclick={on_database}
+                            class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                            title="Session database"
+                            aria-label="Session database"
+                        >
+                            <i class="fas fa-database" aria-hidden="true"></i>
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }}
+                {if props.current_session.is_some() {
+                    html! {
+                        <button
+                            onclick={on_toggle_lock}
+                            class={classes!(
+                                "p-2", "rounded-md", "hover:bg-gray-100", "dark:hover:bg-gray-700",
+                                if locked { "text-yellow-600 dark:text-yellow-400" } else { "text-gray-600 dark:text-gray-300" }
+                            )}
+                            title={if locked { "Unlock session" } else { "Lock session (read-only)" }}
+                            aria-label="Lock session"
+                            aria-pressed={locked.to_string()}
+                        >
+                            <i class={if locked { "fas fa-lock" } else { "fas fa-lock-open" }} aria-hidden="true"></i>
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }}
+                {if props.current_session.is_some() {
+                    html! {
+                        <>
+                            <button
+                                onclick={on_share_html}
+                                class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                                title="Share as HTML"
+                                aria-label="Share as HTML"
+                            >
+                                <i class="fas fa-share-nodes" aria-hidden="true"></i>
+                            </button>
+                            <button
+                                onclick={on_share_link}
+                                class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                                title="Copy read-only viewer link"
+                                aria-label="Copy read-only viewer link"
+                            >
+                                <i class="fas fa-link" aria-hidden="true"></i>
+                            </button>
+                        </>
+                    }
+                } else {
+                    html! {}
+                }}
                 <button
                     onclick={on_dark_mode_toggle}
                     class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
                     title="Toggle dark mode"
+                    aria-label={if props.dark_mode { "Switch to light mode" } else { "Switch to dark mode" }}
+                    aria-pressed={props.dark_mode.to_string()}
                 >
                     {if props.dark_mode {
-                        html! { <i class="fas fa-sun"></i> }
+                        html! { <i class="fas fa-sun" aria-hidden="true"></i> }
                     } else {
-                        html! { <i class="fas fa-moon"></i> }
+                        html! { <i class="fas fa-moon" aria-hidden="true"></i> }
                     }}
                 </button>
-                <button class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300">
-                    <i class="fas fa-ellipsis-v"></i>
+                <button
+                    class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                    aria-label="More options"
+                >
+                    <i class="fas fa-ellipsis-v" aria-hidden="true"></i>
                 </button>
             </div>
+        </header>
+    }
+}
+
+fn render_stats(stats: Option<&SessionStats>) -> Html {
+    let mut rows = match stats {
+        Some(stats) => vec![
+            ("Requests", stats.requests.to_string()),
+            (
+                "Avg time to headers",
+                stats
+                    .avg_headers_ms
+                    .map(|ms| format!("{:.2}s", ms / 1000.0))
+                    .unwrap_or_else(|| "—".to_string()),
+            ),
+            ("Avg latency", format!("{:.2}s", stats.avg_latency_ms / 1000.0)),
+            (
+                "Avg end-to-end throughput",
+                stats
+                    .avg_end_to_end_tokens_per_second
+                    .map(|tps| format!("{:.1} tok/s", tps))
+                    .unwrap_or_else(|| "—".to_string()),
+            ),
+            ("Output tokens", stats.total_output_tokens.to_string()),
+        ],
+        None => vec![],
+    };
+    if let Some(stats) = stats {
+        if let Some(tps) = stats.avg_generation_tokens_per_second {
+            rows.push(("Avg generation speed", format!("{:.1} tok/s", tps)));
+        }
+        if let Some(ms) = stats.avg_queue_ms {
+            rows.push(("Avg queue time", format!("{:.0}ms", ms)));
+        }
+        if let Some(cost) = stats.total_cost {
+            rows.push(("Estimated cost", format!("{:.4}", cost)));
+        }
+    }
+    if let Some(stats) = stats.filter(|stats| stats.total_cached_tokens > 0) {
+        rows.push(("Cached input", stats.total_cached_tokens.to_string()));
+        if let Some(share) =
+            gemini_cache::saved_input_share(stats.total_input_tokens, stats.total_cached_tokens)
+        {
+            rows.push(("Input cost saved", format!("≈{:.0}%", share * 100.0)));
+        }
+    }
+
+    html! {
+        <div
+            class="absolute right-0 top-full mt-2 w-64 z-20 p-3 bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-600 rounded-lg shadow-lg text-sm"
+            role="region"
+            aria-label="Session stats"
+        >
+            <div class="font-semibold text-gray-900 dark:text-gray-100 mb-2">{"Session Stats"}</div>
+            {if rows.is_empty() {
+                html! {
+                    <div class="text-gray-500 dark:text-gray-400">{"No timed responses in this session yet."}</div>
+                }
+            } else {
+                html! {
+                    <dl class="space-y-1">
+                        {for rows.into_iter().map(|(label, value)| html! {
+                            <div class="flex justify-between">
+                                <dt class="text-gray-600 dark:text-gray-300">{label}</dt>
+                                <dd class="font-mono text-gray-900 dark:text-gray-100">{value}</dd>
+                            </div>
+                        })}
+                    </dl>
+                }
+            }}
         </div>
     }
 }
@@ -1,52 +1,358 @@
-use super::message_bubble::MessageBubble;
-use crate::llm_playground::ChatSession;
+use super::{message_bubble::MessageBubble, DiffViewer};
+use crate::llm_playground::{
+    hooks::use_latest_callback,
+    artifacts::Artifact, bookmarks, dialogue, session_pages, ChatSession, Message, MessageRole, ResponseMetrics,
+};
+use gloo_timers::callback::Interval;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use wasm_bindgen::JsCast;
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct ChatRoomProps {
     pub session: Option<ChatSession>,
     pub is_loading: bool,
+    /// Open a large code block or long reply in the artifact panel
+    #[prop_or_default]
+    pub on_open_artifact: Option<Callback<Artifact>>,
+    /// Show the request behind a message, by message id
+    #[prop_or_default]
+    pub on_export_request: Option<Callback<String>>,
+    /// Ask the model to resume the last reply when it hit the max tokens limit
+    #[prop_or_default]
+    pub on_continue: Option<Callback<()>>,
+    /// Pin or unpin a message as a bookmark, by message id
+    #[prop_or_default]
+    pub on_toggle_pin: Option<Callback<String>>,
+    /// Message to scroll to and focus instead of the end of the conversation
+    #[prop_or_default]
+    pub focus_message: Option<String>,
+    /// Show time, model, tokens and latency under each message
+    #[prop_or_default]
+    pub show_message_footer: bool,
+    /// Show reasoning sections unless toggled off on the message
+    #[prop_or_default]
+    pub show_reasoning: bool,
+    /// Message whose send failed and waits in the retry queue
+    #[prop_or_default]
+    pub retry_pending_for: Option<String>,
+    /// Questions suggested under the latest reply
+    #[prop_or_default]
+    pub follow_ups: Vec<String>,
+    /// Send a suggested question
+    #[prop_or_default]
+    pub on_follow_up: Option<Callback<String>>,
+    /// Show another sampled candidate as a reply, by message id and index
+    #[prop_or_default]
+    pub on_select_candidate: Option<Callback<(String, usize)>>,
+    /// Load the page of messages before those loaded; see `session_pages`
+    #[prop_or_default]
+    pub on_load_older: Callback<()>,
 }
 
 #[function_component(ChatRoom)]
 pub fn chat_room(props: &ChatRoomProps) -> Html {
     let messages_container_ref = use_node_ref();
 
-    // Auto-scroll to bottom when new messages arrive
+    // Clock for the footers' relative times. Bubbles only re-render when their
+    // props change, so "just now" moves on when this ticks.
+    let now = use_state(js_sys::Date::now);
+    {
+        let now = now.clone();
+        use_effect_with(props.show_message_footer, move |show_footer| {
+            now.set(js_sys::Date::now());
+            let interval = show_footer.then(|| Interval::new(FOOTER_TICK_MS, move || now.set(js_sys::Date::now())));
+            move || drop(interval)
+        });
+    }
+
+    // Follow new messages only while the user is at the bottom; otherwise count
+    // them for the jump button. Switching sessions restores where the user was.
+    let at_bottom = use_mut_ref(|| true);
+    let scrolled_up = use_state_eq(|| false);
+    let unseen = use_state_eq(|| 0usize);
+    let last_rendered = use_mut_ref(|| (Option::<String>::None, 0usize));
+    // Scroll height and position when older messages were asked for, to keep
+    // the view in place once they are added above
+    let prepend_anchor = use_mut_ref(|| Option::<(i32, i32)>::None);
+    let session_id = props.session.as_ref().map(|s| s.id.clone());
+    let has_older = props.session.as_ref().is_some_and(|s| s.older_pages > 0);
     {
         let messages_container_ref = messages_container_ref.clone();
-        let messages_len = props
-            .session
-            .as_ref()
-            .map(|s| s.messages.len())
-            .unwrap_or(0);
+        let at_bottom = at_bottom.clone();
+        let prepend_anchor = prepend_anchor.clone();
+        let scrolled_up = scrolled_up.clone();
+        let unseen = unseen.clone();
+        let messages = props.session.as_ref().map(|s| s.messages.as_slice()).unwrap_or_default();
+        // The last message's length and the typing indicator change the height
+        // without adding a message, and are followed too
+        let last_len = messages.last().map(|m| m.content.len()).unwrap_or_default();
 
-        use_effect_with(messages_len, move |_| {
+        use_effect_with((session_id.clone(), messages.len(), last_len, props.is_loading), move |(session_id, len, _, _)| {
+            if let Some(container) = messages_container_ref.cast::<web_sys::Element>() {
+                let (previous_session, previous_len) = last_rendered.borrow().clone();
+                let anchor = prepend_anchor.borrow_mut().take();
+                let session_changed = previous_session != *session_id;
+                let at_bottom_now = *at_bottom.borrow();
+                match scroll_update(session_changed, anchor.is_some(), at_bottom_now, previous_len, *len) {
+                    ScrollUpdate::KeepAnchor => {
+                        if let Some((height, top)) = anchor {
+                            container.set_scroll_top(top + container.scroll_height() - height);
+                        }
+                    }
+                    ScrollUpdate::Restore => {
+                        let saved = session_id
+                            .as_ref()
+                            .and_then(|id| SCROLL_POSITIONS.with(|p| p.borrow().get(id).copied()));
+                        container.set_scroll_top(saved.unwrap_or_else(|| container.scroll_height()));
+                        let bottom = is_at_bottom(&container);
+                        *at_bottom.borrow_mut() = bottom;
+                        scrolled_up.set(!bottom);
+                        unseen.set(0);
+                    }
+                    ScrollUpdate::Follow => container.set_scroll_top(container.scroll_height()),
+                    ScrollUpdate::CountUnseen(added) => unseen.set(*unseen + added),
+                    ScrollUpdate::Stay => {}
+                }
+                *last_rendered.borrow_mut() = (session_id.clone(), *len);
+            }
+            || ()
+        });
+    }
+
+    let load_older = {
+        let messages_container_ref = messages_container_ref.clone();
+        let prepend_anchor = prepend_anchor.clone();
+        let on_load_older = props.on_load_older.clone();
+        Callback::from(move |_: ()| {
+            if prepend_anchor.borrow().is_some() {
+                return;
+            }
+            if let Some(container) = messages_container_ref.cast::<web_sys::Element>() {
+                *prepend_anchor.borrow_mut() = Some((container.scroll_height(), container.scroll_top()));
+            }
+            on_load_older.emit(());
+        })
+    };
+
+    let on_scroll = {
+        let messages_container_ref = messages_container_ref.clone();
+        let session_id = session_id.clone();
+        let scrolled_up = scrolled_up.clone();
+        let unseen = unseen.clone();
+        let load_older = load_older.clone();
+        Callback::from(move |_: Event| {
+            let Some(container) = messages_container_ref.cast::<web_sys::Element>() else {
+                return;
+            };
+            if has_older && container.scroll_top() < LOAD_OLDER_THRESHOLD_PX {
+                load_older.emit(());
+            }
+            if let Some(id) = &session_id {
+                SCROLL_POSITIONS.with(|p| p.borrow_mut().insert(id.clone(), container.scroll_top()));
+            }
+            let bottom = is_at_bottom(&container);
+            *at_bottom.borrow_mut() = bottom;
+            scrolled_up.set(!bottom);
+            if bottom {
+                unseen.set(0);
+            }
+        })
+    };
+
+    let jump_to_bottom = {
+        let messages_container_ref = messages_container_ref.clone();
+        Callback::from(move |_: MouseEvent| {
             if let Some(container) = messages_container_ref.cast::<web_sys::Element>() {
+                // The scroll event that follows clears the count
                 container.set_scroll_top(container.scroll_height());
             }
+        })
+    };
+
+    // Bring a bookmarked message into view; runs after the scroll to the bottom
+    {
+        let session_id = props.session.as_ref().map(|s| s.id.clone());
+        use_effect_with((props.focus_message.clone(), session_id), move |(focus_message, _)| {
+            if let Some(message_id) = focus_message {
+                if let Some(element) = web_sys::window()
+                    .and_then(|w| w.document())
+                    .and_then(|d| d.get_element_by_id(&bookmarks::message_element_id(message_id)))
+                    .and_then(|e| e.dyn_into::<web_sys::HtmlElement>().ok())
+                {
+                    let _ = element.focus();
+                }
+            }
+            || ()
+        });
+    }
+
+    // Screen-reader announcement when a response starts and finishes
+    let announcement = use_state(String::new);
+    let was_loading = use_mut_ref(|| false);
+    {
+        let announcement = announcement.clone();
+        let last_reply = props
+            .session
+            .as_ref()
+            .and_then(|s| s.messages.last())
+            .filter(|m| m.role == MessageRole::Assistant)
+            .map(|m| m.content.clone());
+        use_effect_with(props.is_loading, move |is_loading| {
+            let previously_loading = *was_loading.borrow();
+            if *is_loading && !previously_loading {
+                announcement.set("Assistant is responding".to_string());
+            } else if !*is_loading && previously_loading {
+                announcement.set(match last_reply {
+                    Some(content) => {
+                        let preview: String = content.chars().take(200).collect();
+                        format!("Assistant response complete: {}", preview)
+                    }
+                    None => "Assistant response complete".to_string(),
+                });
+            }
+            *was_loading.borrow_mut() = *is_loading;
             || ()
         });
     }
 
+    // Up to two assistant message ids picked for the diff viewer
+    let diff_selection = use_state(Vec::<String>::new);
+    {
+        let diff_selection = diff_selection.clone();
+        let session_id = props.session.as_ref().map(|s| s.id.clone());
+        use_effect_with(session_id, move |_| {
+            diff_selection.set(Vec::new());
+            || ()
+        });
+    }
+
+    let on_diff_select = {
+        let diff_selection = diff_selection.clone();
+        Callback::from(move |id: String| {
+            let mut next = (*diff_selection).clone();
+            if let Some(pos) = next.iter().position(|selected| *selected == id) {
+                next.remove(pos);
+            } else if next.len() < 2 {
+                next.push(id);
+            }
+            diff_selection.set(next);
+        })
+    };
+
+    // Bubbles of messages that didn't change are only re-rendered when their
+    // props differ, so they get callbacks that stay the same between renders
+    let on_diff_select = use_latest_callback(Some(on_diff_select));
+    let on_open_artifact = use_latest_callback(props.on_open_artifact.clone());
+    let on_export_request = use_latest_callback(props.on_export_request.clone());
+    let on_continue = use_latest_callback(props.on_continue.clone());
+    let on_toggle_pin = use_latest_callback(props.on_toggle_pin.clone());
+    let on_select_candidate = use_latest_callback(props.on_select_candidate.clone());
+
+    let on_diff_clear = {
+        let diff_selection = diff_selection.clone();
+        Callback::from(move |_: ()| diff_selection.set(Vec::new()))
+    };
+
+    // Order the pair by position in the conversation so the earlier reply is the original
+    let diff_pair = props.session.as_ref().and_then(|session| {
+        if diff_selection.len() != 2 {
+            return None;
+        }
+        let mut picked = session
+            .messages
+            .iter()
+            .filter(|m| diff_selection.contains(&m.id));
+        Some((picked.next()?.clone(), picked.next()?.clone()))
+    });
+
     html! {
-        <div class="flex-1 overflow-hidden flex flex-col">
+        <div class="relative flex-1 overflow-hidden flex flex-col">
+            {if let Some((original, revised)) = diff_pair {
+                html! { <DiffViewer {original} {revised} on_close={on_diff_clear.clone()} /> }
+            } else {
+                html! {}
+            }}
+            {if diff_selection.len() == 1 {
+                let on_cancel = {
+                    let on_diff_clear = on_diff_clear.clone();
+                    Callback::from(move |_: MouseEvent| on_diff_clear.emit(()))
+                };
+                html! {
+                    <div class="flex items-center justify-between px-4 py-2 text-sm bg-primary-50 dark:bg-primary-900/30 text-primary-700 dark:text-primary-300 border-b border-primary-200 dark:border-primary-800" role="status">
+                        <span>{"Select another assistant response to compare."}</span>
+                        <button onclick={on_cancel} class="text-xs underline">{"Cancel"}</button>
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+            <div class="sr-only" role="status" aria-live="polite" aria-atomic="true">
+                {(*announcement).clone()}
+            </div>
             <div
                 ref={messages_container_ref}
+                onscroll={on_scroll}
                 class="chat-container overflow-y-auto p-4 space-y-6 custom-scrollbar"
                 style="height: calc(100vh - 140px);"
+                role="log"
+                aria-label="Conversation"
             >
                 {if let Some(session) = &props.session {
                     html! {
                         <>
-                            {for session.messages.iter().map(|message| {
+                            {if has_older {
+                                html! {
+                                    <div class="text-center">
+                                        <button
+                                            onclick={load_older.reform(|_: MouseEvent| ())}
+                                            class="text-xs text-gray-500 dark:text-gray-400 hover:text-gray-700 dark:hover:text-gray-200 underline"
+                                        >
+                                            {format!("Load older messages ({} more)", session.older_pages * session_pages::PAGE_SIZE)}
+                                        </button>
+                                    </div>
+                                }
+                            } else {
+                                html! {}
+                            }}
+                            {for session.messages.iter().zip(dialogue::message_speakers(session)).map(|(message, speaker)| {
+                                // Only the newest reply can be continued
+                                let is_last = session.messages.last().map(|m| &m.id) == Some(&message.id);
+                                let on_continue = on_continue.clone().filter(|_| is_last && !props.is_loading);
                                 html! {
                                     <MessageBubble
                                         key={message.id.clone()}
                                         message={message.clone()}
+                                        on_diff_select={on_diff_select.clone()}
+                                        diff_selected={diff_selection.contains(&message.id)}
+                                        {speaker}
+                                        on_open_artifact={on_open_artifact.clone()}
+                                        on_export_request={on_export_request.clone()}
+                                        {on_continue}
+                                        on_toggle_pin={on_toggle_pin.clone()}
+                                        on_select_candidate={on_select_candidate.clone()}
+                                        pinned={session.pinned_messages.contains(&message.id)}
+                                        show_footer={props.show_message_footer}
+                                        now={if props.show_message_footer { *now } else { 0.0 }}
+                                        show_reasoning={props.show_reasoning}
+                                        pending_retry={props.retry_pending_for.as_ref() == Some(&message.id)}
                                     />
                                 }
                             })}
+                            // Metrics strip for the newest assistant reply
+                            {match session.messages.iter().rev().find(|m| m.role == MessageRole::Assistant) {
+                                Some(Message { metrics: Some(metrics), .. }) if !props.is_loading => {
+                                    render_metrics_strip(metrics)
+                                }
+                                _ => html! {},
+                            }}
+                            {match &props.on_follow_up {
+                                Some(on_follow_up) if !props.is_loading => {
+                                    render_follow_ups(&props.follow_ups, on_follow_up)
+                                }
+                                _ => html! {},
+                            }}
                             {if props.is_loading {
                                 html! {
                                     <div class="flex">
@@ -79,6 +385,181 @@ pub fn chat_room(props: &ChatRoomProps) -> Html {
                     }
                 }}
             </div>
+            {if *scrolled_up {
+                html! {
+                    <button
+                        onclick={jump_to_bottom}
+                        class="absolute bottom-4 left-1/2 -translate-x-1/2 px-3 py-1.5 rounded-full shadow-md text-sm bg-primary-600 text-white hover:bg-primary-700 focus:outline-none focus:ring-2 focus:ring-primary-500"
+                        aria-label={if *unseen > 0 { format!("{}, jump to the latest", unseen_label(*unseen)) } else { "Jump to the latest message".to_string() }}
+                    >
+                        {if *unseen > 0 {
+                            html! { <>{format!("{} ", unseen_label(*unseen))}<i class="fas fa-arrow-down" aria-hidden="true"></i></> }
+                        } else {
+                            html! { <i class="fas fa-arrow-down" aria-hidden="true"></i> }
+                        }}
+                    </button>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
+
+/// Within this many pixels of the end counts as at the bottom
+const BOTTOM_THRESHOLD_PX: i32 = 48;
+/// Scrolling within this many pixels of the top loads older messages
+const LOAD_OLDER_THRESHOLD_PX: i32 = 200;
+/// How often the message footers' relative times are refreshed
+const FOOTER_TICK_MS: u32 = 60_000;
+
+thread_local! {
+    /// Scroll position of each session's conversation, kept for the page's life
+    static SCROLL_POSITIONS: RefCell<HashMap<String, i32>> = RefCell::new(HashMap::new());
+}
+
+fn is_at_bottom(container: &web_sys::Element) -> bool {
+    within_bottom(container.scroll_height(), container.scroll_top(), container.client_height())
+}
+
+fn within_bottom(scroll_height: i32, scroll_top: i32, client_height: i32) -> bool {
+    scroll_height - scroll_top - client_height <= BOTTOM_THRESHOLD_PX
+}
+
+/// What a render does to the conversation's scroll position
+#[derive(Debug, PartialEq)]
+enum ScrollUpdate {
+    /// Older messages were put above; keep the ones in view where they were
+    KeepAnchor,
+    /// Another session is shown; go back to where the user left it
+    Restore,
+    /// Stay at the bottom as the conversation grows
+    Follow,
+    /// The user scrolled up; count the new messages for the jump button
+    CountUnseen(usize),
+    Stay,
+}
+
+fn scroll_update(
+    session_changed: bool,
+    anchored: bool,
+    at_bottom: bool,
+    previous_len: usize,
+    len: usize,
+) -> ScrollUpdate {
+    if session_changed {
+        ScrollUpdate::Restore
+    } else if anchored {
+        ScrollUpdate::KeepAnchor
+    } else if at_bottom {
+        ScrollUpdate::Follow
+    } else if len > previous_len {
+        ScrollUpdate::CountUnseen(len - previous_len)
+    } else {
+        ScrollUpdate::Stay
+    }
+}
+
+fn unseen_label(unseen: usize) -> String {
+    format!("{} new message{}", unseen, if unseen == 1 { "" } else { "s" })
+}
+
+fn render_metrics_strip(metrics: &ResponseMetrics) -> Html {
+    let mut parts = Vec::new();
+    if let Some(headers_ms) = metrics.headers_ms {
+        parts.push(format!("headers {:.2}s", headers_ms / 1000.0));
+    }
+    parts.push(format!("total {:.2}s", metrics.latency_ms / 1000.0));
+    if let Some(queue_ms) = metrics.queue_ms {
+        parts.push(format!("queue {:.0}ms", queue_ms));
+    }
+    if let Some(tokens) = metrics.output_tokens {
+        parts.push(format!("{} tokens", tokens));
+    }
+    // Throughput leads the strip; the provider's own generation speed is shown
+    // next to it when reported, since it leaves out network and queueing
+    let throughput = metrics.end_to_end_tokens_per_second().map(|per_second| {
+        let generation = metrics
+            .generation_tokens_per_second()
+            .map(|per_second| format!(" (generation {:.0})", per_second))
+            .unwrap_or_default();
+        html! {
+            <span
+                class="px-1.5 py-0.5 rounded bg-primary-50 dark:bg-primary-900/30 font-mono font-semibold text-primary-700 dark:text-primary-300"
+                title="Output tokens per second over the whole request, network included"
+            >
+                {format!("{:.1} tok/s{}", per_second, generation)}
+            </span>
+        }
+    });
+
+    html! {
+        <div
+            class="-mt-4 flex items-center space-x-2 text-xs text-gray-500 dark:text-gray-400"
+            style="margin-left: 3.25rem;"
+            aria-label="Response metrics"
+        >
+            <i class="fas fa-stopwatch" aria-hidden="true"></i>
+            {throughput.unwrap_or_default()}
+            <span>{parts.join(" · ")}</span>
+        </div>
+    }
+}
+
+/// Suggested follow-up questions as chips under the latest reply
+fn render_follow_ups(follow_ups: &[String], on_follow_up: &Callback<String>) -> Html {
+    if follow_ups.is_empty() {
+        return html! {};
+    }
+
+    html! {
+        <div class="-mt-3 flex flex-wrap gap-2" style="margin-left: 3.25rem;" aria-label="Suggested follow-ups">
+            {for follow_ups.iter().map(|question| {
+                let on_click = {
+                    let on_follow_up = on_follow_up.clone();
+                    let question = question.clone();
+                    Callback::from(move |_: MouseEvent| on_follow_up.emit(question.clone()))
+                };
+                html! {
+                    <button
+                        key={question.clone()}
+                        onclick={on_click}
+                        class="px-3 py-1 text-sm text-left rounded-full border border-primary-200 dark:border-primary-800 bg-primary-50 dark:bg-primary-900/20 text-primary-700 dark:text-primary-300 hover:bg-primary-100 dark:hover:bg-primary-900/40"
+                        title="Send this question"
+                    >
+                        <i class="fas fa-reply mr-2 fa-flip-horizontal" aria-hidden="true"></i>
+                        {question}
+                    </button>
+                }
+            })}
         </div>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_bottom_has_some_slack() {
+        assert!(within_bottom(2000, 1400, 600));
+        assert!(within_bottom(2000, 1400 - BOTTOM_THRESHOLD_PX, 600));
+        assert!(!within_bottom(2000, 1300, 600));
+    }
+
+    #[test]
+    fn new_messages_are_followed_only_at_the_bottom() {
+        assert_eq!(scroll_update(false, false, true, 4, 5), ScrollUpdate::Follow);
+        assert_eq!(scroll_update(false, false, false, 4, 6), ScrollUpdate::CountUnseen(2));
+        // A streaming reply grows without adding a message
+        assert_eq!(scroll_update(false, false, false, 5, 5), ScrollUpdate::Stay);
+        assert_eq!(scroll_update(false, true, true, 5, 25), ScrollUpdate::KeepAnchor);
+        assert_eq!(scroll_update(true, true, false, 5, 3), ScrollUpdate::Restore);
+    }
+
+    #[test]
+    fn the_jump_button_counts_new_messages() {
+        assert_eq!(unseen_label(1), "1 new message");
+        assert_eq!(unseen_label(3), "3 new messages");
+    }
+}
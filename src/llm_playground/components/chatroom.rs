@@ -1,75 +1,172 @@
-use gloo_console::log;
-use gloo_timers::future::TimeoutFuture;
+use gloo_timers::callback::Timeout;
 use wasm_bindgen::JsCast;
 use yew::prelude::*;
 
 use crate::llm_playground::{
+    api_clients::ProviderRequest,
+    app_state::use_app_state,
+    artifacts::Artifact,
+    attachments::{self, Attachment},
+    bookmarks, browser_notifications,
+    budget::{self, BudgetUsage},
+    builtin_tools,
+    candidates,
+    context_usage, continuation, conversation_starters,
+    follow_ups,
+    dialogue,
     components::notification::{NotificationMessage, NotificationType},
-    flexible_client::FlexibleLLMClient,
-    mcp_client::McpClient,
-    ChatSession, FlexibleApiConfig, Message, MessageRole,
+    event_bus::PlaygroundEvent,
+    guardrails,
+    image_generation::{self, ImageEdit},
+    json_repair, logging, moderation, personas, prompt_optimizer, request_export,
+    hooks::use_event_bus,
+    redaction::{self, Finding},
+    request_overrides::RequestOverrides,
+    retry_queue::{self, PendingRetry},
+    self_consistency::{self, ConsensusMethod, ConsensusResult},
+    session_pages,
+    session_recording::{self, RecordedStep, RecordedToolCall},
+    session_store::SessionAction,
+    sub_agent,
+    tab_status::{self, TabStatus},
+    todo_list, tool_dependencies, tool_selection,
+    webhook::{self, WebhookEvent},
+    ChatSession, FlexibleApiConfig, Message, MessageRole, ResponseMetrics,
 };
 
-use super::{ChatRoom as ChatRoomDisplay, InputBar};
+use super::{
+    ArtifactPanel, ChatRoom as ChatRoomDisplay, ContextUsageBar, ImageStrip, InputBar, PromptSuggestion, RequestExportDialog,
+    StarterPrompts, TodoPanel,
+};
 
 #[derive(Properties, PartialEq)]
 pub struct ChatroomProps {
     /// Current session being displayed
     pub session: Option<ChatSession>,
-    /// API configuration for LLM calls
-    pub api_config: FlexibleApiConfig,
-    /// LLM client instance
-    pub llm_client: FlexibleLLMClient,
-    /// Optional MCP client for function tools
-    pub mcp_client: Option<McpClient>,
-    /// Callback when session is updated (for persistence)
-    pub on_session_update: Callback<ChatSession>,
-    /// Callback for notifications
-    pub on_notification: Callback<NotificationMessage>,
+    /// Message scrolled to and focused, e.g. when opened from a bookmark
+    #[prop_or_default]
+    pub focus_message: Option<String>,
+    /// Load the page of messages before those loaded
+    #[prop_or_default]
+    pub on_load_older: Callback<()>,
 }
 
 #[function_component(Chatroom)]
 pub fn chatroom(props: &ChatroomProps) -> Html {
+    let app_state = use_app_state();
+    // Requests go out with the session persona's prompt, tools and temperature
+    let session_config = personas::config_for_session(&app_state.config, props.session.as_ref());
+    // Session updates, notifications and config changes go out on the bus.
+    // Replies and tool results are sent as single-message actions rather than
+    // the whole session
+    let bus = use_event_bus();
+    let on_session_update = bus.callback(PlaygroundEvent::SessionUpdated);
+    let on_session_action = bus.callback(|action| PlaygroundEvent::SessionChanged(Box::new(action)));
+    let on_notification = bus.callback(PlaygroundEvent::Notify);
+    let on_config_change =
+        bus.callback(|config| PlaygroundEvent::ConfigChanged(Box::new(config)));
+
     // Local state for current message input
     let current_message = use_state(|| String::new());
     let is_loading = use_state(|| false);
+    // Draft and its optimized rewrite, waiting to be accepted or rejected
+    let prompt_suggestion = use_state(|| Option::<(String, String)>::None);
+    let is_improving = use_state(|| false);
+    // Starters the model suggested, with the system prompt they were written for
+    let generated_starters = use_state(|| Option::<(String, Vec<String>)>::None);
+    let is_generating_starters = use_state(|| false);
+    // Follow-up questions suggested for a reply, by the reply's id
+    let suggested_follow_ups = use_state(|| Option::<(String, Vec<String>)>::None);
+    // Typed message held back because it looks like it contains sensitive data
+    let pending_redaction = use_state(|| Option::<(String, Vec<Finding>)>::None);
+    // Parameters set in the composer for the next message, and those applying
+    // to the reply in progress
+    let composer_overrides = use_state(RequestOverrides::default);
+    // PDFs attached to the message being written
+    let pending_attachments = use_state(Vec::<Attachment>::new);
+    // Earlier image the message being written asks to have edited
+    let pending_image_edit = use_state(|| Option::<ImageEdit>::None);
+    let turn_overrides = use_mut_ref(RequestOverrides::default);
+    // Tools picked for the reply to a user message, by that message's id
+    let turn_tools = use_mut_ref(|| Option::<(String, Vec<String>)>::None);
+    // When the reply in progress was asked for, tool calls included
+    let run_started_at = use_mut_ref(|| 0.0f64);
+    // Artifacts opened from the conversation, one tab each
+    let open_artifacts = use_state(Vec::<Artifact>::new);
+    let active_artifact = use_state(String::new);
+    // Request rebuilt for the export dialog
+    let exported_request = use_state(|| Option::<ProviderRequest>::None);
     
     // State-driven message flow triggers
     let send_message_trigger = use_state(|| false);
     let function_call_trigger = use_state(|| Option::<serde_json::Value>::None);
-
-    // Helper function to check if error is retryable (429 rate limit)
-    let is_retryable_error = |error: &str| -> bool {
-        error.contains("429")
-            || error.contains("Rate limit exceeded")
-            || error.contains("rate limit")
-    };
-
-    // Helper function for exponential backoff delay
-    let calculate_retry_delay = |base_delay: u32, attempt: u32| -> u32 {
-        base_delay * (2_u32.pow(attempt.min(5))) // Cap at 2^5 to prevent excessive delays
-    };
+    // Set while a reply's tool calls run
+    let tools_running = use_state(|| false);
+    // Tool calls held back by the session budget, with the reason
+    let budget_pause = use_state(|| Option::<(serde_json::Value, String)>::None);
+    // Set when the user lets a paused agent continue, so the calls run once
+    let budget_resumed = use_mut_ref(|| false);
+    // Failed send waiting to be tried again, and failures in a row
+    let pending_retry = use_state(|| Option::<PendingRetry>::None);
+    let retry_attempts = use_mut_ref(|| 0u32);
 
     // Function call execution effect
     {
         let function_call_trigger = function_call_trigger.clone();
+        let tools_running = tools_running.clone();
         let session = props.session.clone();
-        let api_config = props.api_config.clone();
-        let mcp_client = props.mcp_client.clone();
+        let api_config = session_config.clone();
+        let mcp_client = app_state.mcp_client.clone();
+        let llm_client = app_state.llm_client.clone();
         let send_message_trigger = send_message_trigger.clone();
-        let on_session_update = props.on_session_update.clone();
+        let on_session_update = on_session_update.clone();
+        let on_session_action = on_session_action.clone();
+        let on_notification = on_notification.clone();
+        let budget_pause = budget_pause.clone();
+        let budget_resumed = budget_resumed.clone();
+        let bus = bus.clone();
 
         use_effect_with(function_call_trigger.clone(), move |trigger_data| {
             if let Some(function_calls_json) = trigger_data.as_ref() {
-                log!("🔧 Function call trigger activated");
+                logging::debug!("Function call trigger activated");
                 function_call_trigger.set(None); // Reset trigger
                 
                 if let Some(mut current_session) = session {
-                    if let Ok(function_calls) = serde_json::from_value::<Vec<serde_json::Value>>(function_calls_json.clone()) {
+                    session_pages::hydrate(&mut current_session);
+                    // Pause before running more tools once the budget is used up
+                    let resumed = std::mem::take(&mut *budget_resumed.borrow_mut());
+                    let exceeded = if resumed {
+                        current_session.budget_since = js_sys::Date::now();
+                        on_session_update.emit(current_session.clone());
+                        None
+                    } else {
+                        api_config.budget.exceeded(&BudgetUsage::of_session(&current_session))
+                    };
+                    if let Some(reason) = &exceeded {
+                        logging::warn!("Agent paused by the session budget: {}", reason);
+                        on_notification.emit(
+                            NotificationMessage::new(
+                                format!("Agent paused, session budget reached: {}", reason),
+                                NotificationType::Warning,
+                            )
+                            .with_duration(8000),
+                        );
+                        budget_pause.set(Some((function_calls_json.clone(), reason.clone())));
+                    }
+
+                    if let Some(function_calls) = serde_json::from_value::<Vec<serde_json::Value>>(function_calls_json.clone())
+                        .ok()
+                        .filter(|_| exceeded.is_none())
+                    {
                         let on_session_update_clone = on_session_update.clone();
+                        let on_session_action_clone = on_session_action.clone();
                         let api_config_clone = api_config.clone();
                         let mcp_client_clone = mcp_client.clone();
+                        let llm_client_clone = llm_client.clone();
                         let send_message_trigger_clone = send_message_trigger.clone();
+                        let bus_clone = bus.clone();
+                        let tools_running = tools_running.clone();
+                        tools_running.set(true);
 
                         wasm_bindgen_futures::spawn_local(async move {
                             // Execute all function calls
@@ -79,58 +176,87 @@ pub fn chatroom(props: &ChatroomProps) -> Html {
                                     function_call_json.get("id").and_then(|v| v.as_str()),
                                     function_call_json.get("arguments")
                                 ) {
-                                    log!("🔧 Executing function: {} (ID: {})", name, id);
+                                    logging::info!("Executing function {} (id {})", name, id);
+
+                                    // The Task tool runs a sub-agent that reports its progress into the session
+                                    if name == sub_agent::TASK_TOOL_NAME {
+                                        current_session = sub_agent::run_task_in_session(
+                                            current_session,
+                                            id,
+                                            arguments,
+                                            &llm_client_clone,
+                                            &api_config_clone,
+                                            mcp_client_clone.as_ref(),
+                                            &bus_clone,
+                                        ).await;
+                                        continue;
+                                    }
                                     
-                                    // Execute function call
-                                    let response_value = if let Some(tool) = api_config_clone
-                                        .function_tools
-                                        .iter()
-                                        .find(|tool| tool.name == name)
-                                    {
-                                        if tool.is_builtin {
-                                            // Execute built-in tool
-                                            match crate::llm_playground::builtin_tools::execute_builtin_tool(
-                                                name, 
-                                                arguments, 
-                                                mcp_client_clone.as_ref()
-                                            ).await {
-                                                Ok(result) => result,
-                                                Err(error) => serde_json::json!({"error": error}),
-                                            }
-                                        } else {
-                                            // Use mock response
-                                            serde_json::from_str(&tool.mock_response)
-                                                .unwrap_or_else(|_| serde_json::json!({"result": tool.mock_response.clone()}))
-                                        }
+                                    // Execute function call; TodoWrite updates the session's task list.
+                                    // A call missing a step it depends on gets an error instead
+                                    let started_at = js_sys::Date::now();
+                                    let dependency_check = tool_dependencies::check(
+                                        &api_config_clone,
+                                        name,
+                                        arguments,
+                                        &current_session.messages,
+                                    );
+                                    let response_value = if let Err(error) = dependency_check {
+                                        serde_json::json!({ "error": error })
+                                    } else if name == todo_list::TODO_WRITE_TOOL_NAME {
+                                        todo_list::apply_todo_write(&mut current_session, arguments)
                                     } else {
-                                        serde_json::json!({"error": "Unknown function tool"})
+                                        builtin_tools::execute_function_tool(
+                                            &api_config_clone,
+                                            &current_session,
+                                            name,
+                                            arguments,
+                                            mcp_client_clone.as_ref(),
+                                            Some(&bus_clone),
+                                        ).await
                                     };
+                                    session_recording::record(
+                                        &current_session.id,
+                                        RecordedStep::ToolCall(RecordedToolCall {
+                                            call_id: id.to_string(),
+                                            name: name.to_string(),
+                                            arguments: arguments.clone(),
+                                            result: response_value.clone(),
+                                            started_at,
+                                            duration_ms: js_sys::Date::now() - started_at,
+                                        }),
+                                    );
 
                                     // Add function response message
                                     let function_response_message = Message {
                                         id: format!("msg_fr_{}", js_sys::Date::now() as u64),
-                                        role: MessageRole::Function,
-                                        content: format!("Function {} executed", name),
                                         timestamp: js_sys::Date::now(),
-                                        function_call: None,
                                         function_response: Some(serde_json::json!({
                                             "id": id,
                                             "name": name,
                                             "response": response_value
                                         })),
+                                        ..Message::new(MessageRole::Function, format!("Function {} executed", name))
                                     };
                                     
-                                    // Update session with function response
-                                    current_session.messages.push(function_response_message);
+                                    // Update session with function response; TodoWrite also
+                                    // changed the task list, so the whole session goes out
+                                    current_session.messages.push(function_response_message.clone());
                                     current_session.updated_at = js_sys::Date::now();
-                                    
-                                    // Notify parent of session update
-                                    on_session_update_clone.emit(current_session.clone());
+                                    if name == todo_list::TODO_WRITE_TOOL_NAME {
+                                        on_session_update_clone.emit(current_session.clone());
+                                    } else {
+                                        on_session_action_clone.emit(SessionAction::AppendMessage {
+                                            session_id: current_session.id.clone(),
+                                            message: function_response_message,
+                                        });
+                                    }
                                 }
                             }
                             
                             // Trigger next LLM call after all function executions are complete
-                            log!("🔄 All functions executed, triggering next LLM call");
+                            logging::debug!("All functions executed, sending the results");
+                            tools_running.set(false);
                             send_message_trigger_clone.set(true);
                         });
                     }
@@ -140,139 +266,363 @@ pub fn chatroom(props: &ChatroomProps) -> Html {
         });
     }
 
+    // Busy marker in the tab's title and favicon; a run finishing in the
+    // background leaves a done marker until the tab is shown again
+    {
+        let busy = *is_loading || *tools_running || *send_message_trigger || function_call_trigger.is_some();
+        let was_busy = use_mut_ref(|| false);
+        use_effect_with(busy, move |busy| {
+            if *busy {
+                tab_status::set(TabStatus::Busy);
+            } else if *was_busy.borrow() {
+                tab_status::set(if tab_status::tab_hidden() { TabStatus::Done } else { TabStatus::Idle });
+            }
+            *was_busy.borrow_mut() = *busy;
+            || ()
+        });
+    }
+    use_effect_with((), |_| {
+        let listener = tab_status::clear_done_when_shown();
+        move || drop(listener)
+    });
+
     // State-driven LLM message sending effect
     {
         let send_message_trigger = send_message_trigger.clone();
         let is_loading = is_loading.clone();
         let session = props.session.clone();
-        let api_config = props.api_config.clone();
-        let llm_client = props.llm_client.clone();
+        let api_config = session_config.clone();
+        let llm_client = app_state.llm_client.clone();
         let function_call_trigger = function_call_trigger.clone();
-        let on_notification = props.on_notification.clone();
-        let on_session_update = props.on_session_update.clone();
+        let on_notification = on_notification.clone();
+        let on_session_action = on_session_action.clone();
+        let pending_retry = pending_retry.clone();
+        let retry_attempts = retry_attempts.clone();
+        let turn_overrides = turn_overrides.clone();
+        let turn_tools = turn_tools.clone();
+        let run_started_at = run_started_at.clone();
 
         use_effect_with(send_message_trigger.clone(), move |trigger| {
             if **trigger {
-                log!("🚀 Send message trigger activated");
+                logging::debug!("Send message trigger activated");
                 send_message_trigger.set(false); // Reset trigger
                 
-                if let Some(mut current_session) = session {
+                if let Some(mut current_session) = session.filter(|s| !s.locked) {
+                    // Requests carry the whole history, older pages included
+                    session_pages::hydrate(&mut current_session);
                     if !current_session.messages.is_empty() {
                         is_loading.set(true);
                         
-                        let messages = current_session.messages.clone();
-                        let config = api_config.clone();
+                        // The length preset, then composer overrides, which apply
+                        // to the whole reply; a forced tool choice only to its
+                        // first request so tool results can be answered
+                        let mut config = api_config.clone();
+                        api_config.response_length.apply(&mut config);
+                        api_config.self_consistency.apply(&mut config);
+                        turn_overrides.borrow().apply(&mut config);
+                        turn_overrides.borrow_mut().tool_choice = None;
+                        // A run starts with a user message; tool results continue it
+                        let starts_run = current_session.messages.last().is_some_and(|m| m.role == MessageRole::User);
+                        if starts_run {
+                            *run_started_at.borrow_mut() = js_sys::Date::now();
+                        }
+                        // The session's choice is forced the same way
+                        config.shared_settings.tool_choice = config.shared_settings.tool_choice.for_request(starts_run);
                         let client = llm_client.clone();
                         let is_loading_clone = is_loading.clone();
                         let on_notification_clone = on_notification.clone();
                         let function_call_trigger_clone = function_call_trigger.clone();
-                        let on_session_update_clone = on_session_update.clone();
+                        let on_session_action_clone = on_session_action.clone();
 
                         wasm_bindgen_futures::spawn_local(async move {
-                            log!("📤 Calling LLM API with {} messages...", messages.len());
+                            // Moderate the new user message before it reaches the model
+                            if config.moderation.checks_user_messages() {
+                                if let Some(last) = current_session
+                                    .messages
+                                    .last_mut()
+                                    .filter(|m| m.role == MessageRole::User && m.moderation.is_none())
+                                {
+                                    match moderation::moderate(&config, &last.content).await {
+                                        Ok(mut result) => {
+                                            result.blocked = result.flagged && config.moderation.block_flagged;
+                                            let blocked = result.blocked;
+                                            last.moderation = Some(result);
+                                            on_session_action_clone.emit(SessionAction::UpdateMessage {
+                                                session_id: current_session.id.clone(),
+                                                message: last.clone(),
+                                            });
+                                            if blocked {
+                                                on_notification_clone.emit(NotificationMessage::new(
+                                                    "Message flagged by moderation and not sent".to_string(),
+                                                    NotificationType::Warning,
+                                                ));
+                                                is_loading_clone.set(false);
+                                                return;
+                                            }
+                                        }
+                                        Err(error) => on_notification_clone.emit(NotificationMessage::new(
+                                            format!("Moderation check failed: {}", error),
+                                            NotificationType::Warning,
+                                        )),
+                                    }
+                                }
+                            }
+
+                            // Only send the tools the user's message needs, picked once
+                            // per message so the turn's tool calls keep the same set
+                            if config.tool_selection.applies(&config) {
+                                if let Some(user_message) = current_session
+                                    .messages
+                                    .iter()
+                                    .rev()
+                                    .find(|m| m.role == MessageRole::User)
+                                {
+                                    let cached = turn_tools
+                                        .borrow()
+                                        .as_ref()
+                                        .filter(|(id, _)| *id == user_message.id)
+                                        .map(|(_, picked)| picked.clone());
+                                    let picked = match cached {
+                                        Some(picked) => picked,
+                                        None => {
+                                            let selector = config.tool_selection.config_for(&config);
+                                            match tool_selection::select(&client, &selector, &config, &user_message.content).await {
+                                                Ok(picked) => {
+                                                    logging::info!("Tools picked for the message: {:?}", picked);
+                                                    picked
+                                                }
+                                                Err(error) => {
+                                                    on_notification_clone.emit(NotificationMessage::new(
+                                                        format!("Tool selection failed, sending all tools: {}", error),
+                                                        NotificationType::Warning,
+                                                    ));
+                                                    config
+                                                        .get_enabled_function_tools()
+                                                        .iter()
+                                                        .map(|tool| tool.name.clone())
+                                                        .collect()
+                                                }
+                                            }
+                                        }
+                                    };
+                                    *turn_tools.borrow_mut() = Some((user_message.id.clone(), picked.clone()));
+                                    tool_selection::restrict(&mut config, &picked);
+                                }
+                            }
+
+                            // Blocked messages never reach the model
+                            let mut messages: Vec<Message> = current_session
+                                .messages
+                                .iter()
+                                .filter(|m| !m.moderation.as_ref().is_some_and(|r| r.blocked))
+                                .cloned()
+                                .collect();
+
+                            logging::info!("Calling LLM API with {} messages", messages.len());
                             for (i, msg) in messages.iter().enumerate() {
                                 let role_str = match msg.role {
                                     MessageRole::User => "User",
                                     MessageRole::Assistant => "Assistant", 
                                     MessageRole::System => "System",
+                                    MessageRole::Developer => "Developer",
                                     MessageRole::Function => "Function",
                                 };
-                                log!("  Message {}: {} - {}", i + 1, role_str, 
-                                    if msg.content.len() > 100 { &msg.content[..100] } else { &msg.content });
+                                logging::debug!("Message {}: {} - {}", i + 1, role_str,
+                                    msg.content.chars().take(100).collect::<String>());
                             }
 
-                            let mut retry_attempt = 0u32;
-                            let max_retries = 3u32;
-
-                            let api_result = loop {
-                                log!("⏳ Attempting LLM API call (attempt {})...", retry_attempt + 1);
-                                
-                                let (provider_name, model_name) = config.get_current_provider_and_model();
-                                log!("🔍 chatroom::send_message - Provider: {}, Model: {}", &provider_name, &model_name);
-                                
-                                match client.send_message(&messages, &config).await {
-                                    Ok(response) => break Ok(response),
-                                    Err(error) => {
-                                        // Check if this is a retryable error (429 rate limit)
-                                        if is_retryable_error(&error) && retry_attempt < max_retries {
-                                            retry_attempt += 1;
-                                            let delay_ms = calculate_retry_delay(
-                                                config.shared_settings.retry_delay,
-                                                retry_attempt - 1,
-                                            );
-
-                                            // Show notification for rate limit
-                                            let notification = NotificationMessage::new(
-                                                format!("Rate limit hit. Retrying in {}ms... (attempt {}/{})", 
-                                                    delay_ms, retry_attempt, max_retries + 1),
-                                                NotificationType::Warning
-                                            ).with_duration(delay_ms + 1000);
-                                            on_notification_clone.emit(notification);
-
-                                            log!(
-                                                "Rate limit hit, retrying in {}ms (attempt {})",
-                                                delay_ms,
-                                                retry_attempt
-                                            );
-
-                                            // Wait before retry
-                                            TimeoutFuture::new(delay_ms).await;
-                                            continue;
-                                        } else {
-                                            // Non-retryable error or max retries exceeded
-                                            if is_retryable_error(&error) && retry_attempt >= max_retries {
-                                                let final_error = format!("Rate limit exceeded. Max retries ({}) reached. Please wait before trying again.", max_retries + 1);
-                                                let notification = NotificationMessage::new(
-                                                    final_error.clone(),
-                                                    NotificationType::Error,
-                                                ).with_duration(8000);
-                                                on_notification_clone.emit(notification);
-                                                break Err(final_error);
-                                            } else {
-                                                // Show notification for other errors
-                                                let notification = NotificationMessage::new(
-                                                    format!("API Error: {}", error),
-                                                    NotificationType::Error,
-                                                ).with_duration(6000);
-                                                on_notification_clone.emit(notification);
-                                                break Err(error);
-                                            }
-                                        }
-                                    }
+                            // Failed sends aren't retried here; the retry queue below
+                            // owns retries, so a rate-limited key isn't hit again at once
+                            let (provider_name, model_name) = config.get_current_provider_and_model();
+                            logging::debug!("Provider: {}, model: {}", provider_name, model_name);
+
+                            let started_at = js_sys::Date::now();
+                            let mut api_result = client.send_message(&messages, &config).await;
+                            session_recording::record_request(&current_session.id, &config, &messages, started_at, &api_result);
+                            if let Err(error) = &api_result {
+                                on_notification_clone.emit(
+                                    NotificationMessage::new(format!("API Error: {}", error), NotificationType::Error)
+                                        .with_duration(6000),
+                                );
+                            }
+
+                            // Send invalid structured replies, tool arguments and replies
+                            // breaking a retry guardrail back for repair
+                            let mut repair_attempt = 0u32;
+                            while let Ok(response) = &api_result {
+                                let Some(issue) = json_repair::find_issue(response, &config)
+                                    .or_else(|| guardrails::retry_issue(response, &config.guardrails))
+                                else {
+                                    break;
+                                };
+                                if repair_attempt >= config.shared_settings.json_repair_retries() {
+                                    on_notification_clone.emit(
+                                        NotificationMessage::new(
+                                            format!("Invalid output after {} repair attempt(s): {}", repair_attempt, issue.error),
+                                            NotificationType::Error,
+                                        )
+                                        .with_duration(8000),
+                                    );
+                                    break;
+                                }
+                                repair_attempt += 1;
+                                on_notification_clone.emit(NotificationMessage::new(
+                                    format!(
+                                        "Invalid output, asking the model to fix it (attempt {}/{})",
+                                        repair_attempt,
+                                        config.shared_settings.json_repair_retries()
+                                    ),
+                                    NotificationType::Warning,
+                                ));
+                                messages.extend(json_repair::correction_turn(&issue));
+                                let started_at = js_sys::Date::now();
+                                api_result = client.send_message(&messages, &config).await;
+                                session_recording::record_request(&current_session.id, &config, &messages, started_at, &api_result);
+                                if let Err(error) = &api_result {
+                                    on_notification_clone.emit(
+                                        NotificationMessage::new(
+                                            format!("API Error: {}", error),
+                                            NotificationType::Error,
+                                        )
+                                        .with_duration(6000),
+                                    );
                                 }
-                            };
+                            }
 
                             match api_result {
                                 Ok(response) => {
-                                    log!("✅ LLM API response received!");
-                                    log!("📊 Response details:");
-                                    log!("  - Function calls: {}", response.function_calls.len());
-                                    log!("  - Content length: {}", response.content.as_ref().map(|c| c.len()).unwrap_or(0));
+                                    *retry_attempts.borrow_mut() = 0;
+                                    pending_retry.set(None);
+                                    logging::info!(
+                                        "LLM API response received: {} function calls, {} characters",
+                                        response.function_calls.len(),
+                                        response.content.as_ref().map(|c| c.len()).unwrap_or(0)
+                                    );
+
+                                    let metrics = response.timing.map(|timing| ResponseMetrics {
+                                        cost: response.usage.as_ref().and_then(|usage| {
+                                            config.request_cost(usage.prompt_tokens, usage.completion_tokens)
+                                        }),
+                                        model: Some(config.get_current_provider_and_model().1),
+                                        ..ResponseMetrics::new(timing, response.usage.as_ref())
+                                    });
                                     
                                     // Handle LLM response directly here
                                     if response.function_calls.is_empty() {
+                                        // Several candidates wanted: the response's other
+                                        // choices, topped up by asking again when the
+                                        // provider returned fewer
+                                        let mut sampled = candidates::from_response(&response);
+                                        let wanted = config.shared_settings.candidate_count() as usize;
+                                        if wanted > 1 {
+                                            for _ in sampled.len().max(1)..wanted {
+                                                let started_at = js_sys::Date::now();
+                                                let extra = client.send_message(&messages, &config).await;
+                                                session_recording::record_request(&current_session.id, &config, &messages, started_at, &extra);
+                                                match extra {
+                                                    Ok(extra) if extra.function_calls.is_empty() => {
+                                                        sampled.extend(candidates::from_response(&extra).into_iter().take(1));
+                                                    }
+                                                    Ok(_) => {}
+                                                    Err(error) => {
+                                                        on_notification_clone.emit(NotificationMessage::new(
+                                                            format!("Could not sample another candidate: {}", error),
+                                                            NotificationType::Warning,
+                                                        ));
+                                                        break;
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        // Self-consistency: answer with the samples' consensus
+                                        let mut reply = response.content.clone();
+                                        let mut consensus = None;
+                                        if config.self_consistency.enabled {
+                                            if let Some((index, votes)) = self_consistency::majority(&sampled).filter(|_| sampled.len() > 1) {
+                                                reply = Some(sampled[index].clone());
+                                                consensus = Some(ConsensusResult { method: ConsensusMethod::Majority, votes });
+                                                if config.self_consistency.method == ConsensusMethod::Model {
+                                                    let question = messages
+                                                        .iter()
+                                                        .rev()
+                                                        .find(|m| m.role == MessageRole::User)
+                                                        .map(|m| m.content.clone())
+                                                        .unwrap_or_default();
+                                                    match self_consistency::aggregate(&client, &config, &question, &sampled).await {
+                                                        Ok(answer) => {
+                                                            reply = Some(answer);
+                                                            consensus = Some(ConsensusResult { method: ConsensusMethod::Model, votes });
+                                                        }
+                                                        Err(error) => on_notification_clone.emit(NotificationMessage::new(
+                                                            format!("Model aggregation failed, using the majority vote: {}", error),
+                                                            NotificationType::Warning,
+                                                        )),
+                                                    }
+                                                }
+                                            }
+                                        }
                                         // Regular text response - conversation ends here
-                                        if let Some(content) = &response.content {
+                                        if let Some(content) = &reply {
                                             if !content.trim().is_empty() {
+                                                let (content, guardrail_violations) =
+                                                    guardrails::apply(content, &config.guardrails);
+                                                let moderation = if config.moderation.checks_replies() {
+                                                    moderation::moderate(&config, &content)
+                                                        .await
+                                                        .map_err(|error| {
+                                                            on_notification_clone.emit(NotificationMessage::new(
+                                                                format!("Moderation check failed: {}", error),
+                                                                NotificationType::Warning,
+                                                            ))
+                                                        })
+                                                        .ok()
+                                                } else {
+                                                    None
+                                                };
                                                 let assistant_message = Message {
                                                     id: format!("assistant_{}", js_sys::Date::now() as u64),
-                                                    role: MessageRole::Assistant,
-                                                    content: content.clone(),
                                                     timestamp: js_sys::Date::now(),
-                                                    function_call: None,
-                                                    function_response: None,
+                                                    metrics,
+                                                    structured_output: config.active_structured_output().cloned(),
+                                                    guardrail_violations,
+                                                    moderation,
+                                                    finish_reason: response.finish_reason.clone(),
+                                                    reasoning: response.reasoning.clone(),
+                                                    citations: response.citations.clone(),
+                                                    candidates: if sampled.len() > 1 {
+                                                        sampled
+                                                            .iter()
+                                                            .map(|candidate| guardrails::apply(candidate, &config.guardrails).0)
+                                                            .collect()
+                                                    } else {
+                                                        Vec::new()
+                                                    },
+                                                    consensus,
+                                                    native_tool_calls: response.native_tool_calls.clone(),
+                                                    audio: response.audio.clone(),
+                                                    images: response.images.clone(),
+                                                    ..Message::new(MessageRole::Assistant, content)
                                                 };
-                                                current_session.messages.push(assistant_message);
+                                                let reply = assistant_message.content.clone();
+                                                current_session.messages.push(assistant_message.clone());
                                                 current_session.updated_at = js_sys::Date::now();
+                                                on_session_action_clone.emit(SessionAction::AppendMessage {
+                                                    session_id: current_session.id.clone(),
+                                                    message: assistant_message,
+                                                });
+                                                webhook::fire(&config, WebhookEvent::Completed, &current_session, &reply, &on_notification_clone);
+                                                browser_notifications::notify_finished(
+                                                    &config.browser_notifications,
+                                                    *run_started_at.borrow(),
+                                                    &format!("Reply ready · {}", current_session.title),
+                                                    &reply,
+                                                );
                                             }
                                         }
-                                        on_session_update_clone.emit(current_session);
                                     } else {
                                         // Function call response - trigger function execution
                                         let assistant_message = Message {
                                             id: format!("msg_fc_{}", js_sys::Date::now() as u64),
-                                            role: MessageRole::Assistant,
-                                            content: response.content.unwrap_or_default(),
                                             timestamp: js_sys::Date::now(),
                                             function_call: Some(serde_json::json!(response
                                                 .function_calls
@@ -285,11 +635,21 @@ pub fn chatroom(props: &ChatroomProps) -> Html {
                                                     })
                                                 })
                                                 .collect::<Vec<_>>())),
-                                            function_response: None,
+                                            metrics,
+                                            finish_reason: response.finish_reason,
+                                            reasoning: response.reasoning,
+                                            citations: response.citations,
+                                            native_tool_calls: response.native_tool_calls,
+                                            audio: response.audio,
+                                            images: response.images,
+                                            ..Message::new(MessageRole::Assistant, response.content.unwrap_or_default())
                                         };
-                                        current_session.messages.push(assistant_message);
+                                        current_session.messages.push(assistant_message.clone());
                                         current_session.updated_at = js_sys::Date::now();
-                                        on_session_update_clone.emit(current_session);
+                                        on_session_action_clone.emit(SessionAction::AppendMessage {
+                                            session_id: current_session.id.clone(),
+                                            message: assistant_message,
+                                        });
 
                                         // Trigger function call execution
                                         let function_calls_json = serde_json::json!(response
@@ -304,13 +664,38 @@ pub fn chatroom(props: &ChatroomProps) -> Html {
                                             })
                                             .collect::<Vec<_>>());
                                         
-                                        log!("🔄 Triggering function call execution");
+                                        logging::debug!("Triggering function call execution");
                                         function_call_trigger_clone.set(Some(function_calls_json));
                                     }
                                 }
-                                Err(_error) => {
-                                    // Error already handled above with notifications
-                                    log!("❌ API error occurred");
+                                Err(error) => {
+                                    // Already shown to the user as a notification
+                                    logging::error!("LLM API call failed: {}", error);
+                                    webhook::fire(&config, WebhookEvent::Failed, &current_session, &error, &on_notification_clone);
+                                    browser_notifications::notify_finished(
+                                        &config.browser_notifications,
+                                        *run_started_at.borrow(),
+                                        &format!("Generation failed · {}", current_session.title),
+                                        &error,
+                                    );
+                                    // Keep the attempt for the retry queue
+                                    if let Some(last) = current_session
+                                        .messages
+                                        .last()
+                                        .filter(|_| retry_queue::is_transient(&error))
+                                    {
+                                        let attempt = {
+                                            let mut attempts = retry_attempts.borrow_mut();
+                                            *attempts += 1;
+                                            *attempts
+                                        };
+                                        pending_retry.set(Some(PendingRetry::after_failure(
+                                            &last.id,
+                                            &error,
+                                            attempt,
+                                            config.shared_settings.retry_delay,
+                                        )));
+                                    }
                                 }
                             }
 
@@ -323,46 +708,369 @@ pub fn chatroom(props: &ChatroomProps) -> Html {
         });
     }
 
+    // Dialogue mode: the two participants take turns until interrupted.
+    // Bumping the run id interrupts the current run.
+    let dialogue_run = use_mut_ref(|| 0u64);
+    let start_dialogue = {
+        let dialogue_run = dialogue_run.clone();
+        let is_loading = is_loading.clone();
+        let api_config = session_config.clone();
+        let llm_client = app_state.llm_client.clone();
+        let on_session_update = on_session_update.clone();
+        let on_notification = on_notification.clone();
+        Callback::from(move |mut session: ChatSession| {
+            session_pages::hydrate(&mut session);
+            let run_id = {
+                let mut current = dialogue_run.borrow_mut();
+                *current += 1;
+                *current
+            };
+            is_loading.set(true);
+
+            let dialogue_run = dialogue_run.clone();
+            let is_loading = is_loading.clone();
+            let api_config = api_config.clone();
+            let llm_client = llm_client.clone();
+            let on_session_update = on_session_update.clone();
+            let on_notification = on_notification.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let is_current = || *dialogue_run.borrow() == run_id;
+                let result = dialogue::run_dialogue(
+                    session,
+                    &llm_client,
+                    &api_config,
+                    &on_session_update,
+                    is_current,
+                ).await;
+                if let Err(error) = result {
+                    on_notification.emit(
+                        NotificationMessage::new(format!("Dialogue stopped: {}", error), NotificationType::Error)
+                            .with_duration(6000),
+                    );
+                }
+                if *dialogue_run.borrow() == run_id {
+                    is_loading.set(false);
+                }
+            });
+        })
+    };
+
+    let interrupt_dialogue = {
+        let dialogue_run = dialogue_run.clone();
+        let is_loading = is_loading.clone();
+        Callback::from(move |_: ()| {
+            *dialogue_run.borrow_mut() += 1;
+            is_loading.set(false);
+        })
+    };
+
+    // Start a fresh dialogue session automatically, and stop a running one when
+    // switching away from it
+    {
+        let session = props.session.clone();
+        let start_dialogue = start_dialogue.clone();
+        let interrupt_dialogue = interrupt_dialogue.clone();
+        let session_id = props.session.as_ref().map(|s| s.id.clone());
+        use_effect_with(session_id, move |_| {
+            if let Some(session) = session {
+                if session.dialogue.is_some() && dialogue::model_turns(&session) == 0 {
+                    start_dialogue.emit(session);
+                }
+            }
+            move || interrupt_dialogue.emit(())
+        });
+    }
+
+    let extend_dialogue = {
+        let session = props.session.clone();
+        let start_dialogue = start_dialogue.clone();
+        let on_session_update = on_session_update.clone();
+        Callback::from(move |extra_turns: u32| {
+            if let Some(mut current_session) = session.clone() {
+                if let Some(dialogue) = current_session.dialogue.as_mut() {
+                    dialogue.max_turns += extra_turns;
+                }
+                on_session_update.emit(current_session.clone());
+                start_dialogue.emit(current_session);
+            }
+        })
+    };
+
     // Handle user message submission
-    let send_message = {
+    // Send the text as the next user message
+    let send_text = {
         let current_message = current_message.clone();
+        let prompt_suggestion = prompt_suggestion.clone();
+        let pending_redaction = pending_redaction.clone();
+        let composer_overrides = composer_overrides.clone();
+        let pending_attachments = pending_attachments.clone();
+        let pending_image_edit = pending_image_edit.clone();
+        let turn_overrides = turn_overrides.clone();
         let send_message_trigger = send_message_trigger.clone();
         let session = props.session.clone();
-        let on_session_update = props.on_session_update.clone();
+        let on_session_update = on_session_update.clone();
+        let start_dialogue = start_dialogue.clone();
+        let pending_retry = pending_retry.clone();
+        let retry_attempts = retry_attempts.clone();
         
-        Callback::from(move |_: ()| {
-            let message_content = (*current_message).clone();
+        Callback::from(move |message_content: String| {
             if !message_content.trim().is_empty() {
                 if let Some(mut current_session) = session.clone() {
+                    session_pages::hydrate(&mut current_session);
                     // Create user message
                     let user_message = Message {
                         id: format!("user_{}", js_sys::Date::now() as u64),
-                        role: MessageRole::User,
-                        content: message_content.clone(),
                         timestamp: js_sys::Date::now(),
-                        function_call: None,
-                        function_response: None,
+                        attachments: (*pending_attachments).clone(),
+                        image_edit: (*pending_image_edit).clone(),
+                        ..Message::new(MessageRole::User, message_content.clone())
                     };
 
-                    log!("🔍 chatroom::send_message - Adding user message and triggering send");
+                    logging::debug!("Adding user message and triggering send");
                     
                     // Add user message to session
                     current_session.messages.push(user_message);
                     current_session.updated_at = js_sys::Date::now();
                     
                     // Notify parent of session update
-                    on_session_update.emit(current_session);
+                    on_session_update.emit(current_session.clone());
                     
                     // Clear input
                     current_message.set(String::new());
+                    prompt_suggestion.set(None);
+                    pending_redaction.set(None);
+                    *turn_overrides.borrow_mut() = (*composer_overrides).clone();
+                    composer_overrides.set(RequestOverrides::default());
+                    pending_attachments.set(Vec::new());
+                    pending_image_edit.set(None);
+                    // A failed send goes out again together with this one
+                    pending_retry.set(None);
+                    *retry_attempts.borrow_mut() = 0;
                     
-                    // Trigger LLM send
-                    send_message_trigger.set(true);
+                    if current_session.dialogue.is_some() {
+                        // A moderator note in a dialogue; the participants carry on if turns remain
+                        if !dialogue::is_finished(&current_session) {
+                            start_dialogue.emit(current_session);
+                        }
+                    } else {
+                        // Trigger LLM send
+                        send_message_trigger.set(true);
+                    }
+                }
+            }
+        })
+    };
+
+    // Typed messages are checked for sensitive data first
+    let send_message = {
+        let current_message = current_message.clone();
+        let pending_redaction = pending_redaction.clone();
+        let redaction = app_state.config.redaction.clone();
+        let send_text = send_text.clone();
+        Callback::from(move |_: ()| {
+            let text = (*current_message).clone();
+            let findings = redaction.scan(&text);
+            if findings.is_empty() {
+                send_text.emit(text);
+            } else {
+                pending_redaction.set(Some((text, findings)));
+            }
+        })
+    };
+
+    let send_masked = {
+        let pending_redaction = pending_redaction.clone();
+        let redaction = app_state.config.redaction.clone();
+        let send_text = send_text.clone();
+        Callback::from(move |_: ()| {
+            if let Some((text, _)) = &*pending_redaction {
+                send_text.emit(redaction.mask(text));
+            }
+        })
+    };
+
+    let send_unmasked = {
+        let pending_redaction = pending_redaction.clone();
+        let send_text = send_text.clone();
+        Callback::from(move |_: ()| {
+            if let Some((text, _)) = &*pending_redaction {
+                send_text.emit(text.clone());
+            }
+        })
+    };
+
+    let keep_editing = {
+        let pending_redaction = pending_redaction.clone();
+        Callback::from(move |_: ()| pending_redaction.set(None))
+    };
+
+    // Add the draft as a system or developer message; it applies from the next reply
+    let add_instruction = {
+        let current_message = current_message.clone();
+        let session = props.session.clone();
+        let on_session_update = on_session_update.clone();
+        Callback::from(move |role: MessageRole| {
+            let content = current_message.trim().to_string();
+            if content.is_empty() {
+                return;
+            }
+            if let Some(mut current_session) = session.clone() {
+                current_session.messages.push(Message {
+                    id: format!("instruction_{}", js_sys::Date::now() as u64),
+                    timestamp: js_sys::Date::now(),
+                    ..Message::new(role, content)
+                });
+                current_session.updated_at = js_sys::Date::now();
+                on_session_update.emit(current_session);
+                current_message.set(String::new());
+            }
+        })
+    };
+
+    // Ask the model to resume a reply that hit the max tokens limit and
+    // append what it writes to the same message
+    let continue_reply = {
+        let session = props.session.clone();
+        let is_loading = is_loading.clone();
+        let llm_client = app_state.llm_client.clone();
+        let api_config = session_config.clone();
+        let on_session_update = on_session_update.clone();
+        let on_notification = on_notification.clone();
+        Callback::from(move |_: ()| {
+            let Some(mut current_session) = session.clone() else {
+                return;
+            };
+            session_pages::hydrate(&mut current_session);
+            let Some(index) = current_session
+                .messages
+                .iter()
+                .rposition(|m| m.role == MessageRole::Assistant)
+            else {
+                return;
+            };
+            let mut config = api_config.clone();
+            api_config.response_length.apply(&mut config);
+            // The continuation is text; tool calls would have nowhere to go
+            for tool in &mut config.function_tools {
+                tool.enabled = false;
+            }
+            let client = llm_client.clone();
+            let is_loading = is_loading.clone();
+            let on_session_update = on_session_update.clone();
+            let on_notification = on_notification.clone();
+            is_loading.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                let messages = continuation::request_messages(&current_session.messages, index);
+                match client.send_message(&messages, &config).await {
+                    Ok(response) => {
+                        let text = guardrails::apply(
+                            &response.content.clone().unwrap_or_default(),
+                            &config.guardrails,
+                        )
+                        .0;
+                        let metrics = response.timing.map(|timing| ResponseMetrics {
+                            cost: response.usage.as_ref().and_then(|usage| {
+                                config.request_cost(usage.prompt_tokens, usage.completion_tokens)
+                            }),
+                            model: Some(config.get_current_provider_and_model().1),
+                            ..ResponseMetrics::new(timing, response.usage.as_ref())
+                        });
+                        let reply = &mut current_session.messages[index];
+                        reply.content = continuation::stitch(&reply.content, &text);
+                        reply.finish_reason = response.finish_reason;
+                        reply.metrics = continuation::merge_metrics(reply.metrics.take(), metrics);
+                        // Sampled candidates end where the shown one was cut off
+                        reply.candidates.clear();
+                        current_session.updated_at = js_sys::Date::now();
+                        on_session_update.emit(current_session);
+                    }
+                    Err(error) => on_notification.emit(NotificationMessage::new(
+                        format!("Couldn't continue the reply: {}", error),
+                        NotificationType::Error,
+                    )),
                 }
+                is_loading.set(false);
+            });
+        })
+    };
+
+    // Run the tool calls held back by the budget and start a fresh budget
+    let continue_agent = {
+        let budget_pause = budget_pause.clone();
+        let budget_resumed = budget_resumed.clone();
+        let function_call_trigger = function_call_trigger.clone();
+        Callback::from(move |_: ()| {
+            if let Some((function_calls, _)) = (*budget_pause).clone() {
+                *budget_resumed.borrow_mut() = true;
+                budget_pause.set(None);
+                function_call_trigger.set(Some(function_calls));
             }
         })
     };
 
+    // Answer the held back tool calls without running them
+    let stop_agent = {
+        let budget_pause = budget_pause.clone();
+        let session = props.session.clone();
+        let on_session_update = on_session_update.clone();
+        Callback::from(move |_: ()| {
+            let Some((function_calls, _)) = (*budget_pause).clone() else {
+                return;
+            };
+            budget_pause.set(None);
+            if let Some(mut current_session) = session.clone() {
+                let calls = function_calls.as_array().cloned().unwrap_or_default();
+                current_session.messages.extend(budget::stopped_responses(&calls));
+                current_session.updated_at = js_sys::Date::now();
+                on_session_update.emit(current_session);
+            }
+        })
+    };
+
+    // Send the failed attempt again when its wait is over
+    {
+        let send_message_trigger = send_message_trigger.clone();
+        use_effect_with((*pending_retry).clone(), move |pending| {
+            let timeout = pending
+                .as_ref()
+                .and_then(|pending| pending.delay_ms)
+                .map(|delay_ms| Timeout::new(delay_ms, move || send_message_trigger.set(true)));
+            move || drop(timeout)
+        });
+    }
+
+    let retry_now = {
+        let pending_retry = pending_retry.clone();
+        let send_message_trigger = send_message_trigger.clone();
+        Callback::from(move |_: ()| {
+            pending_retry.set(None);
+            send_message_trigger.set(true);
+        })
+    };
+
+    let cancel_retry = {
+        let pending_retry = pending_retry.clone();
+        let retry_attempts = retry_attempts.clone();
+        Callback::from(move |_: ()| {
+            pending_retry.set(None);
+            *retry_attempts.borrow_mut() = 0;
+        })
+    };
+
+    // Pauses and retries belong to the session they happened in
+    {
+        let budget_pause = budget_pause.clone();
+        let pending_retry = pending_retry.clone();
+        let retry_attempts = retry_attempts.clone();
+        let session_id = props.session.as_ref().map(|s| s.id.clone());
+        use_effect_with(session_id, move |_| {
+            budget_pause.set(None);
+            pending_retry.set(None);
+            *retry_attempts.borrow_mut() = 0;
+            || ()
+        });
+    }
+
     let update_message = {
         let current_message = current_message.clone();
         Callback::from(move |message: String| {
@@ -370,6 +1078,216 @@ pub fn chatroom(props: &ChatroomProps) -> Html {
         })
     };
 
+    // Empty sessions get model-written starters once per system prompt
+    let offers_starters = props
+        .session
+        .as_ref()
+        .is_some_and(|s| s.messages.is_empty() && s.dialogue.is_none() && !s.locked);
+    {
+        let generated_starters = generated_starters.clone();
+        let is_generating_starters = is_generating_starters.clone();
+        let api_config = session_config.clone();
+        let llm_client = app_state.llm_client.clone();
+        let wanted = offers_starters && session_config.conversation_starters.generate;
+        use_effect_with(
+            (wanted, session_config.system_prompt.clone()),
+            move |(wanted, system_prompt)| {
+                let cached = generated_starters
+                    .as_ref()
+                    .is_some_and(|(prompt, _)| prompt == system_prompt);
+                if *wanted && !cached && !system_prompt.trim().is_empty() {
+                    is_generating_starters.set(true);
+                    let system_prompt = system_prompt.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        match conversation_starters::generate(&llm_client, &api_config).await {
+                            Ok(starters) => generated_starters.set(Some((system_prompt, starters))),
+                            Err(error) => {
+                                logging::warn!("Could not suggest conversation starters: {}", error);
+                                generated_starters.set(Some((system_prompt, Vec::new())));
+                            }
+                        }
+                        is_generating_starters.set(false);
+                    });
+                }
+                || ()
+            },
+        );
+    }
+
+    // Suggest follow-ups once per reply, after it has finished
+    let answered_id = props
+        .session
+        .as_ref()
+        .filter(|s| s.dialogue.is_none() && !s.locked)
+        .and_then(|s| follow_ups::answered_message(&s.messages))
+        .map(|m| m.id.clone());
+    {
+        let suggested_follow_ups = suggested_follow_ups.clone();
+        let messages = props.session.as_ref().map(|s| s.messages.clone()).unwrap_or_default();
+        let api_config = app_state.config.follow_ups.config_for(&session_config);
+        let llm_client = app_state.llm_client.clone();
+        let wanted = app_state.config.follow_ups.enabled && !*is_loading;
+        use_effect_with((wanted, answered_id.clone()), move |(wanted, answered_id)| {
+            let cached = suggested_follow_ups
+                .as_ref()
+                .is_some_and(|(id, _)| Some(id) == answered_id.as_ref());
+            if let (true, false, Some(answered_id)) = (*wanted, cached, answered_id.clone()) {
+                wasm_bindgen_futures::spawn_local(async move {
+                    let suggestions = follow_ups::generate(&llm_client, &api_config, &messages)
+                        .await
+                        .unwrap_or_else(|error| {
+                            logging::warn!("Could not suggest follow-ups: {}", error);
+                            Vec::new()
+                        });
+                    suggested_follow_ups.set(Some((answered_id, suggestions)));
+                });
+            }
+            || ()
+        });
+    }
+    let shown_follow_ups = (*suggested_follow_ups)
+        .as_ref()
+        .filter(|(id, _)| app_state.config.follow_ups.enabled && Some(id) == answered_id.as_ref())
+        .map(|(_, suggestions)| suggestions.clone())
+        .unwrap_or_default();
+
+    // Rewrite the draft with the current model, using recent messages as context
+    let improve_prompt = {
+        let current_message = current_message.clone();
+        let prompt_suggestion = prompt_suggestion.clone();
+        let is_improving = is_improving.clone();
+        let session = props.session.clone();
+        let api_config = session_config.clone();
+        let llm_client = app_state.llm_client.clone();
+        let on_notification = on_notification.clone();
+        Callback::from(move |_: ()| {
+            let draft = (*current_message).clone();
+            if draft.trim().is_empty() || *is_improving {
+                return;
+            }
+            is_improving.set(true);
+
+            let recent = session.as_ref().map(|s| s.messages.clone()).unwrap_or_default();
+            let prompt_suggestion = prompt_suggestion.clone();
+            let is_improving = is_improving.clone();
+            let api_config = api_config.clone();
+            let llm_client = llm_client.clone();
+            let on_notification = on_notification.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match prompt_optimizer::optimize(&llm_client, &api_config, &draft, &recent).await {
+                    Ok(improved) if improved == draft.trim() => {
+                        on_notification.emit(NotificationMessage::new(
+                            "The prompt is already in good shape".to_string(),
+                            NotificationType::Info,
+                        ));
+                    }
+                    Ok(improved) => prompt_suggestion.set(Some((draft, improved))),
+                    Err(error) => {
+                        on_notification.emit(
+                            NotificationMessage::new(
+                                format!("Could not improve the prompt: {}", error),
+                                NotificationType::Error,
+                            )
+                            .with_duration(6000),
+                        );
+                    }
+                }
+                is_improving.set(false);
+            });
+        })
+    };
+
+    let accept_suggestion = {
+        let current_message = current_message.clone();
+        let prompt_suggestion = prompt_suggestion.clone();
+        Callback::from(move |_: ()| {
+            if let Some((_, improved)) = (*prompt_suggestion).clone() {
+                current_message.set(improved);
+            }
+            prompt_suggestion.set(None);
+        })
+    };
+
+    let reject_suggestion = {
+        let prompt_suggestion = prompt_suggestion.clone();
+        Callback::from(move |_: ()| prompt_suggestion.set(None))
+    };
+
+    // Artifact tabs belong to the session they were opened from
+    {
+        let open_artifacts = open_artifacts.clone();
+        let session_id = props.session.as_ref().map(|s| s.id.clone());
+        use_effect_with(session_id, move |_| {
+            open_artifacts.set(Vec::new());
+            || ()
+        });
+    }
+
+    let open_artifact = {
+        let open_artifacts = open_artifacts.clone();
+        let active_artifact = active_artifact.clone();
+        Callback::from(move |artifact: Artifact| {
+            if !open_artifacts.iter().any(|a| a.id == artifact.id) {
+                let mut next = (*open_artifacts).clone();
+                next.push(artifact.clone());
+                open_artifacts.set(next);
+            }
+            active_artifact.set(artifact.id);
+        })
+    };
+
+    let close_artifact = {
+        let open_artifacts = open_artifacts.clone();
+        Callback::from(move |id: String| {
+            let mut next = (*open_artifacts).clone();
+            next.retain(|a| a.id != id);
+            open_artifacts.set(next);
+        })
+    };
+
+    let select_artifact = {
+        let active_artifact = active_artifact.clone();
+        Callback::from(move |id: String| active_artifact.set(id))
+    };
+
+    // Edited artifacts land in the draft so the user can add instructions
+    let send_artifact_edits = {
+        let current_message = current_message.clone();
+        Callback::from(move |edits: String| {
+            let draft = current_message.trim_end();
+            if draft.is_empty() {
+                current_message.set(edits);
+            } else {
+                current_message.set(format!("{}\n\n{}", draft, edits));
+            }
+        })
+    };
+
+    let export_request = {
+        let exported_request = exported_request.clone();
+        let session = props.session.clone();
+        let api_config = session_config.clone();
+        let llm_client = app_state.llm_client.clone();
+        let on_notification = on_notification.clone();
+        Callback::from(move |message_id: String| {
+            let Some(session) = &session else {
+                return;
+            };
+            match request_export::request_for_message(&llm_client, &api_config, &session.messages, &message_id) {
+                Ok(request) => exported_request.set(Some(request)),
+                Err(error) => on_notification.emit(NotificationMessage::new(
+                    format!("Could not rebuild the request: {}", error),
+                    NotificationType::Error,
+                )),
+            }
+        })
+    };
+
+    let close_export = {
+        let exported_request = exported_request.clone();
+        Callback::from(move |_: ()| exported_request.set(None))
+    };
+
     let create_input_event_callback = {
         let update_message = update_message.clone();
         move |callback: Callback<String>| {
@@ -383,18 +1301,505 @@ pub fn chatroom(props: &ChatroomProps) -> Html {
         }
     };
 
+    let toggle_pin = {
+        let session = props.session.clone();
+        let on_session_update = on_session_update.clone();
+        Callback::from(move |message_id: String| {
+            if let Some(mut current_session) = session.clone() {
+                bookmarks::toggle_pin(&mut current_session, &message_id);
+                on_session_update.emit(current_session);
+            }
+        })
+    };
+
+    // Show another sampled candidate as the reply
+    let select_candidate = {
+        let session = props.session.clone();
+        let guardrail_rules = session_config.guardrails.clone();
+        let on_session_update = on_session_update.clone();
+        Callback::from(move |(message_id, index): (String, usize)| {
+            let Some(mut current_session) = session.clone().filter(|s| !s.locked) else {
+                return;
+            };
+            let changed = current_session
+                .messages
+                .iter_mut()
+                .find(|m| m.id == message_id)
+                .is_some_and(|message| candidates::select(message, index, &guardrail_rules));
+            if changed {
+                current_session.updated_at = js_sys::Date::now();
+                on_session_update.emit(current_session);
+            }
+        })
+    };
+
+    // Tools are toggled in the app's config, unless a persona picks them
+    let toggle_tool = personas::for_session(&app_state.config, props.session.as_ref())
+        .is_none()
+        .then(|| {
+            let config = app_state.config.clone();
+            let on_config_change = on_config_change.clone();
+            Callback::from(move |name: String| {
+                let mut config = config.clone();
+                config.toggle_function_tool(&name);
+                on_config_change.emit(config);
+            })
+        });
+    let toggle_mcp_server = toggle_tool.as_ref().map(|_| {
+        let config = app_state.config.clone();
+        let on_config_change = on_config_change.clone();
+        Callback::from(move |(server, enabled): (String, bool)| {
+            let mut config = config.clone();
+            config.set_mcp_server_tools_enabled(&server, enabled);
+            on_config_change.emit(config);
+        })
+    });
+
+    // Manual check-off from the task panel
+    let toggle_todo = {
+        let session = props.session.clone();
+        let on_session_update = on_session_update.clone();
+        Callback::from(move |todo_id: String| {
+            if let Some(mut current_session) = session.clone() {
+                if todo_list::toggle_todo_by_user(&mut current_session, &todo_id) {
+                    on_session_update.emit(current_session);
+                }
+            }
+        })
+    };
+
+    let locked = props.session.as_ref().is_some_and(|s| s.locked);
+    // What the next request would fill the context window with
+    let next_context = {
+        let mut config = session_config.clone();
+        session_config.response_length.apply(&mut config);
+        composer_overrides.apply(&mut config);
+        let messages = props.session.as_ref().map(|s| s.messages.as_slice()).unwrap_or_default();
+        context_usage::estimate(&config, messages, &current_message)
+    };
+    let todos = props
+        .session
+        .as_ref()
+        .map(|s| s.todos.clone())
+        .unwrap_or_default();
+
     html! {
-        <>
-            <ChatRoomDisplay
-                session={props.session.clone()}
-                is_loading={*is_loading}
-            />
-            <InputBar
-                current_message={(*current_message).clone()}
-                is_loading={*is_loading}
-                on_send_message={send_message}
-                on_message_change={create_input_event_callback(update_message)}
-            />
-        </>
+        <div class="flex-1 flex min-h-0">
+            <div class="flex-1 flex flex-col min-w-0">
+                <ChatRoomDisplay
+                    session={props.session.clone()}
+                    is_loading={*is_loading}
+                    on_open_artifact={Some(open_artifact)}
+                    on_export_request={Some(export_request)}
+                    on_toggle_pin={Some(toggle_pin)}
+                    on_select_candidate={(!*is_loading).then_some(select_candidate)}
+                    focus_message={props.focus_message.clone()}
+                    show_message_footer={!app_state.config.hide_message_footer}
+                    show_reasoning={!app_state.config.hide_reasoning}
+                    retry_pending_for={pending_retry.as_ref().map(|pending| pending.message_id.clone())}
+                    follow_ups={shown_follow_ups}
+                    on_follow_up={Some(send_text.clone())}
+                    // Dialogue turns go to the participants, not the chat model
+                    on_continue={props.session.as_ref().filter(|s| s.dialogue.is_none() && !s.locked).map(|_| continue_reply)}
+                    on_load_older={props.on_load_older.clone()}
+                />
+                {match &props.session {
+                    Some(session) if session.dialogue.is_some() && !session.locked => {
+                        render_dialogue_bar(session, *is_loading, &interrupt_dialogue, &start_dialogue, &extend_dialogue)
+                    }
+                    _ => html! {},
+                }}
+                {match &*budget_pause {
+                    Some((function_calls, reason)) => render_budget_pause(
+                        function_calls.as_array().map(Vec::len).unwrap_or_default(),
+                        reason,
+                        &continue_agent,
+                        &stop_agent,
+                    ),
+                    None => html! {},
+                }}
+                {match &*pending_retry {
+                    Some(pending) => render_retry_banner(pending, *is_loading, &retry_now, &cancel_retry),
+                    None => html! {},
+                }}
+                {render_response_format_bar(&app_state.config, *is_loading, &on_config_change)}
+                {match &*pending_redaction {
+                    Some((_, findings)) => render_redaction_warning(
+                        findings,
+                        *is_loading,
+                        &send_masked,
+                        &send_unmasked,
+                        &keep_editing,
+                    ),
+                    None => html! {},
+                }}
+                {match &*prompt_suggestion {
+                    Some((original, improved)) => html! {
+                        <PromptSuggestion
+                            original={original.clone()}
+                            improved={improved.clone()}
+                            on_accept={accept_suggestion}
+                            on_reject={reject_suggestion}
+                        />
+                    },
+                    None => html! {},
+                }}
+                {if offers_starters && !*is_loading {
+                    let generated = if session_config.conversation_starters.generate {
+                        (*generated_starters)
+                            .as_ref()
+                            .filter(|(prompt, _)| *prompt == session_config.system_prompt)
+                            .map(|(_, starters)| starters.clone())
+                            .unwrap_or_default()
+                    } else {
+                        Vec::new()
+                    };
+                    html! {
+                        <StarterPrompts
+                            starters={session_config.conversation_starters.prompts.clone()}
+                            {generated}
+                            is_generating={*is_generating_starters}
+                            on_pick={send_text.clone()}
+                        />
+                    }
+                } else {
+                    html! {}
+                }}
+                {if locked {
+                    html! {
+                        <div class="p-4 border-t border-gray-200 dark:border-gray-700 text-center text-sm text-gray-600 dark:text-gray-300">
+                            <i class="fas fa-lock mr-2" aria-hidden="true"></i>
+                            {"This session is locked. Unlock it from the header to continue the conversation."}
+                        </div>
+                    }
+                } else {
+                    html! {
+                        <>
+                            {if props.session.as_ref().is_some_and(|session| session.incognito) {
+                                html! {
+                                    <div class="px-4 py-1 border-t border-gray-200 dark:border-gray-700 text-xs text-gray-600 dark:text-gray-300">
+                                        <i class="fas fa-user-secret mr-2" aria-hidden="true"></i>
+                                        {"Incognito: this session isn't saved and is gone when the page is closed or reloaded."}
+                                    </div>
+                                }
+                            } else {
+                                html! {}
+                            }}
+                            <ContextUsageBar usage={next_context} />
+                            {match props.session.as_ref().map(|session| image_generation::session_images(&session.messages)) {
+                                Some(images) if !images.is_empty() => {
+                                    let (provider, model) = session_config.get_current_provider_and_model();
+                                    html! {
+                                        <ImageStrip
+                                            images={images.into_iter().cloned().collect::<Vec<_>>()}
+                                            edit={(*pending_image_edit).clone()}
+                                            on_edit_change={
+                                                let pending_image_edit = pending_image_edit.clone();
+                                                Callback::from(move |edit| pending_image_edit.set(edit))
+                                            }
+                                            image_model={image_generation::is_image_model(
+                                                session_config.provider_uses_transformer(&provider, "gemini"),
+                                                &model,
+                                            )}
+                                        />
+                                    }
+                                }
+                                _ => html! {},
+                            }}
+                            <InputBar
+                                current_message={(*current_message).clone()}
+                                is_loading={*is_loading}
+                                on_send_message={send_message}
+                                on_message_change={create_input_event_callback(update_message)}
+                                on_improve_prompt={Some(improve_prompt)}
+                                is_improving={*is_improving}
+                                overrides={(*composer_overrides).clone()}
+                                on_overrides_change={
+                                    let composer_overrides = composer_overrides.clone();
+                                    Callback::from(move |overrides| composer_overrides.set(overrides))
+                                }
+                                default_temperature={session_config.shared_settings.temperature}
+                                default_max_tokens={session_config.shared_settings.max_tokens}
+                                tool_config={Some(session_config.clone())}
+                                on_toggle_tool={toggle_tool.clone()}
+                                on_toggle_mcp_server={toggle_mcp_server.clone()}
+                                attachments={(*pending_attachments).clone()}
+                                on_attachments_change={
+                                    let pending_attachments = pending_attachments.clone();
+                                    Callback::from(move |attached| pending_attachments.set(attached))
+                                }
+                                native_pdfs={{
+                                    let (provider, model) = session_config.get_current_provider_and_model();
+                                    attachments::reads_pdfs_natively(
+                                        session_config.provider_uses_transformer(&provider, "gemini"),
+                                        &model,
+                                    )
+                                }}
+                                on_add_instruction={
+                                    // Dialogue participants only see each other and the moderator
+                                    props.session.as_ref().filter(|s| s.dialogue.is_none()).map(|_| add_instruction)
+                                }
+                            />
+                        </>
+                    }
+                }}
+            </div>
+            {match &*exported_request {
+                Some(request) => html! {
+                    <RequestExportDialog
+                        request={request.clone()}
+                        on_close={close_export}
+                    />
+                },
+                None => html! {},
+            }}
+            {if open_artifacts.is_empty() {
+                html! {}
+            } else {
+                html! {
+                    <ArtifactPanel
+                        artifacts={(*open_artifacts).clone()}
+                        active_id={(*active_artifact).clone()}
+                        on_select={select_artifact}
+                        on_close={close_artifact}
+                        on_send_edits={send_artifact_edits}
+                    />
+                }
+            }}
+            {if todos.is_empty() {
+                html! {}
+            } else {
+                html! {
+                    <TodoPanel {todos} on_toggle={toggle_todo} disabled={*is_loading || locked} />
+                }
+            }}
+        </div>
+    }
+}
+
+/// Selector for the structured output the next replies must follow, shown
+/// once any schema is defined in settings
+fn render_response_format_bar(
+    config: &FlexibleApiConfig,
+    is_loading: bool,
+    on_config_change: &Callback<FlexibleApiConfig>,
+) -> Html {
+    if config.structured_outputs.is_empty() {
+        return html! {};
+    }
+    let active = config
+        .active_structured_output()
+        .map(|output| output.name.clone())
+        .unwrap_or_default();
+    let on_change = {
+        let config = config.clone();
+        let on_config_change = on_config_change.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlInputElement = e.target_unchecked_into();
+            let mut config = config.clone();
+            config.active_structured_output = Some(select.value()).filter(|name| !name.is_empty());
+            on_config_change.emit(config);
+        })
+    };
+
+    html! {
+        <div class="flex items-center gap-2 px-4 py-2 border-t border-gray-200 dark:border-gray-700 text-sm">
+            <label class="text-gray-600 dark:text-gray-400">
+                <i class="fas fa-code mr-1" aria-hidden="true"></i>{"Response format"}
+            </label>
+            <select
+                class="px-2 py-1 border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                disabled={is_loading}
+                onchange={on_change}
+            >
+                <option value="" selected={active.is_empty()}>{"Free text"}</option>
+                {for config.structured_outputs.iter().map(|output| html! {
+                    <option value={output.name.clone()} selected={output.name == active}>
+                        {format!("JSON: {}", output.name)}
+                    </option>
+                })}
+            </select>
+        </div>
+    }
+}
+
+/// Shown while the session budget holds back the agent's next tool calls
+fn render_budget_pause(
+    pending_calls: usize,
+    reason: &str,
+    on_continue: &Callback<()>,
+    on_stop: &Callback<()>,
+) -> Html {
+    let on_continue = on_continue.clone();
+    let on_stop = on_stop.clone();
+    html! {
+        <div class="flex items-center justify-between gap-2 px-4 py-2 text-sm bg-yellow-50 dark:bg-yellow-900/30 border-t border-yellow-300 dark:border-yellow-700" role="alert">
+            <span class="text-yellow-800 dark:text-yellow-300">
+                <i class="fas fa-wallet mr-2" aria-hidden="true"></i>
+                {format!("Session budget reached ({}). {} tool call(s) waiting.", reason, pending_calls)}
+            </span>
+            <div class="flex shrink-0 gap-2">
+                <button
+                    onclick={Callback::from(move |_: MouseEvent| on_stop.emit(()))}
+                    class="px-3 py-1 text-xs rounded-md bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 text-gray-800 dark:text-gray-200 hover:bg-gray-100 dark:hover:bg-gray-600"
+                >
+                    <i class="fas fa-stop mr-1" aria-hidden="true"></i>{"Stop"}
+                </button>
+                <button
+                    onclick={Callback::from(move |_: MouseEvent| on_continue.emit(()))}
+                    class="px-3 py-1 text-xs rounded-md bg-yellow-600 text-white hover:bg-yellow-700"
+                    title="Run the waiting tool calls and start a new budget from here"
+                >
+                    <i class="fas fa-play mr-1" aria-hidden="true"></i>{"Continue"}
+                </button>
+            </div>
+        </div>
+    }
+}
+
+/// Failed send with its next automatic retry and controls to retry or give up
+fn render_retry_banner(
+    pending: &PendingRetry,
+    is_loading: bool,
+    on_retry: &Callback<()>,
+    on_cancel: &Callback<()>,
+) -> Html {
+    let on_retry = on_retry.clone();
+    let on_cancel = on_cancel.clone();
+    let schedule = match pending.delay_ms {
+        Some(delay_ms) => format!(
+            "Retrying automatically after {}s (attempt {} of {}).",
+            (delay_ms as f64 / 1000.0).ceil(),
+            pending.attempt + 1,
+            retry_queue::MAX_AUTO_RETRIES + 1
+        ),
+        None => "Automatic retries used up.".to_string(),
+    };
+    html! {
+        <div class="flex items-center justify-between gap-2 px-4 py-2 text-sm bg-red-50 dark:bg-red-900/30 border-t border-red-300 dark:border-red-700" role="alert">
+            <span class="min-w-0 text-red-800 dark:text-red-300">
+                <i class="fas fa-redo mr-2" aria-hidden="true"></i>
+                {format!("Send failed: {} ", pending.error.chars().take(160).collect::<String>())}
+                <span class="text-red-700 dark:text-red-400">{schedule}</span>
+            </span>
+            <div class="flex shrink-0 gap-2">
+                <button
+                    onclick={Callback::from(move |_: MouseEvent| on_cancel.emit(()))}
+                    class="px-3 py-1 text-xs rounded-md bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 text-gray-800 dark:text-gray-200 hover:bg-gray-100 dark:hover:bg-gray-600"
+                    title="Stop retrying; the message stays in the conversation"
+                >
+                    {"Cancel"}
+                </button>
+                <button
+                    onclick={Callback::from(move |_: MouseEvent| on_retry.emit(()))}
+                    disabled={is_loading}
+                    class="px-3 py-1 text-xs rounded-md bg-red-600 text-white hover:bg-red-700 disabled:opacity-50"
+                >
+                    <i class="fas fa-redo mr-1" aria-hidden="true"></i>{"Retry now"}
+                </button>
+            </div>
+        </div>
+    }
+}
+
+/// Sensitive data found in a typed message, with the choice to mask it
+fn render_redaction_warning(
+    findings: &[Finding],
+    is_loading: bool,
+    on_mask: &Callback<()>,
+    on_send: &Callback<()>,
+    on_edit: &Callback<()>,
+) -> Html {
+    let on_mask = on_mask.clone();
+    let on_send = on_send.clone();
+    let on_edit = on_edit.clone();
+    html! {
+        <div class="flex items-center justify-between gap-2 px-4 py-2 text-sm bg-yellow-50 dark:bg-yellow-900/30 border-t border-yellow-300 dark:border-yellow-700" role="alert">
+            <span class="min-w-0 text-yellow-800 dark:text-yellow-300">
+                <i class="fas fa-user-shield mr-2" aria-hidden="true"></i>
+                {format!("This message looks like it contains: {}.", redaction::summarize(findings))}
+            </span>
+            <div class="flex shrink-0 gap-2">
+                <button
+                    onclick={Callback::from(move |_: MouseEvent| on_edit.emit(()))}
+                    class="px-3 py-1 text-xs rounded-md bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 text-gray-800 dark:text-gray-200 hover:bg-gray-100 dark:hover:bg-gray-600"
+                >
+                    {"Edit"}
+                </button>
+                <button
+                    onclick={Callback::from(move |_: MouseEvent| on_send.emit(()))}
+                    disabled={is_loading}
+                    class="px-3 py-1 text-xs rounded-md bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 text-gray-800 dark:text-gray-200 hover:bg-gray-100 dark:hover:bg-gray-600 disabled:opacity-50"
+                >
+                    {"Send as is"}
+                </button>
+                <button
+                    onclick={Callback::from(move |_: MouseEvent| on_mask.emit(()))}
+                    disabled={is_loading}
+                    class="px-3 py-1 text-xs rounded-md bg-yellow-600 text-white hover:bg-yellow-700 disabled:opacity-50"
+                >
+                    <i class="fas fa-mask mr-1" aria-hidden="true"></i>{"Mask and send"}
+                </button>
+            </div>
+        </div>
     }
-}
\ No newline at end of file
+}
+
+/// Turn counter and controls shown above the input in dialogue sessions
+fn render_dialogue_bar(
+    session: &ChatSession,
+    is_running: bool,
+    on_interrupt: &Callback<()>,
+    on_resume: &Callback<ChatSession>,
+    on_extend: &Callback<u32>,
+) -> Html {
+    let Some(config) = &session.dialogue else {
+        return html! {};
+    };
+    let turns = dialogue::model_turns(session);
+    let names: Vec<&str> = config.participants.iter().map(|p| p.name.as_str()).collect();
+    let button_class = "px-3 py-1 text-xs rounded-md bg-white dark:bg-gray-700 border border-gray-300 dark:border-gray-600 text-gray-800 dark:text-gray-200 hover:bg-gray-100 dark:hover:bg-gray-600";
+
+    let control = if is_running {
+        let on_interrupt = on_interrupt.clone();
+        html! {
+            <button
+                onclick={Callback::from(move |_: MouseEvent| on_interrupt.emit(()))}
+                class={button_class}
+                title="Stop after the current reply so you can step in"
+            >
+                <i class="fas fa-hand-paper mr-1" aria-hidden="true"></i>{"Interrupt"}
+            </button>
+        }
+    } else if dialogue::is_finished(session) {
+        let on_extend = on_extend.clone();
+        html! {
+            <button onclick={Callback::from(move |_: MouseEvent| on_extend.emit(2))} class={button_class}>
+                <i class="fas fa-plus mr-1" aria-hidden="true"></i>{"2 more turns"}
+            </button>
+        }
+    } else {
+        let on_resume = on_resume.clone();
+        let session = session.clone();
+        html! {
+            <button onclick={Callback::from(move |_: MouseEvent| on_resume.emit(session.clone()))} class={button_class}>
+                <i class="fas fa-play mr-1" aria-hidden="true"></i>{"Resume"}
+            </button>
+        }
+    };
+
+    html! {
+        <div class="flex items-center justify-between px-4 py-2 text-sm bg-gray-50 dark:bg-gray-900/40 border-t border-gray-200 dark:border-gray-600">
+            <span class="text-gray-700 dark:text-gray-300">
+                <i class="fas fa-comments mr-2 text-primary-600 dark:text-primary-400" aria-hidden="true"></i>
+                {format!("{} — turn {} of {}", names.join(" vs "), turns, config.max_turns)}
+                {if is_running {
+                    html! {}
+                } else {
+                    html! { <span class="ml-2 text-xs text-gray-500 dark:text-gray-400">{"Type a message to step in as moderator."}</span> }
+                }}
+            </span>
+            {control}
+        </div>
+    }
+}
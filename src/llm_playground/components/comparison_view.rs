@@ -0,0 +1,728 @@
+// Side-by-side comparison of one prompt across several provider/model combos,
+// with an optional blind arena mode that hides the models until the user votes
+use crate::llm_playground::{
+    api_clients::TokenUsage,
+    app_state::use_app_state,
+    arena::ArenaLeaderboard,
+    components::{message_bubble::MessageBubble, ConfirmAction, ConfirmRequest},
+    hooks::use_confirm,
+    Message, MessageRole,
+};
+use std::rc::Rc;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+pub const MIN_COMPARISON_COLUMNS: usize = 2;
+pub const MAX_COMPARISON_COLUMNS: usize = 4;
+
+/// Outcome of a single column's request
+#[derive(Clone, Debug, PartialEq)]
+pub enum ColumnResult {
+    Running,
+    Done {
+        content: String,
+        usage: Option<TokenUsage>,
+        latency_ms: f64,
+    },
+    Failed {
+        error: String,
+        latency_ms: f64,
+    },
+}
+
+/// The user's verdict on a blind round
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArenaVote {
+    Winner(usize),
+    Tie,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct ComparisonResults {
+    run_id: u64,
+    /// Provider/model combos in the order they are displayed for this run
+    combos: Vec<(String, String)>,
+    /// Whether the combos are hidden until a vote is cast
+    blind: bool,
+    vote: Option<ArenaVote>,
+    columns: Vec<Option<ColumnResult>>,
+}
+
+impl ComparisonResults {
+    fn is_arena_round(&self) -> bool {
+        self.blind && !self.combos.is_empty()
+    }
+}
+
+enum ComparisonAction {
+    Start {
+        run_id: u64,
+        combos: Vec<(String, String)>,
+        blind: bool,
+    },
+    Finish {
+        run_id: u64,
+        index: usize,
+        result: ColumnResult,
+    },
+    Vote(ArenaVote),
+    Reset,
+}
+
+impl Reducible for ComparisonResults {
+    type Action = ComparisonAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            ComparisonAction::Start {
+                run_id,
+                combos,
+                blind,
+            } => Rc::new(Self {
+                run_id,
+                columns: vec![Some(ColumnResult::Running); combos.len()],
+                combos,
+                blind,
+                vote: None,
+            }),
+            ComparisonAction::Finish {
+                run_id,
+                index,
+                result,
+            } => {
+                // Ignore late results from a previous run
+                if run_id != self.run_id || index >= self.columns.len() {
+                    return self;
+                }
+                let mut next = (*self).clone();
+                next.columns[index] = Some(result);
+                Rc::new(next)
+            }
+            ComparisonAction::Vote(vote) => {
+                if !self.is_arena_round() || self.vote.is_some() {
+                    return self;
+                }
+                let mut next = (*self).clone();
+                next.vote = Some(vote);
+                Rc::new(next)
+            }
+            ComparisonAction::Reset => Rc::new(Self::default()),
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ComparisonViewProps {
+    pub on_close: Callback<()>,
+}
+
+#[function_component(ComparisonView)]
+pub fn comparison_view(props: &ComparisonViewProps) -> Html {
+    let app_state = use_app_state();
+    let combos = app_state.config.get_all_provider_models();
+
+    // Start with the current model next to the first different one
+    let columns = use_state(|| {
+        let current = app_state.config.get_current_provider_and_model();
+        let mut initial = vec![current.clone()];
+        if let Some(other) = combos.iter().find(|c| **c != current) {
+            initial.push(other.clone());
+        }
+        while initial.len() < MIN_COMPARISON_COLUMNS {
+            initial.push(current.clone());
+        }
+        initial
+    });
+    let prompt = use_state(String::new);
+    let results = use_reducer(ComparisonResults::default);
+    let blind = use_state(|| false);
+    let leaderboard = use_state(ArenaLeaderboard::load);
+    let show_leaderboard = use_state(|| false);
+
+    let is_running = results
+        .columns
+        .iter()
+        .any(|r| matches!(r, Some(ColumnResult::Running)));
+    let arena_round = results.is_arena_round();
+    let revealed = !arena_round || results.vote.is_some();
+
+    let on_prompt_input = {
+        let prompt = prompt.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            prompt.set(input.value());
+        })
+    };
+
+    let run_comparison = {
+        let columns = columns.clone();
+        let prompt = prompt.clone();
+        let results = results.clone();
+        let blind = blind.clone();
+        let config = app_state.config.clone();
+        let llm_client = app_state.llm_client.clone();
+        Callback::from(move |_: ()| {
+            let text = prompt.trim().to_string();
+            if text.is_empty() {
+                return;
+            }
+
+            // Blind rounds shuffle the columns so position gives nothing away
+            let mut round = (*columns).clone();
+            if *blind {
+                shuffle(&mut round);
+            }
+
+            let run_id = js_sys::Date::now() as u64;
+            results.dispatch(ComparisonAction::Start {
+                run_id,
+                combos: round.clone(),
+                blind: *blind,
+            });
+
+            let messages = vec![Message {
+                id: format!("compare_{}", run_id),
+                timestamp: js_sys::Date::now(),
+                ..Message::new(MessageRole::User, text)
+            }];
+
+            for (index, (provider_name, model_name)) in round.iter().enumerate() {
+                // Each column gets its own config pinned to its model; tools are left out
+                // so every model answers the same plain prompt
+                let mut column_config = config.clone();
+                column_config.set_session_provider(provider_name, model_name);
+                column_config.apply_model_defaults(provider_name, model_name);
+                for tool in column_config.function_tools.iter_mut() {
+                    tool.enabled = false;
+                }
+
+                let client = llm_client.clone();
+                let messages = messages.clone();
+                let results = results.clone();
+
+                wasm_bindgen_futures::spawn_local(async move {
+                    let started = js_sys::Date::now();
+                    let outcome = client.send_message(&messages, &column_config).await;
+                    let latency_ms = js_sys::Date::now() - started;
+
+                    let result = match outcome {
+                        Ok(response) => ColumnResult::Done {
+                            content: response.content.unwrap_or_default(),
+                            usage: response.usage,
+                            latency_ms,
+                        },
+                        Err(error) => ColumnResult::Failed { error, latency_ms },
+                    };
+                    results.dispatch(ComparisonAction::Finish {
+                        run_id,
+                        index,
+                        result,
+                    });
+                });
+            }
+        })
+    };
+
+    let on_run_click = {
+        let run_comparison = run_comparison.clone();
+        Callback::from(move |_: MouseEvent| run_comparison.emit(()))
+    };
+
+    let on_prompt_keydown = {
+        let run_comparison = run_comparison.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "Enter" && (e.ctrl_key() || e.meta_key()) {
+                e.prevent_default();
+                run_comparison.emit(());
+            }
+        })
+    };
+
+    let on_add_column = {
+        let columns = columns.clone();
+        let config = app_state.config.clone();
+        Callback::from(move |_: MouseEvent| {
+            if columns.len() < MAX_COMPARISON_COLUMNS {
+                let mut next = (*columns).clone();
+                next.push(config.get_current_provider_and_model());
+                columns.set(next);
+            }
+        })
+    };
+
+    let on_toggle_blind = {
+        let blind = blind.clone();
+        let results = results.clone();
+        Callback::from(move |_: Event| {
+            blind.set(!*blind);
+            results.dispatch(ComparisonAction::Reset);
+        })
+    };
+
+    let on_vote = {
+        let results = results.clone();
+        let leaderboard = leaderboard.clone();
+        Callback::from(move |vote: ArenaVote| {
+            if !results.is_arena_round() || results.vote.is_some() {
+                return;
+            }
+            let participants: Vec<String> = results
+                .combos
+                .iter()
+                .map(|(provider, model)| format!("{},{}", provider, model))
+                .collect();
+            let winner = match vote {
+                ArenaVote::Winner(index) => participants.get(index).cloned(),
+                ArenaVote::Tie => None,
+            };
+
+            let mut next = (*leaderboard).clone();
+            next.record_vote(&participants, winner.as_deref());
+            next.save();
+            leaderboard.set(next);
+            results.dispatch(ComparisonAction::Vote(vote));
+        })
+    };
+
+    let on_tie = {
+        let on_vote = on_vote.clone();
+        Callback::from(move |_: MouseEvent| on_vote.emit(ArenaVote::Tie))
+    };
+
+    let on_new_round = {
+        let results = results.clone();
+        Callback::from(move |_: MouseEvent| results.dispatch(ComparisonAction::Reset))
+    };
+
+    let on_toggle_leaderboard = {
+        let show_leaderboard = show_leaderboard.clone();
+        Callback::from(move |_: MouseEvent| show_leaderboard.set(!*show_leaderboard))
+    };
+
+    let confirm = use_confirm();
+    let on_reset_leaderboard = {
+        let leaderboard = leaderboard.clone();
+        let confirm = confirm.clone();
+        Callback::from(move |_: MouseEvent| {
+            let leaderboard = leaderboard.clone();
+            confirm.confirm(
+                ConfirmRequest::new(
+                    ConfirmAction::ResetLeaderboard,
+                    "Reset arena leaderboard?",
+                    "All recorded votes and win rates will be cleared.",
+                )
+                .with_confirm_label("Reset"),
+                Callback::from(move |_| {
+                    ArenaLeaderboard::clear();
+                    leaderboard.set(ArenaLeaderboard::default());
+                }),
+            );
+        })
+    };
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    // During a blind round the columns follow the shuffled order of the run
+    let display_columns = if arena_round {
+        results.combos.clone()
+    } else {
+        (*columns).clone()
+    };
+    let can_vote = arena_round && !is_running && results.vote.is_none();
+
+    let grid_class = match display_columns.len() {
+        2 => "grid-cols-2",
+        3 => "grid-cols-3",
+        _ => "grid-cols-4",
+    };
+
+    html! {
+        <div class="flex-1 flex flex-col min-w-0 bg-white dark:bg-gray-800">
+            <header class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
+                <div>
+                    <h2 class="font-semibold text-gray-900 dark:text-gray-100">{"Model Comparison"}</h2>
+                    <div class="text-sm text-gray-600 dark:text-gray-300">
+                        {"Send one prompt to several models at once. Function tools are disabled here."}
+                    </div>
+                </div>
+                <div class="flex items-center space-x-2">
+                    <label class="flex items-center space-x-1 text-sm text-gray-700 dark:text-gray-300" title="Hide which model wrote which answer until you vote">
+                        <input
+                            type="checkbox"
+                            checked={*blind}
+                            onchange={on_toggle_blind}
+                            disabled={is_running}
+                            class="rounded"
+                        />
+                        <span>{"Blind arena"}</span>
+                    </label>
+                    <button
+                        onclick={on_toggle_leaderboard}
+                        aria-expanded={show_leaderboard.to_string()}
+                        class="text-sm px-3 py-1 bg-yellow-100 dark:bg-yellow-900/30 text-yellow-700 dark:text-yellow-400 rounded hover:bg-yellow-200 dark:hover:bg-yellow-900/50"
+                    >
+                        <i class="fas fa-trophy mr-1" aria-hidden="true"></i>{"Leaderboard"}
+                    </button>
+                    {if can_vote {
+                        html! {
+                            <button
+                                onclick={on_tie}
+                                class="text-sm px-3 py-1 bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-300 rounded hover:bg-gray-200 dark:hover:bg-gray-600"
+                            >
+                                <i class="fas fa-equals mr-1" aria-hidden="true"></i>{"Tie"}
+                            </button>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    {if arena_round && !is_running {
+                        html! {
+                            <button
+                                onclick={on_new_round}
+                                class="text-sm px-3 py-1 bg-primary-100 dark:bg-primary-900/30 text-primary-700 dark:text-primary-400 rounded hover:bg-primary-200 dark:hover:bg-primary-900/50"
+                            >
+                                <i class="fas fa-redo mr-1" aria-hidden="true"></i>{"New round"}
+                            </button>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    {if !arena_round && columns.len() < MAX_COMPARISON_COLUMNS {
+                        html! {
+                            <button
+                                onclick={on_add_column}
+                                disabled={is_running}
+                                class="text-sm px-3 py-1 bg-green-100 dark:bg-green-900/30 text-green-600 dark:text-green-400 rounded hover:bg-green-200 dark:hover:bg-green-900/50"
+                            >
+                                <i class="fas fa-plus mr-1" aria-hidden="true"></i>{"Add model"}
+                            </button>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    <button
+                        onclick={on_close}
+                        class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                        aria-label="Close comparison"
+                        title="Back to chat"
+                    >
+                        <i class="fas fa-times" aria-hidden="true"></i>
+                    </button>
+                </div>
+            </header>
+
+            {if *show_leaderboard {
+                render_leaderboard(&leaderboard, on_reset_leaderboard)
+            } else {
+                html! {}
+            }}
+
+            <div class={classes!("flex-1", "grid", "gap-4", "p-4", "overflow-hidden", grid_class)}>
+                {for display_columns.iter().enumerate().map(|(index, (provider_name, model_name))| {
+                    let on_select = {
+                        let columns = columns.clone();
+                        Callback::from(move |e: Event| {
+                            let select: HtmlInputElement = e.target_unchecked_into();
+                            if let Some((provider, model)) = select.value().split_once(',') {
+                                let mut next = (*columns).clone();
+                                next[index] = (provider.to_string(), model.to_string());
+                                columns.set(next);
+                            }
+                        })
+                    };
+                    let on_remove = {
+                        let columns = columns.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            if columns.len() > MIN_COMPARISON_COLUMNS {
+                                let mut next = (*columns).clone();
+                                next.remove(index);
+                                columns.set(next);
+                            }
+                        })
+                    };
+                    let selected = format!("{},{}", provider_name, model_name);
+                    let result = results.columns.get(index).cloned().flatten();
+                    let label = arena_label(index);
+                    let is_winner = matches!(results.vote, Some(ArenaVote::Winner(winner)) if winner == index);
+                    let on_vote_column = {
+                        let on_vote = on_vote.clone();
+                        Callback::from(move |_: MouseEvent| on_vote.emit(ArenaVote::Winner(index)))
+                    };
+
+                    html! {
+                        <section
+                            key={index}
+                            class={classes!(
+                                "flex", "flex-col", "min-w-0", "border", "rounded-lg", "overflow-hidden",
+                                if is_winner {
+                                    "border-yellow-400 dark:border-yellow-500"
+                                } else {
+                                    "border-gray-200 dark:border-gray-600"
+                                }
+                            )}
+                            aria-label={if revealed {
+                                format!("{} - {}", provider_name, model_name)
+                            } else {
+                                label.clone()
+                            }}
+                        >
+                            <div class="flex items-center space-x-2 p-2 border-b border-gray-200 dark:border-gray-600 bg-gray-50 dark:bg-gray-700">
+                                {if arena_round {
+                                    html! {
+                                        <div class="flex-1 min-w-0 p-1 text-sm font-medium text-gray-900 dark:text-gray-100 truncate">
+                                            {if is_winner {
+                                                html! { <i class="fas fa-trophy text-yellow-500 mr-1" aria-label="Winner"></i> }
+                                            } else {
+                                                html! {}
+                                            }}
+                                            {label.clone()}
+                                            {if revealed {
+                                                html! {
+                                                    <span class="ml-2 font-normal text-gray-600 dark:text-gray-300">
+                                                        {format!("{} / {}", provider_name, model_name)}
+                                                    </span>
+                                                }
+                                            } else {
+                                                html! {}
+                                            }}
+                                        </div>
+                                    }
+                                } else {
+                                    html! {
+                                <>
+                                <select
+                                    onchange={on_select}
+                                    disabled={is_running}
+                                    aria-label={format!("Model for column {}", index + 1)}
+                                    class="flex-1 min-w-0 p-1 text-sm border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                                >
+                                    {for combos.iter().map(|(provider, model)| {
+                                        let value = format!("{},{}", provider, model);
+                                        html! {
+                                            <option value={value.clone()} selected={value == selected}>
+                                                {format!("{} / {}", provider, model)}
+                                            </option>
+                                        }
+                                    })}
+                                </select>
+                                {if columns.len() > MIN_COMPARISON_COLUMNS {
+                                    html! {
+                                        <button
+                                            onclick={on_remove}
+                                            disabled={is_running}
+                                            class="text-xs px-1 text-red-600 dark:text-red-400 hover:text-red-800 dark:hover:text-red-300"
+                                            aria-label={format!("Remove column {}", index + 1)}
+                                        >
+                                            <i class="fas fa-times" aria-hidden="true"></i>
+                                        </button>
+                                    }
+                                } else {
+                                    html! {}
+                                }}
+                                </>
+                                    }
+                                }}
+                            </div>
+                            <div class="flex-1 overflow-y-auto custom-scrollbar p-3">
+                                {render_column_body(result.as_ref(), index)}
+                            </div>
+                            <div class="flex items-center justify-between px-3 py-2 border-t border-gray-200 dark:border-gray-600 text-xs text-gray-600 dark:text-gray-300">
+                                // Latency and token counts can hint at the model, so they wait for the vote too
+                                {if revealed {
+                                    render_column_stats(result.as_ref())
+                                } else {
+                                    html! { <span>{"stats hidden until you vote"}</span> }
+                                }}
+                                {if can_vote {
+                                    html! {
+                                        <button
+                                            onclick={on_vote_column}
+                                            class="px-2 py-1 bg-primary-600 hover:bg-primary-700 text-white rounded"
+                                            aria-label={format!("Vote for {}", label)}
+                                        >
+                                            <i class="fas fa-thumbs-up mr-1" aria-hidden="true"></i>{"Better"}
+                                        </button>
+                                    }
+                                } else {
+                                    html! {}
+                                }}
+                            </div>
+                        </section>
+                    }
+                })}
+            </div>
+
+            <div class="p-4 border-t border-gray-200 dark:border-gray-700">
+                <div class="flex items-end border border-gray-300 dark:border-gray-500 rounded-lg bg-white dark:bg-gray-800 p-2">
+                    <textarea
+                        class="flex-1 resize-none border-0 focus:ring-0 bg-transparent p-2 text-sm text-gray-900 dark:text-gray-100"
+                        rows="3"
+                        placeholder="Prompt to send to every model..."
+                        aria-label="Comparison prompt"
+                        style="outline: none;"
+                        value={(*prompt).clone()}
+                        oninput={on_prompt_input}
+                        onkeydown={on_prompt_keydown}
+                        disabled={is_running}
+                    />
+                    <button
+                        onclick={on_run_click}
+                        disabled={is_running || prompt.trim().is_empty()}
+                        class="ml-2 px-4 py-2 bg-primary-600 hover:bg-primary-700 disabled:opacity-50 text-white rounded-md text-sm"
+                    >
+                        {if is_running {
+                            html! { <><i class="fas fa-spinner fa-spin mr-1" aria-hidden="true"></i>{"Running"}</> }
+                        } else {
+                            html! { <><i class="fas fa-play mr-1" aria-hidden="true"></i>{"Run"}</> }
+                        }}
+                    </button>
+                </div>
+                <div class="text-xs text-gray-600 dark:text-gray-300 mt-2">{"Ctrl+Enter to run"}</div>
+            </div>
+
+            {confirm.dialog()}
+        </div>
+    }
+}
+
+/// Anonymous column label used in blind rounds ("Model A", "Model B", ...)
+fn arena_label(index: usize) -> String {
+    format!("Model {}", (b'A' + index as u8) as char)
+}
+
+/// Shuffle the combos in place (Fisher-Yates)
+fn shuffle(items: &mut [(String, String)]) {
+    for i in (1..items.len()).rev() {
+        let j = (js_sys::Math::random() * (i + 1) as f64) as usize;
+        items.swap(i, j.min(i));
+    }
+}
+
+fn render_leaderboard(leaderboard: &ArenaLeaderboard, on_reset: Callback<MouseEvent>) -> Html {
+    let ranked = leaderboard.ranked();
+
+    html! {
+        <div class="mx-4 mt-4 p-3 border border-gray-200 dark:border-gray-600 rounded-lg max-h-64 overflow-y-auto custom-scrollbar">
+            <div class="flex justify-between items-center mb-2">
+                <h3 class="text-sm font-semibold text-gray-900 dark:text-gray-100">{"Arena Leaderboard"}</h3>
+                {if ranked.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <button
+                            onclick={on_reset}
+                            class="text-xs text-red-600 dark:text-red-400 hover:text-red-800 dark:hover:text-red-300"
+                        >
+                            {"Reset"}
+                        </button>
+                    }
+                }}
+            </div>
+            {if ranked.is_empty() {
+                html! {
+                    <div class="text-sm text-gray-500 dark:text-gray-400">
+                        {"No votes yet. Turn on blind arena, run a prompt and pick the better answer."}
+                    </div>
+                }
+            } else {
+                html! {
+                    <table class="w-full text-sm text-left text-gray-700 dark:text-gray-300">
+                        <thead class="text-xs uppercase text-gray-500 dark:text-gray-400">
+                            <tr>
+                                <th class="py-1">{"#"}</th>
+                                <th class="py-1">{"Model"}</th>
+                                <th class="py-1 text-right">{"Win rate"}</th>
+                                <th class="py-1 text-right">{"W / L / T"}</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {for ranked.iter().enumerate().map(|(rank, (key, record))| {
+                                let name = key.replacen(',', " / ", 1);
+                                html! {
+                                    <tr key={key.to_string()} class="border-t border-gray-100 dark:border-gray-700">
+                                        <td class="py-1">{rank + 1}</td>
+                                        <td class="py-1 truncate">{name}</td>
+                                        <td class="py-1 text-right">{format!("{:.0}%", record.win_rate() * 100.0)}</td>
+                                        <td class="py-1 text-right">
+                                            {format!("{} / {} / {}", record.wins, record.losses, record.ties)}
+                                        </td>
+                                    </tr>
+                                }
+                            })}
+                        </tbody>
+                    </table>
+                }
+            }}
+        </div>
+    }
+}
+
+fn render_column_body(result: Option<&ColumnResult>, index: usize) -> Html {
+    match result {
+        None => html! {
+            <div class="text-sm text-gray-500 dark:text-gray-400">{"No response yet"}</div>
+        },
+        Some(ColumnResult::Running) => html! {
+            <div class="flex items-center space-x-2">
+                <div class="animate-spin rounded-full h-4 w-4 border-b-2 border-purple-600"></div>
+                <span class="text-sm text-gray-500 dark:text-gray-400">{"Thinking..."}</span>
+            </div>
+        },
+        Some(ColumnResult::Done { content, .. }) => html! {
+            <MessageBubble
+                message={Message {
+                    id: format!("compare_result_{}", index),
+                    timestamp: js_sys::Date::now(),
+                    ..Message::new(MessageRole::Assistant, content.clone())
+                }}
+            />
+        },
+        Some(ColumnResult::Failed { error, .. }) => html! {
+            <div class="text-sm text-red-600 dark:text-red-400 whitespace-pre-wrap" role="alert">{error}</div>
+        },
+    }
+}
+
+fn render_column_stats(result: Option<&ColumnResult>) -> Html {
+    match result {
+        Some(ColumnResult::Done {
+            usage, latency_ms, ..
+        }) => {
+            let seconds = latency_ms / 1000.0;
+            let tokens = match usage {
+                Some(usage) => {
+                    let per_second = if seconds > 0.0 {
+                        usage.completion_tokens as f64 / seconds
+                    } else {
+                        0.0
+                    };
+                    // Providers such as Groq report their queue and generation time
+                    let reported = match (usage.queue_time, usage.completion_time) {
+                        (queue, Some(generation)) if generation > 0.0 => format!(
+                            " · generation {:.0} tok/s{}",
+                            usage.completion_tokens as f64 / generation,
+                            queue
+                                .map(|queue| format!(" · queue {:.0}ms", queue * 1000.0))
+                                .unwrap_or_default()
+                        ),
+                        _ => String::new(),
+                    };
+                    format!(
+                        "{} in · {} out · {:.1} tok/s{}",
+                        usage.prompt_tokens, usage.completion_tokens, per_second, reported
+                    )
+                }
+                None => "tokens not reported".to_string(),
+            };
+            html! { <span>{format!("{:.2}s · {}", seconds, tokens)}</span> }
+        }
+        Some(ColumnResult::Failed { latency_ms, .. }) => {
+            html! { <span>{format!("failed after {:.2}s", latency_ms / 1000.0)}</span> }
+        }
+        Some(ColumnResult::Running) => html! { <span>{"waiting for response..."}</span> },
+        None => html! { <span>{"—"}</span> },
+    }
+}
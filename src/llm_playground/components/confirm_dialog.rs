@@ -0,0 +1,219 @@
+use crate::llm_playground::hooks::use_focus_trap;
+use gloo_storage::{LocalStorage, Storage};
+use std::collections::HashSet;
+use yew::prelude::*;
+
+const STORAGE_KEY_SKIPPED_CONFIRMATIONS: &str = "llm_playground_skipped_confirmations";
+
+/// Destructive actions that ask for confirmation. Each kind remembers its own
+/// "don't ask again" choice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConfirmAction {
+    DeleteSession,
+    DeleteSessions,
+    DeleteProvider,
+    DeleteFunctionTool,
+    RemoveMcpServer,
+    ResetLeaderboard,
+    DeleteEvalSuite,
+    DeleteKnowledgeDocument,
+    DeleteStructuredOutput,
+    DeletePersona,
+    ScrubSecrets,
+    DiscardStoredData,
+    ClearSessionDatabase,
+}
+
+impl ConfirmAction {
+    pub const ALL: [ConfirmAction; 13] = [
+        ConfirmAction::DeleteSession,
+        ConfirmAction::DeleteSessions,
+        ConfirmAction::DeleteProvider,
+        ConfirmAction::DeleteFunctionTool,
+        ConfirmAction::RemoveMcpServer,
+        ConfirmAction::ResetLeaderboard,
+        ConfirmAction::DeleteEvalSuite,
+        ConfirmAction::DeleteKnowledgeDocument,
+        ConfirmAction::DeleteStructuredOutput,
+        ConfirmAction::DeletePersona,
+        ConfirmAction::ScrubSecrets,
+        ConfirmAction::DiscardStoredData,
+        ConfirmAction::ClearSessionDatabase,
+    ];
+
+    fn storage_id(&self) -> &'static str {
+        match self {
+            ConfirmAction::DeleteSession => "delete_session",
+            ConfirmAction::DeleteSessions => "delete_sessions",
+            ConfirmAction::DeleteProvider => "delete_provider",
+            ConfirmAction::DeleteFunctionTool => "delete_function_tool",
+            ConfirmAction::RemoveMcpServer => "remove_mcp_server",
+            ConfirmAction::ResetLeaderboard => "reset_leaderboard",
+            ConfirmAction::DeleteEvalSuite => "delete_eval_suite",
+            ConfirmAction::DeleteKnowledgeDocument => "delete_knowledge_document",
+            ConfirmAction::DeleteStructuredOutput => "delete_structured_output",
+            ConfirmAction::DeletePersona => "delete_persona",
+            ConfirmAction::ScrubSecrets => "scrub_secrets",
+            ConfirmAction::DiscardStoredData => "discard_stored_data",
+            ConfirmAction::ClearSessionDatabase => "clear_session_database",
+        }
+    }
+
+    /// Whether the user opted out of confirming this action
+    pub fn is_skipped(&self) -> bool {
+        skipped_confirmations().contains(self.storage_id())
+    }
+
+    pub fn skip_from_now_on(&self) {
+        let mut skipped = skipped_confirmations();
+        skipped.insert(self.storage_id().to_string());
+        let _ = LocalStorage::set(STORAGE_KEY_SKIPPED_CONFIRMATIONS, skipped);
+    }
+}
+
+fn skipped_confirmations() -> HashSet<String> {
+    LocalStorage::get(STORAGE_KEY_SKIPPED_CONFIRMATIONS).unwrap_or_default()
+}
+
+/// True when any confirmation has been turned off
+pub fn has_skipped_confirmations() -> bool {
+    !skipped_confirmations().is_empty()
+}
+
+/// Turn every "don't ask again" choice back off
+pub fn reset_skipped_confirmations() {
+    LocalStorage::delete(STORAGE_KEY_SKIPPED_CONFIRMATIONS);
+}
+
+/// What to ask the user
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfirmRequest {
+    pub action: ConfirmAction,
+    pub title: String,
+    pub message: String,
+    pub confirm_label: String,
+}
+
+impl ConfirmRequest {
+    pub fn new(
+        action: ConfirmAction,
+        title: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            action,
+            title: title.into(),
+            message: message.into(),
+            confirm_label: "Delete".to_string(),
+        }
+    }
+
+    pub fn with_confirm_label(mut self, label: impl Into<String>) -> Self {
+        self.confirm_label = label.into();
+        self
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ConfirmDialogProps {
+    pub request: ConfirmRequest,
+    /// Emits whether "don't ask again" was ticked
+    pub on_confirm: Callback<bool>,
+    pub on_cancel: Callback<()>,
+}
+
+#[function_component(ConfirmDialog)]
+pub fn confirm_dialog(props: &ConfirmDialogProps) -> Html {
+    let dont_ask_again = use_state(|| false);
+    let dialog_ref = use_node_ref();
+    use_focus_trap(dialog_ref.clone(), true, props.on_cancel.clone());
+
+    let on_toggle_dont_ask = {
+        let dont_ask_again = dont_ask_again.clone();
+        Callback::from(move |_: Event| dont_ask_again.set(!*dont_ask_again))
+    };
+
+    let on_confirm = {
+        let callback = props.on_confirm.clone();
+        let dont_ask_again = dont_ask_again.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(*dont_ask_again))
+    };
+
+    let on_cancel = {
+        let callback = props.on_cancel.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div
+                ref={dialog_ref}
+                role="alertdialog"
+                aria-modal="true"
+                aria-labelledby="confirm-dialog-title"
+                aria-describedby="confirm-dialog-message"
+                class="bg-white dark:bg-gray-800 rounded-lg shadow-xl max-w-sm w-full mx-4 p-6"
+            >
+                <div class="flex items-start mb-4">
+                    <div class="w-10 h-10 rounded-full bg-red-100 dark:bg-red-900/40 flex items-center justify-center mr-3 flex-shrink-0">
+                        <i class="fas fa-exclamation-triangle text-red-600 dark:text-red-400" aria-hidden="true"></i>
+                    </div>
+                    <div>
+                        <h2 id="confirm-dialog-title" class="text-lg font-semibold text-gray-900 dark:text-white">
+                            {&props.request.title}
+                        </h2>
+                        <p id="confirm-dialog-message" class="mt-1 text-sm text-gray-600 dark:text-gray-300">
+                            {&props.request.message}
+                        </p>
+                    </div>
+                </div>
+
+                <label class="flex items-center space-x-2 mb-6 text-sm text-gray-700 dark:text-gray-300">
+                    <input
+                        type="checkbox"
+                        checked={*dont_ask_again}
+                        onchange={on_toggle_dont_ask}
+                        class="rounded"
+                    />
+                    <span>{"Don't ask again"}</span>
+                </label>
+
+                <div class="flex justify-end space-x-3">
+                    <button
+                        onclick={on_cancel}
+                        class="px-4 py-2 text-sm text-gray-700 dark:text-gray-300 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-md"
+                    >
+                        {"Cancel"}
+                    </button>
+                    <button
+                        onclick={on_confirm}
+                        class="px-4 py-2 text-sm text-white bg-red-600 hover:bg-red-700 rounded-md"
+                    >
+                        {&props.request.confirm_label}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_action_remembers_its_own_choice() {
+        let ids: HashSet<_> = ConfirmAction::ALL.iter().map(ConfirmAction::storage_id).collect();
+        assert_eq!(ids.len(), ConfirmAction::ALL.len());
+    }
+
+    #[test]
+    fn requests_default_to_a_delete_button() {
+        let request =
+            ConfirmRequest::new(ConfirmAction::DeleteSession, "Delete session?", "It can't be undone.");
+        assert_eq!(request.confirm_label, "Delete");
+        let request = request.with_confirm_label("Reset");
+        assert_eq!(request.confirm_label, "Reset");
+        assert_eq!(request.title, "Delete session?");
+    }
+}
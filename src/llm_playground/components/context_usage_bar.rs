@@ -0,0 +1,86 @@
+// Stacked bar above the input bar showing what the next request fills the
+// context window with; clicking it lists the estimated tokens of each part
+use crate::llm_playground::{
+    context_usage::{ContextPart, ContextUsage},
+    provider_config::format_token_count,
+};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ContextUsageBarProps {
+    pub usage: ContextUsage,
+}
+
+fn color(part: ContextPart) -> &'static str {
+    match part {
+        ContextPart::SystemPrompt => "bg-yellow-400",
+        ContextPart::Tools => "bg-orange-400",
+        ContextPart::History => "bg-primary-500",
+        ContextPart::ToolResults => "bg-green-500",
+        ContextPart::Reply => "bg-gray-300 dark:bg-gray-500",
+    }
+}
+
+#[function_component(ContextUsageBar)]
+pub fn context_usage_bar(props: &ContextUsageBarProps) -> Html {
+    let expanded = use_state(|| false);
+    let usage = &props.usage;
+    let total = format_token_count(usage.total() as u32);
+    let summary = match usage.window {
+        Some(window) => format!(
+            "≈{} of {} tokens ({:.0}%)",
+            total,
+            format_token_count(window),
+            usage.total() as f64 * 100.0 / window.max(1) as f64
+        ),
+        None => format!("≈{} tokens · context window unknown", total),
+    };
+    let on_toggle = {
+        let expanded = expanded.clone();
+        Callback::from(move |_: MouseEvent| expanded.set(!*expanded))
+    };
+
+    html! {
+        <div class="px-4 pt-2 border-t border-gray-200 dark:border-gray-700">
+            <button
+                onclick={on_toggle}
+                class="w-full flex items-center gap-2 text-xs text-gray-500 dark:text-gray-400"
+                aria-expanded={expanded.to_string()}
+                title="What the next request fills the context window with"
+            >
+                <div class="flex-1 flex h-2 rounded overflow-hidden bg-gray-100 dark:bg-gray-700" role="img" aria-label={summary.clone()}>
+                    {for usage.parts.iter().filter(|(_, tokens)| *tokens > 0).map(|(part, tokens)| html! {
+                        <div
+                            class={color(*part)}
+                            style={format!("width: {:.2}%", usage.share(*tokens) * 100.0)}
+                            title={format!("{}: ≈{} tokens", part.label(), tokens)}
+                        ></div>
+                    })}
+                </div>
+                <span class={classes!("shrink-0", usage.exceeds_window().then_some("text-red-600 dark:text-red-400"))}>
+                    {if usage.exceeds_window() {
+                        html! { <i class="fas fa-exclamation-triangle mr-1" aria-hidden="true"></i> }
+                    } else {
+                        html! {}
+                    }}
+                    {summary}
+                </span>
+            </button>
+            {if *expanded {
+                html! {
+                    <ul class="mt-1 grid grid-cols-2 sm:grid-cols-5 gap-x-3 gap-y-0.5 text-xs text-gray-600 dark:text-gray-300">
+                        {for usage.parts.iter().map(|(part, tokens)| html! {
+                            <li class="flex items-center gap-1">
+                                <span class={classes!("w-2", "h-2", "rounded-sm", "shrink-0", color(*part))} aria-hidden="true"></span>
+                                <span class="truncate">{part.label()}</span>
+                                <span class="ml-auto font-mono">{format!("≈{}", tokens)}</span>
+                            </li>
+                        })}
+                    </ul>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,148 @@
+// Read-only page for `#/view` links: shows the shared conversation, or a box
+// to paste a link into when the route has no payload
+use crate::llm_playground::{
+    components::ChatRoom,
+    shared_view::{self, SharedConversation},
+};
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ConversationViewerProps {
+    /// Payload from the link that opened the viewer
+    #[prop_or_default]
+    pub payload: Option<String>,
+}
+
+#[function_component(ConversationViewer)]
+pub fn conversation_viewer(props: &ConversationViewerProps) -> Html {
+    let conversation = use_state(|| Option::<SharedConversation>::None);
+    let error = use_state(|| Option::<String>::None);
+    let is_loading = use_state(|| false);
+    let pasted = use_state(String::new);
+
+    let open = {
+        let conversation = conversation.clone();
+        let error = error.clone();
+        let is_loading = is_loading.clone();
+        Callback::from(move |payload: String| {
+            let conversation = conversation.clone();
+            let error = error.clone();
+            let is_loading = is_loading.clone();
+            is_loading.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                match shared_view::decode(&payload).await {
+                    Ok(shared) => {
+                        conversation.set(Some(shared));
+                        error.set(None);
+                    }
+                    Err(message) => error.set(Some(message)),
+                }
+                is_loading.set(false);
+            });
+        })
+    };
+
+    {
+        let open = open.clone();
+        use_effect_with(props.payload.clone(), move |payload| {
+            if let Some(payload) = payload {
+                open.emit(payload.clone());
+            }
+            || ()
+        });
+    }
+
+    let on_paste_input = {
+        let pasted = pasted.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            pasted.set(input.value());
+        })
+    };
+    let on_open_pasted = {
+        let pasted = pasted.clone();
+        let error = error.clone();
+        Callback::from(move |_: MouseEvent| match shared_view::payload_from(&pasted) {
+            Some(payload) => open.emit(payload.to_string()),
+            None => error.set(Some("Paste a viewer link or its payload first".to_string())),
+        })
+    };
+
+    let app_url = web_sys::window()
+        .and_then(|window| window.location().pathname().ok())
+        .unwrap_or_else(|| "/".to_string());
+
+    let body = match &*conversation {
+        Some(shared) => html! {
+            <ChatRoom session={Some(shared.to_session())} is_loading={false} show_message_footer={true} />
+        },
+        None => html! {
+            <div class="flex-1 overflow-y-auto p-6">
+                <div class="max-w-2xl mx-auto space-y-3">
+                    <label for="shared-payload" class="block text-sm text-gray-700 dark:text-gray-300">
+                        {"Paste a shared conversation link or its payload"}
+                    </label>
+                    <textarea
+                        id="shared-payload"
+                        rows="4"
+                        value={(*pasted).clone()}
+                        oninput={on_paste_input}
+                        class="w-full p-2 text-sm font-mono border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-gray-100"
+                    />
+                    <button
+                        onclick={on_open_pasted}
+                        disabled={*is_loading}
+                        class="px-4 py-2 text-sm rounded-md bg-primary-600 text-white hover:bg-primary-700 disabled:opacity-50"
+                    >
+                        {if *is_loading { "Opening..." } else { "Open" }}
+                    </button>
+                </div>
+            </div>
+        },
+    };
+
+    html! {
+        <div class="flex flex-col h-screen bg-white dark:bg-gray-900">
+            <header class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
+                <div>
+                    <h2 class="font-semibold text-gray-900 dark:text-gray-100">
+                        {conversation.as_ref().map_or("Shared conversation".to_string(), |shared| shared.title.clone())}
+                        <span class="ml-2 px-2 py-0.5 text-xs font-normal rounded-full bg-gray-100 dark:bg-gray-700 text-gray-600 dark:text-gray-300">
+                            <i class="fas fa-eye mr-1" aria-hidden="true"></i>
+                            {"Read-only"}
+                        </span>
+                    </h2>
+                    <div class="text-sm text-gray-600 dark:text-gray-300">
+                        {match &*conversation {
+                            Some(shared) => format!("{} messages", shared.messages.len()),
+                            None => "Shared from LLM Playground".to_string(),
+                        }}
+                    </div>
+                </div>
+                <a href={app_url} class="text-sm text-primary-600 dark:text-primary-400 hover:underline">
+                    {"Open the playground"}
+                </a>
+            </header>
+            {match &*error {
+                Some(message) => html! {
+                    <div class="px-4 py-2 text-sm text-red-700 dark:text-red-300 bg-red-50 dark:bg-red-900/20" role="alert">
+                        <i class="fas fa-exclamation-circle mr-2" aria-hidden="true"></i>
+                        {message.clone()}
+                    </div>
+                },
+                None => html! {},
+            }}
+            {if *is_loading && conversation.is_none() && props.payload.is_some() {
+                html! {
+                    <div class="flex-1 flex items-center justify-center text-gray-500 dark:text-gray-400">
+                        <i class="fas fa-spinner fa-spin mr-2" aria-hidden="true"></i>
+                        {"Opening conversation..."}
+                    </div>
+                }
+            } else {
+                body
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,330 @@
+// Diagnostics: the kept log lines with level, target and text filters, the
+// log filter setting, downloads of the logs and an anonymized environment
+// report for bug reports, and the history scrubber
+use super::notification::{NotificationMessage, NotificationType};
+use crate::llm_playground::{
+    app_state::use_app_state,
+    event_bus::PlaygroundEvent,
+    hooks::use_event_bus,
+    html_export,
+    logging::{self, LogEntry, LogLevel},
+};
+use gloo_timers::callback::Interval;
+use std::collections::BTreeSet;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// How often the view checks for new log lines
+const REFRESH_MS: u32 = 1_000;
+
+#[derive(Properties, PartialEq)]
+pub struct DiagnosticsViewProps {
+    /// Included in the environment report
+    pub session_count: usize,
+    /// Replace secrets in stored sessions; true also applies the Sensitive
+    /// Data rules
+    pub on_scrub_secrets: Callback<bool>,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(DiagnosticsView)]
+pub fn diagnostics_view(props: &DiagnosticsViewProps) -> Html {
+    let app_state = use_app_state();
+    let on_notification = use_event_bus().callback(PlaygroundEvent::Notify);
+    let entries = use_state(logging::entries);
+    let min_level = use_state(|| LogLevel::Debug);
+    let target = use_state(String::new);
+    let query = use_state(String::new);
+    let filter_spec = use_state(logging::filter_spec);
+    let filter_error = use_state(|| Option::<String>::None);
+    let scrub_with_rules = use_state(|| false);
+
+    // Pick up lines written since the last refresh
+    {
+        let entries = entries.clone();
+        use_effect_with((), move |_| {
+            let mut seen = logging::generation();
+            let interval = Interval::new(REFRESH_MS, move || {
+                let generation = logging::generation();
+                if generation != seen {
+                    seen = generation;
+                    entries.set(logging::entries());
+                }
+            });
+            move || drop(interval)
+        });
+    }
+
+    let targets: BTreeSet<&str> = entries.iter().map(|e| e.target.as_str()).collect();
+    let needle = query.trim().to_lowercase();
+    let visible: Vec<&LogEntry> = entries
+        .iter()
+        .rev()
+        .filter(|e| e.level >= *min_level)
+        .filter(|e| target.is_empty() || e.target == *target)
+        .filter(|e| needle.is_empty() || e.message.to_lowercase().contains(&needle))
+        .collect();
+
+    let on_level_change = {
+        let min_level = min_level.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some(level) = LogLevel::parse(&input.value()) {
+                min_level.set(level);
+            }
+        })
+    };
+    let on_target_change = {
+        let target = target.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            target.set(input.value());
+        })
+    };
+    let on_query_input = {
+        let query = query.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            query.set(input.value());
+        })
+    };
+    let on_filter_input = {
+        let filter_spec = filter_spec.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            filter_spec.set(input.value());
+        })
+    };
+    let on_apply_filter = {
+        let filter_spec = filter_spec.clone();
+        let filter_error = filter_error.clone();
+        let on_notification = on_notification.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            match logging::set_filter(&filter_spec) {
+                Ok(()) => {
+                    filter_error.set(None);
+                    on_notification.emit(NotificationMessage::new(
+                        "Log filter saved".to_string(),
+                        NotificationType::Success,
+                    ));
+                }
+                Err(error) => filter_error.set(Some(error)),
+            }
+        })
+    };
+    let on_clear = {
+        let entries = entries.clone();
+        Callback::from(move |_: MouseEvent| {
+            logging::clear();
+            entries.set(Vec::new());
+        })
+    };
+
+    let download = {
+        let on_notification = on_notification.clone();
+        move |file_name: &'static str, content: String| {
+            let notification = match html_export::download_file(file_name, &content, "text/plain") {
+                Ok(()) => NotificationMessage::new(
+                    format!("Saved {}", file_name),
+                    NotificationType::Success,
+                ),
+                Err(error) => NotificationMessage::new(
+                    format!("Download failed: {}", error),
+                    NotificationType::Error,
+                ),
+            };
+            on_notification.emit(notification);
+        }
+    };
+    let on_download_logs = {
+        let download = download.clone();
+        let config = app_state.config.clone();
+        Callback::from(move |_: MouseEvent| {
+            download("llm-playground-logs.txt", logging::export_logs(&config));
+        })
+    };
+    let on_download_report = {
+        let config = app_state.config.clone();
+        let session_count = props.session_count;
+        Callback::from(move |_: MouseEvent| {
+            download(
+                "llm-playground-environment.md",
+                logging::environment_report(&config, session_count),
+            );
+        })
+    };
+    let on_copy_report = {
+        let config = app_state.config.clone();
+        let session_count = props.session_count;
+        let on_notification = on_notification.clone();
+        Callback::from(move |_: MouseEvent| {
+            let report = logging::environment_report(&config, session_count);
+            let on_notification = on_notification.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let notification = match html_export::copy_to_clipboard(&report).await {
+                    Ok(()) => NotificationMessage::new(
+                        "Environment report copied".to_string(),
+                        NotificationType::Success,
+                    ),
+                    Err(error) => NotificationMessage::new(error, NotificationType::Error),
+                };
+                on_notification.emit(notification);
+            });
+        })
+    };
+
+    let on_scrub_rules_change = {
+        let scrub_with_rules = scrub_with_rules.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            scrub_with_rules.set(input.checked());
+        })
+    };
+    let on_scrub = {
+        let callback = props.on_scrub_secrets.clone();
+        let scrub_with_rules = scrub_with_rules.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(*scrub_with_rules))
+    };
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    let input_class = "p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 text-sm";
+    let button_class = "text-sm px-3 py-2 bg-gray-100 dark:bg-gray-700 text-gray-900 dark:text-gray-100 rounded-md hover:bg-gray-200 dark:hover:bg-gray-600";
+
+    html! {
+        <div class="flex-1 flex flex-col min-w-0 bg-white dark:bg-gray-800">
+            <header class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
+                <div>
+                    <h2 class="font-semibold text-gray-900 dark:text-gray-100">{"Diagnostics"}</h2>
+                    <div class="text-sm text-gray-600 dark:text-gray-300">
+                        {format!("The last {} log lines of this tab. Downloads hide API keys and URLs.", logging::LOG_CAPACITY)}
+                    </div>
+                </div>
+                <button
+                    onclick={on_close}
+                    class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                    aria-label="Close diagnostics"
+                    title="Back to chat"
+                >
+                    <i class="fas fa-times" aria-hidden="true"></i>
+                </button>
+            </header>
+
+            <div class="p-3 border-b border-gray-200 dark:border-gray-600 flex flex-wrap items-center gap-2">
+                <select aria-label="Minimum level" onchange={on_level_change} class={input_class}>
+                    {for LogLevel::ALL.iter().map(|level| html! {
+                        <option value={level.as_str()} selected={*min_level == *level}>
+                            {format!("{} and above", level.as_str())}
+                        </option>
+                    })}
+                </select>
+                <select aria-label="Target" onchange={on_target_change} class={input_class}>
+                    <option value="" selected={target.is_empty()}>{"All targets"}</option>
+                    {for targets.iter().map(|name| html! {
+                        <option value={name.to_string()} selected={*target == *name}>{*name}</option>
+                    })}
+                </select>
+                <input
+                    type="search"
+                    aria-label="Search log messages"
+                    placeholder="Search messages"
+                    value={(*query).clone()}
+                    oninput={on_query_input}
+                    class={classes!(input_class, "flex-1", "min-w-[10rem]")}
+                />
+                <button onclick={on_clear} class={button_class}>
+                    <i class="fas fa-eraser mr-1" aria-hidden="true"></i>{"Clear"}
+                </button>
+                <button onclick={on_download_logs} class={button_class}>
+                    <i class="fas fa-download mr-1" aria-hidden="true"></i>{"Logs"}
+                </button>
+                <button onclick={on_download_report} class={button_class}>
+                    <i class="fas fa-file-lines mr-1" aria-hidden="true"></i>{"Environment report"}
+                </button>
+                <button onclick={on_copy_report} class={button_class} aria-label="Copy environment report" title="Copy environment report">
+                    <i class="fas fa-copy" aria-hidden="true"></i>
+                </button>
+            </div>
+
+            <form onsubmit={on_apply_filter} class="p-3 border-b border-gray-200 dark:border-gray-600 flex flex-wrap items-center gap-2">
+                <label for="log-filter" class="text-sm text-gray-700 dark:text-gray-300">{"Log filter"}</label>
+                <input
+                    id="log-filter"
+                    type="text"
+                    placeholder={logging::DEFAULT_LOG_FILTER}
+                    value={(*filter_spec).clone()}
+                    oninput={on_filter_input}
+                    aria-describedby="log-filter-help"
+                    class={classes!(input_class, "font-mono", "flex-1")}
+                />
+                <button type="submit" class="text-sm px-3 py-2 bg-primary-600 text-white rounded-md hover:bg-primary-700">
+                    {"Apply"}
+                </button>
+                <p id="log-filter-help" class="w-full text-xs text-gray-500 dark:text-gray-400">
+                    {"A default level and per-target levels, e.g. "}
+                    <code>{"info,api_clients=debug,components::chatroom=warn"}</code>
+                </p>
+                {if let Some(error) = &*filter_error {
+                    html! { <p class="w-full text-xs text-red-600 dark:text-red-400" role="alert">{error}</p> }
+                } else {
+                    html! {}
+                }}
+            </form>
+
+            <div class="p-3 border-b border-gray-200 dark:border-gray-600 flex flex-wrap items-center gap-2">
+                <button onclick={on_scrub} class={button_class}>
+                    <i class="fas fa-broom mr-1" aria-hidden="true"></i>{"Scrub secrets from history"}
+                </button>
+                <label class="flex items-center text-sm text-gray-700 dark:text-gray-300">
+                    <input type="checkbox" checked={*scrub_with_rules} onchange={on_scrub_rules_change} class="mr-2" />
+                    {"Also apply the Sensitive Data rules"}
+                </label>
+                <p class="w-full text-xs text-gray-500 dark:text-gray-400">
+                    {"Replaces the configured API keys and MCP header values in every session, snapshot and recording with [REDACTED]."}
+                </p>
+            </div>
+
+            <div class="flex-1 overflow-y-auto custom-scrollbar p-2 font-mono text-xs" role="log" aria-label="Log lines">
+                {if visible.is_empty() {
+                    html! {
+                        <p class="p-2 text-sm font-sans text-gray-600 dark:text-gray-400">{"No log lines match."}</p>
+                    }
+                } else {
+                    html! {
+                        {for visible.into_iter().map(render_entry)}
+                    }
+                }}
+            </div>
+        </div>
+    }
+}
+
+fn render_entry(entry: &LogEntry) -> Html {
+    let level_class = match entry.level {
+        LogLevel::Debug => "text-gray-500 dark:text-gray-400",
+        LogLevel::Info => "text-blue-600 dark:text-blue-400",
+        LogLevel::Warn => "text-yellow-600 dark:text-yellow-400",
+        LogLevel::Error => "text-red-600 dark:text-red-400",
+    };
+    let time = js_sys::Date::new(&entry.timestamp.into());
+    html! {
+        <div key={entry.id} class="flex gap-2 px-2 py-1 border-b border-gray-100 dark:border-gray-700">
+            <span class="shrink-0 text-gray-500 dark:text-gray-400">
+                {format!(
+                    "{:02}:{:02}:{:02}.{:03}",
+                    time.get_hours(),
+                    time.get_minutes(),
+                    time.get_seconds(),
+                    time.get_milliseconds()
+                )}
+            </span>
+            <span class={classes!("shrink-0", "w-12", "uppercase", level_class)}>{entry.level.as_str()}</span>
+            <span class="shrink-0 text-gray-600 dark:text-gray-300">{&entry.target}</span>
+            <span class="whitespace-pre-wrap break-all text-gray-900 dark:text-gray-100">{&entry.message}</span>
+        </div>
+    }
+}
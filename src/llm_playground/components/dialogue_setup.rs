@@ -0,0 +1,234 @@
+use crate::llm_playground::dialogue::{
+    DialogueConfig, DialogueParticipant, DEFAULT_DIALOGUE_TURNS, MAX_DIALOGUE_TURNS,
+};
+use crate::llm_playground::hooks::use_focus_trap;
+use crate::llm_playground::provider_config::FlexibleApiConfig;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct DialogueSetupProps {
+    pub config: FlexibleApiConfig,
+    pub on_start: Callback<DialogueConfig>,
+    pub on_cancel: Callback<()>,
+}
+
+/// Modal for starting a two-model dialogue session
+#[function_component(DialogueSetup)]
+pub fn dialogue_setup(props: &DialogueSetupProps) -> Html {
+    let combos: Vec<String> = props
+        .config
+        .providers
+        .iter()
+        .flat_map(|p| p.models.iter().map(move |m| format!("{},{}", p.name, m)))
+        .collect();
+
+    let setup = use_state(|| {
+        let (provider, model) = props.config.get_current_provider_and_model();
+        let first = format!("{},{}", provider, model);
+        let second = combos
+            .iter()
+            .find(|combo| **combo != first)
+            .cloned()
+            .unwrap_or_else(|| first.clone());
+        DialogueConfig {
+            participants: vec![
+                DialogueParticipant {
+                    name: "Speaker A".to_string(),
+                    model: first,
+                    system_prompt: String::new(),
+                },
+                DialogueParticipant {
+                    name: "Speaker B".to_string(),
+                    model: second,
+                    system_prompt: String::new(),
+                },
+            ],
+            topic: String::new(),
+            max_turns: DEFAULT_DIALOGUE_TURNS,
+        }
+    });
+    let error = use_state(|| Option::<String>::None);
+
+    let dialog_ref = use_node_ref();
+    use_focus_trap(dialog_ref.clone(), true, props.on_cancel.clone());
+
+    let on_topic_input = {
+        let setup = setup.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            let mut next = (*setup).clone();
+            next.topic = input.value();
+            setup.set(next);
+        })
+    };
+
+    let on_turns_input = {
+        let setup = setup.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(turns) = input.value().parse::<u32>() {
+                let mut next = (*setup).clone();
+                next.max_turns = turns;
+                setup.set(next);
+            }
+        })
+    };
+
+    let update_participant = {
+        let setup = setup.clone();
+        move |index: usize, field: &'static str| {
+            let setup = setup.clone();
+            Callback::from(move |value: String| {
+                let mut next = (*setup).clone();
+                if let Some(participant) = next.participants.get_mut(index) {
+                    match field {
+                        "name" => participant.name = value,
+                        "model" => participant.model = value,
+                        _ => participant.system_prompt = value,
+                    }
+                }
+                setup.set(next);
+            })
+        }
+    };
+
+    let on_start = {
+        let setup = setup.clone();
+        let error = error.clone();
+        let config = props.config.clone();
+        let on_start = props.on_start.clone();
+        Callback::from(move |_: MouseEvent| match setup.validate(&config) {
+            Ok(()) => on_start.emit((*setup).clone()),
+            Err(message) => error.set(Some(message)),
+        })
+    };
+
+    let on_cancel = {
+        let callback = props.on_cancel.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div
+                ref={dialog_ref}
+                role="dialog"
+                aria-modal="true"
+                aria-labelledby="dialogue-setup-title"
+                class="bg-white dark:bg-gray-800 rounded-lg shadow-xl max-w-3xl w-full mx-4 max-h-[90vh] overflow-y-auto"
+            >
+                <div class="p-6 space-y-4">
+                    <div class="flex items-center justify-between">
+                        <h2 id="dialogue-setup-title" class="text-xl font-semibold text-gray-900 dark:text-white">
+                            {"Start a Model Dialogue"}
+                        </h2>
+                        <button
+                            onclick={on_cancel.clone()}
+                            class="text-gray-400 hover:text-gray-600 dark:hover:text-gray-300"
+                            aria-label="Close"
+                        >
+                            <i class="fas fa-times text-lg" aria-hidden="true"></i>
+                        </button>
+                    </div>
+
+                    <div>
+                        <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="dialogue-topic">{"Topic"}</label>
+                        <textarea
+                            id="dialogue-topic"
+                            rows="3"
+                            value={setup.topic.clone()}
+                            oninput={on_topic_input}
+                            class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                            placeholder="e.g. Debate whether remote work is better for junior engineers"
+                        />
+                    </div>
+
+                    <div class="w-40">
+                        <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="dialogue-turns">{"Max turns"}</label>
+                        <input
+                            id="dialogue-turns"
+                            type="number"
+                            min="1"
+                            max={MAX_DIALOGUE_TURNS.to_string()}
+                            value={setup.max_turns.to_string()}
+                            oninput={on_turns_input}
+                            class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                        />
+                    </div>
+
+                    <div class="grid grid-cols-1 md:grid-cols-2 gap-4">
+                        {for setup.participants.iter().enumerate().map(|(index, participant)| {
+                            let on_name = update_participant(index, "name");
+                            let on_model = update_participant(index, "model");
+                            let on_prompt = update_participant(index, "system_prompt");
+                            html! {
+                                <fieldset class="p-4 border border-gray-200 dark:border-gray-600 rounded-md space-y-3">
+                                    <legend class="px-1 text-sm font-medium text-gray-700 dark:text-gray-300">
+                                        {if index == 0 { "Speaks first" } else { "Speaks second" }}
+                                    </legend>
+                                    <input
+                                        type="text"
+                                        aria-label="Participant name"
+                                        value={participant.name.clone()}
+                                        oninput={Callback::from(move |e: InputEvent| {
+                                            let input: HtmlInputElement = e.target_unchecked_into();
+                                            on_name.emit(input.value());
+                                        })}
+                                        class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                                    />
+                                    <select
+                                        aria-label="Participant model"
+                                        onchange={Callback::from(move |e: Event| {
+                                            let input: HtmlInputElement = e.target_unchecked_into();
+                                            on_model.emit(input.value());
+                                        })}
+                                        class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                                    >
+                                        {for combos.iter().map(|combo| html! {
+                                            <option value={combo.clone()} selected={*combo == participant.model}>
+                                                {combo.replace(',', " / ")}
+                                            </option>
+                                        })}
+                                    </select>
+                                    <textarea
+                                        rows="4"
+                                        aria-label="Participant system prompt"
+                                        value={participant.system_prompt.clone()}
+                                        oninput={Callback::from(move |e: InputEvent| {
+                                            let input: HtmlTextAreaElement = e.target_unchecked_into();
+                                            on_prompt.emit(input.value());
+                                        })}
+                                        class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 text-sm"
+                                        placeholder="System prompt, e.g. You argue in favour and cite evidence."
+                                    />
+                                </fieldset>
+                            }
+                        })}
+                    </div>
+
+                    {if let Some(message) = &*error {
+                        html! { <p class="text-sm text-red-600 dark:text-red-400" role="alert">{message}</p> }
+                    } else {
+                        html! {}
+                    }}
+
+                    <div class="flex justify-end space-x-3">
+                        <button
+                            onclick={on_cancel}
+                            class="px-4 py-2 text-sm text-gray-700 dark:text-gray-300 bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-md"
+                        >
+                            {"Cancel"}
+                        </button>
+                        <button
+                            onclick={on_start}
+                            class="px-4 py-2 text-sm text-white bg-primary-600 hover:bg-primary-700 rounded-md"
+                        >
+                            <i class="fas fa-comments mr-2" aria-hidden="true"></i>{"Start Dialogue"}
+                        </button>
+                    </div>
+                </div>
+            </div>
+        </div>
+    }
+}
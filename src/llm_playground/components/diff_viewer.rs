@@ -0,0 +1,111 @@
+// Word-level diff overlay between two assistant messages
+use crate::llm_playground::{
+    hooks::{use_focus_trap, use_offload},
+    offload::{OffloadJob, OffloadResult},
+    word_diff::{diff_stats, DiffOp},
+    Message,
+};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct DiffViewerProps {
+    /// Earlier message (shown as removed text)
+    pub original: Message,
+    /// Later message (shown as added text)
+    pub revised: Message,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(DiffViewer)]
+pub fn diff_viewer(props: &DiffViewerProps) -> Html {
+    let dialog_ref = use_node_ref();
+    use_focus_trap(dialog_ref.clone(), true, props.on_close.clone());
+
+    // Long replies are diffed in the background worker
+    let result = use_offload(OffloadJob::DiffWords {
+        old: props.original.content.clone(),
+        new: props.revised.content.clone(),
+    });
+    let ops: &[DiffOp] = match result.as_deref() {
+        Some(OffloadResult::Diff(ops)) => ops,
+        _ => &[],
+    };
+    let (removed, added) = diff_stats(ops);
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div
+                ref={dialog_ref}
+                role="dialog"
+                aria-modal="true"
+                aria-labelledby="diff-viewer-title"
+                class="bg-white dark:bg-gray-800 rounded-lg shadow-xl max-w-4xl w-full mx-4 max-h-[85vh] flex flex-col"
+            >
+                <div class="flex items-center justify-between p-4 border-b border-gray-200 dark:border-gray-600">
+                    <div>
+                        <h2 id="diff-viewer-title" class="text-lg font-semibold text-gray-900 dark:text-white">
+                            {"Response Diff"}
+                        </h2>
+                        <div class="text-xs text-gray-600 dark:text-gray-300">
+                            {format!(
+                                "{} → {} · ",
+                                format_time(props.original.timestamp),
+                                format_time(props.revised.timestamp)
+                            )}
+                            <span class="text-red-600 dark:text-red-400">{format!("-{} words", removed)}</span>
+                            {" / "}
+                            <span class="text-green-600 dark:text-green-400">{format!("+{} words", added)}</span>
+                        </div>
+                    </div>
+                    <button
+                        onclick={on_close}
+                        class="text-gray-400 hover:text-gray-600 dark:hover:text-gray-300"
+                        aria-label="Close diff"
+                    >
+                        <i class="fas fa-times text-lg" aria-hidden="true"></i>
+                    </button>
+                </div>
+
+                <div class="flex-1 overflow-y-auto custom-scrollbar p-4 text-sm leading-relaxed whitespace-pre-wrap text-gray-800 dark:text-gray-200">
+                    {if result.is_none() {
+                        html! {
+                            <div class="text-gray-500 dark:text-gray-400 italic mb-2" role="status">{"Comparing…"}</div>
+                        }
+                    } else if removed == 0 && added == 0 {
+                        html! {
+                            <div class="text-gray-500 dark:text-gray-400 italic mb-2">{"The two responses are identical."}</div>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    {render_diff_ops(ops)}
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Diff runs as inline text, removed runs struck through and added ones highlighted
+pub fn render_diff_ops(ops: &[DiffOp]) -> Html {
+    ops.iter()
+        .map(|op| match op {
+            DiffOp::Equal(text) => html! { <span>{text}</span> },
+            DiffOp::Removed(text) => html! {
+                <del class="bg-red-100 dark:bg-red-900/40 text-red-700 dark:text-red-300 line-through">{text}</del>
+            },
+            DiffOp::Added(text) => html! {
+                <ins class="bg-green-100 dark:bg-green-900/40 text-green-700 dark:text-green-300 no-underline">{text}</ins>
+            },
+        })
+        .collect()
+}
+
+fn format_time(timestamp: f64) -> String {
+    let date = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(timestamp));
+    format!("{:02}:{:02}", date.get_hours(), date.get_minutes())
+}
@@ -0,0 +1,672 @@
+// Prompt evaluation suites: edit test cases with assertions, run them against a
+// model and see which pass
+use crate::llm_playground::{
+    app_state::use_app_state,
+    components::{ConfirmAction, ConfirmRequest},
+    eval_suite::{self, Assertion, CaseResult, EvalSuite, TestCase},
+    hooks::use_confirm,
+};
+use std::collections::HashMap;
+use std::rc::Rc;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+type SuiteEdit = Box<dyn FnOnce(&mut EvalSuite)>;
+
+/// Results of the latest run, keyed by case id; None while a case is running
+#[derive(Clone, Debug, Default, PartialEq)]
+struct EvalRun {
+    run_id: u64,
+    results: HashMap<String, Option<CaseResult>>,
+}
+
+enum EvalAction {
+    Start {
+        run_id: u64,
+        case_ids: Vec<String>,
+    },
+    Finish {
+        run_id: u64,
+        case_id: String,
+        result: CaseResult,
+    },
+}
+
+impl Reducible for EvalRun {
+    type Action = EvalAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            EvalAction::Start { run_id, case_ids } => Rc::new(Self {
+                run_id,
+                results: case_ids.into_iter().map(|id| (id, None)).collect(),
+            }),
+            EvalAction::Finish {
+                run_id,
+                case_id,
+                result,
+            } => {
+                // Ignore late results from a previous run
+                if run_id != self.run_id {
+                    return self;
+                }
+                let mut next = (*self).clone();
+                next.results.insert(case_id, Some(result));
+                Rc::new(next)
+            }
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct EvalViewProps {
+    pub on_close: Callback<()>,
+}
+
+#[function_component(EvalView)]
+pub fn eval_view(props: &EvalViewProps) -> Html {
+    let app_state = use_app_state();
+    let combos = app_state.config.get_all_provider_models();
+    let suites = use_state(eval_suite::load_suites);
+    let selected = use_state(|| 0usize);
+    let run_model = use_state(|| app_state.config.get_current_provider_and_model());
+    let judge_model = use_state(|| Option::<(String, String)>::None);
+    let run = use_reducer(EvalRun::default);
+    let confirm = use_confirm();
+
+    let is_running = run.results.values().any(|r| r.is_none());
+    let suite = suites.get(*selected).cloned();
+
+    let edit_suite = {
+        let suites = suites.clone();
+        let selected = selected.clone();
+        Callback::from(move |edit: SuiteEdit| {
+            let mut next = (*suites).clone();
+            if let Some(suite) = next.get_mut(*selected) {
+                edit(suite);
+                eval_suite::save_suites(&next);
+                suites.set(next);
+            }
+        })
+    };
+
+    let on_new_suite = {
+        let suites = suites.clone();
+        let selected = selected.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut next = (*suites).clone();
+            next.push(EvalSuite {
+                id: format!("suite_{}", js_sys::Date::now() as u64),
+                name: format!("Suite {}", next.len() + 1),
+                ..Default::default()
+            });
+            eval_suite::save_suites(&next);
+            selected.set(next.len() - 1);
+            suites.set(next);
+        })
+    };
+
+    let on_delete_suite = {
+        let suites = suites.clone();
+        let selected = selected.clone();
+        let confirm = confirm.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(suite) = suites.get(*selected) else {
+                return;
+            };
+            let suites = suites.clone();
+            let selected = selected.clone();
+            confirm.confirm(
+                ConfirmRequest::new(
+                    ConfirmAction::DeleteEvalSuite,
+                    "Delete eval suite?",
+                    format!(
+                        "\"{}\" and its {} test cases will be permanently deleted.",
+                        suite.name,
+                        suite.cases.len()
+                    ),
+                ),
+                Callback::from(move |_| {
+                    let mut next = (*suites).clone();
+                    if *selected < next.len() {
+                        next.remove(*selected);
+                    }
+                    eval_suite::save_suites(&next);
+                    selected.set(0);
+                    suites.set(next);
+                }),
+            );
+        })
+    };
+
+    let on_select_suite = {
+        let selected = selected.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(index) = input.value().parse::<usize>() {
+                selected.set(index);
+            }
+        })
+    };
+
+    let on_run_model_change = {
+        let run_model = run_model.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some((provider, model)) = input.value().split_once(',') {
+                run_model.set((provider.to_string(), model.to_string()));
+            }
+        })
+    };
+
+    let on_judge_model_change = {
+        let judge_model = judge_model.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            judge_model.set(
+                input
+                    .value()
+                    .split_once(',')
+                    .map(|(provider, model)| (provider.to_string(), model.to_string())),
+            );
+        })
+    };
+
+    let on_run = {
+        let suite = suite.clone();
+        let run = run.clone();
+        let run_model = run_model.clone();
+        let judge_model = judge_model.clone();
+        let config = app_state.config.clone();
+        let llm_client = app_state.llm_client.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(suite) = suite.clone() else {
+                return;
+            };
+            if suite.cases.is_empty() {
+                return;
+            }
+            let pinned = |(provider, model): &(String, String)| {
+                let mut pinned = config.clone();
+                pinned.set_session_provider(provider, model);
+                pinned.apply_model_defaults(provider, model);
+                // Eval cases are plain prompts; tools would make runs non-deterministic
+                pinned.function_tools.clear();
+                pinned
+            };
+            let run_config = pinned(&run_model);
+            let judge_config = judge_model.as_ref().map(pinned);
+
+            let run_id = js_sys::Date::now() as u64;
+            run.dispatch(EvalAction::Start {
+                run_id,
+                case_ids: suite.cases.iter().map(|c| c.id.clone()).collect(),
+            });
+
+            let run = run.clone();
+            let llm_client = llm_client.clone();
+            // Cases run one after another to stay clear of rate limits
+            wasm_bindgen_futures::spawn_local(async move {
+                for case in &suite.cases {
+                    let result = eval_suite::run_case(
+                        &suite,
+                        case,
+                        &llm_client,
+                        &run_config,
+                        judge_config.as_ref(),
+                    )
+                    .await;
+                    run.dispatch(EvalAction::Finish {
+                        run_id,
+                        case_id: case.id.clone(),
+                        result,
+                    });
+                }
+            });
+        })
+    };
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    let finished: Vec<&CaseResult> = run.results.values().flatten().collect();
+    let passed = finished.iter().filter(|r| r.passed()).count();
+    let select_class = "p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 text-sm";
+
+    html! {
+        <div class="flex-1 flex flex-col min-w-0 bg-white dark:bg-gray-800">
+            <header class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
+                <div>
+                    <h2 class="font-semibold text-gray-900 dark:text-gray-100">{"Eval Suites"}</h2>
+                    <div class="text-sm text-gray-600 dark:text-gray-300">
+                        {"Run test cases against a model and check each reply with assertions."}
+                    </div>
+                </div>
+                <button
+                    onclick={on_close}
+                    class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                    aria-label="Close eval suites"
+                    title="Back to chat"
+                >
+                    <i class="fas fa-times" aria-hidden="true"></i>
+                </button>
+            </header>
+
+            <div class="p-4 border-b border-gray-200 dark:border-gray-600 flex flex-wrap items-center gap-2">
+                <select aria-label="Eval suite" onchange={on_select_suite} class={select_class} disabled={suites.is_empty()}>
+                    {for suites.iter().enumerate().map(|(index, s)| html! {
+                        <option value={index.to_string()} selected={index == *selected}>{&s.name}</option>
+                    })}
+                </select>
+                <button onclick={on_new_suite} class="text-sm px-3 py-1 bg-green-100 dark:bg-green-900/30 text-green-600 dark:text-green-400 rounded hover:bg-green-200 dark:hover:bg-green-900/50">
+                    <i class="fas fa-plus mr-1" aria-hidden="true"></i>{"New suite"}
+                </button>
+                {if suite.is_some() {
+                    html! {
+                        <button onclick={on_delete_suite} disabled={is_running} class="text-sm px-3 py-1 bg-red-100 dark:bg-red-900/30 text-red-600 dark:text-red-400 rounded hover:bg-red-200 dark:hover:bg-red-900/50">
+                            <i class="fas fa-trash mr-1" aria-hidden="true"></i>{"Delete"}
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }}
+                <div class="flex-1"></div>
+                <label class="text-sm text-gray-700 dark:text-gray-300">{"Model"}</label>
+                <select aria-label="Model to evaluate" onchange={on_run_model_change} class={select_class} disabled={is_running}>
+                    {for combos.iter().map(|(provider, model)| html! {
+                        <option value={format!("{},{}", provider, model)} selected={*run_model == (provider.clone(), model.clone())}>
+                            {format!("{} / {}", provider, model)}
+                        </option>
+                    })}
+                </select>
+                <label class="text-sm text-gray-700 dark:text-gray-300">{"Judge"}</label>
+                <select aria-label="Judge model" onchange={on_judge_model_change} class={select_class} disabled={is_running}>
+                    <option value="" selected={judge_model.is_none()}>{"Same as model"}</option>
+                    {for combos.iter().map(|(provider, model)| html! {
+                        <option value={format!("{},{}", provider, model)} selected={judge_model.as_ref() == Some(&(provider.clone(), model.clone()))}>
+                            {format!("{} / {}", provider, model)}
+                        </option>
+                    })}
+                </select>
+                <button
+                    onclick={on_run}
+                    disabled={is_running || suite.as_ref().is_none_or(|s| s.cases.is_empty())}
+                    class="text-sm px-4 py-2 bg-primary-600 text-white rounded-md hover:bg-primary-700 disabled:opacity-50"
+                >
+                    {if is_running {
+                        html! { <><i class="fas fa-spinner fa-spin mr-1" aria-hidden="true"></i>{"Running..."}</> }
+                    } else {
+                        html! { <><i class="fas fa-play mr-1" aria-hidden="true"></i>{"Run suite"}</> }
+                    }}
+                </button>
+            </div>
+
+            {if run.results.is_empty() {
+                html! {}
+            } else {
+                let all_done = finished.len() == run.results.len();
+                html! {
+                    <div
+                        class={classes!(
+                            "px-4", "py-2", "text-sm", "font-medium", "border-b", "border-gray-200", "dark:border-gray-600",
+                            if all_done && passed == finished.len() { "bg-green-50 dark:bg-green-900/30 text-green-700 dark:text-green-300" }
+                            else if passed < finished.len() { "bg-red-50 dark:bg-red-900/30 text-red-700 dark:text-red-300" }
+                            else { "bg-gray-50 dark:bg-gray-900/30 text-gray-700 dark:text-gray-300" }
+                        )}
+                        role="status"
+                    >
+                        {format!("{} of {} passed", passed, finished.len())}
+                        {if all_done { String::new() } else { format!(" ({} remaining)", run.results.len() - finished.len()) }}
+                    </div>
+                }
+            }}
+
+            <div class="flex-1 overflow-y-auto custom-scrollbar p-4 space-y-4">
+                {if let Some(suite) = &suite {
+                    render_suite_editor(suite, &edit_suite, &run.results)
+                } else {
+                    html! {
+                        <div class="text-center text-gray-600 dark:text-gray-300 py-12">
+                            <i class="fas fa-vial text-3xl mb-2" aria-hidden="true"></i>
+                            <p>{"No eval suites yet. Create one to start adding test cases."}</p>
+                        </div>
+                    }
+                }}
+            </div>
+            {confirm.dialog()}
+        </div>
+    }
+}
+
+fn input_class() -> &'static str {
+    "w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 text-sm"
+}
+
+fn render_suite_editor(
+    suite: &EvalSuite,
+    edit_suite: &Callback<SuiteEdit>,
+    results: &HashMap<String, Option<CaseResult>>,
+) -> Html {
+    let on_name = {
+        let edit_suite = edit_suite.clone();
+        Callback::from(move |e: InputEvent| {
+            let value = e.target_unchecked_into::<HtmlInputElement>().value();
+            edit_suite.emit(Box::new(move |suite| suite.name = value));
+        })
+    };
+    let on_system_prompt = {
+        let edit_suite = edit_suite.clone();
+        Callback::from(move |e: InputEvent| {
+            let value = e.target_unchecked_into::<HtmlTextAreaElement>().value();
+            edit_suite.emit(Box::new(move |suite| suite.system_prompt = value));
+        })
+    };
+    let on_add_case = {
+        let edit_suite = edit_suite.clone();
+        Callback::from(move |_: MouseEvent| {
+            edit_suite.emit(Box::new(|suite| {
+                suite.cases.push(TestCase {
+                    id: format!("case_{}", js_sys::Date::now() as u64),
+                    name: format!("Case {}", suite.cases.len() + 1),
+                    ..Default::default()
+                })
+            }));
+        })
+    };
+
+    html! {
+        <>
+            <div class="grid grid-cols-1 md:grid-cols-2 gap-4">
+                <div>
+                    <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300">{"Suite name"}</label>
+                    <input type="text" value={suite.name.clone()} oninput={on_name} class={input_class()} />
+                </div>
+                <div>
+                    <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300">{"System prompt"}</label>
+                    <textarea
+                        rows="2"
+                        value={suite.system_prompt.clone()}
+                        oninput={on_system_prompt}
+                        class={input_class()}
+                        placeholder="Leave empty to use the configured system prompt"
+                    />
+                </div>
+            </div>
+
+            {for suite.cases.iter().enumerate().map(|(index, case)| {
+                render_case(index, case, edit_suite, results.get(&case.id))
+            })}
+
+            <button onclick={on_add_case} class="text-sm px-3 py-2 border border-dashed border-gray-300 dark:border-gray-600 rounded-md w-full text-gray-600 dark:text-gray-300 hover:bg-gray-50 dark:hover:bg-gray-700">
+                <i class="fas fa-plus mr-1" aria-hidden="true"></i>{"Add test case"}
+            </button>
+        </>
+    }
+}
+
+fn render_case(
+    index: usize,
+    case: &TestCase,
+    edit_suite: &Callback<SuiteEdit>,
+    result: Option<&Option<CaseResult>>,
+) -> Html {
+    let edit_case = {
+        let edit_suite = edit_suite.clone();
+        move |edit: Box<dyn FnOnce(&mut TestCase)>| {
+            edit_suite.emit(Box::new(move |suite| {
+                if let Some(case) = suite.cases.get_mut(index) {
+                    edit(case);
+                }
+            }))
+        }
+    };
+
+    let on_name = {
+        let edit_case = edit_case.clone();
+        Callback::from(move |e: InputEvent| {
+            let value = e.target_unchecked_into::<HtmlInputElement>().value();
+            edit_case(Box::new(move |case| case.name = value));
+        })
+    };
+    let on_input = {
+        let edit_case = edit_case.clone();
+        Callback::from(move |e: InputEvent| {
+            let value = e.target_unchecked_into::<HtmlTextAreaElement>().value();
+            edit_case(Box::new(move |case| case.input = value));
+        })
+    };
+    let on_remove = {
+        let edit_suite = edit_suite.clone();
+        Callback::from(move |_: MouseEvent| {
+            edit_suite.emit(Box::new(move |suite| {
+                if index < suite.cases.len() {
+                    suite.cases.remove(index);
+                }
+            }))
+        })
+    };
+    let add_assertion = |assertion: Assertion, label: &'static str| {
+        let edit_case = edit_case.clone();
+        let onclick = Callback::from(move |_: MouseEvent| {
+            let assertion = assertion.clone();
+            edit_case(Box::new(move |case| case.assertions.push(assertion)));
+        });
+        html! {
+            <button {onclick} class="text-xs px-2 py-1 bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-300 rounded hover:bg-gray-200 dark:hover:bg-gray-600">
+                <i class="fas fa-plus mr-1" aria-hidden="true"></i>{label}
+            </button>
+        }
+    };
+
+    let (status_class, status_label) = match result {
+        None => ("", ""),
+        Some(None) => (
+            "bg-blue-100 dark:bg-blue-900/50 text-blue-700 dark:text-blue-300",
+            "Running",
+        ),
+        Some(Some(result)) if result.passed() => (
+            "bg-green-100 dark:bg-green-900/50 text-green-700 dark:text-green-300",
+            "Pass",
+        ),
+        Some(Some(_)) => (
+            "bg-red-100 dark:bg-red-900/50 text-red-700 dark:text-red-300",
+            "Fail",
+        ),
+    };
+
+    html! {
+        <section key={case.id.clone()} class="p-4 border border-gray-200 dark:border-gray-600 rounded-md space-y-3" aria-label={case.name.clone()}>
+            <div class="flex items-center gap-2">
+                <input type="text" value={case.name.clone()} oninput={on_name} class={classes!(input_class(), "font-medium")} aria-label="Case name" />
+                {if status_label.is_empty() {
+                    html! {}
+                } else {
+                    html! { <span class={classes!("px-2", "py-0.5", "rounded-full", "text-xs", "font-medium", status_class)}>{status_label}</span> }
+                }}
+                <button onclick={on_remove} class="p-2 text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-red-900/30 rounded" aria-label="Remove test case" title="Remove test case">
+                    <i class="fas fa-trash" aria-hidden="true"></i>
+                </button>
+            </div>
+            <textarea rows="3" value={case.input.clone()} oninput={on_input} class={input_class()} placeholder="Prompt sent to the model" aria-label="Case input" />
+
+            <div class="space-y-2">
+                {for case.assertions.iter().enumerate().map(|(assertion_index, assertion)| {
+                    let outcome = match result {
+                        Some(Some(result)) => result.assertions.get(assertion_index),
+                        _ => None,
+                    };
+                    render_assertion(assertion_index, assertion, &edit_case, outcome)
+                })}
+                <div class="flex flex-wrap gap-2">
+                    {add_assertion(Assertion::Contains { text: String::new(), case_sensitive: false }, "Contains")}
+                    {add_assertion(Assertion::Regex { pattern: String::new() }, "Regex")}
+                    {add_assertion(Assertion::JsonSchema { schema: "{\n  \"type\": \"object\"\n}".to_string() }, "JSON schema")}
+                    {add_assertion(Assertion::LlmJudge { criteria: String::new(), min_score: 7 }, "LLM judge")}
+                </div>
+            </div>
+
+            {match result {
+                Some(Some(result)) => html! {
+                    <details class="text-sm">
+                        <summary class="cursor-pointer text-gray-600 dark:text-gray-300">
+                            {format!("Output ({:.0} ms)", result.latency_ms)}
+                        </summary>
+                        <pre class="mt-2 p-3 rounded bg-gray-50 dark:bg-gray-900 text-xs whitespace-pre-wrap text-gray-800 dark:text-gray-200">
+                            {match &result.output {
+                                Ok(text) => text.clone(),
+                                Err(error) => format!("Error: {}", error),
+                            }}
+                        </pre>
+                    </details>
+                },
+                _ => html! {},
+            }}
+        </section>
+    }
+}
+
+fn render_assertion(
+    index: usize,
+    assertion: &Assertion,
+    edit_case: &(impl Fn(Box<dyn FnOnce(&mut TestCase)>) + Clone + 'static),
+    outcome: Option<&eval_suite::AssertionResult>,
+) -> Html {
+    let set = {
+        let edit_case = edit_case.clone();
+        move |update: Box<dyn FnOnce(&mut Assertion)>| {
+            edit_case(Box::new(move |case| {
+                if let Some(assertion) = case.assertions.get_mut(index) {
+                    update(assertion);
+                }
+            }))
+        }
+    };
+    let on_remove = {
+        let edit_case = edit_case.clone();
+        Callback::from(move |_: MouseEvent| {
+            edit_case(Box::new(move |case| {
+                if index < case.assertions.len() {
+                    case.assertions.remove(index);
+                }
+            }))
+        })
+    };
+
+    let fields = match assertion {
+        Assertion::Contains {
+            text,
+            case_sensitive,
+        } => {
+            let set_text = set.clone();
+            let set_case = set.clone();
+            html! {
+                <>
+                    <input
+                        type="text"
+                        value={text.clone()}
+                        oninput={Callback::from(move |e: InputEvent| {
+                            let value = e.target_unchecked_into::<HtmlInputElement>().value();
+                            set_text(Box::new(move |a| if let Assertion::Contains { text, .. } = a { *text = value }));
+                        })}
+                        class={input_class()}
+                        placeholder="Text the reply must contain"
+                    />
+                    <label class="flex items-center space-x-1 text-xs text-gray-600 dark:text-gray-300 whitespace-nowrap">
+                        <input
+                            type="checkbox"
+                            checked={*case_sensitive}
+                            onchange={Callback::from(move |_: Event| {
+                                set_case(Box::new(|a| if let Assertion::Contains { case_sensitive, .. } = a { *case_sensitive = !*case_sensitive }));
+                            })}
+                            class="rounded"
+                        />
+                        <span>{"Match case"}</span>
+                    </label>
+                </>
+            }
+        }
+        Assertion::Regex { pattern } => html! {
+            <input
+                type="text"
+                value={pattern.clone()}
+                oninput={Callback::from(move |e: InputEvent| {
+                    let value = e.target_unchecked_into::<HtmlInputElement>().value();
+                    set(Box::new(move |a| if let Assertion::Regex { pattern } = a { *pattern = value }));
+                })}
+                class={classes!(input_class(), "font-mono")}
+                placeholder="^\\d+ items?$"
+            />
+        },
+        Assertion::JsonSchema { schema } => html! {
+            <textarea
+                rows="4"
+                value={schema.clone()}
+                oninput={Callback::from(move |e: InputEvent| {
+                    let value = e.target_unchecked_into::<HtmlTextAreaElement>().value();
+                    set(Box::new(move |a| if let Assertion::JsonSchema { schema } = a { *schema = value }));
+                })}
+                class={classes!(input_class(), "font-mono")}
+            />
+        },
+        Assertion::LlmJudge {
+            criteria,
+            min_score,
+        } => {
+            let set_criteria = set.clone();
+            let set_score = set.clone();
+            html! {
+                <>
+                    <input
+                        type="text"
+                        value={criteria.clone()}
+                        oninput={Callback::from(move |e: InputEvent| {
+                            let value = e.target_unchecked_into::<HtmlInputElement>().value();
+                            set_criteria(Box::new(move |a| if let Assertion::LlmJudge { criteria, .. } = a { *criteria = value }));
+                        })}
+                        class={input_class()}
+                        placeholder="e.g. Polite, accurate and under 100 words"
+                    />
+                    <label class="flex items-center space-x-1 text-xs text-gray-600 dark:text-gray-300 whitespace-nowrap">
+                        <span>{"Min score"}</span>
+                        <input
+                            type="number"
+                            min="1"
+                            max="10"
+                            value={min_score.to_string()}
+                            oninput={Callback::from(move |e: InputEvent| {
+                                if let Ok(score) = e.target_unchecked_into::<HtmlInputElement>().value().parse::<u8>() {
+                                    set_score(Box::new(move |a| if let Assertion::LlmJudge { min_score, .. } = a { *min_score = score.clamp(1, 10) }));
+                                }
+                            })}
+                            class="w-16 p-1 border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                        />
+                    </label>
+                </>
+            }
+        }
+    };
+
+    html! {
+        <div class="flex items-start gap-2">
+            <span class="w-24 flex-shrink-0 pt-2 text-xs font-medium text-gray-600 dark:text-gray-300">{assertion.kind_label()}</span>
+            <div class="flex-1 min-w-0">
+                <div class="flex items-center gap-2">{fields}</div>
+                {if let Some(outcome) = outcome {
+                    html! {
+                        <p class={classes!("mt-1", "text-xs", if outcome.passed { "text-green-700 dark:text-green-400" } else { "text-red-700 dark:text-red-400" })}>
+                            <i class={classes!("fas", "mr-1", if outcome.passed { "fa-check" } else { "fa-times" })} aria-hidden="true"></i>
+                            {&outcome.detail}
+                        </p>
+                    }
+                } else {
+                    html! {}
+                }}
+            </div>
+            <button onclick={on_remove} class="p-2 text-gray-400 hover:text-red-600 dark:hover:text-red-400" aria-label="Remove assertion" title="Remove assertion">
+                <i class="fas fa-times" aria-hidden="true"></i>
+            </button>
+        </div>
+    }
+}
@@ -0,0 +1,331 @@
+// Fine-tuning export: pick sessions, curate their messages and download the
+// dataset as OpenAI chat or Gemini tuning JSONL
+use super::notification::{NotificationMessage, NotificationType};
+use crate::llm_playground::{
+    app_state::use_app_state,
+    event_bus::PlaygroundEvent,
+    fine_tune_export::{self, DatasetFormat, DatasetSelection},
+    hooks::use_event_bus,
+    html_export,
+    ChatSession, Message, MessageRole,
+};
+use std::collections::HashMap;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct FineTuneViewProps {
+    pub sessions: HashMap<String, ChatSession>,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(FineTuneView)]
+pub fn fine_tune_view(props: &FineTuneViewProps) -> Html {
+    let app_state = use_app_state();
+    let selection = use_state(DatasetSelection::default);
+    let format = use_state(|| DatasetFormat::OpenAiChat);
+    let system_prompt = {
+        // The chat system prompt is the default for every example
+        let default_prompt = app_state.config.system_prompt.clone();
+        use_state(move || default_prompt)
+    };
+    // Session whose messages are shown for curation
+    let focused = use_state(|| Option::<String>::None);
+    let on_notification = use_event_bus().callback(PlaygroundEvent::Notify);
+
+    let mut sessions: Vec<&ChatSession> = props
+        .sessions
+        .values()
+        .filter(|s| s.dialogue.is_none())
+        .collect();
+    sessions.sort_by(|a, b| b.updated_at.total_cmp(&a.updated_at));
+
+    let (dataset, example_count) = fine_tune_export::build_dataset(
+        sessions.iter().copied(),
+        &system_prompt,
+        &selection,
+        *format,
+    );
+
+    let update_selection = {
+        let selection = selection.clone();
+        move |change: Box<dyn Fn(&mut DatasetSelection)>| {
+            let selection = selection.clone();
+            Callback::from(move |_: Event| {
+                let mut next = (*selection).clone();
+                change(&mut next);
+                selection.set(next);
+            })
+        }
+    };
+
+    let on_select_all = {
+        let selection = selection.clone();
+        let ids: Vec<String> = sessions.iter().map(|s| s.id.clone()).collect();
+        Callback::from(move |_: MouseEvent| {
+            let mut next = (*selection).clone();
+            if next.sessions.len() == ids.len() {
+                next.sessions.clear();
+            } else {
+                next.sessions = ids.iter().cloned().collect();
+            }
+            selection.set(next);
+        })
+    };
+
+    let on_format_change = {
+        let format = format.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            format.set(DatasetFormat::from_key(&input.value()));
+        })
+    };
+
+    let on_system_prompt_change = {
+        let system_prompt = system_prompt.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            system_prompt.set(input.value());
+        })
+    };
+
+    let on_export = {
+        let format = *format;
+        let dataset = dataset.clone();
+        let on_notification = on_notification.clone();
+        Callback::from(move |_: MouseEvent| {
+            let file_name = format.file_name();
+            let notification =
+                match html_export::download_file(&file_name, &dataset, "application/jsonl") {
+                    Ok(()) => NotificationMessage::new(
+                        format!("Exported {} examples to {}", example_count, file_name),
+                        NotificationType::Success,
+                    ),
+                    Err(error) => NotificationMessage::new(
+                        format!("Dataset export failed: {}", error),
+                        NotificationType::Error,
+                    ),
+                };
+            on_notification.emit(notification);
+        })
+    };
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    let focused_session = focused
+        .as_ref()
+        .and_then(|id| props.sessions.get(id))
+        .filter(|s| s.dialogue.is_none());
+    let input_class = "p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 text-sm";
+
+    html! {
+        <div class="flex-1 flex flex-col min-w-0 bg-white dark:bg-gray-800">
+            <header class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
+                <div>
+                    <h2 class="font-semibold text-gray-900 dark:text-gray-100">{"Fine-tuning Export"}</h2>
+                    <div class="text-sm text-gray-600 dark:text-gray-300">
+                        {"Each selected session becomes one training example. Tool calls and their results are left out."}
+                    </div>
+                </div>
+                <button
+                    onclick={on_close}
+                    class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                    aria-label="Close fine-tuning export"
+                    title="Back to chat"
+                >
+                    <i class="fas fa-times" aria-hidden="true"></i>
+                </button>
+            </header>
+
+            <div class="p-4 border-b border-gray-200 dark:border-gray-600 space-y-3">
+                <div class="flex flex-wrap items-center gap-3">
+                    <label class="text-sm text-gray-700 dark:text-gray-300" for="fine-tune-format">{"Format"}</label>
+                    <select id="fine-tune-format" onchange={on_format_change} class={input_class}>
+                        {for DatasetFormat::ALL.iter().map(|option| html! {
+                            <option value={option.key()} selected={*option == *format}>{option.label()}</option>
+                        })}
+                    </select>
+                    <span class="text-sm text-gray-600 dark:text-gray-300" role="status">
+                        {format!("{} of {} sessions selected, {} examples", selection.sessions.len(), sessions.len(), example_count)}
+                    </span>
+                    <button
+                        onclick={on_export}
+                        disabled={example_count == 0}
+                        class={classes!(
+                            "ml-auto", "text-sm", "px-4", "py-2", "bg-primary-600", "text-white", "rounded-md",
+                            if example_count == 0 { "opacity-50 cursor-not-allowed" } else { "hover:bg-primary-700" }
+                        )}
+                    >
+                        <i class="fas fa-download mr-1" aria-hidden="true"></i>{"Download JSONL"}
+                    </button>
+                </div>
+                <div>
+                    <label class="block text-sm text-gray-700 dark:text-gray-300 mb-1" for="fine-tune-system-prompt">
+                        {"System prompt for every example (leave empty for none)"}
+                    </label>
+                    <textarea
+                        id="fine-tune-system-prompt"
+                        rows="2"
+                        value={(*system_prompt).clone()}
+                        oninput={on_system_prompt_change}
+                        class={classes!(input_class, "w-full")}
+                    />
+                </div>
+                {if *format == DatasetFormat::Gemini {
+                    html! {
+                        <p class="text-xs text-gray-600 dark:text-gray-300">
+                            {"Gemini tuning has no weights: replies marked \"context only\" are kept as model turns."}
+                        </p>
+                    }
+                } else {
+                    html! {}
+                }}
+            </div>
+
+            <div class="flex-1 flex min-h-0">
+                <div class="w-72 flex-shrink-0 border-r border-gray-200 dark:border-gray-600 overflow-y-auto custom-scrollbar">
+                    <div class="p-3 border-b border-gray-200 dark:border-gray-600">
+                        <button onclick={on_select_all} class="text-xs text-primary-600 dark:text-primary-400 hover:underline">
+                            {if !sessions.is_empty() && selection.sessions.len() == sessions.len() { "Clear selection" } else { "Select all" }}
+                        </button>
+                    </div>
+                    {if sessions.is_empty() {
+                        html! {
+                            <p class="p-4 text-sm text-gray-600 dark:text-gray-300">{"No chat sessions yet."}</p>
+                        }
+                    } else {
+                        html! {
+                            <ul>
+                                {for sessions.iter().map(|session| {
+                                    let id = session.id.clone();
+                                    let checked = selection.sessions.contains(&id);
+                                    let on_toggle = update_selection(Box::new({
+                                        let id = id.clone();
+                                        move |selection: &mut DatasetSelection| {
+                                            if !selection.sessions.remove(&id) {
+                                                selection.sessions.insert(id.clone());
+                                            }
+                                        }
+                                    }));
+                                    let on_focus = {
+                                        let focused = focused.clone();
+                                        let id = id.clone();
+                                        Callback::from(move |_: MouseEvent| focused.set(Some(id.clone())))
+                                    };
+                                    let is_focused = focused.as_deref() == Some(id.as_str());
+                                    html! {
+                                        <li
+                                            key={id.clone()}
+                                            class={classes!(
+                                                "flex", "items-center", "gap-2", "px-3", "py-2", "text-sm",
+                                                if is_focused { "bg-primary-50 dark:bg-gray-700" } else { "hover:bg-gray-50 dark:hover:bg-gray-700" }
+                                            )}
+                                        >
+                                            <input
+                                                type="checkbox"
+                                                checked={checked}
+                                                onchange={on_toggle}
+                                                aria-label={format!("Include {}", session.title)}
+                                            />
+                                            <button onclick={on_focus} class="flex-1 text-left truncate text-gray-900 dark:text-gray-100">
+                                                {session.title.clone()}
+                                                <span class="ml-1 text-xs text-gray-500 dark:text-gray-400">
+                                                    {format!("({})", fine_tune_export::exportable_messages(session).len())}
+                                                </span>
+                                            </button>
+                                        </li>
+                                    }
+                                })}
+                            </ul>
+                        }
+                    }}
+                </div>
+
+                <div class="flex-1 overflow-y-auto custom-scrollbar p-4">
+                    {match focused_session {
+                        Some(session) => html! {
+                            <ul class="space-y-2">
+                                {for fine_tune_export::exportable_messages(session).into_iter().map(|message| {
+                                    render_message_row(message, &selection, &update_selection)
+                                })}
+                            </ul>
+                        },
+                        None => html! {
+                            <div class="text-center text-gray-600 dark:text-gray-300 py-12">
+                                <i class="fas fa-graduation-cap text-3xl mb-2" aria-hidden="true"></i>
+                                <p>{"Click a session to choose which of its messages are included."}</p>
+                            </div>
+                        },
+                    }}
+                </div>
+            </div>
+        </div>
+    }
+}
+
+fn render_message_row(
+    message: &Message,
+    selection: &DatasetSelection,
+    update_selection: &impl Fn(Box<dyn Fn(&mut DatasetSelection)>) -> Callback<Event>,
+) -> Html {
+    let included = selection.includes(message);
+    let id = message.id.clone();
+    let on_toggle = update_selection(Box::new({
+        let id = id.clone();
+        move |selection: &mut DatasetSelection| {
+            if !selection.excluded_messages.remove(&id) {
+                selection.excluded_messages.insert(id.clone());
+            }
+        }
+    }));
+    let role = match message.role {
+        MessageRole::System => "System",
+        MessageRole::Developer => "Developer",
+        MessageRole::User => "User",
+        MessageRole::Assistant => "Assistant",
+        MessageRole::Function => "Function",
+    };
+    let preview: String = message.content.chars().take(300).collect();
+
+    html! {
+        <li
+            key={id.clone()}
+            class={classes!(
+                "p-3", "rounded-md", "border", "border-gray-200", "dark:border-gray-600",
+                (!included).then_some("opacity-50")
+            )}
+        >
+            <div class="flex items-center gap-3 mb-1 text-xs">
+                <label class="flex items-center gap-1 text-gray-700 dark:text-gray-300">
+                    <input type="checkbox" checked={included} onchange={on_toggle} />
+                    <span class="font-medium">{role}</span>
+                </label>
+                {if message.role == MessageRole::Assistant && included {
+                    let on_weight_change = update_selection(Box::new({
+                        let id = id.clone();
+                        move |selection: &mut DatasetSelection| {
+                            if !selection.zero_weight.remove(&id) {
+                                selection.zero_weight.insert(id.clone());
+                            }
+                        }
+                    }));
+                    html! {
+                        <label class="flex items-center gap-1 text-gray-600 dark:text-gray-300" title="Weight 0: kept as context but not trained on">
+                            <input type="checkbox" checked={selection.weight(message) == 0} onchange={on_weight_change} />
+                            {"Context only (weight 0)"}
+                        </label>
+                    }
+                } else {
+                    html! {}
+                }}
+            </div>
+            <p class="text-sm text-gray-800 dark:text-gray-200 whitespace-pre-wrap">
+                {preview}
+                {if message.content.chars().count() > 300 { "…" } else { "" }}
+            </p>
+        </li>
+    }
+}
@@ -1,13 +1,78 @@
 use crate::llm_playground::components::{
-    FunctionToolEditor, McpSettingsPanel, VisualFunctionToolEditor,
+    confirm_dialog::{has_skipped_confirmations, reset_skipped_confirmations},
+    ConfirmAction, ConfirmRequest, FunctionToolEditor, GeminiCacheList, LocalServerScan, LogitBiasEditor, McpSettingsPanel, ResizeHandle,
+    PersonaEditor, StructuredOutputEditor, VisualFunctionToolEditor,
 };
+use crate::llm_playground::api_clients::{
+    gemini_auth::DEFAULT_VERTEX_LOCATION, gemini_cache, GeminiAuth, ProviderDescriptor,
+};
+use crate::llm_playground::audio_output::{self, AudioOutputSettings};
+use crate::llm_playground::browser_notifications::{self, BrowserNotificationSettings};
+use crate::llm_playground::budget::BudgetSettings;
+use crate::llm_playground::context_usage;
+use crate::llm_playground::conversation_starters::StarterSettings;
+use crate::llm_playground::desktop_tools::{self, DesktopToolSettings};
+use crate::llm_playground::follow_ups::FollowUpSettings;
+use crate::llm_playground::eval_suite::compile_regex;
+use crate::llm_playground::guardrails::{
+    GuardrailAction, GuardrailKind, GuardrailRule, PatternType,
+};
+use crate::llm_playground::handoff_tools;
+use crate::llm_playground::hooks::{use_confirm, use_focus_trap};
+use crate::llm_playground::local_discovery::DiscoveredServer;
+use crate::llm_playground::moderation::ModerationSettings;
+use crate::llm_playground::native_tools::NativeToolSettings;
+use crate::llm_playground::redaction::{RedactionRule, RedactionSettings};
+use crate::llm_playground::response_length::ResponseLengthSettings;
 use crate::llm_playground::mcp_client::McpClient;
+use crate::llm_playground::personas::Persona;
 use crate::llm_playground::provider_config::{FlexibleApiConfig, ProviderConfig};
-use crate::llm_playground::types::FunctionTool;
+use crate::llm_playground::sampling_controls::{self, LogitBiasEntry, MAX_STOP_SEQUENCES};
+use crate::llm_playground::self_consistency::{ConsensusMethod, SelfConsistencySettings};
+use crate::llm_playground::tool_selection::ToolSelectionSettings;
+use crate::llm_playground::chart;
+use crate::llm_playground::code_sandbox::{self, CodeSandboxSettings};
+use crate::llm_playground::fetch_tool::{FetchFormat, FetchToolSettings};
+use crate::llm_playground::web_fetch::WebFetchSettings;
+use crate::llm_playground::web_search::{SearchBackend, WebSearchSettings};
+use crate::llm_playground::webhook::{self, WebhookSettings};
+use crate::llm_playground::types::{FunctionTool, ReasoningVisibility, StructuredOutput, ToolChoice, MAX_CANDIDATES};
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::JsCast;
-use web_sys::HtmlInputElement;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
 use yew::prelude::*;
 
+pub const DEFAULT_SETTINGS_PANEL_WIDTH: u32 = 384;
+pub const MIN_SETTINGS_PANEL_WIDTH: u32 = 320;
+pub const MAX_SETTINGS_PANEL_WIDTH: u32 = 960;
+
+/// Keep a dragged or stored width within the settings panel's limits
+pub fn clamp_settings_panel_width(width: u32) -> u32 {
+    width.clamp(MIN_SETTINGS_PANEL_WIDTH, MAX_SETTINGS_PANEL_WIDTH)
+}
+
+/// Width of the panel docked on the right with its edge at `client_x`
+fn docked_width(viewport_width: f64, client_x: f64) -> u32 {
+    clamp_settings_panel_width((viewport_width - client_x).max(0.0) as u32)
+}
+
+const PROVIDER_DESCRIPTOR_EXAMPLE: &str = r#"{
+  "name": "my-gateway",
+  "transformer": ["openai"],
+  "api_base_url": "https://gateway.example.com/v1",
+  "models": ["gpt-4o"],
+  "auth": { "scheme": "header", "name": "x-api-key" },
+  "endpoints": { "chat": "/chat/completions", "models": "/models" }
+}"#;
+
+/// How the settings panel is presented
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum SettingsPanelMode {
+    #[default]
+    Docked,
+    Modal,
+}
+
 #[derive(Properties, PartialEq)]
 pub struct FlexibleSettingsPanelProps {
     pub config: FlexibleApiConfig,
@@ -15,16 +80,54 @@ pub struct FlexibleSettingsPanelProps {
     pub on_close: Callback<()>,
     pub mcp_client: Option<McpClient>,
     pub on_mcp_client_change: Callback<Option<McpClient>>,
+    #[prop_or(DEFAULT_SETTINGS_PANEL_WIDTH)]
+    pub width: u32,
+    #[prop_or_default]
+    pub mode: SettingsPanelMode,
+    #[prop_or_default]
+    pub on_resize: Callback<u32>,
+    /// A resize is over and the width can be saved
+    #[prop_or_default]
+    pub on_resize_end: Callback<()>,
+    #[prop_or_default]
+    pub on_mode_change: Callback<SettingsPanelMode>,
+    /// Section to scroll to, from `routes::SETTINGS_SECTIONS`
+    #[prop_or_default]
+    pub section: Option<&'static str>,
 }
 
 #[function_component(FlexibleSettingsPanel)]
 pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
     let config = use_state(|| props.config.clone());
+    use_effect_with(props.section, |section| {
+        let element = section.and_then(|section| {
+            web_sys::window()?
+                .document()?
+                .get_element_by_id(&format!("settings-{}", section))
+        });
+        if let Some(element) = element {
+            element.scroll_into_view();
+        }
+        || ()
+    });
     let show_function_editor = use_state(|| false);
     let editing_function_index = use_state(|| None::<usize>);
     let use_visual_editor = use_state(|| true);
+    let show_output_editor = use_state(|| false);
+    let editing_output_index = use_state(|| None::<usize>);
+    let show_persona_editor = use_state(|| false);
+    let editing_persona_index = use_state(|| None::<usize>);
     let selected_provider_index = use_state(|| 0);
     let show_add_provider = use_state(|| false);
+    let provider_descriptor = use_state(String::new);
+    let descriptor_error = use_state(|| None::<String>);
+    let dialog_ref = use_node_ref();
+
+    use_focus_trap(
+        dialog_ref.clone(),
+        props.mode == SettingsPanelMode::Modal,
+        props.on_close.clone(),
+    );
 
     // Update local state when props change
     {
@@ -73,6 +176,24 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
                     "name" => new_config.providers[index].name = value,
                     "api_base_url" => new_config.providers[index].api_base_url = value,
                     "api_key" => new_config.providers[index].api_key = value,
+                    "gemini_auth" => {
+                        new_config.providers[index].gemini_auth = if value == "vertex" {
+                            GeminiAuth::Vertex {
+                                project: String::new(),
+                                location: DEFAULT_VERTEX_LOCATION.to_string(),
+                            }
+                        } else {
+                            GeminiAuth::ApiKey
+                        };
+                    }
+                    "vertex_project" | "vertex_location" => {
+                        if let GeminiAuth::Vertex { project, location } =
+                            &mut new_config.providers[index].gemini_auth
+                        {
+                            let target = if field == "vertex_project" { project } else { location };
+                            *target = value.trim().to_string();
+                        }
+                    }
                     _ => {}
                 }
                 config.set(new_config);
@@ -114,6 +235,34 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
         })
     };
 
+    let on_model_defaults_change = {
+        let config = config.clone();
+        let selected_provider_index = selected_provider_index.clone();
+        Callback::from(move |(model, field, value): (String, &'static str, String)| {
+            let mut new_config = (*config).clone();
+            let index = *selected_provider_index;
+            if index < new_config.providers.len() {
+                let value = value.trim();
+                let defaults = new_config.providers[index]
+                    .model_defaults
+                    .entry(model.clone())
+                    .or_default();
+                match field {
+                    "max_tokens" => defaults.max_tokens = value.parse::<u32>().ok(),
+                    "max_temperature" => defaults.max_temperature = value.parse::<f32>().ok(),
+                    "context_window" => defaults.context_window = value.parse::<u32>().ok(),
+                    "input_price" => defaults.input_price = value.parse::<f64>().ok(),
+                    "output_price" => defaults.output_price = value.parse::<f64>().ok(),
+                    _ => {}
+                }
+                if defaults.is_empty() {
+                    new_config.providers[index].model_defaults.remove(&model);
+                }
+                config.set(new_config);
+            }
+        })
+    };
+
     let on_add_provider = {
         let config = config.clone();
         let show_add_provider = show_add_provider.clone();
@@ -127,23 +276,120 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
                 transformer: crate::llm_playground::provider_config::TransformerConfig {
                     r#use: vec!["openai".to_string()],
                 },
+                mock_script: None,
+                plugin: None,
+                gemini_auth: Default::default(),
+                model_defaults: Default::default(),
             });
             config.set(new_config);
             show_add_provider.set(false);
         })
     };
 
+    let on_add_preset = {
+        let config = config.clone();
+        let show_add_provider = show_add_provider.clone();
+        let selected_provider_index = selected_provider_index.clone();
+        Callback::from(move |preset: ProviderConfig| {
+            let mut new_config = (*config).clone();
+            new_config.providers.push(preset);
+            selected_provider_index.set(new_config.providers.len() - 1);
+            config.set(new_config);
+            show_add_provider.set(false);
+        })
+    };
+
+    let on_add_discovered = {
+        let config = config.clone();
+        let show_add_provider = show_add_provider.clone();
+        let selected_provider_index = selected_provider_index.clone();
+        Callback::from(move |server: DiscoveredServer| {
+            let mut new_config = (*config).clone();
+            let index = server.add_to(&mut new_config.providers);
+            selected_provider_index.set(index);
+            config.set(new_config);
+            show_add_provider.set(false);
+        })
+    };
+
+    let on_add_plugin_provider = {
+        let config = config.clone();
+        let show_add_provider = show_add_provider.clone();
+        let provider_descriptor = provider_descriptor.clone();
+        let descriptor_error = descriptor_error.clone();
+        let selected_provider_index = selected_provider_index.clone();
+        Callback::from(move |_: MouseEvent| {
+            let descriptor = match ProviderDescriptor::parse(&provider_descriptor) {
+                Ok(descriptor) => descriptor,
+                Err(error) => {
+                    descriptor_error.set(Some(error));
+                    return;
+                }
+            };
+            if config.get_provider(&descriptor.name).is_some() {
+                descriptor_error.set(Some(format!(
+                    "A provider named \"{}\" already exists",
+                    descriptor.name
+                )));
+                return;
+            }
+            let mut new_config = (*config).clone();
+            new_config.providers.push(descriptor.into_provider_config());
+            selected_provider_index.set(new_config.providers.len() - 1);
+            config.set(new_config);
+            provider_descriptor.set(String::new());
+            descriptor_error.set(None);
+            show_add_provider.set(false);
+        })
+    };
+
+    let confirm = use_confirm();
+    let force_update = use_force_update();
+
     let on_remove_provider = {
         let config = config.clone();
         let selected_provider_index = selected_provider_index.clone();
+        let confirm = confirm.clone();
         Callback::from(move |_| {
-            let mut new_config = (*config).clone();
             let index = *selected_provider_index;
-            if index < new_config.providers.len() && new_config.providers.len() > 1 {
-                new_config.providers.remove(index);
-                selected_provider_index.set(0);
-                config.set(new_config);
+            let Some(provider) = config.providers.get(index) else {
+                return;
+            };
+            if config.providers.len() <= 1 {
+                return;
             }
+
+            let remove = {
+                let config = config.clone();
+                let selected_provider_index = selected_provider_index.clone();
+                Callback::from(move |_| {
+                    let mut new_config = (*config).clone();
+                    if index < new_config.providers.len() && new_config.providers.len() > 1 {
+                        new_config.providers.remove(index);
+                        selected_provider_index.set(0);
+                        config.set(new_config);
+                    }
+                })
+            };
+            confirm.confirm(
+                ConfirmRequest::new(
+                    ConfirmAction::DeleteProvider,
+                    "Delete provider?",
+                    format!(
+                        "\"{}\" and its API key and model list will be removed from the configuration.",
+                        provider.name
+                    ),
+                ),
+                remove,
+            );
+        })
+    };
+
+    let on_reset_confirmations = {
+        let force_update = force_update.clone();
+        Callback::from(move |_: MouseEvent| {
+            reset_skipped_confirmations();
+            force_update.force_update();
         })
     };
 
@@ -184,6 +430,165 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
         })
     };
 
+    let on_candidate_count_change = {
+        let config = config.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(count) = input.value().parse::<u32>() {
+                let mut new_config = (*config).clone();
+                new_config.shared_settings.candidate_count =
+                    Some(count.clamp(1, MAX_CANDIDATES)).filter(|count| *count > 1);
+                config.set(new_config);
+            }
+        })
+    };
+
+    let on_json_repair_retries_change = {
+        let config = config.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(retries) = input.value().parse::<u32>() {
+                let mut new_config = (*config).clone();
+                new_config.shared_settings.json_repair_retries = Some(retries);
+                config.set(new_config);
+            }
+        })
+    };
+
+    let on_message_footer_change = {
+        let config = config.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_config = (*config).clone();
+            new_config.hide_message_footer = !input.checked();
+            config.set(new_config);
+        })
+    };
+
+    let on_reasoning_visibility_change = {
+        let config = config.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some(visibility) = ReasoningVisibility::parse(&input.value()) {
+                let mut new_config = (*config).clone();
+                new_config.shared_settings.reasoning = visibility;
+                config.set(new_config);
+            }
+        })
+    };
+
+    let on_tool_choice_change = {
+        let config = config.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some(choice) = ToolChoice::parse(&input.value()) {
+                let mut new_config = (*config).clone();
+                new_config.shared_settings.tool_choice = choice;
+                config.set(new_config);
+            }
+        })
+    };
+
+    let toggle_allowed_function = {
+        let config = config.clone();
+        Callback::from(move |name: String| {
+            let mut new_config = (*config).clone();
+            new_config.shared_settings.toggle_allowed_function(&name);
+            config.set(new_config);
+        })
+    };
+
+    let update_audio_output = {
+        let config = config.clone();
+        Callback::from(move |audio_output: AudioOutputSettings| {
+            let mut new_config = (*config).clone();
+            new_config.shared_settings.audio_output = audio_output;
+            config.set(new_config);
+        })
+    };
+
+    let update_native_tools = {
+        let config = config.clone();
+        Callback::from(move |native_tools: NativeToolSettings| {
+            let mut new_config = (*config).clone();
+            new_config.shared_settings.native_tools = native_tools;
+            config.set(new_config);
+        })
+    };
+
+    let on_hide_reasoning_change = {
+        let config = config.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_config = (*config).clone();
+            new_config.hide_reasoning = !input.checked();
+            config.set(new_config);
+        })
+    };
+
+    let on_stop_sequences_change = {
+        let config = config.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            let mut new_config = (*config).clone();
+            new_config.shared_settings.stop_sequences =
+                sampling_controls::parse_stop_sequences(&input.value());
+            config.set(new_config);
+        })
+    };
+
+    let on_logit_bias_change = {
+        let config = config.clone();
+        Callback::from(move |entries: Vec<LogitBiasEntry>| {
+            let mut new_config = (*config).clone();
+            new_config.shared_settings.logit_bias = entries;
+            config.set(new_config);
+        })
+    };
+
+    let on_context_cache_ttl_change = {
+        let config = config.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let value = input.value();
+            let mut new_config = (*config).clone();
+            if value.trim().is_empty() {
+                new_config.shared_settings.context_cache_ttl_minutes = None;
+            } else if let Ok(minutes) = value.trim().parse::<u32>() {
+                new_config.shared_settings.context_cache_ttl_minutes = Some(minutes);
+            } else {
+                return;
+            }
+            config.set(new_config);
+        })
+    };
+
+    let on_cors_proxy_change = {
+        let config = config.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let value = input.value();
+            let mut new_config = (*config).clone();
+            new_config.shared_settings.cors_proxy = if value.trim().is_empty() {
+                None
+            } else {
+                Some(value.trim().to_string())
+            };
+            config.set(new_config);
+        })
+    };
+
+    let on_sub_agent_model_change = {
+        let config = config.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let value = input.value();
+            let mut new_config = (*config).clone();
+            new_config.sub_agent_model = if value.is_empty() { None } else { Some(value) };
+            config.set(new_config);
+        })
+    };
+
     let on_system_prompt_change = {
         let config = config.clone();
         Callback::from(move |e: InputEvent| {
@@ -215,12 +620,30 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
 
     let delete_function_tool = {
         let config = config.clone();
+        let confirm = confirm.clone();
         Callback::from(move |index: usize| {
-            let mut new_config = (*config).clone();
-            if index < new_config.function_tools.len() {
-                new_config.function_tools.remove(index);
-                config.set(new_config);
-            }
+            let Some(tool) = config.function_tools.get(index) else {
+                return;
+            };
+
+            let remove = {
+                let config = config.clone();
+                Callback::from(move |_| {
+                    let mut new_config = (*config).clone();
+                    if index < new_config.function_tools.len() {
+                        new_config.function_tools.remove(index);
+                        config.set(new_config);
+                    }
+                })
+            };
+            confirm.confirm(
+                ConfirmRequest::new(
+                    ConfirmAction::DeleteFunctionTool,
+                    "Delete function tool?",
+                    format!("The \"{}\" tool and its schema will be deleted.", tool.name),
+                ),
+                remove,
+            );
         })
     };
 
@@ -257,77 +680,546 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
         })
     };
 
-    let cancel_function_editor = {
-        let show_function_editor = show_function_editor.clone();
-        let editing_function_index = editing_function_index.clone();
-        Callback::from(move |_: ()| {
-            show_function_editor.set(false);
-            editing_function_index.set(None);
+    let toggle_mcp_server_tools = {
+        let config = config.clone();
+        Callback::from(move |(server, enabled): (String, bool)| {
+            let mut new_config = (*config).clone();
+            new_config.set_mcp_server_tools_enabled(&server, enabled);
+            config.set(new_config);
         })
     };
 
-    let current_provider = config.providers.get(*selected_provider_index);
+    let open_output_editor = {
+        let show_output_editor = show_output_editor.clone();
+        let editing_output_index = editing_output_index.clone();
+        Callback::from(move |index: Option<usize>| {
+            editing_output_index.set(index);
+            show_output_editor.set(true);
+        })
+    };
 
-    html! {
-        <div class="absolute inset-y-0 right-0 w-96 bg-white dark:bg-gray-800 border-l border-gray-200 dark:border-gray-700 overflow-y-auto custom-scrollbar z-50">
-            <div class="p-4 border-b border-gray-200 dark:border-gray-700">
-                <div class="flex justify-between items-center">
-                    <h2 class="text-lg font-semibold text-gray-900 dark:text-gray-100">{"Settings"}</h2>
-                    <button
-                        onclick={on_close}
-                        class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-900 dark:text-gray-100"
-                    >
-                        <i class="fas fa-times"></i>
-                    </button>
-                </div>
-            </div>
+    let delete_structured_output = {
+        let config = config.clone();
+        let confirm = confirm.clone();
+        Callback::from(move |index: usize| {
+            let Some(output) = config.structured_outputs.get(index) else {
+                return;
+            };
 
-            <div class="p-4 space-y-6">
-                // Provider Management
-                <div>
-                    <div class="flex justify-between items-center mb-4">
-                        <h3 class="font-medium text-gray-900 dark:text-gray-100">{"LLM Providers"}</h3>
-                        <div class="flex space-x-2">
-                            <button
-                                onclick={
-                                    let show_add_provider = show_add_provider.clone();
-                                    Callback::from(move |_| show_add_provider.set(true))
-                                }
-                                class="text-xs px-2 py-1 bg-green-100 dark:bg-green-900/30 text-green-600 dark:text-green-400 rounded hover:bg-green-200 dark:hover:bg-green-900/50"
-                            >
-                                <i class="fas fa-plus mr-1"></i>{"Add"}
-                            </button>
-                            {if config.providers.len() > 1 {
-                                html! {
-                                    <button
-                                        onclick={on_remove_provider}
-                                        class="text-xs px-2 py-1 bg-red-100 dark:bg-red-900/30 text-red-600 dark:text-red-400 rounded hover:bg-red-200 dark:hover:bg-red-900/50"
-                                    >
-                                        <i class="fas fa-trash mr-1"></i>{"Remove"}
-                                    </button>
-                                }
-                            } else {
-                                html! {}
-                            }}
-                        </div>
-                    </div>
+            let remove = {
+                let config = config.clone();
+                Callback::from(move |_| {
+                    let mut new_config = (*config).clone();
+                    if index < new_config.structured_outputs.len() {
+                        let removed = new_config.structured_outputs.remove(index);
+                        if new_config.active_structured_output.as_deref() == Some(&removed.name) {
+                            new_config.active_structured_output = None;
+                        }
+                        config.set(new_config);
+                    }
+                })
+            };
+            confirm.confirm(
+                ConfirmRequest::new(
+                    ConfirmAction::DeleteStructuredOutput,
+                    "Delete structured output?",
+                    format!("The \"{}\" schema will be deleted.", output.name),
+                ),
+                remove,
+            );
+        })
+    };
 
-                    // Provider selector
-                    <div class="mb-4">
-                        <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300">{"Select Provider"}</label>
-                        <select
-                            value={selected_provider_index.to_string()}
-                            onchange={on_provider_select}
-                            class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
-                        >
-                            {for config.providers.iter().enumerate().map(|(index, provider)| {
-                                html! {
-                                    <option key={index} value={index.to_string()}>
-                                        {&provider.name}
-                                    </option>
-                                }
-                            })}
-                        </select>
+    let save_structured_output = {
+        let config = config.clone();
+        let show_output_editor = show_output_editor.clone();
+        let editing_output_index = editing_output_index.clone();
+        Callback::from(move |output: StructuredOutput| {
+            let mut new_config = (*config).clone();
+            let outputs = &mut new_config.structured_outputs;
+
+            match (*editing_output_index).filter(|&index| index < outputs.len()) {
+                Some(index) => {
+                    // Keep the schema selected in the chat across a rename
+                    if new_config.active_structured_output.as_deref() == Some(&outputs[index].name) {
+                        new_config.active_structured_output = Some(output.name.clone());
+                    }
+                    outputs[index] = output;
+                }
+                None => match outputs.iter_mut().find(|o| o.name == output.name) {
+                    Some(existing) => *existing = output,
+                    None => outputs.push(output),
+                },
+            }
+
+            config.set(new_config);
+            show_output_editor.set(false);
+            editing_output_index.set(None);
+        })
+    };
+
+    let cancel_output_editor = {
+        let show_output_editor = show_output_editor.clone();
+        let editing_output_index = editing_output_index.clone();
+        Callback::from(move |_| {
+            show_output_editor.set(false);
+            editing_output_index.set(None);
+        })
+    };
+
+    let open_persona_editor = {
+        let show_persona_editor = show_persona_editor.clone();
+        let editing_persona_index = editing_persona_index.clone();
+        Callback::from(move |index: Option<usize>| {
+            editing_persona_index.set(index);
+            show_persona_editor.set(true);
+        })
+    };
+
+    let delete_persona = {
+        let config = config.clone();
+        let confirm = confirm.clone();
+        Callback::from(move |index: usize| {
+            let Some(persona) = config.personas.get(index) else {
+                return;
+            };
+
+            let remove = {
+                let config = config.clone();
+                Callback::from(move |_| {
+                    let mut new_config = (*config).clone();
+                    if index < new_config.personas.len() {
+                        new_config.personas.remove(index);
+                        config.set(new_config);
+                    }
+                })
+            };
+            confirm.confirm(
+                ConfirmRequest::new(
+                    ConfirmAction::DeletePersona,
+                    "Delete persona?",
+                    format!(
+                        "\"{}\" will be deleted. Sessions started from it go back to the global prompt and tools.",
+                        persona.name
+                    ),
+                ),
+                remove,
+            );
+        })
+    };
+
+    let save_persona = {
+        let config = config.clone();
+        let show_persona_editor = show_persona_editor.clone();
+        let editing_persona_index = editing_persona_index.clone();
+        Callback::from(move |persona: Persona| {
+            let mut new_config = (*config).clone();
+            let personas = &mut new_config.personas;
+
+            match (*editing_persona_index).filter(|&index| index < personas.len()) {
+                Some(index) => personas[index] = persona,
+                None => match personas.iter_mut().find(|p| p.name == persona.name) {
+                    Some(existing) => *existing = persona,
+                    None => personas.push(persona),
+                },
+            }
+
+            config.set(new_config);
+            show_persona_editor.set(false);
+            editing_persona_index.set(None);
+        })
+    };
+
+    let cancel_persona_editor = {
+        let show_persona_editor = show_persona_editor.clone();
+        let editing_persona_index = editing_persona_index.clone();
+        Callback::from(move |_| {
+            show_persona_editor.set(false);
+            editing_persona_index.set(None);
+        })
+    };
+
+    let update_moderation = {
+        let config = config.clone();
+        Callback::from(move |moderation: ModerationSettings| {
+            let mut new_config = (*config).clone();
+            new_config.moderation = moderation;
+            config.set(new_config);
+        })
+    };
+
+    let update_budget = {
+        let config = config.clone();
+        Callback::from(move |budget: BudgetSettings| {
+            let mut new_config = (*config).clone();
+            new_config.budget = budget;
+            config.set(new_config);
+        })
+    };
+
+    let update_starters = {
+        let config = config.clone();
+        Callback::from(move |starters: StarterSettings| {
+            let mut new_config = (*config).clone();
+            new_config.conversation_starters = starters;
+            config.set(new_config);
+        })
+    };
+
+    let update_response_length = {
+        let config = config.clone();
+        Callback::from(move |response_length: ResponseLengthSettings| {
+            let mut new_config = (*config).clone();
+            new_config.response_length = response_length;
+            config.set(new_config);
+        })
+    };
+
+    let update_self_consistency = {
+        let config = config.clone();
+        Callback::from(move |self_consistency: SelfConsistencySettings| {
+            let mut new_config = (*config).clone();
+            new_config.self_consistency = self_consistency;
+            config.set(new_config);
+        })
+    };
+
+    let update_tool_selection = {
+        let config = config.clone();
+        Callback::from(move |tool_selection: ToolSelectionSettings| {
+            let mut new_config = (*config).clone();
+            new_config.tool_selection = tool_selection;
+            config.set(new_config);
+        })
+    };
+
+    let update_fetch_tool = {
+        let config = config.clone();
+        Callback::from(move |fetch_tool: FetchToolSettings| {
+            let mut new_config = (*config).clone();
+            new_config.fetch_tool = fetch_tool;
+            config.set(new_config);
+        })
+    };
+
+    let update_web_fetch = {
+        let config = config.clone();
+        Callback::from(move |web_fetch: WebFetchSettings| {
+            let mut new_config = (*config).clone();
+            new_config.web_fetch = web_fetch;
+            config.set(new_config);
+        })
+    };
+
+    let update_web_search = {
+        let config = config.clone();
+        Callback::from(move |web_search: WebSearchSettings| {
+            let mut new_config = (*config).clone();
+            new_config.web_search = web_search;
+            config.set(new_config);
+        })
+    };
+
+    let update_code_sandbox = {
+        let config = config.clone();
+        Callback::from(move |code_sandbox: CodeSandboxSettings| {
+            let mut new_config = (*config).clone();
+            new_config.code_sandbox = code_sandbox;
+            config.set(new_config);
+        })
+    };
+
+    let set_default_tool = {
+        let config = config.clone();
+        Callback::from(move |(tool_name, enabled): (&'static str, bool)| {
+            let mut new_config = (*config).clone();
+            new_config.set_default_tool_enabled(tool_name, enabled);
+            config.set(new_config);
+        })
+    };
+
+    let update_browser_notifications = {
+        let config = config.clone();
+        Callback::from(move |browser_notifications: BrowserNotificationSettings| {
+            let mut new_config = (*config).clone();
+            new_config.browser_notifications = browser_notifications;
+            config.set(new_config);
+        })
+    };
+
+    let update_webhook = {
+        let config = config.clone();
+        Callback::from(move |webhook: WebhookSettings| {
+            let mut new_config = (*config).clone();
+            new_config.webhook = webhook;
+            config.set(new_config);
+        })
+    };
+
+    let update_follow_ups = {
+        let config = config.clone();
+        Callback::from(move |follow_ups: FollowUpSettings| {
+            let mut new_config = (*config).clone();
+            new_config.follow_ups = follow_ups;
+            config.set(new_config);
+        })
+    };
+
+    let update_desktop_tools = {
+        let config = config.clone();
+        Callback::from(move |desktop_tools: DesktopToolSettings| {
+            let mut new_config = (*config).clone();
+            new_config.desktop_tools = desktop_tools;
+            config.set(new_config);
+        })
+    };
+
+    let update_redaction = {
+        let config = config.clone();
+        Callback::from(move |redaction: RedactionSettings| {
+            let mut new_config = (*config).clone();
+            new_config.redaction = redaction;
+            config.set(new_config);
+        })
+    };
+
+    let update_guardrail = {
+        let config = config.clone();
+        Callback::from(move |(index, rule): (usize, GuardrailRule)| {
+            let mut new_config = (*config).clone();
+            if let Some(existing) = new_config.guardrails.get_mut(index) {
+                *existing = rule;
+                config.set(new_config);
+            }
+        })
+    };
+
+    let delete_guardrail = {
+        let config = config.clone();
+        Callback::from(move |index: usize| {
+            let mut new_config = (*config).clone();
+            if index < new_config.guardrails.len() {
+                new_config.guardrails.remove(index);
+                config.set(new_config);
+            }
+        })
+    };
+
+    let add_guardrail = {
+        let config = config.clone();
+        Callback::from(move |_| {
+            let mut new_config = (*config).clone();
+            new_config.guardrails.push(GuardrailRule::new());
+            config.set(new_config);
+        })
+    };
+
+    let cancel_function_editor = {
+        let show_function_editor = show_function_editor.clone();
+        let editing_function_index = editing_function_index.clone();
+        Callback::from(move |_: ()| {
+            show_function_editor.set(false);
+            editing_function_index.set(None);
+        })
+    };
+
+    let current_provider = config.providers.get(*selected_provider_index);
+
+    // Defaults of the model used by the current session, shown as hints
+    let (session_provider, session_model) = config.get_current_provider_and_model();
+    let session_defaults = config
+        .get_model_defaults(&session_provider, &session_model)
+        .cloned()
+        .unwrap_or_default();
+    let temperature_max = session_defaults
+        .max_temperature
+        .map(|cap| cap.min(1.0))
+        .unwrap_or(1.0);
+
+    let on_toggle_mode = {
+        let callback = props.on_mode_change.clone();
+        let mode = props.mode;
+        Callback::from(move |_| {
+            callback.emit(match mode {
+                SettingsPanelMode::Docked => SettingsPanelMode::Modal,
+                SettingsPanelMode::Modal => SettingsPanelMode::Docked,
+            });
+        })
+    };
+
+    // The panel is docked on the right, so its width grows as the pointer moves left
+    let on_drag = {
+        let callback = props.on_resize.clone();
+        Callback::from(move |client_x: f64| {
+            let viewport_width = web_sys::window()
+                .and_then(|w| w.inner_width().ok())
+                .and_then(|w| w.as_f64())
+                .unwrap_or(0.0);
+            callback.emit(docked_width(viewport_width, client_x));
+        })
+    };
+
+    let panel = html! {
+        <div class="flex-1 min-w-0 bg-white dark:bg-gray-800 overflow-y-auto custom-scrollbar">
+            <div class="p-4 border-b border-gray-200 dark:border-gray-700">
+                <div class="flex justify-between items-center">
+                    <h2 id="settings-panel-title" class="text-lg font-semibold text-gray-900 dark:text-gray-100">{"Settings"}</h2>
+                    <div class="flex items-center space-x-1">
+                        <button
+                            onclick={on_toggle_mode}
+                            class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-900 dark:text-gray-100"
+                            title={if props.mode == SettingsPanelMode::Docked { "Open as modal" } else { "Dock to side" }}
+                            aria-label={if props.mode == SettingsPanelMode::Docked { "Open settings as modal" } else { "Dock settings to side" }}
+                        >
+                            <i class={if props.mode == SettingsPanelMode::Docked { "fas fa-expand" } else { "fas fa-columns" }} aria-hidden="true"></i>
+                        </button>
+                        <button
+                            onclick={on_close}
+                            class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-900 dark:text-gray-100"
+                            aria-label="Close settings"
+                        >
+                            <i class="fas fa-times" aria-hidden="true"></i>
+                        </button>
+                    </div>
+                </div>
+            </div>
+
+            <div class="p-4 space-y-6">
+                // Provider Management
+                <div id="settings-providers">
+                    <div class="flex justify-between items-center mb-4">
+                        <h3 class="font-medium text-gray-900 dark:text-gray-100">{"LLM Providers"}</h3>
+                        <div class="flex space-x-2">
+                            <button
+                                onclick={
+                                    let show_add_provider = show_add_provider.clone();
+                                    Callback::from(move |_| show_add_provider.set(true))
+                                }
+                                class="text-xs px-2 py-1 bg-green-100 dark:bg-green-900/30 text-green-600 dark:text-green-400 rounded hover:bg-green-200 dark:hover:bg-green-900/50"
+                            >
+                                <i class="fas fa-plus mr-1"></i>{"Add"}
+                            </button>
+                            {if config.providers.len() > 1 {
+                                html! {
+                                    <button
+                                        onclick={on_remove_provider}
+                                        class="text-xs px-2 py-1 bg-red-100 dark:bg-red-900/30 text-red-600 dark:text-red-400 rounded hover:bg-red-200 dark:hover:bg-red-900/50"
+                                    >
+                                        <i class="fas fa-trash mr-1"></i>{"Remove"}
+                                    </button>
+                                }
+                            } else {
+                                html! {}
+                            }}
+                        </div>
+                    </div>
+
+                    {if *show_add_provider {
+                        html! {
+                            <div class="mb-4 p-3 border border-gray-200 dark:border-gray-600 rounded-md space-y-2">
+                                <div class="flex justify-between items-center">
+                                    <span class="text-sm font-medium text-gray-700 dark:text-gray-300">{"New provider"}</span>
+                                    <button
+                                        onclick={on_add_provider}
+                                        class="text-xs px-2 py-1 bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-300 rounded hover:bg-gray-200 dark:hover:bg-gray-600"
+                                    >
+                                        {"Blank OpenAI-compatible"}
+                                    </button>
+                                </div>
+                                {{
+                                    let presets = config.missing_presets();
+                                    if presets.is_empty() {
+                                        html! {}
+                                    } else {
+                                        html! {
+                                            <div class="flex flex-wrap items-center gap-1">
+                                                <span class="text-xs text-gray-600 dark:text-gray-400">{"Presets:"}</span>
+                                                {for presets.into_iter().map(|preset| {
+                                                    let on_add_preset = on_add_preset.clone();
+                                                    let name = preset.name.clone();
+                                                    html! {
+                                                        <button
+                                                            onclick={Callback::from(move |_| on_add_preset.emit(preset.clone()))}
+                                                            class="text-xs px-2 py-1 bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-300 rounded hover:bg-gray-200 dark:hover:bg-gray-600"
+                                                        >
+                                                            {name}
+                                                        </button>
+                                                    }
+                                                })}
+                                            </div>
+                                        }
+                                    }
+                                }}
+                                <LocalServerScan
+                                    providers={config.providers.clone()}
+                                    on_add={on_add_discovered}
+                                />
+                                <label for="provider-descriptor" class="block text-xs text-gray-600 dark:text-gray-400">
+                                    {"Or paste a JSON descriptor with its transformer pipeline, auth scheme (bearer, header, query or none) and endpoints"}
+                                </label>
+                                <textarea
+                                    id="provider-descriptor"
+                                    rows="8"
+                                    placeholder={PROVIDER_DESCRIPTOR_EXAMPLE}
+                                    value={(*provider_descriptor).clone()}
+                                    oninput={
+                                        let provider_descriptor = provider_descriptor.clone();
+                                        Callback::from(move |e: InputEvent| {
+                                            let input: HtmlTextAreaElement = e.target_unchecked_into();
+                                            provider_descriptor.set(input.value());
+                                        })
+                                    }
+                                    class="w-full p-2 text-xs font-mono border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                                />
+                                {if let Some(error) = &*descriptor_error {
+                                    html! { <p class="text-xs text-red-600 dark:text-red-400" role="alert">{error}</p> }
+                                } else {
+                                    html! {}
+                                }}
+                                <div class="flex justify-end space-x-2">
+                                    <button
+                                        onclick={
+                                            let show_add_provider = show_add_provider.clone();
+                                            let descriptor_error = descriptor_error.clone();
+                                            Callback::from(move |_| {
+                                                descriptor_error.set(None);
+                                                show_add_provider.set(false);
+                                            })
+                                        }
+                                        class="text-xs px-2 py-1 text-gray-600 dark:text-gray-300 hover:underline"
+                                    >
+                                        {"Cancel"}
+                                    </button>
+                                    <button
+                                        onclick={on_add_plugin_provider}
+                                        disabled={provider_descriptor.trim().is_empty()}
+                                        class="text-xs px-2 py-1 bg-primary-600 text-white rounded hover:bg-primary-700 disabled:opacity-50"
+                                    >
+                                        {"Add from JSON"}
+                                    </button>
+                                </div>
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }}
+
+                    // Provider selector
+                    <div class="mb-4">
+                        <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300">{"Select Provider"}</label>
+                        <select
+                            value={selected_provider_index.to_string()}
+                            onchange={on_provider_select}
+                            class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                        >
+                            {for config.providers.iter().enumerate().map(|(index, provider)| {
+                                html! {
+                                    <option key={index} value={index.to_string()}>
+                                        {&provider.name}
+                                    </option>
+                                }
+                            })}
+                        </select>
                     </div>
 
                     // Provider configuration
@@ -348,6 +1240,16 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
                                         }
                                         class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
                                     />
+                                    {if let Some(plugin) = &provider.plugin {
+                                        html! {
+                                            <p class="mt-1 text-xs text-gray-500 dark:text-gray-400">
+                                                <i class="fas fa-puzzle-piece mr-1" aria-hidden="true"></i>
+                                                {format!("Plugin provider: {}", plugin.summary())}
+                                            </p>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }}
                                 </div>
 
                                 <div>
@@ -367,8 +1269,16 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
                                     />
                                 </div>
 
+                                {if provider.transformer.r#use.iter().any(|t| t == "gemini") {
+                                    render_gemini_auth(&provider.gemini_auth, &on_provider_field_change)
+                                } else {
+                                    html! {}
+                                }}
+
                                 <div>
-                                    <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300">{"API Key"}</label>
+                                    <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300">
+                                        {if provider.gemini_auth.is_vertex() { "Access Token" } else { "API Key" }}
+                                    </label>
                                     <input
                                         type="password"
                                         value={provider.api_key.clone()}
@@ -398,17 +1308,54 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
                                     <div class="space-y-2">
                                         {for provider.models.iter().enumerate().map(|(model_index, model)| {
                                             let remove_callback = on_remove_model.clone();
+                                            let defaults = provider.model_defaults.get(model).cloned().unwrap_or_default();
+                                            let defaults_input = |field: &'static str, value: Option<String>, placeholder: &'static str| {
+                                                let callback = on_model_defaults_change.clone();
+                                                let model = model.clone();
+                                                html! {
+                                                    <input
+                                                        type="number"
+                                                        value={value.unwrap_or_default()}
+                                                        placeholder={placeholder}
+                                                        title={placeholder}
+                                                        oninput={Callback::from(move |e: InputEvent| {
+                                                            let input: HtmlInputElement = e.target_unchecked_into();
+                                                            callback.emit((model.clone(), field, input.value()));
+                                                        })}
+                                                        class="w-full p-1 text-xs border border-gray-300 dark:border-gray-500 rounded bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                                                    />
+                                                }
+                                            };
                                             html! {
-                                                <div key={model_index} class="flex items-center justify-between p-2 bg-gray-50 dark:bg-gray-600 rounded">
-                                                    <span class="text-sm text-gray-900 dark:text-gray-100">{model}</span>
-                                                    <button
-                                                        onclick={
-                                                            Callback::from(move |_| remove_callback.emit(model_index))
+                                                <div key={model_index} class="p-2 bg-gray-50 dark:bg-gray-600 rounded">
+                                                    <div class="flex items-center justify-between">
+                                                        <span class="text-sm text-gray-900 dark:text-gray-100">{model}</span>
+                                                        <button
+                                                            onclick={
+                                                                Callback::from(move |_| remove_callback.emit(model_index))
+                                                            }
+                                                            class="text-xs px-1 py-0.5 text-red-600 dark:text-red-400 hover:text-red-800 dark:hover:text-red-300"
+                                                            aria-label={format!("Remove model {}", model)}
+                                                        >
+                                                            <i class="fas fa-times" aria-hidden="true"></i>
+                                                        </button>
+                                                    </div>
+                                                    {if !defaults.is_empty() {
+                                                        html! {
+                                                            <div class="text-xs text-gray-500 dark:text-gray-400 mt-1">{defaults.summary()}</div>
                                                         }
-                                                        class="text-xs px-1 py-0.5 text-red-600 dark:text-red-400 hover:text-red-800 dark:hover:text-red-300"
-                                                    >
-                                                        <i class="fas fa-times"></i>
-                                                    </button>
+                                                    } else {
+                                                        html! {}
+                                                    }}
+                                                    <div class="grid grid-cols-3 gap-1 mt-1">
+                                                        {defaults_input("max_tokens", defaults.max_tokens.map(|v| v.to_string()), "Max tokens")}
+                                                        {defaults_input("max_temperature", defaults.max_temperature.map(|v| v.to_string()), "Temp cap")}
+                                                        {defaults_input("context_window", defaults.context_window.map(|v| v.to_string()), "Context")}
+                                                    </div>
+                                                    <div class="grid grid-cols-2 gap-1 mt-1">
+                                                        {defaults_input("input_price", defaults.input_price.map(|v| v.to_string()), "Input price / 1M")}
+                                                        {defaults_input("output_price", defaults.output_price.map(|v| v.to_string()), "Output price / 1M")}
+                                                    </div>
                                                 </div>
                                             }
                                         })}
@@ -466,7 +1413,7 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
                 </div>
 
                 // General Settings (same as before)
-                <div>
+                <div id="settings-general">
                     <h3 class="font-medium mb-2 text-gray-900 dark:text-gray-100">{"General Settings"}</h3>
                     <div class="mb-4">
                         <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="temperature">
@@ -476,12 +1423,21 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
                             type="range"
                             id="temperature"
                             min="0"
-                            max="1"
+                            max={temperature_max.to_string()}
                             step="0.1"
                             value={config.shared_settings.temperature.to_string()}
                             oninput={on_temperature_change}
                             class="w-full"
                         />
+                        {if let Some(cap) = session_defaults.max_temperature {
+                            html! {
+                                <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                                    {format!("{} caps temperature at {:.1}", session_model, cap)}
+                                </p>
+                            }
+                        } else {
+                            html! {}
+                        }}
                     </div>
                     <div class="mb-4">
                         <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="max-tokens">{"Max Tokens"}</label>
@@ -492,6 +1448,41 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
                             oninput={on_max_tokens_change}
                             class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
                         />
+                        {if session_defaults.max_tokens.is_some() || session_defaults.context_window.is_some() {
+                            html! {
+                                <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                                    {format!("{} defaults: {}", session_model, session_defaults.summary())}
+                                </p>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                    </div>
+                    {render_custom_length_settings(&config.response_length, &update_response_length)}
+                    <div class="mb-4">
+                        <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="stop-sequences">{"Stop Sequences"}</label>
+                        <textarea
+                            id="stop-sequences"
+                            rows="2"
+                            value={sampling_controls::format_stop_sequences(&config.shared_settings.stop_sequences)}
+                            oninput={on_stop_sequences_change}
+                            class="w-full p-2 font-mono text-sm border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                            placeholder="###"
+                        />
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                            {format!("Optional. One per line, up to {}; write \\n for a newline. The reply ends before the first one generated.", MAX_STOP_SEQUENCES)}
+                        </p>
+                    </div>
+                    <div class="mb-4">
+                        <span class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300">{"Logit Bias"}</span>
+                        <LogitBiasEditor
+                            entries={config.shared_settings.logit_bias.clone()}
+                            on_change={on_logit_bias_change}
+                            cors_proxy={config.shared_settings.cors_proxy().map(str::to_string)}
+                        />
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                            {"Optional, OpenAI-compatible providers only. Text is split into tokens with the chosen tokenizer, downloaded on first use; give words their leading space as they appear mid-sentence. -100 bans a token, 100 forces it."}
+                        </p>
                     </div>
                     <div class="mb-4">
                         <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="retry-delay">{"Retry Delay (ms)"}</label>
@@ -503,10 +1494,156 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
                             class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
                         />
                     </div>
+                    <div class="mb-4">
+                        <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="candidate-count">{"Candidates"}</label>
+                        <input
+                            type="number"
+                            id="candidate-count"
+                            min="1"
+                            max={MAX_CANDIDATES.to_string()}
+                            value={config.shared_settings.candidate_count().to_string()}
+                            oninput={on_candidate_count_change}
+                            class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                        />
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">{"Replies sampled for each prompt, shown as tabs on the reply. OpenAI-compatible APIs return them in one request; other providers are asked again for each."}</p>
+                    </div>
+                    <div class="mb-4">
+                        <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="json-repair-retries">{"JSON Repair Retries"}</label>
+                        <input
+                            type="number"
+                            id="json-repair-retries"
+                            min="0"
+                            max="5"
+                            value={config.shared_settings.json_repair_retries().to_string()}
+                            oninput={on_json_repair_retries_change}
+                            class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                        />
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">{"Correction turns sent when a structured reply or tool arguments are invalid, or a retry guardrail is broken. 0 turns this off."}</p>
+                    </div>
+                    <div class="mb-4">
+                        <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="context-cache-ttl">{"Gemini Context Cache (minutes)"}</label>
+                        <input
+                            type="number"
+                            id="context-cache-ttl"
+                            min="1"
+                            value={config.shared_settings.context_cache_ttl_minutes.map(|m| m.to_string()).unwrap_or_default()}
+                            oninput={on_context_cache_ttl_change}
+                            class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                            placeholder="Off"
+                        />
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1 mb-2">
+                            {format!(
+                                "Optional. System prompts and tools of about {} tokens or more are cached on Gemini for this long and billed at a discount. Leave empty to send them with every request.",
+                                gemini_cache::MIN_CACHE_TOKENS
+                            )}
+                        </p>
+                        <GeminiCacheList
+                            providers={config.providers.clone()}
+                            cors_proxy={config.shared_settings.cors_proxy().map(str::to_string)}
+                            ttl_minutes={config.shared_settings.context_cache_ttl().unwrap_or(gemini_cache::DEFAULT_CACHE_TTL_MINUTES)}
+                        />
+                    </div>
+                    <div class="mb-4">
+                        <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="cors-proxy">{"CORS Proxy"}</label>
+                        <input
+                            type="text"
+                            id="cors-proxy"
+                            value={config.shared_settings.cors_proxy.clone().unwrap_or_default()}
+                            oninput={on_cors_proxy_change}
+                            class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                            placeholder="https://corsproxy.io/?url={url}"
+                        />
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                            {"Optional. Provider, fetch and MCP requests are routed through this proxy. Use {url} as the target placeholder, or leave it out to append the target URL."}
+                        </p>
+                    </div>
+                    <div class="mb-4">
+                        <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="sub-agent-model">{"Sub-agent Model"}</label>
+                        <select
+                            id="sub-agent-model"
+                            onchange={on_sub_agent_model_change}
+                            class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                        >
+                            <option value="" selected={config.sub_agent_model.is_none()}>{"Same as chat model"}</option>
+                            {for config.providers.iter().flat_map(|provider| {
+                                let sub_agent_model = config.sub_agent_model.clone();
+                                provider.models.iter().map(move |model| {
+                                    let value = format!("{},{}", provider.name, model);
+                                    let selected = sub_agent_model.as_deref() == Some(value.as_str());
+                                    html! {
+                                        <option value={value.clone()} {selected}>
+                                            {format!("{} / {}", provider.name, model)}
+                                        </option>
+                                    }
+                                })
+                            })}
+                        </select>
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                            {"Model the Task tool runs its sub-agents on."}
+                        </p>
+                    </div>
+                    <div class="mb-4">
+                        <label class="flex items-center text-sm text-gray-700 dark:text-gray-300">
+                            <input
+                                type="checkbox"
+                                class="mr-2"
+                                checked={!config.hide_message_footer}
+                                onchange={on_message_footer_change}
+                            />
+                            {"Show message details"}
+                        </label>
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                            {"Time since each message, and the model, tokens and latency of replies."}
+                        </p>
+                    </div>
+                    <div class="mb-4">
+                        <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="reasoning-visibility">{"Reasoning"}</label>
+                        <select
+                            id="reasoning-visibility"
+                            onchange={on_reasoning_visibility_change}
+                            class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                        >
+                            {for ReasoningVisibility::ALL.iter().map(|visibility| html! {
+                                <option selected={config.shared_settings.reasoning == *visibility} value={visibility.label()}>
+                                    {visibility.label()}
+                                </option>
+                            })}
+                        </select>
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                            {"Whether thinking models return their reasoning. Sent as includeThoughts to Gemini and include_reasoning to OpenAI-compatible APIs that take it, such as OpenRouter. Hidden reasoning is dropped even when a provider returns it."}
+                        </p>
+                        <label class="flex items-center mt-2 text-sm text-gray-700 dark:text-gray-300">
+                            <input
+                                type="checkbox"
+                                class="mr-2"
+                                checked={!config.hide_reasoning}
+                                onchange={on_hide_reasoning_change}
+                            />
+                            {"Show reasoning sections"}
+                        </label>
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                            {"Off keeps transcripts clean; the brain button on a message still shows its reasoning."}
+                        </p>
+                    </div>
+                    {render_audio_output_settings(&config.shared_settings.audio_output, update_audio_output)}
+                    {if has_skipped_confirmations() {
+                        html! {
+                            <div class="mb-4">
+                                <button
+                                    onclick={on_reset_confirmations}
+                                    class="text-sm text-primary-600 dark:text-primary-400 hover:underline"
+                                >
+                                    {"Re-enable skipped confirmation prompts"}
+                                </button>
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }}
                 </div>
 
                 // System Prompt
-                <div>
+                <div id="settings-system-prompt">
                     <h3 class="font-medium mb-2 text-gray-900 dark:text-gray-100">{"System Prompt"}</h3>
                     <textarea
                         id="system-prompt"
@@ -518,9 +1655,18 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
                 </div>
 
                 // Function Tools
-                <div>
+                <div id="settings-tools">
                     <div class="flex items-center justify-between mb-4">
-                        <h3 class="font-medium text-gray-900 dark:text-gray-100">{"Function Tools"}</h3>
+                        <div>
+                            <h3 class="font-medium text-gray-900 dark:text-gray-100">{"Function Tools"}</h3>
+                            <p class="text-xs text-gray-500 dark:text-gray-400" title="Estimated from the request the current provider would send">
+                                {format!(
+                                    "{} enabled · ≈{} tokens of definitions per request",
+                                    config.get_enabled_function_tools().len(),
+                                    context_usage::tool_definition_tokens(&config)
+                                )}
+                            </p>
+                        </div>
                         <div class="flex items-center space-x-2">
                             <span class="text-sm text-gray-600 dark:text-gray-300">{"Editor:"}</span>
                             <button
@@ -544,107 +1690,538 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
                             </button>
                         </div>
                     </div>
-                    {for config.function_tools.iter().enumerate().map(|(index, tool)| {
-                        let edit_callback = edit_function_tool.clone();
-                        let delete_callback = delete_function_tool.clone();
-                        let toggle_callback = toggle_function_tool.clone();
+                    <div class="mb-4">
+                        <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="tool-choice">{"Tool choice"}</label>
+                        <select
+                            id="tool-choice"
+                            onchange={on_tool_choice_change}
+                            class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                        >
+                            {for ToolChoice::ALL.iter().map(|choice| html! {
+                                <option selected={config.shared_settings.tool_choice == *choice} value={choice.openai_value()}>
+                                    {choice.label()}
+                                </option>
+                            })}
+                        </select>
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                            {"Sent as tool_choice to OpenAI-compatible APIs and as the function calling mode (AUTO, NONE or ANY) to Gemini. The options menu in the input bar overrides it for one message."}
+                        </p>
+                        {if config.shared_settings.tool_choice == ToolChoice::Required && !config.get_enabled_function_tools().is_empty() {
+                            html! {
+                                <fieldset class="mt-2">
+                                    <legend class="text-xs font-medium text-gray-700 dark:text-gray-300">{"Allowed functions (Gemini)"}</legend>
+                                    <div class="flex flex-wrap gap-x-3 gap-y-1 mt-1">
+                                        {for config.get_enabled_function_tools().into_iter().map(|tool| {
+                                            let name = tool.name.clone();
+                                            let toggle = toggle_allowed_function.clone();
+                                            html! {
+                                                <label class="flex items-center text-xs font-mono text-gray-700 dark:text-gray-300">
+                                                    <input
+                                                        type="checkbox"
+                                                        class="mr-1"
+                                                        checked={config.shared_settings.allowed_functions.contains(&tool.name)}
+                                                        onchange={Callback::from(move |_| toggle.emit(name.clone()))}
+                                                    />
+                                                    {&tool.name}
+                                                </label>
+                                            }
+                                        })}
+                                    </div>
+                                    <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                                        {"Sent as allowedFunctionNames, so Gemini must call one of these. None checked lets it call any enabled function."}
+                                    </p>
+                                </fieldset>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                    </div>
+                    <fieldset class="mb-4">
+                        <legend class="text-sm font-medium mb-1 text-gray-700 dark:text-gray-300">{"Native tools (OpenAI)"}</legend>
+                        {{
+                            let settings = config.shared_settings.native_tools.clone();
+                            let toggle = |edit: fn(&mut NativeToolSettings)| {
+                                let update = update_native_tools.clone();
+                                let settings = settings.clone();
+                                Callback::from(move |_: Event| {
+                                    let mut settings = settings.clone();
+                                    edit(&mut settings);
+                                    update.emit(settings);
+                                })
+                            };
+                            html! {
+                                <div class="flex flex-wrap gap-x-4 gap-y-1">
+                                    <label class="flex items-center text-sm text-gray-700 dark:text-gray-300">
+                                        <input
+                                            type="checkbox"
+                                            class="mr-2"
+                                            checked={settings.web_search}
+                                            onchange={toggle(|s| s.web_search = !s.web_search)}
+                                        />
+                                        <i class="fas fa-globe mr-1 text-gray-500" aria-hidden="true"></i>
+                                        {"Web search"}
+                                    </label>
+                                    <label class="flex items-center text-sm text-gray-700 dark:text-gray-300">
+                                        <input
+                                            type="checkbox"
+                                            class="mr-2"
+                                            checked={settings.code_interpreter}
+                                            onchange={toggle(|s| s.code_interpreter = !s.code_interpreter)}
+                                        />
+                                        <i class="fas fa-terminal mr-1 text-gray-500" aria-hidden="true"></i>
+                                        {"Code interpreter"}
+                                    </label>
+                                </div>
+                            }
+                        }}
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                            {"Run by OpenAI itself, not in the browser. With either on, OpenAI-compatible providers are called through the Responses API, which only OpenAI and a few gateways serve; searches and code runs are shown above the reply. Gemini ignores these."}
+                        </p>
+                    </fieldset>
+                    {{
+                        let costs = context_usage::tool_costs(&config);
+                        let render_tool = |index: usize| {
+                            let tool = &config.function_tools[index];
+                            let tokens = costs[index].1;
+                            let edit_callback = edit_function_tool.clone();
+                            let delete_callback = delete_function_tool.clone();
+                            let toggle_callback = toggle_function_tool.clone();
 
-                        let edit_click = {
-                            let edit_callback = edit_callback.clone();
-                            Callback::from(move |_| edit_callback.emit(index))
-                        };
+                            let edit_click = {
+                                let edit_callback = edit_callback.clone();
+                                Callback::from(move |_| edit_callback.emit(index))
+                            };
 
-                        let delete_click = {
-                            let delete_callback = delete_callback.clone();
-                            Callback::from(move |_| delete_callback.emit(index))
-                        };
+                            let delete_click = {
+                                let delete_callback = delete_callback.clone();
+                                Callback::from(move |_| delete_callback.emit(index))
+                            };
 
-                        let toggle_click = {
-                            let toggle_callback = toggle_callback.clone();
-                            Callback::from(move |_| toggle_callback.emit(index))
-                        };
+                            let toggle_click = {
+                                let toggle_callback = toggle_callback.clone();
+                                Callback::from(move |_| toggle_callback.emit(index))
+                            };
 
-                        html! {
-                            <div key={index} class="bg-gray-100 dark:bg-gray-700 p-4 rounded-md mb-3 border border-gray-200 dark:border-gray-600">
-                                <div class="flex items-start justify-between mb-2">
-                                    <div class="flex-1">
-                                        <div class="flex items-center mb-1">
-                                            <i class="fas fa-function text-purple-500 mr-2"></i>
-                                            <span class="font-medium text-lg text-gray-900 dark:text-gray-100">{&tool.name}</span>
-                                            <span class={format!("ml-2 px-2 py-1 text-xs rounded-full {}",
-                                                if tool.enabled {
-                                                    "bg-green-100 text-green-800 dark:bg-green-900/30 dark:text-green-400"
-                                                } else {
-                                                    "bg-gray-100 text-gray-800 dark:bg-gray-800 dark:text-gray-400"
-                                                }
-                                            )}>
-                                                {if tool.enabled { "Enabled" } else { "Disabled" }}
-                                            </span>
-                                            <span class="ml-2 px-2 py-1 text-xs rounded bg-blue-100 text-blue-800 dark:bg-blue-900/30 dark:text-blue-400">
-                                                {&tool.category}
-                                            </span>
-                                        </div>
-                                        <p class="text-sm text-gray-600 dark:text-gray-300 mb-2">{&tool.description}</p>
-
-                                        // Show parameter count
-                                        <div class="text-xs text-gray-500 dark:text-gray-400">
-                                            {
-                                                if let Some(properties) = tool.parameters.get("properties") {
-                                                    if let Some(obj) = properties.as_object() {
-                                                        format!("{} parameter(s)", obj.len())
+                            html! {
+                                <div key={index} class="bg-gray-100 dark:bg-gray-700 p-4 rounded-md mb-3 border border-gray-200 dark:border-gray-600">
+                                    <div class="flex items-start justify-between mb-2">
+                                        <div class="flex-1">
+                                            <div class="flex items-center mb-1">
+                                                <i class="fas fa-function text-purple-500 mr-2"></i>
+                                                <span class="font-medium text-lg text-gray-900 dark:text-gray-100" title={tool.name.clone()}>{config.tool_label(tool)}</span>
+                                                <span class={format!("ml-2 px-2 py-1 text-xs rounded-full {}",
+                                                    if tool.enabled {
+                                                        "bg-green-100 text-green-800 dark:bg-green-900/30 dark:text-green-400"
+                                                    } else {
+                                                        "bg-gray-100 text-gray-800 dark:bg-gray-800 dark:text-gray-400"
+                                                    }
+                                                )}>
+                                                    {if tool.enabled { "Enabled" } else { "Disabled" }}
+                                                </span>
+                                                <span class="ml-2 px-2 py-1 text-xs rounded bg-blue-100 text-blue-800 dark:bg-blue-900/30 dark:text-blue-400">
+                                                    {&tool.category}
+                                                </span>
+                                            </div>
+                                            <p class="text-sm text-gray-600 dark:text-gray-300 mb-2">{&tool.description}</p>
+
+                                            // Show parameter count
+                                            <div class="text-xs text-gray-500 dark:text-gray-400">
+                                                {
+                                                    if let Some(properties) = tool.parameters.get("properties") {
+                                                        if let Some(obj) = properties.as_object() {
+                                                            format!("{} parameter(s)", obj.len())
+                                                        } else {
+                                                            "No parameters".to_string()
+                                                        }
                                                     } else {
                                                         "No parameters".to_string()
                                                     }
-                                                } else {
-                                                    "No parameters".to_string()
                                                 }
-                                            }
+                                                {format!(" · ≈{} tokens", tokens)}
+                                            </div>
+                                        </div>
+                                        <div class="flex space-x-2 ml-4">
+                                            <button
+                                                onclick={toggle_click}
+                                                class={format!("text-xs px-3 py-1 rounded hover:opacity-80 transition-colors {}",
+                                                    if tool.enabled {
+                                                        "bg-green-100 dark:bg-green-900/30 text-green-600 dark:text-green-400"
+                                                    } else {
+                                                        "bg-gray-100 dark:bg-gray-800 text-gray-600 dark:text-gray-400"
+                                                    }
+                                                )}
+                                                title={if tool.enabled { "Disable tool" } else { "Enable tool" }}
+                                            >
+                                                <i class={if tool.enabled { "fas fa-toggle-on" } else { "fas fa-toggle-off" }}></i>
+                                            </button>
+                                            <button
+                                                onclick={edit_click}
+                                                class="text-xs px-2 py-1 bg-blue-100 dark:bg-blue-900/30 text-blue-600 dark:text-blue-400 rounded hover:bg-blue-200 dark:hover:bg-blue-900/50"
+                                                title="Edit function"
+                                            >
+                                                <i class="fas fa-edit"></i>
+                                            </button>
+                                            <button
+                                                onclick={delete_click}
+                                                class="text-xs px-2 py-1 bg-red-100 dark:bg-red-900/30 text-red-600 dark:text-red-400 rounded hover:bg-red-200 dark:hover:bg-red-900/50"
+                                                title="Delete function"
+                                            >
+                                                <i class="fas fa-trash"></i>
+                                            </button>
                                         </div>
                                     </div>
-                                    <div class="flex space-x-2 ml-4">
-                                        <button
-                                            onclick={toggle_click}
-                                            class={format!("text-xs px-3 py-1 rounded hover:opacity-80 transition-colors {}",
-                                                if tool.enabled {
-                                                    "bg-green-100 dark:bg-green-900/30 text-green-600 dark:text-green-400"
-                                                } else {
-                                                    "bg-gray-100 dark:bg-gray-800 text-gray-600 dark:text-gray-400"
-                                                }
-                                            )}
-                                            title={if tool.enabled { "Disable tool" } else { "Enable tool" }}
-                                        >
-                                            <i class={if tool.enabled { "fas fa-toggle-on" } else { "fas fa-toggle-off" }}></i>
-                                        </button>
-                                        <button
-                                            onclick={edit_click}
-                                            class="text-xs px-2 py-1 bg-blue-100 dark:bg-blue-900/30 text-blue-600 dark:text-blue-400 rounded hover:bg-blue-200 dark:hover:bg-blue-900/50"
-                                            title="Edit function"
-                                        >
-                                            <i class="fas fa-edit"></i>
-                                        </button>
-                                        <button
-                                            onclick={delete_click}
-                                            class="text-xs px-2 py-1 bg-red-100 dark:bg-red-900/30 text-red-600 dark:text-red-400 rounded hover:bg-red-200 dark:hover:bg-red-900/50"
-                                            title="Delete function"
-                                        >
-                                            <i class="fas fa-trash"></i>
-                                        </button>
+                                </div>
+                            }
+                        };
+                        html! {
+                            {for config.function_tool_groups().into_iter().map(|(server, indices)| match server {
+                                None => html! { {for indices.into_iter().map(render_tool)} },
+                                Some(server) => {
+                                    let enabled = indices.iter().filter(|&&index| config.function_tools[index].enabled).count();
+                                    let all_enabled = enabled == indices.len();
+                                    let toggle_click = {
+                                        let toggle = toggle_mcp_server_tools.clone();
+                                        let server = server.to_string();
+                                        Callback::from(move |e: MouseEvent| {
+                                    // Don't fold the group as well
+                                    e.prevent_default();
+                                    toggle.emit((server.clone(), !all_enabled));
+                                })
+                                    };
+                                    html! {
+                                        <details key={server.to_string()} open=true class="mb-3">
+                                            <summary class="flex items-center gap-2 mb-2 cursor-pointer text-sm font-medium text-gray-700 dark:text-gray-300">
+                                                <i class="fas fa-plug text-gray-500" aria-hidden="true"></i>
+                                                <span class="flex-1">{format!("MCP: {}", server)}</span>
+                                                <span class="text-xs text-gray-500 dark:text-gray-400">{format!("{} of {} enabled", enabled, indices.len())}</span>
+                                                <button
+                                                    onclick={toggle_click}
+                                                    class="text-xs px-2 py-1 rounded bg-gray-100 dark:bg-gray-800 text-gray-600 dark:text-gray-400 hover:opacity-80"
+                                                    title={if all_enabled { "Disable all tools of this server" } else { "Enable all tools of this server" }}
+                                                >
+                                                    <i class={if all_enabled { "fas fa-toggle-on" } else { "fas fa-toggle-off" }}></i>
+                                                </button>
+                                            </summary>
+                                            <div class="pl-4">
+                                                {for indices.into_iter().map(render_tool)}
+                                            </div>
+                                        </details>
+                                    }
+                                }
+                            })}
+                        }
+                    }}
+
+                    <button
+                        onclick={add_function_tool}
+                        class="flex items-center justify-center w-full p-3 border-2 border-dashed border-gray-300 dark:border-gray-600 rounded-md text-gray-500 dark:text-gray-400 hover:border-primary-500 hover:text-primary-500 dark:hover:border-primary-400 dark:hover:text-primary-400 transition-colors"
+                    >
+                        <i class="fas fa-plus mr-2"></i> {"Add Function Tool"}
+                    </button>
+                </div>
+
+                // Tool pre-selection
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Tool Pre-selection"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"Before each message is answered, a model picks the enabled tools it needs and only those are sent. A small, fast model keeps this cheap."}
+                    </p>
+                    {render_tool_selection_settings(&config, &update_tool_selection)}
+                </div>
+
+                // Fetch tool output
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Fetch Tool"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"How the fetch tool hands responses to the model. A call can ask for another format or a smaller size."}
+                    </p>
+                    {render_fetch_tool_settings(&config.fetch_tool, &update_fetch_tool)}
+                </div>
+
+                // WebFetch reader model
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"WebFetch Tool"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"WebFetch fetches a page and has this model answer the call's prompt about it, so only the answer reaches the chat."}
+                    </p>
+                    {render_web_fetch_settings(&config, &update_web_fetch)}
+                </div>
+
+                // WebSearch backend
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"WebSearch Tool"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"The search API WebSearch queries. Requests go through the CORS proxy when one is set."}
+                    </p>
+                    {render_web_search_settings(&config.web_search, &update_web_search)}
+                </div>
+
+                // Code execution sandbox
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Code Execution"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"Let models run code in a browser sandbox without network or storage access. Python downloads Pyodide on its first run."}
+                    </p>
+                    {render_code_sandbox_settings(&config, &update_code_sandbox, &set_default_tool)}
+                </div>
+
+                // Chart tool
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Charts"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"Let models draw bar, line, area and pie charts in the chat, e.g. of run_sql results."}
+                    </p>
+                    {render_tool_toggle(&config, &set_default_tool, chart::RENDER_CHART_TOOL_NAME, "Draw charts")}
+                </div>
+
+                // Clipboard and download tools
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Clipboard and Downloads"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"Let models hand you their results directly. You're asked to allow every copy and download."}
+                    </p>
+                    <div class="space-y-2">
+                        {render_tool_toggle(&config, &set_default_tool, handoff_tools::COPY_TO_CLIPBOARD_TOOL_NAME, "Copy to clipboard")}
+                        {render_tool_toggle(&config, &set_default_tool, handoff_tools::SAVE_FILE_TOOL_NAME, "Save files")}
+                    </div>
+                </div>
+
+                // Structured Outputs
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Structured Outputs"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"JSON schemas a reply can be required to follow. Pick one under the chat input."}
+                    </p>
+                    {for config.structured_outputs.iter().enumerate().map(|(index, output)| {
+                        let edit_click = {
+                            let open_output_editor = open_output_editor.clone();
+                            Callback::from(move |_| open_output_editor.emit(Some(index)))
+                        };
+                        let delete_click = {
+                            let delete_structured_output = delete_structured_output.clone();
+                            Callback::from(move |_| delete_structured_output.emit(index))
+                        };
+                        let field_count = output
+                            .schema
+                            .get("properties")
+                            .and_then(|p| p.as_object())
+                            .map_or(0, |p| p.len());
+                        let is_active = config.active_structured_output.as_deref() == Some(&output.name);
+
+                        html! {
+                            <div key={index} class="flex items-center justify-between bg-gray-100 dark:bg-gray-700 p-3 rounded-md mb-3 border border-gray-200 dark:border-gray-600">
+                                <div>
+                                    <div class="flex items-center">
+                                        <i class="fas fa-code text-purple-500 mr-2" aria-hidden="true"></i>
+                                        <span class="font-medium text-gray-900 dark:text-gray-100">{&output.name}</span>
+                                        {if is_active {
+                                            html! {
+                                                <span class="ml-2 px-2 py-0.5 text-xs rounded-full bg-green-100 text-green-800 dark:bg-green-900/30 dark:text-green-400">{"Active"}</span>
+                                            }
+                                        } else {
+                                            html! {}
+                                        }}
                                     </div>
+                                    <div class="text-xs text-gray-500 dark:text-gray-400">{format!("{} top-level field(s)", field_count)}</div>
+                                </div>
+                                <div class="flex space-x-2 ml-4">
+                                    <button
+                                        onclick={edit_click}
+                                        class="text-xs px-2 py-1 bg-blue-100 dark:bg-blue-900/30 text-blue-600 dark:text-blue-400 rounded hover:bg-blue-200 dark:hover:bg-blue-900/50"
+                                        title="Edit schema"
+                                        aria-label={format!("Edit schema {}", output.name)}
+                                    >
+                                        <i class="fas fa-edit" aria-hidden="true"></i>
+                                    </button>
+                                    <button
+                                        onclick={delete_click}
+                                        class="text-xs px-2 py-1 bg-red-100 dark:bg-red-900/30 text-red-600 dark:text-red-400 rounded hover:bg-red-200 dark:hover:bg-red-900/50"
+                                        title="Delete schema"
+                                        aria-label={format!("Delete schema {}", output.name)}
+                                    >
+                                        <i class="fas fa-trash" aria-hidden="true"></i>
+                                    </button>
+                                </div>
+                            </div>
+                        }
+                    })}
+                    <button
+                        onclick={
+                            let open_output_editor = open_output_editor.clone();
+                            Callback::from(move |_| open_output_editor.emit(None))
+                        }
+                        class="flex items-center justify-center w-full p-3 border-2 border-dashed border-gray-300 dark:border-gray-600 rounded-md text-gray-500 dark:text-gray-400 hover:border-primary-500 hover:text-primary-500 dark:hover:border-primary-400 dark:hover:text-primary-400 transition-colors"
+                    >
+                        <i class="fas fa-plus mr-2" aria-hidden="true"></i> {"Add Structured Output"}
+                    </button>
+                </div>
+
+                // Personas
+                <div id="settings-personas">
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Personas"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"A system prompt, model, tools and temperature to start new sessions from."}
+                    </p>
+                    {for config.personas.iter().enumerate().map(|(index, persona)| {
+                        let edit_click = {
+                            let open_persona_editor = open_persona_editor.clone();
+                            Callback::from(move |_| open_persona_editor.emit(Some(index)))
+                        };
+                        let delete_click = {
+                            let delete_persona = delete_persona.clone();
+                            Callback::from(move |_| delete_persona.emit(index))
+                        };
+                        let model = persona
+                            .model()
+                            .map(|(provider, model)| format!("{} - {}", provider, model))
+                            .unwrap_or_else(|| "Current model".to_string());
+
+                        html! {
+                            <div key={persona.name.clone()} class="flex items-center justify-between bg-gray-100 dark:bg-gray-700 p-3 rounded-md mb-3 border border-gray-200 dark:border-gray-600">
+                                <div class="min-w-0">
+                                    <div class="font-medium text-gray-900 dark:text-gray-100 truncate">{persona.label()}</div>
+                                    <div class="text-xs text-gray-500 dark:text-gray-400 truncate">
+                                        {format!("{} · {} tool(s)", model, persona.enabled_tools.len())}
+                                    </div>
+                                </div>
+                                <div class="flex space-x-2 ml-4">
+                                    <button
+                                        onclick={edit_click}
+                                        class="text-xs px-2 py-1 bg-blue-100 dark:bg-blue-900/30 text-blue-600 dark:text-blue-400 rounded hover:bg-blue-200 dark:hover:bg-blue-900/50"
+                                        title="Edit persona"
+                                        aria-label={format!("Edit persona {}", persona.name)}
+                                    >
+                                        <i class="fas fa-edit" aria-hidden="true"></i>
+                                    </button>
+                                    <button
+                                        onclick={delete_click}
+                                        class="text-xs px-2 py-1 bg-red-100 dark:bg-red-900/30 text-red-600 dark:text-red-400 rounded hover:bg-red-200 dark:hover:bg-red-900/50"
+                                        title="Delete persona"
+                                        aria-label={format!("Delete persona {}", persona.name)}
+                                    >
+                                        <i class="fas fa-trash" aria-hidden="true"></i>
+                                    </button>
                                 </div>
                             </div>
                         }
                     })}
+                    <button
+                        onclick={
+                            let open_persona_editor = open_persona_editor.clone();
+                            Callback::from(move |_| open_persona_editor.emit(None))
+                        }
+                        class="flex items-center justify-center w-full p-3 border-2 border-dashed border-gray-300 dark:border-gray-600 rounded-md text-gray-500 dark:text-gray-400 hover:border-primary-500 hover:text-primary-500 dark:hover:border-primary-400 dark:hover:text-primary-400 transition-colors"
+                    >
+                        <i class="fas fa-plus mr-2" aria-hidden="true"></i> {"Add Persona"}
+                    </button>
+                </div>
 
+                // Moderation
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Moderation"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"Check messages with an OpenAI-compatible /moderations endpoint and flag them in the chat."}
+                    </p>
+                    {render_moderation_settings(&config, &update_moderation)}
+                </div>
+
+                // Guardrails
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Guardrails"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"Keyword or regex rules checked against every assistant reply. Retry uses the JSON repair retry count."}
+                    </p>
+                    {for config.guardrails.iter().enumerate().map(|(index, rule)| {
+                        render_guardrail_rule(index, rule, &update_guardrail, &delete_guardrail)
+                    })}
                     <button
-                        onclick={add_function_tool}
+                        onclick={add_guardrail}
                         class="flex items-center justify-center w-full p-3 border-2 border-dashed border-gray-300 dark:border-gray-600 rounded-md text-gray-500 dark:text-gray-400 hover:border-primary-500 hover:text-primary-500 dark:hover:border-primary-400 dark:hover:text-primary-400 transition-colors"
                     >
-                        <i class="fas fa-plus mr-2"></i> {"Add Function Tool"}
+                        <i class="fas fa-plus mr-2" aria-hidden="true"></i> {"Add Guardrail"}
                     </button>
                 </div>
 
-                // MCP Settings
+                // Outgoing message redaction
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Sensitive Data"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"Regex rules checked against typed messages before they are sent. Matches can be masked or sent as written."}
+                    </p>
+                    {render_redaction_settings(&config.redaction, &update_redaction)}
+                </div>
+
+                // Conversation starters
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Conversation Starters"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"Prompts offered on an empty session, one per line. Clicking one sends it."}
+                    </p>
+                    {render_starter_settings(&config.conversation_starters, &update_starters)}
+                </div>
+
+                // Follow-up suggestions
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Follow-up Suggestions"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"Questions suggested under each reply. A small, fast model keeps this cheap."}
+                    </p>
+                    {render_follow_up_settings(&config, &update_follow_ups)}
+                </div>
+
+                // Browser notifications
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Browser Notifications"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"A system notification when a long reply or agent run finishes while the tab is in the background."}
+                    </p>
+                    {render_browser_notification_settings(&config.browser_notifications, &update_browser_notifications)}
+                </div>
+
+                // Completion webhook
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Webhook"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"Posted to when a reply completes or fails, e.g. to log conversations to a spreadsheet."}
+                    </p>
+                    {render_webhook_settings(&config.webhook, &update_webhook)}
+                </div>
+
+                // Self-consistency voting
+                <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Self-Consistency"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"Answer each prompt several times and reply with the consensus. A majority vote compares the final line or \"Answer:\" of each sample; model aggregation asks the session's model to merge them, at the cost of one more request. The samples are listed under the reply."}
+                    </p>
+                    {render_self_consistency_settings(&config.self_consistency, &update_self_consistency)}
+                </div>
+
+                // Session budget
                 <div>
+                    <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Session Budget"}</h3>
+                    <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                        {"The agent pauses before running more tools once a session passes one of these limits, and asks whether to continue. Cost uses the input and output prices set on each model. Leave empty for no limit."}
+                    </p>
+                    {render_budget_settings(&config.budget, &update_budget)}
+                </div>
+
+                // Desktop tools
+                {if desktop_tools::is_desktop() {
+                    html! {
+                        <div>
+                            <h3 class="font-medium mb-1 text-gray-900 dark:text-gray-100">{"Desktop Tools"}</h3>
+                            <p class="text-xs text-gray-500 dark:text-gray-400 mb-4">
+                                {"Run Bash, Read, Write, Edit, Glob, Grep and LS on this machine instead of returning their mock responses."}
+                            </p>
+                            {render_desktop_tool_settings(&config.desktop_tools, &update_desktop_tools)}
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }}
+
+                // MCP Settings
+                <div id="settings-mcp">
                     <h3 class="font-medium mb-4 text-gray-900 dark:text-gray-100">{"MCP Servers"}</h3>
                     <McpSettingsPanel
                         config={
@@ -655,6 +2232,7 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
                                     api_key: "".to_string(),
                                     model: "".to_string(),
                                     base_url: "".to_string(),
+                                    auth: Default::default(),
                                 },
                                 openai: crate::llm_playground::types::OpenAIConfig {
                                     base_url: "".to_string(),
@@ -722,6 +2300,1391 @@ pub fn flexible_settings_panel(props: &FlexibleSettingsPanelProps) -> Html {
             } else {
                 html! {}
             }}
+
+            // Structured Output Editor Modal
+            {if *show_output_editor {
+                let editing_output = (*editing_output_index)
+                    .and_then(|index| config.structured_outputs.get(index).cloned());
+                html! {
+                    <StructuredOutputEditor
+                        output={editing_output}
+                        on_save={save_structured_output}
+                        on_cancel={cancel_output_editor}
+                    />
+                }
+            } else {
+                html! {}
+            }}
+
+            // Persona Editor Modal
+            {if *show_persona_editor {
+                let editing_persona = (*editing_persona_index)
+                    .and_then(|index| config.personas.get(index).cloned());
+                html! {
+                    <PersonaEditor
+                        persona={editing_persona}
+                        config={(*config).clone()}
+                        on_save={save_persona}
+                        on_cancel={cancel_persona_editor}
+                    />
+                }
+            } else {
+                html! {}
+            }}
+
+            {confirm.dialog()}
         </div>
+    };
+
+    match props.mode {
+        SettingsPanelMode::Docked => html! {
+            <aside
+                class="flex flex-shrink-0 h-full border-l border-gray-200 dark:border-gray-700"
+                style={format!("width: {}px", props.width)}
+                aria-labelledby="settings-panel-title"
+            >
+                <ResizeHandle on_drag={on_drag} on_drag_end={props.on_resize_end.clone()} />
+                {panel}
+            </aside>
+        },
+        SettingsPanelMode::Modal => html! {
+            <div class="fixed inset-0 z-50 flex items-center justify-center bg-black bg-opacity-50 p-4">
+                <div
+                    ref={dialog_ref}
+                    role="dialog"
+                    aria-modal="true"
+                    aria-labelledby="settings-panel-title"
+                    class="flex w-full max-w-4xl h-full max-h-[90vh] rounded-lg shadow-xl overflow-hidden"
+                >
+                    {panel}
+                </div>
+            </div>
+        },
+    }
+}
+
+/// One editable guardrail rule
+fn render_guardrail_rule(
+    index: usize,
+    rule: &GuardrailRule,
+    on_update: &Callback<(usize, GuardrailRule)>,
+    on_delete: &Callback<usize>,
+) -> Html {
+    let edit = |apply: fn(&mut GuardrailRule, &HtmlInputElement)| {
+        let rule = rule.clone();
+        let on_update = on_update.clone();
+        move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut rule = rule.clone();
+            apply(&mut rule, &input);
+            on_update.emit((index, rule));
+        }
+    };
+    let regex_error = (rule.pattern_type == PatternType::Regex && !rule.pattern.is_empty())
+        .then(|| compile_regex(&rule.pattern, "").err())
+        .flatten();
+    let select_class = "p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-sm text-gray-900 dark:text-gray-100";
+
+    html! {
+        <div key={rule.id.clone()} class="bg-gray-100 dark:bg-gray-700 p-3 rounded-md mb-3 border border-gray-200 dark:border-gray-600 space-y-2">
+            <div class="flex items-center gap-2">
+                <input
+                    type="checkbox"
+                    checked={rule.enabled}
+                    onchange={edit(|rule, input| rule.enabled = input.checked())}
+                    aria-label="Rule enabled"
+                />
+                <input
+                    type="text"
+                    value={rule.name.clone()}
+                    onchange={edit(|rule, input| rule.name = input.value())}
+                    aria-label="Rule name"
+                    class="flex-1 p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-sm text-gray-900 dark:text-gray-100"
+                />
+                <button
+                    onclick={
+                        let on_delete = on_delete.clone();
+                        Callback::from(move |_| on_delete.emit(index))
+                    }
+                    class="text-xs px-2 py-1 bg-red-100 dark:bg-red-900/30 text-red-600 dark:text-red-400 rounded hover:bg-red-200 dark:hover:bg-red-900/50"
+                    title="Delete rule"
+                    aria-label={format!("Delete rule {}", rule.name)}
+                >
+                    <i class="fas fa-trash" aria-hidden="true"></i>
+                </button>
+            </div>
+            <div class="grid grid-cols-3 gap-2">
+                <select
+                    class={select_class}
+                    aria-label="Rule kind"
+                    onchange={edit(|rule, input| {
+                        rule.kind = if input.value() == "required" { GuardrailKind::Required } else { GuardrailKind::Blocked }
+                    })}
+                >
+                    <option value="blocked" selected={rule.kind == GuardrailKind::Blocked}>{"Must not contain"}</option>
+                    <option value="required" selected={rule.kind == GuardrailKind::Required}>{"Must contain"}</option>
+                </select>
+                <select
+                    class={select_class}
+                    aria-label="Pattern type"
+                    onchange={edit(|rule, input| {
+                        rule.pattern_type = if input.value() == "regex" { PatternType::Regex } else { PatternType::Keyword }
+                    })}
+                >
+                    <option value="keyword" selected={rule.pattern_type == PatternType::Keyword}>{"Keyword"}</option>
+                    <option value="regex" selected={rule.pattern_type == PatternType::Regex}>{"Regex"}</option>
+                </select>
+                <select
+                    class={select_class}
+                    aria-label="Action on violation"
+                    onchange={edit(|rule, input| {
+                        rule.action = match input.value().as_str() {
+                            "Redact" => GuardrailAction::Redact,
+                            "Retry" => GuardrailAction::Retry,
+                            _ => GuardrailAction::Warn,
+                        }
+                    })}
+                >
+                    {for [GuardrailAction::Warn, GuardrailAction::Redact, GuardrailAction::Retry].into_iter().map(|action| html! {
+                        <option value={action.label()} selected={rule.action == action}>{action.label()}</option>
+                    })}
+                </select>
+            </div>
+            <div class="flex items-center gap-2">
+                <input
+                    type="text"
+                    value={rule.pattern.clone()}
+                    onchange={edit(|rule, input| rule.pattern = input.value())}
+                    placeholder={if rule.pattern_type == PatternType::Regex { "\\b\\d{3}-\\d{2}-\\d{4}\\b" } else { "keyword or phrase" }}
+                    aria-label="Pattern"
+                    class="flex-1 p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-sm font-mono text-gray-900 dark:text-gray-100"
+                />
+                <label class="flex items-center text-xs text-gray-700 dark:text-gray-300">
+                    <input
+                        type="checkbox"
+                        checked={rule.case_sensitive}
+                        onchange={edit(|rule, input| rule.case_sensitive = input.checked())}
+                        class="mr-1"
+                    />
+                    {"Match case"}
+                </label>
+            </div>
+            {if let Some(error) = regex_error {
+                html! { <p class="text-xs text-red-600 dark:text-red-400">{error}</p> }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
+
+/// Redaction rules for typed messages
+fn render_redaction_settings(settings: &RedactionSettings, on_change: &Callback<RedactionSettings>) -> Html {
+    let on_enabled = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.enabled = input.checked();
+            on_change.emit(settings);
+        })
+    };
+    let on_add = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut settings = settings.clone();
+            settings.rules.push(RedactionRule::new());
+            on_change.emit(settings);
+        })
+    };
+    let edit = |index: usize, apply: fn(&mut RedactionRule, &HtmlInputElement)| {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            if let Some(rule) = settings.rules.get_mut(index) {
+                apply(rule, &input);
+                on_change.emit(settings);
+            }
+        })
+    };
+    let input_class = "p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-sm text-gray-900 dark:text-gray-100";
+
+    html! {
+        <div>
+            <label class="flex items-center text-sm text-gray-700 dark:text-gray-300 mb-3">
+                <input type="checkbox" checked={settings.enabled} onchange={on_enabled} class="mr-2" />
+                {"Warn before sending sensitive data"}
+            </label>
+            {for settings.rules.iter().enumerate().map(|(index, rule)| {
+                let on_delete = {
+                    let settings = settings.clone();
+                    let on_change = on_change.clone();
+                    Callback::from(move |_: MouseEvent| {
+                        let mut settings = settings.clone();
+                        settings.rules.remove(index);
+                        on_change.emit(settings);
+                    })
+                };
+                let regex_error = (!rule.pattern.is_empty())
+                    .then(|| compile_regex(&rule.pattern, "gi").err())
+                    .flatten();
+                html! {
+                    <div key={rule.id.clone()} class="bg-gray-100 dark:bg-gray-700 p-3 rounded-md mb-3 border border-gray-200 dark:border-gray-600 space-y-2">
+                        <div class="flex items-center gap-2">
+                            <input
+                                type="checkbox"
+                                checked={rule.enabled}
+                                disabled={!settings.enabled}
+                                onchange={edit(index, |rule, input| rule.enabled = input.checked())}
+                                aria-label="Rule enabled"
+                            />
+                            <input
+                                type="text"
+                                value={rule.name.clone()}
+                                onchange={edit(index, |rule, input| rule.name = input.value())}
+                                aria-label="Rule name"
+                                class={classes!("flex-1", input_class)}
+                            />
+                            <button
+                                onclick={on_delete}
+                                class="text-xs px-2 py-1 bg-red-100 dark:bg-red-900/30 text-red-600 dark:text-red-400 rounded hover:bg-red-200 dark:hover:bg-red-900/50"
+                                title="Delete rule"
+                                aria-label={format!("Delete rule {}", rule.name)}
+                            >
+                                <i class="fas fa-trash" aria-hidden="true"></i>
+                            </button>
+                        </div>
+                        <div class="grid grid-cols-3 gap-2">
+                            <input
+                                type="text"
+                                value={rule.pattern.clone()}
+                                onchange={edit(index, |rule, input| rule.pattern = input.value())}
+                                placeholder="\\b\\d{3}-\\d{2}-\\d{4}\\b"
+                                aria-label="Pattern"
+                                class={classes!("col-span-2", "font-mono", input_class)}
+                            />
+                            <input
+                                type="text"
+                                value={rule.mask.clone()}
+                                onchange={edit(index, |rule, input| rule.mask = input.value())}
+                                aria-label="Replace matches with"
+                                title="Replace matches with"
+                                class={classes!("font-mono", input_class)}
+                            />
+                        </div>
+                        {if let Some(error) = regex_error {
+                            html! { <p class="text-xs text-red-600 dark:text-red-400">{error}</p> }
+                        } else {
+                            html! {}
+                        }}
+                    </div>
+                }
+            })}
+            <button
+                onclick={on_add}
+                class="flex items-center justify-center w-full p-3 border-2 border-dashed border-gray-300 dark:border-gray-600 rounded-md text-gray-500 dark:text-gray-400 hover:border-primary-500 hover:text-primary-500 dark:hover:border-primary-400 dark:hover:text-primary-400 transition-colors"
+            >
+                <i class="fas fa-plus mr-2" aria-hidden="true"></i> {"Add Rule"}
+            </button>
+        </div>
+    }
+}
+
+/// Moderation endpoint and when to check messages
+fn render_moderation_settings(
+    config: &FlexibleApiConfig,
+    on_change: &Callback<ModerationSettings>,
+) -> Html {
+    let settings = &config.moderation;
+    let edit = |apply: fn(&mut ModerationSettings, &HtmlInputElement)| {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            apply(&mut settings, &input);
+            on_change.emit(settings);
+        }
+    };
+    let checkbox = |label: &'static str, checked: bool, disabled: bool, apply: fn(&mut ModerationSettings, &HtmlInputElement)| {
+        html! {
+            <label class="flex items-center text-sm text-gray-700 dark:text-gray-300 mb-2">
+                <input type="checkbox" class="mr-2" {checked} {disabled} onchange={edit(apply)} />
+                {label}
+            </label>
+        }
+    };
+
+    html! {
+        <div>
+            {checkbox("Enable moderation", settings.enabled, false, |s, input| s.enabled = input.checked())}
+            <div class="grid grid-cols-2 gap-2 mb-2">
+                <select
+                    aria-label="Moderation provider"
+                    disabled={!settings.enabled}
+                    onchange={edit(|s, input| s.provider = input.value())}
+                    class="p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100"
+                >
+                    {for config.providers.iter().map(|provider| html! {
+                        <option value={provider.name.clone()} selected={provider.name == settings.provider}>
+                            {&provider.name}
+                        </option>
+                    })}
+                </select>
+                <input
+                    type="text"
+                    aria-label="Moderation model"
+                    disabled={!settings.enabled}
+                    value={settings.model.clone()}
+                    onchange={edit(|s, input| s.model = input.value())}
+                    class="p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100"
+                />
+            </div>
+            {checkbox("Check my messages", settings.check_user_messages, !settings.enabled, |s, input| s.check_user_messages = input.checked())}
+            {checkbox("Check assistant replies", settings.check_replies, !settings.enabled, |s, input| s.check_replies = input.checked())}
+            {checkbox("Don't send flagged messages", settings.block_flagged, !settings.enabled, |s, input| s.block_flagged = input.checked())}
+        </div>
+    }
+}
+
+fn render_starter_settings(settings: &StarterSettings, on_change: &Callback<StarterSettings>) -> Html {
+    let on_prompts = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.prompts = input
+                .value()
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect();
+            on_change.emit(settings);
+        })
+    };
+    let on_generate = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.generate = input.checked();
+            on_change.emit(settings);
+        })
+    };
+
+    html! {
+        <div class="space-y-2">
+            <textarea
+                value={settings.prompts.join("\n")}
+                onchange={on_prompts}
+                rows="4"
+                aria-label="Conversation starters"
+                class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100"
+            />
+            <label class="flex items-center text-sm text-gray-700 dark:text-gray-300">
+                <input type="checkbox" checked={settings.generate} onchange={on_generate} class="mr-2" />
+                {"Also suggest starters from the system prompt with the current model"}
+            </label>
+        </div>
+    }
+}
+
+/// Max tokens and guidance of the "Custom" response length preset
+fn render_custom_length_settings(
+    settings: &ResponseLengthSettings,
+    on_change: &Callback<ResponseLengthSettings>,
+) -> Html {
+    let on_max_tokens = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(max_tokens) = input.value().parse::<u32>() {
+                let mut settings = settings.clone();
+                settings.custom_max_tokens = max_tokens.max(1);
+                on_change.emit(settings);
+            }
+        })
+    };
+    let on_guidance = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.custom_guidance = input.value();
+            on_change.emit(settings);
+        })
+    };
+
+    html! {
+        <div class="mb-4">
+            <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="custom-length-guidance">{"Custom Response Length"}</label>
+            <div class="flex gap-2">
+                <input
+                    type="number"
+                    min="1"
+                    aria-label="Custom length max tokens"
+                    value={settings.custom_max_tokens.to_string()}
+                    onchange={on_max_tokens}
+                    class="w-28 p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                />
+                <input
+                    type="text"
+                    id="custom-length-guidance"
+                    value={settings.custom_guidance.clone()}
+                    onchange={on_guidance}
+                    class="flex-1 p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                />
+            </div>
+            <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                {"Used when \"Custom\" is picked as the response length in the chat header. The guidance is appended to the system prompt."}
+            </p>
+        </div>
+    }
+}
+
+fn render_follow_up_settings(
+    config: &FlexibleApiConfig,
+    on_change: &Callback<FollowUpSettings>,
+) -> Html {
+    let settings = &config.follow_ups;
+    let on_enabled = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.enabled = input.checked();
+            on_change.emit(settings);
+        })
+    };
+    let on_model = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.model = input.value();
+            on_change.emit(settings);
+        })
+    };
+
+    html! {
+        <div class="space-y-2">
+            <label class="flex items-center text-sm text-gray-700 dark:text-gray-300">
+                <input type="checkbox" checked={settings.enabled} onchange={on_enabled} class="mr-2" />
+                {"Suggest follow-up questions after each reply"}
+            </label>
+            <select
+                aria-label="Follow-up model"
+                disabled={!settings.enabled}
+                onchange={on_model}
+                class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100"
+            >
+                <option value="" selected={settings.model.is_empty()}>{"Session's model"}</option>
+                {for config.get_all_provider_models().into_iter().map(|(provider, model)| {
+                    let value = format!("{},{}", provider, model);
+                    html! {
+                        <option selected={settings.model == value} value={value.clone()}>
+                            {format!("{} / {}", provider, model)}
+                        </option>
+                    }
+                })}
+            </select>
+        </div>
+    }
+}
+
+fn render_tool_selection_settings(
+    config: &FlexibleApiConfig,
+    on_change: &Callback<ToolSelectionSettings>,
+) -> Html {
+    let settings = &config.tool_selection;
+    let on_enabled = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.enabled = input.checked();
+            on_change.emit(settings);
+        })
+    };
+    let on_model = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.model = input.value();
+            on_change.emit(settings);
+        })
+    };
+    let on_min_tools = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(min_tools) = input.value().parse::<usize>() {
+                let mut settings = settings.clone();
+                settings.min_tools = min_tools.max(1);
+                on_change.emit(settings);
+            }
+        })
+    };
+
+    html! {
+        <div class="space-y-2">
+            <label class="flex items-center text-sm text-gray-700 dark:text-gray-300">
+                <input type="checkbox" checked={settings.enabled} onchange={on_enabled} class="mr-2" />
+                {"Send only the tools each message needs"}
+            </label>
+            <select
+                aria-label="Tool selection model"
+                disabled={!settings.enabled}
+                onchange={on_model}
+                class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100"
+            >
+                <option value="" selected={settings.model.is_empty()}>{"Session's model"}</option>
+                {for config.get_all_provider_models().into_iter().map(|(provider, model)| {
+                    let value = format!("{},{}", provider, model);
+                    html! {
+                        <option selected={settings.model == value} value={value.clone()}>
+                            {format!("{} / {}", provider, model)}
+                        </option>
+                    }
+                })}
+            </select>
+            <label class="flex items-center justify-between text-sm text-gray-700 dark:text-gray-300">
+                {"Only with at least this many tools enabled"}
+                <input
+                    type="number"
+                    min="1"
+                    value={settings.min_tools.to_string()}
+                    disabled={!settings.enabled}
+                    onchange={on_min_tools}
+                    class="w-20 p-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                />
+            </label>
+        </div>
+    }
+}
+
+fn render_fetch_tool_settings(settings: &FetchToolSettings, on_change: &Callback<FetchToolSettings>) -> Html {
+    let on_format = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some(format) = FetchFormat::parse(&input.value()) {
+                let mut settings = settings.clone();
+                settings.format = format;
+                on_change.emit(settings);
+            }
+        })
+    };
+    let on_max_bytes = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(max_bytes) = input.value().parse::<usize>() {
+                let mut settings = settings.clone();
+                settings.max_bytes = max_bytes;
+                on_change.emit(settings);
+            }
+        })
+    };
+
+    html! {
+        <div class="space-y-2">
+            <label class="flex items-center justify-between text-sm text-gray-700 dark:text-gray-300">
+                {"Response body"}
+                <select
+                    onchange={on_format}
+                    class="p-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                >
+                    {for FetchFormat::ALL.iter().map(|format| html! {
+                        <option selected={settings.format == *format} value={format.id()}>{format.label()}</option>
+                    })}
+                </select>
+            </label>
+            <label class="flex items-center justify-between text-sm text-gray-700 dark:text-gray-300">
+                {"Cut bodies at this many bytes (0 for no limit)"}
+                <input
+                    type="number"
+                    min="0"
+                    step="1000"
+                    value={settings.max_bytes.to_string()}
+                    onchange={on_max_bytes}
+                    class="w-24 p-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                />
+            </label>
+        </div>
+    }
+}
+
+fn render_web_fetch_settings(config: &FlexibleApiConfig, on_change: &Callback<WebFetchSettings>) -> Html {
+    let settings = &config.web_fetch;
+    let on_model = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.model = input.value();
+            on_change.emit(settings);
+        })
+    };
+    let on_max_bytes = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(max_bytes) = input.value().parse::<usize>() {
+                let mut settings = settings.clone();
+                settings.max_bytes = max_bytes;
+                on_change.emit(settings);
+            }
+        })
+    };
+
+    html! {
+        <div class="space-y-2">
+            <select
+                aria-label="WebFetch model"
+                onchange={on_model}
+                class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100"
+            >
+                <option value="" selected={settings.model.is_empty()}>{"Session's model"}</option>
+                {for config.get_all_provider_models().into_iter().map(|(provider, model)| {
+                    let value = format!("{},{}", provider, model);
+                    html! {
+                        <option selected={settings.model == value} value={value.clone()}>
+                            {format!("{} / {}", provider, model)}
+                        </option>
+                    }
+                })}
+            </select>
+            <label class="flex items-center justify-between text-sm text-gray-700 dark:text-gray-300">
+                {"Give it at most this many bytes of the page (0 for no limit)"}
+                <input
+                    type="number"
+                    min="0"
+                    step="10000"
+                    value={settings.max_bytes.to_string()}
+                    onchange={on_max_bytes}
+                    class="w-24 p-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                />
+            </label>
+        </div>
+    }
+}
+
+fn render_web_search_settings(settings: &WebSearchSettings, on_change: &Callback<WebSearchSettings>) -> Html {
+    let on_backend = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some(backend) = SearchBackend::parse(&input.value()) {
+                let mut settings = settings.clone();
+                settings.backend = backend;
+                on_change.emit(settings);
+            }
+        })
+    };
+    let on_api_key = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.api_key = input.value().trim().to_string();
+            on_change.emit(settings);
+        })
+    };
+    let on_max_results = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(max_results) = input.value().parse::<usize>() {
+                let mut settings = settings.clone();
+                settings.max_results = max_results.clamp(1, 20);
+                on_change.emit(settings);
+            }
+        })
+    };
+
+    html! {
+        <div class="space-y-2">
+            <label class="flex items-center justify-between text-sm text-gray-700 dark:text-gray-300">
+                {"Search API"}
+                <select
+                    onchange={on_backend}
+                    class="p-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                >
+                    {for SearchBackend::ALL.iter().map(|backend| html! {
+                        <option selected={settings.backend == *backend} value={backend.id()}>{backend.label()}</option>
+                    })}
+                </select>
+            </label>
+            <input
+                type="password"
+                aria-label="Search API key"
+                placeholder={format!("{} API key", settings.backend.label())}
+                value={settings.api_key.clone()}
+                onchange={on_api_key}
+                class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100"
+            />
+            <a
+                href={settings.backend.key_url()}
+                target="_blank"
+                rel="noopener noreferrer"
+                class="text-xs text-primary-600 dark:text-primary-400 hover:underline"
+            >
+                {format!("Get a {} key", settings.backend.label())}
+            </a>
+            <label class="flex items-center justify-between text-sm text-gray-700 dark:text-gray-300">
+                {"Results per search"}
+                <input
+                    type="number"
+                    min="1"
+                    max="20"
+                    value={settings.max_results.to_string()}
+                    onchange={on_max_results}
+                    class="w-20 p-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                />
+            </label>
+        </div>
+    }
+}
+
+fn render_code_sandbox_settings(
+    config: &FlexibleApiConfig,
+    on_change: &Callback<CodeSandboxSettings>,
+    on_tool: &Callback<(&'static str, bool)>,
+) -> Html {
+    let settings = &config.code_sandbox;
+    let on_timeout = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(timeout_secs) = input.value().parse::<u32>() {
+                let mut settings = settings.clone();
+                settings.timeout_secs = timeout_secs.max(1);
+                on_change.emit(settings);
+            }
+        })
+    };
+    let on_max_output = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(max_output_chars) = input.value().parse::<usize>() {
+                let mut settings = settings.clone();
+                settings.max_output_chars = max_output_chars;
+                on_change.emit(settings);
+            }
+        })
+    };
+
+    html! {
+        <div class="space-y-2">
+            {render_tool_toggle(config, on_tool, code_sandbox::RUN_PYTHON_TOOL_NAME, "Python")}
+            {render_tool_toggle(config, on_tool, code_sandbox::RUN_JS_TOOL_NAME, "JavaScript")}
+            <label class="flex items-center justify-between text-sm text-gray-700 dark:text-gray-300">
+                {"Stop a run after this many seconds"}
+                <input
+                    type="number"
+                    min="1"
+                    value={settings.timeout_secs.to_string()}
+                    onchange={on_timeout}
+                    class="w-20 p-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                />
+            </label>
+            <label class="flex items-center justify-between text-sm text-gray-700 dark:text-gray-300">
+                {"Characters of output returned"}
+                <input
+                    type="number"
+                    min="100"
+                    step="1000"
+                    value={settings.max_output_chars.to_string()}
+                    onchange={on_max_output}
+                    class="w-24 p-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                />
+            </label>
+        </div>
+    }
+}
+
+/// Checkbox that turns a built-in tool on or off, with the tool's name
+fn render_tool_toggle(
+    config: &FlexibleApiConfig,
+    on_tool: &Callback<(&'static str, bool)>,
+    tool_name: &'static str,
+    label: &'static str,
+) -> Html {
+    let enabled = config
+        .function_tools
+        .iter()
+        .any(|tool| tool.name == tool_name && tool.enabled);
+    let on_tool = on_tool.clone();
+    let onchange = Callback::from(move |e: Event| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        on_tool.emit((tool_name, input.checked()));
+    });
+    html! {
+        <label class="flex items-center text-sm text-gray-700 dark:text-gray-300">
+            <input type="checkbox" checked={enabled} {onchange} class="mr-2" />
+            {label}
+            <code class="ml-2 text-xs text-gray-500 dark:text-gray-400">{tool_name}</code>
+        </label>
+    }
+}
+
+fn render_browser_notification_settings(
+    settings: &BrowserNotificationSettings,
+    on_change: &Callback<BrowserNotificationSettings>,
+) -> Html {
+    // Turning them on asks the browser for permission first
+    let on_enabled = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.enabled = input.checked();
+            if !settings.enabled {
+                on_change.emit(settings);
+                return;
+            }
+            let on_change = on_change.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let _ = browser_notifications::request_permission().await;
+                on_change.emit(settings);
+            });
+        })
+    };
+    let on_min_seconds = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(min_seconds) = input.value().parse::<u32>() {
+                let mut settings = settings.clone();
+                settings.min_seconds = min_seconds;
+                on_change.emit(settings);
+            }
+        })
+    };
+    let status = match browser_notifications::permission() {
+        None => Some("This browser doesn't support notifications."),
+        Some(web_sys::NotificationPermission::Denied) => {
+            Some("Notifications are blocked for this site in the browser's settings.")
+        }
+        Some(web_sys::NotificationPermission::Granted) => None,
+        Some(_) if settings.enabled => Some("Waiting for the browser's permission."),
+        Some(_) => None,
+    };
+
+    html! {
+        <div class="space-y-2">
+            <label class="flex items-center text-sm text-gray-700 dark:text-gray-300">
+                <input type="checkbox" checked={settings.enabled} onchange={on_enabled} class="mr-2" />
+                {"Notify me when a run finishes in the background"}
+            </label>
+            <label class="flex items-center justify-between text-sm text-gray-700 dark:text-gray-300">
+                {"Only for runs longer than (seconds)"}
+                <input
+                    type="number"
+                    min="0"
+                    value={settings.min_seconds.to_string()}
+                    disabled={!settings.enabled}
+                    onchange={on_min_seconds}
+                    class="w-20 p-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                />
+            </label>
+            {match status {
+                Some(status) => html! {
+                    <p class="text-xs text-yellow-700 dark:text-yellow-400">{status}</p>
+                },
+                None => html! {},
+            }}
+        </div>
+    }
+}
+
+fn render_webhook_settings(
+    settings: &WebhookSettings,
+    on_change: &Callback<WebhookSettings>,
+) -> Html {
+    let update = |apply: fn(&mut WebhookSettings, &HtmlInputElement)| {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            apply(&mut settings, &input);
+            on_change.emit(settings);
+        })
+    };
+    let on_template = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.template = input.value();
+            on_change.emit(settings);
+        })
+    };
+    let placeholders = webhook::PLACEHOLDERS
+        .iter()
+        .map(|name| format!("{{{{{}}}}}", name))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let input_class = "w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100";
+
+    html! {
+        <div class="space-y-2">
+            <label class="flex items-center text-sm text-gray-700 dark:text-gray-300">
+                <input
+                    type="checkbox"
+                    checked={settings.enabled}
+                    onchange={update(|settings, input| settings.enabled = input.checked())}
+                    class="mr-2"
+                />
+                {"Send a webhook when a reply finishes"}
+            </label>
+            <input
+                type="url"
+                aria-label="Webhook URL"
+                placeholder="https://example.com/hooks/llm"
+                value={settings.url.clone()}
+                disabled={!settings.enabled}
+                onchange={update(|settings, input| settings.url = input.value())}
+                class={input_class}
+            />
+            <div class="flex gap-4 text-sm text-gray-700 dark:text-gray-300">
+                <label class="flex items-center">
+                    <input
+                        type="checkbox"
+                        checked={settings.on_completion}
+                        disabled={!settings.enabled}
+                        onchange={update(|settings, input| settings.on_completion = input.checked())}
+                        class="mr-2"
+                    />
+                    {"On completion"}
+                </label>
+                <label class="flex items-center">
+                    <input
+                        type="checkbox"
+                        checked={settings.on_failure}
+                        disabled={!settings.enabled}
+                        onchange={update(|settings, input| settings.on_failure = input.checked())}
+                        class="mr-2"
+                    />
+                    {"On failure"}
+                </label>
+            </div>
+            <textarea
+                aria-label="Webhook body template"
+                value={settings.template().to_string()}
+                disabled={!settings.enabled}
+                onchange={on_template}
+                rows="6"
+                class={classes!(input_class, "font-mono", "text-xs")}
+            />
+            <p class="text-xs text-gray-500 dark:text-gray-400">
+                {format!("Placeholders: {}. Values are JSON-escaped when the body is JSON.", placeholders)}
+            </p>
+        </div>
+    }
+}
+
+fn render_audio_output_settings(
+    settings: &AudioOutputSettings,
+    on_change: Callback<AudioOutputSettings>,
+) -> Html {
+    let select_class = "p-1 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100";
+    let on_toggle = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            on_change.emit(AudioOutputSettings {
+                enabled: input.checked(),
+                ..settings.clone()
+            });
+        })
+    };
+    let on_voice = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            on_change.emit(AudioOutputSettings {
+                voice: input.value(),
+                ..settings.clone()
+            });
+        })
+    };
+    let on_format = {
+        let settings = settings.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            on_change.emit(AudioOutputSettings {
+                format: input.value(),
+                ..settings.clone()
+            });
+        })
+    };
+
+    html! {
+        <div class="mb-4">
+            <label class="flex items-center text-sm font-medium text-gray-700 dark:text-gray-300">
+                <input type="checkbox" class="mr-2" checked={settings.enabled} onchange={on_toggle} />
+                {"Spoken replies"}
+            </label>
+            {if settings.enabled {
+                html! {
+                    <div class="flex items-center gap-2 mt-2 text-sm text-gray-700 dark:text-gray-300">
+                        <label for="audio-voice">{"Voice"}</label>
+                        <select id="audio-voice" onchange={on_voice} class={select_class}>
+                            {for audio_output::VOICES.iter().map(|voice| html! {
+                                <option selected={settings.voice == *voice} value={*voice}>{*voice}</option>
+                            })}
+                        </select>
+                        <label for="audio-format">{"Format"}</label>
+                        <select id="audio-format" onchange={on_format} class={select_class}>
+                            {for audio_output::FORMATS.iter().map(|format| html! {
+                                <option selected={settings.format == *format} value={*format}>{*format}</option>
+                            })}
+                        </select>
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+            <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                {"Asks OpenAI audio models such as gpt-4o-audio-preview to speak the reply too. The clip is saved with the message and played inline; other models reject the request. Clips are large, so long spoken sessions fill browser storage quickly."}
+            </p>
+        </div>
+    }
+}
+
+fn render_self_consistency_settings(
+    settings: &SelfConsistencySettings,
+    on_change: &Callback<SelfConsistencySettings>,
+) -> Html {
+    let on_enabled = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.enabled = input.checked();
+            on_change.emit(settings);
+        })
+    };
+    let on_samples = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(samples) = input.value().parse::<u32>() {
+                let mut settings = settings.clone();
+                settings.samples = samples.clamp(2, MAX_CANDIDATES);
+                on_change.emit(settings);
+            }
+        })
+    };
+    let on_method = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some(method) = ConsensusMethod::parse(&input.value()) {
+                let mut settings = settings.clone();
+                settings.method = method;
+                on_change.emit(settings);
+            }
+        })
+    };
+    let input_class = "p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100";
+
+    html! {
+        <div class="space-y-2">
+            <label class="flex items-center text-sm text-gray-700 dark:text-gray-300">
+                <input type="checkbox" checked={settings.enabled} onchange={on_enabled} class="mr-2" />
+                {"Reply with the consensus of several samples"}
+            </label>
+            <div class="flex gap-2">
+                <input
+                    type="number"
+                    min="2"
+                    max={MAX_CANDIDATES.to_string()}
+                    aria-label="Samples per prompt"
+                    title="Samples per prompt"
+                    disabled={!settings.enabled}
+                    value={settings.samples().to_string()}
+                    onchange={on_samples}
+                    class={classes!("w-20", input_class)}
+                />
+                <select
+                    aria-label="Consensus method"
+                    disabled={!settings.enabled}
+                    onchange={on_method}
+                    class={classes!("flex-1", input_class)}
+                >
+                    {for ConsensusMethod::ALL.iter().map(|method| html! {
+                        <option selected={settings.method == *method} value={method.label()}>{method.label()}</option>
+                    })}
+                </select>
+            </div>
+        </div>
+    }
+}
+
+fn render_budget_settings(settings: &BudgetSettings, on_change: &Callback<BudgetSettings>) -> Html {
+    let edit = |apply: fn(&mut BudgetSettings, &str)| {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            apply(&mut settings, input.value().trim());
+            on_change.emit(settings);
+        })
+    };
+    let input_class = "w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100";
+
+    html! {
+        <div class="grid grid-cols-3 gap-2">
+            <label class="text-xs text-gray-600 dark:text-gray-400">
+                {"Max tokens"}
+                <input
+                    type="number"
+                    min="1"
+                    placeholder="No limit"
+                    value={settings.max_total_tokens.map(|v| v.to_string()).unwrap_or_default()}
+                    onchange={edit(|s, value| s.max_total_tokens = value.parse().ok())}
+                    class={input_class}
+                />
+            </label>
+            <label class="text-xs text-gray-600 dark:text-gray-400">
+                {"Max cost"}
+                <input
+                    type="number"
+                    min="0"
+                    step="0.01"
+                    placeholder="No limit"
+                    value={settings.max_cost.map(|v| v.to_string()).unwrap_or_default()}
+                    onchange={edit(|s, value| s.max_cost = value.parse().ok())}
+                    class={input_class}
+                />
+            </label>
+            <label class="text-xs text-gray-600 dark:text-gray-400">
+                {"Max tool calls"}
+                <input
+                    type="number"
+                    min="1"
+                    placeholder="No limit"
+                    value={settings.max_tool_calls.map(|v| v.to_string()).unwrap_or_default()}
+                    onchange={edit(|s, value| s.max_tool_calls = value.parse().ok())}
+                    class={input_class}
+                />
+            </label>
+        </div>
+    }
+}
+
+/// Allowlist for the desktop file and shell tools, one entry per line
+fn render_desktop_tool_settings(
+    settings: &DesktopToolSettings,
+    on_change: &Callback<DesktopToolSettings>,
+) -> Html {
+    let edit = |apply: fn(&mut DesktopToolSettings, &HtmlInputElement)| {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            apply(&mut settings, &input);
+            on_change.emit(settings);
+        }
+    };
+    let lines = |value: String| -> Vec<String> {
+        value
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|line| !line.is_empty())
+            .collect()
+    };
+    let on_roots_change = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.allowed_roots = lines(input.value());
+            on_change.emit(settings);
+        })
+    };
+    let on_commands_change = {
+        let settings = settings.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            let mut settings = settings.clone();
+            settings.allowed_commands = lines(input.value());
+            on_change.emit(settings);
+        })
+    };
+    let textarea_class = "w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm font-mono text-gray-900 dark:text-gray-100";
+
+    html! {
+        <div>
+            <label class="flex items-center text-sm text-gray-700 dark:text-gray-300 mb-2">
+                <input type="checkbox" class="mr-2" checked={settings.enabled} onchange={edit(|s, input| s.enabled = input.checked())} />
+                {"Use the real filesystem and shell"}
+            </label>
+            <label class="block text-sm text-gray-700 dark:text-gray-300 mb-1" for="desktop-allowed-roots">
+                {"Allowed directories"}
+            </label>
+            <textarea
+                id="desktop-allowed-roots"
+                rows="3"
+                disabled={!settings.enabled}
+                placeholder="/home/me/projects/app"
+                value={settings.allowed_roots.join("\n")}
+                onchange={on_roots_change}
+                class={classes!(textarea_class, "mb-2")}
+            />
+            <label class="block text-sm text-gray-700 dark:text-gray-300 mb-1" for="desktop-allowed-commands">
+                {"Allowed programs for Bash (* allows any)"}
+            </label>
+            <textarea
+                id="desktop-allowed-commands"
+                rows="3"
+                disabled={!settings.enabled}
+                value={settings.allowed_commands.join("\n")}
+                onchange={on_commands_change}
+                class={classes!(textarea_class, "mb-2")}
+            />
+            <label class="flex items-center text-sm text-gray-700 dark:text-gray-300">
+                <input
+                    type="checkbox"
+                    class="mr-2"
+                    checked={settings.auto_approve_reads}
+                    disabled={!settings.enabled}
+                    onchange={edit(|s, input| s.auto_approve_reads = input.checked())}
+                />
+                {"Run Read, Glob, Grep and LS without asking"}
+            </label>
+        </div>
+    }
+}
+
+/// Auth mode of a Gemini provider, with the Vertex AI project and location
+fn render_gemini_auth(auth: &GeminiAuth, on_field_change: &Callback<(String, String)>) -> Html {
+    let input_class = "w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100";
+    let field = |name: &'static str| {
+        let callback = on_field_change.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            callback.emit((name.to_string(), input.value()));
+        })
+    };
+    let on_mode_change = {
+        let callback = on_field_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            callback.emit(("gemini_auth".to_string(), input.value()));
+        })
+    };
+
+    html! {
+        <div>
+            <label class="block text-sm font-medium mb-1 text-gray-700 dark:text-gray-300" for="gemini-auth">{"Authentication"}</label>
+            <select id="gemini-auth" onchange={on_mode_change} class={input_class}>
+                <option value="api_key" selected={auth.is_api_key()}>{"AI Studio API key"}</option>
+                <option value="vertex" selected={auth.is_vertex()}>{"Vertex AI access token"}</option>
+            </select>
+            {if let GeminiAuth::Vertex { project, location } = auth {
+                html! {
+                    <div class="mt-2 grid grid-cols-2 gap-2">
+                        <div>
+                            <label class="block text-xs mb-1 text-gray-600 dark:text-gray-300" for="vertex-project">{"Project ID"}</label>
+                            <input
+                                type="text"
+                                id="vertex-project"
+                                value={project.clone()}
+                                oninput={field("vertex_project")}
+                                class={input_class}
+                                placeholder="my-gcp-project"
+                            />
+                        </div>
+                        <div>
+                            <label class="block text-xs mb-1 text-gray-600 dark:text-gray-300" for="vertex-location">{"Location"}</label>
+                            <input
+                                type="text"
+                                id="vertex-location"
+                                value={location.clone()}
+                                oninput={field("vertex_location")}
+                                class={input_class}
+                                placeholder={DEFAULT_VERTEX_LOCATION}
+                            />
+                        </div>
+                        {match auth.validate() {
+                            Err(error) => html! {
+                                <p class="col-span-2 text-xs text-red-600 dark:text-red-400" role="alert">{error}</p>
+                            },
+                            Ok(()) => html! {
+                                <p class="col-span-2 text-xs text-gray-500 dark:text-gray-400">
+                                    {"Requests go to the Vertex AI endpoint of this project; the base URL is not used. Paste the output of "}
+                                    <code>{"gcloud auth print-access-token"}</code>
+                                    {" as the access token. Tokens expire after an hour."}
+                                </p>
+                            },
+                        }}
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn docked_width_follows_the_pointer_within_limits() {
+        assert_eq!(docked_width(1600.0, 1100.0), 500);
+        assert_eq!(docked_width(1600.0, 1500.0), MIN_SETTINGS_PANEL_WIDTH);
+        assert_eq!(docked_width(1600.0, 0.0), MAX_SETTINGS_PANEL_WIDTH);
+        assert_eq!(docked_width(800.0, 900.0), MIN_SETTINGS_PANEL_WIDTH);
+    }
+
+    #[test]
+    fn stored_widths_outside_the_limits_are_clamped() {
+        assert_eq!(clamp_settings_panel_width(0), MIN_SETTINGS_PANEL_WIDTH);
+        assert_eq!(clamp_settings_panel_width(u32::MAX), MAX_SETTINGS_PANEL_WIDTH);
+        assert_eq!(clamp_settings_panel_width(DEFAULT_SETTINGS_PANEL_WIDTH), DEFAULT_SETTINGS_PANEL_WIDTH);
     }
 }
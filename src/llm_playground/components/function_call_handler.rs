@@ -1,7 +1,6 @@
 use crate::llm_playground::api_clients::{FunctionCallRequest, FunctionResponse};
-use crate::llm_playground::builtin_tools;
+use crate::llm_playground::{builtin_tools, fetch_tool::FetchToolSettings, logging};
 use crate::llm_playground::{ApiConfig, FunctionTool};
-use gloo_console::log;
 use wasm_bindgen_futures;
 use yew::prelude::*;
 
@@ -41,16 +40,19 @@ pub fn function_call_handler(props: &FunctionCallHandlerProps) -> Html {
                 if let Some(tool) = tool {
                     if tool.is_builtin {
                         // Execute built-in tool with real functionality
-                        log!("Executing built-in tool: {}", &function_call.name);
+                        logging::info!("Executing built-in tool: {}", function_call.name);
                         let function_call_clone = function_call.clone();
                         let on_response_clone = on_response.clone();
                         let response_clone = response.clone();
+                        let cors_proxy = config.shared_settings.cors_proxy.clone();
 
                         wasm_bindgen_futures::spawn_local(async move {
                             match builtin_tools::execute_builtin_tool(
                                 &function_call_clone.name,
                                 &function_call_clone.arguments,
                                 None,
+                                cors_proxy.as_deref(),
+                                &FetchToolSettings::default(),
                             )
                             .await
                             {
@@ -125,19 +127,22 @@ pub fn function_call_handler(props: &FunctionCallHandlerProps) -> Html {
             if let Some(tool) = tool {
                 if tool.is_builtin {
                     // Execute built-in tool with real functionality
-                    log!(
+                    logging::info!(
                         "Executing built-in tool (manual approval): {}",
-                        &function_call.name
+                        function_call.name
                     );
                     let function_call_clone = function_call.clone();
                     let on_response_clone = on_response.clone();
                     let response_clone = response.clone();
+                    let cors_proxy = config.shared_settings.cors_proxy.clone();
 
                     wasm_bindgen_futures::spawn_local(async move {
                         match builtin_tools::execute_builtin_tool(
                             &function_call_clone.name,
                             &function_call_clone.arguments,
                             None,
+                            cors_proxy.as_deref(),
+                            &FetchToolSettings::default(),
                         )
                         .await
                         {
@@ -1,3 +1,5 @@
+use crate::llm_playground::hooks::use_focus_trap;
+use crate::llm_playground::tool_dependencies;
 use crate::llm_playground::FunctionTool;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
@@ -24,17 +26,22 @@ pub fn function_tool_editor(props: &FunctionToolEditorProps) -> Html {
             enabled: true,
             category: "Custom".to_string(),
             is_builtin: false,
+            requires: None,
         })
     });
 
     let parameters_text = use_state(|| {
         serde_json::to_string_pretty(&tool.parameters).unwrap_or_else(|_| String::from("{}"))
     });
+    let requires_text = use_state(|| {
+        tool_dependencies::format_list(&tool_dependencies::dependencies_of(&tool))
+    });
 
     // Update local state when props change
     {
         let tool = tool.clone();
         let parameters_text = parameters_text.clone();
+        let requires_text = requires_text.clone();
         let props_tool = props.tool.clone();
         use_effect_with(props_tool, move |props_tool| {
             if let Some(t) = props_tool {
@@ -43,6 +50,9 @@ pub fn function_tool_editor(props: &FunctionToolEditorProps) -> Html {
                     serde_json::to_string_pretty(&t.parameters)
                         .unwrap_or_else(|_| String::from("{}")),
                 );
+                requires_text.set(tool_dependencies::format_list(
+                    &tool_dependencies::dependencies_of(t),
+                ));
             }
             || ()
         });
@@ -95,6 +105,19 @@ pub fn function_tool_editor(props: &FunctionToolEditorProps) -> Html {
         })
     };
 
+    let on_requires_change = {
+        let tool = tool.clone();
+        let requires_text = requires_text.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let text = input.value();
+            let mut new_tool = (*tool).clone();
+            new_tool.requires = Some(tool_dependencies::parse_list(&text));
+            tool.set(new_tool);
+            requires_text.set(text);
+        })
+    };
+
     let on_save_click = {
         let tool = tool.clone();
         let on_save = props.on_save.clone();
@@ -119,18 +142,28 @@ pub fn function_tool_editor(props: &FunctionToolEditorProps) -> Html {
     // Check if this is a built-in tool (read-only)
     let is_builtin = tool.is_builtin;
 
+    let dialog_ref = use_node_ref();
+    use_focus_trap(dialog_ref.clone(), true, props.on_cancel.clone());
+
     html! {
         <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
-            <div class="bg-white dark:bg-gray-800 rounded-lg p-6 w-full max-w-2xl max-h-[80vh] overflow-y-auto">
+            <div
+                ref={dialog_ref}
+                role="dialog"
+                aria-modal="true"
+                aria-labelledby="tool-editor-title"
+                class="bg-white dark:bg-gray-800 rounded-lg p-6 w-full max-w-2xl max-h-[80vh] overflow-y-auto"
+            >
                 <div class="flex justify-between items-center mb-4">
-                    <h3 class="text-lg font-semibold">
+                    <h3 id="tool-editor-title" class="text-lg font-semibold">
                         {if props.tool.is_some() { "Edit Function Tool" } else { "Add Function Tool" }}
                     </h3>
                     <button
                         onclick={on_cancel_click.clone()}
                         class="text-gray-500 hover:text-gray-700 dark:text-gray-400 dark:hover:text-gray-200"
+                        aria-label="Close editor"
                     >
-                        <i class="fas fa-times"></i>
+                        <i class="fas fa-times" aria-hidden="true"></i>
                     </button>
                 </div>
 
@@ -236,6 +269,20 @@ pub fn function_tool_editor(props: &FunctionToolEditorProps) -> Html {
                             html! {}
                         }}
                     </div>
+
+                    <div>
+                        <label class="block text-sm font-medium mb-1">{"Requires"}</label>
+                        <input
+                            type="text"
+                            value={(*requires_text).clone()}
+                            oninput={on_requires_change}
+                            placeholder="e.g., Read(file_path), search_documents"
+                            class="w-full p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 font-mono text-sm"
+                        />
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                            {"Tools that must have succeeded earlier in the conversation before this one runs. Add an argument in parentheses when that call must have used the same value for it."}
+                        </p>
+                    </div>
                 </div>
 
                 <div class="flex justify-end space-x-2 mt-6">
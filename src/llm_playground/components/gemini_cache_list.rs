@@ -0,0 +1,138 @@
+// Gemini context caches created by this browser, with their size and expiry,
+// to extend or delete them before they lapse on their own
+use super::notification::{NotificationMessage, NotificationType};
+use crate::llm_playground::{
+    api_clients::gemini_cache::{self, CacheEndpoint, CachedContext},
+    event_bus::PlaygroundEvent,
+    hooks::use_event_bus,
+    provider_config::ProviderConfig,
+};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct GeminiCacheListProps {
+    /// Searched for the Gemini provider whose key manages each cache
+    pub providers: Vec<ProviderConfig>,
+    pub cors_proxy: Option<String>,
+    /// New lifetime given by Extend
+    pub ttl_minutes: u32,
+}
+
+/// The Gemini provider a cache was created under
+fn provider_for<'a>(
+    providers: &'a [ProviderConfig],
+    cache: &CachedContext,
+) -> Option<&'a ProviderConfig> {
+    providers.iter().find(|provider| {
+        provider.transformer.r#use.iter().any(|t| t == "gemini")
+            && gemini_cache::api_root(&provider.api_base_url) == cache.api_root
+    })
+}
+
+#[function_component(GeminiCacheList)]
+pub fn gemini_cache_list(props: &GeminiCacheListProps) -> Html {
+    let on_notification = use_event_bus().callback(PlaygroundEvent::Notify);
+    let caches = use_state(gemini_cache::load_caches);
+    let busy = use_state(|| Option::<String>::None);
+
+    let run = {
+        let caches = caches.clone();
+        let busy = busy.clone();
+        let providers = props.providers.clone();
+        let cors_proxy = props.cors_proxy.clone();
+        let ttl_minutes = props.ttl_minutes;
+        move |cache: CachedContext, extend: bool| {
+            let Some(provider) = provider_for(&providers, &cache).cloned() else {
+                on_notification.emit(NotificationMessage::new(
+                    "No Gemini provider with this cache's base URL".to_string(),
+                    NotificationType::Error,
+                ));
+                return;
+            };
+            let caches = caches.clone();
+            let busy = busy.clone();
+            let cors_proxy = cors_proxy.clone();
+            let on_notification = on_notification.clone();
+            busy.set(Some(cache.name.clone()));
+            wasm_bindgen_futures::spawn_local(async move {
+                let endpoint = CacheEndpoint {
+                    base_url: &provider.api_base_url,
+                    api_key: &provider.api_key,
+                    cors_proxy: cors_proxy.as_deref(),
+                };
+                let result = if extend {
+                    endpoint
+                        .extend(&cache, ttl_minutes)
+                        .await
+                        .map(|_| format!("Cache extended by {} minutes", ttl_minutes))
+                } else {
+                    endpoint
+                        .delete(&cache)
+                        .await
+                        .map(|_| "Cache deleted".to_string())
+                };
+                let notification = match result {
+                    Ok(message) => NotificationMessage::new(message, NotificationType::Success),
+                    Err(error) => NotificationMessage::new(error, NotificationType::Error),
+                };
+                on_notification.emit(notification);
+                busy.set(None);
+                caches.set(gemini_cache::load_caches());
+            });
+        }
+    };
+
+    let now = js_sys::Date::now();
+    let live: Vec<&CachedContext> = caches.iter().filter(|c| c.expires_at > now).collect();
+    if live.is_empty() {
+        return html! {
+            <p class="text-xs text-gray-500 dark:text-gray-400">{"No context caches yet."}</p>
+        };
+    }
+
+    html! {
+        <ul class="space-y-2" aria-label="Gemini context caches">
+            {for live.into_iter().map(|cache| {
+                let on_extend = {
+                    let run = run.clone();
+                    let cache = cache.clone();
+                    Callback::from(move |_: MouseEvent| run(cache.clone(), true))
+                };
+                let on_delete = {
+                    let run = run.clone();
+                    let cache = cache.clone();
+                    Callback::from(move |_: MouseEvent| run(cache.clone(), false))
+                };
+                let disabled = busy.as_deref() == Some(cache.name.as_str());
+                let minutes_left = ((cache.expires_at - now) / 60_000.0).ceil();
+                html! {
+                    <li key={cache.name.clone()} class="flex items-center gap-2 p-2 text-xs bg-gray-50 dark:bg-gray-700 rounded-md">
+                        <div class="flex-1 min-w-0">
+                            <div class="font-mono truncate text-gray-900 dark:text-gray-100" title={cache.name.clone()}>{&cache.model}</div>
+                            <div class="text-gray-500 dark:text-gray-400">
+                                {format!("{} tokens · expires in {} min", cache.token_count, minutes_left)}
+                            </div>
+                        </div>
+                        <button
+                            onclick={on_extend}
+                            {disabled}
+                            class="px-2 py-1 rounded bg-gray-200 dark:bg-gray-600 text-gray-900 dark:text-gray-100 hover:bg-gray-300 dark:hover:bg-gray-500 disabled:opacity-50"
+                            title="Extend the cache lifetime"
+                        >
+                            {"Extend"}
+                        </button>
+                        <button
+                            onclick={on_delete}
+                            {disabled}
+                            class="px-2 py-1 rounded text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-red-900/30 disabled:opacity-50"
+                            aria-label={format!("Delete cache for {}", cache.model)}
+                            title="Delete cache"
+                        >
+                            <i class="fas fa-trash" aria-hidden="true"></i>
+                        </button>
+                    </li>
+                }
+            })}
+        </ul>
+    }
+}
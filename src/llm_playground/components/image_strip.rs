@@ -0,0 +1,165 @@
+// Strip above the input bar with the images generated in the session; picking
+// one sends it back with the next prompt to be edited, with an optional mask
+use crate::llm_playground::{
+    components::notification::{NotificationMessage, NotificationType},
+    event_bus::PlaygroundEvent,
+    hooks::use_event_bus,
+    image_generation::{self, GeneratedImage, ImageEdit},
+};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ImageStripProps {
+    /// Images of the session, oldest first
+    pub images: Vec<GeneratedImage>,
+    /// Image picked to be edited by the next message
+    pub edit: Option<ImageEdit>,
+    pub on_edit_change: Callback<Option<ImageEdit>>,
+    /// Whether the session's model generates images
+    pub image_model: bool,
+}
+
+#[function_component(ImageStrip)]
+pub fn image_strip(props: &ImageStripProps) -> Html {
+    let mask_input_ref = use_node_ref();
+    let on_notification = use_event_bus().callback(PlaygroundEvent::Notify);
+
+    let on_mask_file = {
+        let edit = props.edit.clone();
+        let on_edit_change = props.on_edit_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            input.set_value("");
+            let Some(mut edit) = edit.clone() else { return };
+            let on_edit_change = on_edit_change.clone();
+            let on_notification = on_notification.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match image_generation::read_mask(&file).await {
+                    Ok(mask) => {
+                        edit.mask = Some(mask);
+                        on_edit_change.emit(Some(edit));
+                    }
+                    Err(error) => {
+                        on_notification.emit(NotificationMessage::new(error, NotificationType::Error))
+                    }
+                }
+            });
+        })
+    };
+
+    let selected_id = props.edit.as_ref().map(|edit| edit.image.id.clone());
+    let edit_controls = match &props.edit {
+        Some(edit) => {
+            let pick_mask = {
+                let mask_input_ref = mask_input_ref.clone();
+                Callback::from(move |_: MouseEvent| {
+                    if let Some(input) = mask_input_ref.cast::<HtmlInputElement>() {
+                        input.click();
+                    }
+                })
+            };
+            let clear_mask = {
+                let edit = edit.clone();
+                let on_edit_change = props.on_edit_change.clone();
+                Callback::from(move |_: MouseEvent| {
+                    on_edit_change.emit(Some(ImageEdit {
+                        mask: None,
+                        ..edit.clone()
+                    }))
+                })
+            };
+            let cancel = {
+                let on_edit_change = props.on_edit_change.clone();
+                Callback::from(move |_: MouseEvent| on_edit_change.emit(None))
+            };
+            html! {
+                <div class="flex flex-wrap items-center gap-2 mt-1 text-xs text-gray-600 dark:text-gray-300">
+                    <span>
+                        <i class="fas fa-pen mr-1" aria-hidden="true"></i>
+                        {"The next message edits the selected image"}
+                    </span>
+                    <input
+                        ref={mask_input_ref.clone()}
+                        type="file"
+                        accept="image/png"
+                        class="hidden"
+                        onchange={on_mask_file}
+                    />
+                    {if edit.mask.is_some() {
+                        html! {
+                            <span class="inline-flex items-center gap-1 px-2 py-0.5 rounded bg-gray-100 dark:bg-gray-700">
+                                {"Mask added"}
+                                <button onclick={clear_mask} class="text-gray-400 hover:text-red-500" aria-label="Remove mask">
+                                    <i class="fas fa-times" aria-hidden="true"></i>
+                                </button>
+                            </span>
+                        }
+                    } else {
+                        html! {
+                            <button
+                                onclick={pick_mask}
+                                class="text-primary-600 dark:text-primary-400 hover:underline"
+                                title="PNG the size of the image whose transparent pixels mark the area to change"
+                            >
+                                {"Add mask"}
+                            </button>
+                        }
+                    }}
+                    <button onclick={cancel} class="text-gray-500 dark:text-gray-400 hover:underline">
+                        {"Cancel"}
+                    </button>
+                    {if props.image_model {
+                        html! {}
+                    } else {
+                        html! {
+                            <span class="text-yellow-700 dark:text-yellow-400">
+                                <i class="fas fa-exclamation-triangle mr-1" aria-hidden="true"></i>
+                                {"The current model doesn't generate images; switch to an image model to edit"}
+                            </span>
+                        }
+                    }}
+                </div>
+            }
+        }
+        None => html! {},
+    };
+
+    html! {
+        <div class="px-4 pt-2 border-t border-gray-200 dark:border-gray-700">
+            <div class="flex gap-2 overflow-x-auto pb-1" role="listbox" aria-label="Images in this session">
+                {for props.images.iter().map(|image| {
+                    let selected = selected_id.as_deref() == Some(image.id.as_str());
+                    let on_click = {
+                        let image = image.clone();
+                        let on_edit_change = props.on_edit_change.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            on_edit_change.emit((!selected).then(|| ImageEdit {
+                                image: image.clone(),
+                                mask: None,
+                            }))
+                        })
+                    };
+                    html! {
+                        <button
+                            onclick={on_click}
+                            role="option"
+                            aria-selected={selected.to_string()}
+                            title={if image.prompt.is_empty() { "Edit this image".to_string() } else { format!("Edit: {}", image.prompt) }}
+                            class={classes!(
+                                "shrink-0", "rounded", "overflow-hidden", "border-2",
+                                if selected { "border-primary-500" } else { "border-transparent hover:border-gray-300 dark:hover:border-gray-600" }
+                            )}
+                        >
+                            <img src={image.data_url()} alt={image.prompt.clone()} class="h-14 w-14 object-cover" />
+                        </button>
+                    }
+                })}
+            </div>
+            {edit_controls}
+        </div>
+    }
+}
@@ -1,4 +1,15 @@
-use web_sys::{HtmlTextAreaElement, KeyboardEvent};
+use super::ToolPopover;
+use crate::llm_playground::{
+    attachments::{self, Attachment, ReadProgress, PDF_MIME_TYPE},
+    components::notification::{NotificationMessage, NotificationType},
+    event_bus::PlaygroundEvent,
+    hooks::{use_event_bus, use_offload},
+    offload::{OffloadJob, OffloadResult},
+    provider_config::FlexibleApiConfig,
+    request_overrides::{RequestOverrides, MAX_TEMPERATURE},
+    MessageRole, ToolChoice,
+};
+use web_sys::{HtmlInputElement, HtmlTextAreaElement, KeyboardEvent};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
@@ -7,11 +18,56 @@ pub struct InputBarProps {
     pub on_message_change: Callback<InputEvent>,
     pub on_send_message: Callback<()>,
     pub is_loading: bool,
+    /// Rewrite the draft with the current model; the button is hidden when unset
+    #[prop_or_default]
+    pub on_improve_prompt: Option<Callback<()>>,
+    #[prop_or_default]
+    pub is_improving: bool,
+    /// Add the draft to the session as a system or developer message without
+    /// sending it; the buttons are hidden when unset
+    #[prop_or_default]
+    pub on_add_instruction: Option<Callback<MessageRole>>,
+    /// Parameters for the next message only; the popover is hidden when
+    /// `on_overrides_change` is unset
+    #[prop_or_default]
+    pub overrides: RequestOverrides,
+    #[prop_or_default]
+    pub on_overrides_change: Option<Callback<RequestOverrides>>,
+    /// The session's values, shown as placeholders
+    #[prop_or_default]
+    pub default_temperature: f32,
+    #[prop_or_default]
+    pub default_max_tokens: u32,
+    /// The session's config, for the function tools popover; hidden when unset
+    #[prop_or_default]
+    pub tool_config: Option<FlexibleApiConfig>,
+    /// Turn a tool on or off; the popover is read-only when unset
+    #[prop_or_default]
+    pub on_toggle_tool: Option<Callback<String>>,
+    /// Turn all tools of an MCP server on or off
+    #[prop_or_default]
+    pub on_toggle_mcp_server: Option<Callback<(String, bool)>>,
+    /// PDFs to send with the next message; attaching is off when
+    /// `on_attachments_change` is unset
+    #[prop_or_default]
+    pub attachments: Vec<Attachment>,
+    #[prop_or_default]
+    pub on_attachments_change: Option<Callback<Vec<Attachment>>>,
+    /// Whether the current model reads PDFs itself rather than their text
+    #[prop_or_default]
+    pub native_pdfs: bool,
 }
 
 #[function_component(InputBar)]
 pub fn input_bar(props: &InputBarProps) -> Html {
     let textarea_ref = use_node_ref();
+    let token_count = use_offload(OffloadJob::CountTokens(props.current_message.clone()));
+    let show_overrides = use_state(|| false);
+    let file_input_ref = use_node_ref();
+    // The file being read and how far it got
+    let reading = use_state(|| Option::<(String, ReadProgress)>::None);
+    let on_notification = use_event_bus().callback(PlaygroundEvent::Notify);
+    let send_blocked = props.is_loading || reading.is_some();
 
     let on_input = props.on_message_change.clone();
 
@@ -24,16 +80,207 @@ pub fn input_bar(props: &InputBarProps) -> Html {
 
     let on_keydown = {
         let on_send = props.on_send_message.clone();
-        let is_loading = props.is_loading;
 
         Callback::from(move |e: KeyboardEvent| {
-            if e.key() == "Enter" && !e.shift_key() && !is_loading {
+            if e.key() == "Enter" && !e.shift_key() && !send_blocked {
                 e.prevent_default();
                 on_send.emit(());
             }
         })
     };
 
+    let improve_button = match &props.on_improve_prompt {
+        Some(callback) => {
+            let callback = callback.clone();
+            let disabled =
+                props.current_message.trim().is_empty() || props.is_loading || props.is_improving;
+            html! {
+                <button
+                    onclick={Callback::from(move |_| callback.emit(()))}
+                    disabled={disabled}
+                    class={classes!(
+                        "p-2", "rounded-md",
+                        if disabled {
+                            "text-gray-400 dark:text-gray-600 cursor-not-allowed"
+                        } else {
+                            "text-gray-500 dark:text-gray-400 hover:text-primary-600 dark:hover:text-primary-400"
+                        }
+                    )}
+                    title="Improve this prompt"
+                    aria-label={if props.is_improving { "Improving prompt" } else { "Improve this prompt" }}
+                >
+                    {if props.is_improving {
+                        html! { <i class="fas fa-spinner fa-spin" aria-hidden="true"></i> }
+                    } else {
+                        html! { <i class="fas fa-magic" aria-hidden="true"></i> }
+                    }}
+                </button>
+            }
+        }
+        None => html! {},
+    };
+
+    let instruction_buttons = match &props.on_add_instruction {
+        Some(callback) => {
+            let disabled = props.current_message.trim().is_empty() || props.is_loading;
+            let button = |role: MessageRole, icon: &'static str, label: &'static str| {
+                let callback = callback.clone();
+                html! {
+                    <button
+                        onclick={Callback::from(move |_| callback.emit(role.clone()))}
+                        disabled={disabled}
+                        class={classes!(
+                            "p-2", "rounded-md",
+                            if disabled {
+                                "text-gray-400 dark:text-gray-600 cursor-not-allowed"
+                            } else {
+                                "text-gray-500 dark:text-gray-400 hover:text-primary-600 dark:hover:text-primary-400"
+                            }
+                        )}
+                        title={label}
+                        aria-label={label}
+                    >
+                        <i class={icon} aria-hidden="true"></i>
+                    </button>
+                }
+            };
+            html! {
+                <>
+                    {button(MessageRole::System, "fas fa-cog", "Add as system message")}
+                    {button(MessageRole::Developer, "fas fa-wrench", "Add as developer message")}
+                </>
+            }
+        }
+        None => html! {},
+    };
+
+    let (attach_button, attachment_chips) = match &props.on_attachments_change {
+        Some(on_change) => {
+            let on_files = {
+                let on_change = on_change.clone();
+                let attached = props.attachments.clone();
+                let reading = reading.clone();
+                let on_notification = on_notification.clone();
+                Callback::from(move |e: Event| {
+                    let input: HtmlInputElement = e.target_unchecked_into();
+                    let Some(list) = input.files() else { return };
+                    let files: Vec<web_sys::File> = (0..list.length()).filter_map(|i| list.get(i)).collect();
+                    // Picking the same file again should fire another change
+                    input.set_value("");
+                    let on_change = on_change.clone();
+                    let mut attached = attached.clone();
+                    let reading = reading.clone();
+                    let on_notification = on_notification.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        for file in files {
+                            let name = file.name();
+                            let progress = {
+                                let reading = reading.clone();
+                                let name = name.clone();
+                                move |progress| reading.set(Some((name.clone(), progress)))
+                            };
+                            match attachments::read_pdf(&file, progress).await {
+                                Ok(attachment) => {
+                                    attached.push(attachment);
+                                    on_change.emit(attached.clone());
+                                }
+                                Err(error) => on_notification.emit(NotificationMessage::new(
+                                    error,
+                                    NotificationType::Error,
+                                )),
+                            }
+                        }
+                        reading.set(None);
+                    });
+                })
+            };
+            let open_picker = {
+                let file_input_ref = file_input_ref.clone();
+                Callback::from(move |_: MouseEvent| {
+                    if let Some(input) = file_input_ref.cast::<HtmlInputElement>() {
+                        input.click();
+                    }
+                })
+            };
+            let button = html! {
+                <>
+                    <input
+                        ref={file_input_ref.clone()}
+                        type="file"
+                        accept={format!("{},.pdf", PDF_MIME_TYPE)}
+                        multiple={true}
+                        class="hidden"
+                        onchange={on_files}
+                    />
+                    <button
+                        onclick={open_picker}
+                        disabled={reading.is_some()}
+                        class="p-2 text-gray-500 dark:text-gray-400 hover:text-gray-700 dark:hover:text-gray-200"
+                        title={format!("Attach PDFs, up to {} each", attachments::format_size(attachments::MAX_PDF_BYTES))}
+                        aria-label="Attach PDF"
+                    >
+                        <i class="fas fa-paperclip" aria-hidden="true"></i>
+                    </button>
+                </>
+            };
+            let sent_as = if props.native_pdfs {
+                "sent as a file"
+            } else {
+                "sent as extracted text; this model can't read PDFs"
+            };
+            let chips = html! {
+                <>
+                    {for props.attachments.iter().enumerate().map(|(index, attachment)| {
+                        let on_remove = {
+                            let on_change = on_change.clone();
+                            let attachments = props.attachments.clone();
+                            Callback::from(move |_: MouseEvent| {
+                                let mut attachments = attachments.clone();
+                                attachments.remove(index);
+                                on_change.emit(attachments);
+                            })
+                        };
+                        let detail = if props.native_pdfs || !attachment.text.trim().is_empty() {
+                            format!("{} · {}", attachment.name, sent_as)
+                        } else {
+                            format!("{} · no text found, so the model won't see it", attachment.name)
+                        };
+                        html! {
+                            <span class="inline-flex items-center gap-1 max-w-full px-2 py-1 rounded bg-gray-100 dark:bg-gray-700 text-xs text-gray-700 dark:text-gray-200" title={detail}>
+                                <i class="fas fa-file-pdf text-red-500" aria-hidden="true"></i>
+                                <span class="truncate">{&attachment.name}</span>
+                                <span class="shrink-0 text-gray-500 dark:text-gray-400">
+                                    {attachments::format_size(attachment.size)}
+                                    {if props.native_pdfs { "" } else { " · text" }}
+                                </span>
+                                <button onclick={on_remove} class="ml-1 text-gray-400 hover:text-red-500" aria-label={format!("Remove {}", attachment.name)}>
+                                    <i class="fas fa-times" aria-hidden="true"></i>
+                                </button>
+                            </span>
+                        }
+                    })}
+                    {match &*reading {
+                        Some((name, progress)) => html! {
+                            <span class="inline-flex items-center gap-1 px-2 py-1 rounded bg-gray-100 dark:bg-gray-700 text-xs text-gray-600 dark:text-gray-300" role="status">
+                                <i class="fas fa-spinner fa-spin" aria-hidden="true"></i>
+                                <span class="truncate">{name}</span>
+                                <span class="shrink-0">{progress.label()}</span>
+                            </span>
+                        },
+                        None => html! {},
+                    }}
+                </>
+            };
+            (button, chips)
+        }
+        None => (html! {}, html! {}),
+    };
+
+    let overrides_button = match &props.on_overrides_change {
+        Some(callback) => render_overrides_popover(props, callback, &show_overrides),
+        None => html! {},
+    };
+
     // Auto-resize textarea
     let on_input_resize = {
         let textarea_ref = textarea_ref.clone();
@@ -60,12 +307,18 @@ pub fn input_bar(props: &InputBarProps) -> Html {
     html! {
         <div class="p-4 border-t border-gray-200 dark:border-gray-700">
             <div class="flex items-end border border-gray-300 dark:border-gray-500 rounded-lg bg-white dark:bg-gray-800 p-2">
-                <div class="flex-1">
+                <div class="flex-1 min-w-0">
+                    {if props.attachments.is_empty() && reading.is_none() {
+                        html! {}
+                    } else {
+                        html! { <div class="flex flex-wrap gap-1 px-2 pt-1">{attachment_chips}</div> }
+                    }}
                     <textarea
                         ref={textarea_ref}
                         class="w-full resize-none border-0 focus:ring-0 bg-transparent dark:bg-transparent p-2 text-sm text-gray-900 dark:text-gray-100"
                         rows="1"
                         placeholder="Type your message here..."
+                        aria-label="Message"
                         style="outline: none; min-height: 20px;"
                         value={props.current_message.clone()}
                         oninput={combined_input}
@@ -74,41 +327,56 @@ pub fn input_bar(props: &InputBarProps) -> Html {
                     />
                 </div>
                 <div class="flex items-center space-x-1">
-                    <button
-                        class="p-2 text-gray-500 dark:text-gray-400 hover:text-gray-700 dark:hover:text-gray-200"
-                        title="Attach file (not implemented)"
-                    >
-                        <i class="fas fa-paperclip"></i>
-                    </button>
+                    {attach_button}
+                    {instruction_buttons}
+                    {improve_button}
+                    {match &props.tool_config {
+                        Some(config) => html! {
+                            <ToolPopover
+                                config={config.clone()}
+                                on_toggle={props.on_toggle_tool.clone()}
+                                on_toggle_server={props.on_toggle_mcp_server.clone()}
+                            />
+                        },
+                        None => html! {},
+                    }}
+                    {overrides_button}
                     <button
                         onclick={on_send}
-                        disabled={props.current_message.trim().is_empty() || props.is_loading}
+                        disabled={props.current_message.trim().is_empty() || send_blocked}
                         class={classes!(
                             "p-2", "rounded-md",
-                            if props.current_message.trim().is_empty() || props.is_loading {
+                            if props.current_message.trim().is_empty() || send_blocked {
                                 "text-gray-400 dark:text-gray-600 cursor-not-allowed"
                             } else {
                                 "text-primary-600 dark:text-primary-400 hover:text-primary-700 dark:hover:text-primary-300 hover:bg-primary-50 dark:hover:bg-primary-900/20"
                             }
                         )}
                         title="Send message (Shift+Enter for new line)"
+                        aria-label={if props.is_loading { "Sending message" } else { "Send message" }}
                     >
                         {if props.is_loading {
-                            html! { <i class="fas fa-spinner fa-spin"></i> }
+                            html! { <i class="fas fa-spinner fa-spin" aria-hidden="true"></i> }
                         } else {
-                            html! { <i class="fas fa-paper-plane"></i> }
+                            html! { <i class="fas fa-paper-plane" aria-hidden="true"></i> }
                         }}
                     </button>
                 </div>
             </div>
             <div class="text-xs text-gray-600 dark:text-gray-300 mt-2 flex justify-between">
                 <span>
-                    <i class="fas fa-keyboard mr-1"></i>
+                    <i class="fas fa-keyboard mr-1" aria-hidden="true"></i>
                     {"Enter to send • Shift+Enter for new line"}
                 </span>
                 {if !props.current_message.is_empty() {
                     html! {
-                        <span>{format!("{} characters", props.current_message.len())}</span>
+                        <span>
+                            {format!("{} characters", props.current_message.len())}
+                            {match token_count.as_deref() {
+                                Some(OffloadResult::Tokens(tokens)) => format!(" · ≈{} tokens", tokens),
+                                _ => String::new(),
+                            }}
+                        </span>
                     }
                 } else {
                     html! {}
@@ -117,3 +385,110 @@ pub fn input_bar(props: &InputBarProps) -> Html {
         </div>
     }
 }
+
+/// Button next to send with a popover for this message's temperature, max
+/// tokens and tool choice
+fn render_overrides_popover(
+    props: &InputBarProps,
+    on_change: &Callback<RequestOverrides>,
+    show: &UseStateHandle<bool>,
+) -> Html {
+    let overrides = &props.overrides;
+    let toggle = {
+        let show = show.clone();
+        Callback::from(move |_: MouseEvent| show.set(!*show))
+    };
+    let edit = |apply: fn(&mut RequestOverrides, &str)| {
+        let overrides = overrides.clone();
+        let on_change = on_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut overrides = overrides.clone();
+            apply(&mut overrides, &input.value());
+            on_change.emit(overrides);
+        })
+    };
+    let on_reset = {
+        let on_change = on_change.clone();
+        Callback::from(move |_: MouseEvent| on_change.emit(RequestOverrides::default()))
+    };
+    let active = !overrides.is_empty();
+    let title = if active {
+        format!("Next message only: {}", overrides.summary())
+    } else {
+        "Parameters for the next message".to_string()
+    };
+    let input_class = "w-full p-1.5 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100";
+
+    html! {
+        <div class="relative">
+            <button
+                onclick={toggle}
+                class={classes!(
+                    "p-2", "rounded-md",
+                    if active {
+                        "text-primary-600 dark:text-primary-400 bg-primary-50 dark:bg-primary-900/20"
+                    } else {
+                        "text-gray-500 dark:text-gray-400 hover:text-primary-600 dark:hover:text-primary-400"
+                    }
+                )}
+                title={title.clone()}
+                aria-label={title}
+                aria-expanded={show.to_string()}
+            >
+                <i class="fas fa-sliders-h" aria-hidden="true"></i>
+            </button>
+            {if **show {
+                html! {
+                    <div class="absolute bottom-full right-0 mb-2 w-64 p-3 space-y-2 rounded-lg shadow-lg border border-gray-200 dark:border-gray-600 bg-white dark:bg-gray-800 z-20" role="dialog" aria-label="Parameters for the next message">
+                        <div class="text-xs text-gray-500 dark:text-gray-400">{"For the next message only"}</div>
+                        <label class="block text-sm text-gray-700 dark:text-gray-300">
+                            {"Temperature"}
+                            <input
+                                type="number"
+                                min="0"
+                                max={MAX_TEMPERATURE.to_string()}
+                                step="0.1"
+                                placeholder={props.default_temperature.to_string()}
+                                value={overrides.temperature.map(|t| t.to_string()).unwrap_or_default()}
+                                onchange={edit(|o, value| o.temperature = RequestOverrides::parse_temperature(value))}
+                                class={input_class}
+                            />
+                        </label>
+                        <label class="block text-sm text-gray-700 dark:text-gray-300">
+                            {"Max tokens"}
+                            <input
+                                type="number"
+                                min="1"
+                                placeholder={props.default_max_tokens.to_string()}
+                                value={overrides.max_tokens.map(|t| t.to_string()).unwrap_or_default()}
+                                onchange={edit(|o, value| o.max_tokens = RequestOverrides::parse_max_tokens(value))}
+                                class={input_class}
+                            />
+                        </label>
+                        <label class="block text-sm text-gray-700 dark:text-gray-300">
+                            {"Tools"}
+                            <select onchange={edit(|o, value| o.tool_choice = ToolChoice::parse(value))} class={input_class}>
+                                <option value="" selected={overrides.tool_choice.is_none()}>{"Session default"}</option>
+                                {for ToolChoice::ALL.iter().map(|choice| html! {
+                                    <option value={choice.openai_value()} selected={overrides.tool_choice == Some(*choice)}>
+                                        {choice.label()}
+                                    </option>
+                                })}
+                            </select>
+                        </label>
+                        <button
+                            onclick={on_reset}
+                            disabled={!active}
+                            class="text-xs text-primary-600 dark:text-primary-400 hover:underline disabled:opacity-50 disabled:no-underline"
+                        >
+                            {"Reset"}
+                        </button>
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,383 @@
+// Knowledge base: upload text documents, choose the embeddings model and let the
+// chat model search them through the search_documents tool
+use crate::llm_playground::{
+    api_clients::embeddings::create_embeddings_client,
+    app_state::use_app_state,
+    components::{ConfirmAction, ConfirmRequest},
+    event_bus::PlaygroundEvent,
+    hooks::{use_confirm, use_event_bus},
+    knowledge::{self, KnowledgeDocument, SEARCH_DOCUMENTS_TOOL_NAME},
+    knowledge_store,
+    provider_config::FlexibleApiConfig,
+};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// Embeddings model suggested when a provider is picked
+fn default_embedding_model(config: &FlexibleApiConfig, provider: &str) -> &'static str {
+    if config.provider_uses_transformer(provider, "gemini") {
+        "text-embedding-004"
+    } else {
+        "text-embedding-3-small"
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct KnowledgeViewProps {
+    pub on_close: Callback<()>,
+}
+
+#[function_component(KnowledgeView)]
+pub fn knowledge_view(props: &KnowledgeViewProps) -> Html {
+    let app_state = use_app_state();
+    let documents = use_state(|| Option::<Vec<KnowledgeDocument>>::None);
+    let reload = use_state(|| 0u32);
+    // File currently being indexed
+    let indexing = use_state(|| Option::<String>::None);
+    let errors = use_state(Vec::<String>::new);
+    let confirm = use_confirm();
+    let on_config_change =
+        use_event_bus().callback(|config| PlaygroundEvent::ConfigChanged(Box::new(config)));
+
+    {
+        let documents = documents.clone();
+        let errors = errors.clone();
+        use_effect_with(*reload, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match knowledge_store::list_documents().await {
+                    Ok(list) => documents.set(Some(list)),
+                    Err(e) => {
+                        documents.set(Some(Vec::new()));
+                        errors.set(vec![e]);
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let (embedding_provider, embedding_model) = app_state
+        .config
+        .embedding_model
+        .as_deref()
+        .and_then(|value| value.split_once(','))
+        .map(|(provider, model)| (provider.to_string(), model.to_string()))
+        .unwrap_or_default();
+    let search_tool = app_state
+        .config
+        .function_tools
+        .iter()
+        .find(|tool| tool.name == SEARCH_DOCUMENTS_TOOL_NAME);
+    let search_enabled = search_tool.is_some_and(|tool| tool.enabled);
+
+    let on_provider_change = {
+        let config = app_state.config.clone();
+        let on_config_change = on_config_change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let provider = input.value();
+            let mut next = config.clone();
+            next.embedding_model = if provider.is_empty() {
+                None
+            } else {
+                Some(format!(
+                    "{},{}",
+                    provider,
+                    default_embedding_model(&config, &provider)
+                ))
+            };
+            on_config_change.emit(next);
+        })
+    };
+
+    let on_model_change = {
+        let config = app_state.config.clone();
+        let on_config_change = on_config_change.clone();
+        let provider = embedding_provider.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = config.clone();
+            next.embedding_model = Some(format!("{},{}", provider, input.value().trim()));
+            on_config_change.emit(next);
+        })
+    };
+
+    let on_toggle_search = {
+        let config = app_state.config.clone();
+        let on_config_change = on_config_change.clone();
+        Callback::from(move |_: Event| {
+            let mut next = config.clone();
+            // Configs saved before the knowledge base existed don't have the tool yet
+            if !next
+                .function_tools
+                .iter()
+                .any(|tool| tool.name == SEARCH_DOCUMENTS_TOOL_NAME)
+            {
+                if let Some(tool) = FlexibleApiConfig::get_default_function_tools()
+                    .into_iter()
+                    .find(|tool| tool.name == SEARCH_DOCUMENTS_TOOL_NAME)
+                {
+                    next.function_tools.push(tool);
+                }
+            }
+            next.toggle_function_tool(SEARCH_DOCUMENTS_TOOL_NAME);
+            on_config_change.emit(next);
+        })
+    };
+
+    let on_upload = {
+        let config = app_state.config.clone();
+        let indexing = indexing.clone();
+        let errors = errors.clone();
+        let reload = reload.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Some(files) = input.files() else {
+                return;
+            };
+            let files: Vec<web_sys::File> =
+                (0..files.length()).filter_map(|i| files.get(i)).collect();
+            // Allow picking the same file again later
+            input.set_value("");
+            if files.is_empty() {
+                return;
+            }
+
+            let config = config.clone();
+            let indexing = indexing.clone();
+            let errors = errors.clone();
+            let reload = reload.clone();
+            errors.set(Vec::new());
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut failures = Vec::new();
+                for file in files {
+                    let name = file.name();
+                    indexing.set(Some(name.clone()));
+                    let text = JsFuture::from(file.text())
+                        .await
+                        .ok()
+                        .and_then(|value| value.as_string());
+                    let result = match text {
+                        Some(text) => knowledge::index_document(&config, &name, &text)
+                            .await
+                            .map(|_| ()),
+                        None => Err("Could not read the file as text".to_string()),
+                    };
+                    if let Err(e) = result {
+                        failures.push(format!("{}: {}", name, e));
+                    }
+                }
+                indexing.set(None);
+                errors.set(failures);
+                reload.set(*reload + 1);
+            });
+        })
+    };
+
+    let on_delete = {
+        let confirm = confirm.clone();
+        let reload = reload.clone();
+        let errors = errors.clone();
+        Callback::from(move |document: KnowledgeDocument| {
+            let reload = reload.clone();
+            let errors = errors.clone();
+            let id = document.id.clone();
+            confirm.confirm(
+                ConfirmRequest::new(
+                    ConfirmAction::DeleteKnowledgeDocument,
+                    "Delete document?",
+                    format!(
+                        "\"{}\" and its {} embedded chunks will be removed from the knowledge base.",
+                        document.name, document.chunk_count
+                    ),
+                ),
+                Callback::from(move |_| {
+                    let reload = reload.clone();
+                    let errors = errors.clone();
+                    let id = id.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        if let Err(e) = knowledge_store::delete_document(&id).await {
+                            errors.set(vec![e]);
+                        }
+                        reload.set(*reload + 1);
+                    });
+                }),
+            );
+        })
+    };
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    let input_class = "p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 text-sm";
+    let can_upload = create_embeddings_client(&app_state.config).is_ok() && indexing.is_none();
+
+    html! {
+        <div class="flex-1 flex flex-col min-w-0 bg-white dark:bg-gray-800">
+            <header class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
+                <div>
+                    <h2 class="font-semibold text-gray-900 dark:text-gray-100">{"Knowledge Base"}</h2>
+                    <div class="text-sm text-gray-600 dark:text-gray-300">
+                        {"Documents are embedded and stored in this browser. The model can search them with the search_documents tool."}
+                    </div>
+                </div>
+                <button
+                    onclick={on_close}
+                    class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                    aria-label="Close knowledge base"
+                    title="Back to chat"
+                >
+                    <i class="fas fa-times" aria-hidden="true"></i>
+                </button>
+            </header>
+
+            <div class="flex-1 overflow-y-auto custom-scrollbar p-4 space-y-4">
+                <div class="flex flex-wrap items-center gap-2">
+                    <label class="text-sm text-gray-700 dark:text-gray-300">{"Embeddings provider"}</label>
+                    <select aria-label="Embeddings provider" onchange={on_provider_change} class={input_class}>
+                        <option value="" selected={embedding_provider.is_empty()}>{"Not configured"}</option>
+                        {for app_state.config.providers.iter().map(|provider| html! {
+                            <option value={provider.name.clone()} selected={provider.name == embedding_provider}>
+                                {provider.name.clone()}
+                            </option>
+                        })}
+                    </select>
+                    <label class="text-sm text-gray-700 dark:text-gray-300" for="embedding-model">{"Model"}</label>
+                    <input
+                        id="embedding-model"
+                        type="text"
+                        value={embedding_model}
+                        onchange={on_model_change}
+                        disabled={embedding_provider.is_empty()}
+                        class={classes!(input_class, "flex-1", "min-w-[12rem]")}
+                        placeholder="text-embedding-3-small"
+                    />
+                </div>
+
+                <label class="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+                    <input type="checkbox" checked={search_enabled} onchange={on_toggle_search} />
+                    {"Let the chat model search these documents"}
+                </label>
+
+                <div class="flex items-center gap-3">
+                    <label
+                        class={classes!(
+                            "text-sm", "px-4", "py-2", "bg-primary-600", "text-white", "rounded-md",
+                            if can_upload { "hover:bg-primary-700 cursor-pointer" } else { "opacity-50 cursor-not-allowed" }
+                        )}
+                    >
+                        <i class="fas fa-upload mr-1" aria-hidden="true"></i>{"Upload documents"}
+                        <input
+                            type="file"
+                            multiple=true
+                            accept=".txt,.md,.markdown,text/plain,text/markdown"
+                            onchange={on_upload}
+                            disabled={!can_upload}
+                            class="hidden"
+                        />
+                    </label>
+                    {if let Some(name) = &*indexing {
+                        html! {
+                            <span class="text-sm text-gray-600 dark:text-gray-300" role="status">
+                                <i class="fas fa-spinner fa-spin mr-1" aria-hidden="true"></i>
+                                {format!("Embedding {}...", name)}
+                            </span>
+                        }
+                    } else if app_state.config.embedding_model.is_none() {
+                        html! {
+                            <span class="text-sm text-gray-600 dark:text-gray-300">
+                                {"Choose an embeddings provider to upload documents."}
+                            </span>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                </div>
+
+                {for errors.iter().map(|error| html! {
+                    <div class="text-sm p-2 rounded-md bg-red-50 dark:bg-red-900/30 text-red-700 dark:text-red-300" role="alert">
+                        {error.clone()}
+                    </div>
+                })}
+
+                {match &*documents {
+                    None => html! {
+                        <div class="text-sm text-gray-600 dark:text-gray-300">{"Loading documents..."}</div>
+                    },
+                    Some(list) if list.is_empty() => html! {
+                        <div class="text-center text-gray-600 dark:text-gray-300 py-12">
+                            <i class="fas fa-book text-3xl mb-2" aria-hidden="true"></i>
+                            <p>{"No documents yet. Upload text or markdown files to build the knowledge base."}</p>
+                        </div>
+                    },
+                    Some(list) => html! {
+                        <ul class="divide-y divide-gray-200 dark:divide-gray-600 border border-gray-200 dark:border-gray-600 rounded-md">
+                            {for list.iter().map(|document| render_document(document, &app_state.config, &on_delete))}
+                        </ul>
+                    },
+                }}
+            </div>
+            {confirm.dialog()}
+        </div>
+    }
+}
+
+fn render_document(
+    document: &KnowledgeDocument,
+    config: &FlexibleApiConfig,
+    on_delete: &Callback<KnowledgeDocument>,
+) -> Html {
+    let stale = config.embedding_model.as_deref() != Some(document.embedding_model.as_str());
+    let onclick = {
+        let on_delete = on_delete.clone();
+        let document = document.clone();
+        Callback::from(move |_: MouseEvent| on_delete.emit(document.clone()))
+    };
+
+    html! {
+        <li class="p-3 flex items-center justify-between gap-2">
+            <div class="min-w-0">
+                <div class="font-medium text-sm text-gray-900 dark:text-gray-100 truncate">{&document.name}</div>
+                <div class="text-xs text-gray-600 dark:text-gray-300">
+                    {format!(
+                        "{} chunks · {} characters · {} · {}",
+                        document.chunk_count,
+                        document.size,
+                        format_date(document.created_at),
+                        document.embedding_model.replace(',', " / ")
+                    )}
+                </div>
+                {if stale {
+                    html! {
+                        <div class="text-xs text-amber-600 dark:text-amber-400">
+                            {"Embedded with a different model; re-upload it to search it with the current model."}
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }}
+            </div>
+            <button
+                {onclick}
+                class="p-2 rounded-md text-gray-500 hover:text-red-600 hover:bg-gray-100 dark:hover:bg-gray-700"
+                aria-label={format!("Delete {}", document.name)}
+                title="Delete document"
+            >
+                <i class="fas fa-trash" aria-hidden="true"></i>
+            </button>
+        </li>
+    }
+}
+
+fn format_date(timestamp: f64) -> String {
+    let date = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(timestamp));
+    format!(
+        "{}-{:02}-{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date()
+    )
+}
@@ -0,0 +1,91 @@
+// Scan for model servers on this machine and add the ones found as providers
+use crate::llm_playground::{
+    local_discovery::{self, DiscoveredServer, LOCAL_SERVER_PROBES},
+    provider_config::ProviderConfig,
+};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct LocalServerScanProps {
+    /// Checked to offer refreshing servers that are already providers
+    pub providers: Vec<ProviderConfig>,
+    pub on_add: Callback<DiscoveredServer>,
+}
+
+#[function_component(LocalServerScan)]
+pub fn local_server_scan(props: &LocalServerScanProps) -> Html {
+    let scanning = use_state(|| false);
+    let servers = use_state(|| Option::<Vec<DiscoveredServer>>::None);
+
+    let on_scan = {
+        let scanning = scanning.clone();
+        let servers = servers.clone();
+        Callback::from(move |_: MouseEvent| {
+            let scanning = scanning.clone();
+            let servers = servers.clone();
+            scanning.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                servers.set(Some(local_discovery::scan().await));
+                scanning.set(false);
+            });
+        })
+    };
+
+    let ports = LOCAL_SERVER_PROBES
+        .iter()
+        .map(|probe| probe.port.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    html! {
+        <div class="space-y-1">
+            <div class="flex items-center gap-2">
+                <button
+                    onclick={on_scan}
+                    disabled={*scanning}
+                    class="text-xs px-2 py-1 bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-300 rounded hover:bg-gray-200 dark:hover:bg-gray-600 disabled:opacity-50"
+                    title={format!("Look for LM Studio, llama.cpp and Ollama on localhost ports {}", ports)}
+                >
+                    <i class={classes!("fas", if *scanning { "fa-spinner fa-spin" } else { "fa-search" }, "mr-1")} aria-hidden="true"></i>
+                    {if *scanning { "Scanning…" } else { "Scan local servers" }}
+                </button>
+            </div>
+            {match &*servers {
+                None => html! {},
+                Some(found) if found.is_empty() => html! {
+                    <p class="text-xs text-gray-500 dark:text-gray-400" role="status">
+                        {format!("No servers with loaded models on ports {}.", ports)}
+                    </p>
+                },
+                Some(found) => html! {
+                    <ul class="space-y-1" aria-label="Local servers found">
+                        {for found.iter().map(|server| {
+                            let known = server.existing_index(&props.providers).is_some();
+                            let on_add = {
+                                let on_add = props.on_add.clone();
+                                let server = server.clone();
+                                Callback::from(move |_: MouseEvent| on_add.emit(server.clone()))
+                            };
+                            html! {
+                                <li key={server.base_url.clone()} class="flex items-center gap-2 p-2 text-xs bg-gray-50 dark:bg-gray-700 rounded-md">
+                                    <div class="flex-1 min-w-0">
+                                        <div class="font-mono truncate text-gray-900 dark:text-gray-100">{&server.base_url}</div>
+                                        <div class="truncate text-gray-500 dark:text-gray-400" title={server.models.join(", ")}>
+                                            {format!("{} models: {}", server.models.len(), server.models.join(", "))}
+                                        </div>
+                                    </div>
+                                    <button
+                                        onclick={on_add}
+                                        class="shrink-0 px-2 py-1 rounded bg-primary-600 text-white hover:bg-primary-700"
+                                    >
+                                        {if known { "Update models" } else { "Add" }}
+                                    </button>
+                                </li>
+                            }
+                        })}
+                    </ul>
+                },
+            }}
+        </div>
+    }
+}
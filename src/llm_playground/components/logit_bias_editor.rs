@@ -0,0 +1,205 @@
+// Logit bias entries, with a lookup that turns text into the token ids the
+// bias is keyed by
+use super::notification::{NotificationMessage, NotificationType};
+use crate::llm_playground::{
+    event_bus::PlaygroundEvent,
+    hooks::use_event_bus,
+    sampling_controls::{self, Encoding, LogitBiasEntry, MAX_BIAS, MIN_BIAS},
+};
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct LogitBiasEditorProps {
+    pub entries: Vec<LogitBiasEntry>,
+    pub on_change: Callback<Vec<LogitBiasEntry>>,
+    /// Used to download the tokenizer vocabulary
+    pub cors_proxy: Option<String>,
+}
+
+/// Shown instead of the token's text so spaces and newlines stay visible
+fn visible(text: &str) -> String {
+    text.replace(' ', "␣").replace('\n', "↵")
+}
+
+#[function_component(LogitBiasEditor)]
+pub fn logit_bias_editor(props: &LogitBiasEditorProps) -> Html {
+    let on_notification = use_event_bus().callback(PlaygroundEvent::Notify);
+    let encoding = use_state(|| Encoding::O200kBase);
+    let lookup = use_state(String::new);
+    let bias = use_state(|| MIN_BIAS);
+    let loading = use_state(|| false);
+
+    let on_encoding_change = {
+        let encoding = encoding.clone();
+        Callback::from(move |e: Event| {
+            let select: HtmlInputElement = e.target_unchecked_into();
+            if let Some(selected) = Encoding::from_name(&select.value()) {
+                encoding.set(selected);
+            }
+        })
+    };
+
+    let on_lookup_input = {
+        let lookup = lookup.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            lookup.set(input.value());
+        })
+    };
+
+    let on_bias_input = {
+        let bias = bias.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(value) = input.value().parse::<i32>() {
+                bias.set(value.clamp(MIN_BIAS, MAX_BIAS));
+            }
+        })
+    };
+
+    // Add every token of the looked up text with the chosen bias
+    let on_add = {
+        let encoding = encoding.clone();
+        let lookup = lookup.clone();
+        let bias = bias.clone();
+        let loading = loading.clone();
+        let entries = props.entries.clone();
+        let on_change = props.on_change.clone();
+        let cors_proxy = props.cors_proxy.clone();
+        Callback::from(move |_: MouseEvent| {
+            if lookup.is_empty() {
+                return;
+            }
+            let encoding = *encoding;
+            let text = (*lookup).clone();
+            let bias = *bias;
+            let lookup = lookup.clone();
+            let loading = loading.clone();
+            let mut entries = entries.clone();
+            let on_change = on_change.clone();
+            let cors_proxy = cors_proxy.clone();
+            let on_notification = on_notification.clone();
+            loading.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                match sampling_controls::load_vocabulary(encoding, cors_proxy.as_deref()).await {
+                    Ok(vocabulary) => {
+                        for token_id in vocabulary.encode(&text) {
+                            let text = vocabulary.decode(token_id).unwrap_or_default();
+                            match entries.iter_mut().find(|e| e.token_id == token_id) {
+                                Some(entry) => entry.bias = bias,
+                                None => entries.push(LogitBiasEntry {
+                                    token_id,
+                                    bias,
+                                    text,
+                                }),
+                            }
+                        }
+                        on_change.emit(entries);
+                        lookup.set(String::new());
+                    }
+                    Err(error) => on_notification
+                        .emit(NotificationMessage::new(error, NotificationType::Error)),
+                }
+                loading.set(false);
+            });
+        })
+    };
+
+    html! {
+        <div class="space-y-2">
+            <div class="flex gap-2">
+                <select
+                    onchange={on_encoding_change}
+                    aria-label="Tokenizer"
+                    class="p-2 text-sm border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                >
+                    {for Encoding::ALL.iter().map(|option| html! {
+                        <option value={option.name()} selected={*option == *encoding}>{option.name()}</option>
+                    })}
+                </select>
+                <input
+                    type="text"
+                    value={(*lookup).clone()}
+                    oninput={on_lookup_input}
+                    aria-label="Text to bias"
+                    placeholder=" word"
+                    class="flex-1 min-w-0 p-2 text-sm font-mono border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                />
+                <input
+                    type="number"
+                    min={MIN_BIAS.to_string()}
+                    max={MAX_BIAS.to_string()}
+                    value={bias.to_string()}
+                    oninput={on_bias_input}
+                    aria-label="Bias"
+                    class="w-20 p-2 text-sm border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                />
+                <button
+                    onclick={on_add}
+                    disabled={*loading || lookup.is_empty()}
+                    class="px-3 py-1 text-sm rounded bg-primary-600 text-white hover:bg-primary-700 disabled:opacity-50"
+                >
+                    {if *loading {
+                        html! { <i class="fas fa-spinner fa-spin" aria-hidden="true"></i> }
+                    } else {
+                        html! { {"Add"} }
+                    }}
+                </button>
+            </div>
+            {if props.entries.is_empty() {
+                html! {}
+            } else {
+                html! {
+                    <ul class="space-y-1" aria-label="Biased tokens">
+                        {for props.entries.iter().enumerate().map(|(index, entry)| {
+                            let on_bias = {
+                                let entries = props.entries.clone();
+                                let on_change = props.on_change.clone();
+                                Callback::from(move |e: InputEvent| {
+                                    let input: HtmlInputElement = e.target_unchecked_into();
+                                    if let Ok(value) = input.value().parse::<i32>() {
+                                        let mut entries = entries.clone();
+                                        entries[index].bias = value.clamp(MIN_BIAS, MAX_BIAS);
+                                        on_change.emit(entries);
+                                    }
+                                })
+                            };
+                            let on_remove = {
+                                let entries = props.entries.clone();
+                                let on_change = props.on_change.clone();
+                                Callback::from(move |_: MouseEvent| {
+                                    let mut entries = entries.clone();
+                                    entries.remove(index);
+                                    on_change.emit(entries);
+                                })
+                            };
+                            html! {
+                                <li key={entry.token_id} class="flex items-center gap-2 p-2 text-xs bg-gray-50 dark:bg-gray-700 rounded-md">
+                                    <span class="font-mono text-gray-500 dark:text-gray-400 w-16">{entry.token_id}</span>
+                                    <span class="flex-1 min-w-0 truncate font-mono text-gray-900 dark:text-gray-100">{visible(&entry.text)}</span>
+                                    <input
+                                        type="number"
+                                        min={MIN_BIAS.to_string()}
+                                        max={MAX_BIAS.to_string()}
+                                        value={entry.bias.to_string()}
+                                        oninput={on_bias}
+                                        aria-label={format!("Bias of token {}", entry.token_id)}
+                                        class="w-16 p-1 border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-800 text-gray-900 dark:text-gray-100"
+                                    />
+                                    <button
+                                        onclick={on_remove}
+                                        class="text-red-500 hover:text-red-700"
+                                        aria-label={format!("Remove token {}", entry.token_id)}
+                                    >
+                                        <i class="fas fa-times" aria-hidden="true"></i>
+                                    </button>
+                                </li>
+                            }
+                        })}
+                    </ul>
+                }
+            }}
+        </div>
+    }
+}
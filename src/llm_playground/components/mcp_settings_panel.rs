@@ -5,7 +5,11 @@ use web_sys::HtmlInputElement;
 use yew::prelude::*;
 
 use crate::llm_playground::{
+    components::{ConfirmAction, ConfirmRequest},
+    hooks::use_confirm,
+    logging,
     mcp_client::{McpClient, McpServerConfig},
+    mcp_presets::{self, McpPreset, McpPresetAuth},
     types::ApiConfig,
 };
 
@@ -26,7 +30,13 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
     let new_server_url = use_state(|| String::new());
     let new_server_auth_token = use_state(|| String::new());
     let new_server_headers_json = use_state(|| String::from("{}"));
+    // None keeps the default mcp_{name}_ prefix, Some("") turns it off
+    let new_server_tool_prefix = use_state(|| None::<String>);
     let show_add_server = use_state(|| false);
+    let show_presets = use_state(|| false);
+    let presets = use_state(mcp_presets::load);
+    // Result of the last "Check for updates", or None while idle
+    let presets_status = use_state(|| None::<String>);
     let show_json_editor = use_state(|| false);
     let editing_server = use_state(|| None::<String>);
     let connection_status = use_state(|| HashMap::<String, String>::new());
@@ -39,6 +49,7 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
         let new_server_url = new_server_url.clone();
         let new_server_auth_token = new_server_auth_token.clone();
         let new_server_headers_json = new_server_headers_json.clone();
+        let new_server_tool_prefix = new_server_tool_prefix.clone();
         let show_add_server = show_add_server.clone();
         let editing_server = editing_server.clone();
 
@@ -82,6 +93,7 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
                         Some(headers)
                     },
                     enabled: true,
+                    tool_prefix: (*new_server_tool_prefix).clone(),
                 };
 
                 let mut updated_mcp_config = new_config.get_mcp_config().clone();
@@ -101,6 +113,7 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
                 new_server_url.set(String::new());
                 new_server_auth_token.set(String::new());
                 new_server_headers_json.set(String::from("{}"));
+                new_server_tool_prefix.set(None);
                 show_add_server.set(false);
                 editing_server.set(None);
             }
@@ -114,6 +127,7 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
         let new_server_url = new_server_url.clone();
         let new_server_auth_token = new_server_auth_token.clone();
         let new_server_headers_json = new_server_headers_json.clone();
+        let new_server_tool_prefix = new_server_tool_prefix.clone();
         let show_add_server = show_add_server.clone();
         let editing_server = editing_server.clone();
 
@@ -143,6 +157,7 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
                     serde_json::to_string_pretty(&other_headers)
                         .unwrap_or_else(|_| "{}".to_string()),
                 );
+                new_server_tool_prefix.set(server_config.tool_prefix.clone());
 
                 editing_server.set(Some(server_name));
                 show_add_server.set(true);
@@ -150,8 +165,60 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
         })
     };
 
+    // Fill the form from a preset; the token, if any, is still to be entered
+    let on_pick_preset = {
+        let new_server_name = new_server_name.clone();
+        let new_server_url = new_server_url.clone();
+        let new_server_auth_token = new_server_auth_token.clone();
+        let new_server_headers_json = new_server_headers_json.clone();
+        let new_server_tool_prefix = new_server_tool_prefix.clone();
+        let show_add_server = show_add_server.clone();
+        let show_presets = show_presets.clone();
+        let editing_server = editing_server.clone();
+
+        Callback::from(move |preset: McpPreset| {
+            new_server_name.set(preset.id.clone());
+            new_server_url.set(preset.url.clone());
+            new_server_auth_token.set(String::new());
+            let headers = match &preset.auth {
+                McpPresetAuth::Header { name } => serde_json::json!({ name.clone(): "" }),
+                _ => serde_json::json!({}),
+            };
+            new_server_headers_json.set(serde_json::to_string_pretty(&headers).unwrap_or_default());
+            new_server_tool_prefix.set(None);
+            editing_server.set(None);
+            show_presets.set(false);
+            show_add_server.set(true);
+        })
+    };
+
+    let on_update_presets = {
+        let presets = presets.clone();
+        let presets_status = presets_status.clone();
+        Callback::from(move |_: MouseEvent| {
+            let presets = presets.clone();
+            let presets_status = presets_status.clone();
+            let version = presets.version;
+            presets_status.set(Some("Checking…".to_string()));
+            wasm_bindgen_futures::spawn_local(async move {
+                match mcp_presets::update().await {
+                    Ok(list) => {
+                        presets_status.set(Some(if list.version > version {
+                            format!("Updated to {} servers", list.servers.len())
+                        } else {
+                            "The list is up to date".to_string()
+                        }));
+                        presets.set(list);
+                    }
+                    Err(error) => presets_status.set(Some(error)),
+                }
+            });
+        })
+    };
+
     // Handle removing a server
-    let on_remove_server = {
+    let confirm = use_confirm();
+    let remove_server = {
         let config = config.clone();
         let on_config_change = props.on_config_change.clone();
 
@@ -164,12 +231,30 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
             // Also remove MCP tools from that server
             new_config
                 .function_tools
-                .retain(|tool| !tool.name.starts_with(&format!("mcp_{}_", server_name)));
+                .retain(|tool| tool.mcp_server() != Some(server_name.as_str()));
 
             on_config_change.emit(new_config);
         })
     };
 
+    let on_remove_server = {
+        let confirm = confirm.clone();
+        Callback::from(move |server_name: String| {
+            confirm.confirm(
+                ConfirmRequest::new(
+                    ConfirmAction::RemoveMcpServer,
+                    "Remove MCP server?",
+                    format!(
+                        "\"{}\" and the tools it provides will be removed from the configuration.",
+                        server_name
+                    ),
+                )
+                .with_confirm_label("Remove"),
+                remove_server.reform(move |_| server_name.clone()),
+            );
+        })
+    };
+
     // Handle toggling server enabled state
     let on_toggle_server = {
         let config = config.clone();
@@ -200,11 +285,12 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
             // Only proceed if there are enabled servers
             let has_enabled_servers = mcp_config.servers.values().any(|server| server.enabled);
             if !has_enabled_servers {
-                gloo_console::log!("No enabled MCP servers to connect to");
+                logging::info!("No enabled MCP servers to connect to");
                 return;
             }
 
-            let mut client = McpClient::new(mcp_config);
+            let mut client = McpClient::new(mcp_config)
+                .with_cors_proxy(config.shared_settings.cors_proxy.clone());
             let status = connection_status.clone();
             let callback = on_mcp_client_change.clone();
 
@@ -228,9 +314,7 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
                         }
                         status.set(new_status);
                         callback.emit(Some(client));
-                        gloo_console::log!(
-                            "MCP client initialized successfully via manual connection"
-                        );
+                        logging::info!("MCP client initialized via manual connection");
                     }
                     Err(e) => {
                         let mut new_status = (*status).clone();
@@ -241,9 +325,9 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
                         }
                         status.set(new_status);
                         callback.emit(None);
-                        gloo_console::log!(
-                            "Failed to initialize MCP client via manual connection:",
-                            &e
+                        logging::error!(
+                            "Failed to initialize MCP client via manual connection: {}",
+                            e
                         );
                     }
                 }
@@ -255,17 +339,93 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
         <div class="mcp-settings-panel p-4 bg-gray-50 rounded-lg">
             <div class="flex items-center justify-between mb-4">
                 <h3 class="text-lg font-medium text-gray-900">{"MCP Server Configuration"}</h3>
-                <button
-                    class="px-3 py-1 bg-blue-500 text-white rounded hover:bg-blue-600"
-                    onclick={
-                        let show_add_server = show_add_server.clone();
-                        Callback::from(move |_| show_add_server.set(!*show_add_server))
-                    }
-                >
-                    {"Add Server"}
-                </button>
+                <div class="flex space-x-2">
+                    <button
+                        class="px-3 py-1 bg-gray-200 text-gray-800 rounded hover:bg-gray-300"
+                        onclick={
+                            let show_presets = show_presets.clone();
+                            Callback::from(move |_| show_presets.set(!*show_presets))
+                        }
+                    >
+                        {"Browse Servers"}
+                    </button>
+                    <button
+                        class="px-3 py-1 bg-blue-500 text-white rounded hover:bg-blue-600"
+                        onclick={
+                            let show_add_server = show_add_server.clone();
+                            Callback::from(move |_| show_add_server.set(!*show_add_server))
+                        }
+                    >
+                        {"Add Server"}
+                    </button>
+                </div>
             </div>
 
+            // Known public servers
+            if *show_presets {
+                <div class="mb-4 p-4 bg-white dark:bg-gray-800 rounded border">
+                    <div class="flex items-center justify-between mb-3">
+                        <h4 class="font-medium text-gray-900 dark:text-gray-100">{"Public MCP Servers"}</h4>
+                        <div class="flex items-center space-x-2">
+                            {if let Some(status) = presets_status.as_ref() {
+                                html! { <span class="text-xs text-gray-500 dark:text-gray-400">{status}</span> }
+                            } else {
+                                html! {}
+                            }}
+                            <button
+                                class="px-2 py-1 text-xs rounded text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700"
+                                onclick={on_update_presets}
+                            >
+                                <i class="fas fa-rotate mr-1" aria-hidden="true"></i>
+                                {"Check for updates"}
+                            </button>
+                        </div>
+                    </div>
+                    <ul class="divide-y divide-gray-200 dark:divide-gray-700">
+                        {for presets.servers.iter().map(|preset| {
+                            let added = preset.is_added(&mcp_config);
+                            let auth = match &preset.auth {
+                                McpPresetAuth::None => "No key".to_string(),
+                                McpPresetAuth::Bearer => "Token".to_string(),
+                                McpPresetAuth::Header { name } => format!("Key in {}", name),
+                            };
+                            let on_pick = {
+                                let on_pick_preset = on_pick_preset.clone();
+                                let preset = preset.clone();
+                                Callback::from(move |_: MouseEvent| on_pick_preset.emit(preset.clone()))
+                            };
+                            html! {
+                                <li key={preset.id.clone()} class="flex items-start gap-3 py-2">
+                                    <div class="flex-1 min-w-0">
+                                        <div class="flex items-center space-x-2">
+                                            <span class="text-sm font-medium text-gray-900 dark:text-gray-100">{&preset.name}</span>
+                                            <span class="px-2 py-0.5 text-xs rounded-full bg-gray-100 text-gray-600 dark:bg-gray-700 dark:text-gray-300">{auth}</span>
+                                            {if let Some(docs_url) = &preset.docs_url {
+                                                html! {
+                                                    <a href={docs_url.clone()} target="_blank" rel="noopener noreferrer" class="text-xs text-blue-600 dark:text-blue-400 hover:underline">
+                                                        {"Docs"}
+                                                    </a>
+                                                }
+                                            } else {
+                                                html! {}
+                                            }}
+                                        </div>
+                                        <p class="text-xs text-gray-600 dark:text-gray-400">{&preset.description}</p>
+                                    </div>
+                                    <button
+                                        class="px-3 py-1 text-sm rounded bg-blue-500 text-white hover:bg-blue-600 disabled:opacity-50 disabled:cursor-not-allowed"
+                                        disabled={added}
+                                        onclick={on_pick}
+                                    >
+                                        {if added { "Added" } else { "Add" }}
+                                    </button>
+                                </li>
+                            }
+                        })}
+                    </ul>
+                </div>
+            }
+
             // Add/Edit Server Form
             if *show_add_server {
                 <div class="mb-4 p-4 bg-white dark:bg-gray-800 rounded border">
@@ -352,6 +512,47 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
                             </p>
                         </div>
 
+                        <div>
+                            <label class="flex items-center mb-1">
+                                <input
+                                    type="checkbox"
+                                    checked={new_server_tool_prefix.as_deref() != Some("")}
+                                    class="h-4 w-4 text-blue-600 focus:ring-blue-500 border-gray-300 rounded"
+                                    onchange={
+                                        let new_server_tool_prefix = new_server_tool_prefix.clone();
+                                        Callback::from(move |e: Event| {
+                                            let input: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+                                            new_server_tool_prefix.set(if input.checked() { None } else { Some(String::new()) });
+                                        })
+                                    }
+                                />
+                                <span class="ml-2 text-sm font-medium text-gray-700 dark:text-gray-300">{"Prefix Tool Names"}</span>
+                            </label>
+                            {if new_server_tool_prefix.as_deref() != Some("") {
+                                html! {
+                                    <input
+                                        type="text"
+                                        placeholder={format!("mcp_{}_", if new_server_name.is_empty() { "server" } else { new_server_name.as_str() })}
+                                        class="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 dark:bg-gray-700 dark:text-white font-mono text-sm"
+                                        value={new_server_tool_prefix.as_deref().unwrap_or_default().to_string()}
+                                        oninput={
+                                            let new_server_tool_prefix = new_server_tool_prefix.clone();
+                                            Callback::from(move |e: InputEvent| {
+                                                let input: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+                                                let prefix = input.value();
+                                                new_server_tool_prefix.set(if prefix.is_empty() { None } else { Some(prefix) });
+                                            })
+                                        }
+                                    />
+                                }
+                            } else {
+                                html! {}
+                            }}
+                            <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                                {"Some models struggle with long tool names. A tool name another server already uses gets a number added."}
+                            </p>
+                        </div>
+
                         <div class="flex justify-between pt-2">
                             <div class="flex space-x-2">
                                 <button
@@ -369,6 +570,7 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
                                         let new_server_url = new_server_url.clone();
                                         let new_server_auth_token = new_server_auth_token.clone();
                                         let new_server_headers_json = new_server_headers_json.clone();
+                                        let new_server_tool_prefix = new_server_tool_prefix.clone();
                                         Callback::from(move |_| {
                                             show_add_server.set(false);
                                             editing_server.set(None);
@@ -376,6 +578,7 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
                                             new_server_url.set(String::new());
                                             new_server_auth_token.set(String::new());
                                             new_server_headers_json.set(String::from("{}"));
+                                            new_server_tool_prefix.set(None);
                                         })
                                     }
                                 >
@@ -433,6 +636,18 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
                                                 </span>
                                             </div>
 
+                                            {match server_config.tool_prefix.as_deref() {
+                                                None => html! {},
+                                                Some(prefix) => html! {
+                                                    <div class="flex items-center">
+                                                        <span class="w-12 font-medium">{"Prefix:"}</span>
+                                                        <span class="font-mono bg-gray-100 dark:bg-gray-700 px-2 py-1 rounded text-xs">
+                                                            {if prefix.is_empty() { "none" } else { prefix }}
+                                                        </span>
+                                                    </div>
+                                                },
+                                            }}
+
                                             {if let Some(headers) = &server_config.headers {
                                                 if !headers.is_empty() {
                                                     html! {
@@ -516,6 +731,8 @@ pub fn mcp_settings_panel(props: &McpSettingsPanelProps) -> Html {
                     <li>{"• MCP tools will appear in your function tools list with 'mcp_' prefix"}</li>
                 </ul>
             </div>
+
+            {confirm.dialog()}
         </div>
     }
 }
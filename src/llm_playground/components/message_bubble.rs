@@ -1,11 +1,67 @@
-use crate::llm_playground::{Message, MessageRole};
+use super::{ChartView, SubAgentPanel};
+use crate::llm_playground::{
+    artifacts::{self, Artifact},
+    attachments,
+    bookmarks, candidates,
+    chart::ChartSpec,
+    guardrails::GuardrailAction,
+    hooks::{use_markdown, use_offload},
+    markdown::{Block, Inline},
+    native_tools::NativeToolCall,
+    offload::{OffloadJob, OffloadResult},
+    structured_output::validate_reply,
+    session_database::{self, QueryResult},
+    sub_agent::SubAgentRun,
+    web_search::{self, SearchResult},
+    Message, MessageRole,
+};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct MessageBubbleProps {
     pub message: Message,
+    /// Toggle this message in the diff selection (assistant messages only)
+    #[prop_or_default]
+    pub on_diff_select: Option<Callback<String>>,
+    #[prop_or_default]
+    pub diff_selected: bool,
+    /// Replaces the role label, e.g. with a dialogue participant's name
+    #[prop_or_default]
+    pub speaker: Option<String>,
+    /// Open a large code block or long reply in the artifact panel
+    #[prop_or_default]
+    pub on_open_artifact: Option<Callback<Artifact>>,
+    /// Show the provider request behind this message as a cURL or reqwest snippet
+    #[prop_or_default]
+    pub on_export_request: Option<Callback<String>>,
+    /// Ask the model to resume this reply; offered when it hit the token limit
+    #[prop_or_default]
+    pub on_continue: Option<Callback<()>>,
+    /// Pin or unpin this message as a bookmark
+    #[prop_or_default]
+    pub on_toggle_pin: Option<Callback<String>>,
+    #[prop_or_default]
+    pub pinned: bool,
+    /// Relative time, model, tokens and latency instead of the plain time
+    #[prop_or_default]
+    pub show_footer: bool,
+    /// Time the footer's relative time is measured from, refreshed by the chat
+    #[prop_or_default]
+    pub now: f64,
+    /// Show the reasoning section; the message's toggle flips this
+    #[prop_or_default]
+    pub show_reasoning: bool,
+    /// The send after this message failed and will be retried
+    #[prop_or_default]
+    pub pending_retry: bool,
+    /// Show another of the reply's sampled candidates, by message id and index
+    #[prop_or_default]
+    pub on_select_candidate: Option<Callback<(String, usize)>>,
 }
 
+/// Horizontal distance a swipe must cover to flip to the next candidate
+const SWIPE_MIN_PX: i32 = 60;
+
 #[function_component(MessageBubble)]
 pub fn message_bubble(props: &MessageBubbleProps) -> Html {
     let (icon_class, bg_class, label, icon) = match props.message.role {
@@ -15,6 +71,12 @@ pub fn message_bubble(props: &MessageBubbleProps) -> Html {
             "System",
             "fas fa-cog text-yellow-600 dark:text-yellow-300",
         ),
+        MessageRole::Developer => (
+            "bg-orange-100 dark:bg-orange-900/50",
+            "bg-orange-50 dark:bg-orange-900/30",
+            "Developer",
+            "fas fa-wrench text-orange-600 dark:text-orange-300",
+        ),
         MessageRole::User => (
             "bg-blue-100 dark:bg-blue-900/50",
             "bg-blue-50 dark:bg-blue-900/30",
@@ -34,20 +96,451 @@ pub fn message_bubble(props: &MessageBubbleProps) -> Html {
             "fas fa-code text-green-600 dark:text-green-300",
         ),
     };
+    let label = props.speaker.as_deref().unwrap_or(label);
+    // Long replies are parsed in the background worker while they stream in
+    let blocks = use_markdown(&props.message);
+    let blocks = blocks.as_deref().map(Vec::as_slice);
+
+    // Per-message flip of the default reasoning visibility
+    let reasoning_flipped = use_state(|| false);
+    let show_reasoning = props.show_reasoning != *reasoning_flipped;
+
+    // Swiping over the content flips between sampled candidates
+    let swipe_start = use_mut_ref(|| Option::<i32>::None);
+    let selected_candidate = candidates::selected(&props.message);
+    let swipe_to = props
+        .on_select_candidate
+        .clone()
+        .filter(|_| props.message.candidates.len() > 1 && props.message.consensus.is_none())
+        .map(|on_select| {
+            let id = props.message.id.clone();
+            let count = props.message.candidates.len();
+            let current = selected_candidate.unwrap_or_default();
+            Callback::from(move |forward: bool| {
+                let index = if forward { current + 1 } else { current.wrapping_sub(1) };
+                if index < count {
+                    on_select.emit((id.clone(), index));
+                }
+            })
+        });
+    let on_pointer_down = {
+        let swipe_start = swipe_start.clone();
+        Callback::from(move |e: PointerEvent| {
+            if e.pointer_type() == "touch" {
+                *swipe_start.borrow_mut() = Some(e.client_x());
+            }
+        })
+    };
+    let on_pointer_up = {
+        let swipe_to = swipe_to.clone();
+        Callback::from(move |e: PointerEvent| {
+            let (Some(start), Some(swipe_to)) = (swipe_start.borrow_mut().take(), &swipe_to) else {
+                return;
+            };
+            let distance = e.client_x() - start;
+            if distance.abs() >= SWIPE_MIN_PX {
+                swipe_to.emit(distance < 0);
+            }
+        })
+    };
 
     html! {
-        <div class="flex">
-            <div class={classes!("w-10", "h-10", "rounded-full", "flex", "items-center", "justify-center", "mr-3", icon_class)}>
+        <div
+            id={bookmarks::message_element_id(&props.message.id)}
+            class="flex rounded-lg focus:outline-none focus:ring-2 focus:ring-primary-500"
+            role="article"
+            aria-label={format!("{} message", label)}
+            tabindex="0"
+        >
+            <div class={classes!("w-10", "h-10", "rounded-full", "flex", "items-center", "justify-center", "mr-3", icon_class)} aria-hidden="true">
                 <i class={icon}></i>
             </div>
             <div class={classes!("flex-1", "rounded-lg", "p-4", bg_class)}>
-                <div class="font-medium mb-1 text-gray-900 dark:text-gray-100">{label}</div>
+                <div class="flex items-center justify-between mb-1">
+                    <div class="font-medium text-gray-900 dark:text-gray-100">
+                        {label}
+                        {if props.pending_retry {
+                            html! {
+                                <span class="ml-2 px-1.5 py-0.5 rounded text-xs font-normal bg-red-100 dark:bg-red-900/40 text-red-700 dark:text-red-300">
+                                    <i class="fas fa-redo mr-1" aria-hidden="true"></i>{"Not answered, retry pending"}
+                                </span>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                    </div>
+                    <div class="flex items-center space-x-1">
+                    {if props.message.reasoning.is_some() {
+                        let on_click = {
+                            let reasoning_flipped = reasoning_flipped.clone();
+                            Callback::from(move |_: MouseEvent| reasoning_flipped.set(!*reasoning_flipped))
+                        };
+                        html! {
+                            <button
+                                onclick={on_click}
+                                class={classes!(
+                                    "text-xs", "px-2", "py-0.5", "rounded", "hover:bg-gray-100", "dark:hover:bg-gray-700",
+                                    if show_reasoning { "text-primary-600 dark:text-primary-400" } else { "text-gray-500 dark:text-gray-400" }
+                                )}
+                                aria-pressed={show_reasoning.to_string()}
+                                title={if show_reasoning { "Hide the reasoning" } else { "Show the reasoning" }}
+                            >
+                                <i class="fas fa-brain" aria-hidden="true"></i>
+                            </button>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    {match &props.on_toggle_pin {
+                        Some(on_toggle_pin) => {
+                            let on_click = {
+                                let on_toggle_pin = on_toggle_pin.clone();
+                                let id = props.message.id.clone();
+                                Callback::from(move |_: MouseEvent| on_toggle_pin.emit(id.clone()))
+                            };
+                            html! {
+                                <button
+                                    onclick={on_click}
+                                    class={classes!(
+                                        "text-xs", "px-2", "py-0.5", "rounded", "hover:bg-gray-100", "dark:hover:bg-gray-700",
+                                        if props.pinned { "text-yellow-500" } else { "text-gray-500 dark:text-gray-400" }
+                                    )}
+                                    aria-pressed={props.pinned.to_string()}
+                                    aria-label={if props.pinned { "Unpin message" } else { "Pin message" }}
+                                    title={if props.pinned { "Remove from bookmarks" } else { "Pin to bookmarks" }}
+                                >
+                                    <i class={if props.pinned { "fas fa-bookmark" } else { "far fa-bookmark" }} aria-hidden="true"></i>
+                                </button>
+                            }
+                        }
+                        None => html! {},
+                    }}
+                    {match &props.on_export_request {
+                        Some(on_export_request) if matches!(props.message.role, MessageRole::User | MessageRole::Assistant) => {
+                            let on_click = {
+                                let on_export_request = on_export_request.clone();
+                                let id = props.message.id.clone();
+                                Callback::from(move |_: MouseEvent| on_export_request.emit(id.clone()))
+                            };
+                            html! {
+                                <button
+                                    onclick={on_click}
+                                    class="text-xs px-2 py-0.5 rounded text-gray-500 dark:text-gray-400 hover:bg-gray-100 dark:hover:bg-gray-700"
+                                    title="Export the request as cURL or Rust reqwest"
+                                >
+                                    <i class="fas fa-terminal mr-1" aria-hidden="true"></i>{"Request"}
+                                </button>
+                            }
+                        }
+                        _ => html! {},
+                    }}
+                    {match &props.on_diff_select {
+                        Some(on_diff_select) if props.message.role == MessageRole::Assistant
+                            && !props.message.content.trim().is_empty() =>
+                        {
+                            let on_click = {
+                                let on_diff_select = on_diff_select.clone();
+                                let id = props.message.id.clone();
+                                Callback::from(move |_: MouseEvent| on_diff_select.emit(id.clone()))
+                            };
+                            html! {
+                                <button
+                                    onclick={on_click}
+                                    class={classes!(
+                                        "text-xs", "px-2", "py-0.5", "rounded",
+                                        if props.diff_selected {
+                                            "bg-primary-600 text-white"
+                                        } else {
+                                            "text-gray-500 dark:text-gray-400 hover:bg-gray-100 dark:hover:bg-gray-700"
+                                        }
+                                    )}
+                                    aria-pressed={props.diff_selected.to_string()}
+                                    title="Select two responses to compare them word by word"
+                                >
+                                    <i class="fas fa-code-compare mr-1" aria-hidden="true"></i>{"Diff"}
+                                </button>
+                            }
+                        }
+                        _ => html! {},
+                    }}
+                    </div>
+                </div>
+
+                // Thinking returned apart from the answer, collapsed by default
+                {if let Some(reasoning) = props.message.reasoning.as_ref().filter(|_| show_reasoning) {
+                    html! {
+                        <details class="mb-2 rounded border border-gray-200 dark:border-gray-600 bg-gray-50 dark:bg-gray-700/50 text-xs">
+                            <summary class="px-3 py-1.5 cursor-pointer select-none text-gray-600 dark:text-gray-300">
+                                <i class="fas fa-brain mr-1" aria-hidden="true"></i>
+                                {format!("Thinking · {} words", reasoning.split_whitespace().count())}
+                            </summary>
+                            <div class="px-3 pb-2 whitespace-pre-wrap italic text-gray-600 dark:text-gray-400">{reasoning}</div>
+                        </details>
+                    }
+                } else {
+                    html! {}
+                }}
+
+                // Web searches and code runs the provider did itself
+                {for props.message.native_tool_calls.iter().map(render_native_tool_call)}
+
+                // Tabs over the candidates sampled for this reply
+                {if props.message.candidates.len() > 1 && props.message.consensus.is_none() {
+                    render_candidate_tabs(&props.message, selected_candidate, props.on_select_candidate.as_ref())
+                } else {
+                    html! {}
+                }}
 
                 // Regular message content
-                <div class="message-content text-sm text-gray-800 dark:text-gray-200">
-                    {render_content(&props.message.content)}
+                <div
+                    class={classes!(
+                        "message-content", "text-sm", "text-gray-800", "dark:text-gray-200",
+                        swipe_to.is_some().then_some("touch-pan-y")
+                    )}
+                    onpointerdown={swipe_to.is_some().then_some(on_pointer_down)}
+                    onpointerup={swipe_to.is_some().then_some(on_pointer_up)}
+                >
+                    {match &props.on_open_artifact {
+                        Some(on_open) if props.message.role == MessageRole::Assistant => {
+                            match artifacts::document_artifact(&props.message.id, &props.message.content) {
+                                Some(document) => render_artifact_preview(document, on_open),
+                                None => render_markdown(&props.message.content, blocks, Some((&props.message.id, on_open))),
+                            }
+                        }
+                        _ => render_markdown(&props.message.content, blocks, None),
+                    }}
                 </div>
 
+                // Samples a self-consistency reply was voted from
+                {if let Some(consensus) = &props.message.consensus {
+                    html! {
+                        <details class="mt-2 rounded border border-gray-200 dark:border-gray-600 text-xs">
+                            <summary class="px-3 py-1.5 cursor-pointer select-none text-gray-600 dark:text-gray-300">
+                                <i class="fas fa-vote-yea mr-1" aria-hidden="true"></i>
+                                {consensus.summary(props.message.candidates.len())}
+                            </summary>
+                            <ol class="px-3 pb-2 space-y-2">
+                                {for props.message.candidates.iter().enumerate().map(|(index, sample)| html! {
+                                    <li>
+                                        <div class="font-medium text-gray-500 dark:text-gray-400">{format!("Sample {}", index + 1)}</div>
+                                        <div class="whitespace-pre-wrap text-gray-700 dark:text-gray-300">{sample}</div>
+                                    </li>
+                                })}
+                            </ol>
+                        </details>
+                    }
+                } else {
+                    html! {}
+                }}
+
+                // PDFs sent with the message
+                {if props.message.attachments.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <div class="mt-2 flex flex-wrap gap-1">
+                            {for props.message.attachments.iter().map(|attachment| html! {
+                                <a
+                                    href={attachment.data_url()}
+                                    download={attachment.name.clone()}
+                                    class="inline-flex items-center gap-1 max-w-full px-2 py-1 rounded border border-current/20 text-xs opacity-90 hover:opacity-100"
+                                    title={format!("Download {}", attachment.name)}
+                                >
+                                    <i class="fas fa-file-pdf" aria-hidden="true"></i>
+                                    <span class="truncate">{&attachment.name}</span>
+                                    <span class="shrink-0 opacity-75">{attachments::format_size(attachment.size)}</span>
+                                </a>
+                            })}
+                        </div>
+                    }
+                }}
+
+                // Image sent back to be edited
+                {if let Some(edit) = &props.message.image_edit {
+                    html! {
+                        <div class="mt-2 flex items-center gap-2 text-xs opacity-90">
+                            <img src={edit.image.data_url()} alt="Image to edit" class="h-12 w-12 object-cover rounded" />
+                            <span>
+                                <i class="fas fa-pen mr-1" aria-hidden="true"></i>
+                                {if edit.mask.is_some() { "Editing this image, with a mask" } else { "Editing this image" }}
+                            </span>
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }}
+
+                // Images an image model replied with
+                {if props.message.images.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <div class="mt-2 flex flex-wrap gap-2">
+                            {for props.message.images.iter().map(|image| html! {
+                                <figure class="relative group">
+                                    <img
+                                        src={image.data_url()}
+                                        alt={image.prompt.clone()}
+                                        class="max-h-80 max-w-full rounded border border-gray-200 dark:border-gray-600"
+                                    />
+                                    <a
+                                        href={image.data_url()}
+                                        download={image.file_name()}
+                                        class="absolute top-1 right-1 px-2 py-1 rounded bg-black/50 text-white text-xs opacity-0 group-hover:opacity-100 focus:opacity-100"
+                                        title="Download image"
+                                        aria-label="Download image"
+                                    >
+                                        <i class="fas fa-download" aria-hidden="true"></i>
+                                    </a>
+                                </figure>
+                            })}
+                        </div>
+                    }
+                }}
+
+                // Spoken version of the reply
+                {if let Some(audio) = &props.message.audio {
+                    html! {
+                        <div class="mt-2 flex items-center gap-2">
+                            <audio controls={true} preload="metadata" src={audio.data_url()} class="h-8 max-w-full" aria-label="Spoken reply"></audio>
+                            <a
+                                href={audio.data_url()}
+                                download={format!("{}.{}", props.message.id, audio.format)}
+                                class="text-xs text-gray-500 dark:text-gray-400 hover:text-gray-700 dark:hover:text-gray-200"
+                                title={format!("Download ({} KB)", audio.size_bytes() / 1024)}
+                                aria-label="Download audio"
+                            >
+                                <i class="fas fa-download" aria-hidden="true"></i>
+                            </a>
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }}
+
+                // Sources a search-backed model cited as [1], [2], ...
+                {if props.message.citations.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <ol class="mt-2 space-y-0.5 text-xs" aria-label="Sources">
+                            {for props.message.citations.iter().enumerate().map(|(index, url)| {
+                                html! {
+                                    <li class="flex items-baseline gap-1 min-w-0">
+                                        <span class="shrink-0 font-mono text-gray-500 dark:text-gray-400">{format!("[{}]", index + 1)}</span>
+                                        {match citation_host(url) {
+                                            Some(host) => html! {
+                                                <a href={url.clone()} target="_blank" rel="noopener noreferrer" title={url.clone()}
+                                                    class="truncate text-primary-600 dark:text-primary-400 hover:underline">
+                                                    {host}
+                                                </a>
+                                            },
+                                            None => html! { <span class="truncate text-gray-600 dark:text-gray-300">{url}</span> },
+                                        }}
+                                    </li>
+                                }
+                            })}
+                        </ol>
+                    }
+                }}
+
+                // Reply cut off by the max tokens limit
+                {if props.message.hit_token_limit() {
+                    html! {
+                        <div class="mt-2 px-3 py-2 rounded border border-yellow-300 dark:border-yellow-700 bg-yellow-50 dark:bg-yellow-900/30 text-xs text-yellow-800 dark:text-yellow-300 flex items-center justify-between gap-2" role="status">
+                            <span>
+                                <i class="fas fa-scissors mr-1" aria-hidden="true"></i>
+                                {"The reply was cut off at the max tokens limit."}
+                            </span>
+                            {match &props.on_continue {
+                                Some(on_continue) => {
+                                    let on_continue = on_continue.clone();
+                                    html! {
+                                        <button
+                                            onclick={Callback::from(move |_: MouseEvent| on_continue.emit(()))}
+                                            class="shrink-0 px-2 py-0.5 rounded bg-yellow-600 text-white hover:bg-yellow-700"
+                                            title="Ask the model to resume where it stopped and add it to this reply"
+                                        >
+                                            <i class="fas fa-forward mr-1" aria-hidden="true"></i>{"Continue"}
+                                        </button>
+                                    }
+                                }
+                                None => html! {},
+                            }}
+                        </div>
+                    }
+                } else {
+                    html! {}
+                }}
+
+                // Check of a structured reply against the schema it was requested with
+                {if let Some(output) = &props.message.structured_output {
+                    match validate_reply(&props.message.content, &output.schema) {
+                        Ok(_) => html! {
+                            <div class="mt-2 inline-flex items-center px-2 py-0.5 rounded text-xs bg-green-100 dark:bg-green-900/40 text-green-800 dark:text-green-300">
+                                <i class="fas fa-check-circle mr-1" aria-hidden="true"></i>
+                                {format!("Matches schema \"{}\"", output.name)}
+                            </div>
+                        },
+                        Err(error) => html! {
+                            <div class="mt-2 px-3 py-2 rounded border border-red-300 dark:border-red-700 bg-red-50 dark:bg-red-900/30 text-xs text-red-800 dark:text-red-300" role="alert">
+                                <i class="fas fa-exclamation-triangle mr-1" aria-hidden="true"></i>
+                                {format!("Does not match schema \"{}\": {}", output.name, error)}
+                            </div>
+                        },
+                    }
+                } else {
+                    html! {}
+                }}
+
+                // Moderation flags
+                {match &props.message.moderation {
+                    Some(result) if result.flagged => html! {
+                        <div class="mt-2 px-3 py-2 rounded border border-red-300 dark:border-red-700 bg-red-50 dark:bg-red-900/30 text-xs text-red-800 dark:text-red-300" role="status">
+                            <i class="fas fa-flag mr-1" aria-hidden="true"></i>
+                            <span class="font-medium">
+                                {if result.blocked { "Flagged by moderation, not sent" } else { "Flagged by moderation" }}
+                            </span>
+                            {if result.categories.is_empty() {
+                                html! {}
+                            } else {
+                                html! {
+                                    <span>
+                                        {": "}
+                                        {result.categories.iter().map(|(category, score)| {
+                                            format!("{} ({:.0}%)", category, score * 100.0)
+                                        }).collect::<Vec<_>>().join(", ")}
+                                    </span>
+                                }
+                            }}
+                        </div>
+                    },
+                    _ => html! {},
+                }}
+
+                // Guardrail rules the reply broke
+                {if props.message.guardrail_violations.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <ul class="mt-2 space-y-1" aria-label="Guardrail violations">
+                            {for props.message.guardrail_violations.iter().map(|violation| {
+                                let (color, icon) = match violation.action {
+                                    GuardrailAction::Redact => ("bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-300", "fas fa-eraser"),
+                                    _ => ("bg-yellow-50 dark:bg-yellow-900/30 text-yellow-800 dark:text-yellow-300", "fas fa-shield-alt"),
+                                };
+                                html! {
+                                    <li class={classes!("px-2", "py-1", "rounded", "text-xs", color)}>
+                                        <i class={classes!(icon, "mr-1")} aria-hidden="true"></i>
+                                        <span class="font-medium">{&violation.rule_name}</span>
+                                        {format!(": {}", violation.detail)}
+                                    </li>
+                                }
+                            })}
+                        </ul>
+                    }
+                }}
+
                 // Function call display
                 {if let Some(function_call) = &props.message.function_call {
                     // Handle function calls as an array
@@ -203,22 +696,47 @@ pub fn message_bubble(props: &MessageBubbleProps) -> Html {
                                 html! {}
                             }}
 
+                            {if let Some(run) = SubAgentRun::from_function_response(function_response) {
+                                html! { <SubAgentPanel {run} /> }
+                            } else {
+                                html! {}
+                            }}
+
+                            {match web_search::results_from_function_response(function_response) {
+                                Some(results) if !results.is_empty() => render_search_results(&results),
+                                _ => html! {},
+                            }}
+
+                            {if let Some(spec) = ChartSpec::from_function_response(function_response) {
+                                html! { <ChartView {spec} /> }
+                            } else {
+                                html! {}
+                            }}
+
+                            {match session_database::results_from_function_response(function_response) {
+                                Some(results) => html! { for results.iter().filter(|result| !result.columns.is_empty()).map(render_query_result) },
+                                None => html! {},
+                            }}
+
                             {if let Some(response) = function_response.get("response") {
                                 html! {
                                     <div>
                                         <div class="text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">{"Response Data:"}</div>
                                         <div class="bg-white dark:bg-gray-800 rounded-md p-3 border border-gray-200 dark:border-gray-600">
                                             <pre class="text-xs font-mono text-gray-800 dark:text-gray-200 overflow-x-auto">
-                                                <code>{serde_json::to_string_pretty(response).unwrap_or_else(|_| "Invalid response".to_string())}</code>
+                                                <JsonCode value={response.clone()} />
                                             </pre>
                                         </div>
                                     </div>
                                 }
+                            } else if function_response.get("sub_agent").is_some() {
+                                // Still running; the panel above shows progress
+                                html! {}
                             } else {
                                 html! {
                                     <div class="bg-white dark:bg-gray-800 rounded-md p-3 border border-gray-200 dark:border-gray-600">
                                         <pre class="text-xs font-mono text-gray-800 dark:text-gray-200 overflow-x-auto">
-                                            <code>{serde_json::to_string_pretty(function_response).unwrap_or_else(|_| "Invalid JSON".to_string())}</code>
+                                            <JsonCode value={function_response.clone()} />
                                         </pre>
                                     </div>
                                 }
@@ -229,178 +747,319 @@ pub fn message_bubble(props: &MessageBubbleProps) -> Html {
                     html! {}
                 }}
 
-                // Timestamp
-                <div class="text-xs text-gray-600 dark:text-gray-300 mt-2">
-                    {format_timestamp(props.message.timestamp)}
-                </div>
+                {if props.show_footer {
+                    render_footer(&props.message, props.now)
+                } else {
+                    html! {
+                        <div class="text-xs text-gray-600 dark:text-gray-300 mt-2">
+                            {format_timestamp(props.message.timestamp)}
+                        </div>
+                    }
+                }}
             </div>
         </div>
     }
 }
 
-fn render_content(content: &str) -> Html {
-    // Enhanced markdown rendering for function calls and formatting
-    parse_markdown(content)
+#[derive(Properties, PartialEq)]
+struct JsonCodeProps {
+    value: serde_json::Value,
+}
+
+/// Pretty-printed JSON; large values are formatted in the background worker
+#[function_component(JsonCode)]
+fn json_code(props: &JsonCodeProps) -> Html {
+    let pretty = use_offload(OffloadJob::PrettyJson(props.value.to_string()));
+    let text = match pretty.as_deref() {
+        Some(OffloadResult::Json(text)) => text.clone(),
+        _ => props.value.to_string(),
+    };
+    html! { <code>{text}</code> }
 }
 
-fn parse_markdown(content: &str) -> Html {
-    let mut lines = Vec::new();
-    let mut in_code_block = false;
-    let mut code_block_content = Vec::new();
-    let mut code_block_language = String::new();
-
-    for line in content.split('\n') {
-        if line.trim().starts_with("```") {
-            if in_code_block {
-                // End of code block
-                let code_content = code_block_content.join("\n");
-                lines.push(html! {
-                    <pre class="bg-gray-100 dark:bg-gray-900 text-gray-800 dark:text-gray-100 p-4 rounded-lg my-3 overflow-x-auto border-l-4 border-blue-500 dark:border-blue-400">
-                        <div class="text-xs text-gray-600 dark:text-gray-400 mb-2">{if code_block_language.is_empty() { "Code" } else { &code_block_language }}</div>
-                        <code class="text-sm">{code_content}</code>
-                    </pre>
+/// Collapsed view of an artifact with a button to open it in the panel
+/// "1 2 3" tabs, one per sampled candidate; read-only without a callback
+fn render_candidate_tabs(
+    message: &Message,
+    selected: Option<usize>,
+    on_select: Option<&Callback<(String, usize)>>,
+) -> Html {
+    html! {
+        <div class="flex items-center gap-1 mb-2 text-xs" role="tablist" aria-label="Candidate replies">
+            <span class="mr-1 text-gray-500 dark:text-gray-400">{"Candidates"}</span>
+            {for (0..message.candidates.len()).map(|index| {
+                let active = selected == Some(index);
+                let onclick = on_select.map(|on_select| {
+                    let on_select = on_select.clone();
+                    let id = message.id.clone();
+                    Callback::from(move |_: MouseEvent| on_select.emit((id.clone(), index)))
                 });
-                code_block_content.clear();
-                code_block_language.clear();
-                in_code_block = false;
-            } else {
-                // Start of code block
-                code_block_language = line.trim().trim_start_matches("```").to_string();
-                in_code_block = true;
-            }
-        } else if in_code_block {
-            code_block_content.push(line.to_string());
-        } else {
-            lines.push(render_line(line));
-        }
+                html! {
+                    <button
+                        {onclick}
+                        disabled={on_select.is_none()}
+                        role="tab"
+                        aria-selected={active.to_string()}
+                        title={format!("Show candidate {} of {}", index + 1, message.candidates.len())}
+                        class={classes!(
+                            "w-6", "h-6", "rounded", "disabled:cursor-default",
+                            if active {
+                                "bg-primary-600 text-white"
+                            } else {
+                                "bg-gray-100 dark:bg-gray-700 text-gray-600 dark:text-gray-300 hover:bg-gray-200 dark:hover:bg-gray-600"
+                            }
+                        )}
+                    >
+                        {index + 1}
+                    </button>
+                }
+            })}
+        </div>
     }
+}
 
+fn render_artifact_preview(artifact: Artifact, on_open: &Callback<Artifact>) -> Html {
+    let (preview, hidden_lines) = artifacts::preview(&artifact.content);
+    let title = artifact.title.clone();
+    let onclick = {
+        let on_open = on_open.clone();
+        Callback::from(move |_: MouseEvent| on_open.emit(artifact.clone()))
+    };
     html! {
-        <div class="space-y-2">
-            {for lines}
+        <div class="my-3 rounded-lg border border-gray-200 dark:border-gray-600 bg-gray-100 dark:bg-gray-900 overflow-hidden">
+            <div class="flex items-center justify-between px-3 py-2 border-b border-gray-200 dark:border-gray-700 text-xs text-gray-600 dark:text-gray-400">
+                <span><i class="fas fa-file-code mr-1" aria-hidden="true"></i>{title}</span>
+                <button
+                    {onclick}
+                    class="px-2 py-0.5 rounded text-primary-600 dark:text-primary-400 hover:bg-primary-50 dark:hover:bg-primary-900/20"
+                >
+                    <i class="fas fa-external-link-alt mr-1" aria-hidden="true"></i>{"Open in panel"}
+                </button>
+            </div>
+            <pre class="px-4 py-3 overflow-x-auto text-sm text-gray-800 dark:text-gray-100"><code>{preview}</code></pre>
+            {if hidden_lines > 0 {
+                html! {
+                    <div class="px-4 pb-2 text-xs text-gray-500 dark:text-gray-400">
+                        {format!("… {} more lines", hidden_lines)}
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
         </div>
     }
 }
 
-fn render_line(line: &str) -> Html {
-    if line.trim().is_empty() {
-        return html! { <div class="h-2"></div> };
+/// `blocks` are the parsed `content`, None while the worker parses it.
+/// `artifact_source` is the message id and open callback; large code blocks
+/// are collapsed into artifacts when it is set
+fn render_markdown(
+    content: &str,
+    blocks: Option<&[Block]>,
+    artifact_source: Option<(&str, &Callback<Artifact>)>,
+) -> Html {
+    let Some(blocks) = blocks else {
+        return html! {
+            <p class="text-gray-800 dark:text-gray-200 leading-relaxed whitespace-pre-wrap">{content}</p>
+        };
+    };
+    html! {
+        <div class="space-y-2">
+            {for blocks.iter().map(|block| render_block(block, artifact_source))}
+        </div>
     }
+}
 
-    if line.starts_with("🔧 ") {
-        // Function call header
-        return html! {
+fn render_block(block: &Block, artifact_source: Option<(&str, &Callback<Artifact>)>) -> Html {
+    match block {
+        Block::Blank => html! { <div class="h-2"></div> },
+        Block::ToolHeader(inline) => html! {
             <div class="flex items-center space-x-2 p-3 bg-orange-50 dark:bg-orange-900/20 rounded-lg border-l-4 border-orange-500">
                 <span class="text-orange-600 dark:text-orange-400">{"🔧"}</span>
-                <span class="font-semibold text-orange-800 dark:text-orange-300">{render_inline_formatting(&line[4..])}</span>
+                <span class="font-semibold text-orange-800 dark:text-orange-300">{render_inline(inline)}</span>
             </div>
-        };
-    }
-
-    if line.starts_with("**") && line.ends_with("**:") {
-        // Section headers like **Arguments**: or **Response**:
-        let content = line.trim_start_matches("**").trim_end_matches("**:");
-        return html! {
+        },
+        Block::Section(title) => html! {
             <div class="font-semibold text-gray-800 dark:text-gray-200 mt-4 mb-2 pb-1 border-b border-gray-200 dark:border-gray-600">
-                {content}
+                {title}
             </div>
-        };
-    }
-
-    if line.starts_with("# ") {
-        return html! {
-            <h1 class="text-xl font-bold my-3 text-gray-900 dark:text-gray-100">{line.trim_start_matches("# ")}</h1>
-        };
-    }
-
-    if line.starts_with("## ") {
-        return html! {
-            <h2 class="text-lg font-semibold my-2 text-gray-800 dark:text-gray-200">{line.trim_start_matches("## ")}</h2>
-        };
-    }
-
-    if line.starts_with("- ") {
-        return html! {
+        },
+        Block::Heading1(text) => html! {
+            <h1 class="text-xl font-bold my-3 text-gray-900 dark:text-gray-100">{text}</h1>
+        },
+        Block::Heading2(text) => html! {
+            <h2 class="text-lg font-semibold my-2 text-gray-800 dark:text-gray-200">{text}</h2>
+        },
+        Block::Bullet(inline) => html! {
             <div class="flex items-start space-x-2 ml-4">
                 <span class="text-gray-500 dark:text-gray-400 mt-1">{"•"}</span>
-                <span class="text-gray-800 dark:text-gray-200">{render_inline_formatting(&line[2..])}</span>
+                <span class="text-gray-800 dark:text-gray-200">{render_inline(inline)}</span>
             </div>
-        };
+        },
+        Block::Paragraph(inline) => html! {
+            <p class="text-gray-800 dark:text-gray-200 leading-relaxed">{render_inline(inline)}</p>
+        },
+        Block::Code {
+            language,
+            content,
+            index,
+        } => {
+            if let Some((message_id, on_open)) = artifact_source {
+                if artifacts::is_large_code_block(content) {
+                    let artifact = Artifact::code_block(message_id, *index, language, content);
+                    return render_artifact_preview(artifact, on_open);
+                }
+            }
+            html! {
+                <pre class="bg-gray-100 dark:bg-gray-900 text-gray-800 dark:text-gray-100 p-4 rounded-lg my-3 overflow-x-auto border-l-4 border-blue-500 dark:border-blue-400">
+                    <div class="text-xs text-gray-600 dark:text-gray-400 mb-2">{if language.is_empty() { "Code" } else { language }}</div>
+                    <code class="text-sm">{content}</code>
+                </pre>
+            }
+        }
     }
+}
 
+fn render_inline(inline: &[Inline]) -> Html {
     html! {
-        <p class="text-gray-800 dark:text-gray-200 leading-relaxed">{render_inline_formatting(line)}</p>
+        {for inline.iter().map(|part| match part {
+            Inline::Text(text) => html! { <span>{text}</span> },
+            Inline::Code(code) => html! {
+                <code class="bg-gray-200 dark:bg-gray-700 text-gray-800 dark:text-gray-200 px-2 py-1 rounded text-sm font-mono">
+                    {code}
+                </code>
+            },
+            Inline::Bold(text) => html! {
+                <strong class="font-semibold text-gray-900 dark:text-gray-100">{text}</strong>
+            },
+        })}
     }
 }
 
-fn render_inline_formatting(text: &str) -> Html {
-    let mut result = Vec::new();
-    let mut chars = text.chars().peekable();
-    let mut current_text = String::new();
-
-    while let Some(ch) = chars.next() {
-        if ch == '`' {
-            // Handle inline code
-            if !current_text.is_empty() {
-                result.push(html! { <span>{current_text.clone()}</span> });
-                current_text.clear();
-            }
-
-            let mut code_content = String::new();
-            while let Some(next_ch) = chars.next() {
-                if next_ch == '`' {
-                    break;
+/// A WebSearch call's results as a numbered list of linked sources
+fn render_search_results(results: &[SearchResult]) -> Html {
+    html! {
+        <ol class="mb-3 space-y-2 text-sm" aria-label="Search results">
+            {for results.iter().enumerate().map(|(index, result)| {
+                let host = result
+                    .url
+                    .split("://")
+                    .nth(1)
+                    .and_then(|rest| rest.split('/').next())
+                    .unwrap_or(&result.url);
+                let title = if result.title.is_empty() { host } else { result.title.as_str() };
+                html! {
+                    <li class="flex items-baseline gap-2 min-w-0">
+                        <span class="shrink-0 font-mono text-xs text-gray-500 dark:text-gray-400">{format!("[{}]", index + 1)}</span>
+                        <div class="min-w-0">
+                            {if result.url.starts_with("https://") || result.url.starts_with("http://") {
+                                html! {
+                                    <a href={result.url.clone()} target="_blank" rel="noopener noreferrer" title={result.url.clone()}
+                                        class="font-medium text-primary-600 dark:text-primary-400 hover:underline">
+                                        {title}
+                                    </a>
+                                }
+                            } else {
+                                html! { <span class="font-medium text-gray-800 dark:text-gray-200">{title}</span> }
+                            }}
+                            <span class="ml-2 text-xs text-gray-500 dark:text-gray-400">{host}</span>
+                            {if result.snippet.is_empty() {
+                                html! {}
+                            } else {
+                                html! { <p class="text-xs text-gray-600 dark:text-gray-300 line-clamp-2">{&result.snippet}</p> }
+                            }}
+                        </div>
+                    </li>
                 }
-                code_content.push(next_ch);
-            }
-
-            result.push(html! {
-                <code class="bg-gray-200 dark:bg-gray-700 text-gray-800 dark:text-gray-200 px-2 py-1 rounded text-sm font-mono">
-                    {code_content}
-                </code>
-            });
-        } else if ch == '*' && chars.peek() == Some(&'*') {
-            // Handle bold text
-            chars.next(); // consume second *
-
-            if !current_text.is_empty() {
-                result.push(html! { <span>{current_text.clone()}</span> });
-                current_text.clear();
-            }
+            })}
+        </ol>
+    }
+}
 
-            let mut bold_content = String::new();
-            let mut found_end = false;
+/// A run_sql statement's rows as a table
+fn render_query_result(result: &QueryResult) -> Html {
+    let cell = |value: &serde_json::Value| match value {
+        serde_json::Value::Null => html! { <span class="text-gray-400 dark:text-gray-500">{"NULL"}</span> },
+        serde_json::Value::String(text) => html! { {text} },
+        value => html! { <span class="font-mono">{value.to_string()}</span> },
+    };
 
-            while let Some(next_ch) = chars.next() {
-                if next_ch == '*' && chars.peek() == Some(&'*') {
-                    chars.next(); // consume second *
-                    found_end = true;
-                    break;
+    html! {
+        <div class="mb-3">
+            <div class="max-h-80 overflow-auto rounded-md border border-gray-200 dark:border-gray-600">
+                <table class="min-w-full text-xs text-left text-gray-800 dark:text-gray-200">
+                    <thead class="sticky top-0 bg-gray-100 dark:bg-gray-700">
+                        <tr>
+                            {for result.columns.iter().map(|column| html! {
+                                <th scope="col" class="px-2 py-1 font-semibold whitespace-nowrap">{column}</th>
+                            })}
+                        </tr>
+                    </thead>
+                    <tbody class="bg-white dark:bg-gray-800 divide-y divide-gray-100 dark:divide-gray-700">
+                        {for result.rows.iter().map(|row| html! {
+                            <tr>
+                                {for row.iter().map(|value| html! {
+                                    <td class="px-2 py-1 align-top">{cell(value)}</td>
+                                })}
+                            </tr>
+                        })}
+                    </tbody>
+                </table>
+            </div>
+            {if result.total_rows > result.rows.len() {
+                html! {
+                    <div class="mt-1 text-xs text-gray-500 dark:text-gray-400">
+                        {format!("First {} of {} rows", result.rows.len(), result.total_rows)}
+                    </div>
                 }
-                bold_content.push(next_ch);
-            }
-
-            if found_end {
-                result.push(html! {
-                    <strong class="font-semibold text-gray-900 dark:text-gray-100">{bold_content}</strong>
-                });
             } else {
-                current_text.push_str("**");
-                current_text.push_str(&bold_content);
-            }
-        } else {
-            current_text.push(ch);
+                html! {}
+            }}
+        </div>
+    }
+}
+
+/// Time since the message with the full date on hover, then what produced it
+fn render_footer(message: &Message, now: f64) -> Html {
+    let absolute = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(message.timestamp))
+        .to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED);
+    let mut details = Vec::new();
+    if let Some(metrics) = &message.metrics {
+        if let Some(model) = &metrics.model {
+            details.push(model.clone());
+        }
+        match (metrics.input_tokens, metrics.output_tokens) {
+            (Some(input), Some(output)) => details.push(format!("{} → {} tokens", input, output)),
+            (None, Some(output)) => details.push(format!("{} tokens", output)),
+            _ => {}
         }
+        details.push(format!("{:.2}s", metrics.latency_ms / 1000.0));
     }
 
-    if !current_text.is_empty() {
-        result.push(html! { <span>{current_text}</span> });
+    html! {
+        <div class="flex flex-wrap items-center gap-x-2 text-xs text-gray-600 dark:text-gray-300 mt-2">
+            <time title={String::from(absolute)}>
+                {format_relative_time(now - message.timestamp)}
+            </time>
+            {for details.into_iter().map(|detail| html! {
+                <>
+                    <span aria-hidden="true">{"·"}</span>
+                    <span>{detail}</span>
+                </>
+            })}
+        </div>
     }
+}
 
-    if result.is_empty() {
-        html! { <span>{text}</span> }
-    } else {
-        html! { <>{for result}</> }
+/// "just now", "5m ago", "3h ago", "2d ago"
+fn format_relative_time(elapsed_ms: f64) -> String {
+    let seconds = (elapsed_ms / 1000.0).max(0.0) as u64;
+    match seconds {
+        0..=9 => "just now".to_string(),
+        10..=59 => format!("{}s ago", seconds),
+        60..=3_599 => format!("{}m ago", seconds / 60),
+        3_600..=86_399 => format!("{}h ago", seconds / 3_600),
+        _ => format!("{}d ago", seconds / 86_400),
     }
 }
 
@@ -410,3 +1069,78 @@ fn format_timestamp(timestamp: f64) -> String {
     let minutes = date.get_minutes();
     format!("{:02}:{:02}", hours, minutes)
 }
+
+fn render_native_tool_call(call: &NativeToolCall) -> Html {
+    let label = html! {
+        <>
+            {call.summary()}
+            {if call.failed() {
+                html! { <span class="ml-1 text-red-600 dark:text-red-400">{"(failed)"}</span> }
+            } else {
+                html! {}
+            }}
+        </>
+    };
+    match call {
+        NativeToolCall::WebSearch { .. } => html! {
+            <div class="mb-2 px-3 py-1.5 rounded border border-gray-200 dark:border-gray-600 bg-gray-50 dark:bg-gray-700/50 text-xs text-gray-600 dark:text-gray-300 truncate">
+                <i class="fas fa-globe mr-1" aria-hidden="true"></i>
+                {label}
+            </div>
+        },
+        NativeToolCall::CodeInterpreter { code, logs, images, .. } => html! {
+            <details class="mb-2 rounded border border-gray-200 dark:border-gray-600 bg-gray-50 dark:bg-gray-700/50 text-xs">
+                <summary class="px-3 py-1.5 cursor-pointer select-none text-gray-600 dark:text-gray-300">
+                    <i class="fas fa-terminal mr-1" aria-hidden="true"></i>
+                    {label}
+                </summary>
+                <div class="px-3 pb-2 space-y-2">
+                    <pre class="p-2 max-h-64 overflow-auto custom-scrollbar rounded bg-gray-100 dark:bg-gray-900 text-gray-800 dark:text-gray-100">{code}</pre>
+                    {if logs.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <pre class="p-2 max-h-48 overflow-auto custom-scrollbar rounded bg-gray-100 dark:bg-gray-900 text-gray-600 dark:text-gray-300" aria-label="Output">{logs}</pre>
+                        }
+                    }}
+                    {for images.iter().map(|url| html! {
+                        <img src={url.clone()} alt="Image made by the code interpreter" class="max-w-full rounded" />
+                    })}
+                </div>
+            </details>
+        },
+    }
+}
+
+/// Host a cited web page is listed under; None for sources that aren't web
+/// links, which are shown as plain text rather than linked
+fn citation_host(url: &str) -> Option<&str> {
+    let rest = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://"))?;
+    rest.split('/').next().filter(|host| !host.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_times_round_down_to_the_largest_unit() {
+        assert_eq!(format_relative_time(0.0), "just now");
+        assert_eq!(format_relative_time(9_999.0), "just now");
+        assert_eq!(format_relative_time(45_000.0), "45s ago");
+        assert_eq!(format_relative_time(119_000.0), "1m ago");
+        assert_eq!(format_relative_time(3.0 * 3_600_000.0 + 59_000.0), "3h ago");
+        assert_eq!(format_relative_time(2.5 * 86_400_000.0), "2d ago");
+        // A message stamped after the chat's last tick
+        assert_eq!(format_relative_time(-30_000.0), "just now");
+    }
+
+    #[test]
+    fn citations_are_listed_by_host_and_only_web_links_are_linked() {
+        assert_eq!(citation_host("https://docs.rs/yew/latest/yew/"), Some("docs.rs"));
+        assert_eq!(citation_host("http://example.com"), Some("example.com"));
+        assert_eq!(citation_host("javascript:alert(1)"), None);
+        assert_eq!(citation_host("ftp://files.example.com/a"), None);
+        assert_eq!(citation_host("https://"), None);
+    }
+}
@@ -1,27 +1,95 @@
 // Component modules
+pub mod ab_experiment_view;
+pub mod artifact_panel;
+pub mod bookmarks_view;
+pub mod chart_view;
 pub mod chat_header;
 pub mod chat_room;
 pub mod chatroom;
+pub mod comparison_view;
+pub mod confirm_dialog;
+pub mod context_usage_bar;
+pub mod conversation_viewer;
+pub mod diagnostics_view;
+pub mod dialogue_setup;
+pub mod diff_viewer;
+pub mod eval_view;
+pub mod fine_tune_view;
+pub mod gemini_cache_list;
 pub mod flexible_settings_panel;
 pub mod function_call_handler;
 pub mod function_tool_editor;
+pub mod image_strip;
 pub mod input_bar;
+pub mod knowledge_view;
+pub mod local_server_scan;
+pub mod logit_bias_editor;
 pub mod mcp_settings_panel;
 pub mod message_bubble;
 pub mod model_selector;
 pub mod notification;
+pub mod persona_editor;
+pub mod prompt_suggestion;
+pub mod quick_model_switcher;
+pub mod replay_view;
+pub mod request_export_dialog;
+pub mod resize_handle;
+pub mod session_database_view;
 pub mod settings_panel;
 pub mod sidebar;
+pub mod snapshot_menu;
+pub mod starter_prompts;
+pub mod storage_recovery_dialog;
+pub mod structured_output_editor;
+pub mod sub_agent_panel;
+pub mod todo_panel;
+pub mod tool_approval_dialog;
+pub mod tool_popover;
+pub mod trace_view;
 pub mod visual_function_tool_editor;
 
+pub use ab_experiment_view::AbExperimentView;
+pub use artifact_panel::ArtifactPanel;
+pub use bookmarks_view::BookmarksView;
+pub use chart_view::ChartView;
 pub use chat_header::ChatHeader;
 pub use chat_room::ChatRoom;
 pub use chatroom::Chatroom;
-pub use flexible_settings_panel::FlexibleSettingsPanel;
+pub use comparison_view::ComparisonView;
+pub use confirm_dialog::{ConfirmAction, ConfirmDialog, ConfirmRequest};
+pub use context_usage_bar::ContextUsageBar;
+pub use conversation_viewer::ConversationViewer;
+pub use diagnostics_view::DiagnosticsView;
+pub use dialogue_setup::DialogueSetup;
+pub use diff_viewer::DiffViewer;
+pub use eval_view::EvalView;
+pub use fine_tune_view::FineTuneView;
+pub use gemini_cache_list::GeminiCacheList;
+pub use flexible_settings_panel::{FlexibleSettingsPanel, SettingsPanelMode};
 pub use function_tool_editor::FunctionToolEditor;
+pub use image_strip::ImageStrip;
 pub use input_bar::InputBar;
+pub use knowledge_view::KnowledgeView;
+pub use local_server_scan::LocalServerScan;
+pub use logit_bias_editor::LogitBiasEditor;
 pub use mcp_settings_panel::McpSettingsPanel;
 pub use model_selector::ModelSelector;
+pub use persona_editor::PersonaEditor;
+pub use prompt_suggestion::PromptSuggestion;
+pub use quick_model_switcher::QuickModelSwitcher;
+pub use replay_view::ReplayView;
+pub use request_export_dialog::RequestExportDialog;
+pub use resize_handle::ResizeHandle;
+pub use session_database_view::SessionDatabaseView;
 pub use settings_panel::SettingsPanel;
 pub use sidebar::Sidebar;
+pub use snapshot_menu::SnapshotMenu;
+pub use starter_prompts::StarterPrompts;
+pub use storage_recovery_dialog::StorageRecoveryDialog;
+pub use structured_output_editor::StructuredOutputEditor;
+pub use sub_agent_panel::SubAgentPanel;
+pub use todo_panel::TodoPanel;
+pub use tool_approval_dialog::ToolApprovalDialog;
+pub use tool_popover::ToolPopover;
+pub use trace_view::TraceView;
 pub use visual_function_tool_editor::VisualFunctionToolEditor;
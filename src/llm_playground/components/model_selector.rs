@@ -1,3 +1,5 @@
+use crate::llm_playground::hooks::use_focus_trap;
+use crate::llm_playground::model_shortcuts::{ModelRef, ModelShortcuts};
 use crate::llm_playground::provider_config::FlexibleApiConfig;
 use web_sys::HtmlInputElement;
 use yew::prelude::*;
@@ -6,6 +8,7 @@ use yew::prelude::*;
 pub struct ModelSelectorProps {
     pub config: FlexibleApiConfig,
     pub on_select: Callback<(String, String)>, // (provider_name, model_name)
+    pub on_persona: Callback<String>,          // persona name
     pub on_cancel: Callback<()>,
     pub show: bool,
 }
@@ -72,6 +75,28 @@ pub fn model_selector(props: &ModelSelectorProps) -> Html {
         })
     };
 
+    // Starred and recently used models, reloaded each time the dialog opens
+    let shortcuts = use_state(ModelShortcuts::load);
+    {
+        let shortcuts = shortcuts.clone();
+        use_effect_with(props.show, move |show| {
+            if *show {
+                shortcuts.set(ModelShortcuts::load());
+            }
+            || ()
+        });
+    }
+
+    let toggle_favorite = {
+        let shortcuts = shortcuts.clone();
+        Callback::from(move |model: ModelRef| {
+            let mut updated = (*shortcuts).clone();
+            updated.toggle_favorite(model);
+            updated.save();
+            shortcuts.set(updated);
+        })
+    };
+
     let on_cancel = {
         let callback = props.on_cancel.clone();
         Callback::from(move |_| {
@@ -79,6 +104,9 @@ pub fn model_selector(props: &ModelSelectorProps) -> Html {
         })
     };
 
+    let dialog_ref = use_node_ref();
+    use_focus_trap(dialog_ref.clone(), props.show, props.on_cancel.clone());
+
     if !props.show {
         return html! {};
     }
@@ -88,24 +116,119 @@ pub fn model_selector(props: &ModelSelectorProps) -> Html {
     let available_models = current_provider
         .map(|p| p.models.clone())
         .unwrap_or_default();
+    let quick_list = shortcuts.quick_list(&props.config);
+    let selection = ModelRef::new(&selected_provider, &selected_model);
+    let selection_is_favorite = shortcuts.is_favorite(&selection);
 
     html! {
         <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
-            <div class="bg-white dark:bg-gray-800 rounded-lg shadow-xl max-w-md w-full mx-4">
+            <div
+                ref={dialog_ref}
+                role="dialog"
+                aria-modal="true"
+                aria-labelledby="model-selector-title"
+                class="bg-white dark:bg-gray-800 rounded-lg shadow-xl max-w-md w-full mx-4"
+            >
                 <div class="p-6">
                     <div class="flex items-center justify-between mb-6">
-                        <h2 class="text-xl font-semibold text-gray-900 dark:text-white">
+                        <h2 id="model-selector-title" class="text-xl font-semibold text-gray-900 dark:text-white">
                             {"Select Model for New Session"}
                         </h2>
                         <button
                             onclick={on_cancel.clone()}
                             class="text-gray-400 hover:text-gray-600 dark:hover:text-gray-300"
+                            aria-label="Close"
                         >
-                            <i class="fas fa-times text-lg"></i>
+                            <i class="fas fa-times text-lg" aria-hidden="true"></i>
                         </button>
                     </div>
 
                     <div class="space-y-4">
+                        // Personas start a session with their own prompt, model and tools
+                        {if props.config.personas.is_empty() {
+                            html! {}
+                        } else {
+                            html! {
+                                <div>
+                                    <div class="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                                        {"Personas"}
+                                    </div>
+                                    <div class="flex flex-wrap gap-2">
+                                        {for props.config.personas.iter().map(|persona| {
+                                            let on_pick = {
+                                                let on_persona = props.on_persona.clone();
+                                                let name = persona.name.clone();
+                                                Callback::from(move |_: MouseEvent| on_persona.emit(name.clone()))
+                                            };
+                                            let model = persona
+                                                .model()
+                                                .map(|(provider, model)| format!("{} - {}", provider, model))
+                                                .unwrap_or_else(|| "current model".to_string());
+                                            html! {
+                                                <button
+                                                    key={persona.name.clone()}
+                                                    onclick={on_pick}
+                                                    class="px-3 py-2 text-sm rounded-md border border-gray-200 dark:border-gray-600 text-gray-900 dark:text-gray-100 hover:bg-gray-50 dark:hover:bg-gray-700"
+                                                    title={format!("Start a session as {} ({})", persona.name, model)}
+                                                >
+                                                    {persona.label()}
+                                                </button>
+                                            }
+                                        })}
+                                    </div>
+                                </div>
+                            }
+                        }}
+
+                        // Favorites and recently used models start a session in one click
+                        {if quick_list.is_empty() {
+                            html! {}
+                        } else {
+                            html! {
+                                <div>
+                                    <div class="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                                        {"Favorites & Recent"}
+                                    </div>
+                                    <ul class="space-y-1">
+                                        {for quick_list.iter().map(|model| {
+                                            let favorite = shortcuts.is_favorite(model);
+                                            let on_pick = {
+                                                let on_select = props.on_select.clone();
+                                                let model = model.clone();
+                                                Callback::from(move |_: MouseEvent| {
+                                                    on_select.emit((model.provider.clone(), model.model.clone()))
+                                                })
+                                            };
+                                            let on_star = {
+                                                let toggle_favorite = toggle_favorite.clone();
+                                                let model = model.clone();
+                                                Callback::from(move |_: MouseEvent| toggle_favorite.emit(model.clone()))
+                                            };
+                                            html! {
+                                                <li key={model.label()} class="flex items-center gap-2">
+                                                    <button
+                                                        onclick={on_pick}
+                                                        class="flex-1 min-w-0 text-left px-3 py-2 text-sm rounded-md border border-gray-200 dark:border-gray-600 text-gray-900 dark:text-gray-100 hover:bg-gray-50 dark:hover:bg-gray-700 truncate"
+                                                        title="Start a session with this model"
+                                                    >
+                                                        {model.label()}
+                                                    </button>
+                                                    <button
+                                                        onclick={on_star}
+                                                        class={classes!("p-2", if favorite { "text-yellow-500" } else { "text-gray-400 hover:text-yellow-500" })}
+                                                        aria-pressed={favorite.to_string()}
+                                                        aria-label={format!("{} {}", if favorite { "Unstar" } else { "Star" }, model.label())}
+                                                    >
+                                                        <i class={if favorite { "fas fa-star" } else { "far fa-star" }} aria-hidden="true"></i>
+                                                    </button>
+                                                </li>
+                                            }
+                                        })}
+                                    </ul>
+                                </div>
+                            }
+                        }}
+
                         // Provider Selection
                         <div>
                             <label class="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
@@ -140,7 +263,7 @@ pub fn model_selector(props: &ModelSelectorProps) -> Html {
                                         <div>{"Type: "}{provider.transformer.r#use.join(", ")}</div>
                                         <div>
                                             {"Status: "}
-                                            {if provider.api_key.is_empty() && provider.name != "ollama" {
+                                            {if provider.api_key.is_empty() && provider.needs_api_key() {
                                                 html! { <span class="text-red-500">{"⚠️ No API key configured"}</span> }
                                             } else {
                                                 html! { <span class="text-green-500">{"✅ Configured"}</span> }
@@ -155,9 +278,24 @@ pub fn model_selector(props: &ModelSelectorProps) -> Html {
 
                         // Model Selection
                         <div>
-                            <label class="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
-                                {"Model"}
-                            </label>
+                            <div class="flex items-center justify-between mb-2">
+                                <label class="block text-sm font-medium text-gray-700 dark:text-gray-300">
+                                    {"Model"}
+                                </label>
+                                <button
+                                    onclick={
+                                        let toggle_favorite = toggle_favorite.clone();
+                                        let selection = selection.clone();
+                                        Callback::from(move |_: MouseEvent| toggle_favorite.emit(selection.clone()))
+                                    }
+                                    disabled={selected_model.is_empty()}
+                                    class={classes!("text-sm", if selection_is_favorite { "text-yellow-500" } else { "text-gray-400 hover:text-yellow-500" })}
+                                    aria-pressed={selection_is_favorite.to_string()}
+                                    title={if selection_is_favorite { "Remove from favorites" } else { "Add to favorites" }}
+                                >
+                                    <i class={if selection_is_favorite { "fas fa-star" } else { "far fa-star" }} aria-hidden="true"></i>
+                                </button>
+                            </div>
                             <select
                                 value={(*selected_model).clone()}
                                 onchange={on_model_change}
@@ -204,7 +342,7 @@ pub fn model_selector(props: &ModelSelectorProps) -> Html {
 
                         // Warning if API key missing
                         {if let Some(provider) = current_provider {
-                            if provider.api_key.is_empty() && provider.name != "ollama" {
+                            if provider.api_key.is_empty() && provider.needs_api_key() {
                                 html! {
                                     <div class="p-3 bg-yellow-50 dark:bg-yellow-900/20 border border-yellow-200 dark:border-yellow-800 rounded-md">
                                         <div class="flex items-center text-yellow-800 dark:text-yellow-200">
@@ -36,7 +36,7 @@ pub fn notification_container(props: &NotificationProps) -> Html {
     }
 
     html! {
-        <div class="fixed top-4 right-4 z-50 space-y-2">
+        <div class="fixed top-4 right-4 z-50 space-y-2" aria-live="polite">
             {for notifications.values().map(|notification| {
                 let notification_id = notification.id.clone();
                 let on_dismiss_clone = on_dismiss.clone();
@@ -53,19 +53,24 @@ pub fn notification_container(props: &NotificationProps) -> Html {
                 };
 
                 html! {
-                    <div key={notification.id.clone()} class={classes!(
-                        "flex", "items-center", "p-4", "rounded-lg", "shadow-lg", "text-white",
-                        "min-w-80", "max-w-96", "transition-all", "duration-300", bg_class
-                    )}>
-                        <i class={classes!("mr-3", "text-lg", icon_class, icon)}></i>
+                    <div
+                        key={notification.id.clone()}
+                        role={if notification.notification_type == NotificationType::Error { "alert" } else { "status" }}
+                        class={classes!(
+                            "flex", "items-center", "p-4", "rounded-lg", "shadow-lg", "text-white",
+                            "min-w-80", "max-w-96", "transition-all", "duration-300", bg_class
+                        )}
+                    >
+                        <i class={classes!("mr-3", "text-lg", icon_class, icon)} aria-hidden="true"></i>
                         <div class="flex-1 text-sm">
                             {&notification.message}
                         </div>
                         <button
                             onclick={dismiss_callback}
                             class="ml-3 text-white hover:text-gray-200 transition-colors"
+                            aria-label="Dismiss notification"
                         >
-                            <i class="fas fa-times"></i>
+                            <i class="fas fa-times" aria-hidden="true"></i>
                         </button>
                     </div>
                 }
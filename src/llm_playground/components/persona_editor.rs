@@ -0,0 +1,266 @@
+use crate::llm_playground::hooks::use_focus_trap;
+use crate::llm_playground::personas::Persona;
+use crate::llm_playground::provider_config::FlexibleApiConfig;
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct PersonaEditorProps {
+    pub persona: Option<Persona>,
+    /// Offers the configured models and tools
+    pub config: FlexibleApiConfig,
+    pub on_save: Callback<Persona>,
+    pub on_cancel: Callback<()>,
+}
+
+#[function_component(PersonaEditor)]
+pub fn persona_editor(props: &PersonaEditorProps) -> Html {
+    let persona = use_state(|| {
+        props.persona.clone().unwrap_or_else(|| Persona {
+            name: String::new(),
+            avatar: "🤖".to_string(),
+            system_prompt: props.config.system_prompt.clone(),
+            model: None,
+            enabled_tools: props
+                .config
+                .function_tools
+                .iter()
+                .filter(|tool| tool.enabled)
+                .map(|tool| tool.name.clone())
+                .collect(),
+            temperature: None,
+        })
+    });
+    let dialog_ref = use_node_ref();
+
+    use_focus_trap(dialog_ref.clone(), true, props.on_cancel.clone());
+
+    // Apply a change to the persona being edited
+    let update = {
+        let persona = persona.clone();
+        move |change: fn(&mut Persona, String)| {
+            let persona = persona.clone();
+            move |value: String| {
+                let mut updated = (*persona).clone();
+                change(&mut updated, value);
+                persona.set(updated);
+            }
+        }
+    };
+    let on_name = update(|p, v| p.name = v);
+    let on_avatar = update(|p, v| p.avatar = v.trim().to_string());
+    let on_prompt = update(|p, v| p.system_prompt = v);
+    let on_model = update(|p, v| p.model = (!v.is_empty()).then_some(v));
+    let on_temperature = update(|p, v| p.temperature = v.parse().ok());
+    let on_override_temperature = update(|p, v| p.temperature = (v == "true").then_some(0.7));
+
+    let error = persona.validate(&props.config).err();
+    let temperature = persona.temperature;
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div
+                ref={dialog_ref}
+                role="dialog"
+                aria-modal="true"
+                aria-labelledby="persona-editor-title"
+                class="bg-white dark:bg-gray-800 rounded-lg w-full max-w-2xl max-h-[90vh] overflow-hidden flex flex-col"
+            >
+                // Header
+                <div class="flex justify-between items-center p-6 border-b border-gray-200 dark:border-gray-700">
+                    <h3 id="persona-editor-title" class="text-xl font-semibold text-gray-900 dark:text-gray-100">
+                        {if props.persona.is_some() { "Edit Persona" } else { "Create Persona" }}
+                    </h3>
+                    <button
+                        onclick={
+                            let on_cancel = props.on_cancel.clone();
+                            Callback::from(move |_| on_cancel.emit(()))
+                        }
+                        class="text-gray-500 hover:text-gray-700 dark:text-gray-400 dark:hover:text-gray-200"
+                        aria-label="Close editor"
+                    >
+                        <i class="fas fa-times text-xl" aria-hidden="true"></i>
+                    </button>
+                </div>
+
+                // Content - scrollable
+                <div class="flex-1 overflow-y-auto p-6 space-y-4">
+                    <div class="flex gap-3">
+                        <div class="w-20">
+                            <label class="block text-sm font-medium mb-2 text-gray-700 dark:text-gray-300">{"Avatar"}</label>
+                            <input
+                                type="text"
+                                value={persona.avatar.clone()}
+                                oninput={Callback::from(move |e: InputEvent| {
+                                    let input: HtmlInputElement = e.target_unchecked_into();
+                                    on_avatar(input.value());
+                                })}
+                                maxlength="8"
+                                class="w-full p-3 text-center border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-gray-100"
+                            />
+                        </div>
+                        <div class="flex-1">
+                            <label class="block text-sm font-medium mb-2 text-gray-700 dark:text-gray-300">{"Name"}</label>
+                            <input
+                                type="text"
+                                value={persona.name.clone()}
+                                oninput={Callback::from(move |e: InputEvent| {
+                                    let input: HtmlInputElement = e.target_unchecked_into();
+                                    on_name(input.value());
+                                })}
+                                placeholder="e.g., Code Reviewer"
+                                class="w-full p-3 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-gray-100"
+                            />
+                        </div>
+                    </div>
+
+                    <div>
+                        <label class="block text-sm font-medium mb-2 text-gray-700 dark:text-gray-300">{"System Prompt"}</label>
+                        <textarea
+                            value={persona.system_prompt.clone()}
+                            oninput={Callback::from(move |e: InputEvent| {
+                                let input: HtmlTextAreaElement = e.target_unchecked_into();
+                                on_prompt(input.value());
+                            })}
+                            rows="5"
+                            class="w-full p-3 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-sm text-gray-900 dark:text-gray-100"
+                        />
+                    </div>
+
+                    <div>
+                        <label class="block text-sm font-medium mb-2 text-gray-700 dark:text-gray-300">{"Default Model"}</label>
+                        <select
+                            onchange={Callback::from(move |e: Event| {
+                                let input: HtmlInputElement = e.target_unchecked_into();
+                                on_model(input.value());
+                            })}
+                            class="w-full p-3 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-gray-100"
+                        >
+                            <option value="" selected={persona.model.is_none()}>{"Current model"}</option>
+                            {for props.config.providers.iter().flat_map(|provider| {
+                                provider.models.iter().map(|model| {
+                                    let value = format!("{},{}", provider.name, model);
+                                    let selected = persona.model.as_deref() == Some(value.as_str());
+                                    html! {
+                                        <option value={value.clone()} selected={selected}>
+                                            {format!("{} - {}", provider.name, model)}
+                                        </option>
+                                    }
+                                })
+                            })}
+                        </select>
+                    </div>
+
+                    <div>
+                        <label class="flex items-center text-sm font-medium mb-2 text-gray-700 dark:text-gray-300">
+                            <input
+                                type="checkbox"
+                                checked={temperature.is_some()}
+                                onchange={Callback::from(move |e: Event| {
+                                    let input: HtmlInputElement = e.target_unchecked_into();
+                                    on_override_temperature(input.checked().to_string());
+                                })}
+                                class="mr-2"
+                            />
+                            {"Temperature"}
+                            {if let Some(temperature) = temperature {
+                                html! { <span class="ml-2 text-gray-500 dark:text-gray-400">{format!("{:.1}", temperature)}</span> }
+                            } else {
+                                html! { <span class="ml-2 text-gray-500 dark:text-gray-400">{"(global setting)"}</span> }
+                            }}
+                        </label>
+                        {if let Some(temperature) = temperature {
+                            html! {
+                                <input
+                                    type="range"
+                                    min="0"
+                                    max="2"
+                                    step="0.1"
+                                    value={temperature.to_string()}
+                                    oninput={Callback::from(move |e: InputEvent| {
+                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                        on_temperature(input.value());
+                                    })}
+                                    aria-label="Temperature"
+                                    class="w-full"
+                                />
+                            }
+                        } else {
+                            html! {}
+                        }}
+                    </div>
+
+                    <div>
+                        <div class="block text-sm font-medium mb-2 text-gray-700 dark:text-gray-300">{"Tools"}</div>
+                        {if props.config.function_tools.is_empty() {
+                            html! { <p class="text-xs text-gray-500 dark:text-gray-400">{"No function tools configured"}</p> }
+                        } else {
+                            html! {
+                                <div class="grid grid-cols-2 gap-1">
+                                    {for props.config.function_tools.iter().map(|tool| {
+                                        let enabled = persona.enabled_tools.contains(&tool.name);
+                                        let on_toggle = {
+                                            let persona = persona.clone();
+                                            let name = tool.name.clone();
+                                            Callback::from(move |_: Event| {
+                                                let mut updated = (*persona).clone();
+                                                if let Some(index) = updated.enabled_tools.iter().position(|t| *t == name) {
+                                                    updated.enabled_tools.remove(index);
+                                                } else {
+                                                    updated.enabled_tools.push(name.clone());
+                                                }
+                                                persona.set(updated);
+                                            })
+                                        };
+                                        html! {
+                                            <label key={tool.name.clone()} class="flex items-center text-sm text-gray-700 dark:text-gray-300 truncate">
+                                                <input type="checkbox" checked={enabled} onchange={on_toggle} class="mr-2" />
+                                                {&tool.name}
+                                            </label>
+                                        }
+                                    })}
+                                </div>
+                            }
+                        }}
+                    </div>
+                </div>
+
+                // Footer
+                <div class="flex items-center justify-end space-x-2 p-6 border-t border-gray-200 dark:border-gray-700">
+                    {if let Some(error) = &error {
+                        html! { <span class="flex-1 text-sm text-red-600 dark:text-red-400">{error}</span> }
+                    } else {
+                        html! {}
+                    }}
+                    <button
+                        onclick={
+                            let on_cancel = props.on_cancel.clone();
+                            Callback::from(move |_| on_cancel.emit(()))
+                        }
+                        class="px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-md hover:bg-gray-50 dark:hover:bg-gray-700"
+                    >
+                        {"Cancel"}
+                    </button>
+                    <button
+                        onclick={
+                            let persona = persona.clone();
+                            let on_save = props.on_save.clone();
+                            Callback::from(move |_| {
+                                let mut saved = (*persona).clone();
+                                saved.name = saved.name.trim().to_string();
+                                on_save.emit(saved);
+                            })
+                        }
+                        disabled={error.is_some()}
+                        class={classes!(
+                            "px-4", "py-2", "rounded-md", "text-white",
+                            if error.is_none() { "bg-primary-600 hover:bg-primary-700" } else { "bg-gray-400 cursor-not-allowed" }
+                        )}
+                    >
+                        {"Save"}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
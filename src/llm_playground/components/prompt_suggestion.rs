@@ -0,0 +1,69 @@
+// Optimized prompt shown as a word diff against the draft, above the input bar
+use super::diff_viewer::render_diff_ops;
+use crate::llm_playground::word_diff::{diff_stats, diff_words};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct PromptSuggestionProps {
+    pub original: String,
+    pub improved: String,
+    /// Replace the draft with the improved prompt
+    pub on_accept: Callback<()>,
+    pub on_reject: Callback<()>,
+}
+
+#[function_component(PromptSuggestion)]
+pub fn prompt_suggestion(props: &PromptSuggestionProps) -> Html {
+    let ops = use_memo(
+        (props.original.clone(), props.improved.clone()),
+        |(original, improved)| diff_words(original, improved),
+    );
+    let (removed, added) = diff_stats(&ops);
+
+    let on_accept = {
+        let callback = props.on_accept.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+    let on_reject = {
+        let callback = props.on_reject.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    html! {
+        <section
+            class="mx-4 mt-2 border border-primary-200 dark:border-primary-800 rounded-lg bg-primary-50 dark:bg-gray-800"
+            aria-labelledby="prompt-suggestion-title"
+        >
+            <div class="flex items-center justify-between px-3 py-2 border-b border-primary-200 dark:border-primary-800">
+                <div class="text-sm">
+                    <span id="prompt-suggestion-title" class="font-medium text-gray-900 dark:text-gray-100">
+                        <i class="fas fa-magic mr-1 text-primary-600 dark:text-primary-400" aria-hidden="true"></i>
+                        {"Improved prompt"}
+                    </span>
+                    <span class="ml-2 text-xs text-gray-600 dark:text-gray-300">
+                        <span class="text-red-600 dark:text-red-400">{format!("-{} words", removed)}</span>
+                        {" / "}
+                        <span class="text-green-600 dark:text-green-400">{format!("+{} words", added)}</span>
+                    </span>
+                </div>
+                <div class="flex space-x-2">
+                    <button
+                        onclick={on_reject}
+                        class="px-3 py-1 text-xs rounded-md border border-gray-300 dark:border-gray-600 text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700"
+                    >
+                        {"Reject"}
+                    </button>
+                    <button
+                        onclick={on_accept}
+                        class="px-3 py-1 text-xs rounded-md bg-primary-600 hover:bg-primary-700 text-white"
+                    >
+                        {"Accept"}
+                    </button>
+                </div>
+            </div>
+            <div class="max-h-48 overflow-y-auto custom-scrollbar px-3 py-2 text-sm leading-relaxed whitespace-pre-wrap text-gray-800 dark:text-gray-200">
+                {render_diff_ops(&ops)}
+            </div>
+        </section>
+    }
+}
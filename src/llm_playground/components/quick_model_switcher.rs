@@ -0,0 +1,175 @@
+// Ctrl+M palette for changing the current model without the full selector:
+// favorites and recent models first, then every configured model, filtered
+// by what is typed
+use crate::llm_playground::hooks::use_focus_trap;
+use crate::llm_playground::model_shortcuts::{ModelRef, ModelShortcuts};
+use crate::llm_playground::provider_config::FlexibleApiConfig;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct QuickModelSwitcherProps {
+    pub config: FlexibleApiConfig,
+    pub show: bool,
+    pub on_select: Callback<(String, String)>, // (provider_name, model_name)
+    pub on_close: Callback<()>,
+}
+
+/// Models to offer for `query` with their icon: the quick list, then the rest
+/// of the settings
+fn candidates(config: &FlexibleApiConfig, query: &str) -> Vec<(ModelRef, &'static str)> {
+    let shortcuts = ModelShortcuts::load();
+    let quick = shortcuts.quick_list(config);
+    let rest = config
+        .providers
+        .iter()
+        .flat_map(|p| p.models.iter().map(|m| ModelRef::new(&p.name, m)))
+        .filter(|m| !quick.contains(m));
+    let query = query.trim().to_lowercase();
+    quick
+        .iter()
+        .cloned()
+        .map(|m| {
+            let icon = if shortcuts.is_favorite(&m) {
+                "fa-star text-yellow-500"
+            } else {
+                "fa-clock-rotate-left text-gray-400"
+            };
+            (m, icon)
+        })
+        .chain(rest.map(|m| (m, "fa-microchip text-gray-400")))
+        .filter(|(m, _)| query.is_empty() || m.label().to_lowercase().contains(&query))
+        .collect()
+}
+
+#[function_component(QuickModelSwitcher)]
+pub fn quick_model_switcher(props: &QuickModelSwitcherProps) -> Html {
+    let query = use_state(String::new);
+    let highlighted = use_state(|| 0usize);
+    let dialog_ref = use_node_ref();
+    use_focus_trap(dialog_ref.clone(), props.show, props.on_close.clone());
+
+    {
+        let query = query.clone();
+        let highlighted = highlighted.clone();
+        use_effect_with(props.show, move |_| {
+            query.set(String::new());
+            highlighted.set(0);
+            || ()
+        });
+    }
+
+    if !props.show {
+        return html! {};
+    }
+
+    let models = candidates(&props.config, &query);
+    let current = {
+        let (provider, model) = props.config.get_current_provider_and_model();
+        ModelRef::new(&provider, &model)
+    };
+
+    let on_input = {
+        let query = query.clone();
+        let highlighted = highlighted.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            query.set(input.value());
+            highlighted.set(0);
+        })
+    };
+
+    let on_keydown = {
+        let highlighted = highlighted.clone();
+        let models = models.clone();
+        let on_select = props.on_select.clone();
+        Callback::from(move |e: KeyboardEvent| match e.key().as_str() {
+            "ArrowDown" => {
+                e.prevent_default();
+                highlighted.set((*highlighted + 1).min(models.len().saturating_sub(1)));
+            }
+            "ArrowUp" => {
+                e.prevent_default();
+                highlighted.set(highlighted.saturating_sub(1));
+            }
+            "Enter" => {
+                e.prevent_default();
+                if let Some((model, _)) = models.get(*highlighted) {
+                    on_select.emit((model.provider.clone(), model.model.clone()));
+                }
+            }
+            _ => {}
+        })
+    };
+
+    let on_backdrop = {
+        let on_close = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| on_close.emit(()))
+    };
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-start justify-center pt-24 z-50" onclick={on_backdrop}>
+            <div
+                ref={dialog_ref}
+                role="dialog"
+                aria-modal="true"
+                aria-label="Switch model"
+                class="bg-white dark:bg-gray-800 rounded-lg shadow-xl max-w-md w-full mx-4 overflow-hidden"
+                onclick={Callback::from(|e: MouseEvent| e.stop_propagation())}
+            >
+                <input
+                    type="text"
+                    value={(*query).clone()}
+                    oninput={on_input}
+                    onkeydown={on_keydown}
+                    placeholder="Switch model…"
+                    aria-label="Model"
+                    aria-controls="quick-model-list"
+                    aria-activedescendant={format!("quick-model-{}", *highlighted)}
+                    class="w-full p-3 text-sm border-b border-gray-200 dark:border-gray-600 bg-transparent text-gray-900 dark:text-gray-100 focus:outline-none"
+                />
+                <ul id="quick-model-list" role="listbox" class="max-h-80 overflow-y-auto custom-scrollbar py-1">
+                    {for models.iter().enumerate().map(|(index, (model, icon))| {
+                        let on_click = {
+                            let on_select = props.on_select.clone();
+                            let model = model.clone();
+                            Callback::from(move |_: MouseEvent| {
+                                on_select.emit((model.provider.clone(), model.model.clone()))
+                            })
+                        };
+                        html! {
+                            <li
+                                key={model.label()}
+                                id={format!("quick-model-{}", index)}
+                                role="option"
+                                aria-selected={(index == *highlighted).to_string()}
+                                onclick={on_click}
+                                class={classes!(
+                                    "flex", "items-center", "gap-2", "px-3", "py-2", "text-sm", "cursor-pointer",
+                                    "text-gray-900", "dark:text-gray-100",
+                                    if index == *highlighted { "bg-primary-100 dark:bg-primary-900/30" } else { "hover:bg-gray-100 dark:hover:bg-gray-700" }
+                                )}
+                            >
+                                <i class={classes!("fas", "text-xs", "w-3", *icon)} aria-hidden="true"></i>
+                                <span class="flex-1 min-w-0 truncate">{model.label()}</span>
+                                {if *model == current {
+                                    html! { <span class="text-xs text-gray-500 dark:text-gray-400">{"current"}</span> }
+                                } else {
+                                    html! {}
+                                }}
+                            </li>
+                        }
+                    })}
+                    {if models.is_empty() {
+                        html! { <li class="px-3 py-2 text-sm text-gray-500 dark:text-gray-400">{"No models match"}</li> }
+                    } else {
+                        html! {}
+                    }}
+                </ul>
+                <div class="px-3 py-2 text-xs text-gray-500 dark:text-gray-400 border-t border-gray-200 dark:border-gray-600">
+                    {"↑↓ to move · Enter to switch · Esc to close"}
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,448 @@
+// Replay of a recorded session: the steps appear one by one with their
+// recorded timing, and the recorded requests can be sent again to another
+// model to compare its replies with the originals
+use crate::llm_playground::{
+    app_state::use_app_state,
+    session_recording::{
+        self, RecordedRequest, RecordedResponse, RecordedStep, RecordedToolCall, SessionRecording,
+    },
+    word_diff::{diff_stats, diff_words},
+    Message, MessageRole,
+};
+use gloo_timers::callback::Timeout;
+use std::collections::HashMap;
+use std::rc::Rc;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+const SPEEDS: [f64; 4] = [0.5, 1.0, 2.0, 4.0];
+
+type RerunResult = (Result<RecordedResponse, String>, f64);
+
+/// Replies of the comparison model, by step index
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Rerun {
+    run_id: u64,
+    model: String,
+    pending: usize,
+    results: HashMap<usize, RerunResult>,
+}
+
+enum RerunAction {
+    Start {
+        run_id: u64,
+        model: String,
+        requests: usize,
+    },
+    Result {
+        run_id: u64,
+        index: usize,
+        result: RerunResult,
+    },
+}
+
+impl Reducible for Rerun {
+    type Action = RerunAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        match action {
+            RerunAction::Start {
+                run_id,
+                model,
+                requests,
+            } => Rc::new(Self {
+                run_id,
+                model,
+                pending: requests,
+                results: HashMap::new(),
+            }),
+            RerunAction::Result {
+                run_id,
+                index,
+                result,
+            } => {
+                // Ignore late results from a previous run
+                if run_id != self.run_id {
+                    return self;
+                }
+                let mut next = (*self).clone();
+                next.results.insert(index, result);
+                next.pending = next.pending.saturating_sub(1);
+                Rc::new(next)
+            }
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ReplayViewProps {
+    pub session_id: String,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(ReplayView)]
+pub fn replay_view(props: &ReplayViewProps) -> Html {
+    let app_state = use_app_state();
+    let combos = app_state.config.get_all_provider_models();
+    let recording = use_state(SessionRecording::default);
+    let position = use_state(|| 0usize);
+    let playing = use_state(|| false);
+    let speed = use_state(|| 1.0f64);
+    let model = use_state(|| app_state.config.get_current_provider_and_model());
+    let rerun = use_reducer(Rerun::default);
+
+    {
+        let recording = recording.clone();
+        let position = position.clone();
+        let playing = playing.clone();
+        use_effect_with(props.session_id.clone(), move |session_id| {
+            recording.set(session_recording::load_recording(session_id).unwrap_or_default());
+            position.set(0);
+            playing.set(false);
+            || ()
+        });
+    }
+
+    // Show the next step once its recorded gap has passed
+    {
+        let recording = recording.clone();
+        let position = position.clone();
+        let playing = playing.clone();
+        use_effect_with(
+            (*playing, *position, recording.steps.len(), *speed),
+            move |(is_playing, shown, total, speed)| {
+                let mut timeout = None;
+                if *is_playing && shown < total {
+                    let next = *shown + 1;
+                    timeout = Some(Timeout::new(
+                        recording.replay_delay_ms(*shown, *speed),
+                        move || position.set(next),
+                    ));
+                } else if *is_playing {
+                    playing.set(false);
+                }
+                move || drop(timeout)
+            },
+        );
+    }
+
+    let total = recording.steps.len();
+    let step_to = |target: fn(usize, usize) -> usize| {
+        let position = position.clone();
+        let playing = playing.clone();
+        Callback::from(move |_: MouseEvent| {
+            playing.set(false);
+            position.set(target(*position, total));
+        })
+    };
+    let on_restart = step_to(|_, _| 0);
+    let on_previous = step_to(|shown, _| shown.saturating_sub(1));
+    let on_next = step_to(|shown, total| (shown + 1).min(total));
+    let on_show_all = step_to(|_, total| total);
+    let on_play = {
+        let playing = playing.clone();
+        let position = position.clone();
+        Callback::from(move |_: MouseEvent| {
+            if !*playing && *position >= total {
+                position.set(0);
+            }
+            playing.set(!*playing);
+        })
+    };
+    let on_speed_change = {
+        let speed = speed.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Ok(value) = input.value().parse::<f64>() {
+                speed.set(value);
+            }
+        })
+    };
+    let on_model_change = {
+        let model = model.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            if let Some((provider, name)) = input.value().split_once(',') {
+                model.set((provider.to_string(), name.to_string()));
+            }
+        })
+    };
+
+    let is_rerunning = rerun.pending > 0;
+    let on_rerun = {
+        let rerun = rerun.clone();
+        let recording = recording.clone();
+        let model = model.clone();
+        let config = app_state.config.clone();
+        let llm_client = app_state.llm_client.clone();
+        Callback::from(move |_: MouseEvent| {
+            let requests: Vec<(usize, RecordedRequest)> = recording
+                .steps
+                .iter()
+                .enumerate()
+                .filter_map(|(index, step)| match step {
+                    RecordedStep::Request(request) => Some((index, request.clone())),
+                    _ => None,
+                })
+                .collect();
+            let (provider, name) = (*model).clone();
+            let run_id = js_sys::Date::now() as u64;
+            rerun.dispatch(RerunAction::Start {
+                run_id,
+                model: format!("{} / {}", provider, name),
+                requests: requests.len(),
+            });
+
+            let rerun = rerun.clone();
+            let config = config.clone();
+            let llm_client = llm_client.clone();
+            // One request at a time, in recorded order
+            wasm_bindgen_futures::spawn_local(async move {
+                for (index, request) in requests {
+                    let result =
+                        session_recording::rerun(&request, &provider, &name, &llm_client, &config)
+                            .await;
+                    rerun.dispatch(RerunAction::Result {
+                        run_id,
+                        index,
+                        result,
+                    });
+                }
+            });
+        })
+    };
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    let select_class = "p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100 text-sm";
+    let control_class = "p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300 disabled:opacity-50";
+
+    html! {
+        <div class="flex-1 flex flex-col min-w-0 bg-white dark:bg-gray-800">
+            <header class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
+                <div>
+                    <h2 class="font-semibold text-gray-900 dark:text-gray-100">{"Session Replay"}</h2>
+                    <div class="text-sm text-gray-600 dark:text-gray-300">
+                        {"Step through the recorded requests and tool calls, or send the requests to another model."}
+                    </div>
+                </div>
+                <button
+                    onclick={on_close}
+                    class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                    aria-label="Close replay"
+                    title="Back to chat"
+                >
+                    <i class="fas fa-times" aria-hidden="true"></i>
+                </button>
+            </header>
+
+            <div class="p-3 border-b border-gray-200 dark:border-gray-600 flex flex-wrap items-center gap-2">
+                <button onclick={on_restart} class={control_class} aria-label="Restart" title="Restart" disabled={total == 0}>
+                    <i class="fas fa-backward-fast" aria-hidden="true"></i>
+                </button>
+                <button onclick={on_previous} class={control_class} aria-label="Previous step" title="Previous step" disabled={*position == 0}>
+                    <i class="fas fa-backward-step" aria-hidden="true"></i>
+                </button>
+                <button
+                    onclick={on_play}
+                    class={control_class}
+                    aria-label={if *playing { "Pause" } else { "Play" }}
+                    title={if *playing { "Pause" } else { "Play" }}
+                    disabled={total == 0}
+                >
+                    <i class={classes!("fas", if *playing { "fa-pause" } else { "fa-play" })} aria-hidden="true"></i>
+                </button>
+                <button onclick={on_next} class={control_class} aria-label="Next step" title="Next step" disabled={*position >= total}>
+                    <i class="fas fa-forward-step" aria-hidden="true"></i>
+                </button>
+                <button onclick={on_show_all} class={control_class} aria-label="Show all steps" title="Show all steps" disabled={*position >= total}>
+                    <i class="fas fa-forward-fast" aria-hidden="true"></i>
+                </button>
+                <span class="text-sm text-gray-600 dark:text-gray-300" role="status">
+                    {format!("Step {} of {}", *position, total)}
+                </span>
+                <select aria-label="Replay speed" onchange={on_speed_change} class={select_class}>
+                    {for SPEEDS.iter().map(|value| html! {
+                        <option value={value.to_string()} selected={*speed == *value}>{format!("{}×", value)}</option>
+                    })}
+                </select>
+
+                <div class="ml-auto flex items-center gap-2">
+                    <select aria-label="Comparison model" onchange={on_model_change} class={select_class} disabled={is_rerunning}>
+                        {for combos.iter().map(|(provider, name)| html! {
+                            <option value={format!("{},{}", provider, name)} selected={*model == (provider.clone(), name.clone())}>
+                                {format!("{} / {}", provider, name)}
+                            </option>
+                        })}
+                    </select>
+                    <button
+                        onclick={on_rerun}
+                        disabled={is_rerunning || total == 0}
+                        class="text-sm px-3 py-2 bg-primary-600 text-white rounded-md hover:bg-primary-700 disabled:opacity-50"
+                    >
+                        {if is_rerunning {
+                            html! { <><i class="fas fa-spinner fa-spin mr-1" aria-hidden="true"></i>{format!("{} left", rerun.pending)}</> }
+                        } else {
+                            html! { <><i class="fas fa-rotate mr-1" aria-hidden="true"></i>{"Re-run requests"}</> }
+                        }}
+                    </button>
+                </div>
+            </div>
+
+            <div class="flex-1 overflow-y-auto custom-scrollbar p-4 space-y-3">
+                {if total == 0 {
+                    html! {
+                        <p class="text-sm text-gray-600 dark:text-gray-400">
+                            {"Nothing recorded yet. Turn on recording in the chat header and send a message."}
+                        </p>
+                    }
+                } else {
+                    html! {
+                        {for recording.steps[..(*position).min(total)].iter().enumerate().map(|(index, step)| match step {
+                            RecordedStep::Request(request) => render_request(
+                                index,
+                                request,
+                                recording.new_messages(index),
+                                rerun.results.get(&index).map(|result| (rerun.model.as_str(), result)),
+                            ),
+                            RecordedStep::ToolCall(call) => render_tool_call(call),
+                        })}
+                    }
+                }}
+            </div>
+        </div>
+    }
+}
+
+fn render_request(
+    index: usize,
+    request: &RecordedRequest,
+    new_messages: &[Message],
+    rerun: Option<(&str, &RerunResult)>,
+) -> Html {
+    let tokens = request
+        .response
+        .as_ref()
+        .and_then(|response| response.usage.as_ref())
+        .map(|usage| format!(" · {} tokens", usage.total_tokens))
+        .unwrap_or_default();
+    let original = render_response(
+        &format!("{} / {}", request.provider, request.model),
+        request.latency_ms,
+        request
+            .response
+            .as_ref()
+            .ok_or(request.error.as_deref().unwrap_or("Failed")),
+    );
+
+    html! {
+        <section class="p-3 rounded-md border border-gray-200 dark:border-gray-600 space-y-2" aria-label={format!("Request {}", index + 1)}>
+            <div class="text-xs font-medium text-gray-500 dark:text-gray-400">
+                <i class="fas fa-paper-plane mr-1" aria-hidden="true"></i>
+                {format!("Request · {} messages sent · {:.0} ms{}", request.messages.len(), request.latency_ms, tokens)}
+            </div>
+            {for new_messages.iter().map(render_sent_message)}
+            {match rerun {
+                Some((model, (result, latency_ms))) => {
+                    let stats = match (result, &request.response) {
+                        (Ok(new), Some(old)) => {
+                            let (removed, added) = diff_stats(&diff_words(
+                                old.content.as_deref().unwrap_or_default(),
+                                new.content.as_deref().unwrap_or_default(),
+                            ));
+                            format!("{} words removed, {} added", removed, added)
+                        }
+                        _ => String::new(),
+                    };
+                    html! {
+                        <>
+                            <div class="grid grid-cols-2 gap-3">
+                                {original}
+                                {render_response(model, *latency_ms, result.as_ref().map_err(|e| e.as_str()))}
+                            </div>
+                            <div class="text-xs text-gray-500 dark:text-gray-400">{stats}</div>
+                        </>
+                    }
+                }
+                None => original,
+            }}
+        </section>
+    }
+}
+
+fn render_sent_message(message: &Message) -> Html {
+    let (label, text) = match (&message.role, &message.function_response) {
+        (_, Some(response)) => (
+            "Tool result",
+            response
+                .get("name")
+                .and_then(|name| name.as_str())
+                .unwrap_or_default()
+                .to_string(),
+        ),
+        (MessageRole::User, _) => ("User", message.content.clone()),
+        (MessageRole::Assistant, _) => ("Assistant", message.content.clone()),
+        (MessageRole::System, _) => ("System", message.content.clone()),
+        (MessageRole::Developer, _) => ("Developer", message.content.clone()),
+        (MessageRole::Function, _) => ("Function", message.content.clone()),
+    };
+    html! {
+        <div class="text-sm text-gray-700 dark:text-gray-300">
+            <span class="font-medium">{format!("{}: ", label)}</span>
+            <span class="whitespace-pre-wrap">{text}</span>
+        </div>
+    }
+}
+
+fn render_response(
+    model: &str,
+    latency_ms: f64,
+    response: Result<&RecordedResponse, &str>,
+) -> Html {
+    html! {
+        <div class="p-3 rounded-md bg-gray-50 dark:bg-gray-900/40 text-sm">
+            <div class="text-xs font-medium text-gray-500 dark:text-gray-400 mb-1">
+                {format!("{} · {:.0} ms", model, latency_ms)}
+            </div>
+            {match response {
+                Ok(response) => html! {
+                    <>
+                        <div class="whitespace-pre-wrap text-gray-800 dark:text-gray-200">
+                            {response.content.clone().unwrap_or_default()}
+                        </div>
+                        {for response.function_calls.iter().map(|call| html! {
+                            <div class="mt-1 font-mono text-xs text-gray-600 dark:text-gray-400">
+                                <i class="fas fa-wrench mr-1" aria-hidden="true"></i>
+                                {format!("{}({})", call.name, call.arguments)}
+                            </div>
+                        })}
+                    </>
+                },
+                Err(error) => html! {
+                    <div class="text-red-600 dark:text-red-400">{format!("Error: {}", error)}</div>
+                },
+            }}
+        </div>
+    }
+}
+
+fn render_tool_call(call: &RecordedToolCall) -> Html {
+    let pretty =
+        |value: &serde_json::Value| serde_json::to_string_pretty(value).unwrap_or_default();
+    html! {
+        <section class="p-3 rounded-md border border-dashed border-gray-300 dark:border-gray-600 text-sm" aria-label={format!("Tool call {}", call.name)}>
+            <div class="text-xs font-medium text-gray-500 dark:text-gray-400">
+                <i class="fas fa-wrench mr-1" aria-hidden="true"></i>
+                {format!("Tool · {} · {:.0} ms", call.name, call.duration_ms)}
+            </div>
+            <details class="mt-1">
+                <summary class="text-xs text-gray-600 dark:text-gray-400 cursor-pointer">{"Arguments and result"}</summary>
+                <pre class="mt-2 p-2 max-h-64 overflow-auto custom-scrollbar rounded-md bg-gray-100 dark:bg-gray-900 text-xs text-gray-800 dark:text-gray-100">{pretty(&call.arguments)}</pre>
+                <pre class="mt-2 p-2 max-h-64 overflow-auto custom-scrollbar rounded-md bg-gray-100 dark:bg-gray-900 text-xs text-gray-800 dark:text-gray-100">{pretty(&call.result)}</pre>
+            </details>
+        </section>
+    }
+}
@@ -0,0 +1,116 @@
+use super::notification::{NotificationMessage, NotificationType};
+use crate::llm_playground::{
+    api_clients::ProviderRequest,
+    event_bus::PlaygroundEvent,
+    hooks::{use_event_bus, use_focus_trap},
+    html_export,
+    request_export::{ExportFormat, API_KEY_VAR},
+};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct RequestExportDialogProps {
+    /// Rebuilt provider request, API key already replaced
+    pub request: ProviderRequest,
+    pub on_close: Callback<()>,
+}
+
+/// The request behind a message as a cURL command or reqwest program
+#[function_component(RequestExportDialog)]
+pub fn request_export_dialog(props: &RequestExportDialogProps) -> Html {
+    let format = use_state(|| ExportFormat::Curl);
+    let dialog_ref = use_node_ref();
+    use_focus_trap(dialog_ref.clone(), true, props.on_close.clone());
+    let on_notification = use_event_bus().callback(PlaygroundEvent::Notify);
+
+    let snippet = format.render(&props.request);
+
+    let on_copy = {
+        let snippet = snippet.clone();
+        let on_notification = on_notification.clone();
+        Callback::from(move |_: MouseEvent| {
+            let snippet = snippet.clone();
+            let on_notification = on_notification.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let notification = match html_export::copy_to_clipboard(&snippet).await {
+                    Ok(()) => NotificationMessage::new(
+                        "Copied to clipboard".to_string(),
+                        NotificationType::Success,
+                    ),
+                    Err(error) => NotificationMessage::new(error, NotificationType::Error),
+                };
+                on_notification.emit(notification);
+            });
+        })
+    };
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div
+                ref={dialog_ref}
+                role="dialog"
+                aria-modal="true"
+                aria-labelledby="request-export-title"
+                class="bg-white dark:bg-gray-800 rounded-lg shadow-xl max-w-3xl w-full mx-4 flex flex-col max-h-[85vh]"
+            >
+                <div class="flex items-center justify-between px-6 py-4 border-b border-gray-200 dark:border-gray-700">
+                    <div>
+                        <h2 id="request-export-title" class="text-lg font-semibold text-gray-900 dark:text-white">
+                            {"Export request"}
+                        </h2>
+                        <p class="text-xs text-gray-600 dark:text-gray-300 mt-1">
+                            {format!("Rebuilt with the current settings. Set {} before running it.", API_KEY_VAR)}
+                        </p>
+                    </div>
+                    <button
+                        onclick={on_close.clone()}
+                        class="text-gray-500 dark:text-gray-400 hover:text-gray-700 dark:hover:text-gray-200"
+                        aria-label="Close"
+                    >
+                        <i class="fas fa-times" aria-hidden="true"></i>
+                    </button>
+                </div>
+                <div class="flex items-center justify-between px-6 pt-4">
+                    <div class="flex space-x-1" role="tablist">
+                        {for [ExportFormat::Curl, ExportFormat::Reqwest].into_iter().map(|option| {
+                            let selected = *format == option;
+                            let onclick = {
+                                let format = format.clone();
+                                Callback::from(move |_: MouseEvent| format.set(option))
+                            };
+                            html! {
+                                <button
+                                    {onclick}
+                                    role="tab"
+                                    aria-selected={selected.to_string()}
+                                    class={classes!(
+                                        "px-3", "py-1", "text-sm", "rounded-md",
+                                        if selected {
+                                            "bg-primary-600 text-white"
+                                        } else {
+                                            "text-gray-600 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700"
+                                        }
+                                    )}
+                                >
+                                    {option.label()}
+                                </button>
+                            }
+                        })}
+                    </div>
+                    <button
+                        onclick={on_copy}
+                        class="px-3 py-1 text-sm rounded-md text-gray-600 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700"
+                    >
+                        <i class="fas fa-copy mr-1" aria-hidden="true"></i>{"Copy"}
+                    </button>
+                </div>
+                <pre class="m-6 mt-3 p-4 flex-1 overflow-auto custom-scrollbar rounded-lg bg-gray-100 dark:bg-gray-900 text-xs text-gray-800 dark:text-gray-100"><code>{snippet}</code></pre>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,108 @@
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, KeyboardEvent, MouseEvent};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ResizeHandleProps {
+    /// Emits the pointer's horizontal position (clientX) while dragging
+    pub on_drag: Callback<f64>,
+    /// Emits once a drag or keyboard nudge is over, when the size can be saved
+    #[prop_or_default]
+    pub on_drag_end: Callback<()>,
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+#[function_component(ResizeHandle)]
+pub fn resize_handle(props: &ResizeHandleProps) -> Html {
+    let dragging = use_state(|| false);
+
+    // Track the pointer on the whole window while dragging so fast moves don't lose the handle
+    {
+        let dragging = dragging.clone();
+        let on_drag = props.on_drag.clone();
+        let on_drag_end = props.on_drag_end.clone();
+        use_effect_with(*dragging, move |is_dragging| {
+            let listeners = if *is_dragging {
+                web_sys::window().map(|window| {
+                    let body_style = window
+                        .document()
+                        .and_then(|d| d.body())
+                        .map(|body| body.style());
+                    if let Some(style) = body_style.as_ref() {
+                        let _ = style.set_property("user-select", "none");
+                        let _ = style.set_property("cursor", "col-resize");
+                    }
+
+                    let on_move = EventListener::new(&window, "mousemove", move |event| {
+                        if let Some(event) = event.dyn_ref::<MouseEvent>() {
+                            on_drag.emit(event.client_x() as f64);
+                        }
+                    });
+                    let on_up = {
+                        let dragging = dragging.clone();
+                        EventListener::new(&window, "mouseup", move |_| {
+                            if let Some(style) = body_style.as_ref() {
+                                let _ = style.remove_property("user-select");
+                                let _ = style.remove_property("cursor");
+                            }
+                            dragging.set(false);
+                            on_drag_end.emit(());
+                        })
+                    };
+                    (on_move, on_up)
+                })
+            } else {
+                None
+            };
+            move || drop(listeners)
+        });
+    }
+
+    let on_mouse_down = {
+        let dragging = dragging.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            dragging.set(true);
+        })
+    };
+
+    // Arrow keys nudge the handle so panes can be resized without a mouse
+    let on_key_down = {
+        let on_drag = props.on_drag.clone();
+        let on_drag_end = props.on_drag_end.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            let step = match e.key().as_str() {
+                "ArrowLeft" => -16.0,
+                "ArrowRight" => 16.0,
+                _ => return,
+            };
+            e.prevent_default();
+            if let Some(handle) = e.target().and_then(|t| t.dyn_into::<Element>().ok()) {
+                let rect = handle.get_bounding_client_rect();
+                on_drag.emit(rect.left() + rect.width() / 2.0 + step);
+                on_drag_end.emit(());
+            }
+        })
+    };
+
+    html! {
+        <div
+            onmousedown={on_mouse_down}
+            onkeydown={on_key_down}
+            role="separator"
+            aria-orientation="vertical"
+            aria-label="Resize pane"
+            tabindex="0"
+            class={classes!(
+                "w-1", "flex-shrink-0", "cursor-col-resize", "transition-colors",
+                "hover:bg-primary-400", "dark:hover:bg-primary-600",
+                "focus:outline-none", "focus:bg-primary-500",
+                if *dragging { "bg-primary-500" } else { "bg-transparent" },
+                props.class.clone()
+            )}
+            title="Drag to resize"
+        />
+    }
+}
@@ -0,0 +1,250 @@
+// A session's SQLite database: import CSV files as tables, see what's in it
+// and let the chat model query it through the run_sql tool
+use crate::llm_playground::{
+    app_state::use_app_state,
+    components::notification::{NotificationMessage, NotificationType},
+    components::{ConfirmAction, ConfirmRequest},
+    event_bus::PlaygroundEvent,
+    hooks::{use_confirm, use_event_bus},
+    session_database::{self, TableInfo, RUN_SQL_TOOL_NAME},
+};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct SessionDatabaseViewProps {
+    pub session_id: String,
+    /// Incognito sessions' databases aren't saved
+    pub incognito: bool,
+    pub on_close: Callback<()>,
+}
+
+#[function_component(SessionDatabaseView)]
+pub fn session_database_view(props: &SessionDatabaseViewProps) -> Html {
+    let app_state = use_app_state();
+    let tables = use_state(|| Option::<Vec<TableInfo>>::None);
+    let reload = use_state(|| 0u32);
+    // File currently being imported
+    let importing = use_state(|| Option::<String>::None);
+    let errors = use_state(Vec::<String>::new);
+    let confirm = use_confirm();
+    let bus = use_event_bus();
+    let on_config_change = bus.callback(|config| PlaygroundEvent::ConfigChanged(Box::new(config)));
+    let on_notification = bus.callback(PlaygroundEvent::Notify);
+
+    {
+        let tables = tables.clone();
+        let errors = errors.clone();
+        use_effect_with((props.session_id.clone(), props.incognito, *reload), move |(session_id, incognito, _)| {
+            let (session_id, incognito) = (session_id.clone(), *incognito);
+            wasm_bindgen_futures::spawn_local(async move {
+                match session_database::tables(&session_id, incognito).await {
+                    Ok(list) => tables.set(Some(list)),
+                    Err(e) => {
+                        tables.set(Some(Vec::new()));
+                        errors.set(vec![e]);
+                    }
+                }
+            });
+            || ()
+        });
+    }
+
+    let tool_enabled = app_state
+        .config
+        .function_tools
+        .iter()
+        .any(|tool| tool.name == RUN_SQL_TOOL_NAME && tool.enabled);
+    let on_toggle_tool = {
+        let config = app_state.config.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut next = config.clone();
+            next.set_default_tool_enabled(RUN_SQL_TOOL_NAME, input.checked());
+            on_config_change.emit(next);
+        })
+    };
+
+    let on_import = {
+        let session_id = props.session_id.clone();
+        let incognito = props.incognito;
+        let importing = importing.clone();
+        let errors = errors.clone();
+        let reload = reload.clone();
+        let on_notification = on_notification.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Some(files) = input.files() else {
+                return;
+            };
+            let files: Vec<web_sys::File> =
+                (0..files.length()).filter_map(|i| files.get(i)).collect();
+            // Allow picking the same file again later
+            input.set_value("");
+            if files.is_empty() {
+                return;
+            }
+
+            let session_id = session_id.clone();
+            let importing = importing.clone();
+            let errors = errors.clone();
+            let reload = reload.clone();
+            let on_notification = on_notification.clone();
+            errors.set(Vec::new());
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut failures = Vec::new();
+                for file in files {
+                    let name = file.name();
+                    importing.set(Some(name.clone()));
+                    let text = JsFuture::from(file.text())
+                        .await
+                        .ok()
+                        .and_then(|value| value.as_string());
+                    let result = match text {
+                        Some(text) => session_database::import_csv(&session_id, incognito, &name, &text).await,
+                        None => Err("Could not read the file as text".to_string()),
+                    };
+                    match result {
+                        Ok((table, rows)) => on_notification.emit(NotificationMessage::new(
+                            format!("Imported {} rows from {} into {}", rows, name, table),
+                            NotificationType::Success,
+                        )),
+                        Err(e) => failures.push(format!("{}: {}", name, e)),
+                    }
+                }
+                importing.set(None);
+                errors.set(failures);
+                reload.set(*reload + 1);
+            });
+        })
+    };
+
+    let on_clear = {
+        let session_id = props.session_id.clone();
+        let confirm = confirm.clone();
+        let tables = tables.clone();
+        Callback::from(move |_: MouseEvent| {
+            let session_id = session_id.clone();
+            let tables = tables.clone();
+            confirm.confirm(
+                ConfirmRequest::new(
+                    ConfirmAction::ClearSessionDatabase,
+                    "Clear database?",
+                    "Every table in this session's database will be deleted.",
+                ),
+                Callback::from(move |_| {
+                    session_database::delete(&session_id);
+                    tables.set(Some(Vec::new()));
+                }),
+            );
+        })
+    };
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    let has_tables = tables.as_ref().is_some_and(|list| !list.is_empty());
+
+    html! {
+        <div class="flex-1 flex flex-col min-w-0 bg-white dark:bg-gray-800">
+            <header class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
+                <div>
+                    <h2 class="font-semibold text-gray-900 dark:text-gray-100">{"Session Database"}</h2>
+                    <div class="text-sm text-gray-600 dark:text-gray-300">
+                        {"A SQLite database kept in this browser for this session. The model can query it with the run_sql tool."}
+                    </div>
+                </div>
+                <button
+                    onclick={on_close}
+                    class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                    aria-label="Close session database"
+                    title="Back to chat"
+                >
+                    <i class="fas fa-times" aria-hidden="true"></i>
+                </button>
+            </header>
+
+            <div class="flex-1 overflow-y-auto custom-scrollbar p-4 space-y-4">
+                <label class="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300">
+                    <input type="checkbox" checked={tool_enabled} onchange={on_toggle_tool} />
+                    {"Let the chat model query this database"}
+                </label>
+
+                <div class="flex items-center gap-3">
+                    <label
+                        class={classes!(
+                            "text-sm", "px-4", "py-2", "bg-primary-600", "text-white", "rounded-md",
+                            if importing.is_none() { "hover:bg-primary-700 cursor-pointer" } else { "opacity-50 cursor-not-allowed" }
+                        )}
+                    >
+                        <i class="fas fa-file-csv mr-1" aria-hidden="true"></i>{"Import CSV"}
+                        <input
+                            type="file"
+                            multiple=true
+                            accept=".csv,.tsv,text/csv,text/tab-separated-values"
+                            onchange={on_import}
+                            disabled={importing.is_some()}
+                            class="hidden"
+                        />
+                    </label>
+                    {if let Some(name) = &*importing {
+                        html! {
+                            <span class="text-sm text-gray-600 dark:text-gray-300" role="status">
+                                <i class="fas fa-spinner fa-spin mr-1" aria-hidden="true"></i>
+                                {format!("Importing {}...", name)}
+                            </span>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    {if has_tables {
+                        html! {
+                            <button
+                                onclick={on_clear}
+                                class="ml-auto px-3 py-2 text-sm rounded-md text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-gray-700"
+                            >
+                                <i class="fas fa-trash mr-1" aria-hidden="true"></i>{"Clear database"}
+                            </button>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                </div>
+
+                {for errors.iter().map(|error| html! {
+                    <div class="text-sm p-2 rounded-md bg-red-50 dark:bg-red-900/30 text-red-700 dark:text-red-300" role="alert">
+                        {error.clone()}
+                    </div>
+                })}
+
+                {match &*tables {
+                    None => html! {
+                        <div class="text-sm text-gray-600 dark:text-gray-300">{"Loading tables..."}</div>
+                    },
+                    Some(list) if list.is_empty() => html! {
+                        <div class="text-center text-gray-600 dark:text-gray-300 py-12">
+                            <i class="fas fa-database text-3xl mb-2" aria-hidden="true"></i>
+                            <p>{"No tables yet. Import a CSV file, or let the model create tables with run_sql."}</p>
+                        </div>
+                    },
+                    Some(list) => html! {
+                        <ul class="divide-y divide-gray-200 dark:divide-gray-600 border border-gray-200 dark:border-gray-600 rounded-md">
+                            {for list.iter().map(|table| html! {
+                                <li class="p-3 min-w-0">
+                                    <div class="font-medium text-sm font-mono text-gray-900 dark:text-gray-100 truncate">{&table.name}</div>
+                                    <div class="text-xs text-gray-600 dark:text-gray-300 truncate">
+                                        {format!("{} rows · {}", table.rows, table.columns.join(", "))}
+                                    </div>
+                                </li>
+                            })}
+                        </ul>
+                    },
+                }}
+            </div>
+            {confirm.dialog()}
+        </div>
+    }
+}
@@ -1,31 +1,456 @@
-use crate::llm_playground::ChatSession;
-use std::collections::HashMap;
+use crate::llm_playground::{
+    provider_config::FlexibleApiConfig,
+    session_bulk::{self, BulkAction},
+    session_merge::MergeOrder,
+    session_pages,
+    session_search::{self, SessionHit},
+    session_store::SessionStore,
+    ChatSession,
+};
+use std::collections::HashSet;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, HtmlInputElement};
 use yew::prelude::*;
 
 #[derive(Properties, PartialEq)]
 pub struct SidebarProps {
-    pub sessions: HashMap<String, ChatSession>,
+    pub sessions: SessionStore,
     pub current_session_id: Option<String>,
     pub on_new_session: Callback<()>,
+    /// Start a session that is never saved
+    pub on_new_incognito_session: Callback<()>,
     pub on_select_session: Callback<String>,
     pub on_delete_session: Callback<String>,
     pub on_toggle_settings: Callback<()>,
+    #[prop_or_default]
+    pub on_compare: Callback<()>,
+    #[prop_or_default]
+    pub on_dialogue: Callback<()>,
+    #[prop_or_default]
+    pub on_evals: Callback<()>,
+    #[prop_or_default]
+    pub on_ab_experiment: Callback<()>,
+    #[prop_or_default]
+    pub on_knowledge: Callback<()>,
+    #[prop_or_default]
+    pub on_fine_tune: Callback<()>,
+    #[prop_or_default]
+    pub on_diagnostics: Callback<()>,
+    #[prop_or_default]
+    pub on_bookmarks: Callback<()>,
+    /// Archive or unarchive a session, by id
+    #[prop_or_default]
+    pub on_toggle_archive: Callback<String>,
+    /// Apply an action to the sessions selected in select mode
+    #[prop_or_default]
+    pub on_bulk_action: Callback<(BulkAction, Vec<String>)>,
+    #[prop_or(DEFAULT_SIDEBAR_WIDTH)]
+    pub width: u32,
+    /// Used for semantic search when an embeddings model is configured
+    #[prop_or_default]
+    pub api_config: FlexibleApiConfig,
 }
 
-#[function_component(Sidebar)]
-pub fn sidebar(props: &SidebarProps) -> Html {
-    // Sort sessions by updated_at (most recent first)
-    let mut sessions_vec: Vec<_> = props.sessions.iter().collect();
-    sessions_vec.sort_by(|a, b| {
+pub const DEFAULT_SIDEBAR_WIDTH: u32 = 256;
+pub const MIN_SIDEBAR_WIDTH: u32 = 200;
+pub const MAX_SIDEBAR_WIDTH: u32 = 480;
+
+/// Keep a dragged or stored width within the sidebar's limits
+pub fn clamp_sidebar_width(width: u32) -> u32 {
+    width.clamp(MIN_SIDEBAR_WIDTH, MAX_SIDEBAR_WIDTH)
+}
+
+/// Sessions ordered by their last update, most recent first
+fn most_recent_first<'a>(
+    sessions: impl Iterator<Item = (&'a String, &'a ChatSession)>,
+) -> Vec<(&'a String, &'a ChatSession)> {
+    let mut sessions: Vec<_> = sessions.collect();
+    sessions.sort_by(|a, b| {
         b.1.updated_at
             .partial_cmp(&a.1.updated_at)
             .unwrap_or(std::cmp::Ordering::Equal)
     });
+    sessions
+}
+
+/// A session in the list, with a matching excerpt while searching
+type ListedSession<'a> = (&'a String, &'a ChatSession, Option<String>);
+
+/// Split listed sessions into the archived ones and the rest, keeping the order
+fn split_archived(listed: Vec<ListedSession<'_>>) -> (Vec<ListedSession<'_>>, Vec<ListedSession<'_>>) {
+    listed.into_iter().partition(|(_, session, _)| session.archived)
+}
+
+#[function_component(Sidebar)]
+pub fn sidebar(props: &SidebarProps) -> Html {
+    let sessions_vec = most_recent_first(props.sessions.iter());
+
+    let search_query = use_state(String::new);
+    // Semantic results for the query they were made for
+    let semantic_results = use_state(|| Option::<(String, Result<Vec<SessionHit>, String>)>::None);
+    let searching = use_state(|| false);
+    let semantic_available = session_search::semantic_available(&props.api_config);
+
+    let query = search_query.trim().to_string();
+    let semantic_for_query = semantic_results
+        .as_ref()
+        .filter(|(searched, _)| *searched == query)
+        .map(|(_, result)| result);
+    let matching: Vec<ListedSession> = if query.is_empty() {
+        sessions_vec
+            .iter()
+            .map(|(id, session)| (*id, *session, None))
+            .collect()
+    } else {
+        let hits = match semantic_for_query {
+            Some(Ok(hits)) => hits.clone(),
+            _ => {
+                // Pages of long sessions that aren't loaded are searched too
+                let paged: Vec<ChatSession> = sessions_vec
+                    .iter()
+                    .filter(|(_, s)| s.older_pages > 0)
+                    .map(|(_, s)| session_pages::hydrated(s))
+                    .collect();
+                let sessions: Vec<&ChatSession> = sessions_vec
+                    .iter()
+                    .map(|(_, s)| paged.iter().find(|p| p.id == s.id).unwrap_or(s))
+                    .collect();
+                session_search::keyword_search(&sessions, &query)
+            }
+        };
+        hits.into_iter()
+            .filter_map(|hit| {
+                props
+                    .sessions
+                    .get_key_value(&hit.session_id)
+                    .map(|(id, session)| (id, session, hit.snippet))
+            })
+            .collect()
+    };
+    // Archived sessions are listed apart, in a section that starts collapsed
+    let (archived, visible) = split_archived(matching);
+    let show_archived = use_state(|| false);
+    let toggle_archived = {
+        let show_archived = show_archived.clone();
+        Callback::from(move |_: MouseEvent| show_archived.set(!*show_archived))
+    };
+
+    let on_search_input = {
+        let search_query = search_query.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            search_query.set(input.value());
+        })
+    };
+
+    let run_semantic_search = {
+        let sessions = props.sessions.clone();
+        let config = props.api_config.clone();
+        let semantic_results = semantic_results.clone();
+        let searching = searching.clone();
+        let query = query.clone();
+        Callback::from(move |_: ()| {
+            if query.is_empty() || *searching {
+                return;
+            }
+            let sessions = sessions.clone();
+            let config = config.clone();
+            let semantic_results = semantic_results.clone();
+            let searching = searching.clone();
+            let query = query.clone();
+            searching.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                let sessions = session_pages::hydrated_all(sessions.values());
+                let result = session_search::semantic_search(&sessions, &query, &config).await;
+                semantic_results.set(Some((query, result)));
+                searching.set(false);
+            });
+        })
+    };
+
+    let on_search_keydown = {
+        let run_semantic_search = run_semantic_search.clone();
+        let search_query = search_query.clone();
+        Callback::from(move |e: KeyboardEvent| match e.key().as_str() {
+            "Enter" if semantic_available => {
+                e.prevent_default();
+                run_semantic_search.emit(());
+            }
+            "Escape" => search_query.set(String::new()),
+            _ => {}
+        })
+    };
+
+    // Select mode: clicking a session selects it for a bulk action
+    let selecting = use_state(|| false);
+    let selected = use_state(HashSet::<String>::new);
+    let tag_input = use_state(String::new);
+    let merge_order = use_state(MergeOrder::default);
+    let selected_ids: Vec<String> = sessions_vec
+        .iter()
+        .filter(|(id, _)| selected.contains(*id))
+        .map(|(id, _)| (*id).clone())
+        .collect();
+    let all_visible_selected =
+        !visible.is_empty() && visible.iter().all(|(id, _, _)| selected.contains(*id));
+
+    let toggle_select_mode = {
+        let selecting = selecting.clone();
+        let selected = selected.clone();
+        Callback::from(move |_: MouseEvent| {
+            selecting.set(!*selecting);
+            selected.set(HashSet::new());
+        })
+    };
+
+    // Selects every session the search shows, or clears them if all are
+    let toggle_select_all = {
+        let selected = selected.clone();
+        let visible_ids: Vec<String> = visible.iter().map(|(id, _, _)| (*id).clone()).collect();
+        Callback::from(move |_: Event| {
+            let mut new_selected = (*selected).clone();
+            if all_visible_selected {
+                for id in &visible_ids {
+                    new_selected.remove(id);
+                }
+            } else {
+                new_selected.extend(visible_ids.iter().cloned());
+            }
+            selected.set(new_selected);
+        })
+    };
+
+    let on_tag_input = {
+        let tag_input = tag_input.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            tag_input.set(input.value());
+        })
+    };
+
+    let on_merge_order_change = {
+        let merge_order = merge_order.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            merge_order.set(MergeOrder::from_key(&input.value()));
+        })
+    };
+
+    let bulk = {
+        let on_bulk_action = props.on_bulk_action.clone();
+        let selected = selected.clone();
+        let tag_input = tag_input.clone();
+        let merge_order = *merge_order;
+        let selected_ids = selected_ids.clone();
+        move |action: BulkAction| {
+            let on_bulk_action = on_bulk_action.clone();
+            let selected = selected.clone();
+            let tag_input = tag_input.clone();
+            let selected_ids = selected_ids.clone();
+            Callback::from(move |_: MouseEvent| {
+                let action = match &action {
+                    BulkAction::Tag(_) => match session_bulk::normalize_tag(&tag_input) {
+                        Some(tag) => BulkAction::Tag(tag),
+                        None => return,
+                    },
+                    BulkAction::Merge(_) => BulkAction::Merge(merge_order),
+                    action => action.clone(),
+                };
+                if action != BulkAction::Export {
+                    selected.set(HashSet::new());
+                    tag_input.set(String::new());
+                }
+                on_bulk_action.emit((action, selected_ids.clone()));
+            })
+        }
+    };
+
+    // One session in the main or the archived list
+    let render_session = |(session_id, session, snippet): &(&String, &ChatSession, Option<String>)| {
+        let is_current = props.current_session_id.as_ref() == Some(session_id);
+        let session_id_clone = (*session_id).clone();
+        let session_id_delete = (*session_id).clone();
+        let on_select = props.on_select_session.clone();
+        let on_delete = props.on_delete_session.clone();
+
+        let is_selected = selected.contains(*session_id);
+        let toggle_selected = {
+            let selected = selected.clone();
+            let session_id = (*session_id).clone();
+            move || {
+                let mut new_selected = (*selected).clone();
+                if !new_selected.remove(&session_id) {
+                    new_selected.insert(session_id.clone());
+                }
+                selected.set(new_selected);
+            }
+        };
+        let click_handler = {
+            let selecting = *selecting;
+            let toggle_selected = toggle_selected.clone();
+            Callback::from(move |e: MouseEvent| {
+                e.stop_propagation();
+                if selecting {
+                    toggle_selected();
+                } else {
+                    on_select.emit(session_id_clone.clone());
+                }
+            })
+        };
+
+        let archive_handler = {
+            let on_toggle_archive = props.on_toggle_archive.clone();
+            let session_id = (*session_id).clone();
+            Callback::from(move |e: MouseEvent| {
+                e.stop_propagation();
+                on_toggle_archive.emit(session_id.clone());
+            })
+        };
+
+        let delete_handler = {
+            let on_delete = on_delete.clone();
+            let session_id_delete = session_id_delete.clone();
+            Callback::from(move |e: MouseEvent| {
+                e.stop_propagation();
+                on_delete.emit(session_id_delete.clone());
+            })
+        };
+
+        // Enter/Space selects, Delete removes, arrow keys move between sessions
+        let key_handler = {
+            let on_select = props.on_select_session.clone();
+            let session_id = (*session_id).clone();
+            let selecting = *selecting;
+            Callback::from(move |e: KeyboardEvent| {
+                match e.key().as_str() {
+                    "Enter" | " " if selecting => {
+                        e.prevent_default();
+                        toggle_selected();
+                    }
+                    "Enter" | " " => {
+                        e.prevent_default();
+                        on_select.emit(session_id.clone());
+                    }
+                    "Delete" => {
+                        e.prevent_default();
+                        on_delete.emit(session_id_delete.clone());
+                    }
+                    "ArrowDown" | "ArrowUp" => {
+                        e.prevent_default();
+                        focus_sibling_session(&e, e.key() == "ArrowDown");
+                    }
+                    _ => {}
+                }
+            })
+        };
+
+        let time_ago = format_time_ago(session.updated_at);
+
+        html! {
+            <li
+                key={session.id.clone()}
+                class={classes!(
+                    "group", "relative", "rounded-md",
+                    if is_current {
+                        "bg-primary-100 dark:bg-primary-900/30"
+                    } else {
+                        "hover:bg-gray-100 dark:hover:bg-gray-700"
+                    }
+                )}
+            >
+                <div
+                    onclick={click_handler}
+                    onkeydown={key_handler}
+                    role="option"
+                    tabindex="0"
+                    aria-selected={if *selecting { is_selected } else { is_current }.to_string()}
+                    data-session-item="true"
+                    class="p-2 cursor-pointer pr-14 rounded-md focus:outline-none focus:ring-2 focus:ring-primary-500"
+                >
+                    <div class="flex items-center gap-2 min-w-0">
+                        {if *selecting {
+                            html! {
+                                <i
+                                    class={classes!(if is_selected { "fas fa-square-check text-primary-600 dark:text-primary-400" } else { "far fa-square text-gray-400" })}
+                                    aria-hidden="true"
+                                ></i>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                        <div class="font-medium truncate text-gray-900 dark:text-gray-100">{&session.title}</div>
+                    </div>
+                    {if session.tags.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <div class="flex flex-wrap gap-1 my-0.5">
+                                {for session.tags.iter().map(|tag| html! {
+                                    <span class="px-1.5 rounded text-xs bg-primary-100 dark:bg-primary-900/40 text-primary-700 dark:text-primary-300">{tag}</span>
+                                })}
+                            </div>
+                        }
+                    }}
+                    {if let Some(snippet) = snippet {
+                        html! { <div class="text-xs text-gray-600 dark:text-gray-300 line-clamp-2">{snippet.clone()}</div> }
+                    } else {
+                        html! {}
+                    }}
+                    <div class="flex items-center justify-between">
+                        <div class="text-xs text-gray-600 dark:text-gray-300">{time_ago}</div>
+                        <div class="flex items-center gap-1">
+                            {if session.incognito {
+                                html! { <i class="fas fa-user-secret text-xs text-gray-700 dark:text-gray-200" aria-label="Incognito, not saved" title="Incognito: not saved"></i> }
+                            } else {
+                                html! {}
+                            }}
+                            {if session.locked {
+                                html! { <i class="fas fa-lock text-xs text-gray-500 dark:text-gray-400" aria-label="Locked"></i> }
+                            } else {
+                                html! {}
+                            }}
+                            {if session.pinned {
+                                html! { <i class="fas fa-thumbtack text-xs text-yellow-500" aria-label="Pinned"></i> }
+                            } else {
+                                html! {}
+                            }}
+                        </div>
+                    </div>
+                </div>
+
+                // Archive and delete buttons (visible on hover or keyboard focus);
+                // locked sessions have no delete button
+                <button
+                    onclick={archive_handler}
+                    class="absolute right-8 top-1 w-6 h-6 rounded-full bg-gray-100 dark:bg-gray-700 text-gray-600 dark:text-gray-300 opacity-0 group-hover:opacity-100 group-focus-within:opacity-100 focus:opacity-100 transition-opacity hover:bg-gray-200 dark:hover:bg-gray-600 flex items-center justify-center"
+                    title={if session.archived { "Unarchive session" } else { "Archive session" }}
+                    aria-label={format!("{} session {}", if session.archived { "Unarchive" } else { "Archive" }, session.title)}
+                >
+                    <i class={classes!("fas", "text-xs", if session.archived { "fa-box-open" } else { "fa-box-archive" })} aria-hidden="true"></i>
+                </button>
+                {if session.locked {
+                    html! {}
+                } else {
+                    html! {
+                        <button
+                            onclick={delete_handler}
+                            class="absolute right-1 top-1 w-6 h-6 rounded-full bg-red-100 dark:bg-red-900/30 text-red-600 dark:text-red-400 opacity-0 group-hover:opacity-100 group-focus-within:opacity-100 focus:opacity-100 transition-opacity hover:bg-red-200 dark:hover:bg-red-900/50 flex items-center justify-center"
+                            title="Delete session"
+                            aria-label={format!("Delete session {}", session.title)}
+                        >
+                            <i class="fas fa-times text-xs" aria-hidden="true"></i>
+                        </button>
+                    }
+                }}
+            </li>
+        }
+    };
 
     let on_new_session = props.on_new_session.clone();
     let new_session_click = Callback::from(move |_| {
         on_new_session.emit(());
     });
+    let new_incognito_click = props.on_new_incognito_session.reform(|_: MouseEvent| ());
 
     let on_settings_click = {
         let on_toggle_settings = props.on_toggle_settings.clone();
@@ -35,7 +460,11 @@ pub fn sidebar(props: &SidebarProps) -> Html {
     };
 
     html! {
-        <div class="w-64 bg-white dark:bg-gray-800 border-r border-gray-200 dark:border-gray-600 flex flex-col">
+        <nav
+            class="flex-shrink-0 bg-white dark:bg-gray-800 border-r border-gray-200 dark:border-gray-600 flex flex-col"
+            style={format!("width: {}px", props.width)}
+            aria-label="Chat sessions"
+        >
             // Header
             <div class="p-4 border-b border-gray-200 dark:border-gray-600">
                 <h1 class="text-xl font-bold text-gray-900 dark:text-gray-100">{"LLM Playground"}</h1>
@@ -46,72 +475,155 @@ pub fn sidebar(props: &SidebarProps) -> Html {
             <div class="flex-1 overflow-y-auto custom-scrollbar">
                 <div class="p-4">
                     <div class="flex justify-between items-center mb-2">
-                        <h2 class="font-semibold text-gray-900 dark:text-gray-100">{"Sessions"}</h2>
-                        <button
-                            onclick={new_session_click}
-                            class="text-primary-600 dark:text-primary-400 hover:text-primary-700 dark:hover:text-primary-300"
-                        >
-                            <i class="fas fa-plus"></i>
-                        </button>
+                        <h2 id="sessions-heading" class="font-semibold text-gray-900 dark:text-gray-100">{"Sessions"}</h2>
+                        <div class="flex items-center gap-3">
+                            <button
+                                onclick={toggle_select_mode}
+                                class={classes!(
+                                    if *selecting { "text-primary-600 dark:text-primary-400" } else { "text-gray-500 dark:text-gray-400" },
+                                    "hover:text-primary-700", "dark:hover:text-primary-300"
+                                )}
+                                aria-label="Select sessions"
+                                aria-pressed={selecting.to_string()}
+                                title={if *selecting { "Done selecting" } else { "Select sessions" }}
+                            >
+                                <i class="fas fa-list-check" aria-hidden="true"></i>
+                            </button>
+                            <button
+                                onclick={new_incognito_click}
+                                class="text-gray-500 dark:text-gray-400 hover:text-gray-700 dark:hover:text-gray-200"
+                                aria-label="New incognito session"
+                                title="New incognito session: never saved, gone on reload"
+                            >
+                                <i class="fas fa-user-secret" aria-hidden="true"></i>
+                            </button>
+                            <button
+                                onclick={new_session_click}
+                                class="text-primary-600 dark:text-primary-400 hover:text-primary-700 dark:hover:text-primary-300"
+                                aria-label="New session"
+                                title="New session"
+                            >
+                                <i class="fas fa-plus" aria-hidden="true"></i>
+                            </button>
+                        </div>
                     </div>
-                    <ul class="space-y-2">
-                        {for sessions_vec.iter().map(|(session_id, session)| {
-                            let is_current = props.current_session_id.as_ref() == Some(session_id);
-                            let session_id_clone = (*session_id).clone();
-                            let session_id_delete = (*session_id).clone();
-                            let on_select = props.on_select_session.clone();
-                            let on_delete = props.on_delete_session.clone();
-
-                            let click_handler = Callback::from(move |e: MouseEvent| {
-                                e.stop_propagation();
-                                on_select.emit(session_id_clone.clone());
-                            });
-
-                            let delete_handler = Callback::from(move |e: MouseEvent| {
-                                e.stop_propagation();
-                                on_delete.emit(session_id_delete.clone());
-                            });
-
-                            let time_ago = format_time_ago(session.updated_at);
-
+                    <div class="relative mb-2">
+                        <input
+                            type="search"
+                            value={(*search_query).clone()}
+                            oninput={on_search_input}
+                            onkeydown={on_search_keydown}
+                            class={classes!(
+                                "w-full", "py-1.5", "pl-2", "text-sm", "border", "border-gray-300", "dark:border-gray-600", "rounded-md",
+                                "bg-white", "dark:bg-gray-700", "text-gray-900", "dark:text-gray-100",
+                                if semantic_available { "pr-8" } else { "pr-2" }
+                            )}
+                            placeholder={if semantic_available { "Search (Enter: by meaning)" } else { "Search sessions" }}
+                            aria-label="Search sessions"
+                        />
+                        {if semantic_available {
                             html! {
-                                <li
-                                    key={session.id.clone()}
-                                    class={classes!(
-                                        "group", "relative", "rounded-md",
-                                        if is_current {
-                                            "bg-primary-100 dark:bg-primary-900/30"
-                                        } else {
-                                            "hover:bg-gray-100 dark:hover:bg-gray-700"
-                                        }
-                                    )}
+                                <button
+                                    onclick={
+                                        let run_semantic_search = run_semantic_search.clone();
+                                        Callback::from(move |_: MouseEvent| run_semantic_search.emit(()))
+                                    }
+                                    disabled={query.is_empty() || *searching}
+                                    class="absolute right-1 top-1/2 -translate-y-1/2 p-1 text-gray-500 hover:text-primary-600 dark:text-gray-400 dark:hover:text-primary-400 disabled:opacity-50"
+                                    aria-label="Semantic search"
+                                    title="Search by meaning"
                                 >
-                                    <div
-                                        onclick={click_handler}
-                                        class="p-2 cursor-pointer pr-8"
+                                    <i class={if *searching { "fas fa-spinner fa-spin text-xs" } else { "fas fa-brain text-xs" }} aria-hidden="true"></i>
+                                </button>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                    </div>
+                    {match semantic_for_query {
+                        Some(Ok(_)) => html! {
+                            <p class="text-xs text-gray-600 dark:text-gray-300 mb-2" role="status">{"Ranked by meaning"}</p>
+                        },
+                        Some(Err(error)) => html! {
+                            <p class="text-xs text-red-600 dark:text-red-400 mb-2" role="status">
+                                {format!("Semantic search failed, showing keyword matches: {}", error)}
+                            </p>
+                        },
+                        None => html! {},
+                    }}
+                    {if *selecting {
+                        let none_selected = selected_ids.is_empty();
+                        let action_class = "px-2 py-1 text-xs rounded border border-gray-300 dark:border-gray-600 text-gray-800 dark:text-gray-200 hover:bg-gray-100 dark:hover:bg-gray-700 disabled:opacity-50";
+                        html! {
+                            <div class="mb-2 p-2 space-y-2 rounded-md bg-gray-50 dark:bg-gray-700/50 text-sm" role="toolbar" aria-label="Bulk actions">
+                                <label class="flex items-center gap-2 text-gray-700 dark:text-gray-300">
+                                    <input
+                                        type="checkbox"
+                                        checked={all_visible_selected}
+                                        onchange={toggle_select_all}
+                                        disabled={visible.is_empty()}
+                                    />
+                                    {if query.is_empty() { "Select all" } else { "Select all matches" }}
+                                    <span class="ml-auto text-xs text-gray-500 dark:text-gray-400">{format!("{} selected", selected_ids.len())}</span>
+                                </label>
+                                <div class="flex flex-wrap gap-1">
+                                    <button onclick={bulk(BulkAction::Export)} disabled={none_selected} class={action_class}>
+                                        <i class="fas fa-file-export mr-1" aria-hidden="true"></i>{"Export"}
+                                    </button>
+                                    <button onclick={bulk(BulkAction::Archive)} disabled={none_selected} class={action_class}>
+                                        <i class="fas fa-box-archive mr-1" aria-hidden="true"></i>{"Archive"}
+                                    </button>
+                                    <button
+                                        onclick={bulk(BulkAction::Delete)}
+                                        disabled={none_selected}
+                                        class="px-2 py-1 text-xs rounded border border-red-300 dark:border-red-700 text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-red-900/30 disabled:opacity-50"
                                     >
-                                        <div class="font-medium truncate text-gray-900 dark:text-gray-100">{&session.title}</div>
-                                        <div class="flex items-center justify-between">
-                                            <div class="text-xs text-gray-600 dark:text-gray-300">{time_ago}</div>
-                                            {if session.pinned {
-                                                html! { <i class="fas fa-thumbtack text-xs text-yellow-500"></i> }
-                                            } else {
-                                                html! {}
-                                            }}
-                                        </div>
-                                    </div>
-
-                                    // Delete button (visible on hover)
+                                        <i class="fas fa-trash mr-1" aria-hidden="true"></i>{"Delete"}
+                                    </button>
+                                </div>
+                                <div class="flex gap-1">
+                                    <input
+                                        type="text"
+                                        value={(*tag_input).clone()}
+                                        oninput={on_tag_input}
+                                        placeholder="Tag"
+                                        aria-label="Tag to add"
+                                        class="flex-1 min-w-0 px-2 py-1 text-xs border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                                    />
                                     <button
-                                        onclick={delete_handler}
-                                        class="absolute right-1 top-1 w-6 h-6 rounded-full bg-red-100 dark:bg-red-900/30 text-red-600 dark:text-red-400 opacity-0 group-hover:opacity-100 transition-opacity hover:bg-red-200 dark:hover:bg-red-900/50 flex items-center justify-center"
-                                        title="Delete session"
+                                        onclick={bulk(BulkAction::Tag(String::new()))}
+                                        disabled={none_selected || tag_input.trim().is_empty()}
+                                        class={action_class}
                                     >
-                                        <i class="fas fa-times text-xs"></i>
+                                        <i class="fas fa-tag mr-1" aria-hidden="true"></i>{"Tag"}
                                     </button>
-                                </li>
-                            }
-                        })}
+                                </div>
+                                <div class="flex gap-1">
+                                    <select
+                                        onchange={on_merge_order_change}
+                                        aria-label="Merge order"
+                                        class="flex-1 min-w-0 px-2 py-1 text-xs border border-gray-300 dark:border-gray-600 rounded bg-white dark:bg-gray-700 text-gray-900 dark:text-gray-100"
+                                    >
+                                        {for MergeOrder::ALL.iter().map(|order| html! {
+                                            <option value={order.key()} selected={*order == *merge_order}>{order.label()}</option>
+                                        })}
+                                    </select>
+                                    <button
+                                        onclick={bulk(BulkAction::Merge(MergeOrder::default()))}
+                                        disabled={selected_ids.len() < 2}
+                                        class={action_class}
+                                        title="Make a new session from the selected ones; they are kept"
+                                    >
+                                        <i class="fas fa-code-merge mr-1" aria-hidden="true"></i>{"Merge"}
+                                    </button>
+                                </div>
+                            </div>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    <ul class="space-y-2" role="listbox" aria-labelledby="sessions-heading" aria-multiselectable={selecting.to_string()}>
+                        {for visible.iter().map(render_session)}
                         {if sessions_vec.is_empty() {
                             html! {
                                 <li class="p-4 text-center text-gray-600 dark:text-gray-300">
@@ -119,27 +631,152 @@ pub fn sidebar(props: &SidebarProps) -> Html {
                                     <p class="text-sm">{"Click + to create one"}</p>
                                 </li>
                             }
+                        } else if visible.is_empty() && archived.is_empty() {
+                            html! {
+                                <li class="p-4 text-center text-sm text-gray-600 dark:text-gray-300">
+                                    {"No sessions match"}
+                                </li>
+                            }
                         } else {
                             html! {}
                         }}
                     </ul>
+                    {if archived.is_empty() {
+                        html! {}
+                    } else {
+                        html! {
+                            <div class="mt-4">
+                                <button
+                                    onclick={toggle_archived}
+                                    class="w-full flex items-center gap-2 text-sm font-semibold text-gray-600 dark:text-gray-300 hover:text-gray-900 dark:hover:text-gray-100"
+                                    aria-expanded={show_archived.to_string()}
+                                    aria-controls="archived-sessions"
+                                >
+                                    <i class={classes!("fas", "text-xs", if *show_archived { "fa-chevron-down" } else { "fa-chevron-right" })} aria-hidden="true"></i>
+                                    {format!("Archived ({})", archived.len())}
+                                </button>
+                                {if *show_archived {
+                                    html! {
+                                        <ul id="archived-sessions" class="space-y-2 mt-2" role="listbox" aria-label="Archived sessions">
+                                            {for archived.iter().map(render_session)}
+                                        </ul>
+                                    }
+                                } else {
+                                    html! {}
+                                }}
+                            </div>
+                        }
+                    }}
                 </div>
             </div>
 
-            // Settings Button
-            <div class="p-4 border-t border-gray-200 dark:border-gray-600">
+            // Tool and Settings Buttons
+            <div class="p-4 border-t border-gray-200 dark:border-gray-600 space-y-2">
+                <button
+                    onclick={
+                        let on_bookmarks = props.on_bookmarks.clone();
+                        Callback::from(move |_| on_bookmarks.emit(()))
+                    }
+                    class="w-full py-2 px-4 bg-gray-100 dark:bg-gray-700 rounded-md hover:bg-gray-200 dark:hover:bg-gray-600 flex items-center justify-center text-gray-900 dark:text-gray-100"
+                >
+                    <i class="fas fa-bookmark mr-2" aria-hidden="true"></i> {"Bookmarks"}
+                </button>
+                <button
+                    onclick={
+                        let on_compare = props.on_compare.clone();
+                        Callback::from(move |_| on_compare.emit(()))
+                    }
+                    class="w-full py-2 px-4 bg-gray-100 dark:bg-gray-700 rounded-md hover:bg-gray-200 dark:hover:bg-gray-600 flex items-center justify-center text-gray-900 dark:text-gray-100"
+                >
+                    <i class="fas fa-columns mr-2" aria-hidden="true"></i> {"Compare Models"}
+                </button>
+                <button
+                    onclick={
+                        let on_dialogue = props.on_dialogue.clone();
+                        Callback::from(move |_| on_dialogue.emit(()))
+                    }
+                    class="w-full py-2 px-4 bg-gray-100 dark:bg-gray-700 rounded-md hover:bg-gray-200 dark:hover:bg-gray-600 flex items-center justify-center text-gray-900 dark:text-gray-100"
+                >
+                    <i class="fas fa-comments mr-2" aria-hidden="true"></i> {"Model Dialogue"}
+                </button>
+                <button
+                    onclick={
+                        let on_evals = props.on_evals.clone();
+                        Callback::from(move |_| on_evals.emit(()))
+                    }
+                    class="w-full py-2 px-4 bg-gray-100 dark:bg-gray-700 rounded-md hover:bg-gray-200 dark:hover:bg-gray-600 flex items-center justify-center text-gray-900 dark:text-gray-100"
+                >
+                    <i class="fas fa-vial mr-2" aria-hidden="true"></i> {"Eval Suites"}
+                </button>
+                <button
+                    onclick={
+                        let on_ab_experiment = props.on_ab_experiment.clone();
+                        Callback::from(move |_| on_ab_experiment.emit(()))
+                    }
+                    class="w-full py-2 px-4 bg-gray-100 dark:bg-gray-700 rounded-md hover:bg-gray-200 dark:hover:bg-gray-600 flex items-center justify-center text-gray-900 dark:text-gray-100"
+                >
+                    <i class="fas fa-flask mr-2" aria-hidden="true"></i> {"A/B Prompts"}
+                </button>
+                <button
+                    onclick={
+                        let on_knowledge = props.on_knowledge.clone();
+                        Callback::from(move |_| on_knowledge.emit(()))
+                    }
+                    class="w-full py-2 px-4 bg-gray-100 dark:bg-gray-700 rounded-md hover:bg-gray-200 dark:hover:bg-gray-600 flex items-center justify-center text-gray-900 dark:text-gray-100"
+                >
+                    <i class="fas fa-book mr-2" aria-hidden="true"></i> {"Knowledge"}
+                </button>
+                <button
+                    onclick={
+                        let on_fine_tune = props.on_fine_tune.clone();
+                        Callback::from(move |_| on_fine_tune.emit(()))
+                    }
+                    class="w-full py-2 px-4 bg-gray-100 dark:bg-gray-700 rounded-md hover:bg-gray-200 dark:hover:bg-gray-600 flex items-center justify-center text-gray-900 dark:text-gray-100"
+                >
+                    <i class="fas fa-graduation-cap mr-2" aria-hidden="true"></i> {"Fine-tune Export"}
+                </button>
+                <button
+                    onclick={
+                        let on_diagnostics = props.on_diagnostics.clone();
+                        Callback::from(move |_| on_diagnostics.emit(()))
+                    }
+                    class="w-full py-2 px-4 bg-gray-100 dark:bg-gray-700 rounded-md hover:bg-gray-200 dark:hover:bg-gray-600 flex items-center justify-center text-gray-900 dark:text-gray-100"
+                >
+                    <i class="fas fa-stethoscope mr-2" aria-hidden="true"></i> {"Diagnostics"}
+                </button>
                 <button
                     onclick={on_settings_click}
                     class="w-full py-2 px-4 bg-gray-100 dark:bg-gray-700 rounded-md hover:bg-gray-200 dark:hover:bg-gray-600 flex items-center justify-center text-gray-900 dark:text-gray-100"
                 >
-                    <i class="fas fa-cog mr-2"></i> {"Settings"}
+                    <i class="fas fa-cog mr-2" aria-hidden="true"></i> {"Settings"}
                 </button>
             </div>
-        </div>
+        </nav>
     }
 }
 
-fn format_time_ago(timestamp: f64) -> String {
+/// Move keyboard focus to the previous or next session item in the list
+fn focus_sibling_session(e: &KeyboardEvent, forward: bool) {
+    let Some(current) = e.target().and_then(|t| t.dyn_into::<HtmlElement>().ok()) else {
+        return;
+    };
+    let Some(item) = current.parent_element() else {
+        return;
+    };
+    let sibling = if forward {
+        item.next_element_sibling()
+    } else {
+        item.previous_element_sibling()
+    };
+    if let Some(target) = sibling
+        .and_then(|li| li.query_selector("[data-session-item]").ok().flatten())
+        .and_then(|el| el.dyn_into::<HtmlElement>().ok())
+    {
+        let _ = target.focus();
+    }
+}
+
+pub fn format_time_ago(timestamp: f64) -> String {
     let now = js_sys::Date::now();
     let diff = now - timestamp;
     let seconds = diff / 1000.0;
@@ -157,3 +794,54 @@ fn format_time_ago(timestamp: f64) -> String {
         format!("{} day{} ago", days, if days == 1 { "" } else { "s" })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_widths_outside_the_limits_are_clamped() {
+        assert_eq!(clamp_sidebar_width(3), MIN_SIDEBAR_WIDTH);
+        assert_eq!(clamp_sidebar_width(5000), MAX_SIDEBAR_WIDTH);
+        assert_eq!(clamp_sidebar_width(DEFAULT_SIDEBAR_WIDTH), DEFAULT_SIDEBAR_WIDTH);
+    }
+
+    fn session(id: &str, updated_at: f64, archived: bool) -> ChatSession {
+        ChatSession {
+            id: id.to_string(),
+            title: id.to_string(),
+            messages: Vec::new(),
+            created_at: 0.0,
+            updated_at,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: false,
+            tags: Vec::new(),
+            archived,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    #[test]
+    fn archived_sessions_are_listed_apart_in_recency_order() {
+        let sessions: Vec<(String, ChatSession)> =
+            [("a", 1.0, false), ("b", 4.0, true), ("c", 3.0, false), ("d", 2.0, true)]
+                .into_iter()
+                .map(|(id, updated_at, archived)| (id.to_string(), session(id, updated_at, archived)))
+                .collect();
+        let ordered = most_recent_first(sessions.iter().map(|(id, session)| (id, session)));
+        let ids: Vec<_> = ordered.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["b", "c", "d", "a"]);
+
+        let listed = ordered.into_iter().map(|(id, session)| (id, session, None)).collect();
+        let (archived, visible) = split_archived(listed);
+        let ids = |list: Vec<ListedSession>| list.into_iter().map(|(id, _, _)| id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(archived), ["b", "d"]);
+        assert_eq!(ids(visible), ["c", "a"]);
+    }
+}
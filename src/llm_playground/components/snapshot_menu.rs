@@ -0,0 +1,96 @@
+// Snapshot picker under the chat header: take a snapshot now or put an
+// earlier one back
+use super::sidebar::format_time_ago;
+use crate::llm_playground::{
+    session_snapshots::{self, SessionSnapshot, SnapshotReason},
+    ChatSession,
+};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct SnapshotMenuProps {
+    pub session: ChatSession,
+    pub on_restore: Callback<SessionSnapshot>,
+}
+
+#[function_component(SnapshotMenu)]
+pub fn snapshot_menu(props: &SnapshotMenuProps) -> Html {
+    let snapshots = use_state(Vec::<SessionSnapshot>::new);
+    {
+        let snapshots = snapshots.clone();
+        let key = (props.session.id.clone(), props.session.messages.len());
+        use_effect_with(key, move |(session_id, _)| {
+            snapshots.set(session_snapshots::load_snapshots(session_id));
+            || ()
+        });
+    }
+
+    let on_take = {
+        let snapshots = snapshots.clone();
+        let session = props.session.clone();
+        Callback::from(move |_: MouseEvent| {
+            session_snapshots::take_snapshot(&session, SnapshotReason::Manual);
+            snapshots.set(session_snapshots::load_snapshots(&session.id));
+        })
+    };
+
+    html! {
+        <div
+            class="absolute right-0 top-full mt-2 w-80 z-20 p-3 bg-white dark:bg-gray-800 border border-gray-200 dark:border-gray-600 rounded-lg shadow-lg text-sm"
+            role="dialog"
+            aria-label="Snapshots"
+        >
+            <div class="flex items-center justify-between mb-2">
+                <div class="font-semibold text-gray-900 dark:text-gray-100">{"Snapshots"}</div>
+                <button
+                    onclick={on_take}
+                    class="px-2 py-1 text-xs rounded bg-primary-600 text-white hover:bg-primary-700"
+                >
+                    <i class="fas fa-camera mr-1" aria-hidden="true"></i>{"Take snapshot"}
+                </button>
+            </div>
+            {if snapshots.is_empty() {
+                html! {
+                    <div class="text-gray-500 dark:text-gray-400">
+                        {format!(
+                            "No snapshots yet. One is taken every {} messages and before messages are removed.",
+                            session_snapshots::SNAPSHOT_INTERVAL
+                        )}
+                    </div>
+                }
+            } else {
+                html! {
+                    <ul class="space-y-1 max-h-72 overflow-y-auto custom-scrollbar">
+                        {for snapshots.iter().rev().map(|snapshot| {
+                            let on_restore = {
+                                let on_restore = props.on_restore.clone();
+                                let snapshot = snapshot.clone();
+                                Callback::from(move |_: MouseEvent| on_restore.emit(snapshot.clone()))
+                            };
+                            let count = snapshot.session.messages.len();
+                            html! {
+                                <li key={snapshot.taken_at.to_string()} class="flex items-center justify-between gap-2 p-2 rounded-md bg-gray-50 dark:bg-gray-700">
+                                    <div class="min-w-0">
+                                        <div class="text-gray-900 dark:text-gray-100">
+                                            {format!("{} message{}", count, if count == 1 { "" } else { "s" })}
+                                        </div>
+                                        <div class="text-xs text-gray-500 dark:text-gray-400 truncate">
+                                            {format!("{} · {}", snapshot.reason.label(), format_time_ago(snapshot.taken_at))}
+                                        </div>
+                                    </div>
+                                    <button
+                                        onclick={on_restore}
+                                        class="shrink-0 px-2 py-1 text-xs rounded border border-gray-300 dark:border-gray-600 text-gray-800 dark:text-gray-200 hover:bg-gray-100 dark:hover:bg-gray-600"
+                                        title="Replace the conversation with this snapshot; the current one is snapshotted first"
+                                    >
+                                        {"Restore"}
+                                    </button>
+                                </li>
+                            }
+                        })}
+                    </ul>
+                }
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,65 @@
+// Clickable prompts above the input bar of an empty session: the configured
+// starters, then the ones the model suggested for the system prompt
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct StarterPromptsProps {
+    pub starters: Vec<String>,
+    #[prop_or_default]
+    pub generated: Vec<String>,
+    /// Suggestions are still being generated
+    #[prop_or_default]
+    pub is_generating: bool,
+    /// Send the prompt
+    pub on_pick: Callback<String>,
+}
+
+#[function_component(StarterPrompts)]
+pub fn starter_prompts(props: &StarterPromptsProps) -> Html {
+    if props.starters.is_empty() && props.generated.is_empty() && !props.is_generating {
+        return html! {};
+    }
+
+    let chip = |prompt: &String, generated: bool| {
+        let on_click = {
+            let on_pick = props.on_pick.clone();
+            let prompt = prompt.clone();
+            Callback::from(move |_: MouseEvent| on_pick.emit(prompt.clone()))
+        };
+        html! {
+            <button
+                key={prompt.clone()}
+                onclick={on_click}
+                class="px-3 py-2 text-sm text-left rounded-lg border border-gray-200 dark:border-gray-600 bg-white dark:bg-gray-800 text-gray-800 dark:text-gray-200 hover:border-primary-500 hover:bg-primary-50 dark:hover:bg-primary-900/20"
+                title={if generated { "Suggested for this system prompt" } else { "Send this prompt" }}
+            >
+                {if generated {
+                    html! { <i class="fas fa-wand-magic-sparkles mr-2 text-primary-500" aria-hidden="true"></i> }
+                } else {
+                    html! {}
+                }}
+                {prompt}
+            </button>
+        }
+    };
+
+    html! {
+        <div class="px-4 pt-3 border-t border-gray-200 dark:border-gray-700" aria-label="Conversation starters">
+            <div class="text-xs font-medium text-gray-500 dark:text-gray-400 mb-2">{"Try one of these"}</div>
+            <div class="flex flex-wrap gap-2">
+                {for props.starters.iter().map(|prompt| chip(prompt, false))}
+                {for props.generated.iter().map(|prompt| chip(prompt, true))}
+                {if props.is_generating {
+                    html! {
+                        <span class="px-3 py-2 text-sm text-gray-500 dark:text-gray-400" role="status">
+                            <i class="fas fa-spinner fa-spin mr-2" aria-hidden="true"></i>
+                            {"Suggesting more…"}
+                        </span>
+                    }
+                } else {
+                    html! {}
+                }}
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,121 @@
+use super::notification::{NotificationMessage, NotificationType};
+use crate::llm_playground::{
+    event_bus::PlaygroundEvent,
+    hooks::{use_event_bus, use_focus_trap},
+    html_export,
+    storage_recovery::QuarantinedPayload,
+};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct StorageRecoveryDialogProps {
+    pub payloads: Vec<QuarantinedPayload>,
+    pub on_import: Callback<QuarantinedPayload>,
+    /// Called with the quarantine key
+    pub on_discard: Callback<String>,
+    pub on_close: Callback<()>,
+}
+
+/// Lists stored data that couldn't be read on load. Each payload can be
+/// downloaded as is, imported as far as it reads, or discarded; closing the
+/// dialog leaves them in quarantine for the next load.
+#[function_component(StorageRecoveryDialog)]
+pub fn storage_recovery_dialog(props: &StorageRecoveryDialogProps) -> Html {
+    let dialog_ref = use_node_ref();
+    use_focus_trap(dialog_ref.clone(), true, props.on_close.clone());
+    let on_notification = use_event_bus().callback(PlaygroundEvent::Notify);
+
+    let rows = props.payloads.iter().map(|payload| {
+        let on_download = {
+            let payload = payload.clone();
+            let on_notification = on_notification.clone();
+            Callback::from(move |_: MouseEvent| {
+                if let Err(error) = html_export::download_file(&payload.file_name(), &payload.text, "application/json") {
+                    on_notification.emit(NotificationMessage::new(error, NotificationType::Error));
+                }
+            })
+        };
+        let on_import = {
+            let payload = payload.clone();
+            let callback = props.on_import.clone();
+            Callback::from(move |_: MouseEvent| callback.emit(payload.clone()))
+        };
+        let on_discard = {
+            let quarantine_key = payload.quarantine_key.clone();
+            let callback = props.on_discard.clone();
+            Callback::from(move |_: MouseEvent| callback.emit(quarantine_key.clone()))
+        };
+
+        html! {
+            <li key={payload.quarantine_key.clone()} class="flex items-center gap-2 py-3">
+                <div class="mr-auto min-w-0">
+                    <div class="text-sm font-medium text-gray-900 dark:text-white">{payload.label()}</div>
+                    <div class="text-xs text-gray-600 dark:text-gray-400 truncate">
+                        {format!("{} · {} bytes", payload.quarantine_key, payload.text.len())}
+                    </div>
+                </div>
+                <button
+                    onclick={on_download}
+                    class="px-3 py-1.5 text-sm rounded-md text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700"
+                >
+                    <i class="fas fa-download mr-1" aria-hidden="true"></i>
+                    {"Download"}
+                </button>
+                {if payload.can_import() {
+                    html! {
+                        <button
+                            onclick={on_import}
+                            class="px-3 py-1.5 text-sm rounded-md bg-primary-600 hover:bg-primary-700 text-white"
+                        >
+                            {"Import what can be read"}
+                        </button>
+                    }
+                } else {
+                    html! {}
+                }}
+                <button
+                    onclick={on_discard}
+                    class="px-3 py-1.5 text-sm rounded-md text-red-600 dark:text-red-400 hover:bg-red-50 dark:hover:bg-gray-700"
+                >
+                    {"Discard"}
+                </button>
+            </li>
+        }
+    });
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div
+                ref={dialog_ref}
+                role="alertdialog"
+                aria-modal="true"
+                aria-labelledby="storage-recovery-title"
+                class="bg-white dark:bg-gray-800 rounded-lg shadow-xl max-w-xl w-full mx-4 p-6"
+            >
+                <h2 id="storage-recovery-title" class="text-lg font-semibold text-gray-900 dark:text-white mb-1">
+                    <i class="fas fa-triangle-exclamation mr-2" aria-hidden="true"></i>
+                    {"Some stored data couldn't be read"}
+                </h2>
+                <p class="text-sm text-gray-700 dark:text-gray-300 mb-2">
+                    {"It was set aside and defaults are in use. Download it to keep a copy, or import the parts that still read."}
+                </p>
+                <ul class="divide-y divide-gray-200 dark:divide-gray-700 mb-4">
+                    {for rows}
+                </ul>
+                <div class="flex justify-end">
+                    <button
+                        onclick={on_close}
+                        class="px-4 py-2 text-sm rounded-md text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700"
+                    >
+                        {"Later"}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,378 @@
+use crate::llm_playground::hooks::use_focus_trap;
+use crate::llm_playground::structured_output::{fields_to_schema, schema_to_fields, SchemaField};
+use crate::llm_playground::StructuredOutput;
+use std::rc::Rc;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+/// Nesting depth below which object fields can't get child fields
+const MAX_DEPTH: usize = 3;
+
+/// The field list at `path`: the top-level fields for an empty path, otherwise
+/// the children of the field the path points to
+fn fields_at_mut<'a>(fields: &'a mut Vec<SchemaField>, path: &[usize]) -> &'a mut Vec<SchemaField> {
+    match path.split_first() {
+        None => fields,
+        Some((index, rest)) => fields_at_mut(&mut fields[*index].children, rest),
+    }
+}
+
+type FieldChange = Rc<dyn Fn(&mut Vec<SchemaField>)>;
+
+/// Change to the field list at a path
+type FieldUpdate = Callback<(Vec<usize>, FieldChange)>;
+
+#[derive(Properties, PartialEq)]
+pub struct StructuredOutputEditorProps {
+    pub output: Option<StructuredOutput>,
+    pub on_save: Callback<StructuredOutput>,
+    pub on_cancel: Callback<()>,
+}
+
+#[function_component(StructuredOutputEditor)]
+pub fn structured_output_editor(props: &StructuredOutputEditorProps) -> Html {
+    let name = use_state(String::new);
+    let fields = use_state(|| vec![SchemaField::default()]);
+    let show_json_preview = use_state(|| false);
+    let dialog_ref = use_node_ref();
+
+    use_focus_trap(dialog_ref.clone(), true, props.on_cancel.clone());
+
+    // Initialize from the existing schema if editing
+    {
+        let name = name.clone();
+        let fields = fields.clone();
+        use_effect_with(props.output.clone(), move |output| {
+            if let Some(output) = output {
+                name.set(output.name.clone());
+                let parsed = schema_to_fields(&output.schema);
+                if !parsed.is_empty() {
+                    fields.set(parsed);
+                }
+            }
+            || ()
+        });
+    }
+
+    // Apply a change to the field list at `path`
+    let update = {
+        let fields = fields.clone();
+        Callback::from(move |(path, change): (Vec<usize>, FieldChange)| {
+            let mut new_fields = (*fields).clone();
+            change(fields_at_mut(&mut new_fields, &path));
+            fields.set(new_fields);
+        })
+    };
+
+    let has_field = fields.iter().any(|f| !f.name.trim().is_empty());
+    let can_save = !name.trim().is_empty() && has_field;
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div
+                ref={dialog_ref}
+                role="dialog"
+                aria-modal="true"
+                aria-labelledby="structured-output-editor-title"
+                class="bg-white dark:bg-gray-800 rounded-lg w-full max-w-4xl max-h-[90vh] overflow-hidden flex flex-col"
+            >
+                // Header
+                <div class="flex justify-between items-center p-6 border-b border-gray-200 dark:border-gray-700">
+                    <h3 id="structured-output-editor-title" class="text-xl font-semibold text-gray-900 dark:text-gray-100">
+                        {if props.output.is_some() { "Edit Structured Output" } else { "Create Structured Output" }}
+                    </h3>
+                    <button
+                        onclick={
+                            let on_cancel = props.on_cancel.clone();
+                            Callback::from(move |_| on_cancel.emit(()))
+                        }
+                        class="text-gray-500 hover:text-gray-700 dark:text-gray-400 dark:hover:text-gray-200"
+                        aria-label="Close editor"
+                    >
+                        <i class="fas fa-times text-xl" aria-hidden="true"></i>
+                    </button>
+                </div>
+
+                // Content - scrollable
+                <div class="flex-1 overflow-y-auto p-6 space-y-6">
+                    <div class="bg-gray-50 dark:bg-gray-700 p-4 rounded-lg">
+                        <label class="block text-sm font-medium mb-2 text-gray-700 dark:text-gray-300">{"Schema Name"}</label>
+                        <input
+                            type="text"
+                            value={(*name).clone()}
+                            oninput={
+                                let name = name.clone();
+                                Callback::from(move |e: InputEvent| {
+                                    let input: HtmlInputElement = e.target_unchecked_into();
+                                    name.set(input.value());
+                                })
+                            }
+                            placeholder="e.g., sentiment_report, extracted_contact"
+                            class="w-full p-3 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-gray-900 dark:text-gray-100"
+                        />
+                        <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">{"Letters, digits and underscores; providers reject other characters"}</p>
+                    </div>
+
+                    <div class="bg-gray-50 dark:bg-gray-700 p-4 rounded-lg">
+                        <h4 class="text-lg font-medium mb-4 flex items-center text-gray-900 dark:text-gray-100">
+                            <i class="fas fa-sitemap text-green-500 mr-2" aria-hidden="true"></i>
+                            {"Fields"}
+                        </h4>
+                        {render_fields(&fields, Vec::new(), &update)}
+                    </div>
+
+                    // JSON Preview Section (Optional Toggle)
+                    <div class="bg-gray-50 dark:bg-gray-700 p-4 rounded-lg">
+                        <div class="flex items-center justify-between mb-4">
+                            <h4 class="text-lg font-medium flex items-center text-gray-900 dark:text-gray-100">
+                                <i class="fas fa-code text-gray-500 mr-2" aria-hidden="true"></i>
+                                {"JSON Schema Preview"}
+                            </h4>
+                            <button
+                                onclick={
+                                    let show_json_preview = show_json_preview.clone();
+                                    Callback::from(move |_| show_json_preview.set(!*show_json_preview))
+                                }
+                                class="text-sm px-3 py-1 bg-gray-200 dark:bg-gray-600 rounded-md hover:bg-gray-300 dark:hover:bg-gray-500"
+                            >
+                                {if *show_json_preview { "Hide" } else { "Show" }}
+                            </button>
+                        </div>
+                        {if *show_json_preview {
+                            html! {
+                                <pre class="bg-gray-800 text-green-400 p-4 rounded-md text-xs overflow-x-auto">
+                                    {serde_json::to_string_pretty(&fields_to_schema(&fields)).unwrap_or_default()}
+                                </pre>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                    </div>
+                </div>
+
+                // Footer
+                <div class="flex justify-end space-x-2 p-6 border-t border-gray-200 dark:border-gray-700">
+                    <button
+                        onclick={
+                            let on_cancel = props.on_cancel.clone();
+                            Callback::from(move |_| on_cancel.emit(()))
+                        }
+                        class="px-4 py-2 border border-gray-300 dark:border-gray-600 rounded-md hover:bg-gray-50 dark:hover:bg-gray-700"
+                    >
+                        {"Cancel"}
+                    </button>
+                    <button
+                        onclick={
+                            let name = name.clone();
+                            let fields = fields.clone();
+                            let on_save = props.on_save.clone();
+                            Callback::from(move |_| {
+                                if name.trim().is_empty() {
+                                    return;
+                                }
+                                on_save.emit(StructuredOutput {
+                                    name: name.trim().to_string(),
+                                    schema: fields_to_schema(&fields),
+                                });
+                            })
+                        }
+                        disabled={!can_save}
+                        class={classes!(
+                            "px-4", "py-2", "rounded-md", "text-white",
+                            if can_save { "bg-primary-600 hover:bg-primary-700" } else { "bg-gray-400 cursor-not-allowed" }
+                        )}
+                    >
+                        {"Save"}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Rows for the fields at `path`, with child rows for object fields
+fn render_fields(all_fields: &[SchemaField], path: Vec<usize>, update: &FieldUpdate) -> Html {
+    let mut fields = all_fields;
+    for index in &path {
+        fields = &fields[*index].children;
+    }
+    let depth = path.len();
+
+    let change = |index: usize, apply: fn(&mut SchemaField, String)| {
+        let update = update.clone();
+        let path = path.clone();
+        move |value: String| {
+            update.emit((
+                path.clone(),
+                Rc::new(move |fields: &mut Vec<SchemaField>| {
+                    apply(&mut fields[index], value.clone())
+                }),
+            ))
+        }
+    };
+
+    html! {
+        <div class={classes!("space-y-3", (depth > 0).then_some("pl-4 border-l-2 border-gray-200 dark:border-gray-600"))}>
+            {for fields.iter().enumerate().map(|(index, field)| {
+                let on_name = change(index, |f, v| f.name = v);
+                let on_type = change(index, |f, v| f.field_type = v);
+                let on_items_type = change(index, |f, v| f.items_type = v);
+                let on_description = change(index, |f, v| f.description = v);
+                let on_enum = change(index, |f, v| {
+                    f.enum_values = v
+                        .split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                });
+                let on_required = change(index, |f, v| f.required = v == "true");
+                let on_delete = {
+                    let update = update.clone();
+                    let path = path.clone();
+                    Callback::from(move |_: MouseEvent| {
+                        update.emit((path.clone(), Rc::new(move |fields: &mut Vec<SchemaField>| {
+                            fields.remove(index);
+                        })))
+                    })
+                };
+                let has_children = field.field_type == "object"
+                    || (field.field_type == "array" && field.items_type == "object");
+
+                html! {
+                    <div key={index} class="border border-gray-200 dark:border-gray-600 rounded-md p-3 bg-white dark:bg-gray-800">
+                        <div class="grid grid-cols-1 md:grid-cols-12 gap-2 items-center">
+                            <input
+                                type="text"
+                                value={field.name.clone()}
+                                oninput={Callback::from(move |e: InputEvent| {
+                                    let input: HtmlInputElement = e.target_unchecked_into();
+                                    on_name(input.value());
+                                })}
+                                placeholder="field_name"
+                                aria-label="Field name"
+                                class="md:col-span-3 p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-sm text-gray-900 dark:text-gray-100"
+                            />
+                            <select
+                                onchange={Callback::from(move |e: Event| {
+                                    let input: HtmlInputElement = e.target_unchecked_into();
+                                    on_type(input.value());
+                                })}
+                                aria-label="Field type"
+                                class="md:col-span-2 p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-sm text-gray-900 dark:text-gray-100"
+                            >
+                                {for type_options(&field.field_type, depth + 1 < MAX_DEPTH, true)}
+                            </select>
+                            {if field.field_type == "array" {
+                                html! {
+                                    <select
+                                        onchange={Callback::from(move |e: Event| {
+                                            let input: HtmlInputElement = e.target_unchecked_into();
+                                            on_items_type(input.value());
+                                        })}
+                                        aria-label="Element type"
+                                        title="Element type"
+                                        class="md:col-span-2 p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-sm text-gray-900 dark:text-gray-100"
+                                    >
+                                        {for type_options(&field.items_type, depth + 1 < MAX_DEPTH, false)}
+                                    </select>
+                                }
+                            } else if field.field_type == "string" {
+                                html! {
+                                    <input
+                                        type="text"
+                                        value={field.enum_values.join(", ")}
+                                        onchange={Callback::from(move |e: Event| {
+                                            let input: HtmlInputElement = e.target_unchecked_into();
+                                            on_enum(input.value());
+                                        })}
+                                        placeholder="allowed values, comma separated"
+                                        aria-label="Allowed values"
+                                        class="md:col-span-2 p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-sm text-gray-900 dark:text-gray-100"
+                                    />
+                                }
+                            } else {
+                                html! { <div class="md:col-span-2"></div> }
+                            }}
+                            <input
+                                type="text"
+                                value={field.description.clone()}
+                                oninput={Callback::from(move |e: InputEvent| {
+                                    let input: HtmlInputElement = e.target_unchecked_into();
+                                    on_description(input.value());
+                                })}
+                                placeholder="Description"
+                                aria-label="Field description"
+                                class="md:col-span-3 p-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-800 text-sm text-gray-900 dark:text-gray-100"
+                            />
+                            <label class="md:col-span-1 flex items-center text-xs text-gray-700 dark:text-gray-300">
+                                <input
+                                    type="checkbox"
+                                    checked={field.required}
+                                    onchange={Callback::from(move |e: Event| {
+                                        let input: HtmlInputElement = e.target_unchecked_into();
+                                        on_required(input.checked().to_string());
+                                    })}
+                                    class="mr-1"
+                                />
+                                {"Required"}
+                            </label>
+                            <button
+                                onclick={on_delete}
+                                class="md:col-span-1 text-red-500 hover:text-red-700 text-sm justify-self-end"
+                                title="Delete field"
+                                aria-label={format!("Delete field {}", index + 1)}
+                            >
+                                <i class="fas fa-trash" aria-hidden="true"></i>
+                            </button>
+                        </div>
+                        {if has_children && depth + 1 < MAX_DEPTH {
+                            let mut child_path = path.clone();
+                            child_path.push(index);
+                            html! {
+                                <div class="mt-3">
+                                    {render_fields(all_fields, child_path, update)}
+                                </div>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                    </div>
+                }
+            })}
+            <button
+                onclick={
+                    let update = update.clone();
+                    let path = path.clone();
+                    Callback::from(move |_: MouseEvent| {
+                        update.emit((path.clone(), Rc::new(|fields: &mut Vec<SchemaField>| {
+                            fields.push(SchemaField::default());
+                        })))
+                    })
+                }
+                class="w-full p-2 border-2 border-dashed border-gray-300 dark:border-gray-600 rounded-md text-sm text-gray-500 dark:text-gray-400 hover:border-primary-500 hover:text-primary-500 transition-colors"
+            >
+                <i class="fas fa-plus mr-2" aria-hidden="true"></i>
+                {if depth == 0 { "Add Field" } else { "Add Nested Field" }}
+            </button>
+        </div>
+    }
+}
+
+/// Type choices for a field or array element; nested objects only while the
+/// depth limit allows, arrays of arrays never
+fn type_options(selected: &str, allow_object: bool, allow_array: bool) -> Vec<Html> {
+    [
+        ("string", "Text (string)"),
+        ("number", "Number"),
+        ("integer", "Whole number (integer)"),
+        ("boolean", "True/False (boolean)"),
+        ("array", "List (array)"),
+        ("object", "Object"),
+    ]
+    .into_iter()
+    .filter(|(value, _)| (allow_object || *value != "object") && (allow_array || *value != "array"))
+    .map(|(value, label)| {
+        html! { <option value={value} selected={selected == value}>{label}</option> }
+    })
+    .collect()
+}
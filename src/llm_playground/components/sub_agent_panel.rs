@@ -0,0 +1,95 @@
+use crate::llm_playground::sub_agent::{SubAgentRun, SubAgentStatus, SubAgentStep};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct SubAgentPanelProps {
+    pub run: SubAgentRun,
+}
+
+/// Collapsible transcript of a Task tool sub-agent, open while it is running
+#[function_component(SubAgentPanel)]
+pub fn sub_agent_panel(props: &SubAgentPanelProps) -> Html {
+    let run = &props.run;
+    let (status_icon, status_label, status_class) = match run.status {
+        SubAgentStatus::Running => (
+            "fas fa-spinner fa-spin",
+            "Running",
+            "text-blue-700 dark:text-blue-300 bg-blue-100 dark:bg-blue-900/50",
+        ),
+        SubAgentStatus::Completed => (
+            "fas fa-check",
+            "Completed",
+            "text-green-700 dark:text-green-300 bg-green-100 dark:bg-green-900/50",
+        ),
+        SubAgentStatus::Failed => (
+            "fas fa-times",
+            "Failed",
+            "text-red-700 dark:text-red-300 bg-red-100 dark:bg-red-900/50",
+        ),
+    };
+    let tool_calls = run
+        .steps
+        .iter()
+        .filter(|step| matches!(step, SubAgentStep::ToolCall { .. }))
+        .count();
+
+    html! {
+        <details
+            class="mb-3 rounded-md border border-indigo-200 dark:border-indigo-700 bg-white dark:bg-gray-800"
+            open={run.status == SubAgentStatus::Running}
+        >
+            <summary class="cursor-pointer select-none px-3 py-2 flex items-center flex-wrap gap-2 text-sm">
+                <i class="fas fa-robot text-indigo-600 dark:text-indigo-400" aria-hidden="true"></i>
+                <span class="font-medium text-gray-800 dark:text-gray-200">
+                    {if run.description.is_empty() { "Sub-agent task" } else { run.description.as_str() }}
+                </span>
+                <span class={classes!("inline-flex", "items-center", "px-2", "py-0.5", "rounded-full", "text-xs", status_class)}>
+                    <i class={classes!(status_icon, "mr-1")} aria-hidden="true"></i>
+                    {status_label}
+                </span>
+                <span class="text-xs text-gray-500 dark:text-gray-400">
+                    {format!("{} · {} · {} tool call{}", run.agent_type, run.model.replace(',', " / "), tool_calls, if tool_calls == 1 { "" } else { "s" })}
+                </span>
+            </summary>
+            <div class="px-3 pb-3 space-y-2 border-t border-indigo-100 dark:border-indigo-800">
+                {if run.steps.is_empty() {
+                    html! {
+                        <div class="pt-2 text-xs text-gray-500 dark:text-gray-400 italic">{"Waiting for the sub-agent..."}</div>
+                    }
+                } else {
+                    html! {
+                        <>{for run.steps.iter().map(render_step)}</>
+                    }
+                }}
+            </div>
+        </details>
+    }
+}
+
+fn render_step(step: &SubAgentStep) -> Html {
+    match step {
+        SubAgentStep::Message { content } => html! {
+            <div class="pt-2 text-sm text-gray-800 dark:text-gray-200 whitespace-pre-wrap">{content}</div>
+        },
+        SubAgentStep::ToolCall { name, arguments } => html! {
+            <div class="pt-2 text-xs">
+                <span class="font-medium text-blue-700 dark:text-blue-300">
+                    <i class="fas fa-cog mr-1" aria-hidden="true"></i>{name}
+                </span>
+                <pre class="mt-1 p-2 rounded bg-gray-50 dark:bg-gray-900 font-mono text-gray-700 dark:text-gray-300 overflow-x-auto">
+                    {serde_json::to_string_pretty(arguments).unwrap_or_default()}
+                </pre>
+            </div>
+        },
+        SubAgentStep::ToolResult { name, result } => html! {
+            <details class="text-xs">
+                <summary class="cursor-pointer text-green-700 dark:text-green-300">
+                    <i class="fas fa-reply mr-1" aria-hidden="true"></i>{format!("{} result", name)}
+                </summary>
+                <pre class="mt-1 p-2 rounded bg-gray-50 dark:bg-gray-900 font-mono text-gray-700 dark:text-gray-300 overflow-x-auto max-h-48">
+                    {serde_json::to_string_pretty(result).unwrap_or_default()}
+                </pre>
+            </details>
+        },
+    }
+}
@@ -0,0 +1,107 @@
+use crate::llm_playground::{TodoItem, TodoStatus};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct TodoPanelProps {
+    pub todos: Vec<TodoItem>,
+    /// Emits the id of the todo the user checked or unchecked
+    pub on_toggle: Callback<String>,
+    /// Checking items off is disabled while the model is responding
+    #[prop_or_default]
+    pub disabled: bool,
+}
+
+/// Live view of the session's TodoWrite task list
+#[function_component(TodoPanel)]
+pub fn todo_panel(props: &TodoPanelProps) -> Html {
+    let completed = props
+        .todos
+        .iter()
+        .filter(|t| t.status == TodoStatus::Completed)
+        .count();
+
+    let render_group = |status: TodoStatus, heading: &'static str| {
+        let items: Vec<&TodoItem> = props.todos.iter().filter(|t| t.status == status).collect();
+        if items.is_empty() {
+            return html! {};
+        }
+        html! {
+            <div class="mb-4">
+                <h3 class="text-xs font-semibold uppercase tracking-wide text-gray-500 dark:text-gray-400 mb-2">
+                    {format!("{} ({})", heading, items.len())}
+                </h3>
+                <ul class="space-y-1">
+                    {for items.into_iter().map(|item| render_item(item, &props.on_toggle, props.disabled))}
+                </ul>
+            </div>
+        }
+    };
+
+    html! {
+        <aside
+            class="w-64 flex-shrink-0 border-l border-gray-200 dark:border-gray-600 bg-white dark:bg-gray-800 flex flex-col"
+            aria-label="Task list"
+        >
+            <div class="p-4 border-b border-gray-200 dark:border-gray-600">
+                <h2 class="font-semibold text-gray-900 dark:text-gray-100">
+                    <i class="fas fa-tasks mr-2 text-primary-600 dark:text-primary-400" aria-hidden="true"></i>
+                    {"Tasks"}
+                </h2>
+                <p class="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                    {format!("{} of {} completed", completed, props.todos.len())}
+                </p>
+            </div>
+            <div class="flex-1 overflow-y-auto custom-scrollbar p-4">
+                {render_group(TodoStatus::InProgress, "In progress")}
+                {render_group(TodoStatus::Pending, "Pending")}
+                {render_group(TodoStatus::Completed, "Completed")}
+            </div>
+        </aside>
+    }
+}
+
+fn render_item(item: &TodoItem, on_toggle: &Callback<String>, disabled: bool) -> Html {
+    let is_completed = item.status == TodoStatus::Completed;
+    let onchange = {
+        let on_toggle = on_toggle.clone();
+        let id = item.id.clone();
+        Callback::from(move |_: Event| on_toggle.emit(id.clone()))
+    };
+    let priority_class = match item.priority.as_str() {
+        "high" => "text-red-600 dark:text-red-400",
+        "medium" => "text-yellow-600 dark:text-yellow-400",
+        _ => "text-gray-400 dark:text-gray-500",
+    };
+
+    html! {
+        <li key={item.id.clone()}>
+            <label class="flex items-start space-x-2 text-sm cursor-pointer">
+                <input
+                    type="checkbox"
+                    class="mt-1 rounded"
+                    checked={is_completed}
+                    {disabled}
+                    {onchange}
+                />
+                <span class={classes!(
+                    "flex-1",
+                    if is_completed { "line-through text-gray-400 dark:text-gray-500" } else { "text-gray-800 dark:text-gray-200" }
+                )}>
+                    {if item.status == TodoStatus::InProgress {
+                        html! { <i class="fas fa-spinner fa-spin mr-1 text-primary-500" aria-hidden="true"></i> }
+                    } else {
+                        html! {}
+                    }}
+                    {&item.content}
+                </span>
+                {if item.priority.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <i class={classes!("fas", "fa-flag", "text-xs", "mt-1", priority_class)} title={format!("{} priority", item.priority)}></i>
+                    }
+                }}
+            </label>
+        </li>
+    }
+}
@@ -0,0 +1,118 @@
+use crate::llm_playground::{
+    desktop_tools::{self, ApprovalRequest},
+    event_bus::PlaygroundEvent,
+    handoff_tools,
+    hooks::{use_event_bus, use_event_subscription, use_focus_trap},
+};
+use std::collections::VecDeque;
+use yew::prelude::*;
+
+/// Asks before a desktop tool touches the machine or a handoff tool copies or
+/// saves something, one call at a time. Requests arrive on the event bus; calls
+/// still waiting when the dialog goes away are denied.
+#[function_component(ToolApprovalDialog)]
+pub fn tool_approval_dialog() -> Html {
+    let queue = use_mut_ref(VecDeque::<ApprovalRequest>::new);
+    let update = use_force_update();
+    let bus = use_event_bus();
+    {
+        let queue = queue.clone();
+        let update = update.clone();
+        use_event_subscription(&bus, move |event| {
+            if let PlaygroundEvent::ToolApproval(request) = event {
+                queue.borrow_mut().push_back(request.clone());
+                update.force_update();
+            }
+        });
+    }
+    {
+        let queue = queue.clone();
+        use_effect_with((), move |_| {
+            move || {
+                for request in queue.borrow_mut().drain(..) {
+                    request.respond(false);
+                }
+            }
+        });
+    }
+
+    let current = queue.borrow().front().cloned();
+    let answer = {
+        let queue = queue.clone();
+        let update = update.clone();
+        move |approved: bool| {
+            let queue = queue.clone();
+            let update = update.clone();
+            Callback::from(move |_: ()| {
+                let request = queue.borrow_mut().pop_front();
+                if let Some(request) = request {
+                    request.respond(approved);
+                }
+                update.force_update();
+            })
+        }
+    };
+    let dialog_ref = use_node_ref();
+    use_focus_trap(dialog_ref.clone(), current.is_some(), answer(false));
+
+    let Some(request) = current else {
+        return html! {};
+    };
+    let waiting = queue.borrow().len() - 1;
+    let arguments = serde_json::to_string_pretty(&request.arguments).unwrap_or_default();
+    let handoff = handoff_tools::handles(&request.tool_name);
+    let (icon, description) = if handoff {
+        let icon = if request.tool_name == handoff_tools::SAVE_FILE_TOOL_NAME { "fas fa-download" } else { "fas fa-clipboard" };
+        (icon, handoff_tools::describe(&request.tool_name, &request.arguments))
+    } else {
+        ("fas fa-terminal", desktop_tools::describe(&request.tool_name, &request.arguments))
+    };
+
+    html! {
+        <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
+            <div
+                ref={dialog_ref}
+                role="alertdialog"
+                aria-modal="true"
+                aria-labelledby="tool-approval-title"
+                class="bg-white dark:bg-gray-800 rounded-lg shadow-xl max-w-xl w-full mx-4 p-6"
+            >
+                <h2 id="tool-approval-title" class="text-lg font-semibold text-gray-900 dark:text-white mb-1">
+                    <i class={classes!(icon, "mr-2")} aria-hidden="true"></i>
+                    {format!("Allow {}?", request.tool_name)}
+                </h2>
+                <p class="text-sm text-gray-700 dark:text-gray-300 mb-3 break-all">
+                    {description}
+                </p>
+                // Show what will be copied or saved without an extra click
+                <details class="mb-4" open={handoff}>
+                    <summary class="text-xs text-gray-600 dark:text-gray-400 cursor-pointer">{"Arguments"}</summary>
+                    <pre class="mt-2 p-3 max-h-64 overflow-auto custom-scrollbar rounded-md bg-gray-100 dark:bg-gray-900 text-xs text-gray-800 dark:text-gray-100">{arguments}</pre>
+                </details>
+                <div class="flex items-center justify-end gap-2">
+                    {if waiting > 0 {
+                        html! {
+                            <span class="mr-auto text-xs text-gray-600 dark:text-gray-400">
+                                {format!("{} more waiting", waiting)}
+                            </span>
+                        }
+                    } else {
+                        html! {}
+                    }}
+                    <button
+                        onclick={answer(false).reform(|_: MouseEvent| ())}
+                        class="px-4 py-2 text-sm rounded-md text-gray-700 dark:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700"
+                    >
+                        {"Deny"}
+                    </button>
+                    <button
+                        onclick={answer(true).reform(|_: MouseEvent| ())}
+                        class="px-4 py-2 text-sm rounded-md bg-primary-600 hover:bg-primary-700 text-white"
+                    >
+                        {"Allow"}
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
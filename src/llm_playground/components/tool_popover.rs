@@ -0,0 +1,120 @@
+// Button next to the composer that lists the function tools with what each
+// definition costs in tokens, and turns them on or off for the session. MCP
+// tools are listed under their server, with a switch for all of them.
+use crate::llm_playground::{context_usage, provider_config::FlexibleApiConfig};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ToolPopoverProps {
+    /// The session's config, tools as the next request sends them
+    pub config: FlexibleApiConfig,
+    /// Toggle a tool by name; the list is read-only when unset, e.g. while a
+    /// persona picks the session's tools
+    #[prop_or_default]
+    pub on_toggle: Option<Callback<String>>,
+    /// Turn all tools of an MCP server on or off
+    #[prop_or_default]
+    pub on_toggle_server: Option<Callback<(String, bool)>>,
+}
+
+#[function_component(ToolPopover)]
+pub fn tool_popover(props: &ToolPopoverProps) -> Html {
+    let show = use_state(|| false);
+    let enabled = props.config.get_enabled_function_tools().len();
+    let total = context_usage::tool_definition_tokens(&props.config);
+    let title = format!("{} tools enabled · ≈{} tokens of definitions", enabled, total);
+    let toggle = {
+        let show = show.clone();
+        Callback::from(move |_: MouseEvent| show.set(!*show))
+    };
+
+    html! {
+        <div class="relative">
+            <button
+                onclick={toggle}
+                class="p-2 rounded-md text-gray-500 dark:text-gray-400 hover:text-primary-600 dark:hover:text-primary-400 text-xs whitespace-nowrap"
+                title={title.clone()}
+                aria-label={title}
+                aria-expanded={show.to_string()}
+            >
+                <i class="fas fa-toolbox mr-1" aria-hidden="true"></i>
+                {enabled}
+            </button>
+            {if *show {
+                // Costs are only worked out while the list is open
+                let costs = context_usage::tool_costs(&props.config);
+                html! {
+                    <div class="absolute bottom-full right-0 mb-2 w-72 max-h-80 overflow-y-auto p-3 rounded-lg shadow-lg border border-gray-200 dark:border-gray-600 bg-white dark:bg-gray-800 z-20" role="dialog" aria-label="Function tools">
+                        <div class="flex justify-between text-xs text-gray-500 dark:text-gray-400 mb-2">
+                            <span>{format!("{} of {} enabled", enabled, props.config.function_tools.len())}</span>
+                            <span>{format!("≈{} tokens", total)}</span>
+                        </div>
+                        {if props.on_toggle.is_none() {
+                            html! {
+                                <p class="text-xs text-gray-500 dark:text-gray-400 mb-2">{"The session's persona picks its tools."}</p>
+                            }
+                        } else {
+                            html! {}
+                        }}
+                        {if props.config.function_tools.is_empty() {
+                            html! { <p class="text-sm text-gray-500 dark:text-gray-400">{"No function tools configured"}</p> }
+                        } else {
+                            html! {}
+                        }}
+                        {for props.config.function_tool_groups().into_iter().map(|(server, indices)| {
+                            let tools = indices.iter().map(|&index| {
+                                let tool = &props.config.function_tools[index];
+                                let onchange = props.on_toggle.clone().map(|on_toggle| {
+                                    let name = tool.name.clone();
+                                    Callback::from(move |_: Event| on_toggle.emit(name.clone()))
+                                });
+                                html! {
+                                    <li key={tool.name.clone()}>
+                                        <label class="flex items-center gap-2 text-sm text-gray-700 dark:text-gray-300" title={format!("{}\n{}", tool.name, tool.description)}>
+                                            <input
+                                                type="checkbox"
+                                                checked={tool.enabled}
+                                                disabled={onchange.is_none()}
+                                                {onchange}
+                                            />
+                                            <span class="truncate flex-1 font-mono text-xs">{props.config.tool_label(tool)}</span>
+                                            <span class="shrink-0 text-xs text-gray-500 dark:text-gray-400">{format!("≈{}", costs[index].1)}</span>
+                                        </label>
+                                    </li>
+                                }
+                            });
+                            let Some(server) = server else {
+                                return html! { <ul class="space-y-1 mb-2">{for tools}</ul> };
+                            };
+                            let enabled = indices.iter().filter(|&&index| props.config.function_tools[index].enabled).count();
+                            let all_enabled = enabled == indices.len();
+                            let onchange = props.on_toggle_server.clone().filter(|_| props.on_toggle.is_some()).map(|on_toggle_server| {
+                                let server = server.to_string();
+                                Callback::from(move |_: Event| on_toggle_server.emit((server.clone(), !all_enabled)))
+                            });
+                            html! {
+                                <div key={server.to_string()} class="mb-2">
+                                    <label class="flex items-center gap-2 text-xs font-medium text-gray-600 dark:text-gray-300 border-t border-gray-200 dark:border-gray-700 pt-2 mb-1">
+                                        <input
+                                            type="checkbox"
+                                            checked={all_enabled}
+                                            disabled={onchange.is_none()}
+                                            aria-label={format!("All tools of {}", server)}
+                                            {onchange}
+                                        />
+                                        <i class="fas fa-plug" aria-hidden="true"></i>
+                                        <span class="truncate flex-1">{server}</span>
+                                        <span class="shrink-0 text-gray-500 dark:text-gray-400">{format!("{}/{}", enabled, indices.len())}</span>
+                                    </label>
+                                    <ul class="space-y-1 pl-5">{for tools}</ul>
+                                </div>
+                            }
+                        })}
+                    </div>
+                }
+            } else {
+                html! {}
+            }}
+        </div>
+    }
+}
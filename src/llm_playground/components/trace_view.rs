@@ -0,0 +1,189 @@
+// Timeline of a session's tool calls, grouped by the model turn that asked for
+// them, with a waterfall of their timing and their arguments and results
+use crate::llm_playground::{
+    components::notification::{NotificationMessage, NotificationType},
+    event_bus::PlaygroundEvent,
+    hooks::use_event_bus,
+    html_export, session_recording,
+    trace::{self, TraceSpan, TraceStatus, TraceTurn},
+    trace_export, ChatSession,
+};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct TraceViewProps {
+    pub session: ChatSession,
+    pub on_close: Callback<()>,
+}
+
+fn status_icon(status: TraceStatus) -> (&'static str, &'static str) {
+    match status {
+        TraceStatus::Ok => ("fas fa-check-circle text-green-500", "Succeeded"),
+        TraceStatus::Error => ("fas fa-times-circle text-red-500", "Failed"),
+        TraceStatus::Pending => ("fas fa-circle-notch text-gray-400", "No result yet"),
+    }
+}
+
+fn format_duration(duration_ms: Option<f64>) -> String {
+    match duration_ms {
+        Some(ms) if ms >= 1000.0 => format!("{:.1} s", ms / 1000.0),
+        Some(ms) => format!("{:.0} ms", ms),
+        None => "—".to_string(),
+    }
+}
+
+#[function_component(TraceView)]
+pub fn trace_view(props: &TraceViewProps) -> Html {
+    let on_notification = use_event_bus().callback(PlaygroundEvent::Notify);
+    let recording = session_recording::load_recording(&props.session.id);
+    let turns = trace::build(&props.session.messages, recording.as_ref());
+    let spans: Vec<&TraceSpan> = turns.iter().flat_map(|turn| &turn.spans).collect();
+    let failed = spans.iter().filter(|span| span.status == TraceStatus::Error).count();
+    let tool_time: f64 = spans.iter().filter_map(|span| span.duration_ms).sum();
+    // The waterfall spans from the first traced turn to the last call's end
+    let start = turns.first().map_or(0.0, |turn| turn.started_at);
+    let end = turns.iter().map(TraceTurn::ended_at).fold(start, f64::max);
+    let range = (end - start).max(1.0);
+
+    let on_export = {
+        let session = props.session.clone();
+        let recording = recording.clone();
+        Callback::from(move |_: MouseEvent| {
+            let otlp = trace_export::to_otlp(&session, recording.as_ref());
+            let json = serde_json::to_string_pretty(&otlp).unwrap_or_default();
+            let file_name = trace_export::file_name(&session);
+            let notification = match html_export::download_file(&file_name, &json, "application/json") {
+                Ok(()) => NotificationMessage::new(
+                    format!("Exported the trace to {}", file_name),
+                    NotificationType::Success,
+                ),
+                Err(error) => NotificationMessage::new(
+                    format!("Trace export failed: {}", error),
+                    NotificationType::Error,
+                ),
+            };
+            on_notification.emit(notification);
+        })
+    };
+
+    let on_close = {
+        let callback = props.on_close.clone();
+        Callback::from(move |_: MouseEvent| callback.emit(()))
+    };
+
+    html! {
+        <div class="flex-1 flex flex-col min-w-0 bg-white dark:bg-gray-800">
+            <header class="p-4 border-b border-gray-200 dark:border-gray-600 flex justify-between items-center">
+                <div>
+                    <h2 class="font-semibold text-gray-900 dark:text-gray-100">{"Tool Call Trace"}</h2>
+                    <div class="text-sm text-gray-600 dark:text-gray-300">
+                        {format!(
+                            "{} call(s) over {} turn(s) · {} failed · {} in tools",
+                            spans.len(),
+                            turns.len(),
+                            failed,
+                            format_duration(Some(tool_time))
+                        )}
+                    </div>
+                </div>
+                <div class="flex items-center gap-1">
+                    <button
+                        onclick={on_export}
+                        class="text-sm px-3 py-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                        title="Download as OpenTelemetry (OTLP) JSON, with model and tool spans, timings and token counts"
+                    >
+                        <i class="fas fa-file-export mr-1" aria-hidden="true"></i>{"Export OTLP"}
+                    </button>
+                    <button
+                        onclick={on_close}
+                        class="p-2 rounded-md hover:bg-gray-100 dark:hover:bg-gray-700 text-gray-600 dark:text-gray-300"
+                        aria-label="Close trace"
+                        title="Back to chat"
+                    >
+                        <i class="fas fa-times" aria-hidden="true"></i>
+                    </button>
+                </div>
+            </header>
+
+            <div class="flex-1 overflow-y-auto custom-scrollbar p-4 space-y-4">
+                {if turns.is_empty() {
+                    html! {
+                        <p class="text-sm text-gray-600 dark:text-gray-400">
+                            {"No tool calls in this session yet."}
+                        </p>
+                    }
+                } else {
+                    html! {
+                        {for turns.iter().map(|turn| render_turn(turn, start, range))}
+                    }
+                }}
+                {if recording.is_none() && !turns.is_empty() {
+                    html! {
+                        <p class="text-xs text-gray-500 dark:text-gray-400">
+                            {"Durations are estimated from message times. Turn on recording in the chat header for exact ones."}
+                        </p>
+                    }
+                } else {
+                    html! {}
+                }}
+            </div>
+        </div>
+    }
+}
+
+fn render_turn(turn: &TraceTurn, start: f64, range: f64) -> Html {
+    let mut details = vec![format!("Turn {}", turn.number)];
+    if let Some(model) = &turn.model {
+        details.push(model.clone());
+    }
+    if let Some(latency) = turn.latency_ms {
+        details.push(format!("model {}", format_duration(Some(latency))));
+    }
+    html! {
+        <section aria-label={format!("Turn {}", turn.number)}>
+            <h3 class="text-xs font-medium text-gray-500 dark:text-gray-400 mb-1">
+                <i class="fas fa-robot mr-1" aria-hidden="true"></i>
+                {details.join(" · ")}
+            </h3>
+            <ul class="space-y-1">
+                {for turn.spans.iter().map(|span| render_span(span, start, range))}
+            </ul>
+        </section>
+    }
+}
+
+fn render_span(span: &TraceSpan, start: f64, range: f64) -> Html {
+    let pretty = |value: &serde_json::Value| serde_json::to_string_pretty(value).unwrap_or_default();
+    let (icon, status) = status_icon(span.status);
+    let left = ((span.started_at - start) / range * 100.0).clamp(0.0, 100.0);
+    let width = (span.duration_ms.unwrap_or(0.0) / range * 100.0).clamp(0.5, 100.0 - left);
+    let bar = match span.status {
+        TraceStatus::Ok => "bg-green-500",
+        TraceStatus::Error => "bg-red-500",
+        TraceStatus::Pending => "bg-gray-400",
+    };
+    html! {
+        <li key={span.call_id.clone()}>
+            <details class="rounded-md border border-gray-200 dark:border-gray-600">
+                <summary class="grid grid-cols-[1rem_8rem_1fr_6rem_4rem] sm:grid-cols-[1rem_10rem_1fr_12rem_4rem] items-center gap-2 p-2 text-xs cursor-pointer">
+                    <i class={icon} title={status} aria-label={status}></i>
+                    <span class="font-mono truncate text-gray-900 dark:text-gray-100">{&span.name}</span>
+                    <span class="truncate text-gray-600 dark:text-gray-400">{trace::summarize_arguments(&span.arguments)}</span>
+                    <span class="relative h-2 rounded bg-gray-100 dark:bg-gray-700" aria-hidden="true">
+                        <span class={classes!("absolute", "inset-y-0", "rounded", bar)} style={format!("left: {:.2}%; width: {:.2}%", left, width)}></span>
+                    </span>
+                    <span class="text-right font-mono text-gray-600 dark:text-gray-400">{format_duration(span.duration_ms)}</span>
+                </summary>
+                <div class="px-2 pb-2">
+                    <pre class="mt-1 p-2 max-h-64 overflow-auto custom-scrollbar rounded-md bg-gray-100 dark:bg-gray-900 text-xs text-gray-800 dark:text-gray-100">{pretty(&span.arguments)}</pre>
+                    {match &span.result {
+                        Some(result) => html! {
+                            <pre class="mt-2 p-2 max-h-64 overflow-auto custom-scrollbar rounded-md bg-gray-100 dark:bg-gray-900 text-xs text-gray-800 dark:text-gray-100">{pretty(result)}</pre>
+                        },
+                        None => html! {},
+                    }}
+                </div>
+            </details>
+        </li>
+    }
+}
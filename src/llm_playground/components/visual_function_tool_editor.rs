@@ -1,3 +1,4 @@
+use crate::llm_playground::hooks::use_focus_trap;
 use crate::llm_playground::FunctionTool;
 use serde_json::json;
 use web_sys::HtmlInputElement;
@@ -118,6 +119,9 @@ pub fn visual_function_tool_editor(props: &VisualFunctionToolEditorProps) -> Htm
     let parameters = use_state(|| Vec::<Parameter>::new());
     let mock_fields = use_state(|| vec![MockResponseField::default()]);
     let show_json_preview = use_state(|| false);
+    let dialog_ref = use_node_ref();
+
+    use_focus_trap(dialog_ref.clone(), true, props.on_cancel.clone());
 
     // Initialize from existing tool if editing
     {
@@ -233,10 +237,16 @@ pub fn visual_function_tool_editor(props: &VisualFunctionToolEditorProps) -> Htm
 
     html! {
         <div class="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
-            <div class="bg-white dark:bg-gray-800 rounded-lg w-full max-w-4xl max-h-[90vh] overflow-hidden flex flex-col">
+            <div
+                ref={dialog_ref}
+                role="dialog"
+                aria-modal="true"
+                aria-labelledby="visual-tool-editor-title"
+                class="bg-white dark:bg-gray-800 rounded-lg w-full max-w-4xl max-h-[90vh] overflow-hidden flex flex-col"
+            >
                 // Header
                 <div class="flex justify-between items-center p-6 border-b border-gray-200 dark:border-gray-700">
-                    <h3 class="text-xl font-semibold text-gray-900 dark:text-gray-100">
+                    <h3 id="visual-tool-editor-title" class="text-xl font-semibold text-gray-900 dark:text-gray-100">
                         {if props.tool.is_some() { "Edit Function Tool" } else { "Create Function Tool" }}
                     </h3>
                     <button
@@ -245,8 +255,9 @@ pub fn visual_function_tool_editor(props: &VisualFunctionToolEditorProps) -> Htm
                             Callback::from(move |_| on_cancel.emit(()))
                         }
                         class="text-gray-500 hover:text-gray-700 dark:text-gray-400 dark:hover:text-gray-200"
+                        aria-label="Close editor"
                     >
-                        <i class="fas fa-times text-xl"></i>
+                        <i class="fas fa-times text-xl" aria-hidden="true"></i>
                     </button>
                 </div>
 
@@ -325,8 +336,9 @@ pub fn visual_function_tool_editor(props: &VisualFunctionToolEditorProps) -> Htm
                                                 onclick={delete_param}
                                                 class="text-red-500 hover:text-red-700 text-sm"
                                                 title="Delete parameter"
+                                                aria-label={format!("Delete parameter {}", index + 1)}
                                             >
-                                                <i class="fas fa-trash"></i>
+                                                <i class="fas fa-trash" aria-hidden="true"></i>
                                             </button>
                                         </div>
 
@@ -465,8 +477,9 @@ pub fn visual_function_tool_editor(props: &VisualFunctionToolEditorProps) -> Htm
                                                         onclick={delete_field}
                                                         class="text-red-500 hover:text-red-700 text-sm"
                                                         title="Delete field"
+                                                        aria-label="Delete response field"
                                                     >
-                                                        <i class="fas fa-trash"></i>
+                                                        <i class="fas fa-trash" aria-hidden="true"></i>
                                                     </button>
                                                 }
                                             } else {
@@ -666,6 +679,7 @@ pub fn visual_function_tool_editor(props: &VisualFunctionToolEditorProps) -> Htm
                                     enabled: true,
                                     category: "Custom".to_string(),
                                     is_builtin: false,
+                                    requires: None,
                                 };
 
                                 on_save.emit(tool);
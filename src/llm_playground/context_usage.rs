@@ -0,0 +1,216 @@
+// Context window usage
+// Estimates what the next request will fill the model's context window with:
+// the system prompt, tool definitions, chat history, tool calls and results,
+// and the room kept for the reply. Counts use the same rough estimate as the
+// input bar, so they show proportions rather than exact provider counts.
+
+use crate::llm_playground::{
+    flexible_client::FlexibleLLMClient, offload::estimate_tokens,
+    provider_config::FlexibleApiConfig, Message, MessageRole,
+};
+
+/// Tokens of role markers and separators each message adds
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+/// Request body fields the tool definitions and tool choice go out in
+const TOOL_FIELDS: [&str; 3] = ["tools", "tool_choice", "toolConfig"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContextPart {
+    SystemPrompt,
+    Tools,
+    History,
+    ToolResults,
+    /// Max tokens kept free for the reply
+    Reply,
+}
+
+impl ContextPart {
+    pub const ALL: [ContextPart; 5] = [
+        ContextPart::SystemPrompt,
+        ContextPart::Tools,
+        ContextPart::History,
+        ContextPart::ToolResults,
+        ContextPart::Reply,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContextPart::SystemPrompt => "System prompt",
+            ContextPart::Tools => "Tool definitions",
+            ContextPart::History => "History",
+            ContextPart::ToolResults => "Tool calls & results",
+            ContextPart::Reply => "Reserved for reply",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContextUsage {
+    /// Estimated tokens per part, in ContextPart::ALL order
+    pub parts: Vec<(ContextPart, usize)>,
+    /// The model's context window, when known
+    pub window: Option<u32>,
+}
+
+impl ContextUsage {
+    pub fn total(&self) -> usize {
+        self.parts.iter().map(|(_, tokens)| tokens).sum()
+    }
+
+    /// Share of the window a part takes, or of the total when the window is
+    /// unknown or already exceeded
+    pub fn share(&self, tokens: usize) -> f64 {
+        let whole = self
+            .window
+            .map(|window| window as usize)
+            .filter(|window| *window >= self.total())
+            .unwrap_or_else(|| self.total())
+            .max(1);
+        tokens as f64 / whole as f64
+    }
+
+    pub fn exceeds_window(&self) -> bool {
+        self.window.is_some_and(|window| self.total() > window as usize)
+    }
+}
+
+/// Estimated tokens of the enabled tools' definitions, serialized the way the
+/// session provider's client sends them
+pub fn tool_definition_tokens(config: &FlexibleApiConfig) -> usize {
+    match FlexibleLLMClient::new().build_request(&[], config) {
+        Ok(request) => TOOL_FIELDS
+            .iter()
+            .filter_map(|field| request.body.get(*field))
+            .map(|value| estimate_tokens(&value.to_string()))
+            .sum(),
+        Err(_) => openai_tool_tokens(config),
+    }
+}
+
+/// What each tool's definition would cost on its own, enabled or not
+pub fn tool_costs(config: &FlexibleApiConfig) -> Vec<(String, usize)> {
+    config
+        .function_tools
+        .iter()
+        .map(|tool| {
+            let mut single = config.clone();
+            for other in &mut single.function_tools {
+                other.enabled = other.name == tool.name;
+            }
+            (tool.name.clone(), tool_definition_tokens(&single))
+        })
+        .collect()
+}
+
+/// OpenAI-style estimate, for providers whose request can't be built
+fn openai_tool_tokens(config: &FlexibleApiConfig) -> usize {
+    config
+        .get_enabled_function_tools()
+        .iter()
+        .map(|tool| {
+            let definition = serde_json::json!({
+                "type": "function",
+                "function": {
+                    "name": tool.name,
+                    "description": tool.description,
+                    "parameters": tool.parameters
+                }
+            });
+            estimate_tokens(&definition.to_string())
+        })
+        .sum()
+}
+
+/// What the next request, with `draft` as its new message, would send
+pub fn estimate(config: &FlexibleApiConfig, messages: &[Message], draft: &str) -> ContextUsage {
+    let mut system = if config.system_prompt.trim().is_empty() {
+        0
+    } else {
+        estimate_tokens(&config.system_prompt) + MESSAGE_OVERHEAD_TOKENS
+    };
+    let mut history = 0;
+    let mut tool_results = 0;
+    // Blocked messages are never sent, and thinking is never sent back
+    for message in messages
+        .iter()
+        .filter(|m| !m.moderation.as_ref().is_some_and(|r| r.blocked))
+    {
+        let content = estimate_tokens(&message.content) + MESSAGE_OVERHEAD_TOKENS;
+        match message.role {
+            MessageRole::System | MessageRole::Developer => system += content,
+            MessageRole::Function => tool_results += content,
+            MessageRole::User | MessageRole::Assistant => history += content,
+        }
+        tool_results += [&message.function_call, &message.function_response]
+            .into_iter()
+            .flatten()
+            .map(|value| estimate_tokens(&value.to_string()))
+            .sum::<usize>();
+    }
+    if !draft.trim().is_empty() {
+        history += estimate_tokens(draft) + MESSAGE_OVERHEAD_TOKENS;
+    }
+
+    let (provider, model) = config.get_current_provider_and_model();
+    let tokens = |part: ContextPart| match part {
+        ContextPart::SystemPrompt => system,
+        ContextPart::Tools => tool_definition_tokens(config),
+        ContextPart::History => history,
+        ContextPart::ToolResults => tool_results,
+        ContextPart::Reply => config.shared_settings.max_tokens as usize,
+    };
+    ContextUsage {
+        parts: ContextPart::ALL.iter().map(|part| (*part, tokens(*part))).collect(),
+        window: config
+            .get_model_defaults(&provider, &model)
+            .and_then(|defaults| defaults.context_window),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(tokens: [usize; 5], window: Option<u32>) -> ContextUsage {
+        ContextUsage {
+            parts: ContextPart::ALL.into_iter().zip(tokens).collect(),
+            window,
+        }
+    }
+
+    #[test]
+    fn shares_are_of_the_window_until_it_is_exceeded() {
+        let within = usage([100, 200, 300, 0, 400], Some(2000));
+        assert_eq!(within.total(), 1000);
+        assert!(!within.exceeds_window());
+        assert_eq!(within.share(500), 0.25);
+
+        let over = usage([1000, 1000, 1000, 0, 1000], Some(2000));
+        assert!(over.exceeds_window());
+        assert_eq!(over.share(1000), 0.25);
+
+        let unknown = usage([0; 5], None);
+        assert_eq!(unknown.share(0), 0.0);
+    }
+
+    #[test]
+    fn only_enabled_tools_count_towards_the_request() {
+        let mut config = FlexibleApiConfig::default();
+        for tool in &mut config.function_tools {
+            tool.enabled = false;
+        }
+        assert_eq!(tool_definition_tokens(&config), 0);
+
+        let costs = tool_costs(&config);
+        assert_eq!(costs.len(), config.function_tools.len());
+        assert!(costs.iter().all(|(_, tokens)| *tokens > 0));
+
+        let (first, first_cost) = costs[0].clone();
+        config.function_tools[0].enabled = true;
+        assert_eq!(config.function_tools[0].name, first);
+        assert_eq!(tool_definition_tokens(&config), first_cost);
+
+        config.function_tools[0].description.push_str(&" longer".repeat(100));
+        assert!(tool_definition_tokens(&config) > first_cost);
+    }
+}
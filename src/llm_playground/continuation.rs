@@ -0,0 +1,108 @@
+// Continuing cut-off replies
+// A reply that hit the max tokens limit is resumed by sending the conversation
+// with a request to carry on, and the answer is appended to the same message
+// rather than shown as a new one. Models often repeat the last words they
+// wrote before going on, so the start of the continuation that overlaps the
+// end of the reply is dropped.
+
+use crate::llm_playground::{Message, MessageRole, ResponseMetrics, CONTINUE_PROMPT};
+
+/// Shorter overlaps are taken as chance and kept
+const MIN_OVERLAP_CHARS: usize = 8;
+/// Longest repeat looked for at the start of a continuation
+const MAX_OVERLAP_CHARS: usize = 400;
+
+/// Number of chars at the start of `continuation` that repeat the end of
+/// `reply`, or 0 when the overlap is too short to be more than chance
+fn overlap(reply: &str, continuation: &str) -> usize {
+    let longest = continuation.chars().count().min(MAX_OVERLAP_CHARS);
+    (MIN_OVERLAP_CHARS..=longest)
+        .rev()
+        .find(|&chars| {
+            let end = continuation
+                .char_indices()
+                .nth(chars)
+                .map_or(continuation.len(), |(at, _)| at);
+            reply.ends_with(&continuation[..end])
+        })
+        .unwrap_or(0)
+}
+
+/// The reply with the continuation appended and any repeat of its end removed
+pub fn stitch(reply: &str, continuation: &str) -> String {
+    // A repeat is usually preceded by the whitespace the reply stopped on
+    let trimmed = continuation.trim_start();
+    let repeated = overlap(reply, trimmed);
+    let rest = if repeated > 0 {
+        trimmed.chars().skip(repeated).collect::<String>()
+    } else {
+        continuation.to_string()
+    };
+    format!("{}{}", reply, rest)
+}
+
+/// The conversation up to the cut-off reply with the request to resume it;
+/// the request isn't kept in the session
+pub fn request_messages(messages: &[Message], reply_index: usize) -> Vec<Message> {
+    let mut request: Vec<Message> = messages[..=reply_index]
+        .iter()
+        .filter(|m| !m.moderation.as_ref().is_some_and(|r| r.blocked))
+        .cloned()
+        .collect();
+    request.push(Message {
+        id: format!("continue_{}", js_sys::Date::now() as u64),
+        timestamp: js_sys::Date::now(),
+        ..Message::new(MessageRole::User, CONTINUE_PROMPT)
+    });
+    request
+}
+
+/// Metrics of a reply and its continuation taken together
+pub fn merge_metrics(
+    reply: Option<ResponseMetrics>,
+    continuation: Option<ResponseMetrics>,
+) -> Option<ResponseMetrics> {
+    let add = |a: Option<u32>, b: Option<u32>| match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or(0) + b.unwrap_or(0)),
+    };
+    match (reply, continuation) {
+        (Some(reply), Some(continuation)) => Some(ResponseMetrics {
+            latency_ms: reply.latency_ms + continuation.latency_ms,
+            output_tokens: add(reply.output_tokens, continuation.output_tokens),
+            input_tokens: add(reply.input_tokens, continuation.input_tokens),
+            cached_tokens: add(reply.cached_tokens, continuation.cached_tokens),
+            generation_ms: reply
+                .generation_ms
+                .zip(continuation.generation_ms)
+                .map(|(a, b)| a + b),
+            cost: reply.cost.zip(continuation.cost).map(|(a, b)| a + b),
+            ..reply
+        }),
+        (reply, continuation) => reply.or(continuation),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_words_are_dropped() {
+        assert_eq!(
+            stitch("The quick brown fox jumps over the la", "fox jumps over the lazy dog."),
+            "The quick brown fox jumps over the lazy dog."
+        );
+        assert_eq!(
+            stitch("First line\nSecond li", "\n\nSecond line\nThird line"),
+            "First line\nSecond line\nThird line"
+        );
+    }
+
+    #[test]
+    fn short_overlaps_are_kept() {
+        // "the " matching is chance, not a repeat
+        assert_eq!(stitch("I saw the ", "the cat"), "I saw the the cat");
+        assert_eq!(stitch("Numbers: 1, 2, 3", ", 4, 5"), "Numbers: 1, 2, 3, 4, 5");
+    }
+}
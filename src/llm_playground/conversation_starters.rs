@@ -0,0 +1,119 @@
+// Conversation starters
+// An empty session offers a few prompts to click instead of a blank input:
+// the list from the settings and, when enabled, suggestions the current model
+// writes for the session's system prompt.
+
+use crate::llm_playground::{
+    flexible_client::FlexibleLLMClient, provider_config::FlexibleApiConfig, Message, MessageRole,
+};
+use serde::{Deserialize, Serialize};
+
+/// Suggestions asked from the model
+pub const GENERATED_STARTERS: usize = 3;
+/// Characters a suggestion may have; longer lines are dropped
+const MAX_STARTER_CHARS: usize = 200;
+
+pub const META_PROMPT: &str = "You suggest how to start a conversation with an AI assistant. \
+Given the assistant's system prompt, write short, concrete first messages a user could send to \
+see what the assistant does well. Write them in the user's voice, one per line, without \
+numbering or quotes, and nothing else.";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StarterSettings {
+    pub prompts: Vec<String>,
+    /// Also ask the model for starters that fit the system prompt
+    pub generate: bool,
+}
+
+impl Default for StarterSettings {
+    fn default() -> Self {
+        Self {
+            prompts: vec![
+                "Explain a concept I'm learning in simple terms".to_string(),
+                "Help me debug a piece of code".to_string(),
+                "Draft an email for me".to_string(),
+                "Brainstorm ideas for a project".to_string(),
+            ],
+            generate: false,
+        }
+    }
+}
+
+/// The single user message asking for suggestions
+pub fn build_request(system_prompt: &str) -> String {
+    format!(
+        "System prompt:\n{}\n\nSuggest {} first messages.",
+        system_prompt.trim(),
+        GENERATED_STARTERS
+    )
+}
+
+/// Suggestions from the model's reply, one per line with list markers and
+/// quotes removed
+pub fn parse_suggestions(reply: &str) -> Vec<String> {
+    reply
+        .lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '-' | '*' | '•' | '.' | ')'))
+                .trim()
+                .trim_matches('"')
+                .trim()
+        })
+        .filter(|line| !line.is_empty() && line.chars().count() <= MAX_STARTER_CHARS)
+        .take(GENERATED_STARTERS)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Ask the config's current model for starters fitting its system prompt
+pub async fn generate(
+    client: &FlexibleLLMClient,
+    config: &FlexibleApiConfig,
+) -> Result<Vec<String>, String> {
+    let request = build_request(&config.system_prompt);
+    let mut config = config.clone();
+    config.system_prompt = META_PROMPT.to_string();
+    config.active_structured_output = None;
+    for tool in &mut config.function_tools {
+        tool.enabled = false;
+    }
+
+    let message = Message {
+        id: format!("starters_{}", js_sys::Date::now() as u64),
+        timestamp: js_sys::Date::now(),
+        ..Message::new(MessageRole::User, request)
+    };
+    let response = client.send_message(&[message], &config).await?;
+    let suggestions = parse_suggestions(&response.content.unwrap_or_default());
+    if suggestions.is_empty() {
+        return Err("The model returned no suggestions".to_string());
+    }
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggestions_lose_list_markers_and_quotes() {
+        let reply = "1. \"Review this function for bugs\"\n\n- Explain ownership in Rust\n* Write a unit test for my parser\n• One too many";
+        assert_eq!(
+            parse_suggestions(reply),
+            [
+                "Review this function for bugs",
+                "Explain ownership in Rust",
+                "Write a unit test for my parser",
+            ]
+        );
+    }
+
+    #[test]
+    fn overlong_lines_are_dropped() {
+        let reply = format!("{}\nShort one", "x".repeat(MAX_STARTER_CHARS + 1));
+        assert_eq!(parse_suggestions(&reply), ["Short one"]);
+        assert!(build_request("  You review code.  ").contains("You review code.\n"));
+    }
+}
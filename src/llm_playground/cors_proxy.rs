@@ -0,0 +1,139 @@
+// CORS proxy support
+// Rewrites outgoing request URLs so that endpoints without CORS headers can be
+// reached from the browser through a user-configured proxy.
+
+use gloo_net::http::{Request, RequestBuilder};
+
+/// Header sent to the proxy so it can tell the call apart from a plain page load
+/// (required by cors-anywhere style proxies)
+const PROXY_HEADERS: [(&str, &str); 1] = [("X-Requested-With", "XMLHttpRequest")];
+
+/// Return the configured proxy if it is set to something non-empty
+fn active_proxy(proxy: Option<&str>) -> Option<&str> {
+    proxy.map(str::trim).filter(|p| !p.is_empty())
+}
+
+/// Rewrite a target URL so it is routed through the proxy.
+///
+/// Supported proxy formats:
+/// - `https://proxy.example/?url={url}`: `{url}` is replaced with the encoded target
+/// - `https://proxy.example/?url=`: the encoded target is appended
+/// - `https://proxy.example/`: the raw target is appended (cors-anywhere style)
+pub fn proxied_url(proxy: Option<&str>, url: &str) -> String {
+    let Some(proxy) = active_proxy(proxy) else {
+        return url.to_string();
+    };
+
+    if proxy.contains("{url}") {
+        proxy.replace("{url}", &encode_uri_component(url))
+    } else if proxy.ends_with('=') {
+        format!("{}{}", proxy, encode_uri_component(url))
+    } else if proxy.ends_with('/') {
+        format!("{}{}", proxy, url)
+    } else {
+        format!("{}/{}", proxy, url)
+    }
+}
+
+/// Headers to add to a request when it goes through the proxy
+pub fn proxy_headers(proxy: Option<&str>) -> &'static [(&'static str, &'static str)] {
+    if active_proxy(proxy).is_some() {
+        &PROXY_HEADERS
+    } else {
+        &[]
+    }
+}
+
+/// Add the proxy headers to a web_sys request's headers
+pub fn apply_proxy_headers(headers: &web_sys::Headers, proxy: Option<&str>) -> Result<(), String> {
+    for (key, value) in proxy_headers(proxy) {
+        headers
+            .set(key, value)
+            .map_err(|e| format!("Failed to set header {}: {:?}", key, e))?;
+    }
+    Ok(())
+}
+
+/// Start a GET request routed through the proxy (if any)
+pub fn get(url: &str, proxy: Option<&str>) -> RequestBuilder {
+    with_proxy_headers(Request::get(&proxied_url(proxy, url)), proxy)
+}
+
+/// Start a POST request routed through the proxy (if any)
+pub fn post(url: &str, proxy: Option<&str>) -> RequestBuilder {
+    with_proxy_headers(Request::post(&proxied_url(proxy, url)), proxy)
+}
+
+/// Start a PATCH request routed through the proxy (if any)
+pub fn patch(url: &str, proxy: Option<&str>) -> RequestBuilder {
+    with_proxy_headers(Request::patch(&proxied_url(proxy, url)), proxy)
+}
+
+/// Start a DELETE request routed through the proxy (if any)
+pub fn delete(url: &str, proxy: Option<&str>) -> RequestBuilder {
+    with_proxy_headers(Request::delete(&proxied_url(proxy, url)), proxy)
+}
+
+fn with_proxy_headers(builder: RequestBuilder, proxy: Option<&str>) -> RequestBuilder {
+    proxy_headers(proxy)
+        .iter()
+        .fold(builder, |builder, (key, value)| builder.header(key, value))
+}
+
+/// Percent-encode a string the same way JavaScript's encodeURIComponent does
+pub fn encode_uri_component(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'!'
+            | b'~'
+            | b'*'
+            | b'\''
+            | b'('
+            | b')' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxied_url_without_proxy() {
+        let url = "https://api.openai.com/v1/models";
+        assert_eq!(proxied_url(None, url), url);
+        assert_eq!(proxied_url(Some("  "), url), url);
+        assert!(proxy_headers(None).is_empty());
+    }
+
+    #[test]
+    fn test_proxied_url_formats() {
+        let url = "https://api.example.com/v1/chat?key=a b";
+        assert_eq!(
+            proxied_url(Some("https://proxy.dev/?url={url}"), url),
+            "https://proxy.dev/?url=https%3A%2F%2Fapi.example.com%2Fv1%2Fchat%3Fkey%3Da%20b"
+        );
+        assert_eq!(
+            proxied_url(Some("https://proxy.dev/?url="), url),
+            "https://proxy.dev/?url=https%3A%2F%2Fapi.example.com%2Fv1%2Fchat%3Fkey%3Da%20b"
+        );
+        assert_eq!(
+            proxied_url(Some("https://proxy.dev/"), url),
+            "https://proxy.dev/https://api.example.com/v1/chat?key=a b"
+        );
+        assert_eq!(
+            proxied_url(Some("https://proxy.dev"), url),
+            "https://proxy.dev/https://api.example.com/v1/chat?key=a b"
+        );
+        assert_eq!(proxy_headers(Some("https://proxy.dev")).len(), 1);
+    }
+}
@@ -0,0 +1,347 @@
+// Desktop tools
+// In the Tauri desktop build the Bash, Read, Write, Edit, Glob, Grep and LS
+// tools run against the real filesystem and shell through the `run_tool`
+// command instead of returning their mock responses. Calls are checked against
+// an allowlist of directories and programs, and anything that changes the
+// machine waits for the user's approval. The backend checks both again: the
+// allowlist takes effect there once the user confirms it, and approvals are
+// asked for in native dialogs, so a script in the page can't grant either.
+
+use crate::llm_playground::event_bus::{EventBus, PlaygroundEvent};
+use js_sys::{Function, Object, Promise, Reflect};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+pub const DESKTOP_TOOL_NAMES: [&str; 7] = ["Bash", "Read", "Write", "Edit", "Glob", "Grep", "LS"];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DesktopToolSettings {
+    /// Run the tools for real when the app runs under Tauri
+    pub enabled: bool,
+    /// Directories the file tools may touch, subdirectories included
+    pub allowed_roots: Vec<String>,
+    /// Programs Bash may start; "*" allows any
+    pub allowed_commands: Vec<String>,
+    /// Run Read, Glob, Grep and LS without asking
+    pub auto_approve_reads: bool,
+}
+
+impl Default for DesktopToolSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allowed_roots: Vec::new(),
+            // Programs that can start others, like git, cargo or find, are left out
+            allowed_commands: ["ls", "cat", "pwd", "head", "tail", "wc", "rg", "grep"]
+                .iter()
+                .map(|c| c.to_string())
+                .collect(),
+            auto_approve_reads: true,
+        }
+    }
+}
+
+impl DesktopToolSettings {
+    fn allows_any_command(&self) -> bool {
+        self.allowed_commands.iter().any(|c| c.trim() == "*")
+    }
+}
+
+/// Tools that only look at the filesystem
+pub fn is_read_only(tool_name: &str) -> bool {
+    matches!(tool_name, "Read" | "Glob" | "Grep" | "LS")
+}
+
+/// Whether the app runs inside the Tauri shell
+pub fn is_desktop() -> bool {
+    tauri_invoke().is_some()
+}
+
+/// Whether `tool_name` runs for real instead of returning its mock response
+pub fn handles(settings: &DesktopToolSettings, tool_name: &str) -> bool {
+    settings.enabled && DESKTOP_TOOL_NAMES.contains(&tool_name) && is_desktop()
+}
+
+/// The arguments the tool will run with: Glob and Grep search the first
+/// allowed root when no path is given. Fails when a path or program is not
+/// on the allowlist.
+pub fn prepare(
+    settings: &DesktopToolSettings,
+    tool_name: &str,
+    arguments: &Value,
+) -> Result<Value, String> {
+    let mut arguments = arguments.clone();
+    if tool_name == "Bash" {
+        let command = arguments
+            .get("command")
+            .and_then(|c| c.as_str())
+            .ok_or("Missing command")?;
+        check_command(settings, command)?;
+        return Ok(arguments);
+    }
+
+    let key = match tool_name {
+        "Read" | "Write" | "Edit" => "file_path",
+        _ => "path",
+    };
+    let path = match arguments.get(key).and_then(|p| p.as_str()) {
+        Some(path) if !path.trim().is_empty() => path.to_string(),
+        _ if matches!(tool_name, "Glob" | "Grep") => {
+            let root = settings
+                .allowed_roots
+                .first()
+                .ok_or("No allowed directories are configured")?
+                .clone();
+            arguments[key] = Value::String(root.clone());
+            root
+        }
+        _ => return Err(format!("Missing {}", key)),
+    };
+    check_path(settings, &path)?;
+    Ok(arguments)
+}
+
+/// The path must be absolute and inside one of the allowed roots once `.`
+/// and `..` are resolved
+pub fn check_path(settings: &DesktopToolSettings, path: &str) -> Result<(), String> {
+    let path = normalize_path(path).ok_or_else(|| format!("{} is not an absolute path", path))?;
+    let allowed = settings
+        .allowed_roots
+        .iter()
+        .filter_map(|root| normalize_path(root))
+        .any(|root| path == root || path.starts_with(&format!("{}/", root.trim_end_matches('/'))));
+    if allowed {
+        Ok(())
+    } else {
+        Err(format!("{} is outside the allowed directories", path))
+    }
+}
+
+/// Every program the command starts must be on the allowlist, named without
+/// a path. Command substitution and environment assignments can start
+/// anything, so they need "*". The backend also refuses options that make an
+/// allowed program start others, like `find -exec`.
+pub fn check_command(settings: &DesktopToolSettings, command: &str) -> Result<(), String> {
+    if settings.allows_any_command() {
+        return Ok(());
+    }
+    if command.contains('`') || command.contains("$(") {
+        return Err("Command substitution needs every program to be allowed".to_string());
+    }
+    // `2>&1` style redirections are not command separators
+    let command = command.replace(">&", ">").replace("<&", "<");
+    let separators: &[char] = &[';', '|', '&', '\n', '(', ')'];
+    for segment in command.split(separators) {
+        let Some(program) = segment.split_whitespace().next() else {
+            continue;
+        };
+        if program.contains('=') {
+            return Err("Setting environment variables needs every program to be allowed".to_string());
+        }
+        if program.contains('/') || program.contains('\\') {
+            return Err(format!("{} must be named without a path", program));
+        }
+        if !settings.allowed_commands.iter().any(|c| c.trim() == program) {
+            return Err(format!("{} is not an allowed program", program));
+        }
+    }
+    Ok(())
+}
+
+/// Lexically resolve `.` and `..` in an absolute path, with `/` separators
+fn normalize_path(path: &str) -> Option<String> {
+    let path = path.trim().replace('\\', "/");
+    // Windows drive letters keep their prefix
+    let (prefix, rest) = match path.find(":/") {
+        Some(1) => path.split_at(2),
+        _ => ("", path.as_str()),
+    };
+    if !rest.starts_with('/') {
+        return None;
+    }
+    let mut parts: Vec<&str> = Vec::new();
+    for part in rest.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    Some(format!("{}/{}", prefix, parts.join("/")))
+}
+
+/// One line for the approval dialog
+pub fn describe(tool_name: &str, arguments: &Value) -> String {
+    let text = |key: &str| arguments.get(key).and_then(|v| v.as_str()).unwrap_or("");
+    match tool_name {
+        "Bash" => text("command").to_string(),
+        "Write" => format!(
+            "Write {} lines to {}",
+            text("content").lines().count(),
+            text("file_path")
+        ),
+        "Edit" => format!("Edit {}", text("file_path")),
+        "Read" => format!("Read {}", text("file_path")),
+        "Glob" | "Grep" => format!("{} \"{}\" in {}", tool_name, text("pattern"), text("path")),
+        _ => format!("List {}", text("path")),
+    }
+}
+
+/// A tool call waiting for the user; answered once with `respond`
+#[derive(Clone)]
+pub struct ApprovalRequest {
+    pub tool_name: String,
+    pub arguments: Value,
+    resolve: Function,
+}
+
+impl ApprovalRequest {
+    pub fn respond(&self, approved: bool) {
+        let _ = self
+            .resolve
+            .call1(&JsValue::NULL, &JsValue::from_bool(approved));
+    }
+}
+
+impl PartialEq for ApprovalRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.tool_name == other.tool_name
+            && self.arguments == other.arguments
+            && self.resolve == other.resolve
+    }
+}
+
+impl std::fmt::Debug for ApprovalRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApprovalRequest")
+            .field("tool_name", &self.tool_name)
+            .field("arguments", &self.arguments)
+            .finish()
+    }
+}
+
+/// Publish an approval request and wait for the answer
+pub async fn request_approval(bus: &EventBus, tool_name: &str, arguments: &Value) -> bool {
+    let mut resolve = None;
+    let promise = Promise::new(&mut |resolve_fn, _| resolve = Some(resolve_fn));
+    let Some(resolve) = resolve else {
+        return false;
+    };
+    bus.publish(PlaygroundEvent::ToolApproval(ApprovalRequest {
+        tool_name: tool_name.to_string(),
+        arguments: arguments.clone(),
+        resolve,
+    }));
+    JsFuture::from(promise)
+        .await
+        .ok()
+        .and_then(|answer| answer.as_bool())
+        .unwrap_or(false)
+}
+
+/// Check, approve and run a desktop tool call. The settings go to the
+/// backend first, which asks the user to confirm them when they allow more
+/// than before; approvals are native dialogs shown by the backend.
+pub async fn run(settings: &DesktopToolSettings, tool_name: &str, arguments: &Value) -> Result<Value, String> {
+    let arguments = prepare(settings, tool_name, arguments)?;
+    let policy = serde_json::to_string(settings).map_err(|e| e.to_string())?;
+    invoke("set_tool_policy", &[("policy", policy.into())]).await?;
+
+    let tool = JsValue::from_str(tool_name);
+    let arguments = JsValue::from_str(&arguments.to_string());
+    let approval = if settings.auto_approve_reads && is_read_only(tool_name) {
+        JsValue::NULL
+    } else {
+        invoke("approve_tool", &[("tool", tool.clone()), ("arguments", arguments.clone())]).await?
+    };
+    let result = invoke("run_tool", &[("tool", tool), ("arguments", arguments), ("approval", approval)]).await?;
+    let text = result.as_string().ok_or("run_tool returned no text")?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid run_tool result: {}", e))
+}
+
+/// `window.__TAURI__.core` and its `invoke`, present when `withGlobalTauri` is on
+fn tauri_invoke() -> Option<(JsValue, Function)> {
+    let window = web_sys::window()?;
+    let tauri = Reflect::get(&window, &JsValue::from_str("__TAURI__")).ok()?;
+    let core = Reflect::get(&tauri, &JsValue::from_str("core")).ok()?;
+    let invoke = Reflect::get(&core, &JsValue::from_str("invoke"))
+        .ok()?
+        .dyn_into::<Function>()
+        .ok()?;
+    Some((core, invoke))
+}
+
+/// Call a backend command with the given arguments
+async fn invoke(command: &str, arguments: &[(&str, JsValue)]) -> Result<JsValue, String> {
+    let (core, invoke) = tauri_invoke().ok_or("Not running in the desktop app")?;
+    let payload = Object::new();
+    for (name, value) in arguments {
+        let _ = Reflect::set(&payload, &(*name).into(), value);
+    }
+
+    let promise: Promise = invoke
+        .call2(&core, &command.into(), &payload)
+        .map_err(|e| format!("{:?}", e))?
+        .dyn_into()
+        .map_err(|_| format!("{} did not return a promise", command))?;
+    JsFuture::from(promise)
+        .await
+        .map_err(|e| e.as_string().unwrap_or_else(|| format!("{:?}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn settings() -> DesktopToolSettings {
+        DesktopToolSettings {
+            enabled: true,
+            allowed_roots: vec!["/home/me/project".to_string()],
+            ..DesktopToolSettings::default()
+        }
+    }
+
+    #[test]
+    fn paths_must_stay_inside_allowed_roots() {
+        let settings = settings();
+        assert!(check_path(&settings, "/home/me/project/src/main.rs").is_ok());
+        assert!(check_path(&settings, "/home/me/project").is_ok());
+        assert!(check_path(&settings, "/home/me/project/../secrets").is_err());
+        assert!(check_path(&settings, "/home/me/project-old/a.rs").is_err());
+        assert!(check_path(&settings, "src/main.rs").is_err());
+
+        let windows = DesktopToolSettings {
+            allowed_roots: vec!["C:\\work".to_string()],
+            ..settings.clone()
+        };
+        assert!(check_path(&windows, "C:\\work\\notes.md").is_ok());
+
+        // Glob without a path searches the first root
+        let prepared = prepare(&settings, "Glob", &json!({ "pattern": "**/*.rs" })).unwrap();
+        assert_eq!(prepared["path"], "/home/me/project");
+        assert!(prepare(&settings, "Read", &json!({})).is_err());
+    }
+
+    #[test]
+    fn commands_must_use_allowed_programs() {
+        let settings = settings();
+        assert!(check_command(&settings, "ls src && cat Cargo.toml | grep version 2>&1").is_ok());
+        assert!(check_command(&settings, "git status").is_err());
+        assert!(check_command(&settings, "LD_PRELOAD=/tmp/x.so ls").is_err());
+        assert!(check_command(&settings, "/tmp/bin/ls").is_err());
+        assert!(check_command(&settings, "ls; rm -rf /").is_err());
+        assert!(check_command(&settings, "cat $(which curl)").is_err());
+
+        let any = DesktopToolSettings {
+            allowed_commands: vec!["*".to_string()],
+            ..settings
+        };
+        assert!(check_command(&any, "rm -rf build").is_ok());
+    }
+}
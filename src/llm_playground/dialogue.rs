@@ -0,0 +1,337 @@
+// Two-model dialogue mode
+// Two configured models take turns replying to each other on a seed topic. The
+// conversation is stored as a normal session: the seed and any human interjections
+// are user messages, and model replies are assistant messages that alternate
+// between the two participants.
+
+use crate::llm_playground::{
+    flexible_client::FlexibleLLMClient, provider_config::FlexibleApiConfig, ChatSession, Message,
+    MessageRole, ResponseMetrics,
+};
+use serde::{Deserialize, Serialize};
+use yew::Callback;
+
+pub const DEFAULT_DIALOGUE_TURNS: u32 = 6;
+pub const MAX_DIALOGUE_TURNS: u32 = 50;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DialogueParticipant {
+    pub name: String,
+    /// "provider,model" this participant runs on
+    pub model: String,
+    #[serde(default)]
+    pub system_prompt: String,
+}
+
+/// Setup of a dialogue session
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DialogueConfig {
+    /// Exactly two participants; the first one speaks first
+    pub participants: Vec<DialogueParticipant>,
+    pub topic: String,
+    /// Total number of model replies
+    pub max_turns: u32,
+}
+
+impl DialogueConfig {
+    /// Validate the setup before starting a session
+    pub fn validate(&self, config: &FlexibleApiConfig) -> Result<(), String> {
+        if self.topic.trim().is_empty() {
+            return Err("Enter a topic for the dialogue".to_string());
+        }
+        if self.participants.len() != 2 {
+            return Err("A dialogue needs exactly two participants".to_string());
+        }
+        if self.max_turns == 0 || self.max_turns > MAX_DIALOGUE_TURNS {
+            return Err(format!(
+                "Max turns must be between 1 and {}",
+                MAX_DIALOGUE_TURNS
+            ));
+        }
+        for participant in &self.participants {
+            if participant.name.trim().is_empty() {
+                return Err("Each participant needs a name".to_string());
+            }
+            if resolve_model(config, &participant.model).is_none() {
+                return Err(format!(
+                    "Select a configured model for {}",
+                    participant.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Number of model replies so far
+pub fn model_turns(session: &ChatSession) -> u32 {
+    session
+        .messages
+        .iter()
+        .filter(|m| m.role == MessageRole::Assistant)
+        .count() as u32
+}
+
+/// Index of the participant who speaks next
+pub fn next_speaker(session: &ChatSession) -> usize {
+    (model_turns(session) % 2) as usize
+}
+
+pub fn is_finished(session: &ChatSession) -> bool {
+    session
+        .dialogue
+        .as_ref()
+        .is_none_or(|dialogue| model_turns(session) >= dialogue.max_turns)
+}
+
+/// Speaker label for every message of a dialogue session, in order
+pub fn message_speakers(session: &ChatSession) -> Vec<Option<String>> {
+    let Some(dialogue) = &session.dialogue else {
+        return vec![None; session.messages.len()];
+    };
+    let mut replies = 0;
+    session
+        .messages
+        .iter()
+        .enumerate()
+        .map(|(index, message)| match message.role {
+            MessageRole::Assistant => {
+                let name = dialogue
+                    .participants
+                    .get(replies % 2)
+                    .map(|p| p.name.clone());
+                replies += 1;
+                name
+            }
+            MessageRole::User if index == 0 => Some("Topic".to_string()),
+            MessageRole::User => Some("Moderator".to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The conversation as seen by participant `speaker`: its own replies are
+/// assistant messages, everything else is a user message prefixed with who said
+/// it. Consecutive user messages are merged so roles keep alternating.
+pub fn participant_messages(session: &ChatSession, speaker: usize) -> Vec<Message> {
+    let speakers = message_speakers(session);
+    let mut messages: Vec<Message> = Vec::new();
+    let mut replies = 0;
+
+    for (message, label) in session.messages.iter().zip(speakers) {
+        let (role, content) = match message.role {
+            MessageRole::Assistant => {
+                let own = replies % 2 == speaker;
+                replies += 1;
+                if own {
+                    (MessageRole::Assistant, message.content.clone())
+                } else {
+                    (
+                        MessageRole::User,
+                        format!("{}: {}", label.unwrap_or_default(), message.content),
+                    )
+                }
+            }
+            MessageRole::User => (
+                MessageRole::User,
+                format!("{}: {}", label.unwrap_or_default(), message.content),
+            ),
+            _ => continue,
+        };
+
+        match messages.last_mut() {
+            Some(last) if last.role == MessageRole::User && role == MessageRole::User => {
+                last.content.push_str("\n\n");
+                last.content.push_str(&content);
+            }
+            _ => messages.push(Message {
+                id: message.id.clone(),
+                timestamp: message.timestamp,
+                ..Message::new(role, content)
+            }),
+        }
+    }
+    messages
+}
+
+fn resolve_model<'a>(config: &FlexibleApiConfig, model: &'a str) -> Option<(&'a str, &'a str)> {
+    model.split_once(',').filter(|(provider, model)| {
+        config
+            .get_provider(provider)
+            .is_some_and(|p| p.models.iter().any(|m| m == model))
+    })
+}
+
+/// Config for one participant's request: its model, its system prompt plus the
+/// dialogue framing, and no function tools
+fn participant_config(
+    config: &FlexibleApiConfig,
+    dialogue: &DialogueConfig,
+    speaker: usize,
+) -> FlexibleApiConfig {
+    let mut participant_config = config.clone();
+    let participant = &dialogue.participants[speaker];
+    let other = &dialogue.participants[1 - speaker];
+
+    if let Some((provider, model)) = resolve_model(config, &participant.model) {
+        participant_config.set_session_provider(provider, model);
+        participant_config.apply_model_defaults(provider, model);
+    }
+
+    let framing = format!(
+        "You are {name} in a conversation with {other} about the topic below. \
+         Messages from {other} are prefixed with \"{other}:\" and notes from the human \
+         moderator with \"Moderator:\". Reply only as {name}, without a name prefix, \
+         and keep your turn to a few paragraphs.",
+        name = participant.name,
+        other = other.name,
+    );
+    participant_config.system_prompt = if participant.system_prompt.trim().is_empty() {
+        framing
+    } else {
+        format!("{}\n\n{}", participant.system_prompt.trim(), framing)
+    };
+    participant_config.function_tools.clear();
+    participant_config
+}
+
+/// Let the participants take turns until the dialogue reaches its max turns or
+/// `is_current` reports that the run was interrupted.
+///
+/// Every reply is added to the session and emitted through `on_session_update`.
+/// A reply that arrives after an interrupt is dropped.
+pub async fn run_dialogue(
+    mut session: ChatSession,
+    llm_client: &FlexibleLLMClient,
+    config: &FlexibleApiConfig,
+    on_session_update: &Callback<ChatSession>,
+    is_current: impl Fn() -> bool,
+) -> Result<ChatSession, String> {
+    let Some(dialogue) = session.dialogue.clone() else {
+        return Err("This session is not a dialogue".to_string());
+    };
+    if dialogue.participants.len() != 2 {
+        return Err("A dialogue needs exactly two participants".to_string());
+    }
+
+    while !is_finished(&session) && is_current() {
+        let speaker = next_speaker(&session);
+        let messages = participant_messages(&session, speaker);
+        let speaker_config = participant_config(config, &dialogue, speaker);
+
+        let response = llm_client
+            .send_message(&messages, &speaker_config)
+            .await
+            .map_err(|e| format!("{}: {}", dialogue.participants[speaker].name, e))?;
+        if !is_current() {
+            break;
+        }
+
+        let metrics = response.timing.map(|timing| ResponseMetrics {
+            model: Some(speaker_config.get_current_provider_and_model().1),
+            ..ResponseMetrics::new(timing, response.usage.as_ref())
+        });
+        session.messages.push(Message {
+            id: format!("assistant_{}", js_sys::Date::now() as u64),
+            timestamp: js_sys::Date::now(),
+            metrics,
+            finish_reason: response.finish_reason,
+            reasoning: response.reasoning,
+            citations: response.citations,
+            native_tool_calls: response.native_tool_calls,
+            audio: response.audio,
+            ..Message::new(MessageRole::Assistant, response.content.unwrap_or_default())
+        });
+        session.updated_at = js_sys::Date::now();
+        on_session_update.emit(session.clone());
+    }
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: MessageRole, content: &str) -> Message {
+        Message {
+            id: content.to_string(),
+            ..Message::new(role, content)
+        }
+    }
+
+    fn dialogue_session(messages: Vec<Message>) -> ChatSession {
+        ChatSession {
+            id: "s".to_string(),
+            title: "Dialogue".to_string(),
+            messages,
+            created_at: 0.0,
+            updated_at: 0.0,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: false,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: Some(DialogueConfig {
+                participants: vec![
+                    DialogueParticipant {
+                        name: "Ada".to_string(),
+                        ..Default::default()
+                    },
+                    DialogueParticipant {
+                        name: "Bo".to_string(),
+                        ..Default::default()
+                    },
+                ],
+                topic: "Tabs or spaces".to_string(),
+                max_turns: 4,
+            }),
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    #[test]
+    fn labels_speakers_and_alternates_turns() {
+        let session = dialogue_session(vec![
+            message(MessageRole::User, "Tabs or spaces"),
+            message(MessageRole::Assistant, "Tabs"),
+            message(MessageRole::User, "Be civil"),
+            message(MessageRole::Assistant, "Spaces"),
+            message(MessageRole::Assistant, "Tabs again"),
+        ]);
+        let speakers: Vec<String> = message_speakers(&session)
+            .into_iter()
+            .map(Option::unwrap)
+            .collect();
+        assert_eq!(speakers, ["Topic", "Ada", "Moderator", "Bo", "Ada"]);
+        assert_eq!(next_speaker(&session), 1);
+        assert!(!is_finished(&session));
+    }
+
+    #[test]
+    fn builds_each_participants_perspective() {
+        let session = dialogue_session(vec![
+            message(MessageRole::User, "Tabs or spaces"),
+            message(MessageRole::Assistant, "Tabs"),
+            message(MessageRole::User, "Be civil"),
+        ]);
+
+        let bo = participant_messages(&session, 1);
+        assert_eq!(bo.len(), 1);
+        assert_eq!(bo[0].role, MessageRole::User);
+        assert_eq!(
+            bo[0].content,
+            "Topic: Tabs or spaces\n\nAda: Tabs\n\nModerator: Be civil"
+        );
+
+        let ada = participant_messages(&session, 0);
+        let roles: Vec<MessageRole> = ada.into_iter().map(|m| m.role).collect();
+        assert_eq!(
+            roles,
+            [MessageRole::User, MessageRole::Assistant, MessageRole::User]
+        );
+    }
+}
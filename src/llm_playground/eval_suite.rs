@@ -0,0 +1,485 @@
+// Prompt evaluation suites
+// A suite is a list of test cases run against one model; each case's output is
+// checked with assertions (text, regex, JSON schema or an LLM judge) so prompt
+// changes can be regression-tested. Suites live in their own localStorage key.
+
+use crate::llm_playground::{
+    flexible_client::FlexibleLLMClient, provider_config::FlexibleApiConfig, Message, MessageRole,
+};
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+const STORAGE_KEY_EVAL_SUITES: &str = "llm_playground_eval_suites";
+
+/// Check applied to a test case's output
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Assertion {
+    Contains {
+        text: String,
+        #[serde(default)]
+        case_sensitive: bool,
+    },
+    Regex {
+        pattern: String,
+    },
+    /// Output must be JSON (optionally inside a code fence) matching the schema.
+    /// The schema is kept as text so it can be edited freely.
+    JsonSchema {
+        schema: String,
+    },
+    /// Another model scores the output from 1 to 10 against the criteria
+    LlmJudge {
+        criteria: String,
+        min_score: u8,
+    },
+}
+
+impl Assertion {
+    pub fn kind_label(&self) -> &'static str {
+        match self {
+            Assertion::Contains { .. } => "Contains",
+            Assertion::Regex { .. } => "Regex",
+            Assertion::JsonSchema { .. } => "JSON schema",
+            Assertion::LlmJudge { .. } => "LLM judge",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TestCase {
+    pub id: String,
+    pub name: String,
+    pub input: String,
+    #[serde(default)]
+    pub assertions: Vec<Assertion>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct EvalSuite {
+    pub id: String,
+    pub name: String,
+    /// Used instead of the configured system prompt when not empty
+    #[serde(default)]
+    pub system_prompt: String,
+    #[serde(default)]
+    pub cases: Vec<TestCase>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssertionResult {
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaseResult {
+    pub output: Result<String, String>,
+    pub assertions: Vec<AssertionResult>,
+    pub latency_ms: f64,
+}
+
+impl CaseResult {
+    /// A case passes when the request succeeded and every assertion passed
+    pub fn passed(&self) -> bool {
+        self.output.is_ok() && self.assertions.iter().all(|a| a.passed)
+    }
+}
+
+/// All saved suites
+pub fn load_suites() -> Vec<EvalSuite> {
+    LocalStorage::get::<String>(STORAGE_KEY_EVAL_SUITES)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_suites(suites: &[EvalSuite]) {
+    if let Ok(suites_str) = serde_json::to_string(suites) {
+        let _ = LocalStorage::set(STORAGE_KEY_EVAL_SUITES, suites_str);
+    }
+}
+
+/// Run one test case and evaluate its assertions. LLM-judge assertions use
+/// `judge_config` (or the run's config when it is None).
+pub async fn run_case(
+    suite: &EvalSuite,
+    case: &TestCase,
+    llm_client: &FlexibleLLMClient,
+    config: &FlexibleApiConfig,
+    judge_config: Option<&FlexibleApiConfig>,
+) -> CaseResult {
+    let mut run_config = config.clone();
+    if !suite.system_prompt.trim().is_empty() {
+        run_config.system_prompt = suite.system_prompt.clone();
+    }
+
+    let started = js_sys::Date::now();
+    let output = llm_client
+        .send_message(&[user_message(&case.input)], &run_config)
+        .await
+        .map(|response| response.content.unwrap_or_default());
+    let latency_ms = js_sys::Date::now() - started;
+
+    let mut assertions = Vec::new();
+    if let Ok(text) = &output {
+        for assertion in &case.assertions {
+            let result = match assertion {
+                Assertion::LlmJudge {
+                    criteria,
+                    min_score,
+                } => {
+                    judge(
+                        criteria,
+                        *min_score,
+                        &case.input,
+                        text,
+                        llm_client,
+                        judge_config.unwrap_or(config),
+                    )
+                    .await
+                }
+                _ => check_assertion(assertion, text),
+            };
+            assertions.push(result);
+        }
+    }
+
+    CaseResult {
+        output,
+        assertions,
+        latency_ms,
+    }
+}
+
+/// Evaluate an assertion that doesn't need a model call
+pub fn check_assertion(assertion: &Assertion, output: &str) -> AssertionResult {
+    match assertion {
+        Assertion::Contains {
+            text,
+            case_sensitive,
+        } => {
+            let passed = if *case_sensitive {
+                output.contains(text.as_str())
+            } else {
+                output.to_lowercase().contains(&text.to_lowercase())
+            };
+            AssertionResult {
+                passed,
+                detail: format!(
+                    "{} \"{}\"",
+                    if passed { "Contains" } else { "Missing" },
+                    text
+                ),
+            }
+        }
+        Assertion::Regex { pattern } => match regex_matches(pattern, output) {
+            Ok(passed) => AssertionResult {
+                passed,
+                detail: format!(
+                    "{} /{}/",
+                    if passed { "Matches" } else { "Does not match" },
+                    pattern
+                ),
+            },
+            Err(error) => AssertionResult {
+                passed: false,
+                detail: error,
+            },
+        },
+        Assertion::JsonSchema { schema } => {
+            let schema = match serde_json::from_str::<Value>(schema) {
+                Ok(schema) => schema,
+                Err(e) => {
+                    return AssertionResult {
+                        passed: false,
+                        detail: format!("Schema is not valid JSON: {}", e),
+                    }
+                }
+            };
+            match serde_json::from_str::<Value>(strip_code_fence(output)) {
+                Ok(value) => match validate_json_schema(&value, &schema, "$") {
+                    Ok(()) => AssertionResult {
+                        passed: true,
+                        detail: "Valid JSON matching the schema".to_string(),
+                    },
+                    Err(error) => AssertionResult {
+                        passed: false,
+                        detail: error,
+                    },
+                },
+                Err(e) => AssertionResult {
+                    passed: false,
+                    detail: format!("Output is not valid JSON: {}", e),
+                },
+            }
+        }
+        Assertion::LlmJudge { .. } => AssertionResult {
+            passed: false,
+            detail: "LLM judge assertions need a model call".to_string(),
+        },
+    }
+}
+
+fn regex_matches(pattern: &str, text: &str) -> Result<bool, String> {
+    Ok(compile_regex(pattern, "")?.test(text))
+}
+
+/// A JS regular expression, or an error for an invalid pattern
+pub fn compile_regex(pattern: &str, flags: &str) -> Result<js_sys::RegExp, String> {
+    // Validate through the JS engine so an invalid pattern doesn't throw
+    let valid = js_sys::Function::new_with_args(
+        "p, f",
+        "try { new RegExp(p, f); return true; } catch (e) { return false; }",
+    )
+    .call2(&wasm_bindgen::JsValue::NULL, &pattern.into(), &flags.into())
+    .map(|v| v.as_bool().unwrap_or(false))
+    .unwrap_or(false);
+    if !valid {
+        return Err(format!("Invalid regex: /{}/", pattern));
+    }
+    Ok(js_sys::RegExp::new(pattern, flags))
+}
+
+/// The JSON body of a reply, without a surrounding markdown code fence
+pub fn strip_code_fence(output: &str) -> &str {
+    let trimmed = output.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+    let body = rest.split_once('\n').map_or("", |(_, body)| body);
+    body.trim_end().strip_suffix("```").unwrap_or(body).trim()
+}
+
+/// Validate `value` against the commonly used subset of JSON Schema: type,
+/// enum, required, properties, additionalProperties, items, minItems/maxItems,
+/// minLength/maxLength and minimum/maximum
+pub fn validate_json_schema(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| matches_type(value, t)) {
+            return Err(format!("{}: expected {}", path, types.join(" or ")));
+        }
+    }
+
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            return Err(format!(
+                "{}: {} is not one of the allowed values",
+                path, value
+            ));
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for key in required.iter().filter_map(|k| k.as_str()) {
+                    if !object.contains_key(key) {
+                        return Err(format!("{}: missing required property \"{}\"", path, key));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(|p| p.as_object());
+            for (key, property_value) in object {
+                match properties.and_then(|p| p.get(key)) {
+                    Some(property_schema) => validate_json_schema(
+                        property_value,
+                        property_schema,
+                        &format!("{}.{}", path, key),
+                    )?,
+                    None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                        return Err(format!("{}: unexpected property \"{}\"", path, key));
+                    }
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            check_bound(schema, "minItems", items.len(), path, |n, b| n >= b)?;
+            check_bound(schema, "maxItems", items.len(), path, |n, b| n <= b)?;
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_json_schema(item, item_schema, &format!("{}[{}]", path, index))?;
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count();
+            check_bound(schema, "minLength", length, path, |n, b| n >= b)?;
+            check_bound(schema, "maxLength", length, path, |n, b| n <= b)?;
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(minimum) = schema.get("minimum").and_then(|m| m.as_f64()) {
+                if number < minimum {
+                    return Err(format!(
+                        "{}: {} is below the minimum {}",
+                        path, number, minimum
+                    ));
+                }
+            }
+            if let Some(maximum) = schema.get("maximum").and_then(|m| m.as_f64()) {
+                if number > maximum {
+                    return Err(format!(
+                        "{}: {} is above the maximum {}",
+                        path, number, maximum
+                    ));
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn matches_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn check_bound(
+    schema: &Value,
+    keyword: &str,
+    actual: usize,
+    path: &str,
+    within: impl Fn(usize, usize) -> bool,
+) -> Result<(), String> {
+    match schema.get(keyword).and_then(|b| b.as_u64()) {
+        Some(bound) if !within(actual, bound as usize) => Err(format!(
+            "{}: violates {} {} (got {})",
+            path, keyword, bound, actual
+        )),
+        _ => Ok(()),
+    }
+}
+
+async fn judge(
+    criteria: &str,
+    min_score: u8,
+    input: &str,
+    output: &str,
+    llm_client: &FlexibleLLMClient,
+    judge_config: &FlexibleApiConfig,
+) -> AssertionResult {
+    let mut config = judge_config.clone();
+    config.system_prompt = "You are a strict evaluator of AI assistant responses. \
+        Score the response from 1 (fails the criteria) to 10 (fully meets them). \
+        Reply with the line \"SCORE: <n>\" followed by a one-sentence reason."
+        .to_string();
+    config.function_tools.clear();
+
+    let prompt = format!(
+        "Criteria:\n{}\n\nUser input:\n{}\n\nResponse to evaluate:\n{}",
+        criteria, input, output
+    );
+    match llm_client
+        .send_message(&[user_message(&prompt)], &config)
+        .await
+    {
+        Ok(response) => {
+            let verdict = response.content.unwrap_or_default();
+            match parse_judge_score(&verdict) {
+                Some(score) => AssertionResult {
+                    passed: score >= min_score,
+                    detail: format!(
+                        "Judge score {}/10 (needs {}): {}",
+                        score,
+                        min_score,
+                        verdict.trim()
+                    ),
+                },
+                None => AssertionResult {
+                    passed: false,
+                    detail: format!("Judge reply had no score: {}", verdict.trim()),
+                },
+            }
+        }
+        Err(error) => AssertionResult {
+            passed: false,
+            detail: format!("Judge request failed: {}", error),
+        },
+    }
+}
+
+/// Read "SCORE: n" (1-10) from a judge reply
+pub fn parse_judge_score(verdict: &str) -> Option<u8> {
+    let upper = verdict.to_uppercase();
+    let after = &upper[upper.find("SCORE")? + "SCORE".len()..];
+    let digits: String = after
+        .trim_start_matches(|c: char| c == ':' || c == '*' || c.is_whitespace())
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits
+        .parse::<u8>()
+        .ok()
+        .filter(|score| (1..=10).contains(score))
+}
+
+fn user_message(content: &str) -> Message {
+    Message {
+        id: format!("eval_{}", js_sys::Date::now() as u64),
+        timestamp: js_sys::Date::now(),
+        ..Message::new(MessageRole::User, content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_and_schema_assertions() {
+        let contains = Assertion::Contains {
+            text: "PARIS".to_string(),
+            case_sensitive: false,
+        };
+        assert!(check_assertion(&contains, "The capital is Paris.").passed);
+
+        let schema = serde_json::json!({
+            "type": "object",
+            "required": ["name", "tags"],
+            "properties": {
+                "name": { "type": "string", "minLength": 1 },
+                "tags": { "type": "array", "items": { "type": "string" }, "maxItems": 2 }
+            },
+            "additionalProperties": false
+        });
+        let assertion = Assertion::JsonSchema {
+            schema: schema.to_string(),
+        };
+        assert!(
+            check_assertion(
+                &assertion,
+                "```json\n{\"name\": \"a\", \"tags\": [\"x\"]}\n```"
+            )
+            .passed
+        );
+        assert!(!check_assertion(&assertion, "{\"name\": \"a\"}").passed);
+        assert!(!check_assertion(&assertion, "{\"name\": \"a\", \"tags\": [1]}").passed);
+        assert!(!check_assertion(&assertion, "{\"name\": \"a\", \"tags\": [], \"x\": 1}").passed);
+        assert!(!check_assertion(&assertion, "not json").passed);
+    }
+
+    #[test]
+    fn parses_judge_scores() {
+        assert_eq!(parse_judge_score("SCORE: 8\nClear and correct."), Some(8));
+        assert_eq!(parse_judge_score("**Score:** 10 - perfect"), Some(10));
+        assert_eq!(parse_judge_score("Score: 11"), None);
+        assert_eq!(parse_judge_score("Looks good"), None);
+    }
+}
@@ -0,0 +1,210 @@
+// Playground event bus
+// Components publish typed events to one dispatcher, shared through a Yew
+// context, instead of receiving a callback prop for every layer they sit
+// under. The playground subscribes and applies the events to its state.
+
+use crate::llm_playground::{
+    components::notification::NotificationMessage, desktop_tools::ApprovalRequest,
+    provider_config::FlexibleApiConfig, session_store::SessionAction, ChatSession,
+};
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use yew::Callback;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PlaygroundEvent {
+    /// Show a toast notification
+    Notify(NotificationMessage),
+    /// A session changed and should be stored
+    SessionUpdated(ChatSession),
+    /// A single change to a session, applied without sending it whole
+    SessionChanged(Box<SessionAction>),
+    /// The configuration changed outside the settings panel
+    ConfigChanged(Box<FlexibleApiConfig>),
+    /// A desktop tool call waits for the user's approval
+    ToolApproval(ApprovalRequest),
+}
+
+type Handler = Rc<dyn Fn(&PlaygroundEvent)>;
+
+#[derive(Default)]
+struct Subscribers {
+    next_id: Cell<u64>,
+    handlers: RefCell<Vec<(u64, Handler)>>,
+}
+
+/// Central dispatcher. Clones share the same subscribers.
+#[derive(Clone, Default)]
+pub struct EventBus {
+    inner: Rc<Subscribers>,
+}
+
+impl PartialEq for EventBus {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `handler` for every published event until the subscription is dropped
+    pub fn subscribe(&self, handler: impl Fn(&PlaygroundEvent) + 'static) -> Subscription {
+        let id = self.inner.next_id.get();
+        self.inner.next_id.set(id + 1);
+        self.inner
+            .handlers
+            .borrow_mut()
+            .push((id, Rc::new(handler)));
+        Subscription {
+            subscribers: Rc::downgrade(&self.inner),
+            id,
+        }
+    }
+
+    /// Deliver an event to the subscribers in the order they subscribed.
+    /// Handlers may publish or (un)subscribe; that applies from the next event.
+    pub fn publish(&self, event: PlaygroundEvent) {
+        let handlers: Vec<Handler> = self
+            .inner
+            .handlers
+            .borrow()
+            .iter()
+            .map(|(_, handler)| handler.clone())
+            .collect();
+        for handler in handlers {
+            handler(&event);
+        }
+    }
+
+    /// A callback publishing the event built from its argument, e.g.
+    /// `bus.callback(PlaygroundEvent::Notify)`
+    pub fn callback<T: 'static>(
+        &self,
+        to_event: impl Fn(T) -> PlaygroundEvent + 'static,
+    ) -> Callback<T> {
+        let bus = self.clone();
+        Callback::from(move |value| bus.publish(to_event(value)))
+    }
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus")
+            .field("subscribers", &self.inner.handlers.borrow().len())
+            .finish()
+    }
+}
+
+/// Unsubscribes its handler when dropped
+#[must_use = "the handler is removed when the subscription is dropped"]
+pub struct Subscription {
+    subscribers: Weak<Subscribers>,
+    id: u64,
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(subscribers) = self.subscribers.upgrade() {
+            subscribers
+                .handlers
+                .borrow_mut()
+                .retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str) -> ChatSession {
+        ChatSession {
+            id: id.to_string(),
+            title: String::new(),
+            messages: Vec::new(),
+            created_at: 0.0,
+            updated_at: 0.0,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: false,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    fn session_id(event: &PlaygroundEvent) -> String {
+        match event {
+            PlaygroundEvent::SessionUpdated(session) => session.id.clone(),
+            _ => String::new(),
+        }
+    }
+
+    #[test]
+    fn delivers_in_order_until_unsubscribed() {
+        let bus = EventBus::new();
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let first = {
+            let log = log.clone();
+            bus.subscribe(move |event| log.borrow_mut().push(format!("a:{}", session_id(event))))
+        };
+        let _second = {
+            let log = log.clone();
+            bus.subscribe(move |event| log.borrow_mut().push(format!("b:{}", session_id(event))))
+        };
+
+        bus.callback(PlaygroundEvent::SessionUpdated)
+            .emit(session("s1"));
+        drop(first);
+        bus.publish(PlaygroundEvent::SessionUpdated(session("s2")));
+
+        assert_eq!(*log.borrow(), ["a:s1", "b:s1", "b:s2"]);
+        assert_eq!(bus.inner.handlers.borrow().len(), 1);
+        assert_eq!(bus.clone(), bus);
+        assert_ne!(EventBus::new(), bus);
+    }
+
+    #[test]
+    fn handlers_can_publish_and_unsubscribe() {
+        let bus = EventBus::new();
+        let seen = Rc::new(RefCell::new(Vec::new()));
+
+        // A handler that reacts to s1 by publishing s2
+        let _relay = {
+            let relay_bus = bus.clone();
+            bus.subscribe(move |event| {
+                if session_id(event) == "s1" {
+                    relay_bus.publish(PlaygroundEvent::SessionUpdated(session("s2")));
+                }
+            })
+        };
+        // A one-shot handler that drops its own subscription
+        let once: Rc<RefCell<Option<Subscription>>> = Rc::new(RefCell::new(None));
+        let subscription = {
+            let once = once.clone();
+            let seen = seen.clone();
+            bus.subscribe(move |event| {
+                seen.borrow_mut().push(session_id(event));
+                once.borrow_mut().take();
+            })
+        };
+        *once.borrow_mut() = Some(subscription);
+
+        bus.publish(PlaygroundEvent::SessionUpdated(session("s1")));
+        bus.publish(PlaygroundEvent::SessionUpdated(session("s3")));
+
+        // The relayed s2 is handled while s1 is being delivered; the one-shot
+        // handler still gets s1, which was already under way, but not s3
+        assert_eq!(*seen.borrow(), ["s2", "s1"]);
+        assert_eq!(bus.inner.handlers.borrow().len(), 1);
+    }
+}
@@ -24,6 +24,7 @@ pub async fn mcp_integration_example() -> Result<(), String> {
         url: Some("https://api.githubcopilot.com/mcp/".to_string()),
         headers: Some(github_headers),
         enabled: true,
+        tool_prefix: None,
     });
     
     // Add a custom MCP server
@@ -33,6 +34,7 @@ pub async fn mcp_integration_example() -> Result<(), String> {
         url: Some("http://localhost:8080/mcp/".to_string()),
         headers: None,
         enabled: true,
+        tool_prefix: None,
     });
 
     // 2. Initialize MCP client
@@ -63,7 +65,7 @@ pub async fn mcp_integration_example() -> Result<(), String> {
                 "sort": "stars"
             });
             
-            match execute_builtin_tool(tool_name, &arguments, Some(&mcp_client)).await {
+            match execute_builtin_tool(tool_name, &arguments, Some(&mcp_client), None, &Default::default()).await {
                 Ok(result) => {
                     println!("MCP tool result: {}", result);
                 }
@@ -95,6 +97,7 @@ pub fn configure_mcp_servers_example() -> McpConfig {
         url: Some("https://api.githubcopilot.com/mcp/".to_string()),
         headers: Some(github_headers),
         enabled: true,
+        tool_prefix: None,
     });
     
     // Example 2: Local development MCP server
@@ -104,6 +107,7 @@ pub fn configure_mcp_servers_example() -> McpConfig {
         url: Some("http://localhost:3000/mcp/".to_string()),
         headers: None,
         enabled: false, // Disabled by default
+        tool_prefix: None,
     });
     
     // Example 3: Custom API with authentication
@@ -117,6 +121,7 @@ pub fn configure_mcp_servers_example() -> McpConfig {
         url: Some("https://api.example.com/mcp/".to_string()),
         headers: Some(custom_headers),
         enabled: true,
+        tool_prefix: None,
     });
     
     config
@@ -139,7 +144,7 @@ pub async fn handle_mcp_function_call(
     }
     
     // Handle other built-in tools
-    execute_builtin_tool(tool_name, arguments, mcp_client).await
+    execute_builtin_tool(tool_name, arguments, mcp_client, None, &Default::default()).await
 }
 
 /// Example of updating function tools list with MCP tools
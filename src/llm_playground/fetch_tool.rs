@@ -0,0 +1,653 @@
+// Fetch tool output
+// What the built-in fetch tool hands the model: the raw text, parsed JSON, or
+// for web pages the readable part converted to markdown, so a page isn't a
+// wall of scripts and markup. The main article (or <main>) is kept when the
+// page marks one, and navigation, scripts, forms and the like are dropped.
+// The result is cut to a byte limit from the settings, which a call can
+// lower but not raise.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FetchFormat {
+    /// By content type: JSON parsed, HTML as markdown, the rest as text
+    #[default]
+    Auto,
+    Text,
+    Json,
+    Markdown,
+}
+
+impl FetchFormat {
+    pub const ALL: [FetchFormat; 4] = [
+        FetchFormat::Auto,
+        FetchFormat::Text,
+        FetchFormat::Json,
+        FetchFormat::Markdown,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            FetchFormat::Auto => "auto",
+            FetchFormat::Text => "text",
+            FetchFormat::Json => "json",
+            FetchFormat::Markdown => "markdown",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            FetchFormat::Auto => "By content type",
+            FetchFormat::Text => "Raw text",
+            FetchFormat::Json => "JSON",
+            FetchFormat::Markdown => "Page as markdown",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.id() == value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FetchToolSettings {
+    /// Used when a call doesn't ask for a format
+    pub format: FetchFormat,
+    /// Largest body handed to the model, in bytes; 0 for no limit
+    pub max_bytes: usize,
+}
+
+impl Default for FetchToolSettings {
+    fn default() -> Self {
+        Self {
+            format: FetchFormat::Auto,
+            max_bytes: 20_000,
+        }
+    }
+}
+
+impl FetchToolSettings {
+    /// The format and byte limit for a call with these arguments
+    pub fn for_call(&self, arguments: &Value) -> (FetchFormat, usize) {
+        let format = arguments
+            .get("format")
+            .and_then(Value::as_str)
+            .and_then(FetchFormat::parse)
+            .unwrap_or(self.format);
+        let requested = arguments
+            .get("max_bytes")
+            .and_then(Value::as_u64)
+            .map(|bytes| bytes as usize);
+        let max_bytes = match (requested, self.max_bytes) {
+            (Some(requested), 0) => requested,
+            (Some(requested), limit) => requested.min(limit),
+            (None, limit) => limit,
+        };
+        (format, max_bytes)
+    }
+}
+
+/// The fields describing a fetched body: `format`, `body`, the page `title`
+/// when there is one, whether it was `truncated` and its `total_bytes`
+pub fn process(url: &str, content_type: &str, text: &str, format: FetchFormat, max_bytes: usize) -> Map<String, Value> {
+    let format = match format {
+        FetchFormat::Auto if content_type.contains("json") => FetchFormat::Json,
+        FetchFormat::Auto if content_type.contains("html") => FetchFormat::Markdown,
+        FetchFormat::Auto => FetchFormat::Text,
+        format => format,
+    };
+    let mut fields = Map::new();
+    let (format, body) = match format {
+        FetchFormat::Json => match serde_json::from_str::<Value>(text) {
+            // Whole JSON when it fits, its text cut to size when it doesn't
+            Ok(value) if max_bytes == 0 || text.len() <= max_bytes => {
+                fields.insert("format".to_string(), json!("json"));
+                fields.insert("body".to_string(), value);
+                fields.insert("truncated".to_string(), json!(false));
+                fields.insert("total_bytes".to_string(), json!(text.len()));
+                return fields;
+            }
+            Ok(_) => (FetchFormat::Json, text.to_string()),
+            Err(_) => (FetchFormat::Text, text.to_string()),
+        },
+        FetchFormat::Markdown => {
+            let (title, markdown) = html_to_markdown(text, url);
+            if let Some(title) = title {
+                fields.insert("title".to_string(), json!(title));
+            }
+            (FetchFormat::Markdown, markdown)
+        }
+        _ => (FetchFormat::Text, text.to_string()),
+    };
+    let total_bytes = body.len();
+    let (body, truncated) = truncate(&body, max_bytes);
+    fields.insert("format".to_string(), json!(format.id()));
+    fields.insert("body".to_string(), json!(body));
+    fields.insert("truncated".to_string(), json!(truncated));
+    fields.insert("total_bytes".to_string(), json!(total_bytes));
+    fields
+}
+
+/// `text` cut to at most `max_bytes` on a character boundary; 0 keeps it all
+fn truncate(text: &str, max_bytes: usize) -> (&str, bool) {
+    if max_bytes == 0 || text.len() <= max_bytes {
+        return (text, false);
+    }
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (&text[..end], true)
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Open(String, &'a str),
+    Close(String),
+    Text(&'a str),
+}
+
+/// Elements whose text is code or UI, never page content
+const SKIPPED: [&str; 12] = [
+    "script", "style", "noscript", "svg", "template", "nav", "footer", "aside", "form", "iframe", "button", "head",
+];
+/// Elements that have no closing tag
+const VOID: [&str; 12] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "wbr",
+];
+
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+    while !rest.is_empty() {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            continue;
+        }
+        let is_tag = rest.starts_with('<')
+            && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/');
+        if !is_tag {
+            // Text runs to the next tag; a '<' that starts none is text too
+            let first = rest.chars().next().map_or(1, char::len_utf8);
+            let end = rest[first..].find('<').map_or(rest.len(), |end| end + first);
+            tokens.push(Token::Text(&rest[..end]));
+            rest = &rest[end..];
+            continue;
+        }
+        let end = tag_end(rest);
+        let inner = rest[1..end].trim_end_matches('/');
+        rest = rest.get(end + 1..).unwrap_or("");
+        if let Some(name) = inner.strip_prefix('/') {
+            tokens.push(Token::Close(name.trim().to_ascii_lowercase()));
+            continue;
+        }
+        let name_end = inner
+            .find(|c: char| c.is_whitespace())
+            .unwrap_or(inner.len());
+        let name = inner[..name_end].to_ascii_lowercase();
+        let attributes = &inner[name_end..];
+        // Script and style bodies may hold '<'; skip to their closing tag
+        if matches!(name.as_str(), "script" | "style" | "title" | "textarea") {
+            let closing = format!("</{}", name);
+            let body_end = rest.to_ascii_lowercase().find(&closing).unwrap_or(rest.len());
+            let body = &rest[..body_end];
+            rest = &rest[body_end..];
+            rest = rest.find('>').map_or("", |end| &rest[end + 1..]);
+            tokens.push(Token::Open(name.clone(), attributes));
+            tokens.push(Token::Text(body));
+            tokens.push(Token::Close(name));
+            continue;
+        }
+        tokens.push(Token::Open(name, attributes));
+    }
+    tokens
+}
+
+/// Index of the '>' closing the tag `tag` starts with, quotes respected
+fn tag_end(tag: &str) -> usize {
+    let mut quote = None;
+    for (index, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return index,
+            _ => {}
+        }
+    }
+    tag.len()
+}
+
+/// The value of `name` among a tag's attributes
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    let mut rest = attributes;
+    while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+        rest = &rest[start..];
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = &rest[..key_end];
+        rest = rest[key_end..].trim_start();
+        let Some(value) = rest.strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let (found, after) = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let body = &value[1..];
+                let end = body.find(quote).unwrap_or(body.len());
+                (&body[..end], body.get(end + 1..).unwrap_or(""))
+            }
+            _ => {
+                let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                (&value[..end], &value[end..])
+            }
+        };
+        if key.eq_ignore_ascii_case(name) {
+            return Some(decode_entities(found));
+        }
+        rest = after;
+    }
+    None
+}
+
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..].find(';').filter(|&end| end <= 10).map(|end| &rest[1..end + 1]);
+        let character = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "mdash" => Some('—'),
+            "ndash" => Some('–'),
+            "hellip" => Some('…'),
+            "copy" => Some('©'),
+            "lsquo" => Some('‘'),
+            "rsquo" => Some('’'),
+            "ldquo" => Some('“'),
+            "rdquo" => Some('”'),
+            _ => {
+                let number = entity.strip_prefix('#')?;
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => number.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+        match (entity, character) {
+            (Some(entity), Some(character)) => {
+                decoded.push(character);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// `href` as an absolute URL, read against the page at `base`
+fn resolve_link(base: &str, href: &str) -> String {
+    if href.contains("://") || href.starts_with("mailto:") {
+        return href.to_string();
+    }
+    let Some((scheme, after_scheme)) = base.split_once("://") else {
+        return href.to_string();
+    };
+    if let Some(host_relative) = href.strip_prefix("//") {
+        return format!("{}://{}", scheme, host_relative);
+    }
+    let origin_end = after_scheme.find('/').unwrap_or(after_scheme.len());
+    let origin = &base[..scheme.len() + 3 + origin_end];
+    if href.starts_with('/') {
+        return format!("{}{}", origin, href);
+    }
+    let path = after_scheme[origin_end..].split(['?', '#']).next().unwrap_or("");
+    let directory = path.rfind('/').map_or("/", |end| &path[..end + 1]);
+    format!("{}{}{}", origin, directory, href)
+}
+
+/// The tokens of the page's main content: the first <article>, else <main>,
+/// else everything
+fn main_content<'a, 'b>(tokens: &'b [Token<'a>]) -> (&'b [Token<'a>], bool) {
+    for container in ["article", "main"] {
+        let Some(start) = tokens
+            .iter()
+            .position(|token| matches!(token, Token::Open(name, _) if name == container))
+        else {
+            continue;
+        };
+        let mut depth = 0;
+        for (index, token) in tokens.iter().enumerate().skip(start) {
+            match token {
+                Token::Open(name, _) if name == container => depth += 1,
+                Token::Close(name) if name == container => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return (&tokens[start + 1..index], true);
+                    }
+                }
+                _ => {}
+            }
+        }
+        return (&tokens[start + 1..], true);
+    }
+    (tokens, false)
+}
+
+#[derive(Default)]
+struct MarkdownWriter {
+    out: String,
+    /// Ordered lists hold the next item's number
+    lists: Vec<Option<usize>>,
+    /// Where each open link's text starts, and its target
+    links: Vec<Option<(usize, String)>>,
+    in_pre: bool,
+}
+
+impl MarkdownWriter {
+    /// Start a new paragraph
+    fn block(&mut self) {
+        if !self.out.is_empty() {
+            while self.out.ends_with(' ') {
+                self.out.pop();
+            }
+            if !self.out.ends_with("\n\n") {
+                self.out.push_str(if self.out.ends_with('\n') { "\n" } else { "\n\n" });
+            }
+        }
+    }
+
+    fn line(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let text = decode_entities(text);
+        if self.in_pre {
+            self.out.push_str(&text);
+            return;
+        }
+        let words: Vec<&str> = text.split_whitespace().collect();
+        if words.is_empty() {
+            if !text.is_empty() && !self.out.is_empty() && !self.out.ends_with([' ', '\n', '[']) {
+                self.out.push(' ');
+            }
+            return;
+        }
+        if text.starts_with(char::is_whitespace) && !self.out.is_empty() && !self.out.ends_with([' ', '\n', '[']) {
+            self.out.push(' ');
+        }
+        self.out.push_str(&words.join(" "));
+        if text.ends_with(char::is_whitespace) {
+            self.out.push(' ');
+        }
+    }
+
+    fn open(&mut self, name: &str, attributes: &str, base: &str) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.block();
+                let level = name[1..].parse().unwrap_or(1);
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+            }
+            "p" | "div" | "section" | "article" | "main" | "header" | "table" | "figure" | "dl" => self.block(),
+            "blockquote" => {
+                self.block();
+                self.out.push_str("> ");
+            }
+            "tr" | "dt" | "dd" => self.line(),
+            "td" | "th" => self.out.push(' '),
+            "br" => self.out.push('\n'),
+            "hr" => {
+                self.block();
+                self.out.push_str("---");
+                self.block();
+            }
+            "ul" | "ol" => {
+                if self.lists.is_empty() {
+                    self.block();
+                }
+                self.lists.push((name == "ol").then_some(1));
+            }
+            "li" => {
+                self.line();
+                let depth = self.lists.len().max(1);
+                self.out.push_str(&"  ".repeat(depth - 1));
+                match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        self.out.push_str(&format!("{}. ", number));
+                        *number += 1;
+                    }
+                    _ => self.out.push_str("- "),
+                }
+            }
+            "a" => {
+                let href = attribute(attributes, "href")
+                    .filter(|href| !href.starts_with('#') && !href.starts_with("javascript:"));
+                self.links.push(href.map(|href| {
+                    self.out.push('[');
+                    (self.out.len(), resolve_link(base, &href))
+                }));
+            }
+            "strong" | "b" if !self.in_pre => self.out.push_str("**"),
+            "em" | "i" if !self.in_pre => self.out.push('*'),
+            "code" if !self.in_pre => self.out.push('`'),
+            "pre" => {
+                self.block();
+                self.out.push_str("```\n");
+                self.in_pre = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn close(&mut self, name: &str) {
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "p" | "div" | "section" | "article" | "main" | "header"
+            | "table" | "figure" | "dl" | "blockquote" => self.block(),
+            "ul" | "ol" => {
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.block();
+                }
+            }
+            "a" => {
+                if let Some(Some((start, href))) = self.links.pop() {
+                    if self.out.len() == start {
+                        // A link without text, e.g. around an image
+                        self.out.pop();
+                    } else {
+                        self.out.push_str(&format!("]({})", href));
+                    }
+                }
+            }
+            "strong" | "b" if !self.in_pre => self.out.push_str("**"),
+            "em" | "i" if !self.in_pre => self.out.push('*'),
+            "code" if !self.in_pre => self.out.push('`'),
+            "pre" => {
+                self.in_pre = false;
+                self.line();
+                self.out.push_str("```");
+                self.block();
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut markdown = String::new();
+        let mut blank_lines = 0;
+        for line in self.out.lines() {
+            let line = line.trim_end();
+            if line.is_empty() {
+                blank_lines += 1;
+                if blank_lines > 1 {
+                    continue;
+                }
+            } else {
+                blank_lines = 0;
+            }
+            markdown.push_str(line);
+            markdown.push('\n');
+        }
+        markdown.trim().to_string()
+    }
+}
+
+/// The page's title and its readable content as markdown. Links are made
+/// absolute against `url`.
+pub fn html_to_markdown(html: &str, url: &str) -> (Option<String>, String) {
+    let tokens = tokenize(html);
+    let title = tokens.windows(2).find_map(|pair| match pair {
+        [Token::Open(name, _), Token::Text(text)] if name == "title" => {
+            let title = decode_entities(text).split_whitespace().collect::<Vec<_>>().join(" ");
+            (!title.is_empty()).then_some(title)
+        }
+        _ => None,
+    });
+    let (content, in_container) = main_content(&tokens);
+
+    let mut writer = MarkdownWriter::default();
+    // The skipped element being passed over and how deeply it is nested
+    let mut skipping: Option<(&str, usize)> = None;
+    for token in content {
+        if let Some((skipped, depth)) = skipping.as_mut() {
+            match token {
+                Token::Open(name, _) if name == *skipped => *depth += 1,
+                Token::Close(name) if name == *skipped => {
+                    *depth -= 1;
+                    if *depth == 0 {
+                        skipping = None;
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        match token {
+            Token::Open(name, attributes) => {
+                // A site's header is skipped, an article's is kept
+                let skipped = SKIPPED.contains(&name.as_str()) || (name == "header" && !in_container) || name == "title";
+                if skipped && !VOID.contains(&name.as_str()) {
+                    skipping = Some((name.as_str(), 1));
+                } else {
+                    writer.open(name, attributes, url);
+                }
+            }
+            Token::Close(name) => writer.close(name),
+            Token::Text(text) => writer.text(text),
+        }
+    }
+    (title, writer.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r##"<!DOCTYPE html>
+<html><head><title>Rust &amp; Wasm</title><style>p { color: red; }</style></head>
+<body>
+  <header><a href="/">Home</a></header>
+  <nav><ul><li><a href="/docs">Docs</a></li></ul></nav>
+  <article>
+    <h1>Getting   started</h1>
+    <p>Install <code>wasm-pack</code> with <a href="/install?x=1">the installer</a>, then run it.</p>
+    <ul><li>Fast</li><li><strong>Small</strong> builds</li></ul>
+    <pre>fn main() {
+    println!("hi");
+}</pre>
+    <script>if (a < b) { track(); }</script>
+    <p>See <a href="guide.html">the guide</a> &mdash; or <a href="#top">top</a>.</p>
+  </article>
+  <footer>© 2024</footer>
+</body></html>"##;
+
+    #[test]
+    fn pages_become_markdown_of_their_article() {
+        let (title, markdown) = html_to_markdown(PAGE, "https://example.com/book/intro.html");
+        assert_eq!(title.as_deref(), Some("Rust & Wasm"));
+        assert_eq!(
+            markdown,
+            "# Getting started\n\n\
+             Install `wasm-pack` with [the installer](https://example.com/install?x=1), then run it.\n\n\
+             - Fast\n\
+             - **Small** builds\n\n\
+             ```\nfn main() {\n    println!(\"hi\");\n}\n```\n\n\
+             See [the guide](https://example.com/book/guide.html) — or top."
+        );
+    }
+
+    #[test]
+    fn pages_without_an_article_drop_their_chrome() {
+        let html = "<body><header>Site</header><nav>Menu</nav><h2>News</h2><ol><li>One</li><li>Two</li></ol><footer>Legal</footer></body>";
+        let (title, markdown) = html_to_markdown(html, "https://example.com");
+        assert_eq!(title, None);
+        assert_eq!(markdown, "## News\n\n1. One\n2. Two");
+    }
+
+    #[test]
+    fn entities_and_attributes_are_decoded() {
+        assert_eq!(decode_entities("a &lt;b&gt; &#65;&#x42; &unknown; &"), "a <b> AB &unknown; &");
+        assert_eq!(attribute(r#" class=x href='/a?b=1&amp;c=2' "#, "href").as_deref(), Some("/a?b=1&c=2"));
+        assert_eq!(attribute(" data-x=\"y\"", "href"), None);
+    }
+
+    #[test]
+    fn bodies_are_cut_on_a_character_boundary() {
+        let fields = process("https://example.com", "text/plain", "héllo", FetchFormat::Auto, 2);
+        assert_eq!(fields["format"], "text");
+        assert_eq!(fields["body"], "h");
+        assert_eq!(fields["truncated"], true);
+        assert_eq!(fields["total_bytes"], 6);
+    }
+
+    #[test]
+    fn json_is_parsed_when_it_fits() {
+        let fields = process("https://example.com", "application/json", r#"{"a": [1, 2]}"#, FetchFormat::Auto, 100);
+        assert_eq!(fields["body"]["a"][1], 2);
+        assert_eq!(fields["truncated"], false);
+
+        let fields = process("https://example.com", "application/json", r#"{"a": [1, 2]}"#, FetchFormat::Auto, 5);
+        assert_eq!(fields["format"], "json");
+        assert_eq!(fields["body"], r#"{"a":"#);
+
+        // Asking for JSON from a page that isn't gives its text
+        let fields = process("https://example.com", "text/html", "<p>Hi</p>", FetchFormat::Json, 0);
+        assert_eq!(fields["format"], "text");
+        assert_eq!(fields["body"], "<p>Hi</p>");
+    }
+
+    #[test]
+    fn calls_can_lower_the_byte_limit_only() {
+        let settings = FetchToolSettings::default();
+        let arguments = json!({ "format": "text", "max_bytes": 100 });
+        assert_eq!(settings.for_call(&arguments), (FetchFormat::Text, 100));
+        assert_eq!(settings.for_call(&json!({ "max_bytes": 1_000_000 })).1, settings.max_bytes);
+        assert_eq!(settings.for_call(&json!({ "format": "pdf" })).0, FetchFormat::Auto);
+    }
+}
@@ -0,0 +1,266 @@
+// Fine-tuning dataset export
+// Turns chat sessions into JSONL training files: OpenAI chat fine-tuning
+// (one `{"messages": [...]}` line per session, with per-reply weights) or
+// Gemini supervised tuning (`systemInstruction` plus alternating `contents`).
+// Only text turns are exported; tool calls and their results are skipped.
+
+use crate::llm_playground::{ChatSession, Message, MessageRole};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DatasetFormat {
+    OpenAiChat,
+    Gemini,
+}
+
+impl DatasetFormat {
+    pub const ALL: [DatasetFormat; 2] = [DatasetFormat::OpenAiChat, DatasetFormat::Gemini];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DatasetFormat::OpenAiChat => "OpenAI chat (JSONL)",
+            DatasetFormat::Gemini => "Gemini tuning (JSONL)",
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            DatasetFormat::OpenAiChat => "openai",
+            DatasetFormat::Gemini => "gemini",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Self {
+        if key == "gemini" {
+            DatasetFormat::Gemini
+        } else {
+            DatasetFormat::OpenAiChat
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("fine-tune-{}.jsonl", self.key())
+    }
+}
+
+/// What goes into the dataset
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DatasetSelection {
+    /// Session ids, one training example each
+    pub sessions: HashSet<String>,
+    /// Messages left out of their example
+    pub excluded_messages: HashSet<String>,
+    /// Assistant replies kept as context but not trained on (OpenAI weight 0)
+    pub zero_weight: HashSet<String>,
+}
+
+impl DatasetSelection {
+    pub fn includes(&self, message: &Message) -> bool {
+        !self.excluded_messages.contains(&message.id)
+    }
+
+    pub fn weight(&self, message: &Message) -> u8 {
+        if self.zero_weight.contains(&message.id) {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+/// Text turns of a session that can go into an example
+pub fn exportable_messages(session: &ChatSession) -> Vec<&Message> {
+    session
+        .messages
+        .iter()
+        .filter(|m| m.role != MessageRole::Function && m.function_response.is_none())
+        .filter(|m| !m.content.trim().is_empty())
+        .filter(|m| !m.moderation.as_ref().is_some_and(|r| r.blocked))
+        .collect()
+}
+
+/// One OpenAI chat fine-tuning example, or None when no reply is trained on
+pub fn openai_example(
+    session: &ChatSession,
+    system_prompt: &str,
+    selection: &DatasetSelection,
+) -> Option<Value> {
+    let mut messages = Vec::new();
+    if !system_prompt.trim().is_empty() {
+        messages.push(json!({ "role": "system", "content": system_prompt.trim() }));
+    }
+    let mut trained_replies = 0;
+    for message in exportable_messages(session) {
+        if !selection.includes(message) {
+            continue;
+        }
+        messages.push(match message.role {
+            MessageRole::Assistant => {
+                let weight = selection.weight(message);
+                trained_replies += weight as usize;
+                json!({ "role": "assistant", "content": message.content, "weight": weight })
+            }
+            MessageRole::System | MessageRole::Developer => {
+                json!({ "role": "system", "content": message.content })
+            }
+            _ => json!({ "role": "user", "content": message.content }),
+        });
+    }
+    (trained_replies > 0).then(|| json!({ "messages": messages }))
+}
+
+/// One Gemini tuning example, or None without a model turn. System messages
+/// join the system instruction and consecutive turns of one role are merged,
+/// since Gemini needs user and model turns to alternate.
+pub fn gemini_example(
+    session: &ChatSession,
+    system_prompt: &str,
+    selection: &DatasetSelection,
+) -> Option<Value> {
+    let mut system_parts: Vec<&str> = Vec::new();
+    if !system_prompt.trim().is_empty() {
+        system_parts.push(system_prompt.trim());
+    }
+    let mut turns: Vec<(&str, Vec<&str>)> = Vec::new();
+    for message in exportable_messages(session) {
+        if !selection.includes(message) {
+            continue;
+        }
+        let role = match message.role {
+            MessageRole::System | MessageRole::Developer => {
+                system_parts.push(&message.content);
+                continue;
+            }
+            MessageRole::Assistant => "model",
+            _ => "user",
+        };
+        match turns.last_mut() {
+            Some((last_role, texts)) if *last_role == role => texts.push(&message.content),
+            _ => turns.push((role, vec![&message.content])),
+        }
+    }
+    // Tuning data starts with a user turn
+    while turns.first().is_some_and(|(role, _)| *role == "model") {
+        turns.remove(0);
+    }
+    if !turns.iter().any(|(role, _)| *role == "model") {
+        return None;
+    }
+
+    let contents: Vec<Value> = turns
+        .into_iter()
+        .map(|(role, texts)| json!({ "role": role, "parts": [{ "text": texts.join("\n\n") }] }))
+        .collect();
+    let mut example = json!({ "contents": contents });
+    if !system_parts.is_empty() {
+        example["systemInstruction"] = json!({
+            "role": "system",
+            "parts": [{ "text": system_parts.join("\n\n") }]
+        });
+    }
+    Some(example)
+}
+
+/// JSONL for the selected sessions, and how many examples it holds
+pub fn build_dataset<'a>(
+    sessions: impl IntoIterator<Item = &'a ChatSession>,
+    system_prompt: &str,
+    selection: &DatasetSelection,
+    format: DatasetFormat,
+) -> (String, usize) {
+    let lines: Vec<String> = sessions
+        .into_iter()
+        .filter(|session| selection.sessions.contains(&session.id))
+        .filter_map(|session| match format {
+            DatasetFormat::OpenAiChat => openai_example(session, system_prompt, selection),
+            DatasetFormat::Gemini => gemini_example(session, system_prompt, selection),
+        })
+        .map(|example| example.to_string())
+        .collect();
+    let count = lines.len();
+    let mut jsonl = lines.join("\n");
+    if count > 0 {
+        jsonl.push('\n');
+    }
+    (jsonl, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::test_message;
+
+    fn session() -> ChatSession {
+        ChatSession {
+            id: "s1".to_string(),
+            title: "Test".to_string(),
+            messages: vec![
+                test_message("m1", MessageRole::User, "Hi"),
+                test_message("m2", MessageRole::Assistant, "Hello!"),
+                test_message("m3", MessageRole::User, "Weather?"),
+                test_message("m4", MessageRole::Function, "{\"temp\": 20}"),
+                test_message("m5", MessageRole::User, "In Oslo"),
+                test_message("m6", MessageRole::Assistant, "20 degrees."),
+            ],
+            created_at: 0.0,
+            updated_at: 0.0,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: false,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    #[test]
+    fn openai_examples_carry_weights_and_exclusions() {
+        let mut selection = DatasetSelection::default();
+        selection.sessions.insert("s1".to_string());
+        selection.excluded_messages.insert("m3".to_string());
+        selection.zero_weight.insert("m2".to_string());
+
+        let example = openai_example(&session(), "Be brief.", &selection).unwrap();
+        let roles: Vec<&str> = example["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(roles, ["system", "user", "assistant", "user", "assistant"]);
+        assert_eq!(example["messages"][2]["weight"], 0);
+        assert_eq!(example["messages"][4]["weight"], 1);
+
+        selection.zero_weight.insert("m6".to_string());
+        let (jsonl, count) = build_dataset([&session()], "", &selection, DatasetFormat::OpenAiChat);
+        assert_eq!((jsonl.as_str(), count), ("", 0));
+    }
+
+    #[test]
+    fn gemini_examples_alternate_turns() {
+        let mut selection = DatasetSelection::default();
+        selection.sessions.insert("s1".to_string());
+        selection.excluded_messages.insert("m1".to_string());
+
+        let (jsonl, count) =
+            build_dataset([&session()], "Be brief.", &selection, DatasetFormat::Gemini);
+        assert_eq!(count, 1);
+        let example: Value = serde_json::from_str(jsonl.trim_end()).unwrap();
+        assert_eq!(
+            example["systemInstruction"]["parts"][0]["text"],
+            "Be brief."
+        );
+        let contents = example["contents"].as_array().unwrap();
+        // The leading model turn is dropped and the two user turns are merged
+        assert_eq!(contents.len(), 2);
+        assert_eq!(contents[0]["role"], "user");
+        assert_eq!(contents[0]["parts"][0]["text"], "Weather?\n\nIn Oslo");
+        assert_eq!(contents[1]["role"], "model");
+    }
+}
@@ -1,9 +1,10 @@
 // Flexible LLM client that can work with any provider configuration
 use super::api_clients::{
-    GeminiClient, LLMClient, LLMResponse, OpenAIClient, StreamCallback,
-    UnifiedMessageRole,
+    huggingface_client::HUGGINGFACE_TRANSFORMER, mock_provider::MOCK_TRANSFORMER, provider_registry,
+    LLMClient, LLMResponse, MessageConversionService, ProviderRequest, StreamCallback, UnifiedMessage,
 };
 use crate::llm_playground::{
+    logging,
     provider_config::{FlexibleApiConfig, ProviderConfig},
     Message,
 };
@@ -19,19 +20,18 @@ impl FlexibleLLMClient {
         Self
     }
 
-    /// Get the appropriate client for the current session provider
-    fn get_client_for_provider(&self, provider: &ProviderConfig) -> Box<dyn LLMClient + 'static> {
-        // Determine which client to use based on the transformer configuration
-        if provider.transformer.r#use.contains(&"gemini".to_string()) {
-            Box::new(GeminiClient::new())
-        } else {
-            // Default to OpenAI-compatible client for everything else
-            Box::new(OpenAIClient::new())
-        }
+    /// The client for a provider, from the registry of provider factories
+    fn get_client_for_provider(&self, provider: &ProviderConfig) -> Result<Box<dyn LLMClient>, String> {
+        // A provider from a plugin descriptor only needs its own factory
+        provider_registry(std::slice::from_ref(provider)).create_client(provider)
+    }
+
+    fn convert_messages(messages: &[Message]) -> Vec<UnifiedMessage> {
+        MessageConversionService::new().convert_legacy_to_unified(messages)
     }
 
-    /// Create a temporary config for the legacy API
-    fn create_legacy_config(
+    /// The provider's settings in the form the clients take
+    fn client_config(
         &self,
         provider: &ProviderConfig,
         config: &FlexibleApiConfig,
@@ -47,24 +47,21 @@ impl FlexibleLLMClient {
                     api_key: provider.api_key.clone(),
                     model: model.to_string(),
                     base_url: provider.api_base_url.clone(),
+                    auth: provider.gemini_auth.clone(),
                 },
                 openai: OpenAIConfig {
                     base_url: "".to_string(),
                     api_key: "".to_string(),
                     model: "".to_string(),
                 },
-                shared_settings: crate::llm_playground::types::SharedSettings {
-                    temperature: config.shared_settings.temperature,
-                    max_tokens: config.shared_settings.max_tokens,
-                    retry_delay: config.shared_settings.retry_delay,
-                },
+                shared_settings: config.shared_settings_for_model(&provider.name, model),
                 system_prompt: config.system_prompt.clone(),
                 function_tools: config
                     .get_enabled_function_tools()
                     .into_iter()
                     .cloned()
                     .collect(),
-                structured_outputs: config.structured_outputs.clone(),
+                structured_outputs: config.active_structured_output().cloned().into_iter().collect(),
                 mcp_config: McpConfig::default(),
             }
         } else {
@@ -75,24 +72,21 @@ impl FlexibleLLMClient {
                     api_key: "".to_string(),
                     model: "".to_string(),
                     base_url: "".to_string(),
+                    auth: Default::default(),
                 },
                 openai: OpenAIConfig {
                     base_url: provider.api_base_url.clone(),
                     api_key: provider.api_key.clone(),
                     model: model.to_string(),
                 },
-                shared_settings: crate::llm_playground::types::SharedSettings {
-                    temperature: config.shared_settings.temperature,
-                    max_tokens: config.shared_settings.max_tokens,
-                    retry_delay: config.shared_settings.retry_delay,
-                },
+                shared_settings: config.shared_settings_for_model(&provider.name, model),
                 system_prompt: config.system_prompt.clone(),
                 function_tools: config
                     .get_enabled_function_tools()
                     .into_iter()
                     .cloned()
                     .collect(),
-                structured_outputs: config.structured_outputs.clone(),
+                structured_outputs: config.active_structured_output().cloned().into_iter().collect(),
                 mcp_config: McpConfig::default(),
             }
         }
@@ -105,29 +99,24 @@ impl FlexibleLLMClient {
     ) -> Pin<Box<dyn Future<Output = Result<LLMResponse, String>>>> {
         let (provider_name, model_name) = config.get_current_provider_and_model();
         
-        // Debug logging
-        use gloo_console::log;
-        log!("🔍 FlexibleLLMClient::send_message - Provider: {}, Model: {}", &provider_name, &model_name);
-        log!("🔍 Session provider setting:", format!("{:?}", &config.current_session_provider));
-        log!("🔍 Router default: {}", &config.router.default);
+        logging::debug!("Provider: {}, model: {}", provider_name, model_name);
+        logging::debug!("Session provider setting: {:?}", config.current_session_provider);
+        logging::debug!("Router default: {}", config.router.default);
 
         if let Some(provider) = config.get_provider(&provider_name) {
-            log!("🔍 Found provider: {}", &provider.name);
-            log!("🔍 Provider transformer:", format!("{:?}", &provider.transformer.r#use));
-            log!("🔍 Provider API URL: {}", &provider.api_base_url);
+            logging::debug!("Provider transformer: {:?}", provider.transformer.r#use);
+            logging::debug!("Provider API URL: {}", provider.api_base_url);
             
-            let client = self.get_client_for_provider(provider);
-            let legacy_config = self.create_legacy_config(provider, config, &model_name);
+            let client = match self.get_client_for_provider(provider) {
+                Ok(client) => client,
+                Err(error) => return Box::pin(async move { Err(error) }),
+            };
+            let client_config = self.client_config(provider, config, &model_name);
             
             // Log which client type we're using
-            if provider.transformer.r#use.contains(&"gemini".to_string()) {
-                log!("🔍 Using GeminiClient for provider: {}", &provider_name);
-            } else {
-                log!("🔍 Using OpenAIClient for provider: {}", &provider_name);
-            }
+            logging::debug!("Using {} client for provider: {}", client.client_name(), provider_name);
             
-            // Convert legacy messages to unified format
-            let unified_messages = client.convert_legacy_messages(messages);
+            let unified_messages = Self::convert_messages(messages);
             
             // Clone system prompt to avoid lifetime issues
             let system_prompt = if config.system_prompt.is_empty() {
@@ -136,16 +125,16 @@ impl FlexibleLLMClient {
                 Some(config.system_prompt.clone())
             };
             
-            log!("📤 Sending to {} client with {} unified messages...", client.client_name(), unified_messages.len());
+            logging::debug!("Sending {} unified messages to the {} client", unified_messages.len(), client.client_name());
             
             // Clone data to move into the async block
             Box::pin(async move {
                 let system_prompt_ref = system_prompt.as_ref().map(|s| s.as_str());
-                client.send_message(&unified_messages, &legacy_config, system_prompt_ref).await
+                client.send_message(&unified_messages, &client_config, system_prompt_ref).await
             })
         } else {
             let provider_name_clone = provider_name.clone();
-            log!("❌ Provider '{}' not found in config", &provider_name);
+            logging::error!("Provider '{}' not found in config", provider_name);
             Box::pin(async move { Err(format!("Provider '{}' not found", provider_name_clone)) })
         }
     }
@@ -159,10 +148,12 @@ impl FlexibleLLMClient {
         let (provider_name, model_name) = config.get_current_provider_and_model();
 
         if let Some(provider) = config.get_provider(&provider_name) {
-            let client = self.get_client_for_provider(provider);
-            let legacy_config = self.create_legacy_config(provider, config, &model_name);
-            // Convert legacy messages to unified format
-            let unified_messages = client.convert_legacy_messages(messages);
+            let client = match self.get_client_for_provider(provider) {
+                Ok(client) => client,
+                Err(error) => return Box::pin(async move { Err(error) }),
+            };
+            let client_config = self.client_config(provider, config, &model_name);
+            let unified_messages = Self::convert_messages(messages);
             
             // Clone system prompt to avoid lifetime issues
             let system_prompt = if config.system_prompt.is_empty() {
@@ -173,13 +164,34 @@ impl FlexibleLLMClient {
             
             Box::pin(async move {
                 let system_prompt_ref = system_prompt.as_ref().map(|s| s.as_str());
-                client.send_message_stream(&unified_messages, &legacy_config, system_prompt_ref, callback).await
+                client.send_message_stream(&unified_messages, &client_config, system_prompt_ref, callback).await
             })
         } else {
             Box::pin(async move { Err(format!("Provider '{}' not found", provider_name)) })
         }
     }
 
+    /// The request `send_message` would make for these messages, API key included
+    pub fn build_request(
+        &self,
+        messages: &[Message],
+        config: &FlexibleApiConfig,
+    ) -> Result<ProviderRequest, String> {
+        let (provider_name, model_name) = config.get_current_provider_and_model();
+        let provider = config
+            .get_provider(&provider_name)
+            .ok_or_else(|| format!("Provider '{}' not found", provider_name))?;
+        let client = self.get_client_for_provider(provider)?;
+        let client_config = self.client_config(provider, config, &model_name);
+        let unified_messages = Self::convert_messages(messages);
+        let system_prompt = if config.system_prompt.is_empty() {
+            None
+        } else {
+            Some(config.system_prompt.as_str())
+        };
+        client.build_request(&unified_messages, &client_config, system_prompt)
+    }
+
     pub fn get_available_models(
         &self,
         config: &FlexibleApiConfig,
@@ -187,11 +199,14 @@ impl FlexibleLLMClient {
         let (provider_name, _) = config.get_current_provider_and_model();
 
         if let Some(provider) = config.get_provider(&provider_name) {
-            let client = self.get_client_for_provider(provider);
-            let legacy_config = self.create_legacy_config(provider, config, &provider.models[0]);
+            let client = match self.get_client_for_provider(provider) {
+                Ok(client) => client,
+                Err(error) => return Box::pin(async move { Err(error) }),
+            };
+            let client_config = self.client_config(provider, config, &provider.models[0]);
             
             Box::pin(async move {
-                client.get_available_models(&legacy_config).await
+                client.get_available_models(&client_config).await
             })
         } else {
             Box::pin(async move { Err(format!("Provider '{}' not found", provider_name)) })
@@ -209,10 +224,12 @@ impl FlexibleLLMClient {
         if provider.name.is_empty() {
             return Err("Provider name cannot be empty".to_string());
         }
-        if provider.api_base_url.is_empty() {
+        // The mock provider answers locally and needs neither URL nor key
+        let is_mock = provider.transformer.r#use.iter().any(|t| t == MOCK_TRANSFORMER);
+        if provider.api_base_url.is_empty() && !is_mock {
             return Err("API base URL cannot be empty".to_string());
         }
-        if provider.api_key.is_empty() && provider.name != "ollama" {
+        if provider.api_key.is_empty() && provider.needs_api_key() {
             return Err("API key cannot be empty".to_string());
         }
         if provider.models.is_empty() {
@@ -223,7 +240,12 @@ impl FlexibleLLMClient {
         }
 
         // Check if transformer type is supported
-        let supported_transformers = ["openai", "gemini"];
+        let supported_transformers = [
+            "openai",
+            "gemini",
+            MOCK_TRANSFORMER,
+            HUGGINGFACE_TRANSFORMER,
+        ];
         if !provider
             .transformer
             .r#use
@@ -249,27 +271,26 @@ impl FlexibleLLMClient {
             return Box::pin(async move { Err(e) });
         }
 
-        let client = self.get_client_for_provider(provider);
-        let legacy_config = self.create_legacy_config(provider, config, &provider.models[0]);
+        let client = match self.get_client_for_provider(provider) {
+            Ok(client) => client,
+            Err(error) => return Box::pin(async move { Err(error) }),
+        };
+        let client_config = self.client_config(provider, config, &provider.models[0]);
 
         // Send a simple test message
         let test_messages = vec![Message {
             id: "test".to_string(),
-            role: crate::llm_playground::MessageRole::User,
-            content: "Hello, this is a connection test.".to_string(),
             timestamp: js_sys::Date::now(),
-            function_call: None,
-            function_response: None,
+            ..Message::new(crate::llm_playground::MessageRole::User, "Hello, this is a connection test.")
         }];
 
         Box::pin(async move {
-            // Convert legacy messages to unified format
-            let unified_messages = client.convert_legacy_messages(&test_messages);
+            let unified_messages = Self::convert_messages(&test_messages);
             
             // No system prompt for test
             let system_prompt = None;
             
-            match client.send_message(&unified_messages, &legacy_config, system_prompt).await {
+            match client.send_message(&unified_messages, &client_config, system_prompt).await {
                 Ok(_) => Ok("Connection successful".to_string()),
                 Err(e) => Err(format!("Connection failed: {}", e)),
             }
@@ -282,3 +303,64 @@ impl Default for FlexibleLLMClient {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::{test_message, FunctionTool, MessageRole};
+    use serde_json::json;
+
+    fn config(provider: &str, model: &str) -> FlexibleApiConfig {
+        let mut config = FlexibleApiConfig {
+            function_tools: vec![FunctionTool {
+                name: "get_weather".to_string(),
+                description: "Current weather for a city".to_string(),
+                parameters: json!({"type": "object", "properties": {"city": {"type": "string"}}}),
+                mock_response: "{}".to_string(),
+                enabled: true,
+                category: "Custom".to_string(),
+                is_builtin: false,
+                requires: None,
+            }],
+            ..FlexibleApiConfig::default()
+        };
+        config.set_session_provider(provider, model);
+        config
+    }
+
+    #[test]
+    fn both_providers_send_messages_and_tools() {
+        let client = FlexibleLLMClient::new();
+        let messages = [test_message("u1", MessageRole::User, "Weather in Oslo?")];
+
+        let request = client
+            .build_request(&messages, &config("openrouter", "meta-llama/llama-3.1-8b-instruct:free"))
+            .unwrap();
+        assert_eq!(request.body["tools"][0]["function"]["name"], "get_weather");
+        assert!(request.body["messages"].to_string().contains("Weather in Oslo?"));
+
+        let request = client
+            .build_request(&messages, &config("gemini", "gemini-2.5-flash"))
+            .unwrap();
+        assert!(request.url.contains("gemini-2.5-flash"));
+        assert_eq!(request.body["tools"][0]["functionDeclarations"][0]["name"], "get_weather");
+        assert!(request.body["contents"].to_string().contains("Weather in Oslo?"));
+    }
+
+    #[test]
+    fn requests_carry_the_whole_session() {
+        let messages = [
+            test_message("u1", MessageRole::User, "My name is Ada."),
+            test_message("a1", MessageRole::Assistant, "Hello Ada!"),
+            test_message("u2", MessageRole::User, "What is my name?"),
+        ];
+        let config = config("openrouter", "meta-llama/llama-3.1-8b-instruct:free");
+        // A fresh client, as after a refresh, sends the same history
+        let first = FlexibleLLMClient::new().build_request(&messages, &config).unwrap();
+        let second = FlexibleLLMClient::new().build_request(&messages, &config).unwrap();
+        assert_eq!(first, second);
+        let sent = first.body["messages"].as_array().unwrap();
+        let contents: Vec<_> = sent.iter().filter_map(|m| m["content"].as_str()).collect();
+        assert!(contents.ends_with(&["My name is Ada.", "Hello Ada!", "What is my name?"]));
+    }
+}
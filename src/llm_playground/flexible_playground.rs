@@ -1,40 +1,141 @@
 // Updated LLM Playground with flexible provider system
-use gloo_console::log;
 use gloo_storage::{LocalStorage, Storage};
-use gloo_timers::future::TimeoutFuture;
-use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use wasm_bindgen::JsCast;
 use yew::prelude::*;
+use yew_router::prelude::*;
 
 use crate::llm_playground::{
+    app_state::{AppAction, AppState, AppStateHandle},
+    components::flexible_settings_panel::{clamp_settings_panel_width, DEFAULT_SETTINGS_PANEL_WIDTH},
     components::notification::{use_notifications, NotificationContainer, NotificationMessage, NotificationType},
-    flexible_client::FlexibleLLMClient,
+    components::sidebar::{clamp_sidebar_width, DEFAULT_SIDEBAR_WIDTH},
+    dialogue::DialogueConfig,
+    event_bus::{EventBus, PlaygroundEvent},
+    history_scrubber::Scrubber,
+    html_export, logging,
     mcp_client::McpClient,
-    ChatHeader, Chatroom, ChatSession, FlexibleApiConfig, FlexibleSettingsPanel,
-    ModelSelector, Sidebar, Message, MessageRole,
+    model_shortcuts,
+    personas::{self, Persona},
+    routes::Route,
+    session_bulk::{self, BulkAction},
+    session_database, session_merge, session_pages,
+    session_lock, session_recording,
+    session_snapshots::{self, SessionSnapshot, SnapshotReason},
+    session_store::{SessionAction, SessionStore},
+    shared_view, storage,
+    storage_recovery::{self, QuarantinedPayload, Salvaged},
+    use_confirm, use_event_subscription, AbExperimentView, BookmarksView, ChatHeader, Chatroom, ChatSession, ComparisonView, ConfirmAction, ConfirmRequest, DiagnosticsView, DialogueSetup, EvalView, FineTuneView, FlexibleApiConfig, FlexibleSettingsPanel, KnowledgeView,
+    ModelSelector, QuickModelSwitcher, ReplayView, ResizeHandle, SessionDatabaseView, SettingsPanelMode, Sidebar, Message, MessageRole, StorageRecoveryDialog, ToolApprovalDialog, TraceView,
 };
 
-const STORAGE_KEY_FLEXIBLE_CONFIG: &str = "llm_playground_flexible_config";
-const STORAGE_KEY_SESSIONS: &str = "llm_playground_sessions";
+const STORAGE_KEY_FLEXIBLE_CONFIG: &str = storage::FLEXIBLE_CONFIG_KEY;
 const STORAGE_KEY_CURRENT_SESSION: &str = "llm_playground_current_session";
 const STORAGE_KEY_DARK_MODE: &str = "llm_playground_dark_mode";
+const STORAGE_KEY_SIDEBAR_WIDTH: &str = "llm_playground_sidebar_width";
+const STORAGE_KEY_SETTINGS_WIDTH: &str = "llm_playground_settings_width";
+const STORAGE_KEY_SETTINGS_MODE: &str = "llm_playground_settings_mode";
 
+/// What the main content area shows
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MainView {
+    Chat,
+    Comparison,
+    Evals,
+    AbExperiment,
+    Knowledge,
+    FineTune,
+    Replay,
+    Trace,
+    Database,
+    Diagnostics,
+    Bookmarks,
+}
+
+impl MainView {
+    fn from_route(route: &Route) -> Self {
+        match route {
+            Route::Compare => MainView::Comparison,
+            Route::Evals => MainView::Evals,
+            Route::AbExperiment => MainView::AbExperiment,
+            Route::Knowledge => MainView::Knowledge,
+            Route::FineTune => MainView::FineTune,
+            Route::Replay { .. } => MainView::Replay,
+            Route::Trace { .. } => MainView::Trace,
+            Route::Database { .. } => MainView::Database,
+            Route::Diagnostics => MainView::Diagnostics,
+            Route::Bookmarks => MainView::Bookmarks,
+            _ => MainView::Chat,
+        }
+    }
+}
+
+/// Route for what is on screen; an open settings panel keeps the section in
+/// `current` so following a section link doesn't get rewritten
+fn route_for(view: MainView, session_id: Option<&String>, show_settings: bool, current: Option<&Route>) -> Route {
+    if show_settings {
+        return current.filter(|route| route.is_settings()).cloned().unwrap_or(Route::Settings);
+    }
+    let session = |route: fn(String) -> Route| session_id.cloned().map_or(Route::Home, route);
+    match view {
+        MainView::Chat => session(|id| Route::Session { id }),
+        MainView::Replay => session(|id| Route::Replay { id }),
+        MainView::Trace => session(|id| Route::Trace { id }),
+        MainView::Database => session(|id| Route::Database { id }),
+        MainView::Comparison => Route::Compare,
+        MainView::Evals => Route::Evals,
+        MainView::AbExperiment => Route::AbExperiment,
+        MainView::Knowledge => Route::Knowledge,
+        MainView::FineTune => Route::FineTune,
+        MainView::Diagnostics => Route::Diagnostics,
+        MainView::Bookmarks => Route::Bookmarks,
+    }
+}
 
 #[function_component(FlexibleLLMPlayground)]
 pub fn flexible_llm_playground() -> Html {
     // State management
-    let sessions = use_state(|| HashMap::<String, ChatSession>::new());
+    // Sessions, changed through actions so an update copies only its session
+    let sessions = use_reducer(SessionStore::default);
     let current_session_id = use_state(|| Option::<String>::None);
-    let api_config = use_state(|| FlexibleApiConfig::default());
+    // Config, clients and dark mode, shared with the views through context
+    let app_state = use_reducer(AppState::default);
     let show_settings = use_state(|| false);
     let show_model_selector = use_state(|| false);
-    let dark_mode = use_state(|| false);
-    let llm_client = use_state(|| FlexibleLLMClient::new());
-    let mcp_client = use_state(|| Option::<McpClient>::None);
+    let show_quick_switch = use_state(|| false);
+    let main_view = use_state(|| MainView::Chat);
+    // Message to bring into view after opening a bookmark
+    let focus_message = use_state(|| Option::<String>::None);
+    let show_dialogue_setup = use_state(|| false);
+    let sidebar_width = use_state(|| {
+        LocalStorage::get::<u32>(STORAGE_KEY_SIDEBAR_WIDTH)
+            .map(clamp_sidebar_width)
+            .unwrap_or(DEFAULT_SIDEBAR_WIDTH)
+    });
+    let settings_width = use_state(|| {
+        LocalStorage::get::<u32>(STORAGE_KEY_SETTINGS_WIDTH)
+            .map(clamp_settings_panel_width)
+            .unwrap_or(DEFAULT_SETTINGS_PANEL_WIDTH)
+    });
+    // Bumped when a pane resize ends, so widths are saved once per drag
+    let pane_resizes = use_state(|| 0u32);
+    let settings_mode = use_state(|| {
+        LocalStorage::get::<SettingsPanelMode>(STORAGE_KEY_SETTINGS_MODE).unwrap_or_default()
+    });
+    // Set once sessions are loaded, so a session route can be resolved
+    let storage_loaded = use_state(|| false);
+    // Stored data that couldn't be read, offered for recovery after loading
+    let quarantined = use_state(Vec::<QuarantinedPayload>::new);
+    // Whether the route has been applied yet; until then the state isn't
+    // written back to the location, which would replace the route
+    let route_applied = use_mut_ref(|| false);
+    let route = use_route::<Route>();
+    let navigator = use_navigator();
 
     // Notification system
     let (notifications, add_notification, dismiss_notification) = use_notifications();
+    // Components below publish notifications, session and config updates here
+    let event_bus = use_memo((), |_| EventBus::new());
 
 
 
@@ -44,54 +145,55 @@ pub fn flexible_llm_playground() -> Html {
     let mcp_config_hash = use_state(|| 0u64);
 
     {
-        let api_config = api_config.clone();
-        let mcp_client = mcp_client.clone();
+        let app_state = app_state.clone();
         let mcp_config_hash = mcp_config_hash.clone();
 
-        use_effect_with(api_config.clone(), move |config| {
+        use_effect_with(app_state.config.clone(), move |config| {
             let mcp_config = config.mcp_config.clone();
+            let cors_proxy = config.shared_settings.cors_proxy.clone();
 
             // Calculate a simple hash of the MCP config to detect actual changes
             let config_str = serde_json::to_string(&mcp_config).unwrap_or_default();
             let mut hasher = std::collections::hash_map::DefaultHasher::new();
             config_str.hash(&mut hasher);
+            cors_proxy.hash(&mut hasher);
             let new_hash = hasher.finish();
 
             // Only initialize if the MCP config actually changed
             if new_hash != *mcp_config_hash {
                 mcp_config_hash.set(new_hash);
 
-                let mcp_client = mcp_client.clone();
                 let has_enabled_servers = mcp_config.servers.values().any(|server| server.enabled);
 
                 if has_enabled_servers {
                     // Check if we already have a client with the same config to avoid re-initialization
-                    let needs_initialization = if let Some(current_client) = mcp_client.as_ref() {
+                    let needs_initialization = if let Some(current_client) = app_state.mcp_client.as_ref() {
                         // Compare current client config with new config
                         let current_config_str =
                             serde_json::to_string(current_client.get_config()).unwrap_or_default();
                         current_config_str != config_str
+                            || current_client.cors_proxy() != cors_proxy.as_deref()
                     } else {
                         true
                     };
 
                     if needs_initialization {
                         wasm_bindgen_futures::spawn_local(async move {
-                            let mut client = McpClient::new(mcp_config);
+                            let mut client = McpClient::new(mcp_config).with_cors_proxy(cors_proxy);
                             match client.initialize().await {
                                 Ok(_) => {
-                                    log!("MCP client initialized successfully in background");
-                                    mcp_client.set(Some(client));
+                                    logging::info!("MCP client initialized in background");
+                                    app_state.dispatch(AppAction::SetMcpClient(Some(client)));
                                 }
                                 Err(e) => {
-                                    log!("Failed to initialize MCP client:", &e);
-                                    mcp_client.set(None);
+                                    logging::error!("Failed to initialize MCP client: {}", e);
+                                    app_state.dispatch(AppAction::SetMcpClient(None));
                                 }
                             }
                         });
                     }
                 } else {
-                    mcp_client.set(None);
+                    app_state.dispatch(AppAction::SetMcpClient(None));
                 }
             }
 
@@ -99,80 +201,33 @@ pub fn flexible_llm_playground() -> Html {
         });
     }
 
-    // Auto-update function tools when MCP client changes
-    // Use a flag to prevent infinite loops when updating tools
-    let updating_mcp_tools = use_state(|| false);
-
-    {
-        let api_config = api_config.clone();
-        let mcp_client = mcp_client.clone();
-        let updating_mcp_tools = updating_mcp_tools.clone();
-
-        use_effect_with(mcp_client.clone(), move |client| {
-            if !*updating_mcp_tools {
-                if let Some(mcp_client) = client.as_ref() {
-                    updating_mcp_tools.set(true);
-                    let mcp_tools = mcp_client.get_function_tools();
-                    let mut new_config = (*api_config).clone();
-                    new_config.add_mcp_tools(mcp_tools);
-                    api_config.set(new_config);
-                                
-                    let (provider_name, model_name) = api_config.get_current_provider_and_model();
-                    log!("🔍 playground::use_effect_with mcp_client - Provider: {}, Model: {}", &provider_name, &model_name);
-                    
-                    log!("Added MCP tools to function tools list");
-
-                    // Reset the flag after a brief delay to allow config update to complete
-                    wasm_bindgen_futures::spawn_local({
-                        let updating_mcp_tools = updating_mcp_tools.clone();
-                        async move {
-                            TimeoutFuture::new(100).await;
-                            updating_mcp_tools.set(false);
-                        }
-                    });
-                }
-            }
-            || ()
-        });
-    }
-
     // Load data from localStorage on mount
     {
         let sessions = sessions.clone();
         let current_session_id = current_session_id.clone();
-        let api_config = api_config.clone();
-        let dark_mode = dark_mode.clone();
+        let app_state = app_state.clone();
+        let storage_loaded = storage_loaded.clone();
+        let quarantined = quarantined.clone();
 
         use_effect_with((), move |_| {
+            // Upgrade what an older version stored before reading any of it
+            storage::migrate_storage();
+
             // Load API config only if not already set (to avoid overriding session-specific settings)
-            if let Ok(config_str) = LocalStorage::get::<String>(STORAGE_KEY_FLEXIBLE_CONFIG) {
-                if let Ok(loaded_config) = serde_json::from_str::<FlexibleApiConfig>(&config_str) {
-                    // Only load if current config is still default (hasn't been modified)
-                    let current_config = (*api_config).clone();
-                    if current_config.current_session_provider.is_none() {
-                        api_config.set(loaded_config);
-                        
-                        let (provider_name, model_name) = api_config.get_current_provider_and_model();
-                        
-                        // Debug logging
-                        use gloo_console::log;
-                        log!("🔍 playground::use_effect_with - Provider: {}, Model: {}", &provider_name, &model_name);
-                        
-                    }
-                }
+            if let Some(mut loaded_config) = storage::load_payload::<FlexibleApiConfig>(STORAGE_KEY_FLEXIBLE_CONFIG) {
+                loaded_config.clamp_model_defaults();
+                let (provider_name, model_name) = loaded_config.get_current_provider_and_model();
+                logging::debug!("Loaded config with provider: {}, model: {}", provider_name, model_name);
+                app_state.dispatch(AppAction::LoadConfig(loaded_config));
             }
 
-            // Load sessions
-            if let Ok(sessions_str) = LocalStorage::get::<String>(STORAGE_KEY_SESSIONS) {
-                if let Ok(loaded_sessions) =
-                    serde_json::from_str::<HashMap<String, ChatSession>>(&sessions_str)
+            // Load sessions, with only the newest messages of long ones
+            if let Some(loaded_sessions) = session_pages::load_all() {
+                for (id, session) in loaded_sessions.iter()
                 {
-                    for session in loaded_sessions.iter()
-                    {
-                        log!(format!("🗨️ Session {} now has {} messages", session.0, session.1.messages.len()));
-                    }
-                    sessions.set(loaded_sessions);
+                    logging::debug!("Session {} now has {} messages", id, session_pages::message_count(session));
                 }
+                sessions.dispatch(SessionAction::Load(loaded_sessions));
             }
 
             // Load current session
@@ -182,7 +237,7 @@ pub fn flexible_llm_playground() -> Html {
 
             // Load dark mode
             if let Ok(dark) = LocalStorage::get::<bool>(STORAGE_KEY_DARK_MODE) {
-                dark_mode.set(dark);
+                app_state.dispatch(AppAction::SetDarkMode(dark));
 
                 // Apply dark mode class immediately on load
                 if let Some(window) = web_sys::window() {
@@ -199,35 +254,114 @@ pub fn flexible_llm_playground() -> Html {
                 }
             }
 
+            quarantined.set(storage_recovery::quarantined());
+            storage_loaded.set(true);
+            || ()
+        });
+    }
+
+    // State to location; declared before the effect below so the render that
+    // applies the route doesn't write back the state from before it
+    {
+        let route = route.clone();
+        let route_applied = route_applied.clone();
+        let navigator = navigator.clone();
+        let state_route = route_for(*main_view, current_session_id.as_ref(), *show_settings, route.as_ref());
+        use_effect_with(state_route, move |state_route| {
+            if *route_applied.borrow() && route.as_ref() != Some(state_route) {
+                if let Some(navigator) = navigator {
+                    navigator.push(state_route);
+                }
+            }
+            || ()
+        });
+    }
+
+    // Location to state, on load and when going back or forward
+    {
+        let sessions = sessions.clone();
+        let current_session_id = current_session_id.clone();
+        let main_view = main_view.clone();
+        let show_settings = show_settings.clone();
+        let add_notification = add_notification.clone();
+        use_effect_with((route.clone(), *storage_loaded), move |(route, loaded)| {
+            if *loaded {
+                if let Some(route) = route {
+                    match route.session_id() {
+                        Some(id) if sessions.contains_key(id) => current_session_id.set(Some(id.to_string())),
+                        Some(_) => add_notification.emit(NotificationMessage::new(
+                            "The linked session isn't in this browser".to_string(),
+                            NotificationType::Warning,
+                        )),
+                        None => {}
+                    }
+                    main_view.set(MainView::from_route(route));
+                    show_settings.set(route.is_settings());
+                }
+                *route_applied.borrow_mut() = true;
+            }
             || ()
         });
     }
 
     // Save to localStorage when state changes
     {
-        let api_config = api_config.clone();
-        use_effect_with(api_config.clone(), move |config| {
-            if let Ok(config_str) = serde_json::to_string(&**config) {
+        use_effect_with(app_state.config.clone(), move |config| {
+            if let Ok(config_str) = storage::payload_json(config) {
                 let _ = LocalStorage::set(STORAGE_KEY_FLEXIBLE_CONFIG, config_str);
             }
             || ()
         });
     }
 
+    // Nothing is written before the stored sessions are read, which would
+    // drop their pages
     {
         let sessions = sessions.clone();
-        use_effect_with(sessions.clone(), move |sessions| {
-            if let Ok(sessions_str) = serde_json::to_string(&**sessions) {
-                let _ = LocalStorage::set(STORAGE_KEY_SESSIONS, sessions_str);
+        use_effect_with((sessions.clone(), *storage_loaded), move |(sessions, loaded)| {
+            if *loaded {
+                session_pages::save_all(sessions.values());
             }
             || ()
         });
     }
 
+    // An opened session gets its recent messages loaded
     {
+        let sessions = sessions.clone();
+        use_effect_with((*current_session_id).clone(), move |session_id| {
+            if let Some(mut session) = session_id.as_ref().and_then(|id| sessions.get(id)).cloned() {
+                if session_pages::load_recent(&mut session) {
+                    sessions.dispatch(SessionAction::Put(session));
+                }
+            }
+            || ()
+        });
+    }
+
+    // Older messages, a page at a time as the conversation is scrolled up
+    let load_older_messages = {
+        let sessions = sessions.clone();
         let current_session_id = current_session_id.clone();
+        Callback::from(move |_: ()| {
+            let Some(mut session) = current_session_id.as_ref().and_then(|id| sessions.get(id)).cloned() else {
+                return;
+            };
+            if session_pages::load_older(&mut session) {
+                sessions.dispatch(SessionAction::Put(session));
+            }
+        })
+    };
+
+    {
+        let current_session_id = current_session_id.clone();
+        let sessions = sessions.clone();
         use_effect_with(current_session_id.clone(), move |session_id| {
-            if let Some(id) = session_id.as_ref() {
+            let incognito = session_id
+                .as_ref()
+                .and_then(|id| sessions.get(id))
+                .is_some_and(|session| session.incognito);
+            if let Some(id) = session_id.as_ref().filter(|_| !incognito) {
                 let _ = LocalStorage::set(STORAGE_KEY_CURRENT_SESSION, id.clone());
             }
             || ()
@@ -235,16 +369,15 @@ pub fn flexible_llm_playground() -> Html {
     }
 
     {
-        let dark_mode = dark_mode.clone();
-        use_effect_with(dark_mode.clone(), move |dark| {
-            let _ = LocalStorage::set(STORAGE_KEY_DARK_MODE, **dark);
+        use_effect_with(app_state.dark_mode, move |dark| {
+            let _ = LocalStorage::set(STORAGE_KEY_DARK_MODE, *dark);
 
             // Apply dark mode class to document
             if let Some(window) = web_sys::window() {
                 if let Some(document) = window.document() {
                     if let Some(html_element) = document.document_element() {
                         let class_list = html_element.class_list();
-                        if **dark {
+                        if *dark {
                             let _ = class_list.add_1("dark");
                         } else {
                             let _ = class_list.remove_1("dark");
@@ -256,6 +389,21 @@ pub fn flexible_llm_playground() -> Html {
         });
     }
 
+    // Persist pane layout; widths only when a resize ends, not on every move
+    {
+        let widths = (*sidebar_width, *settings_width);
+        use_effect_with(*pane_resizes, move |_| {
+            let (sidebar_width, settings_width) = widths;
+            let _ = LocalStorage::set(STORAGE_KEY_SIDEBAR_WIDTH, sidebar_width);
+            let _ = LocalStorage::set(STORAGE_KEY_SETTINGS_WIDTH, settings_width);
+            || ()
+        });
+        use_effect_with(*settings_mode, move |settings_mode| {
+            let _ = LocalStorage::set(STORAGE_KEY_SETTINGS_MODE, *settings_mode);
+            || ()
+        });
+    }
+
     // Session update callback for Chatroom component
     let on_session_update = {
         let sessions = sessions.clone();
@@ -263,11 +411,34 @@ pub fn flexible_llm_playground() -> Html {
         Callback::from(move |updated_session: ChatSession| {
             if let Some(session_id) = current_session_id.as_ref() {
                 if session_id == &updated_session.id {
-                    let mut new_sessions = (*sessions).clone();
-                    new_sessions.insert(updated_session.id.clone(), updated_session);
-                    sessions.set(new_sessions);
+                    if let Some(current) = sessions.get(session_id) {
+                        if !session_lock::allows_update(current, &updated_session) {
+                            logging::warn!("Ignored a change to locked session {}", session_id);
+                            return;
+                        }
+                        if current.messages.len() != updated_session.messages.len() {
+                            session_snapshots::autosave(current, &updated_session);
+                        }
+                    }
+                    sessions.dispatch(SessionAction::Put(updated_session));
+                }
+            }
+        })
+    };
+
+    // Single-message changes, e.g. a sub-agent's progress; added messages are
+    // snapshotted as with whole-session updates
+    let on_session_action = {
+        let sessions = sessions.clone();
+        Callback::from(move |action: SessionAction| {
+            if let SessionAction::AppendMessage { session_id, message } = &action {
+                if let Some(current) = sessions.get(session_id) {
+                    let mut updated = current.clone();
+                    updated.messages.push(message.clone());
+                    session_snapshots::autosave(current, &updated);
                 }
             }
+            sessions.dispatch(action);
         })
     };
 
@@ -279,16 +450,20 @@ pub fn flexible_llm_playground() -> Html {
         })
     };
 
-    let on_model_selected = {
+    // Starts a session with the given model, optionally as a persona
+    let start_session = {
         let sessions = sessions.clone();
         let current_session_id = current_session_id.clone();
-        let api_config = api_config.clone();
+        let app_state = app_state.clone();
         let show_model_selector = show_model_selector.clone();
-        Callback::from(move |(provider_name, model_name): (String, String)| {
-            log!("🆕 Creating new session with provider: {}, model: {}", &provider_name, &model_name);
+        Callback::from(move |(provider_name, model_name, persona): (String, String, Option<Persona>)| {
+            logging::info!("Creating new session with provider: {}, model: {}", provider_name, model_name);
             
             let session_id = format!("session_{}", js_sys::Date::now() as u64);
-            let session_title = format!("{} - {}", &provider_name, &model_name);
+            let session_title = match &persona {
+                Some(persona) => persona.label(),
+                None => format!("{} - {}", &provider_name, &model_name),
+            };
 
             let new_session = ChatSession {
                 id: session_id.clone(),
@@ -297,39 +472,89 @@ pub fn flexible_llm_playground() -> Html {
                 created_at: js_sys::Date::now(),
                 updated_at: js_sys::Date::now(),
                 pinned: false,
+                pinned_messages: Vec::new(),
+                locked: false,
+                tags: Vec::new(),
+                archived: false,
+                todos: Vec::new(),
+                budget_since: 0.0,
+                dialogue: None,
+                persona: persona.map(|persona| persona.name),
+                incognito: false,
+                older_pages: 0,
             };
 
+            model_shortcuts::record_use(&provider_name, &model_name);
+
             // Update API config with selected provider/model for this session
-            let mut new_config = (*api_config).clone();
-            log!("🔧 Setting session provider to: {},{}", &provider_name, &model_name);
+            let mut new_config = app_state.config.clone();
             new_config.set_session_provider(&provider_name, &model_name);
-            log!("🔧 New config session provider:", format!("{:?}", &new_config.current_session_provider));
-            
-            // Set the config state and ensure it's applied immediately
-            api_config.set(new_config.clone());
-    
-            let (provider_name, model_name) = api_config.get_current_provider_and_model();
-            log!("🔍 playground::on_model_selected - Provider: {}, Model: {}", &provider_name, &model_name);
+            new_config.apply_model_defaults(&provider_name, &model_name);
             
-            
-            // Force a re-render to ensure the config update propagates
-            // This ensures the hook gets the updated config
-            log!("🔧 Config updated with session provider");
+            let (provider_name, model_name) = new_config.get_current_provider_and_model();
+            app_state.dispatch(AppAction::SetConfig(new_config));
+            logging::debug!("Session provider set to: {}, model: {}", provider_name, model_name);
 
             // Add session and set as current
-            let mut new_sessions = (*sessions).clone();
-            new_sessions.insert(session_id.clone(), new_session);
-            for session in new_sessions.iter()
-            {
-                log!(format!("🗨️ Session {} now has {} messages", session.0, session.1.messages.len()));
-            }
-            sessions.set(new_sessions);
+            sessions.dispatch(SessionAction::Put(new_session));
             current_session_id.set(Some(session_id.clone()));
-            log!("🔧 Set current session to: {}", &session_id);
+            logging::debug!("Set current session to: {}", session_id);
             show_model_selector.set(false);
         })
     };
 
+    // A throwaway session on the current model that is never saved
+    let start_incognito_session = {
+        let sessions = sessions.clone();
+        let current_session_id = current_session_id.clone();
+        let main_view = main_view.clone();
+        Callback::from(move |_: ()| {
+            let now = js_sys::Date::now();
+            let session_id = format!("incognito_{}", now as u64);
+            let new_session = ChatSession {
+                id: session_id.clone(),
+                title: "Incognito".to_string(),
+                messages: Vec::new(),
+                created_at: now,
+                updated_at: now,
+                pinned: false,
+                pinned_messages: Vec::new(),
+                locked: false,
+                tags: Vec::new(),
+                archived: false,
+                todos: Vec::new(),
+                budget_since: 0.0,
+                dialogue: None,
+                persona: None,
+                incognito: true,
+                older_pages: 0,
+            };
+            sessions.dispatch(SessionAction::Put(new_session));
+            current_session_id.set(Some(session_id));
+            main_view.set(MainView::Chat);
+        })
+    };
+
+    let on_model_selected = start_session.reform(|(provider_name, model_name): (String, String)| {
+        (provider_name, model_name, None)
+    });
+
+    let on_persona_selected = {
+        let app_state = app_state.clone();
+        let start_session = start_session.clone();
+        Callback::from(move |name: String| {
+            let config = &app_state.config;
+            let Some(persona) = config.personas.iter().find(|p| p.name == name) else {
+                return;
+            };
+            // Personas without a model of their own start on the current one
+            let (provider_name, model_name) = persona
+                .model()
+                .unwrap_or_else(|| config.get_current_provider_and_model());
+            start_session.emit((provider_name, model_name, Some(persona.clone())));
+        })
+    };
+
     let on_model_selector_cancel = {
         let show_model_selector = show_model_selector.clone();
         Callback::from(move |_: ()| {
@@ -337,24 +562,88 @@ pub fn flexible_llm_playground() -> Html {
         })
     };
 
+    // Ctrl+M opens the quick model switcher from anywhere
+    {
+        let show_quick_switch = show_quick_switch.clone();
+        use_effect_with((), move |_| {
+            let listener = web_sys::window().map(|window| {
+                gloo::events::EventListener::new(&window, "keydown", move |event| {
+                    let Some(event) = event.dyn_ref::<web_sys::KeyboardEvent>() else {
+                        return;
+                    };
+                    if (event.ctrl_key() || event.meta_key()) && event.key().eq_ignore_ascii_case("m") {
+                        event.prevent_default();
+                        show_quick_switch.set(true);
+                    }
+                })
+            });
+            move || drop(listener)
+        });
+    }
+
+    let on_quick_switch = {
+        let app_state = app_state.clone();
+        let show_quick_switch = show_quick_switch.clone();
+        let add_notification = add_notification.clone();
+        Callback::from(move |(provider_name, model_name): (String, String)| {
+            model_shortcuts::record_use(&provider_name, &model_name);
+            let mut new_config = app_state.config.clone();
+            new_config.set_session_provider(&provider_name, &model_name);
+            new_config.apply_model_defaults(&provider_name, &model_name);
+            app_state.dispatch(AppAction::SetConfig(new_config));
+            show_quick_switch.set(false);
+            add_notification.emit(
+                NotificationMessage::new(
+                    format!("Switched to {} - {}", provider_name, model_name),
+                    NotificationType::Info,
+                )
+                .with_duration(2000),
+            );
+        })
+    };
+
+    let close_quick_switch = {
+        let show_quick_switch = show_quick_switch.clone();
+        Callback::from(move |_: ()| show_quick_switch.set(false))
+    };
+
     let switch_session = {
         let current_session_id = current_session_id.clone();
+        let main_view = main_view.clone();
+        let focus_message = focus_message.clone();
         Callback::from(move |session_id: String| {
             current_session_id.set(Some(session_id));
+            main_view.set(MainView::Chat);
+            focus_message.set(None);
         })
     };
 
-    let delete_session = {
+    let open_bookmark = {
         let sessions = sessions.clone();
         let current_session_id = current_session_id.clone();
-        Callback::from(move |session_id: String| {
-            let mut new_sessions = (*sessions).clone();
-            new_sessions.remove(&session_id);
-            for session in new_sessions.iter()
-            {
-                log!(format!("🗨️ Session {} now has {} messages", session.0, session.1.messages.len()));
+        let main_view = main_view.clone();
+        let focus_message = focus_message.clone();
+        Callback::from(move |(session_id, message_id): (String, String)| {
+            // The message may be on a page that isn't loaded yet
+            if let Some(session) = sessions.get(&session_id).filter(|session| session.older_pages > 0) {
+                sessions.dispatch(SessionAction::Put(session_pages::hydrated(session)));
             }
-            sessions.set(new_sessions);
+            current_session_id.set(Some(session_id));
+            main_view.set(MainView::Chat);
+            focus_message.set(Some(message_id));
+        })
+    };
+
+    let confirm = use_confirm();
+
+    let remove_session = {
+        let sessions = sessions.clone();
+        let current_session_id = current_session_id.clone();
+        Callback::from(move |session_id: String| {
+            sessions.dispatch(SessionAction::Remove(vec![session_id.clone()]));
+            session_recording::delete_recording(&session_id);
+            session_snapshots::delete_snapshots(&session_id);
+            session_database::delete(&session_id);
 
             // If we're deleting the current session, clear current session
             if current_session_id.as_ref() == Some(&session_id) {
@@ -363,35 +652,284 @@ pub fn flexible_llm_playground() -> Html {
         })
     };
 
+    let delete_session = {
+        let sessions = sessions.clone();
+        let confirm = confirm.clone();
+        let add_notification = add_notification.clone();
+        Callback::from(move |session_id: String| {
+            if sessions.get(&session_id).is_some_and(|s| s.locked) {
+                add_notification.emit(NotificationMessage::new(
+                    "This session is locked. Unlock it to delete it.".to_string(),
+                    NotificationType::Warning,
+                ));
+                return;
+            }
+            let title = sessions
+                .get(&session_id)
+                .map(|s| s.title.clone())
+                .unwrap_or_default();
+            confirm.confirm(
+                ConfirmRequest::new(
+                    ConfirmAction::DeleteSession,
+                    "Delete session?",
+                    format!("\"{}\" and all of its messages will be permanently deleted.", title),
+                ),
+                remove_session.reform(move |_| session_id.clone()),
+            );
+        })
+    };
+
+    // Bring back what still reads from a quarantined payload
+    let import_quarantined = {
+        let sessions = sessions.clone();
+        let app_state = app_state.clone();
+        let quarantined = quarantined.clone();
+        let add_notification = add_notification.clone();
+        Callback::from(move |payload: QuarantinedPayload| {
+            let Some(salvage) = storage_recovery::salvage(&payload) else {
+                add_notification.emit(NotificationMessage::new(
+                    format!("Nothing in the stored {} could be read", payload.label().to_lowercase()),
+                    NotificationType::Warning,
+                ));
+                return;
+            };
+            let summary = salvage.summary();
+            match salvage.salvaged {
+                Salvaged::Config(config) => app_state.dispatch(AppAction::LoadConfig(*config)),
+                Salvaged::Sessions(recovered) => {
+                    for session in recovered.into_values() {
+                        sessions.dispatch(SessionAction::Put(session));
+                    }
+                }
+            }
+            storage_recovery::discard(&payload.quarantine_key);
+            quarantined.set(storage_recovery::quarantined());
+            add_notification.emit(NotificationMessage::new(summary, NotificationType::Success));
+        })
+    };
+
+    let discard_quarantined = {
+        let confirm = confirm.clone();
+        let quarantined = quarantined.clone();
+        Callback::from(move |quarantine_key: String| {
+            let quarantined = quarantined.clone();
+            confirm.confirm(
+                ConfirmRequest::new(
+                    ConfirmAction::DiscardStoredData,
+                    "Discard stored data?",
+                    "The unreadable data will be deleted. Download it first to keep a copy.",
+                )
+                .with_confirm_label("Discard"),
+                Callback::from(move |_| {
+                    storage_recovery::discard(&quarantine_key);
+                    quarantined.set(storage_recovery::quarantined());
+                }),
+            );
+        })
+    };
+
+    let close_recovery = {
+        let quarantined = quarantined.clone();
+        Callback::from(move |_| quarantined.set(Vec::new()))
+    };
+
+    // Replace secrets in every stored session after showing what was found
+    let scrub_secrets = {
+        let sessions = sessions.clone();
+        let confirm = confirm.clone();
+        let add_notification = add_notification.clone();
+        let config = app_state.config.clone();
+        Callback::from(move |include_rules: bool| {
+            let scrubber = Scrubber::new(&config, include_rules);
+            let (_, found) = scrubber.scrub_all(&session_pages::hydrated_all(sessions.values()), true);
+            if found.matches == 0 {
+                add_notification.emit(NotificationMessage::new(
+                    "No secrets found in the stored sessions".to_string(),
+                    NotificationType::Info,
+                ));
+                return;
+            }
+            let sessions = sessions.clone();
+            let add_notification = add_notification.clone();
+            confirm.confirm(
+                ConfirmRequest::new(
+                    ConfirmAction::ScrubSecrets,
+                    "Scrub secrets?",
+                    format!(
+                        "Found {}. They will be replaced with [REDACTED]; this can't be undone.",
+                        found.summary()
+                    ),
+                )
+                .with_confirm_label("Scrub"),
+                Callback::from(move |_| {
+                    let (changed, report) = scrubber.scrub_all(&session_pages::hydrated_all(sessions.values()), false);
+                    for session in changed {
+                        sessions.dispatch(SessionAction::Put(session));
+                    }
+                    add_notification.emit(NotificationMessage::new(
+                        format!("Replaced {}", report.summary()),
+                        NotificationType::Success,
+                    ));
+                }),
+            );
+        })
+    };
+
+    let bulk_action = {
+        let sessions = sessions.clone();
+        let current_session_id = current_session_id.clone();
+        let confirm = confirm.clone();
+        let add_notification = add_notification.clone();
+        Callback::from(move |(action, ids): (BulkAction, Vec<String>)| match action {
+            BulkAction::Delete => {
+                let (ids, locked) = session_bulk::deletable(&sessions.to_map(), &ids);
+                if ids.is_empty() {
+                    add_notification.emit(NotificationMessage::new(
+                        "The selected sessions are locked. Unlock them to delete them.".to_string(),
+                        NotificationType::Warning,
+                    ));
+                    return;
+                }
+                let skipped = if locked > 0 {
+                    format!(" {} locked session{} will be kept.", locked, if locked == 1 { "" } else { "s" })
+                } else {
+                    String::new()
+                };
+                let sessions = sessions.clone();
+                let current_session_id = current_session_id.clone();
+                confirm.confirm(
+                    ConfirmRequest::new(
+                        ConfirmAction::DeleteSessions,
+                        "Delete sessions?",
+                        format!(
+                            "{} session{} and all of their messages will be permanently deleted.{}",
+                            ids.len(),
+                            if ids.len() == 1 { "" } else { "s" },
+                            skipped
+                        ),
+                    ),
+                    Callback::from(move |_| {
+                        for id in &ids {
+                            session_recording::delete_recording(id);
+                            session_snapshots::delete_snapshots(id);
+                            session_database::delete(id);
+                        }
+                        sessions.dispatch(SessionAction::Remove(ids.clone()));
+                        if current_session_id.as_ref().is_some_and(|id| ids.contains(id)) {
+                            current_session_id.set(None);
+                        }
+                    }),
+                );
+            }
+            BulkAction::Export => {
+                let result = session_bulk::export_json(&sessions.to_map(), &ids).and_then(|json| {
+                    let file_name = session_bulk::export_file_name(ids.len());
+                    html_export::download_file(&file_name, &json, "application/json").map(|_| file_name)
+                });
+                add_notification.emit(match result {
+                    Ok(file_name) => NotificationMessage::new(format!("Exported {}", file_name), NotificationType::Success),
+                    Err(error) => NotificationMessage::new(format!("Export failed: {}", error), NotificationType::Error),
+                });
+            }
+            BulkAction::Merge(order) => {
+                let sources: Vec<ChatSession> = ids
+                    .iter()
+                    .filter_map(|id| sessions.get(id))
+                    .map(session_pages::hydrated)
+                    .collect();
+                let sources: Vec<&ChatSession> = sources.iter().collect();
+                if sources.len() < 2 {
+                    return;
+                }
+                let now = js_sys::Date::now();
+                let merged = session_merge::merge(&sources, order, format!("session_{}", now as u64), now);
+                let message = format!("Merged {} sessions into \"{}\"", sources.len(), merged.title);
+                let merged_id = merged.id.clone();
+                sessions.dispatch(SessionAction::Put(merged));
+                current_session_id.set(Some(merged_id));
+                add_notification.emit(NotificationMessage::new(message, NotificationType::Success));
+            }
+            action => {
+                let mut new_sessions = sessions.to_map();
+                let changed = session_bulk::apply(&mut new_sessions, &ids, &action);
+                for session in ids.iter().filter_map(|id| new_sessions.remove(id)) {
+                    sessions.dispatch(SessionAction::Put(session));
+                }
+                let verb = if action == BulkAction::Archive { "Archived" } else { "Tagged" };
+                add_notification.emit(NotificationMessage::new(
+                    format!("{} {} session{}", verb, changed, if changed == 1 { "" } else { "s" }),
+                    NotificationType::Success,
+                ));
+            }
+        })
+    };
+
     let toggle_pin_session = {
         let sessions = sessions.clone();
         Callback::from(move |session_id: String| {
-            let mut new_sessions = (*sessions).clone();
-            if let Some(session) = new_sessions.get_mut(&session_id) {
+            if let Some(mut session) = sessions.get(&session_id).cloned() {
                 session.pinned = !session.pinned;
-                for session in new_sessions.iter()
-                {
-                    log!(format!("🗨️ Session {} now has {} messages", session.0, session.1.messages.len()));
+                sessions.dispatch(SessionAction::Put(session));
+            }
+        })
+    };
+
+    let toggle_archive_session = {
+        let sessions = sessions.clone();
+        Callback::from(move |session_id: String| {
+            if let Some(mut session) = sessions.get(&session_id).cloned() {
+                session.archived = !session.archived;
+                sessions.dispatch(SessionAction::Put(session));
+            }
+        })
+    };
+
+    let toggle_lock_session = {
+        let sessions = sessions.clone();
+        let current_session_id = current_session_id.clone();
+        Callback::from(move |_: ()| {
+            if let Some(session_id) = current_session_id.as_ref() {
+                if let Some(mut session) = sessions.get(session_id).cloned() {
+                    session.locked = !session.locked;
+                    sessions.dispatch(SessionAction::Put(session));
                 }
-                sessions.set(new_sessions);
             }
         })
     };
 
+    let restore_snapshot = {
+        let sessions = sessions.clone();
+        let add_notification = add_notification.clone();
+        Callback::from(move |snapshot: SessionSnapshot| {
+            let Some(current) = sessions.get(&snapshot.session.id) else {
+                return;
+            };
+            if current.locked {
+                add_notification.emit(NotificationMessage::new(
+                    "This session is locked. Unlock it to restore a snapshot.".to_string(),
+                    NotificationType::Warning,
+                ));
+                return;
+            }
+            session_snapshots::take_snapshot(current, SnapshotReason::BeforeRestore);
+            sessions.dispatch(SessionAction::Put(snapshot.restore(js_sys::Date::now())));
+            add_notification.emit(NotificationMessage::new(
+                "Snapshot restored. The previous conversation was snapshotted too.".to_string(),
+                NotificationType::Success,
+            ));
+        })
+    };
+
     let clear_current_session = {
         let sessions = sessions.clone();
         let current_session_id = current_session_id.clone();
         Callback::from(move |_: ()| {
             if let Some(session_id) = current_session_id.as_ref() {
-                let mut new_sessions = (*sessions).clone();
-                if let Some(session) = new_sessions.get_mut(session_id) {
+                if let Some(mut session) = sessions.get(session_id).cloned() {
+                    session_snapshots::take_snapshot(&session, SnapshotReason::BeforeRemoval);
                     session.messages.clear();
                     session.updated_at = js_sys::Date::now();
-                    for session in new_sessions.iter()
-                    {
-                        log!(format!("🗨️ Session {} now has {} messages", session.0, session.1.messages.len()));
-                    }
-                    sessions.set(new_sessions);
+                    sessions.dispatch(SessionAction::Put(session));
                 }
             }
         })
@@ -406,25 +944,45 @@ pub fn flexible_llm_playground() -> Html {
     };
 
     let save_settings = {
-        let api_config = api_config.clone();
+        let app_state = app_state.clone();
         let show_settings = show_settings.clone();
         Callback::from(move |config: FlexibleApiConfig| {
-            api_config.set(config);
-    
-            let (provider_name, model_name) = api_config.get_current_provider_and_model();
+            let (provider_name, model_name) = config.get_current_provider_and_model();
+            app_state.dispatch(AppAction::SetConfig(config));
             
-            // Debug logging
-            use gloo_console::log;
-            log!("🔍 playground::save_settings - Provider: {}, Model: {}", &provider_name, &model_name);
+            logging::debug!("Settings saved with provider: {}, model: {}", provider_name, model_name);
             
             show_settings.set(false);
         })
     };
 
+    // Config changes made outside the settings panel
+    let update_config = {
+        let app_state = app_state.clone();
+        Callback::from(move |config: FlexibleApiConfig| {
+            app_state.dispatch(AppAction::SetConfig(config))
+        })
+    };
+
+    {
+        let add_notification = add_notification.clone();
+        let on_session_update = on_session_update.clone();
+        let on_session_action = on_session_action.clone();
+        let update_config = update_config.clone();
+        use_event_subscription(&event_bus, move |event| match event {
+            PlaygroundEvent::Notify(notification) => add_notification.emit(notification.clone()),
+            PlaygroundEvent::SessionUpdated(session) => on_session_update.emit(session.clone()),
+            PlaygroundEvent::SessionChanged(action) => on_session_action.emit((**action).clone()),
+            PlaygroundEvent::ConfigChanged(config) => update_config.emit((**config).clone()),
+            // Answered by the tool approval dialog
+            PlaygroundEvent::ToolApproval(_) => {}
+        });
+    }
+
     let on_mcp_client_change = {
-        let mcp_client = mcp_client.clone();
+        let app_state = app_state.clone();
         Callback::from(move |client: Option<McpClient>| {
-            mcp_client.set(client);
+            app_state.dispatch(AppAction::SetMcpClient(client));
         })
     };
 
@@ -435,73 +993,194 @@ pub fn flexible_llm_playground() -> Html {
         })
     };
 
+    // Comparison, eval and experiment views replace the chat until toggled off
+    let toggle_view = {
+        let main_view = main_view.clone();
+        move |view: MainView| {
+            let main_view = main_view.clone();
+            Callback::from(move |_: ()| {
+                main_view.set(if *main_view == view { MainView::Chat } else { view });
+            })
+        }
+    };
+    let toggle_comparison = toggle_view(MainView::Comparison);
+    let toggle_evals = toggle_view(MainView::Evals);
+    let toggle_ab_experiment = toggle_view(MainView::AbExperiment);
+    let toggle_knowledge = toggle_view(MainView::Knowledge);
+    let toggle_fine_tune = toggle_view(MainView::FineTune);
+    let toggle_replay = toggle_view(MainView::Replay);
+    let toggle_trace = toggle_view(MainView::Trace);
+    let toggle_database = toggle_view(MainView::Database);
+    let toggle_diagnostics = toggle_view(MainView::Diagnostics);
+    let toggle_bookmarks = toggle_view(MainView::Bookmarks);
+
+    // Two-model dialogue mode
+    let toggle_dialogue_setup = {
+        let show_dialogue_setup = show_dialogue_setup.clone();
+        Callback::from(move |_: ()| {
+            show_dialogue_setup.set(!*show_dialogue_setup);
+        })
+    };
+
+    let on_dialogue_start = {
+        let sessions = sessions.clone();
+        let current_session_id = current_session_id.clone();
+        let show_dialogue_setup = show_dialogue_setup.clone();
+        let main_view = main_view.clone();
+        Callback::from(move |dialogue: DialogueConfig| {
+            let now = js_sys::Date::now();
+            let session_id = format!("session_{}", now as u64);
+            let topic_preview: String = dialogue.topic.trim().chars().take(40).collect();
+
+            // The seed topic opens the conversation; the chatroom starts the turns
+            let new_session = ChatSession {
+                id: session_id.clone(),
+                title: format!("Dialogue: {}", topic_preview),
+                messages: vec![Message {
+                    id: format!("user_{}", now as u64),
+                    timestamp: now,
+                    ..Message::new(MessageRole::User, dialogue.topic.trim())
+                }],
+                created_at: now,
+                updated_at: now,
+                pinned: false,
+                pinned_messages: Vec::new(),
+                locked: false,
+                tags: Vec::new(),
+                archived: false,
+                todos: Vec::new(),
+                budget_since: 0.0,
+                dialogue: Some(dialogue),
+                persona: None,
+                incognito: false,
+                older_pages: 0,
+            };
+
+            sessions.dispatch(SessionAction::Put(new_session));
+            current_session_id.set(Some(session_id));
+            main_view.set(MainView::Chat);
+            show_dialogue_setup.set(false);
+        })
+    };
+
+    // Pane resizing
+    let on_sidebar_drag = {
+        let sidebar_width = sidebar_width.clone();
+        Callback::from(move |client_x: f64| {
+            sidebar_width.set(clamp_sidebar_width(client_x.max(0.0) as u32));
+        })
+    };
+
+    let on_resize_end = {
+        let pane_resizes = pane_resizes.clone();
+        Callback::from(move |_: ()| pane_resizes.set(pane_resizes.wrapping_add(1)))
+    };
+
+    let on_settings_resize = {
+        let settings_width = settings_width.clone();
+        Callback::from(move |width: u32| {
+            settings_width.set(width);
+        })
+    };
+
+    let on_settings_mode_change = {
+        let settings_mode = settings_mode.clone();
+        Callback::from(move |mode: SettingsPanelMode| {
+            settings_mode.set(mode);
+        })
+    };
+
     // Dark mode toggle
     let toggle_dark_mode = {
-        let dark_mode = dark_mode.clone();
+        let app_state = app_state.clone();
         Callback::from(move |_| {
-            dark_mode.set(!*dark_mode);
-        })
-    };
-
-
-
-    // Create a legacy API config for components that still need it
-    let create_legacy_api_config = |flexible_config: &FlexibleApiConfig| {
-        let (provider_name, model_name) = flexible_config.get_current_provider_and_model();
-
-        if let Some(provider) = flexible_config.get_provider(&provider_name) {
-            if provider.transformer.r#use.contains(&"gemini".to_string()) {
-                crate::llm_playground::ApiConfig {
-                    current_provider: crate::llm_playground::ApiProvider::Gemini,
-                    gemini: crate::llm_playground::GeminiConfig {
-                        api_key: provider.api_key.clone(),
-                        model: model_name.clone(),
-                        base_url: provider.api_base_url.clone(),
-                    },
-                    openai: crate::llm_playground::OpenAIConfig {
-                        base_url: "".to_string(),
-                        api_key: "".to_string(),
-                        model: "".to_string(),
-                    },
-                    shared_settings: crate::llm_playground::types::SharedSettings {
-                        temperature: flexible_config.shared_settings.temperature,
-                        max_tokens: flexible_config.shared_settings.max_tokens,
-                        retry_delay: flexible_config.shared_settings.retry_delay,
-                    },
-                    system_prompt: flexible_config.system_prompt.clone(),
-                    function_tools: flexible_config.function_tools.clone(),
-                    structured_outputs: flexible_config.structured_outputs.clone(),
-                    mcp_config: crate::llm_playground::mcp_client::McpConfig::default(),
-                }
-            } else {
-                crate::llm_playground::ApiConfig {
-                    current_provider: crate::llm_playground::ApiProvider::OpenAI,
-                    gemini: crate::llm_playground::GeminiConfig {
-                        api_key: "".to_string(),
-                        model: "".to_string(),
-                        base_url: "".to_string(),
-                    },
-                    openai: crate::llm_playground::OpenAIConfig {
-                        base_url: provider.api_base_url.clone(),
-                        api_key: provider.api_key.clone(),
-                        model: model_name.clone(),
-                    },
-                    shared_settings: crate::llm_playground::types::SharedSettings {
-                        temperature: flexible_config.shared_settings.temperature,
-                        max_tokens: flexible_config.shared_settings.max_tokens,
-                        retry_delay: flexible_config.shared_settings.retry_delay,
-                    },
-                    system_prompt: flexible_config.system_prompt.clone(),
-                    function_tools: flexible_config.function_tools.clone(),
-                    structured_outputs: flexible_config.structured_outputs.clone(),
-                    mcp_config: crate::llm_playground::mcp_client::McpConfig::default(),
-                }
+            app_state.dispatch(AppAction::ToggleDarkMode);
+        })
+    };
+
+    // Download the current session as a standalone HTML page with API keys stripped
+    let share_as_html = {
+        let sessions = sessions.clone();
+        let current_session_id = current_session_id.clone();
+        let app_state = app_state.clone();
+        let add_notification = add_notification.clone();
+        Callback::from(move |_| {
+            let Some(session) = current_session_id
+                .as_ref()
+                .and_then(|id| sessions.get(id))
+                .map(session_pages::hydrated)
+            else {
+                return;
+            };
+
+            let secrets: Vec<String> = app_state
+                .config
+                .providers
+                .iter()
+                .map(|p| p.api_key.clone())
+                .collect();
+            let html = html_export::render_session_html(&session, &secrets);
+            let file_name = html_export::export_file_name(&session);
+
+            match html_export::download_file(&file_name, &html, "text/html") {
+                Ok(()) => add_notification.emit(NotificationMessage::new(
+                    format!("Exported {}", file_name),
+                    NotificationType::Success,
+                )),
+                Err(error) => add_notification.emit(NotificationMessage::new(
+                    format!("HTML export failed: {}", error),
+                    NotificationType::Error,
+                )),
             }
-        } else {
-            crate::llm_playground::ApiConfig::default()
-        }
+        })
     };
 
+
+    // Copy a link that opens the current session in the read-only viewer
+    let share_as_link = {
+        let sessions = sessions.clone();
+        let current_session_id = current_session_id.clone();
+        let app_state = app_state.clone();
+        let add_notification = add_notification.clone();
+        Callback::from(move |_| {
+            let Some(session) = current_session_id
+                .as_ref()
+                .and_then(|id| sessions.get(id))
+                .map(session_pages::hydrated)
+            else {
+                return;
+            };
+
+            let secrets: Vec<String> = app_state
+                .config
+                .providers
+                .iter()
+                .map(|p| p.api_key.clone())
+                .collect();
+            let shared = shared_view::SharedConversation::from_session(&session, &secrets);
+            let add_notification = add_notification.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = async {
+                    let link = shared_view::view_url(&shared_view::encode(&shared).await?)?;
+                    html_export::copy_to_clipboard(&link).await?;
+                    Ok::<_, String>(link.len())
+                }
+                .await;
+                add_notification.emit(match result {
+                    Ok(length) => NotificationMessage::new(
+                        format!("Viewer link copied ({} characters)", length),
+                        NotificationType::Success,
+                    ),
+                    Err(error) => NotificationMessage::new(
+                        format!("Couldn't create viewer link: {}", error),
+                        NotificationType::Error,
+                    ),
+                });
+            });
+        })
+    };
+
+
     // Get current session
     let current_session = current_session_id
         .as_ref()
@@ -510,41 +1189,130 @@ pub fn flexible_llm_playground() -> Html {
 
     // Get current provider info for display
     let current_provider_info = {
-        let (provider_name, model_name) = api_config.get_current_provider_and_model();
+        let (provider_name, model_name) = app_state.config.get_current_provider_and_model();
         format!("{} - {}", provider_name, model_name)
     };
 
     html! {
-        <div class={classes!("flex", "h-screen", "overflow-hidden", if *dark_mode { "dark" } else { "" })}>
+        <ContextProvider<EventBus> context={(*event_bus).clone()}>
+        <ContextProvider<AppStateHandle> context={app_state.clone()}>
+        <div class={classes!("flex", "h-screen", "overflow-hidden", if app_state.dark_mode { "dark" } else { "" })}>
             <div class="flex h-full w-full bg-gray-50 dark:bg-gray-900">
                 // Sidebar
                 <Sidebar
                     sessions={(*sessions).clone()}
                     current_session_id={(*current_session_id).clone()}
                     on_new_session={create_new_session.clone()}
+                    on_new_incognito_session={start_incognito_session}
                     on_select_session={switch_session}
                     on_delete_session={delete_session}
                     on_toggle_settings={toggle_settings}
+                    on_compare={toggle_comparison.clone()}
+                    on_dialogue={toggle_dialogue_setup.clone()}
+                    on_evals={toggle_evals.clone()}
+                    on_ab_experiment={toggle_ab_experiment.clone()}
+                    on_knowledge={toggle_knowledge.clone()}
+                    on_fine_tune={toggle_fine_tune.clone()}
+                    on_diagnostics={toggle_diagnostics.clone()}
+                    on_bookmarks={toggle_bookmarks.clone()}
+                    on_toggle_archive={toggle_archive_session}
+                    on_bulk_action={bulk_action}
+                    width={*sidebar_width}
+                    api_config={app_state.config.clone()}
                 />
+                <ResizeHandle on_drag={on_sidebar_drag} on_drag_end={on_resize_end.clone()} />
 
                 // Main content area
                 <div class="flex-1 flex flex-col min-w-0">
-                    {if let Some(session) = current_session {
+                    {if *main_view == MainView::Comparison {
+                        html! {
+                            <ComparisonView
+                                on_close={toggle_comparison.clone()}
+                            />
+                        }
+                    } else if *main_view == MainView::Evals {
+                        html! {
+                            <EvalView
+                                on_close={toggle_evals.clone()}
+                            />
+                        }
+                    } else if *main_view == MainView::AbExperiment {
+                        html! {
+                            <AbExperimentView
+                                on_close={toggle_ab_experiment.clone()}
+                            />
+                        }
+                    } else if *main_view == MainView::Knowledge {
+                        html! {
+                            <KnowledgeView
+                                on_close={toggle_knowledge.clone()}
+                            />
+                        }
+                    } else if *main_view == MainView::FineTune {
+                        html! {
+                            <FineTuneView
+                                sessions={session_pages::hydrated_all(sessions.values())}
+                                on_close={toggle_fine_tune.clone()}
+                            />
+                        }
+                    } else if *main_view == MainView::Diagnostics {
+                        html! {
+                            <DiagnosticsView
+                                session_count={sessions.len()}
+                                on_scrub_secrets={scrub_secrets}
+                                on_close={toggle_diagnostics.clone()}
+                            />
+                        }
+                    } else if *main_view == MainView::Bookmarks {
+                        html! {
+                            <BookmarksView
+                                sessions={session_pages::hydrated_all(sessions.values())}
+                                on_open={open_bookmark}
+                                on_close={toggle_bookmarks.clone()}
+                            />
+                        }
+                    } else if let (MainView::Replay, Some(session)) = (*main_view, current_session.as_ref()) {
+                        html! {
+                            <ReplayView
+                                session_id={session.id.clone()}
+                                on_close={toggle_replay.clone()}
+                            />
+                        }
+                    } else if let (MainView::Trace, Some(session)) = (*main_view, current_session.as_ref()) {
+                        html! {
+                            <TraceView
+                                session={session.clone()}
+                                on_close={toggle_trace.clone()}
+                            />
+                        }
+                    } else if let (MainView::Database, Some(session)) = (*main_view, current_session.as_ref()) {
+                        html! {
+                            <SessionDatabaseView
+                                session_id={session.id.clone()}
+                                incognito={session.incognito}
+                                on_close={toggle_database.clone()}
+                            />
+                        }
+                    } else if let Some(session) = current_session {
                         html! {
                             <>
                                 <ChatHeader
                                     current_session={Some(session.clone())}
-                                    api_config={create_legacy_api_config(&*api_config)}
                                     on_toggle_dark_mode={toggle_dark_mode}
-                                    dark_mode={*dark_mode}
+                                    dark_mode={app_state.dark_mode}
+                                    on_share_html={share_as_html}
+                                    on_share_link={share_as_link}
+                                    on_replay={toggle_replay}
+                                    on_trace={toggle_trace}
+                                    on_database={toggle_database}
+                                    on_toggle_lock={toggle_lock_session}
+                                    on_restore_snapshot={restore_snapshot}
+                                    persona={personas::for_session(&app_state.config, Some(&session)).cloned()}
                                 />
                                 <Chatroom
                                     session={Some(session.clone())}
-                                    api_config={(*api_config).clone()}
-                                    llm_client={(*llm_client).clone()}
-                                    mcp_client={(*mcp_client).clone()}
-                                    on_session_update={on_session_update}
-                                    on_notification={add_notification}
+                                    focus_message={(*focus_message).clone()}
+                                    on_load_older={load_older_messages}
                                 />
                             </>
                         }
@@ -580,11 +1348,17 @@ pub fn flexible_llm_playground() -> Html {
                 {if *show_settings {
                     html! {
                         <FlexibleSettingsPanel
-                            config={(*api_config).clone()}
+                            config={app_state.config.clone()}
                             on_save={save_settings}
                             on_close={close_settings}
-                            mcp_client={(*mcp_client).clone()}
+                            mcp_client={app_state.mcp_client.clone()}
                             on_mcp_client_change={on_mcp_client_change}
+                            width={*settings_width}
+                            mode={*settings_mode}
+                            on_resize={on_settings_resize}
+                            on_resize_end={on_resize_end}
+                            on_mode_change={on_settings_mode_change}
+                            section={route.as_ref().and_then(Route::settings_section)}
                         />
                     }
                 } else {
@@ -593,11 +1367,46 @@ pub fn flexible_llm_playground() -> Html {
 
                 // Model selector modal
                 <ModelSelector
-                    config={(*api_config).clone()}
+                    config={app_state.config.clone()}
                     on_select={on_model_selected}
+                    on_persona={on_persona_selected}
                     on_cancel={on_model_selector_cancel}
                     show={*show_model_selector}
                 />
+                <QuickModelSwitcher
+                    config={app_state.config.clone()}
+                    show={*show_quick_switch}
+                    on_select={on_quick_switch}
+                    on_close={close_quick_switch}
+                />
+
+                {if *show_dialogue_setup {
+                    html! {
+                        <DialogueSetup
+                            config={app_state.config.clone()}
+                            on_start={on_dialogue_start}
+                            on_cancel={toggle_dialogue_setup}
+                        />
+                    }
+                } else {
+                    html! {}
+                }}
+
+                {if quarantined.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <StorageRecoveryDialog
+                            payloads={(*quarantined).clone()}
+                            on_import={import_quarantined}
+                            on_discard={discard_quarantined}
+                            on_close={close_recovery}
+                        />
+                    }
+                }}
+
+                {confirm.dialog()}
+                <ToolApprovalDialog />
 
                 // Notification container
                 <NotificationContainer
@@ -606,5 +1415,7 @@ pub fn flexible_llm_playground() -> Html {
                 />
             </div>
         </div>
+        </ContextProvider<AppStateHandle>>
+        </ContextProvider<EventBus>>
     }
 }
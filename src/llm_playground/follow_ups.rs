@@ -0,0 +1,129 @@
+// Follow-up suggestions
+// After a reply, a model (the session's or a cheaper one picked in settings)
+// proposes a few questions the user might ask next. They're shown as chips
+// under the latest reply; clicking one sends it.
+
+use crate::llm_playground::{
+    conversation_starters, flexible_client::FlexibleLLMClient, provider_config::FlexibleApiConfig,
+    Message, MessageRole,
+};
+use serde::{Deserialize, Serialize};
+
+/// Recent chat messages given to the model as context
+pub const CONTEXT_MESSAGES: usize = 4;
+/// Characters of each context message included
+const CONTEXT_MESSAGE_CHARS: usize = 1500;
+
+pub const META_PROMPT: &str = "You suggest what a user could ask next in a conversation with an \
+AI assistant. Given the latest exchange, write two or three short follow-up questions that dig \
+deeper, clarify or take the next practical step. Write them in the user's voice, one per line, \
+without numbering or quotes, and nothing else.";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FollowUpSettings {
+    pub enabled: bool,
+    /// "provider,model" asked for the suggestions; empty uses the session's model
+    pub model: String,
+}
+
+impl FollowUpSettings {
+    /// The config the suggestions are requested with
+    pub fn config_for(&self, session_config: &FlexibleApiConfig) -> FlexibleApiConfig {
+        let mut config = session_config.clone();
+        if let Some((provider, model)) = self.model.split_once(',') {
+            config.set_session_provider(provider, model);
+            config.apply_model_defaults(provider, model);
+        }
+        config
+    }
+}
+
+/// The reply suggestions are offered for: the last message, when it is an
+/// assistant answer rather than a tool call
+pub fn answered_message(messages: &[Message]) -> Option<&Message> {
+    messages.last().filter(|m| {
+        m.role == MessageRole::Assistant && m.function_call.is_none() && !m.content.trim().is_empty()
+    })
+}
+
+/// The single user message asking for suggestions
+pub fn build_request(recent: &[Message]) -> String {
+    let context: Vec<String> = recent
+        .iter()
+        .filter(|m| matches!(m.role, MessageRole::User | MessageRole::Assistant))
+        .filter(|m| !m.content.trim().is_empty())
+        .rev()
+        .take(CONTEXT_MESSAGES)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .map(|m| {
+            let role = if m.role == MessageRole::User {
+                "User"
+            } else {
+                "Assistant"
+            };
+            let content: String = m.content.chars().take(CONTEXT_MESSAGE_CHARS).collect();
+            format!("{}: {}", role, content)
+        })
+        .collect();
+    format!(
+        "Conversation:\n{}\n\nSuggest follow-up questions.",
+        context.join("\n\n")
+    )
+}
+
+/// Ask for follow-up questions to the conversation's latest reply
+pub async fn generate(
+    client: &FlexibleLLMClient,
+    config: &FlexibleApiConfig,
+    messages: &[Message],
+) -> Result<Vec<String>, String> {
+    let mut config = config.clone();
+    config.system_prompt = META_PROMPT.to_string();
+    config.active_structured_output = None;
+    for tool in &mut config.function_tools {
+        tool.enabled = false;
+    }
+
+    let message = Message {
+        id: format!("follow_ups_{}", js_sys::Date::now() as u64),
+        timestamp: js_sys::Date::now(),
+        ..Message::new(MessageRole::User, build_request(messages))
+    };
+    let response = client.send_message(&[message], &config).await?;
+    let suggestions = conversation_starters::parse_suggestions(&response.content.unwrap_or_default());
+    if suggestions.is_empty() {
+        return Err("The model returned no suggestions".to_string());
+    }
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_keeps_the_latest_exchange() {
+        let mut recent = vec![Message::new(MessageRole::System, "ignored")];
+        recent.extend((0..6).map(|i| Message::new(MessageRole::User, &format!("turn {}", i))));
+        recent.push(Message::new(MessageRole::Assistant, "answer"));
+        let request = build_request(&recent);
+        assert!(request.starts_with("Conversation:\nUser: turn 3\n\nUser: turn 4"));
+        assert!(request.ends_with("Assistant: answer\n\nSuggest follow-up questions."));
+        assert!(!request.contains("ignored"));
+    }
+
+    #[test]
+    fn only_answers_get_suggestions() {
+        let mut messages = vec![Message::new(MessageRole::User, "hi")];
+        assert!(answered_message(&messages).is_none());
+        messages.push(Message::new(MessageRole::Assistant, "  "));
+        assert!(answered_message(&messages).is_none());
+        messages.push(Message::new(MessageRole::Assistant, "Hello!"));
+        assert!(answered_message(&messages).is_some());
+        messages.last_mut().unwrap().function_call = Some(serde_json::json!({"name": "search"}));
+        assert!(answered_message(&messages).is_none());
+    }
+}
@@ -0,0 +1,284 @@
+// Output guardrails
+// User-defined keyword or regex rules checked against assistant replies. A rule
+// either blocks a pattern or requires one; a violation is shown on the message,
+// redacts the match, or sends the reply back for another attempt.
+
+use crate::llm_playground::{
+    api_clients::LLMResponse, eval_suite::compile_regex, json_repair::RepairIssue,
+};
+use serde::{Deserialize, Serialize};
+
+pub const REDACTED: &str = "[REDACTED]";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum GuardrailKind {
+    /// The reply must not contain the pattern
+    #[default]
+    Blocked,
+    /// The reply must contain the pattern
+    Required,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum PatternType {
+    #[default]
+    Keyword,
+    Regex,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum GuardrailAction {
+    #[default]
+    Warn,
+    /// Replace blocked matches; a missing required pattern only warns
+    Redact,
+    /// Ask the model for a new reply, up to the JSON repair retry count
+    Retry,
+}
+
+impl GuardrailAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GuardrailAction::Warn => "Warn",
+            GuardrailAction::Redact => "Redact",
+            GuardrailAction::Retry => "Retry",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GuardrailRule {
+    pub id: String,
+    pub name: String,
+    pub kind: GuardrailKind,
+    pub pattern_type: PatternType,
+    pub pattern: String,
+    /// Keywords otherwise match ignoring ASCII case, regexes get the `i` flag
+    #[serde(default)]
+    pub case_sensitive: bool,
+    pub action: GuardrailAction,
+    pub enabled: bool,
+}
+
+impl Default for GuardrailRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GuardrailRule {
+    pub fn new() -> Self {
+        Self {
+            id: format!("guardrail_{}", js_sys::Date::now() as u64),
+            name: "New rule".to_string(),
+            kind: GuardrailKind::Blocked,
+            pattern_type: PatternType::Keyword,
+            pattern: String::new(),
+            case_sensitive: false,
+            action: GuardrailAction::Warn,
+            enabled: true,
+        }
+    }
+
+    /// The first match of the pattern in `text`
+    pub fn first_match(&self, text: &str) -> Result<Option<String>, String> {
+        match self.pattern_type {
+            PatternType::Keyword => Ok(keyword_ranges(text, &self.pattern, self.case_sensitive)
+                .first()
+                .map(|&(start, end)| text[start..end].to_string())),
+            PatternType::Regex => {
+                let regex = compile_regex(&self.pattern, &self.regex_flags(false))?;
+                Ok(regex
+                    .exec(text)
+                    .and_then(|found| found.get(0).as_string())
+                    .filter(|found| !found.is_empty()))
+            }
+        }
+    }
+
+    /// `text` with every match of the pattern replaced by REDACTED
+    pub fn redact(&self, text: &str) -> Result<String, String> {
+        match self.pattern_type {
+            PatternType::Keyword => {
+                let mut redacted = String::with_capacity(text.len());
+                let mut last = 0;
+                for (start, end) in keyword_ranges(text, &self.pattern, self.case_sensitive) {
+                    redacted.push_str(&text[last..start]);
+                    redacted.push_str(REDACTED);
+                    last = end;
+                }
+                redacted.push_str(&text[last..]);
+                Ok(redacted)
+            }
+            PatternType::Regex => {
+                let regex = compile_regex(&self.pattern, &self.regex_flags(true))?;
+                Ok(String::from(
+                    js_sys::JsString::from(text).replace_by_pattern(&regex, REDACTED),
+                ))
+            }
+        }
+    }
+
+    fn regex_flags(&self, global: bool) -> String {
+        let mut flags = String::new();
+        if global {
+            flags.push('g');
+        }
+        if !self.case_sensitive {
+            flags.push('i');
+        }
+        flags
+    }
+}
+
+/// A rule a reply broke, kept on the message for display
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GuardrailViolation {
+    pub rule_name: String,
+    pub action: GuardrailAction,
+    pub detail: String,
+}
+
+/// Byte ranges of non-overlapping keyword occurrences
+fn keyword_ranges(text: &str, keyword: &str, case_sensitive: bool) -> Vec<(usize, usize)> {
+    if keyword.is_empty() {
+        return Vec::new();
+    }
+    // ASCII lowercasing keeps byte offsets valid in the original text
+    let (haystack, needle) = if case_sensitive {
+        (text.to_string(), keyword.to_string())
+    } else {
+        (text.to_ascii_lowercase(), keyword.to_ascii_lowercase())
+    };
+    haystack
+        .match_indices(&needle)
+        .map(|(start, found)| (start, start + found.len()))
+        .collect()
+}
+
+/// Check a reply against every enabled rule. Returns the reply with redactions
+/// applied and the violations found.
+pub fn apply(text: &str, rules: &[GuardrailRule]) -> (String, Vec<GuardrailViolation>) {
+    let mut text = text.to_string();
+    let mut violations = Vec::new();
+
+    for rule in rules.iter().filter(|r| r.enabled && !r.pattern.is_empty()) {
+        let violation = |action: GuardrailAction, detail: String| GuardrailViolation {
+            rule_name: rule.name.clone(),
+            action,
+            detail,
+        };
+        let found = match rule.first_match(&text) {
+            Ok(found) => found,
+            Err(error) => {
+                violations.push(violation(GuardrailAction::Warn, error));
+                continue;
+            }
+        };
+
+        match (rule.kind, found) {
+            (GuardrailKind::Blocked, Some(found)) => {
+                if rule.action == GuardrailAction::Redact {
+                    match rule.redact(&text) {
+                        Ok(redacted) => {
+                            text = redacted;
+                            violations.push(violation(
+                                rule.action,
+                                "Blocked content was redacted".to_string(),
+                            ));
+                        }
+                        Err(error) => violations.push(violation(GuardrailAction::Warn, error)),
+                    }
+                } else {
+                    violations.push(violation(rule.action, format!("Contains \"{}\"", found)));
+                }
+            }
+            (GuardrailKind::Required, None) => {
+                let action = match rule.action {
+                    GuardrailAction::Redact => GuardrailAction::Warn,
+                    action => action,
+                };
+                violations.push(violation(
+                    action,
+                    format!("Missing required pattern \"{}\"", rule.pattern),
+                ));
+            }
+            _ => {}
+        }
+    }
+    (text, violations)
+}
+
+/// A correction request for the first violated rule with the retry action.
+/// Only plain text replies are checked; tool calls go through.
+pub fn retry_issue(response: &LLMResponse, rules: &[GuardrailRule]) -> Option<RepairIssue> {
+    if !response.function_calls.is_empty() {
+        return None;
+    }
+    let content = response.content.as_deref().unwrap_or_default();
+    let (_, violations) = apply(content, rules);
+    violations
+        .into_iter()
+        .find(|v| v.action == GuardrailAction::Retry)
+        .map(|v| RepairIssue {
+            invalid_output: content.to_string(),
+            error: format!("it breaks the rule \"{}\": {}", v.rule_name, v.detail),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(kind: GuardrailKind, pattern: &str, action: GuardrailAction) -> GuardrailRule {
+        GuardrailRule {
+            id: "r".to_string(),
+            name: pattern.to_string(),
+            kind,
+            pattern_type: PatternType::Keyword,
+            pattern: pattern.to_string(),
+            case_sensitive: false,
+            action,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn redacts_blocked_keywords_ignoring_case() {
+        let rules = [rule(
+            GuardrailKind::Blocked,
+            "secret",
+            GuardrailAction::Redact,
+        )];
+        let (text, violations) = apply("A Secret, another SECRET.", &rules);
+        assert_eq!(text, "A [REDACTED], another [REDACTED].");
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].action, GuardrailAction::Redact);
+
+        let mut exact = rules[0].clone();
+        exact.case_sensitive = true;
+        assert_eq!(
+            apply("A Secret", &[exact]),
+            ("A Secret".to_string(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn reports_missing_required_patterns() {
+        let mut disabled = rule(GuardrailKind::Blocked, "hello", GuardrailAction::Warn);
+        disabled.enabled = false;
+        let rules = [
+            rule(GuardrailKind::Required, "Sources:", GuardrailAction::Redact),
+            rule(GuardrailKind::Blocked, "as an ai", GuardrailAction::Retry),
+            disabled,
+        ];
+        let (text, violations) = apply("Hello! As an AI I think so.", &rules);
+        assert_eq!(text, "Hello! As an AI I think so.");
+        let actions: Vec<GuardrailAction> = violations.iter().map(|v| v.action).collect();
+        assert_eq!(actions, [GuardrailAction::Warn, GuardrailAction::Retry]);
+        assert_eq!(violations[1].detail, "Contains \"As an AI\"");
+
+        let (_, violations) = apply("Fine. Sources: none", &rules);
+        assert!(violations.is_empty());
+    }
+}
@@ -0,0 +1,143 @@
+// Handoff tools
+// `copy_to_clipboard` and `save_file` let a model hand its finished work to the
+// user directly, as text on the clipboard or a file in their downloads, instead
+// of leaving it to be copied out of a reply. Every call waits in the tool
+// approval dialog, and clicking Allow there is also the user gesture browsers
+// want before writing to the clipboard or starting a download.
+
+use crate::llm_playground::{attachments, desktop_tools, event_bus::EventBus, html_export};
+use serde_json::{json, Value};
+
+pub const COPY_TO_CLIPBOARD_TOOL_NAME: &str = "copy_to_clipboard";
+pub const SAVE_FILE_TOOL_NAME: &str = "save_file";
+
+/// Longest file name offered for download
+const MAX_FILE_NAME_CHARS: usize = 120;
+
+/// Whether `tool_name` is one of the handoff tools
+pub fn handles(tool_name: &str) -> bool {
+    tool_name == COPY_TO_CLIPBOARD_TOOL_NAME || tool_name == SAVE_FILE_TOOL_NAME
+}
+
+/// The file name to download as: no folders and none of the characters file
+/// systems reject
+pub fn safe_file_name(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '|' | '?' | '*') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .take(MAX_FILE_NAME_CHARS)
+        .collect();
+    let cleaned = cleaned.trim_matches(|c: char| c == '.' || c.is_whitespace());
+    if cleaned.is_empty() {
+        "download.txt".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// MIME type for a file name's extension, plain text when unknown
+pub fn mime_type(file_name: &str) -> &'static str {
+    let extension = file_name
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "md" | "markdown" => "text/markdown",
+        "csv" => "text/csv",
+        "tsv" => "text/tab-separated-values",
+        "json" => "application/json",
+        "html" | "htm" => "text/html",
+        "css" => "text/css",
+        "js" | "mjs" => "text/javascript",
+        "svg" => "image/svg+xml",
+        "xml" => "application/xml",
+        "yaml" | "yml" => "application/yaml",
+        "ics" => "text/calendar",
+        _ => "text/plain",
+    }
+}
+
+/// One line for the approval dialog
+pub fn describe(tool_name: &str, arguments: &Value) -> String {
+    let content = arguments.get("content").and_then(Value::as_str).unwrap_or_default();
+    if tool_name == SAVE_FILE_TOOL_NAME {
+        let file_name = arguments.get("file_name").and_then(Value::as_str).unwrap_or_default();
+        format!("Download {} ({})", file_name, attachments::format_size(content.len()))
+    } else {
+        let characters = content.chars().count();
+        format!(
+            "Copy {} character{} to the clipboard",
+            characters,
+            if characters == 1 { "" } else { "s" }
+        )
+    }
+}
+
+/// Ask the user, then copy or save the call's content
+pub async fn run(tool_name: &str, arguments: &Value, approvals: Option<&EventBus>) -> Result<Value, String> {
+    let content = arguments
+        .get("content")
+        .and_then(Value::as_str)
+        .ok_or("Missing required parameter: content")?;
+    let mut arguments = arguments.clone();
+    let file_name = if tool_name == SAVE_FILE_TOOL_NAME {
+        let requested = arguments
+            .get("file_name")
+            .and_then(Value::as_str)
+            .ok_or("Missing required parameter: file_name")?;
+        let file_name = safe_file_name(requested);
+        // The dialog shows the name the file will really get
+        arguments["file_name"] = json!(file_name);
+        Some(file_name)
+    } else {
+        None
+    };
+
+    let bus = approvals.ok_or("This tool needs the user's permission, which can't be asked for here")?;
+    if !desktop_tools::request_approval(bus, tool_name, &arguments).await {
+        return Err("The user declined this tool call".to_string());
+    }
+
+    match file_name {
+        Some(file_name) => {
+            html_export::download_file(&file_name, content, mime_type(&file_name))?;
+            Ok(json!({ "saved": true, "file_name": file_name, "bytes": content.len() }))
+        }
+        None => {
+            html_export::copy_to_clipboard(content).await?;
+            Ok(json!({ "copied": true, "characters": content.chars().count() }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_names_are_made_safe() {
+        assert_eq!(safe_file_name("report.csv"), "report.csv");
+        assert_eq!(safe_file_name("../../etc/passwd"), "passwd");
+        assert_eq!(safe_file_name("C:\\Users\\me\\notes.md"), "notes.md");
+        assert_eq!(safe_file_name("what? \"now\".txt"), "what_ _now_.txt");
+        assert_eq!(safe_file_name(" .. "), "download.txt");
+        assert_eq!(safe_file_name(&"a".repeat(200)).len(), MAX_FILE_NAME_CHARS);
+        assert_eq!(mime_type("data.JSON"), "application/json");
+        assert_eq!(mime_type("README"), "text/plain");
+    }
+
+    #[test]
+    fn calls_are_described_for_approval() {
+        let save = json!({ "file_name": "plan.md", "content": "# Plan" });
+        assert_eq!(describe(SAVE_FILE_TOOL_NAME, &save), "Download plan.md (1 KB)");
+        let copy = json!({ "content": "héllo" });
+        assert_eq!(describe(COPY_TO_CLIPBOARD_TOOL_NAME, &copy), "Copy 5 characters to the clipboard");
+    }
+}
@@ -0,0 +1,228 @@
+// History scrubber
+// Rewrites secrets found in stored sessions, their snapshots and recordings to
+// [REDACTED] before a workspace is exported or shared. The secrets are the API
+// keys and MCP header values currently configured, and optionally whatever the
+// Sensitive Data rules match.
+
+use crate::llm_playground::{
+    guardrails::REDACTED,
+    provider_config::FlexibleApiConfig,
+    redaction::RedactionRule,
+    session_recording::RECORDINGS_KEY,
+    session_snapshots::SNAPSHOTS_KEY,
+    ChatSession,
+};
+use gloo_storage::{LocalStorage, Storage};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Shorter configured values are placeholders like "ollama", not secrets
+const MIN_SECRET_CHARS: usize = 12;
+
+/// Local storage entries scrubbed next to the sessions
+const STORED_KEYS: [&str; 2] = [SNAPSHOTS_KEY, RECORDINGS_KEY];
+
+/// What a scrub found or replaced
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ScrubReport {
+    pub sessions: usize,
+    /// Snapshot and recording entries
+    pub stored: usize,
+    pub matches: usize,
+}
+
+impl ScrubReport {
+    pub fn summary(&self) -> String {
+        let plural = |count: usize| if count == 1 { "" } else { "s" };
+        format!(
+            "{} secret{} in {} session{} and {} snapshot or recording entr{}",
+            self.matches,
+            plural(self.matches),
+            self.sessions,
+            plural(self.sessions),
+            self.stored,
+            if self.stored == 1 { "y" } else { "ies" }
+        )
+    }
+}
+
+pub struct Scrubber {
+    /// Exact values to replace, longest first so a key containing another is
+    /// replaced whole
+    secrets: Vec<String>,
+    /// Regex rules, with REDACTED as their mask
+    rules: Vec<RedactionRule>,
+}
+
+impl Scrubber {
+    /// The configured API keys and MCP header values, plus the enabled
+    /// Sensitive Data rules when `include_rules` is set
+    pub fn new(config: &FlexibleApiConfig, include_rules: bool) -> Self {
+        let keys = config.providers.iter().map(|p| p.api_key.clone());
+        let headers = config
+            .get_mcp_config()
+            .servers
+            .values()
+            .filter_map(|server| server.headers.as_ref())
+            .flat_map(|headers| headers.values())
+            .flat_map(|value| {
+                // "Bearer <token>" is also stored on its own elsewhere
+                let token = value.strip_prefix("Bearer ").map(str::to_string);
+                std::iter::once(value.clone()).chain(token)
+            });
+        let rules = if include_rules && config.redaction.enabled {
+            config
+                .redaction
+                .rules
+                .iter()
+                .filter(|rule| rule.enabled && !rule.pattern.is_empty())
+                .map(|rule| RedactionRule {
+                    mask: REDACTED.to_string(),
+                    ..rule.clone()
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self::from_secrets(keys.chain(headers).collect(), rules)
+    }
+
+    pub fn from_secrets(secrets: Vec<String>, rules: Vec<RedactionRule>) -> Self {
+        let mut secrets: Vec<String> = secrets
+            .into_iter()
+            .map(|secret| secret.trim().to_string())
+            .filter(|secret| secret.chars().count() >= MIN_SECRET_CHARS)
+            .collect();
+        secrets.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        secrets.dedup();
+        Self { secrets, rules }
+    }
+
+    /// Nothing is configured to look for
+    pub fn is_empty(&self) -> bool {
+        self.secrets.is_empty() && self.rules.is_empty()
+    }
+
+    /// `text` with every secret replaced, and how many were
+    pub fn scrub_text(&self, text: &str) -> (String, usize) {
+        let mut text = text.to_string();
+        let mut matches = 0;
+        for secret in &self.secrets {
+            let count = text.matches(secret.as_str()).count();
+            if count > 0 {
+                text = text.replace(secret.as_str(), REDACTED);
+                matches += count;
+            }
+        }
+        for rule in &self.rules {
+            let count = rule.count_matches(&text).unwrap_or_default();
+            if count > 0 {
+                if let Ok(masked) = rule.mask_in(&text) {
+                    text = masked;
+                    matches += count;
+                }
+            }
+        }
+        (text, matches)
+    }
+
+    /// Scrub every string in a JSON tree in place
+    pub fn scrub_value(&self, value: &mut Value) -> usize {
+        match value {
+            Value::String(text) => {
+                let (scrubbed, matches) = self.scrub_text(text);
+                if matches > 0 {
+                    *text = scrubbed;
+                }
+                matches
+            }
+            Value::Array(items) => items.iter_mut().map(|item| self.scrub_value(item)).sum(),
+            Value::Object(fields) => fields.values_mut().map(|field| self.scrub_value(field)).sum(),
+            _ => 0,
+        }
+    }
+
+    /// The session with its secrets replaced, if it had any
+    pub fn scrub_session(&self, session: &ChatSession) -> Option<(ChatSession, usize)> {
+        let mut value = serde_json::to_value(session).ok()?;
+        let matches = self.scrub_value(&mut value);
+        if matches == 0 {
+            return None;
+        }
+        let scrubbed = serde_json::from_value(value).ok()?;
+        Some((scrubbed, matches))
+    }
+
+    /// Scrub the sessions, and the snapshots and recordings in local storage
+    /// unless `dry_run` is set. Returns the changed sessions.
+    pub fn scrub_all(
+        &self,
+        sessions: &HashMap<String, ChatSession>,
+        dry_run: bool,
+    ) -> (Vec<ChatSession>, ScrubReport) {
+        let mut report = ScrubReport::default();
+        let mut changed = Vec::new();
+        for session in sessions.values() {
+            if let Some((scrubbed, matches)) = self.scrub_session(session) {
+                report.sessions += 1;
+                report.matches += matches;
+                changed.push(scrubbed);
+            }
+        }
+
+        for key in STORED_KEYS {
+            let Ok(mut entries) = LocalStorage::get::<HashMap<String, Value>>(key) else {
+                continue;
+            };
+            let mut found = 0;
+            for entry in entries.values_mut() {
+                let matches = self.scrub_value(entry);
+                if matches > 0 {
+                    report.stored += 1;
+                    found += matches;
+                }
+            }
+            report.matches += found;
+            if found > 0 && !dry_run {
+                let _ = LocalStorage::set(key, &entries);
+            }
+        }
+        (changed, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaces_secrets_longest_first() {
+        let scrubber = Scrubber::from_secrets(
+            vec![
+                "sk-live-1234567890".to_string(),
+                "sk-live-1234567890-extended".to_string(),
+                " ollama ".to_string(),
+            ],
+            Vec::new(),
+        );
+        let (text, matches) =
+            scrubber.scrub_text("keys sk-live-1234567890-extended and sk-live-1234567890, ollama");
+        assert_eq!(text, "keys [REDACTED] and [REDACTED], ollama");
+        assert_eq!(matches, 2);
+        assert!(Scrubber::from_secrets(vec!["short".to_string()], Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn scrubs_nested_json_strings() {
+        let scrubber = Scrubber::from_secrets(vec!["token-abcdefghijkl".to_string()], Vec::new());
+        let mut value = serde_json::json!({
+            "content": "use token-abcdefghijkl",
+            "function_call": {"arguments": {"auth": ["token-abcdefghijkl"]}},
+            "count": 3
+        });
+        assert_eq!(scrubber.scrub_value(&mut value), 2);
+        assert_eq!(value["content"], "use [REDACTED]");
+        assert_eq!(value["function_call"]["arguments"]["auth"][0], "[REDACTED]");
+        assert_eq!(value["count"], 3);
+    }
+}
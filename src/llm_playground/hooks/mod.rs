@@ -1,4 +1,16 @@
 // Hooks for LLM Playground
+pub mod use_confirm;
+pub mod use_event_bus;
+pub mod use_focus_trap;
+pub mod use_latest_callback;
 pub mod use_llm_chat;
+pub mod use_markdown;
+pub mod use_offload;
 
-pub use use_llm_chat::use_llm_chat;
\ No newline at end of file
+pub use use_confirm::use_confirm;
+pub use use_event_bus::{use_event_bus, use_event_subscription};
+pub use use_focus_trap::use_focus_trap;
+pub use use_latest_callback::use_latest_callback;
+pub use use_llm_chat::use_llm_chat;
+pub use use_markdown::use_markdown;
+pub use use_offload::use_offload;
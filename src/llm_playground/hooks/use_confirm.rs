@@ -0,0 +1,57 @@
+use crate::llm_playground::components::{ConfirmDialog, ConfirmRequest};
+use yew::prelude::*;
+
+/// Handle returned by [`use_confirm`]
+#[derive(Clone)]
+pub struct UseConfirmHandle {
+    pending: UseStateHandle<Option<(ConfirmRequest, Callback<()>)>>,
+}
+
+impl UseConfirmHandle {
+    /// Ask before running `on_confirm`, unless the user chose not to be asked
+    /// again for this kind of action
+    pub fn confirm(&self, request: ConfirmRequest, on_confirm: Callback<()>) {
+        if request.action.is_skipped() {
+            on_confirm.emit(());
+        } else {
+            self.pending.set(Some((request, on_confirm)));
+        }
+    }
+
+    /// The dialog to render while a confirmation is pending
+    pub fn dialog(&self) -> Html {
+        let Some((request, on_confirm)) = (*self.pending).clone() else {
+            return html! {};
+        };
+
+        let on_dialog_confirm = {
+            let pending = self.pending.clone();
+            let action = request.action;
+            Callback::from(move |dont_ask_again: bool| {
+                if dont_ask_again {
+                    action.skip_from_now_on();
+                }
+                pending.set(None);
+                on_confirm.emit(());
+            })
+        };
+        let on_cancel = {
+            let pending = self.pending.clone();
+            Callback::from(move |_| pending.set(None))
+        };
+
+        html! {
+            <ConfirmDialog {request} on_confirm={on_dialog_confirm} {on_cancel} />
+        }
+    }
+}
+
+/// Confirmation modal for destructive actions.
+///
+/// Call `confirm` from an event handler and render `dialog()` somewhere in the
+/// component's output.
+#[hook]
+pub fn use_confirm() -> UseConfirmHandle {
+    let pending = use_state(|| None);
+    UseConfirmHandle { pending }
+}
@@ -0,0 +1,34 @@
+use crate::llm_playground::event_bus::{EventBus, PlaygroundEvent};
+use std::rc::Rc;
+use yew::prelude::*;
+
+/// The playground's event bus from context. Outside a provider a private bus
+/// is returned, so publishing is a no-op.
+#[hook]
+pub fn use_event_bus() -> EventBus {
+    let fallback = use_memo((), |_| EventBus::new());
+    use_context::<EventBus>().unwrap_or_else(|| (*fallback).clone())
+}
+
+/// Handle events published on `bus` while the component is mounted.
+///
+/// The handler from the latest render is the one called, so it can read
+/// state handles without going stale.
+#[hook]
+pub fn use_event_subscription<F>(bus: &EventBus, handler: F)
+where
+    F: Fn(&PlaygroundEvent) + 'static,
+{
+    let latest = use_mut_ref(|| Option::<Rc<dyn Fn(&PlaygroundEvent)>>::None);
+    *latest.borrow_mut() = Some(Rc::new(handler));
+
+    use_effect_with(bus.clone(), move |bus| {
+        let subscription = bus.subscribe(move |event| {
+            let handler = latest.borrow().clone();
+            if let Some(handler) = handler {
+                handler(event);
+            }
+        });
+        move || drop(subscription)
+    });
+}
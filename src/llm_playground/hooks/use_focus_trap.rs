@@ -0,0 +1,126 @@
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{Element, HtmlElement, KeyboardEvent};
+use yew::prelude::*;
+
+const FOCUSABLE_SELECTOR: &str = "a[href], button:not([disabled]), textarea:not([disabled]), \
+    input:not([disabled]), select:not([disabled]), [tabindex]:not([tabindex='-1'])";
+
+/// Trap keyboard focus inside the referenced container while `active` is true.
+///
+/// Focus moves to the first focusable element on activation, Tab/Shift+Tab wrap
+/// around inside the container, Escape emits `on_escape`, and focus returns to the
+/// previously focused element when the trap is released.
+#[hook]
+pub fn use_focus_trap(container: NodeRef, active: bool, on_escape: Callback<()>) {
+    // The listener lives as long as the trap, so it reads the newest callback
+    // rather than the one from the render that activated it
+    let latest_on_escape = use_mut_ref(|| on_escape.clone());
+    *latest_on_escape.borrow_mut() = on_escape;
+
+    use_effect_with(active, move |active| {
+        let mut listener = None;
+        let mut previous_focus = None;
+
+        if *active {
+            if let Some(element) = container.cast::<Element>() {
+                previous_focus = web_sys::window()
+                    .and_then(|w| w.document())
+                    .and_then(|d| d.active_element())
+                    .and_then(|el| el.dyn_into::<HtmlElement>().ok());
+
+                if let Some(first) = focusable_elements(&element).first() {
+                    let _ = first.focus();
+                }
+
+                let container = element.clone();
+                listener = Some(EventListener::new(&element, "keydown", move |event| {
+                    let Some(event) = event.dyn_ref::<KeyboardEvent>() else {
+                        return;
+                    };
+                    match event.key().as_str() {
+                        "Escape" => {
+                            // Keep an outer trap (e.g. a dialog opened from a modal) from closing too
+                            event.prevent_default();
+                            event.stop_propagation();
+                            let on_escape = latest_on_escape.borrow().clone();
+                            on_escape.emit(());
+                        }
+                        "Tab" => {
+                            let focusable = focusable_elements(&container);
+                            if focusable.is_empty() {
+                                event.prevent_default();
+                                return;
+                            }
+                            let active_element = web_sys::window()
+                                .and_then(|w| w.document())
+                                .and_then(|d| d.active_element());
+                            let position = focusable.iter().position(|element| {
+                                active_element.as_ref() == Some(element.as_ref())
+                            });
+
+                            let target = wrapped_focus(position, focusable.len(), event.shift_key());
+                            if let Some(index) = target {
+                                event.prevent_default();
+                                event.stop_propagation();
+                                let _ = focusable[index].focus();
+                            }
+                        }
+                        _ => {}
+                    }
+                }));
+            }
+        }
+
+        move || {
+            drop(listener);
+            if let Some(element) = previous_focus {
+                let _ = element.focus();
+            }
+        }
+    });
+}
+
+fn focusable_elements(container: &Element) -> Vec<HtmlElement> {
+    let Ok(nodes) = container.query_selector_all(FOCUSABLE_SELECTOR) else {
+        return vec![];
+    };
+    (0..nodes.length())
+        .filter_map(|i| nodes.get(i))
+        .filter_map(|node| node.dyn_into::<HtmlElement>().ok())
+        .collect()
+}
+
+/// Where Tab moves focus when it has to wrap around: from the last element to
+/// the first, or with Shift from the first to the last. None leaves Tab to the
+/// browser.
+fn wrapped_focus(position: Option<usize>, len: usize, backwards: bool) -> Option<usize> {
+    let last = len.checked_sub(1)?;
+    match position? {
+        0 if backwards => Some(last),
+        index if !backwards && index == last => Some(0),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tab_wraps_at_the_ends_of_the_container() {
+        assert_eq!(wrapped_focus(Some(2), 3, false), Some(0));
+        assert_eq!(wrapped_focus(Some(0), 3, true), Some(2));
+        assert_eq!(wrapped_focus(Some(0), 1, false), Some(0));
+        assert_eq!(wrapped_focus(Some(0), 1, true), Some(0));
+    }
+
+    #[test]
+    fn tab_inside_the_container_is_left_to_the_browser() {
+        assert_eq!(wrapped_focus(Some(1), 3, false), None);
+        assert_eq!(wrapped_focus(Some(1), 3, true), None);
+        assert_eq!(wrapped_focus(Some(0), 3, false), None);
+        assert_eq!(wrapped_focus(None, 3, false), None);
+        assert_eq!(wrapped_focus(None, 0, true), None);
+    }
+}
@@ -0,0 +1,24 @@
+use yew::prelude::*;
+
+/// A callback that stays the same across renders and forwards to `callback`
+/// from the latest one, so children given it aren't re-rendered just because
+/// the parent made a new closure. None while `callback` is None.
+#[hook]
+pub fn use_latest_callback<T>(callback: Option<Callback<T>>) -> Option<Callback<T>>
+where
+    T: 'static,
+{
+    let is_some = callback.is_some();
+    let latest = use_mut_ref(|| Option::<Callback<T>>::None);
+    *latest.borrow_mut() = callback;
+
+    let stable = use_memo((), move |_| {
+        Callback::from(move |value: T| {
+            let callback = latest.borrow().clone();
+            if let Some(callback) = callback {
+                callback.emit(value);
+            }
+        })
+    });
+    is_some.then(|| (*stable).clone())
+}
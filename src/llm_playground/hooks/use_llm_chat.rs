@@ -1,5 +1,4 @@
 // Hook for sending messages to LLM only
-use gloo_console::log;
 use gloo_timers::future::TimeoutFuture;
 use yew::prelude::*;
 
@@ -7,6 +6,7 @@ use crate::llm_playground::{
     api_clients::LLMResponse,
     components::notification::{NotificationMessage, NotificationType},
     flexible_client::FlexibleLLMClient,
+    logging,
     mcp_client::McpClient,
     FlexibleApiConfig, Message, MessageRole,
 };
@@ -62,27 +62,27 @@ pub fn use_llm_chat(
                 // Note: System prompt is now handled inside the API clients, not here
 
                 // Send single request to LLM (no loop - playground will handle function call responses)
-                log!("🚀 Sending {} messages to LLM", current_messages.len());
                 let mut retry_attempt = 0u32;
                 let max_retries = 3u32;
 
-                log!("📤 Calling LLM API with {} messages...", current_messages.len());
+                logging::info!("Calling LLM API with {} messages", current_messages.len());
                 for (i, msg) in current_messages.iter().enumerate() {
                     let role_str = match msg.role {
                         MessageRole::User => "User",
                         MessageRole::Assistant => "Assistant", 
                         MessageRole::System => "System",
+                        MessageRole::Developer => "Developer",
                         MessageRole::Function => "Function",
                     };
-                    log!("  Message {}: {} - {}", i + 1, role_str, 
-                        if msg.content.len() > 100 { &msg.content[..100] } else { &msg.content });
+                    logging::debug!("Message {}: {} - {}", i + 1, role_str,
+                        msg.content.chars().take(100).collect::<String>());
                 }
                              
                 let api_result = loop {
-                    log!("⏳ Attempting LLM API call (attempt {})...", retry_attempt + 1);
+                    logging::debug!("LLM API call attempt {}", retry_attempt + 1);
                     
                     let (provider_name, model_name) = config.get_current_provider_and_model();
-                    log!("🔍 use_llm_chat::send_message mcp_client - Provider: {}, Model: {}", &provider_name, &model_name);
+                    logging::debug!("Provider: {}, model: {}", provider_name, model_name);
                     
                     match client.send_message(&current_messages, &config).await {
                         Ok(response) => break Ok(response),
@@ -103,7 +103,7 @@ pub fn use_llm_chat(
                                 ).with_duration(delay_ms + 1000);
                                 add_notification_clone.emit(notification);
 
-                                log!(
+                                logging::warn!(
                                     "Rate limit hit, retrying in {}ms (attempt {})",
                                     delay_ms,
                                     retry_attempt
@@ -138,17 +138,18 @@ pub fn use_llm_chat(
 
                 match api_result {
                     Ok(response) => {
-                        log!("✅ LLM API response received!");
-                        log!("📊 Response details:");
-                        log!("  - Function calls: {}", response.function_calls.len());
-                        log!("  - Content length: {}", response.content.as_ref().map(|c| c.len()).unwrap_or(0));
+                        logging::info!(
+                            "LLM API response received: {} function calls, {} characters",
+                            response.function_calls.len(),
+                            response.content.as_ref().map(|c| c.len()).unwrap_or(0)
+                        );
                         
                         // Send response back to playground for handling
                         on_llm_response_clone.emit(response);
                     }
-                    Err(_error) => {
-                        // Error already handled above with notifications
-                        log!("❌ API error occurred");
+                    Err(error) => {
+                        // Already shown to the user as a notification
+                        logging::error!("LLM API call failed: {}", error);
                     }
                 }
 
@@ -0,0 +1,53 @@
+use crate::llm_playground::markdown::{content_key, parse_blocks, Block};
+use crate::llm_playground::offload::{self, OffloadJob, OffloadResult, OFFLOAD_MIN_CHARS};
+use crate::llm_playground::Message;
+use std::rc::Rc;
+use yew::prelude::*;
+
+/// Blocks of the message's content, from its cache when it was parsed before.
+/// Short content is parsed during render; long content goes to the background
+/// worker, and until its blocks arrive those shown last are returned, or None
+/// at first.
+#[hook]
+pub fn use_markdown(message: &Message) -> Option<Rc<Vec<Block>>> {
+    let force_update = use_force_update();
+    let shown = use_mut_ref(|| Option::<Rc<Vec<Block>>>::None);
+    let key = content_key(&message.content);
+    let current_key = use_mut_ref(|| key);
+    *current_key.borrow_mut() = key;
+
+    let cache = &message.markdown;
+    let blocks = cache.get(key).or_else(|| {
+        (message.content.len() < OFFLOAD_MIN_CHARS)
+            .then(|| cache.put(key, parse_blocks(&message.content)))
+    });
+    {
+        // Only cloned when the worker has to parse it
+        let pending = blocks.is_none().then(|| message.content.clone());
+        let cache = cache.clone();
+        let shown = shown.clone();
+        use_effect_with(key, move |key| {
+            if let Some(content) = pending {
+                let key = *key;
+                offload::offload(OffloadJob::ParseMarkdown(content), move |result| {
+                    // Results for text that has changed since are dropped
+                    if let OffloadResult::Markdown(blocks) = result {
+                        if *current_key.borrow() == key {
+                            *shown.borrow_mut() = Some(cache.put(key, blocks));
+                            force_update.force_update();
+                        }
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    match blocks {
+        Some(blocks) => {
+            *shown.borrow_mut() = Some(blocks.clone());
+            Some(blocks)
+        }
+        None => shown.borrow().clone(),
+    }
+}
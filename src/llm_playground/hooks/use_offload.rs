@@ -0,0 +1,36 @@
+use crate::llm_playground::offload::{self, OffloadJob, OffloadResult, OFFLOAD_MIN_CHARS};
+use std::rc::Rc;
+use yew::prelude::*;
+
+/// Result of `job`. Small jobs are run during render; large ones go to the
+/// background worker, and until their result arrives the previous result is
+/// returned, or None at first.
+#[hook]
+pub fn use_offload(job: OffloadJob) -> Option<Rc<OffloadResult>> {
+    let background = use_state(|| Option::<Rc<OffloadResult>>::None);
+    let last_inline = use_mut_ref(|| Option::<Rc<OffloadResult>>::None);
+    let inline = use_memo(job.clone(), |job| {
+        (job.input_len() < OFFLOAD_MIN_CHARS).then(|| Rc::new(job.run()))
+    });
+    {
+        let background = background.clone();
+        use_effect_with(job, move |job| {
+            if job.input_len() >= OFFLOAD_MIN_CHARS {
+                offload::offload(job.clone(), move |result| {
+                    background.set(Some(Rc::new(result)))
+                });
+            }
+            || ()
+        });
+    }
+
+    match &*inline {
+        Some(result) => {
+            *last_inline.borrow_mut() = Some(result.clone());
+            Some(result.clone())
+        }
+        None => (*background)
+            .clone()
+            .or_else(|| last_inline.borrow().clone()),
+    }
+}
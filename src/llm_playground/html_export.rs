@@ -0,0 +1,371 @@
+// Standalone HTML export of a chat session
+// Produces a self-contained, styled page with markdown pre-rendered, tool calls
+// folded into <details> blocks and API keys stripped, ready to attach to a bug
+// report or embed in a blog post.
+
+use crate::llm_playground::{ChatSession, Message, MessageRole};
+use pulldown_cmark::{html::push_html, Event, Options, Parser, Tag, TagEnd};
+use wasm_bindgen::JsCast;
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Prefixes of well-known API key formats
+const SECRET_PREFIXES: [&str; 9] = [
+    "sk-",
+    "sk_",
+    "AIza",
+    "ghp_",
+    "gho_",
+    "github_pat_",
+    "xoxb-",
+    "hf_",
+    "Bearer ",
+];
+
+/// Shortest token after a prefix that is treated as a key
+const MIN_SECRET_LEN: usize = 16;
+
+const STYLES: &str = r#"
+body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", Roboto, sans-serif; margin: 0; background: #f3f4f6; color: #1f2937; }
+main { max-width: 860px; margin: 0 auto; padding: 2rem 1rem; }
+header h1 { margin: 0 0 .25rem; font-size: 1.5rem; }
+header p { margin: 0 0 2rem; color: #6b7280; font-size: .875rem; }
+.message { background: #fff; border: 1px solid #e5e7eb; border-radius: .5rem; padding: 1rem 1.25rem; margin-bottom: 1rem; }
+.message.user { background: #eff6ff; border-color: #bfdbfe; }
+.message.system { background: #fefce8; border-color: #fde68a; }
+.message.function { background: #f0fdf4; border-color: #bbf7d0; }
+.role { font-weight: 600; font-size: .875rem; margin-bottom: .5rem; display: flex; justify-content: space-between; }
+.role time { font-weight: 400; color: #6b7280; }
+.content { line-height: 1.6; overflow-wrap: anywhere; }
+.content pre, details pre { background: #111827; color: #f9fafb; padding: .75rem 1rem; border-radius: .375rem; overflow-x: auto; font-size: .8125rem; }
+.content code { font-family: ui-monospace, SFMono-Regular, Menlo, monospace; }
+.content :not(pre) > code { background: #e5e7eb; padding: .1rem .3rem; border-radius: .25rem; }
+details { margin-top: .75rem; border: 1px solid #fed7aa; background: #fff7ed; border-radius: .375rem; padding: .5rem .75rem; }
+details.response { border-color: #bbf7d0; background: #f0fdf4; }
+summary { cursor: pointer; font-weight: 600; font-size: .875rem; }
+footer { text-align: center; color: #9ca3af; font-size: .75rem; margin-top: 2rem; }
+@media (prefers-color-scheme: dark) {
+  body { background: #111827; color: #e5e7eb; }
+  .message { background: #1f2937; border-color: #374151; }
+  .message.user { background: #1e3a8a33; border-color: #1e40af; }
+  .message.system { background: #713f1233; border-color: #a16207; }
+  .message.function { background: #14532d33; border-color: #15803d; }
+  .content :not(pre) > code { background: #374151; }
+  details { background: #7c2d1233; border-color: #c2410c; }
+  details.response { background: #14532d33; border-color: #15803d; }
+}
+"#;
+
+/// Render a session as a standalone HTML document.
+///
+/// `secrets` holds known values (e.g. configured API keys) that must never
+/// appear in the output; well-known key formats are stripped as well.
+pub fn render_session_html(session: &ChatSession, secrets: &[String]) -> String {
+    let title = escape_html(&strip_secrets(&session.title, secrets));
+    let mut body = String::new();
+
+    for message in &session.messages {
+        render_message(&mut body, message, secrets);
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"UTF-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">\n\
+         <title>{title}</title>\n<style>{STYLES}</style>\n</head>\n<body>\n<main>\n\
+         <header><h1>{title}</h1><p>{count} messages · exported {exported}</p></header>\n\
+         {body}<footer>Exported from LLM Playground</footer>\n</main>\n</body>\n</html>\n",
+        title = title,
+        count = session.messages.len(),
+        exported = format_date(js_sys::Date::now()),
+        body = body,
+    )
+}
+
+fn render_message(out: &mut String, message: &Message, secrets: &[String]) {
+    let (class, label) = match message.role {
+        MessageRole::System => ("system", "System"),
+        MessageRole::Developer => ("system", "Developer"),
+        MessageRole::User => ("user", "You"),
+        MessageRole::Assistant => ("assistant", "Assistant"),
+        MessageRole::Function => ("function", "Function"),
+    };
+
+    out.push_str(&format!(
+        "<section class=\"message {}\">\n<div class=\"role\"><span>{}</span><time>{}</time></div>\n",
+        class,
+        label,
+        format_date(message.timestamp)
+    ));
+
+    if let Some(reasoning) = message.reasoning.as_deref() {
+        out.push_str(&format!(
+            "<details class=\"reasoning\"><summary>Thinking</summary><pre>{}</pre></details>\n",
+            escape_html(&strip_secrets(reasoning, secrets))
+        ));
+    }
+
+    if !message.content.trim().is_empty() {
+        out.push_str("<div class=\"content\">");
+        out.push_str(&render_markdown(&strip_secrets(&message.content, secrets)));
+        out.push_str("</div>\n");
+    }
+
+    if !message.citations.is_empty() {
+        out.push_str("<ol class=\"sources\">");
+        for url in &message.citations {
+            let escaped = escape_html(url);
+            if url.starts_with("https://") || url.starts_with("http://") {
+                out.push_str(&format!("<li><a href=\"{}\">{}</a></li>", escaped, escaped));
+            } else {
+                out.push_str(&format!("<li>{}</li>", escaped));
+            }
+        }
+        out.push_str("</ol>\n");
+    }
+
+    if let Some(function_call) = &message.function_call {
+        let calls = match function_call.as_array() {
+            Some(calls) => calls.clone(),
+            None => vec![function_call.clone()],
+        };
+        for call in &calls {
+            let name = call
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or("unknown");
+            let arguments = call.get("arguments").cloned().unwrap_or_default();
+            push_details(
+                out,
+                "call",
+                &format!("🔧 Tool call: {}", name),
+                &arguments,
+                secrets,
+            );
+        }
+    }
+
+    if let Some(function_response) = &message.function_response {
+        let name = function_response
+            .get("name")
+            .and_then(|n| n.as_str())
+            .unwrap_or("unknown");
+        let response = function_response
+            .get("response")
+            .unwrap_or(function_response);
+        push_details(
+            out,
+            "response",
+            &format!("↩ Tool result: {}", name),
+            response,
+            secrets,
+        );
+    }
+
+    out.push_str("</section>\n");
+}
+
+fn push_details(
+    out: &mut String,
+    class: &str,
+    summary: &str,
+    value: &serde_json::Value,
+    secrets: &[String],
+) {
+    let json = serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string());
+    out.push_str(&format!(
+        "<details class=\"{}\"><summary>{}</summary><pre><code>{}</code></pre></details>\n",
+        class,
+        escape_html(summary),
+        escape_html(&strip_secrets(&json, secrets))
+    ));
+}
+
+/// Markdown to HTML; raw HTML in the source is shown as text rather than
+/// injected, and links and images to anything but `is_safe_url` are left out,
+/// keeping their text
+fn render_markdown(content: &str) -> String {
+    // Whether each open link or image was kept, so its end matches
+    let mut kept = Vec::new();
+    let parser = Parser::new_ext(
+        content,
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+    )
+    .filter_map(move |event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Some(Event::Text(html)),
+        Event::Start(Tag::Link { ref dest_url, .. }) | Event::Start(Tag::Image { ref dest_url, .. }) => {
+            let safe = is_safe_url(dest_url);
+            kept.push(safe);
+            safe.then_some(event)
+        }
+        Event::End(TagEnd::Link | TagEnd::Image) => kept.pop().unwrap_or(true).then_some(event),
+        other => Some(other),
+    });
+    let mut html = String::new();
+    push_html(&mut html, parser);
+    html
+}
+
+/// Whether an exported link or image may point at `url`: http, https, mailto
+/// or a relative URL. Other schemes such as `javascript:` run in the page.
+fn is_safe_url(url: &str) -> bool {
+    match url.find([':', '/', '?', '#']) {
+        Some(index) if url[index..].starts_with(':') => {
+            let scheme = &url[..index];
+            ["http", "https", "mailto"]
+                .iter()
+                .any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+        }
+        _ => true,
+    }
+}
+
+/// Remove known secrets and anything that looks like an API key
+pub fn strip_secrets(text: &str, secrets: &[String]) -> String {
+    let mut result = text.to_string();
+    for secret in secrets.iter().map(|s| s.trim()).filter(|s| s.len() >= 8) {
+        result = result.replace(secret, REDACTED);
+    }
+
+    for prefix in SECRET_PREFIXES {
+        let mut scrubbed = String::with_capacity(result.len());
+        let mut rest = result.as_str();
+        while let Some(pos) = rest.find(prefix) {
+            let after = &rest[pos + prefix.len()..];
+            let key_len = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'))
+                .unwrap_or(after.len());
+            scrubbed.push_str(&rest[..pos]);
+            if key_len >= MIN_SECRET_LEN {
+                scrubbed.push_str(REDACTED);
+            } else {
+                scrubbed.push_str(&rest[pos..pos + prefix.len() + key_len]);
+            }
+            rest = &after[key_len..];
+        }
+        scrubbed.push_str(rest);
+        result = scrubbed;
+    }
+    result
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn format_date(timestamp: f64) -> String {
+    let date = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(timestamp));
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02}",
+        date.get_full_year(),
+        date.get_month() + 1,
+        date.get_date(),
+        date.get_hours(),
+        date.get_minutes()
+    )
+}
+
+/// File name for an exported session, derived from its title
+pub fn export_file_name(session: &ChatSession) -> String {
+    let slug: String = session
+        .title
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "conversation.html".to_string()
+    } else {
+        format!("{}.html", slug)
+    }
+}
+
+/// Offer `content` to the user as a file download
+pub fn download_file(file_name: &str, content: &str, mime_type: &str) -> Result<(), String> {
+    let blob = gloo::file::Blob::new_with_options(content, Some(mime_type));
+    let url = gloo::file::ObjectUrl::from(blob);
+
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("No document available")?;
+    let anchor = document
+        .create_element("a")
+        .map_err(|e| format!("Failed to create link: {:?}", e))?;
+    anchor
+        .set_attribute("href", &url)
+        .and_then(|_| anchor.set_attribute("download", file_name))
+        .map_err(|e| format!("Failed to prepare download: {:?}", e))?;
+    anchor
+        .dyn_into::<web_sys::HtmlElement>()
+        .map_err(|_| "Download link is not an HTML element".to_string())?
+        .click();
+
+    // Revoking the URL right away can cancel the download in some browsers
+    gloo_timers::callback::Timeout::new(1_000, move || drop(url)).forget();
+    Ok(())
+}
+
+/// Put `text` on the system clipboard
+pub async fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    let window = web_sys::window().ok_or("No window available")?;
+    let promise = window.navigator().clipboard().write_text(text);
+    wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Copy failed: {:?}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_secrets() {
+        let secrets = vec!["my-custom-secret-value".to_string()];
+        let text = "key sk-abcdefghijklmnopqrstuvwx and AIzaSyA1234567890abcdefghij, \
+                    custom my-custom-secret-value, short sk-abc stays";
+        let stripped = strip_secrets(text, &secrets);
+
+        assert!(!stripped.contains("sk-abcdefghijklmnopqrstuvwx"));
+        assert!(!stripped.contains("AIzaSyA1234567890abcdefghij"));
+        assert!(!stripped.contains("my-custom-secret-value"));
+        assert!(stripped.contains("short sk-abc stays"));
+        assert_eq!(stripped.matches(REDACTED).count(), 3);
+    }
+
+    #[test]
+    fn test_render_markdown_escapes_raw_html() {
+        let html = render_markdown("**bold** <script>alert(1)</script>");
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(!html.contains("<script>"));
+    }
+
+    #[test]
+    fn test_render_markdown_drops_unsafe_urls() {
+        let html = render_markdown(
+            "[docs](https://example.com) [mail](mailto:a@b.c) [up](../notes.md) \
+             [x](javascript:alert(1)) [y](JavaScript&#58;alert(1)) ![img](data:text/html,hi) \
+             <java\tscript:alert(1)>",
+        );
+        assert!(html.contains("<a href=\"https://example.com\">docs</a>"));
+        assert!(html.contains("<a href=\"mailto:a@b.c\">mail</a>"));
+        assert!(html.contains("<a href=\"../notes.md\">up</a>"));
+        assert!(!html.to_lowercase().contains("javascript"), "{}", html);
+        assert!(!html.contains("data:"));
+        assert!(html.contains("x") && html.contains("img"));
+        assert!(is_safe_url("/a:b"));
+        assert!(!is_safe_url("vbscript:msgbox"));
+    }
+}
@@ -0,0 +1,175 @@
+// Image generation and editing
+// Image models answer with pictures: Gemini's image models return them as
+// inlineData parts next to the text, and OpenAI's gpt-image and DALL·E models
+// are called through the Images API instead of chat completions. Any image
+// generated in a session can be picked from its image strip and sent back
+// with the next prompt to be edited, optionally with a mask marking the area
+// to change, so a picture can be refined over several turns.
+
+use crate::llm_playground::Message;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// Largest mask accepted; OpenAI takes PNGs of at most 4 MB
+pub const MAX_MASK_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GeneratedImage {
+    pub id: String,
+    pub mime_type: String,
+    /// The image, base64-encoded
+    pub data: String,
+    /// Prompt the image was made from
+    #[serde(default)]
+    pub prompt: String,
+    /// Image this one is an edit of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edited_from: Option<String>,
+}
+
+impl GeneratedImage {
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime_type, self.data)
+    }
+
+    /// File name to download the image as
+    pub fn file_name(&self) -> String {
+        let extension = self.mime_type.rsplit('/').next().unwrap_or("png");
+        format!("{}.{}", self.id, extension.replace("jpeg", "jpg"))
+    }
+}
+
+/// An earlier image sent back with a user message to be edited
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImageEdit {
+    pub image: GeneratedImage,
+    /// PNG, base64-encoded, whose transparent pixels mark the area to change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask: Option<String>,
+}
+
+/// Said alongside the mask to models that take it as a second image
+pub const MASK_INSTRUCTION: &str =
+    "The second image is a mask: change only the area that is transparent in it and keep the rest of the first image as it is.";
+
+/// Whether a model generates images; `gemini` is for models behind the
+/// Gemini API, the rest are called with the OpenAI format
+pub fn is_image_model(gemini: bool, model: &str) -> bool {
+    let model = model.rsplit('/').next().unwrap_or(model).to_lowercase();
+    if gemini {
+        model.starts_with("gemini-") && model.contains("image")
+    } else {
+        model.starts_with("gpt-image") || model.starts_with("dall-e")
+    }
+}
+
+/// Every image generated in the session, oldest first
+pub fn session_images(messages: &[Message]) -> Vec<&GeneratedImage> {
+    messages.iter().flat_map(|message| &message.images).collect()
+}
+
+/// Text of a reply that came back as images only
+pub fn caption(images: &[GeneratedImage]) -> String {
+    match images {
+        [image] if image.edited_from.is_some() => "Edited image".to_string(),
+        [_] => "Generated image".to_string(),
+        images => format!("Generated {} images", images.len()),
+    }
+}
+
+/// Body of an OpenAI Images API generation request
+pub fn openai_generation_body(model: &str, prompt: &str) -> Value {
+    let mut body = json!({ "model": model, "prompt": prompt, "n": 1 });
+    // gpt-image models always answer with base64 and reject the parameter
+    if model.starts_with("dall-e") {
+        body["response_format"] = json!("b64_json");
+    }
+    body
+}
+
+/// Images in an OpenAI Images API response; ids start with `id_prefix`
+pub fn parse_openai_images(
+    response: &Value,
+    prompt: &str,
+    edited_from: Option<&str>,
+    id_prefix: &str,
+) -> Result<Vec<GeneratedImage>, String> {
+    if let Some(message) = response["error"]["message"].as_str() {
+        return Err(format!("OpenAI API error: {}", message));
+    }
+    let mime_type = format!(
+        "image/{}",
+        response["output_format"].as_str().unwrap_or("png").replace("jpg", "jpeg")
+    );
+    let images: Vec<GeneratedImage> = response["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| item["b64_json"].as_str())
+        .enumerate()
+        .map(|(index, data)| GeneratedImage {
+            id: format!("{}_{}", id_prefix, index),
+            mime_type: mime_type.clone(),
+            data: data.to_string(),
+            prompt: prompt.to_string(),
+            edited_from: edited_from.map(str::to_string),
+        })
+        .collect();
+    if images.is_empty() {
+        return Err("No image in the OpenAI response".to_string());
+    }
+    Ok(images)
+}
+
+/// Read a picked mask PNG
+pub async fn read_mask(file: &web_sys::File) -> Result<String, String> {
+    let name = file.name();
+    if file.type_() != "image/png" && !name.to_lowercase().ends_with(".png") {
+        return Err(format!("{} is not a PNG; masks must be PNGs", name));
+    }
+    if file.size() as usize > MAX_MASK_BYTES {
+        return Err(format!("{} is larger than 4 MB", name));
+    }
+    let buffer = wasm_bindgen_futures::JsFuture::from(file.array_buffer())
+        .await
+        .map_err(|e| format!("Couldn't read {}: {:?}", name, e))?;
+    let bytes = js_sys::Uint8Array::new(&buffer).to_vec();
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// Decoded bytes of a base64 image, for multipart uploads
+pub fn decode(data: &str) -> Result<Vec<u8>, String> {
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| format!("Invalid image data: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_models_by_name() {
+        assert!(is_image_model(true, "gemini-2.5-flash-image-preview"));
+        assert!(is_image_model(true, "gemini-2.0-flash-preview-image-generation"));
+        assert!(!is_image_model(true, "gemini-2.5-flash"));
+        assert!(is_image_model(false, "gpt-image-1"));
+        assert!(is_image_model(false, "openai/dall-e-3"));
+        assert!(!is_image_model(false, "gpt-4o"));
+    }
+
+    #[test]
+    fn reads_openai_images() {
+        let response = json!({ "output_format": "webp", "data": [{ "b64_json": "AAAA" }] });
+        let images = parse_openai_images(&response, "a red fox", Some("img_1_0"), "img_2").unwrap();
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].data_url(), "data:image/webp;base64,AAAA");
+        assert_eq!(images[0].edited_from.as_deref(), Some("img_1_0"));
+        assert_eq!(caption(&images), "Edited image");
+
+        assert!(openai_generation_body("dall-e-3", "fox").get("response_format").is_some());
+        assert!(openai_generation_body("gpt-image-1", "fox").get("response_format").is_none());
+        assert!(parse_openai_images(&json!({ "data": [] }), "fox", None, "img").is_err());
+    }
+}
@@ -0,0 +1,167 @@
+// Invalid-JSON auto-repair
+// A reply that must follow the active structured output, or a tool call whose
+// arguments don't parse or don't match the tool's parameters, is sent back to
+// the model with the reason it was rejected. The correction turns only live in
+// the request; the session keeps the final reply.
+
+use crate::llm_playground::{
+    api_clients::LLMResponse, eval_suite::validate_json_schema, provider_config::FlexibleApiConfig,
+    structured_output::validate_reply, Message, MessageRole,
+};
+use serde_json::Value;
+
+/// Why a response was rejected, and the output to show the model
+#[derive(Clone, Debug, PartialEq)]
+pub struct RepairIssue {
+    pub invalid_output: String,
+    pub error: String,
+}
+
+/// The first problem with a response: invalid arguments of a tool call, or a
+/// text reply that doesn't follow the active structured output
+pub fn find_issue(response: &LLMResponse, config: &FlexibleApiConfig) -> Option<RepairIssue> {
+    if !response.function_calls.is_empty() {
+        return response.function_calls.iter().find_map(|call| {
+            let error = match &call.arguments {
+                // Clients keep unparseable arguments as the raw string
+                Value::String(_) => "the arguments are not valid JSON".to_string(),
+                arguments => {
+                    let tool = config
+                        .function_tools
+                        .iter()
+                        .find(|tool| tool.enabled && tool.name == call.name)?;
+                    let error = validate_json_schema(arguments, &tool.parameters, "$").err()?;
+                    format!("the arguments don't match the tool's parameters: {}", error)
+                }
+            };
+            let arguments = match &call.arguments {
+                Value::String(raw) => raw.clone(),
+                arguments => arguments.to_string(),
+            };
+            Some(RepairIssue {
+                invalid_output: format!("Call to `{}` with arguments: {}", call.name, arguments),
+                error: format!("in the call to `{}`, {}", call.name, error),
+            })
+        });
+    }
+
+    let output = config.active_structured_output()?;
+    let content = response.content.as_deref().unwrap_or_default();
+    validate_reply(content, &output.schema)
+        .err()
+        .map(|error| RepairIssue {
+            invalid_output: content.to_string(),
+            error,
+        })
+}
+
+/// The rejected output followed by a user turn asking for a fixed one
+pub fn correction_turn(issue: &RepairIssue) -> [Message; 2] {
+    let now = js_sys::Date::now();
+    let message = |id: String, role: MessageRole, content: String| Message {
+        id,
+        timestamp: now,
+        ..Message::new(role, content)
+    };
+    [
+        message(
+            format!("repair_output_{}", now as u64),
+            MessageRole::Assistant,
+            issue.invalid_output.clone(),
+        ),
+        message(
+            format!("repair_request_{}", now as u64),
+            MessageRole::User,
+            format!(
+                "Your output was invalid because {}. Please fix it and reply again, following the required format exactly.",
+                issue.error
+            ),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::{api_clients::FunctionCallRequest, FunctionTool, StructuredOutput};
+    use serde_json::json;
+
+    fn response(content: &str, calls: Vec<FunctionCallRequest>) -> LLMResponse {
+        LLMResponse {
+            content: Some(content.to_string()),
+            function_calls: calls,
+            finish_reason: None,
+            reasoning: None,
+            citations: Vec::new(),
+            alternatives: Vec::new(),
+            native_tool_calls: Vec::new(),
+            audio: None,
+            images: Vec::new(),
+            usage: None,
+            timing: None,
+        }
+    }
+
+    fn call(arguments: Value) -> FunctionCallRequest {
+        FunctionCallRequest {
+            id: "call_1".to_string(),
+            name: "get_weather".to_string(),
+            arguments,
+        }
+    }
+
+    #[test]
+    fn finds_invalid_tool_arguments() {
+        let mut config = FlexibleApiConfig::default();
+        config.function_tools = vec![FunctionTool {
+            name: "get_weather".to_string(),
+            description: String::new(),
+            parameters: json!({
+                "type": "object",
+                "properties": { "city": { "type": "string" } },
+                "required": ["city"]
+            }),
+            mock_response: String::new(),
+            enabled: true,
+            category: String::new(),
+            is_builtin: false,
+            requires: None,
+        }];
+
+        assert_eq!(
+            find_issue(
+                &response("", vec![call(json!({ "city": "Oslo" }))]),
+                &config
+            ),
+            None
+        );
+        let missing = find_issue(&response("", vec![call(json!({}))]), &config).unwrap();
+        assert!(missing.error.contains("get_weather"));
+        assert!(missing.error.contains("city"));
+        let raw = find_issue(&response("", vec![call(json!("{city: Oslo"))]), &config).unwrap();
+        assert!(raw.error.contains("not valid JSON"));
+        assert!(raw.invalid_output.ends_with("{city: Oslo"));
+    }
+
+    #[test]
+    fn checks_text_replies_only_with_an_active_schema() {
+        let mut config = FlexibleApiConfig::default();
+        config.structured_outputs = vec![StructuredOutput {
+            name: "score".to_string(),
+            schema: json!({ "type": "object", "required": ["score"] }),
+        }];
+        assert_eq!(
+            find_issue(&response("plain text", Vec::new()), &config),
+            None
+        );
+
+        config.active_structured_output = Some("score".to_string());
+        assert_eq!(
+            find_issue(&response("{\"score\": 1}", Vec::new()), &config),
+            None
+        );
+        let issue = find_issue(&response("plain text", Vec::new()), &config).unwrap();
+        assert_eq!(issue.invalid_output, "plain text");
+        assert!(issue.error.starts_with("Reply is not valid JSON"));
+    }
+}
@@ -0,0 +1,244 @@
+// Knowledge base for retrieval-augmented chat
+// Uploaded text documents are split into overlapping chunks, embedded with the
+// configured embeddings model and kept in IndexedDB. The `search_documents` tool
+// embeds the model's query and returns the most similar chunks.
+
+use crate::llm_playground::{
+    api_clients::embeddings::{cosine_similarity, create_embeddings_client, embed_all},
+    knowledge_store,
+    provider_config::FlexibleApiConfig,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const SEARCH_DOCUMENTS_TOOL_NAME: &str = "search_documents";
+
+/// Target chunk size in characters
+pub const CHUNK_SIZE: usize = 1200;
+/// Characters shared between neighbouring chunks so a sentence cut at a chunk
+/// boundary is still found whole in one of them
+pub const CHUNK_OVERLAP: usize = 200;
+pub const DEFAULT_TOP_K: usize = 4;
+pub const MAX_TOP_K: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KnowledgeDocument {
+    pub id: String,
+    pub name: String,
+    /// Size of the uploaded text in characters
+    pub size: usize,
+    pub chunk_count: usize,
+    pub created_at: f64,
+    /// "provider,model" the chunks were embedded with
+    pub embedding_model: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KnowledgeChunk {
+    pub document_id: String,
+    pub document_name: String,
+    pub index: usize,
+    pub text: String,
+    pub embedding: Vec<f32>,
+}
+
+/// Split text into chunks of about `max_chars` characters, breaking at paragraph,
+/// line or word boundaries where possible, with `overlap` characters repeated
+/// between consecutive chunks
+pub fn chunk_text(text: &str, max_chars: usize, overlap: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let overlap = overlap.min(max_chars / 4);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < chars.len() {
+        let mut end = (start + max_chars).min(chars.len());
+        if end < chars.len() {
+            // Prefer the last paragraph break, then line break, then space in the
+            // second half of the window
+            let window = &chars[start + max_chars / 2..end];
+            let last = |pattern: &dyn Fn(&[char]) -> bool| {
+                (1..window.len())
+                    .rev()
+                    .find(|&i| pattern(&window[i - 1..=i]))
+                    .map(|i| start + max_chars / 2 + i + 1)
+            };
+            end = last(&|pair| pair == ['\n', '\n'])
+                .or_else(|| last(&|pair| pair[1] == '\n'))
+                .or_else(|| last(&|pair| pair[1].is_whitespace()))
+                .unwrap_or(end);
+        }
+
+        let chunk: String = chars[start..end].iter().collect();
+        if !chunk.trim().is_empty() {
+            chunks.push(chunk.trim().to_string());
+        }
+        if end >= chars.len() {
+            break;
+        }
+
+        // Start the next chunk `overlap` characters back, moved back to the start
+        // of the word it lands in
+        let target = end.saturating_sub(overlap).max(start + 1);
+        start = (start + 1..=target)
+            .rev()
+            .take(overlap + 1)
+            .find(|&i| chars[i - 1].is_whitespace())
+            .unwrap_or(target);
+    }
+    chunks
+}
+
+/// The `k` chunks most similar to the query vector, best first. Chunks embedded
+/// with a model of a different dimension are skipped.
+pub fn top_k<'a>(
+    query: &[f32],
+    chunks: &'a [KnowledgeChunk],
+    k: usize,
+) -> Vec<(f32, &'a KnowledgeChunk)> {
+    let mut scored: Vec<(f32, &KnowledgeChunk)> = chunks
+        .iter()
+        .filter(|chunk| chunk.embedding.len() == query.len())
+        .map(|chunk| (cosine_similarity(query, &chunk.embedding), chunk))
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored.truncate(k);
+    scored
+}
+
+/// Embed texts with the configured embeddings model
+pub async fn embed_texts(
+    config: &FlexibleApiConfig,
+    texts: &[String],
+) -> Result<Vec<Vec<f32>>, String> {
+    let client = create_embeddings_client(config)?;
+    embed_all(client.as_ref(), texts).await
+}
+
+/// Chunk, embed and store an uploaded document
+pub async fn index_document(
+    config: &FlexibleApiConfig,
+    name: &str,
+    text: &str,
+) -> Result<KnowledgeDocument, String> {
+    let texts = chunk_text(text, CHUNK_SIZE, CHUNK_OVERLAP);
+    if texts.is_empty() {
+        return Err(format!("{} has no text to index", name));
+    }
+    let embeddings = embed_texts(config, &texts).await?;
+
+    let document = KnowledgeDocument {
+        id: format!("doc_{}", js_sys::Date::now() as u64),
+        name: name.to_string(),
+        size: text.chars().count(),
+        chunk_count: texts.len(),
+        created_at: js_sys::Date::now(),
+        embedding_model: config.embedding_model.clone().unwrap_or_default(),
+    };
+    let chunks: Vec<KnowledgeChunk> = texts
+        .into_iter()
+        .zip(embeddings)
+        .enumerate()
+        .map(|(index, (text, embedding))| KnowledgeChunk {
+            document_id: document.id.clone(),
+            document_name: document.name.clone(),
+            index,
+            text,
+            embedding,
+        })
+        .collect();
+    knowledge_store::put_document(&document, &chunks).await?;
+    Ok(document)
+}
+
+/// Run the `search_documents` tool: embed the query and return the top-k chunks
+pub async fn search_documents(
+    config: &FlexibleApiConfig,
+    arguments: &Value,
+) -> Result<Value, String> {
+    let query = arguments
+        .get("query")
+        .and_then(|q| q.as_str())
+        .filter(|q| !q.trim().is_empty())
+        .ok_or("Missing required parameter: query")?;
+    let k = arguments
+        .get("top_k")
+        .and_then(|k| k.as_u64())
+        .map_or(DEFAULT_TOP_K, |k| (k as usize).clamp(1, MAX_TOP_K));
+
+    let chunks = knowledge_store::all_chunks().await?;
+    if chunks.is_empty() {
+        return Err("The knowledge base is empty. Ask the user to upload documents.".to_string());
+    }
+    let query_embedding = embed_texts(config, &[query.to_string()])
+        .await?
+        .pop()
+        .unwrap_or_default();
+
+    let results: Vec<Value> = top_k(&query_embedding, &chunks, k)
+        .into_iter()
+        .map(|(score, chunk)| {
+            serde_json::json!({
+                "document": chunk.document_name,
+                "chunk": chunk.index,
+                "score": (score * 1000.0).round() / 1000.0,
+                "text": chunk.text,
+            })
+        })
+        .collect();
+    if results.is_empty() {
+        return Err(
+            "No documents were embedded with the current embeddings model. Re-upload them."
+                .to_string(),
+        );
+    }
+    Ok(serde_json::json!({ "query": query, "results": results }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_overlap_at_word_boundaries() {
+        let text = "alpha beta gamma delta epsilon zeta eta theta iota kappa";
+        let chunks = chunk_text(text, 20, 6);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 20));
+        // Every word survives whole in some chunk
+        for word in text.split(' ') {
+            assert!(chunks.iter().any(|c| c.split(' ').any(|w| w == word)));
+        }
+        // Neighbouring chunks share text
+        assert!(chunks.windows(2).all(|pair| pair[1]
+            .split(' ')
+            .next()
+            .is_some_and(|w| pair[0].contains(w))));
+        assert_eq!(chunk_text("short", 20, 6), ["short"]);
+        assert!(chunk_text("  \n ", 20, 6).is_empty());
+    }
+
+    #[test]
+    fn ranks_chunks_by_cosine_similarity() {
+        let chunk = |index: usize, embedding: Vec<f32>| KnowledgeChunk {
+            document_id: "d".to_string(),
+            document_name: "doc".to_string(),
+            index,
+            text: String::new(),
+            embedding,
+        };
+        let chunks = vec![
+            chunk(0, vec![0.0, 1.0]),
+            chunk(1, vec![1.0, 0.1]),
+            chunk(2, vec![1.0, 0.0, 0.0]),
+            chunk(3, vec![0.7, 0.7]),
+        ];
+        let ranked: Vec<usize> = top_k(&[1.0, 0.0], &chunks, 2)
+            .into_iter()
+            .map(|(_, c)| c.index)
+            .collect();
+        assert_eq!(ranked, [1, 3]);
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+}
@@ -0,0 +1,213 @@
+// IndexedDB storage for the knowledge base
+// Document metadata and chunk vectors are too large for LocalStorage, so they live
+// in an IndexedDB database. Records are stored as JSON strings: "documents" holds
+// one KnowledgeDocument per document id, "chunks" holds the document's
+// Vec<KnowledgeChunk> under the same id so a document is deleted with one key.
+// "message_embeddings" caches chat message vectors for semantic session search.
+
+use crate::llm_playground::knowledge::{KnowledgeChunk, KnowledgeDocument};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbOpenDbRequest, IdbRequest, IdbTransactionMode};
+
+const DB_NAME: &str = "llm_playground_knowledge";
+const DB_VERSION: u32 = 2;
+const DOCUMENTS_STORE: &str = "documents";
+const CHUNKS_STORE: &str = "chunks";
+const MESSAGE_EMBEDDINGS_STORE: &str = "message_embeddings";
+
+/// Cached embedding of one chat message
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MessageEmbedding {
+    pub message_id: String,
+    /// "provider,model" the vector was made with
+    pub model: String,
+    pub embedding: Vec<f32>,
+}
+
+/// Store a document and its chunks, replacing any previous version
+pub async fn put_document(
+    document: &KnowledgeDocument,
+    chunks: &[KnowledgeChunk],
+) -> Result<(), String> {
+    let db = open().await?;
+    let result = async {
+        let stores = js_sys::Array::of2(&DOCUMENTS_STORE.into(), &CHUNKS_STORE.into());
+        let transaction = db
+            .transaction_with_str_sequence_and_mode(&stores, IdbTransactionMode::Readwrite)
+            .map_err(|e| format!("Failed to start transaction: {:?}", e))?;
+        let key = JsValue::from_str(&document.id);
+        // Issue both writes before awaiting so the transaction stays active
+        let document_request = store(&transaction, DOCUMENTS_STORE)?
+            .put_with_key(&to_js(document)?, &key)
+            .map_err(|e| format!("Failed to store document: {:?}", e))?;
+        let chunks_request = store(&transaction, CHUNKS_STORE)?
+            .put_with_key(&to_js(chunks)?, &key)
+            .map_err(|e| format!("Failed to store chunks: {:?}", e))?;
+        request_result(&document_request).await?;
+        request_result(&chunks_request).await?;
+        Ok(())
+    }
+    .await;
+    db.close();
+    result
+}
+
+/// All documents in the knowledge base, newest first
+pub async fn list_documents() -> Result<Vec<KnowledgeDocument>, String> {
+    let mut documents: Vec<KnowledgeDocument> = get_all(DOCUMENTS_STORE).await?;
+    documents.sort_by(|a, b| b.created_at.total_cmp(&a.created_at));
+    Ok(documents)
+}
+
+/// Every stored chunk across all documents
+pub async fn all_chunks() -> Result<Vec<KnowledgeChunk>, String> {
+    let chunks: Vec<Vec<KnowledgeChunk>> = get_all(CHUNKS_STORE).await?;
+    Ok(chunks.into_iter().flatten().collect())
+}
+
+/// Remove a document and its chunks
+pub async fn delete_document(document_id: &str) -> Result<(), String> {
+    let db = open().await?;
+    let result = async {
+        let stores = js_sys::Array::of2(&DOCUMENTS_STORE.into(), &CHUNKS_STORE.into());
+        let transaction = db
+            .transaction_with_str_sequence_and_mode(&stores, IdbTransactionMode::Readwrite)
+            .map_err(|e| format!("Failed to start transaction: {:?}", e))?;
+        let key = JsValue::from_str(document_id);
+        let requests = [DOCUMENTS_STORE, CHUNKS_STORE]
+            .into_iter()
+            .map(|name| {
+                store(&transaction, name)?
+                    .delete(&key)
+                    .map_err(|e| format!("Failed to delete from {}: {:?}", name, e))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        for request in &requests {
+            request_result(request).await?;
+        }
+        Ok(())
+    }
+    .await;
+    db.close();
+    result
+}
+
+/// Cached message embeddings made with the given model
+pub async fn message_embeddings(model: &str) -> Result<Vec<MessageEmbedding>, String> {
+    let embeddings: Vec<MessageEmbedding> = get_all(MESSAGE_EMBEDDINGS_STORE).await?;
+    Ok(embeddings
+        .into_iter()
+        .filter(|embedding| embedding.model == model)
+        .collect())
+}
+
+/// Add message embeddings to the cache
+pub async fn put_message_embeddings(embeddings: &[MessageEmbedding]) -> Result<(), String> {
+    let db = open().await?;
+    let result = async {
+        let transaction = db
+            .transaction_with_str_and_mode(MESSAGE_EMBEDDINGS_STORE, IdbTransactionMode::Readwrite)
+            .map_err(|e| format!("Failed to start transaction: {:?}", e))?;
+        let store = store(&transaction, MESSAGE_EMBEDDINGS_STORE)?;
+        let requests = embeddings
+            .iter()
+            .map(|embedding| {
+                let key =
+                    JsValue::from_str(&format!("{}|{}", embedding.model, embedding.message_id));
+                store
+                    .put_with_key(&to_js(embedding)?, &key)
+                    .map_err(|e| format!("Failed to store message embedding: {:?}", e))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        for request in &requests {
+            request_result(request).await?;
+        }
+        Ok(())
+    }
+    .await;
+    db.close();
+    result
+}
+
+async fn get_all<T: DeserializeOwned>(store_name: &str) -> Result<Vec<T>, String> {
+    let db = open().await?;
+    let result = async {
+        let transaction = db
+            .transaction_with_str(store_name)
+            .map_err(|e| format!("Failed to start transaction: {:?}", e))?;
+        let request = store(&transaction, store_name)?
+            .get_all()
+            .map_err(|e| format!("Failed to read {}: {:?}", store_name, e))?;
+        let values: js_sys::Array = request_result(&request).await?.unchecked_into();
+        values
+            .iter()
+            .filter_map(|value| value.as_string())
+            .map(|json| {
+                serde_json::from_str(&json)
+                    .map_err(|e| format!("Corrupted record in {}: {}", store_name, e))
+            })
+            .collect()
+    }
+    .await;
+    db.close();
+    result
+}
+
+async fn open() -> Result<IdbDatabase, String> {
+    let factory = web_sys::window()
+        .and_then(|window| window.indexed_db().ok().flatten())
+        .ok_or("IndexedDB is not available in this browser")?;
+    let request: IdbOpenDbRequest = factory
+        .open_with_u32(DB_NAME, DB_VERSION)
+        .map_err(|e| format!("Failed to open knowledge database: {:?}", e))?;
+
+    let upgrade_request = request.clone();
+    let on_upgrade = Closure::once_into_js(move || {
+        if let Ok(db) = upgrade_request.result() {
+            let db: IdbDatabase = db.unchecked_into();
+            // Stores from an earlier version already exist and fail to create
+            for name in [DOCUMENTS_STORE, CHUNKS_STORE, MESSAGE_EMBEDDINGS_STORE] {
+                let _ = db.create_object_store(name);
+            }
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+    Ok(request_result(&request).await?.unchecked_into())
+}
+
+fn store(
+    transaction: &web_sys::IdbTransaction,
+    name: &str,
+) -> Result<web_sys::IdbObjectStore, String> {
+    transaction
+        .object_store(name)
+        .map_err(|e| format!("Missing object store {}: {:?}", name, e))
+}
+
+/// Wait for an IndexedDB request to finish and return its result
+async fn request_result(request: &IdbRequest) -> Result<JsValue, String> {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let success_request = request.clone();
+        let on_success = Closure::once_into_js(move || {
+            let result = success_request.result().unwrap_or(JsValue::UNDEFINED);
+            let _ = resolve.call1(&JsValue::UNDEFINED, &result);
+        });
+        let on_error = Closure::once_into_js(move || {
+            let _ = reject.call1(&JsValue::UNDEFINED, &JsValue::UNDEFINED);
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise)
+        .await
+        .map_err(|_| "IndexedDB request failed".to_string())
+}
+
+fn to_js<T: Serialize + ?Sized>(value: &T) -> Result<JsValue, String> {
+    serde_json::to_string(value)
+        .map(|json| JsValue::from_str(&json))
+        .map_err(|e| format!("Failed to serialize record: {}", e))
+}
@@ -0,0 +1,230 @@
+// Local model server discovery
+// Probes the ports LM Studio (1234), llama.cpp's server (8080) and Ollama
+// (11434) listen on by default for a model list, so servers running on this
+// machine can be added as providers, or have their models refreshed, without
+// typing URLs. Closed ports refuse the connection at once, so the probes run
+// one after the other. The servers must accept calls from the page: LM
+// Studio needs CORS enabled in its server settings, and Ollama accepts pages
+// served from localhost unless OLLAMA_ORIGINS says otherwise.
+use crate::llm_playground::{
+    api_clients::provider_plugin::{AuthScheme, ProviderPlugin},
+    logging,
+    provider_config::{ProviderConfig, TransformerConfig},
+};
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ServerKind {
+    /// Lists its models at /v1/models
+    OpenAiCompatible,
+    /// Lists its models at /api/tags and chats at /v1
+    Ollama,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LocalServerProbe {
+    pub name: &'static str,
+    pub port: u16,
+    pub kind: ServerKind,
+}
+
+pub const LOCAL_SERVER_PROBES: [LocalServerProbe; 3] = [
+    LocalServerProbe {
+        name: "lm-studio",
+        port: 1234,
+        kind: ServerKind::OpenAiCompatible,
+    },
+    LocalServerProbe {
+        name: "llama-cpp",
+        port: 8080,
+        kind: ServerKind::OpenAiCompatible,
+    },
+    LocalServerProbe {
+        name: "ollama",
+        port: 11434,
+        kind: ServerKind::Ollama,
+    },
+];
+
+impl LocalServerProbe {
+    /// Base URL of the OpenAI-compatible API
+    pub fn base_url(&self) -> String {
+        format!("http://localhost:{}/v1", self.port)
+    }
+
+    fn models_url(&self) -> String {
+        match self.kind {
+            ServerKind::OpenAiCompatible => format!("{}/models", self.base_url()),
+            ServerKind::Ollama => format!("http://localhost:{}/api/tags", self.port),
+        }
+    }
+}
+
+/// Model names from a model list response
+pub fn parse_models(kind: ServerKind, body: &Value) -> Vec<String> {
+    let (list, key) = match kind {
+        ServerKind::OpenAiCompatible => ("data", "id"),
+        ServerKind::Ollama => ("models", "name"),
+    };
+    body.get(list)
+        .and_then(Value::as_array)
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|model| model.get(key).and_then(Value::as_str))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiscoveredServer {
+    pub name: String,
+    pub base_url: String,
+    pub models: Vec<String>,
+}
+
+impl DiscoveredServer {
+    /// The provider already set up for this server, if any
+    pub fn existing_index(&self, providers: &[ProviderConfig]) -> Option<usize> {
+        providers
+            .iter()
+            .position(|p| p.api_base_url.trim_end_matches('/') == self.base_url)
+    }
+
+    /// Add the server as a provider, or refresh the models of the provider
+    /// already pointing at it. Returns the provider's index.
+    pub fn add_to(self, providers: &mut Vec<ProviderConfig>) -> usize {
+        if let Some(index) = self.existing_index(providers) {
+            providers[index].models = self.models;
+            return index;
+        }
+
+        let mut name = self.name.clone();
+        let mut suffix = 2;
+        while providers.iter().any(|p| p.name == name) {
+            name = format!("{}-{}", self.name, suffix);
+            suffix += 1;
+        }
+        providers.push(ProviderConfig {
+            name,
+            api_base_url: self.base_url,
+            api_key: String::new(),
+            models: self.models,
+            transformer: TransformerConfig {
+                r#use: vec!["openai".to_string()],
+            },
+            model_defaults: Default::default(),
+            mock_script: None,
+            // Local servers take no API key
+            plugin: Some(ProviderPlugin {
+                auth: AuthScheme::None,
+                ..Default::default()
+            }),
+            gemini_auth: Default::default(),
+        });
+        providers.len() - 1
+    }
+}
+
+/// Ask one server for its models; None when nothing answers or no model is
+/// loaded
+pub async fn probe(probe: LocalServerProbe) -> Option<DiscoveredServer> {
+    let response = gloo_net::http::Request::get(&probe.models_url())
+        .send()
+        .await
+        .ok()?;
+    if !response.ok() {
+        logging::debug!(
+            "Port {} answered with status {}",
+            probe.port,
+            response.status()
+        );
+        return None;
+    }
+    let body: Value = response.json().await.ok()?;
+    let models = parse_models(probe.kind, &body);
+    if models.is_empty() {
+        return None;
+    }
+    Some(DiscoveredServer {
+        name: probe.name.to_string(),
+        base_url: probe.base_url(),
+        models,
+    })
+}
+
+/// Servers answering on the default ports
+pub async fn scan() -> Vec<DiscoveredServer> {
+    let mut servers = Vec::new();
+    for local_probe in LOCAL_SERVER_PROBES {
+        if let Some(server) = probe(local_probe).await {
+            logging::info!(
+                "Found {} with {} models",
+                server.base_url,
+                server.models.len()
+            );
+            servers.push(server);
+        }
+    }
+    servers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn model_lists_of_both_server_kinds_are_read() {
+        let openai = json!({ "object": "list", "data": [{ "id": "qwen2.5-7b-instruct" }, { "id": "text-embedding-nomic" }] });
+        assert_eq!(
+            parse_models(ServerKind::OpenAiCompatible, &openai),
+            ["qwen2.5-7b-instruct", "text-embedding-nomic"]
+        );
+        let ollama = json!({ "models": [{ "name": "llama3.2:latest", "size": 1 }] });
+        assert_eq!(
+            parse_models(ServerKind::Ollama, &ollama),
+            ["llama3.2:latest"]
+        );
+        assert!(parse_models(ServerKind::Ollama, &openai).is_empty());
+
+        assert_eq!(
+            LOCAL_SERVER_PROBES[2].models_url(),
+            "http://localhost:11434/api/tags"
+        );
+        assert_eq!(
+            LOCAL_SERVER_PROBES[0].models_url(),
+            "http://localhost:1234/v1/models"
+        );
+    }
+
+    #[test]
+    fn discovered_servers_are_added_once_and_refreshed_after() {
+        let mut providers = Vec::new();
+        let server = DiscoveredServer {
+            name: "lm-studio".to_string(),
+            base_url: "http://localhost:1234/v1".to_string(),
+            models: vec!["a".to_string()],
+        };
+        assert_eq!(server.clone().add_to(&mut providers), 0);
+        assert!(!providers[0].needs_api_key());
+
+        let refreshed = DiscoveredServer {
+            models: vec!["a".to_string(), "b".to_string()],
+            ..server.clone()
+        };
+        assert_eq!(refreshed.add_to(&mut providers), 0);
+        assert_eq!(providers.len(), 1);
+        assert_eq!(providers[0].models, ["a", "b"]);
+
+        // Same name, different server
+        let other = DiscoveredServer {
+            base_url: "http://localhost:1235/v1".to_string(),
+            ..server
+        };
+        assert_eq!(other.add_to(&mut providers), 1);
+        assert_eq!(providers[1].name, "lm-studio-2");
+    }
+}
@@ -0,0 +1,471 @@
+// Structured logging
+// Every log line has a level and a target, the module that wrote it. Lines
+// that pass the filter go to the browser console and into a ring buffer that
+// the diagnostics panel shows. The filter is written like RUST_LOG: a default
+// level plus per-target levels, e.g. "info,api_clients=debug".
+//
+//     logging::info!("Sending {} messages", messages.len());
+
+use crate::llm_playground::{html_export, provider_config::FlexibleApiConfig};
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+const LOG_FILTER_KEY: &str = "llm_playground_log_filter";
+pub const DEFAULT_LOG_FILTER: &str = "info";
+/// Lines kept in memory; older ones are dropped
+pub const LOG_CAPACITY: usize = 1_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 4] = [
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Warn,
+        LogLevel::Error,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "debug" | "trace" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warn" | "warning" => Some(LogLevel::Warn),
+            "error" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub id: u64,
+    pub timestamp: f64,
+    pub level: LogLevel,
+    pub target: String,
+    pub message: String,
+}
+
+/// Which lines are kept: the most specific matching target wins
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogFilter {
+    default: LogLevel,
+    targets: Vec<(String, LogLevel)>,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self {
+            default: LogLevel::Info,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl LogFilter {
+    /// Parse "level,target=level,..."; a bare level sets the default
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = Self::default();
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.split_once('=') {
+                Some((target, level)) => {
+                    let level = LogLevel::parse(level)
+                        .ok_or_else(|| format!("Unknown log level '{}'", level.trim()))?;
+                    filter.targets.push((target.trim().to_string(), level));
+                }
+                None => {
+                    filter.default = LogLevel::parse(part)
+                        .ok_or_else(|| format!("Unknown log level '{}'", part))?;
+                }
+            }
+        }
+        Ok(filter)
+    }
+
+    pub fn enabled(&self, level: LogLevel, target: &str) -> bool {
+        let threshold = self
+            .targets
+            .iter()
+            .filter(|(prefix, _)| {
+                target == prefix
+                    || target
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default);
+        level >= threshold
+    }
+}
+
+/// The most recent `capacity` log lines
+#[derive(Clone, Debug)]
+pub struct LogBuffer {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+    next_id: u64,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+            next_id: 0,
+        }
+    }
+
+    pub fn push(&mut self, timestamp: f64, level: LogLevel, target: &str, message: String) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(LogEntry {
+            id: self.next_id,
+            timestamp,
+            level,
+            target: target.to_string(),
+            message,
+        });
+        self.next_id += 1;
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter()
+    }
+
+    /// Changes whenever a line is added, so views know when to refresh
+    pub fn generation(&self) -> u64 {
+        self.next_id
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+struct Logger {
+    spec: String,
+    filter: LogFilter,
+    buffer: LogBuffer,
+}
+
+thread_local! {
+    static LOGGER: RefCell<Logger> = RefCell::new({
+        let spec = LocalStorage::get::<String>(LOG_FILTER_KEY)
+            .unwrap_or_else(|_| DEFAULT_LOG_FILTER.to_string());
+        Logger {
+            filter: LogFilter::parse(&spec).unwrap_or_default(),
+            spec,
+            buffer: LogBuffer::new(LOG_CAPACITY),
+        }
+    });
+}
+
+/// The module path below `llm_playground`, e.g. "components::chatroom"
+pub fn short_target(module_path: &str) -> &str {
+    module_path
+        .split_once("::llm_playground::")
+        .map(|(_, rest)| rest)
+        .unwrap_or(module_path)
+}
+
+/// Write one line; use the `debug!` ... `error!` macros instead of calling this
+pub fn log(level: LogLevel, module_path: &str, message: String) {
+    // Native builds, i.e. the unit tests, have no console or storage to use
+    if !cfg!(target_arch = "wasm32") {
+        return;
+    }
+    let target = short_target(module_path);
+    let kept = LOGGER.with(|logger| {
+        let mut logger = logger.borrow_mut();
+        if !logger.filter.enabled(level, target) {
+            return false;
+        }
+        logger
+            .buffer
+            .push(js_sys::Date::now(), level, target, message.clone());
+        true
+    });
+    if !kept {
+        return;
+    }
+    let line = format!("[{}] {}", target, message);
+    match level {
+        LogLevel::Debug => gloo_console::debug!(line),
+        LogLevel::Info => gloo_console::info!(line),
+        LogLevel::Warn => gloo_console::warn!(line),
+        LogLevel::Error => gloo_console::error!(line),
+    }
+}
+
+macro_rules! log_debug {
+    ($($arg:tt)*) => {
+        $crate::llm_playground::logging::log(
+            $crate::llm_playground::logging::LogLevel::Debug,
+            module_path!(),
+            format!($($arg)*),
+        )
+    };
+}
+
+macro_rules! log_info {
+    ($($arg:tt)*) => {
+        $crate::llm_playground::logging::log(
+            $crate::llm_playground::logging::LogLevel::Info,
+            module_path!(),
+            format!($($arg)*),
+        )
+    };
+}
+
+macro_rules! log_warn {
+    ($($arg:tt)*) => {
+        $crate::llm_playground::logging::log(
+            $crate::llm_playground::logging::LogLevel::Warn,
+            module_path!(),
+            format!($($arg)*),
+        )
+    };
+}
+
+macro_rules! log_error {
+    ($($arg:tt)*) => {
+        $crate::llm_playground::logging::log(
+            $crate::llm_playground::logging::LogLevel::Error,
+            module_path!(),
+            format!($($arg)*),
+        )
+    };
+}
+
+// Exported under the level names; a bare `warn` would clash with the attribute
+pub(crate) use {log_debug as debug, log_error as error, log_info as info, log_warn as warn};
+
+pub fn entries() -> Vec<LogEntry> {
+    LOGGER.with(|logger| logger.borrow().buffer.entries().cloned().collect())
+}
+
+pub fn generation() -> u64 {
+    LOGGER.with(|logger| logger.borrow().buffer.generation())
+}
+
+pub fn clear() {
+    LOGGER.with(|logger| logger.borrow_mut().buffer.clear());
+}
+
+pub fn filter_spec() -> String {
+    LOGGER.with(|logger| logger.borrow().spec.clone())
+}
+
+/// Change and save the filter; lines already kept stay in the buffer
+pub fn set_filter(spec: &str) -> Result<(), String> {
+    let filter = LogFilter::parse(spec)?;
+    LOGGER.with(|logger| {
+        let mut logger = logger.borrow_mut();
+        logger.filter = filter;
+        logger.spec = spec.trim().to_string();
+    });
+    LocalStorage::set(LOG_FILTER_KEY, spec.trim())
+        .map_err(|e| format!("Failed to save log filter: {}", e))
+}
+
+/// Hide API keys and URLs in text meant for a bug report
+pub fn anonymize(text: &str, secrets: &[String]) -> String {
+    html_export::strip_secrets(text, secrets)
+        .split_inclusive(char::is_whitespace)
+        .map(|word| {
+            let trimmed = word.trim_end();
+            match trimmed.find("http://").or_else(|| trimmed.find("https://")) {
+                Some(start) => format!("{}[url]{}", &word[..start], &word[trimmed.len()..]),
+                None => word.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn secrets(config: &FlexibleApiConfig) -> Vec<String> {
+    config
+        .providers
+        .iter()
+        .map(|provider| provider.api_key.clone())
+        .collect()
+}
+
+fn format_time(timestamp: f64) -> String {
+    String::from(js_sys::Date::new(&timestamp.into()).to_iso_string())
+}
+
+/// All kept lines as text, with API keys and URLs hidden
+pub fn export_logs(config: &FlexibleApiConfig) -> String {
+    let secrets = secrets(config);
+    entries()
+        .iter()
+        .map(|entry| {
+            format!(
+                "{} {:<5} {}: {}\n",
+                format_time(entry.timestamp),
+                entry.level.as_str().to_uppercase(),
+                entry.target,
+                anonymize(&entry.message, &secrets)
+            )
+        })
+        .collect()
+}
+
+/// Browser, configuration shape and recent problems, without keys, URLs,
+/// provider names or conversation content
+pub fn environment_report(config: &FlexibleApiConfig, session_count: usize) -> String {
+    let navigator = web_sys::window().map(|window| window.navigator());
+    let navigator_value = |read: fn(&web_sys::Navigator) -> Option<String>| {
+        navigator
+            .as_ref()
+            .and_then(read)
+            .unwrap_or_else(|| "unknown".to_string())
+    };
+    let (provider, model) = config.get_current_provider_and_model();
+    let current_transformer = config
+        .get_provider(&provider)
+        .and_then(|p| p.transformer.r#use.first().cloned())
+        .unwrap_or_else(|| "unknown".to_string());
+    let providers: Vec<String> = config
+        .providers
+        .iter()
+        .map(|p| {
+            format!(
+                "{} ({} models, key {})",
+                p.transformer.r#use.join("+"),
+                p.models.len(),
+                if p.api_key.is_empty() {
+                    "missing"
+                } else {
+                    "set"
+                }
+            )
+        })
+        .collect();
+    let all = entries();
+    let count = |level: LogLevel| all.iter().filter(|e| e.level == level).count();
+    let secrets = secrets(config);
+    let recent_problems: Vec<String> = all
+        .iter()
+        .filter(|e| e.level >= LogLevel::Warn)
+        .rev()
+        .take(10)
+        .map(|e| {
+            format!(
+                "- {} {} {}: {}",
+                format_time(e.timestamp),
+                e.level.as_str(),
+                e.target,
+                anonymize(&e.message, &secrets)
+            )
+        })
+        .collect();
+
+    let mut report = format!(
+        "## Environment\n\
+         - App version: {}\n\
+         - Build: {}\n\
+         - User agent: {}\n\
+         - Language: {}\n\
+         - Platform: {}\n\
+         - Current model: {} / {}\n\
+         - Providers: {}\n\
+         - Enabled tools: {} of {}\n\
+         - MCP servers: {}\n\
+         - Sessions: {}\n\
+         - Log filter: {}\n\
+         - Kept log lines: {} debug, {} info, {} warn, {} error\n",
+        env!("CARGO_PKG_VERSION"),
+        if crate::llm_playground::desktop_tools::is_desktop() {
+            "desktop"
+        } else {
+            "web"
+        },
+        navigator_value(|n| n.user_agent().ok()),
+        navigator_value(|n| n.language()),
+        navigator_value(|n| n.platform().ok()),
+        current_transformer,
+        model,
+        providers.join(", "),
+        config.function_tools.iter().filter(|t| t.enabled).count(),
+        config.function_tools.len(),
+        config.mcp_config.servers.len(),
+        session_count,
+        filter_spec(),
+        count(LogLevel::Debug),
+        count(LogLevel::Info),
+        count(LogLevel::Warn),
+        count(LogLevel::Error),
+    );
+    report.push_str("\n## Recent warnings and errors\n");
+    if recent_problems.is_empty() {
+        report.push_str("None\n");
+    } else {
+        report.push_str(&recent_problems.join("\n"));
+        report.push('\n');
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_uses_most_specific_target() {
+        let filter =
+            LogFilter::parse("warn, api_clients=debug, api_clients::gemini_client=error").unwrap();
+        assert!(!filter.enabled(LogLevel::Info, "components::chatroom"));
+        assert!(filter.enabled(LogLevel::Warn, "components::chatroom"));
+        assert!(filter.enabled(LogLevel::Debug, "api_clients::openai_client"));
+        assert!(!filter.enabled(LogLevel::Warn, "api_clients::gemini_client"));
+        // A prefix only matches whole path segments
+        assert!(!filter.enabled(LogLevel::Debug, "api_clients_extra"));
+
+        assert_eq!(LogFilter::parse("").unwrap(), LogFilter::default());
+        assert!(LogFilter::parse("info,chatroom=loud").is_err());
+        assert_eq!(
+            short_target("llm_playground_rs::llm_playground::components::chatroom"),
+            "components::chatroom"
+        );
+    }
+
+    #[test]
+    fn buffer_keeps_latest_lines_and_report_text_is_anonymized() {
+        let mut buffer = LogBuffer::new(2);
+        for message in ["one", "two", "three"] {
+            buffer.push(0.0, LogLevel::Info, "chatroom", message.to_string());
+        }
+        let kept: Vec<&str> = buffer.entries().map(|e| e.message.as_str()).collect();
+        assert_eq!(kept, ["two", "three"]);
+        assert_eq!(buffer.generation(), 3);
+
+        let secrets = vec!["my-custom-secret-value".to_string(), "ollama".to_string()];
+        assert_eq!(
+            anonymize(
+                "401 from https://api.example.com/v1 with key my-custom-secret-value (ollama)",
+                &secrets
+            ),
+            "401 from [url] with key [REDACTED] (ollama)"
+        );
+    }
+}
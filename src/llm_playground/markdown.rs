@@ -0,0 +1,240 @@
+// Markdown subset of message bodies
+// Parsing is kept apart from rendering so long replies can be parsed in the
+// background worker; the message bubble turns the blocks into Html.
+
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Inline {
+    Text(String),
+    Code(String),
+    Bold(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Block {
+    Blank,
+    /// Line starting with "🔧 ", written for function calls
+    ToolHeader(Vec<Inline>),
+    /// Section title such as "**Arguments**:"
+    Section(String),
+    Heading1(String),
+    Heading2(String),
+    Bullet(Vec<Inline>),
+    Paragraph(Vec<Inline>),
+    /// Fenced code block; `index` counts the code blocks of the text
+    Code {
+        language: String,
+        content: String,
+        index: usize,
+    },
+}
+
+/// Blocks parsed from a message's text, kept on the message so a bubble that
+/// is built again, e.g. after switching sessions, doesn't parse it again.
+/// Clones share the cache. It is never stored and never makes two messages
+/// unequal.
+#[derive(Clone, Default)]
+pub struct MarkdownCache(Rc<RefCell<Option<ParsedText>>>);
+
+/// Key of a text and its blocks
+type ParsedText = (u64, Rc<Vec<Block>>);
+
+/// Key of `content` in a cache
+pub fn content_key(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl MarkdownCache {
+    /// Blocks of the text with `key`, if it is the one parsed last
+    pub fn get(&self, key: u64) -> Option<Rc<Vec<Block>>> {
+        self.0
+            .borrow()
+            .as_ref()
+            .filter(|(parsed, _)| *parsed == key)
+            .map(|(_, blocks)| blocks.clone())
+    }
+
+    /// Keep `blocks` as those of the text with `key`
+    pub fn put(&self, key: u64, blocks: Vec<Block>) -> Rc<Vec<Block>> {
+        let blocks = Rc::new(blocks);
+        *self.0.borrow_mut() = Some((key, blocks.clone()));
+        blocks
+    }
+}
+
+impl PartialEq for MarkdownCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for MarkdownCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MarkdownCache")
+    }
+}
+
+/// Split `content` into blocks. An unclosed code fence hides the lines after
+/// it until the fence is closed.
+pub fn parse_blocks(content: &str) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut in_code_block = false;
+    let mut code_block_content = Vec::new();
+    let mut code_block_language = String::new();
+    let mut code_block_index = 0;
+
+    for line in content.split('\n') {
+        if line.trim().starts_with("```") {
+            if in_code_block {
+                blocks.push(Block::Code {
+                    language: std::mem::take(&mut code_block_language),
+                    content: code_block_content.join("\n"),
+                    index: code_block_index,
+                });
+                code_block_index += 1;
+                code_block_content.clear();
+                in_code_block = false;
+            } else {
+                code_block_language = line.trim().trim_start_matches("```").to_string();
+                in_code_block = true;
+            }
+        } else if in_code_block {
+            code_block_content.push(line);
+        } else {
+            blocks.push(parse_line(line));
+        }
+    }
+    blocks
+}
+
+fn parse_line(line: &str) -> Block {
+    if line.trim().is_empty() {
+        Block::Blank
+    } else if let Some(rest) = line.strip_prefix("🔧 ") {
+        Block::ToolHeader(parse_inline(rest))
+    } else if line.starts_with("**") && line.ends_with("**:") {
+        Block::Section(
+            line.trim_start_matches("**")
+                .trim_end_matches("**:")
+                .to_string(),
+        )
+    } else if let Some(rest) = line.strip_prefix("# ") {
+        Block::Heading1(rest.trim_start_matches("# ").to_string())
+    } else if let Some(rest) = line.strip_prefix("## ") {
+        Block::Heading2(rest.trim_start_matches("## ").to_string())
+    } else if let Some(rest) = line.strip_prefix("- ") {
+        Block::Bullet(parse_inline(rest))
+    } else {
+        Block::Paragraph(parse_inline(line))
+    }
+}
+
+/// `code` and **bold** runs; an unclosed ** is kept as text
+pub fn parse_inline(text: &str) -> Vec<Inline> {
+    let mut result = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut current_text = String::new();
+
+    while let Some(ch) = chars.next() {
+        if ch == '`' {
+            if !current_text.is_empty() {
+                result.push(Inline::Text(std::mem::take(&mut current_text)));
+            }
+            let code: String = chars.by_ref().take_while(|c| *c != '`').collect();
+            result.push(Inline::Code(code));
+        } else if ch == '*' && chars.peek() == Some(&'*') {
+            chars.next();
+            let mut bold = String::new();
+            let mut found_end = false;
+            while let Some(next_ch) = chars.next() {
+                if next_ch == '*' && chars.peek() == Some(&'*') {
+                    chars.next();
+                    found_end = true;
+                    break;
+                }
+                bold.push(next_ch);
+            }
+            if found_end {
+                if !current_text.is_empty() {
+                    result.push(Inline::Text(std::mem::take(&mut current_text)));
+                }
+                result.push(Inline::Bold(bold));
+            } else {
+                current_text.push_str("**");
+                current_text.push_str(&bold);
+            }
+        } else {
+            current_text.push(ch);
+        }
+    }
+
+    if !current_text.is_empty() {
+        result.push(Inline::Text(current_text));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blocks_follow_lines_and_code_fences() {
+        let blocks = parse_blocks(
+            "# Title\n\n- item `x`\n```rust\nfn main() {}\n```\n**Arguments**:\n```\nopen",
+        );
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Heading1("Title".to_string()),
+                Block::Blank,
+                Block::Bullet(vec![
+                    Inline::Text("item ".to_string()),
+                    Inline::Code("x".to_string()),
+                ]),
+                Block::Code {
+                    language: "rust".to_string(),
+                    content: "fn main() {}".to_string(),
+                    index: 0,
+                },
+                Block::Section("Arguments".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn inline_bold_needs_a_closing_marker() {
+        assert_eq!(
+            parse_inline("a **b** c"),
+            vec![
+                Inline::Text("a ".to_string()),
+                Inline::Bold("b".to_string()),
+                Inline::Text(" c".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_inline("2 ** 3"),
+            vec![Inline::Text("2 ** 3".to_string())]
+        );
+        assert!(parse_inline("").is_empty());
+    }
+
+    #[test]
+    fn cache_is_shared_by_clones_and_keyed_by_text() {
+        let cache = MarkdownCache::default();
+        let copy = cache.clone();
+        cache.put(content_key("# Title"), parse_blocks("# Title"));
+        assert_eq!(
+            copy.get(content_key("# Title")).as_deref(),
+            Some(&vec![Block::Heading1("Title".to_string())])
+        );
+        assert!(copy.get(content_key("# Title 2")).is_none());
+    }
+}
@@ -8,6 +8,7 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{Request, RequestInit, RequestMode, Response};
 
+use crate::llm_playground::cors_proxy;
 use crate::llm_playground::types::FunctionTool;
 
 #[wasm_bindgen]
@@ -23,6 +24,24 @@ pub struct McpServerConfig {
     pub url: Option<String>,
     pub headers: Option<HashMap<String, String>>,
     pub enabled: bool,
+    /// Put before this server's tool names. None uses `mcp_{server}_`; an
+    /// empty prefix leaves the names as the server exports them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_prefix: Option<String>,
+}
+
+/// Category of the function tools a server provides
+pub fn tool_category(server_name: &str) -> String {
+    format!("MCP ({})", server_name)
+}
+
+impl McpServerConfig {
+    /// The prefix for the tools of the server stored under `server_name`
+    pub fn tool_prefix(&self, server_name: &str) -> String {
+        self.tool_prefix
+            .clone()
+            .unwrap_or_else(|| format!("mcp_{}_", McpClient::sanitize_name_for_gemini(server_name)))
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -66,6 +85,7 @@ pub struct McpClient {
     config: McpConfig,
     available_tools: HashMap<String, McpTool>,
     session_ids: HashMap<String, String>, // server_name -> session_id
+    cors_proxy: Option<String>,
 }
 
 impl Default for McpConfig {
@@ -88,6 +108,7 @@ impl Default for McpConfig {
                     headers
                 }),
                 enabled: false, // Disabled by default until user configures token
+                tool_prefix: None,
             },
         );
 
@@ -101,15 +122,29 @@ impl McpClient {
             config,
             available_tools: HashMap::new(),
             session_ids: HashMap::new(),
+            cors_proxy: None,
         }
     }
 
+    /// Route all server requests through the given CORS proxy
+    pub fn with_cors_proxy(mut self, cors_proxy: Option<String>) -> Self {
+        self.cors_proxy = cors_proxy;
+        self
+    }
+
+    /// Get the CORS proxy used for server requests
+    pub fn cors_proxy(&self) -> Option<&str> {
+        self.cors_proxy.as_deref()
+    }
+
     /// Initialize connections to all enabled MCP servers
     pub async fn initialize(&mut self) -> Result<(), String> {
         log("Initializing MCP client connections...");
 
-        // Clone the servers to avoid borrowing issues
-        let servers = self.config.servers.clone();
+        // Clone the servers to avoid borrowing issues. Sorted, so a name
+        // two servers share is always renamed on the same one.
+        let mut servers: Vec<_> = self.config.servers.clone().into_iter().collect();
+        servers.sort_by(|a, b| a.0.cmp(&b.0));
         for (server_name, server_config) in servers {
             if server_config.enabled {
                 match self.connect_to_server(&server_name, &server_config).await {
@@ -168,10 +203,19 @@ impl McpClient {
         // List available tools
         match self.list_tools(server_name).await {
             Ok(tools) => {
+                self.available_tools
+                    .retain(|_, mcp_tool| mcp_tool.server_name != server_name);
+                let prefix = config.tool_prefix(server_name);
                 for tool in tools {
-                    let sanitized_name =
-                        Self::create_gemini_tool_name(&tool.server_name, &tool.name);
-                    self.available_tools.insert(sanitized_name, tool);
+                    let wanted = Self::create_tool_name(&prefix, &tool.name);
+                    let tool_name = Self::unique_tool_name(&self.available_tools, &wanted);
+                    if tool_name != wanted {
+                        log(&format!(
+                            "MCP tool {} from {} is exposed as {}; {} is taken by another tool",
+                            tool.name, server_name, tool_name, wanted
+                        ));
+                    }
+                    self.available_tools.insert(tool_name, tool);
                 }
             }
             Err(e) => {
@@ -352,14 +396,17 @@ impl McpClient {
         opts.set_body(&JsValue::from_str(&body));
 
         // Create the request
-        let web_request = Request::new_with_str_and_init(url, &opts)
-            .map_err(|e| format!("Failed to create request: {:?}", e))?;
+        let proxy = self.cors_proxy.as_deref();
+        let web_request =
+            Request::new_with_str_and_init(&cors_proxy::proxied_url(proxy, url), &opts)
+                .map_err(|e| format!("Failed to create request: {:?}", e))?;
 
         // Set headers
         let request_headers = web_request.headers();
         request_headers
             .set("Content-Type", "application/json")
             .map_err(|e| format!("Failed to set content-type header: {:?}", e))?;
+        cors_proxy::apply_proxy_headers(&request_headers, proxy)?;
 
         // Add session ID header if available (for subsequent requests)
         if let Some(ref session_id) = session_id {
@@ -456,24 +503,34 @@ impl McpClient {
     }
 
     /// Create a sanitized tool name for Gemini API
-    fn create_gemini_tool_name(server_name: &str, tool_name: &str) -> String {
-        let sanitized_server = Self::sanitize_name_for_gemini(server_name);
+    fn create_tool_name(prefix: &str, tool_name: &str) -> String {
         let sanitized_tool = Self::sanitize_name_for_gemini(tool_name);
-        let combined = format!("mcp_{}_{}", sanitized_server, sanitized_tool);
-        Self::sanitize_name_for_gemini(&combined)
+        Self::sanitize_name_for_gemini(&format!("{}{}", prefix, sanitized_tool))
+    }
+
+    /// `name`, or `name` with a number after it when a tool already has it
+    fn unique_tool_name(taken: &HashMap<String, McpTool>, name: &str) -> String {
+        if !taken.contains_key(name) {
+            return name.to_string();
+        }
+        (2..)
+            .map(|number| {
+                let suffix = format!("_{}", number);
+                // Keep within the 64 characters Gemini allows
+                let base: String = name.chars().take(64 - suffix.len()).collect();
+                format!("{}{}", base, suffix)
+            })
+            .find(|candidate| !taken.contains_key(candidate))
+            .unwrap_or_default()
     }
 
     /// Convert MCP tools to FunctionTool format for the LLM playground
     pub fn get_function_tools(&self) -> Vec<FunctionTool> {
         let mut function_tools = Vec::new();
 
-        for (prefixed_name, mcp_tool) in &self.available_tools {
-            // Create a Gemini-compatible name for the tool
-            let gemini_compatible_name =
-                Self::create_gemini_tool_name(&mcp_tool.server_name, &mcp_tool.name);
-
+        for (tool_name, mcp_tool) in &self.available_tools {
             let function_tool = FunctionTool {
-                name: gemini_compatible_name,
+                name: tool_name.clone(),
                 description: mcp_tool
                     .description
                     .clone()
@@ -481,11 +538,13 @@ impl McpClient {
                 parameters: mcp_tool.input_schema.clone(),
                 mock_response: r#"{"status": "success", "source": "mcp_server"}"#.to_string(),
                 enabled: true,
-                category: format!("MCP ({})", mcp_tool.server_name),
+                category: tool_category(&mcp_tool.server_name),
                 is_builtin: true,
+                requires: None,
             };
             function_tools.push(function_tool);
         }
+        function_tools.sort_by(|a, b| a.name.cmp(&b.name));
 
         function_tools
     }
@@ -540,7 +599,7 @@ mod tests {
         // Test special characters
         assert_eq!(
             McpClient::sanitize_name_for_gemini("test@#$%^&*()tool"),
-            "test__________tool"
+            "test_________tool"
         );
 
         // Test starting with number (should be prefixed with underscore)
@@ -562,24 +621,72 @@ mod tests {
         );
     }
 
+    fn server(tool_prefix: Option<&str>) -> McpServerConfig {
+        McpServerConfig {
+            name: "Server".to_string(),
+            server_type: "http".to_string(),
+            url: None,
+            headers: None,
+            enabled: true,
+            tool_prefix: tool_prefix.map(str::to_string),
+        }
+    }
+
+    fn tool(name: &str, server_name: &str) -> McpTool {
+        McpTool {
+            name: name.to_string(),
+            description: None,
+            input_schema: serde_json::json!({}),
+            server_name: server_name.to_string(),
+        }
+    }
+
     #[test]
-    fn test_create_gemini_tool_name() {
+    fn test_create_tool_name() {
+        let prefix = server(None).tool_prefix("GitHub Copilot MCP");
         assert_eq!(
-            McpClient::create_gemini_tool_name("GitHub Copilot MCP", "request_copilot_review"),
+            McpClient::create_tool_name(&prefix, "request_copilot_review"),
             "mcp_GitHub_Copilot_MCP_request_copilot_review"
         );
 
         assert_eq!(
-            McpClient::create_gemini_tool_name("simple", "tool"),
+            McpClient::create_tool_name(&server(None).tool_prefix("simple"), "tool"),
             "mcp_simple_tool"
         );
 
         // Test with special characters
         assert_eq!(
-            McpClient::create_gemini_tool_name("test@server", "test#tool"),
+            McpClient::create_tool_name(&server(None).tool_prefix("test@server"), "test#tool"),
             "mcp_test_server_test_tool"
         );
     }
+
+    #[test]
+    fn test_custom_and_empty_prefixes() {
+        let prefix = server(Some("gh_")).tool_prefix("GitHub Copilot MCP");
+        assert_eq!(McpClient::create_tool_name(&prefix, "review"), "gh_review");
+
+        let prefix = server(Some("")).tool_prefix("GitHub Copilot MCP");
+        assert_eq!(McpClient::create_tool_name(&prefix, "review"), "review");
+        assert_eq!(McpClient::create_tool_name(&prefix, "1st-pass"), "_1st-pass");
+    }
+
+    #[test]
+    fn test_colliding_names_are_numbered() {
+        let mut taken = HashMap::new();
+        assert_eq!(McpClient::unique_tool_name(&taken, "search"), "search");
+
+        taken.insert("search".to_string(), tool("search", "a"));
+        assert_eq!(McpClient::unique_tool_name(&taken, "search"), "search_2");
+        taken.insert("search_2".to_string(), tool("search", "b"));
+        assert_eq!(McpClient::unique_tool_name(&taken, "search"), "search_3");
+
+        let long_name = "a".repeat(64);
+        taken.insert(long_name.clone(), tool(&long_name, "a"));
+        let renamed = McpClient::unique_tool_name(&taken, &long_name);
+        assert_eq!(renamed.len(), 64);
+        assert!(renamed.ends_with("_2"));
+    }
 }
 
 // UUID generation for WASM
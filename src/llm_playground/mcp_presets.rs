@@ -0,0 +1,159 @@
+// Remote MCP server presets
+// A curated list of public MCP servers with their endpoint and the kind of
+// credential they take, so one can be added from the MCP settings without
+// looking up its URL. The list ships in `mcp_presets.json`; a newer copy of
+// that file can be fetched from the repository and is kept in localStorage
+// until the app ships a list at least as new.
+use crate::llm_playground::{logging, mcp_client::McpConfig};
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const BUILTIN_PRESETS: &str = include_str!("mcp_presets.json");
+const STORAGE_KEY_MCP_PRESETS: &str = "llm_playground_mcp_presets";
+pub const PRESETS_URL: &str =
+    "https://raw.githubusercontent.com/jyasuu/llm-playground-rs/main/src/llm_playground/mcp_presets.json";
+
+/// The credential a server expects
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum McpPresetAuth {
+    None,
+    /// A token sent as `Authorization: Bearer …`
+    Bearer,
+    /// A key sent in the named header
+    Header { name: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct McpPreset {
+    /// Used as the server name when added
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    pub auth: McpPresetAuth,
+    pub description: String,
+    /// Where to read about the server or get a token
+    #[serde(default)]
+    pub docs_url: Option<String>,
+}
+
+impl McpPreset {
+    /// Whether a configured server already points at this preset's URL
+    pub fn is_added(&self, config: &McpConfig) -> bool {
+        let url = self.url.trim_end_matches('/');
+        config
+            .servers
+            .values()
+            .any(|server| server.url.as_deref().map(|u| u.trim_end_matches('/')) == Some(url))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct McpPresetList {
+    /// Raised whenever the list changes
+    pub version: u32,
+    pub servers: Vec<McpPreset>,
+}
+
+/// Read a preset list, rejecting one with repeated ids or plain-HTTP URLs
+pub fn parse(text: &str) -> Result<McpPresetList, String> {
+    let list: McpPresetList =
+        serde_json::from_str(text).map_err(|e| format!("Invalid preset list: {}", e))?;
+    let mut ids = HashSet::new();
+    for preset in &list.servers {
+        if !ids.insert(preset.id.as_str()) {
+            return Err(format!("Preset {} is listed twice", preset.id));
+        }
+        if !preset.url.starts_with("https://") {
+            return Err(format!("Preset {} doesn't use HTTPS", preset.id));
+        }
+    }
+    Ok(list)
+}
+
+/// The list this build ships with
+pub fn builtin() -> McpPresetList {
+    parse(BUILTIN_PRESETS).expect("mcp_presets.json is a valid preset list")
+}
+
+/// The newest list known: a fetched one when it's newer than the built-in one
+pub fn load() -> McpPresetList {
+    let builtin = builtin();
+    match LocalStorage::get::<McpPresetList>(STORAGE_KEY_MCP_PRESETS) {
+        Ok(fetched) if fetched.version > builtin.version => fetched,
+        _ => builtin,
+    }
+}
+
+/// Fetch the list from the repository, keeping it when it's newer than the
+/// one in use. Returns the list to use afterwards.
+pub async fn update() -> Result<McpPresetList, String> {
+    let response = gloo_net::http::Request::get(PRESETS_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Couldn't fetch the server list: {}", e))?;
+    if !response.ok() {
+        return Err(format!("Couldn't fetch the server list (status {})", response.status()));
+    }
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Couldn't read the server list: {}", e))?;
+    let fetched = parse(&text)?;
+    let current = load();
+    if fetched.version <= current.version {
+        logging::debug!("MCP server list {} is current", current.version);
+        return Ok(current);
+    }
+    let _ = LocalStorage::set(STORAGE_KEY_MCP_PRESETS, &fetched);
+    Ok(fetched)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::mcp_client::McpServerConfig;
+    use std::collections::HashMap;
+
+    #[test]
+    fn builtin_list_is_valid() {
+        let list = builtin();
+        assert!(!list.servers.is_empty());
+        assert!(list.servers.iter().all(|preset| !preset.description.is_empty()));
+    }
+
+    #[test]
+    fn repeated_ids_and_plain_http_are_rejected() {
+        let preset = r#"{"id": "a", "name": "A", "url": "https://a.example.com/mcp", "auth": {"type": "none"}, "description": "A"}"#;
+        let twice = format!(r#"{{"version": 2, "servers": [{}, {}]}}"#, preset, preset);
+        assert_eq!(parse(&twice).unwrap_err(), "Preset a is listed twice");
+
+        let http = format!(r#"{{"version": 2, "servers": [{}]}}"#, preset.replace("https", "http"));
+        assert!(parse(&http).is_err());
+
+        let header = preset.replace(r#"{"type": "none"}"#, r#"{"type": "header", "name": "X-Key"}"#);
+        let list = parse(&format!(r#"{{"version": 2, "servers": [{}]}}"#, header)).unwrap();
+        assert_eq!(list.servers[0].auth, McpPresetAuth::Header { name: "X-Key".to_string() });
+    }
+
+    #[test]
+    fn added_presets_match_on_url() {
+        let preset = builtin().servers.into_iter().find(|preset| preset.id == "deepwiki").unwrap();
+        let mut config = McpConfig { servers: HashMap::new() };
+        assert!(!preset.is_added(&config));
+
+        config.servers.insert(
+            "wiki".to_string(),
+            McpServerConfig {
+                name: "wiki".to_string(),
+                server_type: "http".to_string(),
+                url: Some(format!("{}/", preset.url)),
+                headers: None,
+                enabled: true,
+                tool_prefix: None,
+            },
+        );
+        assert!(preset.is_added(&config));
+    }
+}
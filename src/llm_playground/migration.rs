@@ -1,5 +1,5 @@
 // Migration utilities for converting old config to new flexible config
-use crate::llm_playground::{ApiConfig, ApiProvider, FlexibleApiConfig, ProviderConfig, TransformerConfig};
+use crate::llm_playground::{storage, ApiConfig, ApiProvider, FlexibleApiConfig, ProviderConfig, TransformerConfig};
 
 pub fn migrate_old_config_to_flexible(old_config: ApiConfig) -> FlexibleApiConfig {
     let mut flexible_config = FlexibleApiConfig::default();
@@ -70,7 +70,7 @@ pub fn migrate_if_needed() -> Option<FlexibleApiConfig> {
                 let flexible_config = migrate_old_config_to_flexible(old_config);
                 
                 // Save the new config
-                if let Ok(new_config_str) = serde_json::to_string(&flexible_config) {
+                if let Ok(new_config_str) = storage::payload_json(&flexible_config) {
                     let _ = LocalStorage::set("llm_playground_flexible_config", new_config_str);
                 }
                 
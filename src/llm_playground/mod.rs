@@ -1,14 +1,87 @@
 // LLM Playground module
+pub mod ab_experiment;
 pub mod api_clients;
+pub mod app_state;
+pub mod arena;
+pub mod artifacts;
+pub mod attachments;
+pub mod audio_output;
+pub mod bookmarks;
+pub mod browser_notifications;
+pub mod budget;
 pub mod builtin_tools;
+pub mod candidates;
+pub mod chart;
+pub mod code_sandbox;
 pub mod components;
+pub mod context_usage;
+pub mod continuation;
+pub mod conversation_starters;
+pub mod cors_proxy;
+pub mod desktop_tools;
+pub mod dialogue;
+pub mod eval_suite;
+pub mod event_bus;
+pub mod fetch_tool;
+pub mod fine_tune_export;
 pub mod flexible_client;
 pub mod flexible_playground;
+pub mod follow_ups;
+pub mod guardrails;
+pub mod handoff_tools;
+pub mod history_scrubber;
+pub mod image_generation;
 pub mod hooks;
+pub mod html_export;
+pub mod json_repair;
+pub mod knowledge;
+pub mod knowledge_store;
+pub mod local_discovery;
+pub mod logging;
+pub mod markdown;
 pub mod mcp_client;
+pub mod mcp_presets;
+pub mod model_shortcuts;
+pub mod moderation;
+pub mod native_tools;
+pub mod offload;
+pub mod pdf_text;
+pub mod personas;
+pub mod prompt_optimizer;
 pub mod provider_config;
+pub mod redaction;
+pub mod request_export;
+pub mod request_overrides;
+pub mod response_length;
+pub mod retry_queue;
+pub mod routes;
+pub mod sampling_controls;
+pub mod self_consistency;
+pub mod session_bulk;
+pub mod session_database;
+pub mod session_lock;
+pub mod session_merge;
+pub mod session_pages;
+pub mod session_recording;
+pub mod session_search;
+pub mod session_snapshots;
+pub mod session_store;
+pub mod shared_view;
 pub mod storage;
+pub mod storage_recovery;
+pub mod structured_output;
+pub mod sub_agent;
+pub mod tab_status;
+pub mod todo_list;
+pub mod tool_dependencies;
+pub mod tool_selection;
+pub mod trace;
+pub mod trace_export;
 pub mod types;
+pub mod web_fetch;
+pub mod web_search;
+pub mod webhook;
+pub mod word_diff;
 
 pub use api_clients::*;
 pub use components::*;
@@ -19,6 +92,5 @@ pub use storage::*;
 pub use types::*;
 
 use crate::llm_playground::api_clients::{GeminiClient, OpenAIClient};
-use gloo_console::log;
 use std::collections::HashMap;
 use yew::prelude::*;
@@ -0,0 +1,139 @@
+// Favorite and recent models
+// Starred provider/model pairs and the last few used are kept in local
+// storage and offered first by the model selector and the Ctrl+M quick
+// switcher. Pairs whose provider or model was removed from the settings are
+// left out.
+use crate::llm_playground::provider_config::FlexibleApiConfig;
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+
+const MODEL_SHORTCUTS_KEY: &str = "llm_playground_model_shortcuts";
+
+/// Recently used models remembered
+pub const MAX_RECENTS: usize = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelRef {
+    pub provider: String,
+    pub model: String,
+}
+
+impl ModelRef {
+    pub fn new(provider: &str, model: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        format!("{} - {}", self.provider, self.model)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelShortcuts {
+    #[serde(default)]
+    pub favorites: Vec<ModelRef>,
+    /// Most recent first
+    #[serde(default)]
+    pub recents: Vec<ModelRef>,
+}
+
+impl ModelShortcuts {
+    pub fn load() -> Self {
+        LocalStorage::get(MODEL_SHORTCUTS_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let _ = LocalStorage::set(MODEL_SHORTCUTS_KEY, self);
+    }
+
+    /// Move the model to the front of the recents
+    pub fn record_use(&mut self, model: ModelRef) {
+        self.recents.retain(|recent| *recent != model);
+        self.recents.insert(0, model);
+        self.recents.truncate(MAX_RECENTS);
+    }
+
+    pub fn is_favorite(&self, model: &ModelRef) -> bool {
+        self.favorites.contains(model)
+    }
+
+    /// Star the model, or unstar it if it was starred
+    pub fn toggle_favorite(&mut self, model: ModelRef) {
+        if let Some(index) = self.favorites.iter().position(|f| *f == model) {
+            self.favorites.remove(index);
+        } else {
+            self.favorites.push(model);
+        }
+    }
+
+    /// Favorites, then recents that are not favorites, limited to models the
+    /// settings still have
+    pub fn quick_list(&self, config: &FlexibleApiConfig) -> Vec<ModelRef> {
+        let available = |m: &&ModelRef| {
+            config
+                .get_provider(&m.provider)
+                .is_some_and(|p| p.models.contains(&m.model))
+        };
+        self.favorites
+            .iter()
+            .filter(available)
+            .chain(
+                self.recents
+                    .iter()
+                    .filter(|m| !self.favorites.contains(m))
+                    .filter(available),
+            )
+            .cloned()
+            .collect()
+    }
+}
+
+/// Remember a model the user just picked
+pub fn record_use(provider: &str, model: &str) {
+    let mut shortcuts = ModelShortcuts::load();
+    shortcuts.record_use(ModelRef::new(provider, model));
+    shortcuts.save();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recents_are_newest_first_without_duplicates() {
+        let mut shortcuts = ModelShortcuts::default();
+        for i in 0..MAX_RECENTS + 2 {
+            shortcuts.record_use(ModelRef::new("openai", &format!("m{}", i)));
+        }
+        shortcuts.record_use(ModelRef::new("openai", "m3"));
+        assert_eq!(shortcuts.recents.len(), MAX_RECENTS);
+        assert_eq!(shortcuts.recents[0].model, "m3");
+        assert_eq!(shortcuts.recents[1].model, "m6");
+        assert_eq!(
+            shortcuts.recents.iter().filter(|m| m.model == "m3").count(),
+            1
+        );
+    }
+
+    #[test]
+    fn quick_list_puts_favorites_first_and_skips_removed_models() {
+        let config = FlexibleApiConfig::default();
+        let provider = &config.providers[0];
+        let first = ModelRef::new(&provider.name, &provider.models[0]);
+        let second = ModelRef::new(&provider.name, &provider.models[1]);
+
+        let mut shortcuts = ModelShortcuts::default();
+        shortcuts.record_use(first.clone());
+        shortcuts.record_use(ModelRef::new("removed", "model"));
+        shortcuts.record_use(second.clone());
+        shortcuts.toggle_favorite(first.clone());
+        assert!(shortcuts.is_favorite(&first));
+
+        assert_eq!(shortcuts.quick_list(&config), [first.clone(), second]);
+        shortcuts.toggle_favorite(first.clone());
+        assert!(!shortcuts.is_favorite(&first));
+    }
+}
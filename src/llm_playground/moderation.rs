@@ -0,0 +1,157 @@
+// Moderation checks
+// Outgoing user messages and assistant replies can be sent to an
+// OpenAI-compatible `/moderations` endpoint. Flagged categories are shown on the
+// message, and flagged user messages can be kept from reaching the model.
+
+use crate::llm_playground::{cors_proxy, provider_config::FlexibleApiConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModerationSettings {
+    pub enabled: bool,
+    /// Provider whose `/moderations` endpoint and API key are used
+    pub provider: String,
+    pub model: String,
+    pub check_user_messages: bool,
+    pub check_replies: bool,
+    /// Keep flagged user messages from being sent to the model
+    pub block_flagged: bool,
+}
+
+impl Default for ModerationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: "openai".to_string(),
+            model: "omni-moderation-latest".to_string(),
+            check_user_messages: true,
+            check_replies: true,
+            block_flagged: false,
+        }
+    }
+}
+
+impl ModerationSettings {
+    pub fn checks_user_messages(&self) -> bool {
+        self.enabled && self.check_user_messages
+    }
+
+    pub fn checks_replies(&self) -> bool {
+        self.enabled && self.check_replies
+    }
+}
+
+/// Outcome of a moderation check, kept on the message
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModerationResult {
+    pub flagged: bool,
+    /// Flagged categories with their scores, highest first
+    pub categories: Vec<(String, f64)>,
+    /// The message was flagged and not sent to the model
+    #[serde(default)]
+    pub blocked: bool,
+}
+
+/// Read the first result of a `/moderations` response
+pub fn parse_response(response: &Value) -> Result<ModerationResult, String> {
+    let result = response
+        .get("results")
+        .and_then(|r| r.get(0))
+        .ok_or("Moderation response has no results")?;
+    let scores = result.get("category_scores");
+    let mut categories: Vec<(String, f64)> = result
+        .get("categories")
+        .and_then(|c| c.as_object())
+        .map(|categories| {
+            categories
+                .iter()
+                .filter(|(_, flagged)| flagged.as_bool() == Some(true))
+                .map(|(name, _)| {
+                    let score = scores
+                        .and_then(|s| s.get(name))
+                        .and_then(|s| s.as_f64())
+                        .unwrap_or_default();
+                    (name.clone(), score)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    categories.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    Ok(ModerationResult {
+        flagged: result
+            .get("flagged")
+            .and_then(|f| f.as_bool())
+            .unwrap_or(!categories.is_empty()),
+        categories,
+        blocked: false,
+    })
+}
+
+/// Run text through the configured moderation endpoint
+pub async fn moderate(config: &FlexibleApiConfig, text: &str) -> Result<ModerationResult, String> {
+    let settings = &config.moderation;
+    let provider = config
+        .get_provider(&settings.provider)
+        .ok_or_else(|| format!("Moderation provider '{}' not found", settings.provider))?;
+    if provider.transformer.r#use.contains(&"gemini".to_string()) {
+        return Err("Moderation needs an OpenAI-compatible provider".to_string());
+    }
+
+    let url = format!(
+        "{}/moderations",
+        provider.api_base_url.trim_end_matches('/')
+    );
+    let body = serde_json::json!({ "model": settings.model, "input": text });
+    let response = cors_proxy::post(&url, config.shared_settings.cors_proxy())
+        .header("Content-Type", "application/json")
+        .header("Authorization", &format!("Bearer {}", provider.api_key))
+        .json(&body)
+        .map_err(|e| format!("Failed to create request: {}", e))?
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+    if !response.ok() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("Moderation API error {}: {}", status, text));
+    }
+    let json = response
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Failed to parse moderation response: {}", e))?;
+    parse_response(&json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_flagged_categories_by_score() {
+        let response = json!({
+            "results": [{
+                "flagged": true,
+                "categories": { "hate": false, "violence": true, "harassment": true },
+                "category_scores": { "hate": 0.1, "violence": 0.4, "harassment": 0.9 }
+            }]
+        });
+        let result = parse_response(&response).unwrap();
+        assert!(result.flagged);
+        assert_eq!(
+            result.categories,
+            [
+                ("harassment".to_string(), 0.9),
+                ("violence".to_string(), 0.4)
+            ]
+        );
+        assert!(!result.blocked);
+
+        let clean = parse_response(&json!({ "results": [{ "flagged": false, "categories": {} }] }));
+        assert_eq!(clean, Ok(ModerationResult::default()));
+        assert!(parse_response(&json!({})).is_err());
+    }
+}
@@ -0,0 +1,165 @@
+// Provider-native tools
+// OpenAI's Responses API can search the web and run Python in a sandbox on its
+// own. These tools are switched on apart from the function tools; requests
+// that use them go to the Responses API, and what the tools did comes back as
+// output items that are shown with the reply.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NativeToolSettings {
+    pub web_search: bool,
+    pub code_interpreter: bool,
+}
+
+impl NativeToolSettings {
+    pub fn any(&self) -> bool {
+        self.web_search || self.code_interpreter
+    }
+
+    /// Entries for the request's `tools` list
+    pub fn request_tools(&self) -> Vec<Value> {
+        let mut tools = Vec::new();
+        if self.web_search {
+            tools.push(json!({ "type": "web_search" }));
+        }
+        if self.code_interpreter {
+            tools.push(json!({ "type": "code_interpreter", "container": { "type": "auto" } }));
+        }
+        tools
+    }
+}
+
+/// What a native tool did while the reply was generated
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NativeToolCall {
+    WebSearch {
+        /// Queries searched for, or pages opened
+        #[serde(default)]
+        queries: Vec<String>,
+        #[serde(default)]
+        status: String,
+    },
+    CodeInterpreter {
+        #[serde(default)]
+        code: String,
+        /// What the code printed
+        #[serde(default)]
+        logs: String,
+        /// URLs of the images it made
+        #[serde(default)]
+        images: Vec<String>,
+        #[serde(default)]
+        status: String,
+    },
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| items.iter().filter_map(Value::as_str).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+impl NativeToolCall {
+    /// Read a Responses API output item; None for other kinds of items
+    pub fn from_output_item(item: &Value) -> Option<Self> {
+        let status = item["status"].as_str().unwrap_or_default().to_string();
+        match item["type"].as_str()? {
+            "web_search_call" => {
+                let action = &item["action"];
+                let mut queries = strings(&action["queries"]);
+                if queries.is_empty() {
+                    queries.extend(
+                        [&action["query"], &action["url"]]
+                            .into_iter()
+                            .filter_map(|value| value.as_str())
+                            .map(str::to_string),
+                    );
+                }
+                Some(NativeToolCall::WebSearch { queries, status })
+            }
+            "code_interpreter_call" => {
+                let outputs = item["outputs"].as_array().map(Vec::as_slice).unwrap_or_default();
+                let of_type = |kind: &'static str| {
+                    outputs.iter().filter(move |output| output["type"] == kind)
+                };
+                Some(NativeToolCall::CodeInterpreter {
+                    code: item["code"].as_str().unwrap_or_default().to_string(),
+                    logs: of_type("logs")
+                        .filter_map(|output| output["logs"].as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    images: of_type("image")
+                        .filter_map(|output| output["url"].as_str())
+                        .map(str::to_string)
+                        .collect(),
+                    status,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        let (NativeToolCall::WebSearch { status, .. } | NativeToolCall::CodeInterpreter { status, .. }) =
+            self;
+        status == "failed"
+    }
+
+    /// One-line description, e.g. "Searched the web: rust wasm"
+    pub fn summary(&self) -> String {
+        match self {
+            NativeToolCall::WebSearch { queries, .. } if queries.is_empty() => {
+                "Searched the web".to_string()
+            }
+            NativeToolCall::WebSearch { queries, .. } => {
+                format!("Searched the web: {}", queries.join(" · "))
+            }
+            NativeToolCall::CodeInterpreter { images, .. } if !images.is_empty() => {
+                format!("Ran code, made {} image(s)", images.len())
+            }
+            NativeToolCall::CodeInterpreter { .. } => "Ran code".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_native_output_items() {
+        let search = json!({
+            "type": "web_search_call",
+            "id": "ws_1",
+            "status": "completed",
+            "action": { "type": "search", "query": "rust wasm" }
+        });
+        assert_eq!(
+            NativeToolCall::from_output_item(&search),
+            Some(NativeToolCall::WebSearch {
+                queries: vec!["rust wasm".to_string()],
+                status: "completed".to_string(),
+            })
+        );
+
+        let code = json!({
+            "type": "code_interpreter_call",
+            "status": "completed",
+            "code": "print(2 + 2)",
+            "outputs": [
+                { "type": "logs", "logs": "4" },
+                { "type": "image", "url": "https://example.com/plot.png" }
+            ]
+        });
+        let call = NativeToolCall::from_output_item(&code).unwrap();
+        assert_eq!(call.summary(), "Ran code, made 1 image(s)");
+        assert!(matches!(call, NativeToolCall::CodeInterpreter { ref logs, .. } if logs == "4"));
+
+        assert_eq!(NativeToolCall::from_output_item(&json!({ "type": "message" })), None);
+    }
+}
@@ -0,0 +1,288 @@
+// Background worker for heavy work
+// Markdown parsing, token counting, word diffs and JSON pretty-printing of
+// large inputs run in a Web Worker (src/bin/worker.rs) so the page stays
+// responsive while long replies stream in. Small inputs, and every input once
+// the worker has failed to load or answer, are handled on the main thread
+// instead.
+
+use crate::llm_playground::{
+    logging,
+    markdown::{self, Block},
+    word_diff::{self, DiffOp},
+};
+use gloo::worker::{HandlerId, Spawnable, Worker, WorkerBridge, WorkerScope};
+use gloo_timers::callback::Timeout;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Script Trunk builds from src/bin/worker.rs, next to the page
+const WORKER_SCRIPT: &str = "worker.js";
+/// Inputs shorter than this are cheaper to handle than to send to the worker
+pub const OFFLOAD_MIN_CHARS: usize = 4_000;
+/// A worker that has not answered its first job by then is given up on
+const WORKER_TIMEOUT_MS: u32 = 10_000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum OffloadJob {
+    ParseMarkdown(String),
+    CountTokens(String),
+    DiffWords {
+        old: String,
+        new: String,
+    },
+    /// Compact JSON text to pretty-print
+    PrettyJson(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum OffloadResult {
+    Markdown(Vec<Block>),
+    Tokens(usize),
+    Diff(Vec<DiffOp>),
+    Json(String),
+}
+
+impl OffloadJob {
+    pub fn input_len(&self) -> usize {
+        match self {
+            OffloadJob::ParseMarkdown(text)
+            | OffloadJob::CountTokens(text)
+            | OffloadJob::PrettyJson(text) => text.len(),
+            OffloadJob::DiffWords { old, new } => old.len() + new.len(),
+        }
+    }
+
+    pub fn run(&self) -> OffloadResult {
+        match self {
+            OffloadJob::ParseMarkdown(text) => {
+                OffloadResult::Markdown(markdown::parse_blocks(text))
+            }
+            OffloadJob::CountTokens(text) => OffloadResult::Tokens(estimate_tokens(text)),
+            OffloadJob::DiffWords { old, new } => {
+                OffloadResult::Diff(word_diff::diff_words(old, new))
+            }
+            OffloadJob::PrettyJson(text) => OffloadResult::Json(
+                serde_json::from_str::<serde_json::Value>(text)
+                    .and_then(|value| serde_json::to_string_pretty(&value))
+                    .unwrap_or_else(|_| text.clone()),
+            ),
+        }
+    }
+}
+
+/// Rough token count: a token per four letters or digits of a word, and one
+/// per punctuation mark or CJK character
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut word_len = 0usize;
+    for ch in text.chars() {
+        if ch.is_alphanumeric() && !is_cjk(ch) {
+            word_len += 1;
+            continue;
+        }
+        tokens += word_len.div_ceil(4);
+        word_len = 0;
+        if !ch.is_whitespace() {
+            tokens += 1;
+        }
+    }
+    tokens + word_len.div_ceil(4)
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(ch as u32, 0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF)
+}
+
+/// Worker side: answers each job with its result, tagged with the job's id
+pub struct OffloadWorker;
+
+impl Worker for OffloadWorker {
+    type Message = ();
+    type Input = (u64, OffloadJob);
+    type Output = (u64, OffloadResult);
+
+    fn create(_scope: &WorkerScope<Self>) -> Self {
+        Self
+    }
+
+    fn update(&mut self, _scope: &WorkerScope<Self>, _msg: Self::Message) {}
+
+    fn received(&mut self, scope: &WorkerScope<Self>, (id, job): Self::Input, who: HandlerId) {
+        scope.respond(who, (id, job.run()));
+    }
+}
+
+struct Pending {
+    job: OffloadJob,
+    on_done: Box<dyn FnOnce(OffloadResult)>,
+}
+
+#[derive(Default)]
+struct Dispatcher {
+    bridge: Option<WorkerBridge<OffloadWorker>>,
+    /// Set once the worker has answered, after which it is waited for
+    answered: bool,
+    failed: bool,
+    next_id: u64,
+    pending: HashMap<u64, Pending>,
+}
+
+thread_local! {
+    static DISPATCHER: Rc<RefCell<Dispatcher>> = Rc::new(RefCell::new(Dispatcher::default()));
+}
+
+/// The worker script's URL for a page whose base URL is `base`, so it's found
+/// when Trunk builds with `--public-url /<repo>` too
+pub fn worker_url(base: &str) -> String {
+    let base = base.split(['#', '?']).next().unwrap_or(base);
+    let directory = base.rfind('/').map_or(base, |end| &base[..=end]);
+    format!("{}{}", directory, WORKER_SCRIPT)
+}
+
+/// Stop using the worker and handle every waiting job on the main thread
+fn give_up(reason: &str) {
+    let ids: Vec<u64> = DISPATCHER.with(|dispatcher| {
+        let mut state = dispatcher.borrow_mut();
+        if !state.failed {
+            logging::warn!("{}, working on the main thread", reason);
+            state.failed = true;
+            state.bridge = None;
+        }
+        state.pending.keys().copied().collect()
+    });
+    for id in ids {
+        finish(id, None);
+    }
+}
+
+/// The bridge can't report the worker's load errors, so look for its script
+/// ourselves and give up as soon as it is missing rather than at the timeout
+async fn check_worker_script(url: String) {
+    let found = match gloo_net::http::Request::get(&url).send().await {
+        Ok(response) => response.ok(),
+        Err(_) => false,
+    };
+    let answered = DISPATCHER.with(|dispatcher| dispatcher.borrow().answered);
+    if !found && !answered {
+        give_up(&format!("Background worker script {} could not be loaded", url));
+    }
+}
+
+fn finish(id: u64, result: Option<OffloadResult>) {
+    let pending = DISPATCHER.with(|dispatcher| {
+        let mut state = dispatcher.borrow_mut();
+        state.answered |= result.is_some();
+        state.pending.remove(&id)
+    });
+    if let Some(pending) = pending {
+        let result = result.unwrap_or_else(|| pending.job.run());
+        (pending.on_done)(result);
+    }
+}
+
+/// Run `job` and pass its result to `on_done`, in the worker when the input
+/// is large
+pub fn offload(job: OffloadJob, on_done: impl FnOnce(OffloadResult) + 'static) {
+    if job.input_len() < OFFLOAD_MIN_CHARS {
+        on_done(job.run());
+        return;
+    }
+    let dispatcher = DISPATCHER.with(Rc::clone);
+    let mut state = dispatcher.borrow_mut();
+    if state.failed {
+        drop(state);
+        on_done(job.run());
+        return;
+    }
+    if state.bridge.is_none() {
+        let base = gloo::utils::document().base_uri().ok().flatten().unwrap_or_default();
+        let url = worker_url(&base);
+        let bridge = OffloadWorker::spawner()
+            .callback(|(id, result)| finish(id, Some(result)))
+            .spawn(&url);
+        state.bridge = Some(bridge);
+        wasm_bindgen_futures::spawn_local(check_worker_script(url));
+    }
+
+    let id = state.next_id;
+    state.next_id += 1;
+    if let Some(bridge) = &state.bridge {
+        bridge.send((id, job.clone()));
+    }
+    state.pending.insert(
+        id,
+        Pending {
+            job,
+            on_done: Box::new(on_done),
+        },
+    );
+    drop(state);
+
+    Timeout::new(WORKER_TIMEOUT_MS, move || {
+        let timed_out = DISPATCHER.with(|dispatcher| {
+            let state = dispatcher.borrow();
+            !state.answered && state.pending.contains_key(&id)
+        });
+        if timed_out {
+            // Everything still waiting would time out as well
+            give_up("Background worker did not answer");
+        }
+    })
+    .forget();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jobs_run_on_the_main_thread_give_their_results() {
+        let markdown = OffloadJob::ParseMarkdown("# Title".to_string());
+        assert_eq!(
+            markdown.run(),
+            OffloadResult::Markdown(vec![Block::Heading1("Title".to_string())])
+        );
+        assert_eq!(
+            OffloadJob::PrettyJson("{\"a\":1}".to_string()).run(),
+            OffloadResult::Json("{\n  \"a\": 1\n}".to_string())
+        );
+        // Text that is not JSON comes back unchanged
+        assert_eq!(
+            OffloadJob::PrettyJson("not json".to_string()).run(),
+            OffloadResult::Json("not json".to_string())
+        );
+        let diff = OffloadJob::DiffWords {
+            old: "a b".to_string(),
+            new: "a c".to_string(),
+        };
+        assert_eq!(diff.input_len(), 6);
+        assert!(matches!(diff.run(), OffloadResult::Diff(ops) if !ops.is_empty()));
+    }
+
+    #[test]
+    fn worker_script_is_found_next_to_the_page() {
+        assert_eq!(worker_url("http://127.0.0.1:8080/"), "http://127.0.0.1:8080/worker.js");
+        assert_eq!(
+            worker_url("https://me.github.io/llm-playground-rs/index.html#/session/1"),
+            "https://me.github.io/llm-playground-rs/worker.js"
+        );
+        assert_eq!(
+            worker_url("https://me.github.io/llm-playground-rs/?view=1"),
+            "https://me.github.io/llm-playground-rs/worker.js"
+        );
+    }
+
+    #[test]
+    fn token_estimate_counts_words_punctuation_and_cjk() {
+        assert_eq!(estimate_tokens(""), 0);
+        // "Hello" is two tokens, "," and "!" one each, "world" two
+        assert_eq!(estimate_tokens("Hello, world!"), 6);
+        assert_eq!(estimate_tokens("你好"), 2);
+        assert_eq!(
+            OffloadJob::CountTokens("a b c".to_string()).run(),
+            OffloadResult::Tokens(3)
+        );
+    }
+}
@@ -0,0 +1,329 @@
+// Text extraction from PDFs
+// A best-effort reader for models that can't take PDF files: it finds the
+// content streams, inflates them with the browser's DecompressionStream and
+// collects the strings the text operators show. Scanned pages and fonts with
+// custom encodings come out empty or garbled.
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+/// A stream in the file, still compressed if `flate`
+#[derive(Debug, PartialEq)]
+pub struct RawStream<'a> {
+    pub flate: bool,
+    pub data: &'a [u8],
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|at| at + from)
+}
+
+fn rfind(haystack: &[u8], needle: &[u8], before: usize) -> Option<usize> {
+    haystack[..before.min(haystack.len())]
+        .windows(needle.len())
+        .rposition(|window| window == needle)
+}
+
+/// Streams that may hold page text: images, fonts, object streams and
+/// cross-reference streams are skipped
+pub fn streams(pdf: &[u8]) -> Vec<RawStream<'_>> {
+    const SKIPPED: [&str; 7] = ["/Image", "/XRef", "/ObjStm", "/Length1", "/FontFile", "/DCTDecode", "/JPXDecode"];
+    let mut found = Vec::new();
+    let mut at = 0;
+    while let Some(keyword) = find(pdf, b"stream", at) {
+        at = keyword + b"stream".len();
+        // Only "stream" right after a dictionary starts one; "endstream" doesn't
+        let before = pdf[..keyword].iter().rposition(|b| !b.is_ascii_whitespace());
+        if before.is_none_or(|end| end < 1 || &pdf[end - 1..=end] != b">>") {
+            continue;
+        }
+        let start = match pdf.get(at..at + 2) {
+            Some([b'\r', b'\n']) => at + 2,
+            Some([b'\n', _]) | Some([b'\r', _]) => at + 1,
+            _ => continue,
+        };
+        let Some(end) = find(pdf, b"endstream", start) else {
+            break;
+        };
+        let dictionary_start = rfind(pdf, b"obj", keyword).unwrap_or(0);
+        let dictionary = String::from_utf8_lossy(&pdf[dictionary_start..keyword]);
+        let compact: String = dictionary.split_whitespace().collect();
+        if !SKIPPED.iter().any(|name| compact.contains(name)) {
+            let mut data = &pdf[start..end];
+            while let [rest @ .., b'\r' | b'\n'] = data {
+                data = rest;
+            }
+            found.push(RawStream {
+                flate: compact.contains("/FlateDecode"),
+                data,
+            });
+        }
+        at = end + b"endstream".len();
+    }
+    found
+}
+
+/// Bytes of a PDF string shown as text: UTF-16 with a byte order mark, or
+/// else one byte per character
+fn decode(bytes: &[u8]) -> String {
+    if let [0xFE, 0xFF, rest @ ..] = bytes {
+        let units: Vec<u16> = rest.chunks(2).map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)])).collect();
+        return String::from_utf16_lossy(&units);
+    }
+    bytes
+        .iter()
+        .map(|&byte| byte as char)
+        .filter(|c| !c.is_control() || c.is_whitespace())
+        .collect()
+}
+
+fn literal_string(content: &[u8], at: &mut usize) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut depth = 1;
+    while let Some(&byte) = content.get(*at) {
+        *at += 1;
+        match byte {
+            b'\\' => {
+                let Some(&escaped) = content.get(*at) else { break };
+                *at += 1;
+                match escaped {
+                    b'n' => bytes.push(b'\n'),
+                    b'r' => bytes.push(b'\r'),
+                    b't' => bytes.push(b'\t'),
+                    b'b' | b'f' | b'\n' | b'\r' => {}
+                    b'0'..=b'7' => {
+                        let mut value = (escaped - b'0') as u32;
+                        for _ in 0..2 {
+                            match content.get(*at) {
+                                Some(&digit @ b'0'..=b'7') => {
+                                    value = value * 8 + (digit - b'0') as u32;
+                                    *at += 1;
+                                }
+                                _ => break,
+                            }
+                        }
+                        bytes.push(value as u8);
+                    }
+                    other => bytes.push(other),
+                }
+            }
+            b'(' => {
+                depth += 1;
+                bytes.push(byte);
+            }
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+                bytes.push(byte);
+            }
+            _ => bytes.push(byte),
+        }
+    }
+    bytes
+}
+
+fn hex_string(content: &[u8], at: &mut usize) -> Vec<u8> {
+    let mut digits = Vec::new();
+    while let Some(&byte) = content.get(*at) {
+        *at += 1;
+        if byte == b'>' {
+            break;
+        }
+        if let Some(digit) = (byte as char).to_digit(16) {
+            digits.push(digit as u8);
+        }
+    }
+    digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+        .collect()
+}
+
+enum Operand {
+    Number(f64),
+    Text(String),
+    /// A TJ array: its strings, with spaces where the kerning leaves a gap
+    Array(String),
+}
+
+/// The text a page content stream shows, a line per text line
+pub fn text_from_content(content: &[u8]) -> String {
+    let mut text = String::new();
+    let mut operands: Vec<Operand> = Vec::new();
+    let mut array: Option<String> = None;
+    let mut at = 0;
+    let new_line = |text: &mut String| {
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+    };
+
+    while let Some(&byte) = content.get(at) {
+        at += 1;
+        match byte {
+            // Dictionaries and names carry no text
+            b'<' if content.get(at) == Some(&b'<') => at += 1,
+            b'/' => {
+                while content.get(at).is_some_and(|b| !b.is_ascii_whitespace() && !b"/[]()<>".contains(b)) {
+                    at += 1;
+                }
+            }
+            b'(' | b'<' => {
+                let bytes = if byte == b'(' {
+                    literal_string(content, &mut at)
+                } else {
+                    hex_string(content, &mut at)
+                };
+                let decoded = decode(&bytes);
+                match &mut array {
+                    Some(shown) => shown.push_str(&decoded),
+                    None => operands.push(Operand::Text(decoded)),
+                }
+            }
+            b'[' => array = Some(String::new()),
+            b']' => {
+                if let Some(shown) = array.take() {
+                    operands.push(Operand::Array(shown));
+                }
+            }
+            b'%' => {
+                while content.get(at).is_some_and(|b| *b != b'\n' && *b != b'\r') {
+                    at += 1;
+                }
+            }
+            b'0'..=b'9' | b'-' | b'+' | b'.' => {
+                let start = at - 1;
+                while content.get(at).is_some_and(|b| b.is_ascii_digit() || *b == b'.') {
+                    at += 1;
+                }
+                let number = std::str::from_utf8(&content[start..at])
+                    .ok()
+                    .and_then(|n| n.parse::<f64>().ok())
+                    .unwrap_or(0.0);
+                match &mut array {
+                    // Kerning this wide is a gap between words
+                    Some(shown) if number < -200.0 && !shown.ends_with(' ') => shown.push(' '),
+                    Some(_) => {}
+                    None => operands.push(Operand::Number(number)),
+                }
+            }
+            b'\'' | b'"' => {
+                new_line(&mut text);
+                if let Some(Operand::Text(shown)) = operands.pop() {
+                    text.push_str(&shown);
+                }
+                operands.clear();
+            }
+            b if b.is_ascii_alphabetic() || b == b'*' => {
+                let start = at - 1;
+                while content.get(at).is_some_and(|b| b.is_ascii_alphabetic() || *b == b'*') {
+                    at += 1;
+                }
+                match &content[start..at] {
+                    b"Tj" => {
+                        if let Some(Operand::Text(shown)) = operands.last() {
+                            text.push_str(shown);
+                        }
+                    }
+                    b"TJ" => {
+                        if let Some(Operand::Array(shown)) = operands.last() {
+                            text.push_str(shown);
+                        }
+                    }
+                    b"T*" | b"ET" => new_line(&mut text),
+                    b"Td" | b"TD" => match operands.as_slice() {
+                        [.., Operand::Number(_), Operand::Number(y)] if *y != 0.0 => new_line(&mut text),
+                        _ if !text.ends_with([' ', '\n']) && !text.is_empty() => text.push(' '),
+                        _ => {}
+                    },
+                    _ => {}
+                }
+                operands.clear();
+            }
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Inflate a FlateDecode stream with the browser's DecompressionStream
+async fn inflate(data: &[u8]) -> Result<Vec<u8>, String> {
+    let error = |e: wasm_bindgen::JsValue| format!("{:?}", e);
+    let constructor = js_sys::Reflect::get(&js_sys::global(), &"DecompressionStream".into()).map_err(error)?;
+    if constructor.is_undefined() {
+        return Err("This browser can't decompress PDF streams".to_string());
+    }
+    let decompressor = js_sys::Reflect::construct(
+        constructor.unchecked_ref::<js_sys::Function>(),
+        &js_sys::Array::of1(&"deflate".into()),
+    )
+    .map_err(error)?;
+    let mut bytes = data.to_vec();
+    let source = web_sys::Response::new_with_opt_u8_array(Some(&mut bytes)).map_err(error)?;
+    let body = source.body().ok_or("Empty stream")?;
+    let inflated = body.pipe_through(decompressor.unchecked_ref());
+    let response = web_sys::Response::new_with_opt_readable_stream(Some(&inflated)).map_err(error)?;
+    let buffer = JsFuture::from(response.array_buffer().map_err(error)?)
+        .await
+        .map_err(error)?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+/// The text of a PDF; `on_progress` gets the streams read so far and their count
+pub async fn extract_text(pdf: &[u8], on_progress: impl Fn(usize, usize)) -> String {
+    let streams = streams(pdf);
+    let mut pages = Vec::new();
+    for (index, stream) in streams.iter().enumerate() {
+        on_progress(index, streams.len());
+        let content = if stream.flate {
+            // A stream that won't inflate is skipped rather than failing the file
+            match inflate(stream.data).await {
+                Ok(content) => content,
+                Err(_) => continue,
+            }
+        } else {
+            stream.data.to_vec()
+        };
+        let text = text_from_content(&content);
+        if !text.trim().is_empty() {
+            pages.push(text);
+        }
+    }
+    on_progress(streams.len(), streams.len());
+    pages
+        .join("\n")
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_content_streams_and_skips_images() {
+        let pdf = b"%PDF-1.4\n4 0 obj\n<< /Length 44 >>\nstream\nBT /F1 12 Tf 72 712 Td (Hello) Tj ET\nendstream\nendobj\n\
+5 0 obj\n<< /Type /XObject /Subtype /Image /Filter /DCTDecode >>\nstream\n\xff\xd8\xff\nendstream\nendobj\n\
+6 0 obj\n<< /Filter /FlateDecode /Length 3 >>\nstream\r\nabc\r\nendstream\nendobj\n";
+        let found = streams(pdf);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0], RawStream { flate: false, data: b"BT /F1 12 Tf 72 712 Td (Hello) Tj ET" });
+        assert_eq!(found[1], RawStream { flate: true, data: b"abc" });
+    }
+
+    #[test]
+    fn collects_shown_text() {
+        let content = b"BT /F1 12 Tf 72 712 Td (Quarterly \\(draft\\)) Tj 0 -14 Td [(Reve) 20 (nue) -300 (grew)] TJ T* <48692E> Tj ET";
+        assert_eq!(text_from_content(content), "Quarterly (draft)\nRevenue grew\nHi.\n");
+    }
+}
@@ -0,0 +1,179 @@
+// Personas
+// A persona bundles a system prompt, a default model, the tools it may call
+// and a temperature under a name and an emoji. A session started from a
+// persona keeps its name; its requests then use the persona's prompt, tools
+// and temperature instead of the global ones. The model is only the one the
+// session starts with and can still be switched.
+use crate::llm_playground::{provider_config::FlexibleApiConfig, ChatSession};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Persona {
+    pub name: String,
+    /// An emoji shown next to the name
+    #[serde(default)]
+    pub avatar: String,
+    pub system_prompt: String,
+    /// Model new sessions start with; None keeps the current one
+    #[serde(default)]
+    pub model: Option<String>, // Format: "provider_name,model_name"
+    /// Names of the function tools the persona may call
+    #[serde(default)]
+    pub enabled_tools: Vec<String>,
+    /// None keeps the global temperature
+    #[serde(default)]
+    pub temperature: Option<f32>,
+}
+
+impl Persona {
+    pub fn label(&self) -> String {
+        if self.avatar.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.avatar, self.name)
+        }
+    }
+
+    /// The default model as (provider_name, model_name)
+    pub fn model(&self) -> Option<(String, String)> {
+        self.model
+            .as_deref()
+            .and_then(|model| model.split_once(','))
+            .map(|(provider, model)| (provider.to_string(), model.to_string()))
+    }
+
+    /// Check the persona before saving it
+    pub fn validate(&self, config: &FlexibleApiConfig) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Give the persona a name".to_string());
+        }
+        if let Some((provider, model)) = self.model() {
+            let known = config
+                .get_provider(&provider)
+                .is_some_and(|p| p.models.contains(&model));
+            if !known {
+                return Err(format!("Unknown model {} - {}", provider, model));
+            }
+        }
+        if let Some(missing) = self
+            .enabled_tools
+            .iter()
+            .find(|name| !config.function_tools.iter().any(|tool| tool.name == **name))
+        {
+            return Err(format!("Unknown tool {}", missing));
+        }
+        Ok(())
+    }
+
+    /// `config` with the persona's prompt, tools and temperature
+    pub fn apply(&self, config: &FlexibleApiConfig) -> FlexibleApiConfig {
+        let mut config = config.clone();
+        config.system_prompt = self.system_prompt.clone();
+        for tool in &mut config.function_tools {
+            tool.enabled = self.enabled_tools.contains(&tool.name);
+        }
+        if let Some(temperature) = self.temperature {
+            config.shared_settings.temperature = temperature;
+        }
+        config
+    }
+}
+
+/// The persona a session was started from, if it still exists
+pub fn for_session<'a>(
+    config: &'a FlexibleApiConfig,
+    session: Option<&ChatSession>,
+) -> Option<&'a Persona> {
+    let name = session?.persona.as_deref()?;
+    config.personas.iter().find(|persona| persona.name == name)
+}
+
+/// The config requests in `session` are sent with
+pub fn config_for_session(
+    config: &FlexibleApiConfig,
+    session: Option<&ChatSession>,
+) -> FlexibleApiConfig {
+    match for_session(config, session) {
+        Some(persona) => persona.apply(config),
+        None => config.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn persona(config: &FlexibleApiConfig) -> Persona {
+        let provider = &config.providers[0];
+        Persona {
+            name: "Reviewer".to_string(),
+            avatar: "🧐".to_string(),
+            system_prompt: "Review the code.".to_string(),
+            model: Some(format!("{},{}", provider.name, provider.models[0])),
+            enabled_tools: vec![config.function_tools[0].name.clone()],
+            temperature: Some(0.2),
+        }
+    }
+
+    #[test]
+    fn personas_override_prompt_tools_and_temperature() {
+        let config = FlexibleApiConfig::default();
+        let persona = persona(&config);
+        let applied = persona.apply(&config);
+        assert_eq!(applied.system_prompt, "Review the code.");
+        assert_eq!(applied.shared_settings.temperature, 0.2);
+        let enabled: Vec<_> = applied
+            .function_tools
+            .iter()
+            .filter(|tool| tool.enabled)
+            .map(|tool| tool.name.as_str())
+            .collect();
+        assert_eq!(enabled, [config.function_tools[0].name.as_str()]);
+        assert_eq!(persona.label(), "🧐 Reviewer");
+        assert_eq!(
+            persona.model(),
+            Some((
+                config.providers[0].name.clone(),
+                config.providers[0].models[0].clone()
+            ))
+        );
+    }
+
+    #[test]
+    fn unknown_models_and_tools_are_rejected() {
+        let mut config = FlexibleApiConfig::default();
+        let mut persona = persona(&config);
+        assert!(persona.validate(&config).is_ok());
+
+        persona.model = Some("nowhere,nothing".to_string());
+        assert!(persona.validate(&config).is_err());
+        persona.model = None;
+        persona.enabled_tools.push("missing_tool".to_string());
+        assert!(persona.validate(&config).is_err());
+
+        // Sessions only use personas that still exist
+        persona.enabled_tools.pop();
+        config.personas.push(persona);
+        let mut session = ChatSession {
+            id: "s".to_string(),
+            title: "Review".to_string(),
+            messages: Vec::new(),
+            created_at: 0.0,
+            updated_at: 0.0,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: false,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: Some("Reviewer".to_string()),
+            incognito: false,
+            older_pages: 0,
+        };
+        assert!(for_session(&config, Some(&session)).is_some());
+        session.persona = Some("Gone".to_string());
+        assert_eq!(config_for_session(&config, Some(&session)), config);
+    }
+}
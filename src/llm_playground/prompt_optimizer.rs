@@ -0,0 +1,119 @@
+// Prompt optimizer
+// Rewrites the draft in the input bar with the current model. The draft and the
+// last few chat messages go out with a meta-prompt; the reply is expected to
+// carry the rewritten prompt between <prompt> tags.
+
+use crate::llm_playground::{
+    flexible_client::FlexibleLLMClient, provider_config::FlexibleApiConfig, Message, MessageRole,
+};
+
+/// Recent chat messages given to the model as context for the rewrite
+pub const CONTEXT_MESSAGES: usize = 6;
+/// Characters of each context message included
+const CONTEXT_MESSAGE_CHARS: usize = 1000;
+
+pub const META_PROMPT: &str =
+    "You are an expert prompt engineer. Rewrite the user's draft message \
+so a language model answers it as well as possible: make the goal and any constraints explicit, \
+add missing context from the conversation, specify the desired output format when it helps, and \
+remove ambiguity. Keep the user's intent, language and voice; don't answer the prompt and don't \
+invent requirements. Reply with only the improved prompt between <prompt> and </prompt>.";
+
+/// The single user message asking for the rewrite
+pub fn build_request(draft: &str, recent: &[Message]) -> String {
+    let context: Vec<String> = recent
+        .iter()
+        .filter(|m| matches!(m.role, MessageRole::User | MessageRole::Assistant))
+        .filter(|m| !m.content.trim().is_empty())
+        .rev()
+        .take(CONTEXT_MESSAGES)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .map(|m| {
+            let role = if m.role == MessageRole::User {
+                "User"
+            } else {
+                "Assistant"
+            };
+            let content: String = m.content.chars().take(CONTEXT_MESSAGE_CHARS).collect();
+            format!("{}: {}", role, content)
+        })
+        .collect();
+
+    let mut request = String::new();
+    if !context.is_empty() {
+        request.push_str("Recent conversation:\n");
+        request.push_str(&context.join("\n\n"));
+        request.push_str("\n\n");
+    }
+    request.push_str("Draft to improve:\n");
+    request.push_str(draft.trim());
+    request
+}
+
+/// The rewritten prompt from the model's reply: the text between the
+/// <prompt> tags, or the whole reply when the tags are missing
+pub fn extract_prompt(reply: &str) -> String {
+    let inner = reply
+        .split_once("<prompt>")
+        .map(|(_, rest)| {
+            rest.split_once("</prompt>")
+                .map_or(rest, |(inner, _)| inner)
+        })
+        .unwrap_or(reply);
+    inner.trim().to_string()
+}
+
+/// Ask the config's current model for an improved version of the draft
+pub async fn optimize(
+    client: &FlexibleLLMClient,
+    config: &FlexibleApiConfig,
+    draft: &str,
+    recent: &[Message],
+) -> Result<String, String> {
+    let mut config = config.clone();
+    config.system_prompt = META_PROMPT.to_string();
+    config.active_structured_output = None;
+    for tool in &mut config.function_tools {
+        tool.enabled = false;
+    }
+
+    let message = Message {
+        id: format!("optimize_{}", js_sys::Date::now() as u64),
+        timestamp: js_sys::Date::now(),
+        ..Message::new(MessageRole::User, build_request(draft, recent))
+    };
+    let response = client.send_message(&[message], &config).await?;
+    let improved = extract_prompt(&response.content.unwrap_or_default());
+    if improved.is_empty() {
+        return Err("The model returned an empty prompt".to_string());
+    }
+    Ok(improved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_includes_recent_context_in_order() {
+        let mut recent = vec![Message::new(MessageRole::System, "ignored")];
+        recent.extend((0..8).map(|i| Message::new(MessageRole::User, &format!("turn {}", i))));
+        let request = build_request("  fix my code ", &recent);
+        assert!(request.starts_with("Recent conversation:\nUser: turn 2\n\nUser: turn 3"));
+        assert!(request.ends_with("User: turn 7\n\nDraft to improve:\nfix my code"));
+        assert!(!request.contains("ignored"));
+        assert_eq!(build_request("hi", &[]), "Draft to improve:\nhi");
+    }
+
+    #[test]
+    fn extracts_prompt_between_tags() {
+        assert_eq!(
+            extract_prompt("Here you go:\n<prompt>\nExplain X.\n</prompt>\nHope it helps"),
+            "Explain X."
+        );
+        assert_eq!(extract_prompt("<prompt>Unclosed"), "Unclosed");
+        assert_eq!(extract_prompt("  Just the prompt  "), "Just the prompt");
+    }
+}
@@ -1,6 +1,31 @@
 // New flexible provider configuration system
+use crate::llm_playground::api_clients::{
+    gemini_auth::GeminiAuth,
+    huggingface_client::{self, HUGGINGFACE_TRANSFORMER},
+    mock_provider::MOCK_TRANSFORMER,
+    provider_plugin::{PluginEndpoints, ProviderPlugin},
+    MockScript,
+};
+use crate::llm_playground::browser_notifications::BrowserNotificationSettings;
+use crate::llm_playground::budget::BudgetSettings;
+use crate::llm_playground::conversation_starters::StarterSettings;
+use crate::llm_playground::follow_ups::FollowUpSettings;
+use crate::llm_playground::guardrails::GuardrailRule;
+use crate::llm_playground::code_sandbox::CodeSandboxSettings;
+use crate::llm_playground::desktop_tools::DesktopToolSettings;
+use crate::llm_playground::fetch_tool::FetchToolSettings;
+use crate::llm_playground::moderation::ModerationSettings;
+use crate::llm_playground::redaction::RedactionSettings;
+use crate::llm_playground::response_length::ResponseLengthSettings;
+use crate::llm_playground::self_consistency::SelfConsistencySettings;
+use crate::llm_playground::tool_selection::ToolSelectionSettings;
+use crate::llm_playground::web_fetch::WebFetchSettings;
+use crate::llm_playground::web_search::WebSearchSettings;
+use crate::llm_playground::webhook::WebhookSettings;
 use crate::llm_playground::mcp_client::McpConfig;
+use crate::llm_playground::personas::Persona;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ProviderConfig {
@@ -9,6 +34,141 @@ pub struct ProviderConfig {
     pub api_key: String,
     pub models: Vec<String>,
     pub transformer: TransformerConfig,
+    // Optional per-model generation defaults, keyed by model name
+    #[serde(default)]
+    pub model_defaults: HashMap<String, ModelDefaults>,
+    // Scripted replies for providers using the "mock" transformer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mock_script: Option<MockScript>,
+    // Auth scheme and endpoints of providers added from a JSON descriptor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<ProviderPlugin>,
+    // AI Studio key or Vertex AI project for providers using the "gemini" transformer
+    #[serde(default, skip_serializing_if = "GeminiAuth::is_api_key")]
+    pub gemini_auth: GeminiAuth,
+}
+
+impl ProviderConfig {
+    /// Ollama, the mock provider, self-hosted TGI servers and plugins without
+    /// auth run without an API key
+    pub fn needs_api_key(&self) -> bool {
+        self.name != "ollama"
+            && !self.transformer.r#use.iter().any(|t| t == MOCK_TRANSFORMER)
+            && (!self.transformer.r#use.iter().any(|t| t == HUGGINGFACE_TRANSFORMER)
+                || huggingface_client::is_hosted(&self.api_base_url))
+            && self.plugin.as_ref().is_none_or(ProviderPlugin::needs_api_key)
+    }
+}
+
+/// Highest temperature any supported provider accepts
+pub const MAX_TEMPERATURE_CAP: f32 = 2.0;
+
+/// Generation defaults applied when a model is selected
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelDefaults {
+    pub max_tokens: Option<u32>,
+    pub max_temperature: Option<f32>,
+    pub context_window: Option<u32>,
+    /// Price per million input tokens
+    #[serde(default)]
+    pub input_price: Option<f64>,
+    /// Price per million output tokens
+    #[serde(default)]
+    pub output_price: Option<f64>,
+}
+
+impl ModelDefaults {
+    /// Check whether no default is set
+    pub fn is_empty(&self) -> bool {
+        self.max_tokens.is_none()
+            && self.max_temperature.is_none()
+            && self.context_window.is_none()
+            && self.input_price.is_none()
+            && self.output_price.is_none()
+    }
+
+    /// The defaults with values no model accepts dropped: zero token counts,
+    /// negative prices, and a temperature cap outside 0 to 2 is pulled into it.
+    /// Max tokens never exceed the context window.
+    pub fn clamped(self) -> Self {
+        let context_window = self.context_window.filter(|&tokens| tokens > 0);
+        let max_tokens = self
+            .max_tokens
+            .filter(|&tokens| tokens > 0)
+            .map(|tokens| context_window.map_or(tokens, |window| tokens.min(window)));
+        let price = |price: Option<f64>| price.filter(|price| price.is_finite() && *price >= 0.0);
+        Self {
+            max_tokens,
+            max_temperature: self
+                .max_temperature
+                .filter(|cap| cap.is_finite())
+                .map(|cap| cap.clamp(0.0, MAX_TEMPERATURE_CAP)),
+            context_window,
+            input_price: price(self.input_price),
+            output_price: price(self.output_price),
+        }
+    }
+
+    /// Estimated cost of a request, None when the model has no prices
+    pub fn cost(&self, input_tokens: u32, output_tokens: u32) -> Option<f64> {
+        if self.input_price.is_none() && self.output_price.is_none() {
+            return None;
+        }
+        Some(
+            (input_tokens as f64 * self.input_price.unwrap_or_default()
+                + output_tokens as f64 * self.output_price.unwrap_or_default())
+                / 1_000_000.0,
+        )
+    }
+
+    /// Short human-readable summary used as a hint in the UI
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(context_window) = self.context_window {
+            parts.push(format!("{} ctx", format_token_count(context_window)));
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            parts.push(format!("{} max out", format_token_count(max_tokens)));
+        }
+        if let Some(max_temperature) = self.max_temperature {
+            parts.push(format!("temp ≤ {:.1}", max_temperature));
+        }
+        if self.input_price.is_some() || self.output_price.is_some() {
+            parts.push(format!(
+                "{}/{} per 1M",
+                self.input_price.unwrap_or_default(),
+                self.output_price.unwrap_or_default()
+            ));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Format a token count compactly, e.g. 128000 -> "128k"
+pub fn format_token_count(tokens: u32) -> String {
+    if tokens >= 1_000_000 && tokens.is_multiple_of(1_000_000) {
+        format!("{}M", tokens / 1_000_000)
+    } else if tokens >= 1_000 {
+        format!("{}k", tokens / 1_000)
+    } else {
+        tokens.to_string()
+    }
+}
+
+/// Build a model defaults map with only the context window set
+fn context_windows(entries: &[(&str, u32)]) -> HashMap<String, ModelDefaults> {
+    entries
+        .iter()
+        .map(|(model, context_window)| {
+            (
+                model.to_string(),
+                ModelDefaults {
+                    context_window: Some(*context_window),
+                    ..Default::default()
+                },
+            )
+        })
+        .collect()
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -37,6 +197,71 @@ pub struct FlexibleApiConfig {
     pub mcp_config: McpConfig,
     // Session-specific settings
     pub current_session_provider: Option<String>, // Format: "provider_name,model_name"
+    // Model used by the Task tool's sub-agents; None runs them on the chat's model
+    #[serde(default)]
+    pub sub_agent_model: Option<String>, // Format: "provider_name,model_name"
+    // Embeddings model for the knowledge base; the model name need not be in the provider's chat models
+    #[serde(default)]
+    pub embedding_model: Option<String>, // Format: "provider_name,model_name"
+    // Name of the structured output the next replies must follow; None for free text
+    #[serde(default)]
+    pub active_structured_output: Option<String>,
+    // Keyword and regex rules checked against assistant replies
+    #[serde(default)]
+    pub guardrails: Vec<GuardrailRule>,
+    #[serde(default)]
+    pub moderation: ModerationSettings,
+    // Real filesystem and shell tools in the desktop build
+    #[serde(default)]
+    pub desktop_tools: DesktopToolSettings,
+    // Format and size of what the fetch tool returns
+    #[serde(default)]
+    pub fetch_tool: FetchToolSettings,
+    // Model and page size the WebFetch tool reads pages with
+    #[serde(default)]
+    pub web_fetch: WebFetchSettings,
+    // Search API the WebSearch tool queries
+    #[serde(default)]
+    pub web_search: WebSearchSettings,
+    // Time and output limits of the run_python and run_js tools
+    #[serde(default)]
+    pub code_sandbox: CodeSandboxSettings,
+    // Token, cost and tool call caps the agent loop pauses at
+    #[serde(default)]
+    pub budget: BudgetSettings,
+    /// Hide the time, model, tokens and latency under each message
+    #[serde(default)]
+    pub hide_message_footer: bool,
+    /// Collapse reasoning sections away unless shown on the message
+    #[serde(default)]
+    pub hide_reasoning: bool,
+    // Prompt, model, tools and temperature bundles new sessions can start from
+    #[serde(default)]
+    pub personas: Vec<Persona>,
+    // Prompts offered on an empty session
+    #[serde(default)]
+    pub conversation_starters: StarterSettings,
+    // Questions suggested under the latest reply
+    #[serde(default)]
+    pub follow_ups: FollowUpSettings,
+    // Sensitive data checked for in typed messages before they are sent
+    #[serde(default)]
+    pub redaction: RedactionSettings,
+    // Length preset picked in the chat header
+    #[serde(default)]
+    pub response_length: ResponseLengthSettings,
+    // Sample several replies and answer with their consensus
+    #[serde(default)]
+    pub self_consistency: SelfConsistencySettings,
+    // Have a model pick the tools each message needs
+    #[serde(default)]
+    pub tool_selection: ToolSelectionSettings,
+    // URL posted to when a reply completes or fails
+    #[serde(default)]
+    pub webhook: WebhookSettings,
+    // System notification when a long run finishes in a background tab
+    #[serde(default)]
+    pub browser_notifications: BrowserNotificationSettings,
 }
 
 // Re-export from types to avoid duplication
@@ -64,6 +289,10 @@ impl Default for FlexibleApiConfig {
                     transformer: TransformerConfig {
                         r#use: vec!["openai".to_string()],
                     },
+                    mock_script: None,
+                    plugin: None,
+                    gemini_auth: Default::default(),
+                    model_defaults: HashMap::new(),
                 },
                 ProviderConfig {
                     name: "gemini".to_string(),
@@ -81,6 +310,18 @@ impl Default for FlexibleApiConfig {
                     transformer: TransformerConfig {
                         r#use: vec!["gemini".to_string()],
                     },
+                    mock_script: None,
+                    plugin: None,
+                    gemini_auth: Default::default(),
+                    model_defaults: context_windows(&[
+                        ("gemini-2.5-flash", 1_048_576),
+                        ("gemini-2.5-pro", 1_048_576),
+                        ("gemini-2.5-flash-lite", 1_048_576),
+                        ("gemini-1.5-flash", 1_048_576),
+                        ("gemini-1.5-pro", 2_097_152),
+                        ("gemini-2.0-flash", 1_048_576),
+                        ("gemini-2.0-flash-lite", 1_048_576),
+                    ]),
                 },
                 ProviderConfig {
                     name: "gemini-openai".to_string(),
@@ -98,6 +339,18 @@ impl Default for FlexibleApiConfig {
                     transformer: TransformerConfig {
                         r#use: vec!["openai".to_string()],
                     },
+                    mock_script: None,
+                    plugin: None,
+                    gemini_auth: Default::default(),
+                    model_defaults: context_windows(&[
+                        ("gemini-2.5-flash", 1_048_576),
+                        ("gemini-2.5-pro", 1_048_576),
+                        ("gemini-2.5-flash-lite", 1_048_576),
+                        ("gemini-1.5-flash", 1_048_576),
+                        ("gemini-1.5-pro", 2_097_152),
+                        ("gemini-2.0-flash", 1_048_576),
+                        ("gemini-2.0-flash-lite", 1_048_576),
+                    ]),
                 },
                 ProviderConfig {
                     name: "openai".to_string(),
@@ -112,6 +365,110 @@ impl Default for FlexibleApiConfig {
                     transformer: TransformerConfig {
                         r#use: vec!["openai".to_string()],
                     },
+                    mock_script: None,
+                    plugin: None,
+                    gemini_auth: Default::default(),
+                    model_defaults: context_windows(&[
+                        ("gpt-4o", 128_000),
+                        ("gpt-4o-mini", 128_000),
+                        ("gpt-4-turbo", 128_000),
+                        ("gpt-3.5-turbo", 16_385),
+                    ]),
+                },
+                ProviderConfig {
+                    name: "groq".to_string(),
+                    api_base_url: "https://api.groq.com/openai/v1".to_string(),
+                    api_key: String::new(),
+                    models: vec![
+                        "llama-3.3-70b-versatile".to_string(),
+                        "llama-3.1-8b-instant".to_string(),
+                        "openai/gpt-oss-120b".to_string(),
+                        "openai/gpt-oss-20b".to_string(),
+                    ],
+                    transformer: TransformerConfig {
+                        r#use: vec!["openai".to_string()],
+                    },
+                    mock_script: None,
+                    plugin: None,
+                    gemini_auth: Default::default(),
+                    model_defaults: context_windows(&[
+                        ("llama-3.3-70b-versatile", 131_072),
+                        ("llama-3.1-8b-instant", 131_072),
+                        ("openai/gpt-oss-120b", 131_072),
+                        ("openai/gpt-oss-20b", 131_072),
+                    ]),
+                },
+                ProviderConfig {
+                    name: "grok".to_string(),
+                    api_base_url: "https://api.x.ai/v1".to_string(),
+                    api_key: String::new(),
+                    models: vec![
+                        "grok-4".to_string(),
+                        "grok-3".to_string(),
+                        "grok-3-mini".to_string(),
+                    ],
+                    transformer: TransformerConfig {
+                        r#use: vec!["openai".to_string()],
+                    },
+                    mock_script: None,
+                    plugin: None,
+                    gemini_auth: Default::default(),
+                    model_defaults: context_windows(&[
+                        ("grok-4", 256_000),
+                        ("grok-3", 131_072),
+                        ("grok-3-mini", 131_072),
+                    ]),
+                },
+                ProviderConfig {
+                    name: "perplexity".to_string(),
+                    api_base_url: "https://api.perplexity.ai".to_string(),
+                    api_key: String::new(),
+                    models: vec![
+                        "sonar".to_string(),
+                        "sonar-pro".to_string(),
+                        "sonar-reasoning".to_string(),
+                        "sonar-reasoning-pro".to_string(),
+                    ],
+                    transformer: TransformerConfig {
+                        r#use: vec!["openai".to_string()],
+                    },
+                    mock_script: None,
+                    // The API has no model list endpoint
+                    plugin: Some(ProviderPlugin {
+                        auth: Default::default(),
+                        endpoints: PluginEndpoints {
+                            models: None,
+                            ..Default::default()
+                        },
+                    }),
+                    gemini_auth: Default::default(),
+                    model_defaults: context_windows(&[
+                        ("sonar", 127_072),
+                        ("sonar-pro", 200_000),
+                        ("sonar-reasoning", 127_072),
+                        ("sonar-reasoning-pro", 127_072),
+                    ]),
+                },
+                ProviderConfig {
+                    name: "huggingface".to_string(),
+                    api_base_url: huggingface_client::INFERENCE_API_URL.to_string(),
+                    api_key: String::new(),
+                    models: vec![
+                        "mistralai/Mistral-7B-Instruct-v0.3".to_string(),
+                        "HuggingFaceH4/zephyr-7b-beta".to_string(),
+                        "microsoft/Phi-3-mini-4k-instruct".to_string(),
+                    ],
+                    transformer: TransformerConfig {
+                        r#use: vec![HUGGINGFACE_TRANSFORMER.to_string()],
+                    },
+                    mock_script: None,
+                    plugin: None,
+                    gemini_auth: Default::default(),
+                    model_defaults: context_windows(&[
+                        ("mistralai/Mistral-7B-Instruct-v0.3", 32_768),
+                        ("HuggingFaceH4/zephyr-7b-beta", 32_768),
+                        ("microsoft/Phi-3-mini-4k-instruct", 4_096),
+                    ]),
                 },
                 ProviderConfig {
                     name: "ollama".to_string(),
@@ -126,6 +483,23 @@ impl Default for FlexibleApiConfig {
                     transformer: TransformerConfig {
                         r#use: vec!["openai".to_string()],
                     },
+                    mock_script: None,
+                    plugin: None,
+                    gemini_auth: Default::default(),
+                    model_defaults: HashMap::new(),
+                },
+                ProviderConfig {
+                    name: "mock".to_string(),
+                    api_base_url: String::new(),
+                    api_key: String::new(),
+                    models: vec!["scripted".to_string(), "echo".to_string()],
+                    transformer: TransformerConfig {
+                        r#use: vec!["mock".to_string()],
+                    },
+                    mock_script: Some(MockScript::demo()),
+                    plugin: None,
+                    gemini_auth: Default::default(),
+                    model_defaults: HashMap::new(),
                 },
             ],
             router: RouterConfig {
@@ -140,12 +514,45 @@ impl Default for FlexibleApiConfig {
                 temperature: 0.7,
                 max_tokens: 2048,
                 retry_delay: 2000,
+                cors_proxy: None,
+                json_repair_retries: None,
+                context_cache_ttl_minutes: None,
+                stop_sequences: Vec::new(),
+                logit_bias: Vec::new(),
+                tool_choice: Default::default(),
+                allowed_functions: Default::default(),
+                native_tools: Default::default(),
+                audio_output: Default::default(),
+                candidate_count: None,
+                reasoning: Default::default(),
             },
             system_prompt: "You are a helpful assistant that responds in markdown format. Always be concise and to the point.".to_string(),
             function_tools: Self::get_default_function_tools(),
             structured_outputs: vec![],
             mcp_config: McpConfig::default(),
             current_session_provider: None,
+            sub_agent_model: None,
+            embedding_model: None,
+            active_structured_output: None,
+            guardrails: Vec::new(),
+            moderation: ModerationSettings::default(),
+            desktop_tools: DesktopToolSettings::default(),
+            fetch_tool: FetchToolSettings::default(),
+            web_fetch: WebFetchSettings::default(),
+            web_search: WebSearchSettings::default(),
+            code_sandbox: CodeSandboxSettings::default(),
+            budget: BudgetSettings::default(),
+            hide_message_footer: false,
+            hide_reasoning: false,
+            personas: Vec::new(),
+            conversation_starters: StarterSettings::default(),
+            follow_ups: FollowUpSettings::default(),
+            redaction: RedactionSettings::default(),
+            response_length: ResponseLengthSettings::default(),
+            self_consistency: SelfConsistencySettings::default(),
+            tool_selection: ToolSelectionSettings::default(),
+            webhook: WebhookSettings::default(),
+            browser_notifications: BrowserNotificationSettings::default(),
         }
     }
 }
@@ -156,6 +563,16 @@ impl FlexibleApiConfig {
         self.providers.iter().find(|p| p.name == name)
     }
 
+    /// Built-in providers missing from this config, e.g. presets added after
+    /// it was saved or removed by the user
+    pub fn missing_presets(&self) -> Vec<ProviderConfig> {
+        Self::default()
+            .providers
+            .into_iter()
+            .filter(|preset| self.get_provider(&preset.name).is_none())
+            .collect()
+    }
+
     /// Get provider and model from session setting or default
     pub fn get_current_provider_and_model(&self) -> (String, String) {
         if let Some(ref session_provider) = self.current_session_provider {
@@ -176,11 +593,72 @@ impl FlexibleApiConfig {
         }
     }
 
+    /// The structured output selected for the next request, if it still exists
+    pub fn active_structured_output(&self) -> Option<&StructuredOutput> {
+        let name = self.active_structured_output.as_deref()?;
+        self.structured_outputs.iter().find(|output| output.name == name)
+    }
+
     /// Set the current session provider and model
     pub fn set_session_provider(&mut self, provider_name: &str, model_name: &str) {
         self.current_session_provider = Some(format!("{},{}", provider_name, model_name));
     }
 
+    /// Clamp every model's defaults to the ranges models accept, e.g. after
+    /// loading a stored config, and drop the ones left empty
+    pub fn clamp_model_defaults(&mut self) {
+        for provider in &mut self.providers {
+            provider.model_defaults = std::mem::take(&mut provider.model_defaults)
+                .into_iter()
+                .map(|(model, defaults)| (model, defaults.clamped()))
+                .filter(|(_, defaults)| !defaults.is_empty())
+                .collect();
+        }
+    }
+
+    /// Get the generation defaults configured for a provider's model
+    pub fn get_model_defaults(&self, provider_name: &str, model_name: &str) -> Option<&ModelDefaults> {
+        self.get_provider(provider_name)
+            .and_then(|provider| provider.model_defaults.get(model_name))
+            .filter(|defaults| !defaults.is_empty())
+    }
+
+    /// Estimated cost of a request to the current model
+    pub fn request_cost(&self, input_tokens: u32, output_tokens: u32) -> Option<f64> {
+        let (provider_name, model_name) = self.get_current_provider_and_model();
+        self.get_model_defaults(&provider_name, &model_name)
+            .and_then(|defaults| defaults.cost(input_tokens, output_tokens))
+    }
+
+    /// Shared settings with the model's temperature cap enforced
+    pub fn shared_settings_for_model(&self, provider_name: &str, model_name: &str) -> SharedSettings {
+        let mut settings = self.shared_settings.clone();
+        if let Some(max_temperature) = self
+            .get_model_defaults(provider_name, model_name)
+            .and_then(|defaults| defaults.max_temperature)
+        {
+            settings.temperature = settings.temperature.min(max_temperature);
+        }
+        settings
+    }
+
+    /// Apply a model's defaults to the shared settings (max tokens, temperature cap)
+    pub fn apply_model_defaults(&mut self, provider_name: &str, model_name: &str) {
+        let Some(defaults) = self
+            .get_model_defaults(provider_name, model_name)
+            .cloned()
+            .map(ModelDefaults::clamped)
+        else {
+            return;
+        };
+        if let Some(max_tokens) = defaults.max_tokens {
+            self.shared_settings.max_tokens = max_tokens;
+        }
+        if let Some(max_temperature) = defaults.max_temperature {
+            self.shared_settings.temperature = self.shared_settings.temperature.min(max_temperature);
+        }
+    }
+
     /// Get all available provider-model combinations
     pub fn get_all_provider_models(&self) -> Vec<(String, String)> {
         let mut combinations = Vec::new();
@@ -219,6 +697,22 @@ impl FlexibleApiConfig {
         crate::llm_playground::types::ApiConfig::get_default_function_tools()
     }
 
+    /// Turn a built-in tool on or off, adding it from the defaults to configs
+    /// saved before it existed
+    pub fn set_default_tool_enabled(&mut self, tool_name: &str, enabled: bool) {
+        if !self.function_tools.iter().any(|tool| tool.name == tool_name) {
+            if let Some(tool) = Self::get_default_function_tools()
+                .into_iter()
+                .find(|tool| tool.name == tool_name)
+            {
+                self.function_tools.push(tool);
+            }
+        }
+        if let Some(tool) = self.function_tools.iter_mut().find(|tool| tool.name == tool_name) {
+            tool.enabled = enabled;
+        }
+    }
+
     /// Toggle a function tool's enabled state
     pub fn toggle_function_tool(&mut self, tool_name: &str) {
         if let Some(tool) = self.function_tools.iter_mut().find(|t| t.name == tool_name) {
@@ -300,12 +794,57 @@ impl FlexibleApiConfig {
 
     /// Add MCP tools to the function tools list
     pub fn add_mcp_tools(&mut self, mcp_tools: Vec<FunctionTool>) {
-        // Remove existing MCP tools first
-        self.function_tools
-            .retain(|tool| !tool.name.starts_with("mcp_"));
+        // Remove existing MCP tools first, remembering which were turned off
+        let disabled: std::collections::HashSet<String> = self
+            .function_tools
+            .iter()
+            .filter(|tool| tool.mcp_server().is_some() && !tool.enabled)
+            .map(|tool| tool.name.clone())
+            .collect();
+        self.function_tools.retain(|tool| tool.mcp_server().is_none());
 
         // Add new MCP tools
-        self.function_tools.extend(mcp_tools);
+        self.function_tools
+            .extend(mcp_tools.into_iter().map(|tool| FunctionTool {
+                enabled: !disabled.contains(&tool.name),
+                ..tool
+            }));
+    }
+
+    /// Indices of the function tools grouped by where they come from: the
+    /// configured tools first, then each MCP server's by server name
+    pub fn function_tool_groups(&self) -> Vec<(Option<&str>, Vec<usize>)> {
+        let mut groups: Vec<(Option<&str>, Vec<usize>)> = vec![(None, Vec::new())];
+        for (index, tool) in self.function_tools.iter().enumerate() {
+            let server = tool.mcp_server();
+            match groups.iter_mut().find(|(group, _)| *group == server) {
+                Some((_, indices)) => indices.push(index),
+                None => groups.push((server, vec![index])),
+            }
+        }
+        groups[1..].sort_by(|a, b| a.0.cmp(&b.0));
+        groups.retain(|(_, indices)| !indices.is_empty());
+        groups
+    }
+
+    /// A tool's name without the prefix its MCP server puts in front
+    pub fn tool_label<'a>(&self, tool: &'a FunctionTool) -> &'a str {
+        tool.mcp_server()
+            .and_then(|server| {
+                let prefix = self.mcp_config.servers.get(server)?.tool_prefix(server);
+                tool.name.strip_prefix(prefix.as_str())
+            })
+            .filter(|label| !label.is_empty())
+            .unwrap_or(&tool.name)
+    }
+
+    /// Turn every tool of an MCP server on or off
+    pub fn set_mcp_server_tools_enabled(&mut self, server: &str, enabled: bool) {
+        for tool in self.function_tools.iter_mut() {
+            if tool.mcp_server() == Some(server) {
+                tool.enabled = enabled;
+            }
+        }
     }
 
     /// Get MCP configuration
@@ -323,3 +862,199 @@ impl FlexibleApiConfig {
         self.function_tools.iter().collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::mcp_client::{self, McpServerConfig};
+
+    fn mcp_tool(server: &str, name: &str) -> FunctionTool {
+        FunctionTool {
+            name: name.to_string(),
+            description: String::new(),
+            parameters: serde_json::json!({}),
+            mock_response: String::new(),
+            enabled: true,
+            category: mcp_client::tool_category(server),
+            is_builtin: true,
+            requires: None,
+        }
+    }
+
+    fn config_with_servers() -> FlexibleApiConfig {
+        let mut config = FlexibleApiConfig::default();
+        for (server, tool_prefix) in [("wiki", None), ("docs", Some("d_"))] {
+            config.mcp_config.servers.insert(
+                server.to_string(),
+                McpServerConfig {
+                    name: server.to_string(),
+                    server_type: "http".to_string(),
+                    url: None,
+                    headers: None,
+                    enabled: true,
+                    tool_prefix: tool_prefix.map(str::to_string),
+                },
+            );
+        }
+        config.add_mcp_tools(vec![
+            mcp_tool("wiki", "mcp_wiki_search"),
+            mcp_tool("docs", "d_search"),
+            mcp_tool("docs", "d_fetch"),
+        ]);
+        config
+    }
+
+    #[test]
+    fn mcp_tools_are_grouped_by_server() {
+        let config = config_with_servers();
+        let own = config.function_tools.len() - 3;
+        let groups = config.function_tool_groups();
+        let servers: Vec<_> = groups.iter().map(|(server, _)| *server).collect();
+        assert_eq!(servers, [None, Some("docs"), Some("wiki")]);
+        assert_eq!(groups[0].1, (0..own).collect::<Vec<_>>());
+        assert_eq!(groups[1].1, [own + 1, own + 2]);
+
+        let labels: Vec<_> = groups[1].1.iter().map(|&index| config.tool_label(&config.function_tools[index])).collect();
+        assert_eq!(labels, ["search", "fetch"]);
+        assert_eq!(config.tool_label(&config.function_tools[own]), "search");
+        assert_eq!(config.tool_label(&config.function_tools[0]), config.function_tools[0].name);
+    }
+
+    #[test]
+    fn a_server_switch_survives_reconnecting() {
+        let mut config = config_with_servers();
+        config.set_mcp_server_tools_enabled("docs", false);
+        let enabled = |config: &FlexibleApiConfig, name: &str| {
+            config.function_tools.iter().find(|tool| tool.name == name).unwrap().enabled
+        };
+        assert!(!enabled(&config, "d_search"));
+        assert!(enabled(&config, "mcp_wiki_search"));
+
+        config.add_mcp_tools(vec![mcp_tool("docs", "d_search"), mcp_tool("docs", "d_new")]);
+        assert!(!enabled(&config, "d_search"));
+        assert!(enabled(&config, "d_new"));
+        assert!(!config.function_tools.iter().any(|tool| tool.name == "mcp_wiki_search"));
+    }
+
+    #[test]
+    fn default_tools_are_added_when_enabled() {
+        let mut config = FlexibleApiConfig::default();
+        config.function_tools.retain(|tool| tool.name != "run_python");
+        config.set_default_tool_enabled("run_python", true);
+        let tool = config.function_tools.iter().find(|tool| tool.name == "run_python").unwrap();
+        assert!(tool.enabled && tool.is_builtin);
+
+        config.set_default_tool_enabled("run_python", false);
+        assert_eq!(config.function_tools.iter().filter(|tool| tool.name == "run_python").count(), 1);
+        assert!(config.get_enabled_function_tools().iter().all(|tool| tool.name != "run_python"));
+        config.set_default_tool_enabled("no_such_tool", true);
+        assert!(config.function_tools.iter().all(|tool| tool.name != "no_such_tool"));
+    }
+
+    fn config_with_defaults(defaults: ModelDefaults) -> (FlexibleApiConfig, String, String) {
+        let mut config = FlexibleApiConfig::default();
+        let provider = config.providers[0].name.clone();
+        let model = config.providers[0].models[0].clone();
+        config.providers[0].model_defaults.insert(model.clone(), defaults);
+        (config, provider, model)
+    }
+
+    #[test]
+    fn token_counts_are_shortened() {
+        assert_eq!(format_token_count(512), "512");
+        assert_eq!(format_token_count(8_192), "8k");
+        assert_eq!(format_token_count(128_000), "128k");
+        assert_eq!(format_token_count(2_000_000), "2M");
+        assert_eq!(format_token_count(1_048_576), "1048k");
+    }
+
+    #[test]
+    fn model_defaults_set_max_tokens_and_cap_temperature() {
+        let (mut config, provider, model) = config_with_defaults(ModelDefaults {
+            max_tokens: Some(4_096),
+            max_temperature: Some(0.5),
+            ..Default::default()
+        });
+        config.shared_settings.temperature = 0.9;
+        config.apply_model_defaults(&provider, &model);
+        assert_eq!(config.shared_settings.max_tokens, 4_096);
+        assert_eq!(config.shared_settings.temperature, 0.5);
+
+        config.shared_settings.temperature = 0.2;
+        config.apply_model_defaults(&provider, &model);
+        assert_eq!(config.shared_settings.temperature, 0.2);
+
+        let before = config.shared_settings.clone();
+        config.apply_model_defaults(&provider, "no-such-model");
+        assert_eq!(config.shared_settings, before);
+    }
+
+    #[test]
+    fn stored_defaults_are_clamped_on_load() {
+        let (mut config, provider, model) = config_with_defaults(ModelDefaults {
+            max_tokens: Some(500_000),
+            max_temperature: Some(7.5),
+            context_window: Some(128_000),
+            input_price: Some(-1.0),
+            output_price: Some(f64::NAN),
+        });
+        config.providers[0].model_defaults.insert(
+            "broken".to_string(),
+            ModelDefaults { max_tokens: Some(0), context_window: Some(0), ..Default::default() },
+        );
+        config.clamp_model_defaults();
+
+        assert_eq!(
+            config.get_model_defaults(&provider, &model),
+            Some(&ModelDefaults {
+                max_tokens: Some(128_000),
+                max_temperature: Some(MAX_TEMPERATURE_CAP),
+                context_window: Some(128_000),
+                input_price: None,
+                output_price: None,
+            })
+        );
+        assert!(!config.providers[0].model_defaults.contains_key("broken"));
+        let negative = ModelDefaults { max_temperature: Some(-0.3), ..Default::default() };
+        assert_eq!(negative.clamped().max_temperature, Some(0.0));
+    }
+
+    #[test]
+    fn a_zero_max_tokens_default_is_not_applied() {
+        let (mut config, provider, model) = config_with_defaults(ModelDefaults {
+            max_tokens: Some(0),
+            ..Default::default()
+        });
+        let max_tokens = config.shared_settings.max_tokens;
+        config.apply_model_defaults(&provider, &model);
+        assert_eq!(config.shared_settings.max_tokens, max_tokens);
+    }
+
+    #[test]
+    fn defaults_summary_and_cost() {
+        let defaults = ModelDefaults {
+            max_tokens: Some(8_192),
+            max_temperature: Some(1.0),
+            context_window: Some(128_000),
+            input_price: Some(2.5),
+            output_price: Some(10.0),
+        };
+        assert_eq!(defaults.summary(), "128k ctx · 8k max out · temp ≤ 1.0 · 2.5/10 per 1M");
+        assert_eq!(defaults.cost(1_000_000, 100_000), Some(3.5));
+        assert_eq!(ModelDefaults::default().cost(1_000, 1_000), None);
+        assert!(ModelDefaults::default().is_empty());
+    }
+
+    #[test]
+    fn removed_presets_such_as_groq_can_be_added_back() {
+        let mut config = FlexibleApiConfig::default();
+        assert!(config.missing_presets().is_empty());
+        let groq = config.get_provider("groq").unwrap();
+        assert!(groq.needs_api_key());
+        assert_eq!(groq.transformer.r#use, ["openai"]);
+
+        config.providers.retain(|provider| provider.name != "groq");
+        let missing: Vec<_> = config.missing_presets().into_iter().map(|preset| preset.name).collect();
+        assert_eq!(missing, ["groq"]);
+    }
+}
@@ -0,0 +1,181 @@
+// Outgoing message redaction
+// Before a typed message is sent, it is checked against regex rules for
+// sensitive data such as email addresses, API keys and card numbers. Matches
+// are shown in a warning that offers to mask them or send the message as is.
+
+use crate::llm_playground::eval_suite::compile_regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RedactionRule {
+    pub id: String,
+    pub name: String,
+    /// JavaScript regex, matched ignoring case
+    pub pattern: String,
+    /// Text put in place of each match
+    pub mask: String,
+    pub enabled: bool,
+}
+
+impl Default for RedactionRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RedactionRule {
+    pub fn new() -> Self {
+        Self {
+            id: format!("redaction_{}", js_sys::Date::now() as u64),
+            name: "New rule".to_string(),
+            pattern: String::new(),
+            mask: "[REDACTED]".to_string(),
+            enabled: true,
+        }
+    }
+
+    fn preset(id: &str, name: &str, pattern: &str, mask: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            pattern: pattern.to_string(),
+            mask: mask.to_string(),
+            enabled: true,
+        }
+    }
+
+    /// Number of matches in `text`
+    pub fn count_matches(&self, text: &str) -> Result<usize, String> {
+        let regex = compile_regex(&self.pattern, "gi")?;
+        Ok(js_sys::JsString::from(text)
+            .match_(&regex)
+            .map(|found| js_sys::Array::from(&found).length() as usize)
+            .unwrap_or_default())
+    }
+
+    /// `text` with every match replaced by the mask
+    pub fn mask_in(&self, text: &str) -> Result<String, String> {
+        let regex = compile_regex(&self.pattern, "gi")?;
+        Ok(String::from(
+            js_sys::JsString::from(text).replace_by_pattern(&regex, &self.mask),
+        ))
+    }
+}
+
+/// Rules for the common kinds of sensitive data
+pub fn default_rules() -> Vec<RedactionRule> {
+    vec![
+        RedactionRule::preset(
+            "redaction_email",
+            "Email address",
+            r"[A-Z0-9._%+-]+@[A-Z0-9.-]+\.[A-Z]{2,}",
+            "[EMAIL]",
+        ),
+        RedactionRule::preset(
+            "redaction_api_key",
+            "API key",
+            r"\b(?:sk-(?:proj-|ant-)?[A-Z0-9_-]{20,}|AIza[0-9A-Z_-]{35}|gh[pousr]_[A-Z0-9]{36,}|xox[abprs]-[A-Z0-9-]{10,}|AKIA[0-9A-Z]{16})\b",
+            "[API_KEY]",
+        ),
+        RedactionRule::preset(
+            "redaction_card",
+            "Card number",
+            r"\b(?:\d[ -]?){12,18}\d\b",
+            "[CARD_NUMBER]",
+        ),
+    ]
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionSettings {
+    /// Check typed messages before they are sent
+    pub enabled: bool,
+    pub rules: Vec<RedactionRule>,
+}
+
+impl Default for RedactionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: default_rules(),
+        }
+    }
+}
+
+/// Sensitive data of one kind found in a message
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    pub rule_name: String,
+    pub count: usize,
+}
+
+impl RedactionSettings {
+    fn active_rules(&self) -> impl Iterator<Item = &RedactionRule> {
+        self.rules
+            .iter()
+            .filter(move |rule| self.enabled && rule.enabled && !rule.pattern.is_empty())
+    }
+
+    /// What the enabled rules find in `text`; rules with an invalid pattern
+    /// are skipped
+    pub fn scan(&self, text: &str) -> Vec<Finding> {
+        self.active_rules()
+            .filter_map(|rule| {
+                let count = rule.count_matches(text).ok()?;
+                (count > 0).then(|| Finding {
+                    rule_name: rule.name.clone(),
+                    count,
+                })
+            })
+            .collect()
+    }
+
+    /// `text` with the matches of every enabled rule masked
+    pub fn mask(&self, text: &str) -> String {
+        self.active_rules().fold(text.to_string(), |text, rule| {
+            rule.mask_in(&text).unwrap_or(text)
+        })
+    }
+}
+
+/// "Email address, API key (2)"
+pub fn summarize(findings: &[Finding]) -> String {
+    findings
+        .iter()
+        .map(|finding| match finding.count {
+            1 => finding.rule_name.clone(),
+            count => format!("{} ({})", finding.rule_name, count),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_counts_repeated_findings() {
+        let findings = [
+            Finding {
+                rule_name: "Email address".to_string(),
+                count: 1,
+            },
+            Finding {
+                rule_name: "API key".to_string(),
+                count: 2,
+            },
+        ];
+        assert_eq!(summarize(&findings), "Email address, API key (2)");
+        assert_eq!(summarize(&[]), "");
+    }
+
+    #[test]
+    fn old_configs_get_the_default_rules() {
+        let settings: RedactionSettings = serde_json::from_str("{}").unwrap();
+        assert!(settings.enabled);
+        assert_eq!(settings.rules.len(), 3);
+        assert!(settings.rules.iter().all(|rule| !rule.mask.is_empty()));
+    }
+}
@@ -0,0 +1,236 @@
+// Request export
+// Rebuilds the provider request behind a message and renders it as a cURL
+// command or a Rust reqwest program, with the API key swapped for an
+// environment variable.
+
+use crate::llm_playground::{
+    api_clients::ProviderRequest, flexible_client::FlexibleLLMClient,
+    provider_config::FlexibleApiConfig, Message, MessageRole,
+};
+
+/// Environment variable the exported snippets read the API key from
+pub const API_KEY_VAR: &str = "API_KEY";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExportFormat {
+    Curl,
+    Reqwest,
+}
+
+impl ExportFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Curl => "cURL",
+            ExportFormat::Reqwest => "Rust (reqwest)",
+        }
+    }
+
+    pub fn render(&self, request: &ProviderRequest) -> String {
+        match self {
+            ExportFormat::Curl => to_curl(request),
+            ExportFormat::Reqwest => to_reqwest(request),
+        }
+    }
+}
+
+/// The request that produced a message, with the current settings: the
+/// history before an assistant reply, or up to and including a user message.
+/// The API key is replaced by `$API_KEY`.
+pub fn request_for_message(
+    client: &FlexibleLLMClient,
+    config: &FlexibleApiConfig,
+    messages: &[Message],
+    message_id: &str,
+) -> Result<ProviderRequest, String> {
+    let index = messages
+        .iter()
+        .position(|m| m.id == message_id)
+        .ok_or("Message not found")?;
+    let end = if messages[index].role == MessageRole::Assistant {
+        index
+    } else {
+        index + 1
+    };
+    // Messages held back by moderation never reach the model
+    let history: Vec<Message> = messages[..end]
+        .iter()
+        .filter(|m| !m.moderation.as_ref().is_some_and(|r| r.blocked))
+        .cloned()
+        .collect();
+    if history.is_empty() {
+        return Err("No messages precede this one".to_string());
+    }
+
+    let request = client.build_request(&history, config)?;
+    let (provider_name, _) = config.get_current_provider_and_model();
+    let api_key = config
+        .get_provider(&provider_name)
+        .map(|p| p.api_key.clone())
+        .unwrap_or_default();
+    Ok(redact_key(request, &api_key))
+}
+
+/// Replace the API key in the URL and headers with `$API_KEY`
+pub fn redact_key(mut request: ProviderRequest, api_key: &str) -> ProviderRequest {
+    let api_key = api_key.trim();
+    if api_key.is_empty() {
+        return request;
+    }
+    let placeholder = format!("${}", API_KEY_VAR);
+    request.url = request.url.replace(api_key, &placeholder);
+    for (_, value) in &mut request.headers {
+        *value = value.replace(api_key, &placeholder);
+    }
+    request
+}
+
+pub fn to_curl(request: &ProviderRequest) -> String {
+    let body = serde_json::to_string_pretty(&request.body).unwrap_or_default();
+    let mut lines = vec![format!("curl {}", double_quote(&request.url))];
+    for (name, value) in &request.headers {
+        lines.push(format!(
+            "  -H {}",
+            double_quote(&format!("{}: {}", name, value))
+        ));
+    }
+    lines.push(format!("  -d {}", single_quote(&body)));
+    lines.join(" \\\n")
+}
+
+pub fn to_reqwest(request: &ProviderRequest) -> String {
+    let uses_key = request.url.contains(&format!("${}", API_KEY_VAR))
+        || request
+            .headers
+            .iter()
+            .any(|(_, value)| value.contains(&format!("${}", API_KEY_VAR)));
+    let body = serde_json::to_string_pretty(&request.body).unwrap_or_default();
+    let hashes = "#".repeat(raw_string_hashes(&body));
+
+    let mut code = String::from(
+        "// Cargo.toml: reqwest = { version = \"0.12\", features = [\"json\"] }\n\
+         //             serde_json = \"1\"\n\
+         //             tokio = { version = \"1\", features = [\"full\"] }\n\n\
+         #[tokio::main]\n\
+         async fn main() -> Result<(), Box<dyn std::error::Error>> {\n",
+    );
+    if uses_key {
+        code.push_str(&format!(
+            "    let api_key = std::env::var({:?})?;\n",
+            API_KEY_VAR
+        ));
+    }
+    code.push_str(&format!(
+        "    let body: serde_json::Value = serde_json::from_str(r{hashes}\"{body}\"{hashes})?;\n\n"
+    ));
+    code.push_str("    let response = reqwest::Client::new()\n");
+    code.push_str(&format!("        .post({})\n", rust_string(&request.url)));
+    for (name, value) in &request.headers {
+        code.push_str(&format!(
+            "        .header({:?}, {})\n",
+            name,
+            rust_string(value)
+        ));
+    }
+    code.push_str(
+        "        .json(&body)\n\
+         \x20       .send()\n\
+         \x20       .await?;\n\n\
+         \x20   println!(\"{}\", response.text().await?);\n\
+         \x20   Ok(())\n\
+         }\n",
+    );
+    code
+}
+
+/// A shell double-quoted string; `$API_KEY` is left for the shell to expand
+fn double_quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    for ch in text.chars() {
+        if matches!(ch, '"' | '\\' | '`' | '$') {
+            quoted.push('\\');
+        }
+        quoted.push(ch);
+    }
+    let placeholder = format!("${}", API_KEY_VAR);
+    format!(
+        "\"{}\"",
+        quoted.replace(&format!("\\{}", placeholder), &placeholder)
+    )
+}
+
+/// A shell single-quoted string, taken literally
+fn single_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// A Rust string expression, a `format!` reading `api_key` when the key is needed
+fn rust_string(text: &str) -> String {
+    let placeholder = format!("${}", API_KEY_VAR);
+    if !text.contains(&placeholder) {
+        return format!("{:?}", text);
+    }
+    let template = text
+        .replace('{', "{{")
+        .replace('}', "}}")
+        .replace(&placeholder, "{api_key}");
+    format!("format!({:?})", template)
+}
+
+/// Enough `#`s that the raw string can't end early
+fn raw_string_hashes(text: &str) -> usize {
+    (1..)
+        .find(|&count| !text.contains(&format!("\"{}", "#".repeat(count))))
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request() -> ProviderRequest {
+        redact_key(
+            ProviderRequest {
+                url: "https://api.example.com/v1/chat/completions".to_string(),
+                headers: vec![
+                    ("Content-Type".to_string(), "application/json".to_string()),
+                    ("Authorization".to_string(), "Bearer sk-secret".to_string()),
+                ],
+                body: json!({ "model": "m", "messages": [{ "role": "user", "content": "It's \"$5\"" }] }),
+            },
+            "sk-secret",
+        )
+    }
+
+    #[test]
+    fn renders_curl_without_the_key() {
+        let curl = to_curl(&request());
+        assert!(!curl.contains("sk-secret"));
+        assert!(curl.starts_with("curl \"https://api.example.com/v1/chat/completions\" \\\n"));
+        assert!(curl.contains("  -H \"Authorization: Bearer $API_KEY\" \\\n"));
+        assert!(curl.contains("It'\\''s \\\"$5\\\""));
+
+        let gemini = redact_key(
+            ProviderRequest {
+                url: "https://g.example/models/m:generateContent?key=AIza1".to_string(),
+                headers: Vec::new(),
+                body: json!({}),
+            },
+            "AIza1",
+        );
+        assert!(to_curl(&gemini)
+            .starts_with("curl \"https://g.example/models/m:generateContent?key=$API_KEY\""));
+    }
+
+    #[test]
+    fn renders_reqwest_reading_the_key_from_the_environment() {
+        let code = to_reqwest(&request());
+        assert!(!code.contains("sk-secret"));
+        assert!(code.contains("let api_key = std::env::var(\"API_KEY\")?;"));
+        assert!(code.contains(".header(\"Authorization\", format!(\"Bearer {api_key}\"))"));
+        assert!(code.contains(".post(\"https://api.example.com/v1/chat/completions\")"));
+        // The body contains `"$5\"` but no `"#`, so one hash is enough
+        assert!(code.contains("serde_json::from_str(r#\"{"));
+        assert_eq!(raw_string_hashes("a\"#b"), 2);
+    }
+}
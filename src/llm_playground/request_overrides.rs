@@ -0,0 +1,91 @@
+// Per-message parameter overrides
+// The composer's parameter popover changes the temperature, max tokens or tool
+// choice for the reply to the next message only. The session's settings are
+// left as they are; the overrides are cleared once the message is sent.
+
+use crate::llm_playground::{provider_config::FlexibleApiConfig, ToolChoice};
+
+pub const MAX_TEMPERATURE: f32 = 2.0;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RequestOverrides {
+    pub temperature: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub tool_choice: Option<ToolChoice>,
+}
+
+impl RequestOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Temperature from the popover's input; empty or invalid clears it
+    pub fn parse_temperature(value: &str) -> Option<f32> {
+        value
+            .trim()
+            .parse::<f32>()
+            .ok()
+            .filter(|t| t.is_finite())
+            .map(|t| t.clamp(0.0, MAX_TEMPERATURE))
+    }
+
+    /// Max tokens from the popover's input; empty, zero or invalid clears it
+    pub fn parse_max_tokens(value: &str) -> Option<u32> {
+        value.trim().parse::<u32>().ok().filter(|tokens| *tokens > 0)
+    }
+
+    /// Put the overrides into the config a request is sent with
+    pub fn apply(&self, config: &mut FlexibleApiConfig) {
+        if let Some(temperature) = self.temperature {
+            config.shared_settings.temperature = temperature;
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            config.shared_settings.max_tokens = max_tokens;
+        }
+        if let Some(tool_choice) = self.tool_choice {
+            config.shared_settings.tool_choice = tool_choice;
+        }
+    }
+
+    /// "temperature 1.2 · 256 tokens", shown on the composer's button
+    pub fn summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(temperature) = self.temperature {
+            parts.push(format!("temperature {}", temperature));
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            parts.push(format!("{} tokens", max_tokens));
+        }
+        if let Some(tool_choice) = self.tool_choice {
+            parts.push(tool_choice.label().to_lowercase());
+        }
+        parts.join(" · ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inputs_are_parsed_and_clamped() {
+        assert_eq!(RequestOverrides::parse_temperature(" 1.5 "), Some(1.5));
+        assert_eq!(RequestOverrides::parse_temperature("7"), Some(MAX_TEMPERATURE));
+        assert_eq!(RequestOverrides::parse_temperature(""), None);
+        assert_eq!(RequestOverrides::parse_max_tokens("256"), Some(256));
+        assert_eq!(RequestOverrides::parse_max_tokens("0"), None);
+        assert_eq!(RequestOverrides::parse_max_tokens("-3"), None);
+    }
+
+    #[test]
+    fn summary_lists_only_set_values() {
+        assert!(RequestOverrides::default().is_empty());
+        let overrides = RequestOverrides {
+            temperature: Some(1.2),
+            max_tokens: None,
+            tool_choice: Some(ToolChoice::None),
+        };
+        assert!(!overrides.is_empty());
+        assert_eq!(overrides.summary(), "temperature 1.2 · no tools");
+    }
+}
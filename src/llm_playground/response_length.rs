@@ -0,0 +1,124 @@
+// Response length presets
+// Picked in the chat header, a preset sets the max tokens of the next replies
+// and appends a sentence on the wanted length to the system prompt, so the
+// model aims for that length instead of being cut off at the token limit.
+
+use crate::llm_playground::provider_config::FlexibleApiConfig;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LengthPreset {
+    /// The session's max tokens and system prompt as they are
+    #[default]
+    Default,
+    Short,
+    Medium,
+    Long,
+    /// Max tokens and guidance from the settings
+    Custom,
+}
+
+impl LengthPreset {
+    pub const ALL: [LengthPreset; 5] = [
+        LengthPreset::Default,
+        LengthPreset::Short,
+        LengthPreset::Medium,
+        LengthPreset::Long,
+        LengthPreset::Custom,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LengthPreset::Default => "Default",
+            LengthPreset::Short => "Short",
+            LengthPreset::Medium => "Medium",
+            LengthPreset::Long => "Long",
+            LengthPreset::Custom => "Custom",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|preset| preset.label() == value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResponseLengthSettings {
+    pub preset: LengthPreset,
+    pub custom_max_tokens: u32,
+    pub custom_guidance: String,
+}
+
+impl Default for ResponseLengthSettings {
+    fn default() -> Self {
+        Self {
+            preset: LengthPreset::Default,
+            custom_max_tokens: 2048,
+            custom_guidance: "Answer in about 300 words.".to_string(),
+        }
+    }
+}
+
+impl ResponseLengthSettings {
+    /// Max tokens and system prompt guidance of the selected preset
+    pub fn limits(&self) -> Option<(u32, &str)> {
+        match self.preset {
+            LengthPreset::Default => None,
+            LengthPreset::Short => Some((
+                256,
+                "Keep your answer short: a few sentences at most, without preamble.",
+            )),
+            LengthPreset::Medium => Some((
+                1024,
+                "Answer in a few focused paragraphs, covering what matters most.",
+            )),
+            LengthPreset::Long => Some((
+                4096,
+                "Give a thorough, detailed answer with examples where they help.",
+            )),
+            LengthPreset::Custom => Some((self.custom_max_tokens.max(1), self.custom_guidance.trim())),
+        }
+    }
+
+    /// Put the preset into the config a request is sent with
+    pub fn apply(&self, config: &mut FlexibleApiConfig) {
+        let Some((max_tokens, guidance)) = self.limits() else {
+            return;
+        };
+        config.shared_settings.max_tokens = max_tokens;
+        if !guidance.is_empty() {
+            config.system_prompt = if config.system_prompt.trim().is_empty() {
+                guidance.to_string()
+            } else {
+                format!("{}\n\n{}", config.system_prompt.trim_end(), guidance)
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_round_trip_through_labels() {
+        for preset in LengthPreset::ALL {
+            assert_eq!(LengthPreset::parse(preset.label()), Some(preset));
+        }
+        assert_eq!(LengthPreset::parse("Huge"), None);
+    }
+
+    #[test]
+    fn custom_preset_uses_the_settings() {
+        let mut settings = ResponseLengthSettings::default();
+        assert_eq!(settings.limits(), None);
+        settings.preset = LengthPreset::Custom;
+        settings.custom_max_tokens = 0;
+        settings.custom_guidance = "  One line.  ".to_string();
+        assert_eq!(settings.limits(), Some((1, "One line.")));
+        settings.preset = LengthPreset::Short;
+        assert_eq!(settings.limits().map(|(tokens, _)| tokens), Some(256));
+    }
+}
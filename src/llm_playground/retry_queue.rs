@@ -0,0 +1,100 @@
+// Retry queue for failed sends
+// A send that fails with a network error, a rate limit or a server error
+// keeps its user message and is tried again on a schedule built from the
+// retry delay setting, doubling each time. The user can retry at once or
+// cancel. Other errors, such as a bad API key, are not retried. The chat sends
+// once per attempt, so these are the only retries.
+
+/// Automatic retries before the user has to retry by hand
+pub const MAX_AUTO_RETRIES: u32 = 5;
+/// Longest wait between automatic retries
+const MAX_RETRY_DELAY_MS: u32 = 60_000;
+
+/// Whether the error may go away by itself
+pub fn is_transient(error: &str) -> bool {
+    let error = error.to_lowercase();
+    let server_error = ["500", "502", "503", "504", "529"].iter().any(|status| {
+        error.contains(&format!("error {}", status)) || error.contains(&format!("status {}", status))
+    });
+    server_error
+        || error.contains("429")
+        || ["network error", "failed to fetch", "timed out", "timeout", "rate limit", "overloaded"]
+            .iter()
+            .any(|needle| error.contains(needle))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingRetry {
+    /// The last message when the send failed, shown as not answered
+    pub message_id: String,
+    pub error: String,
+    /// Failed attempts so far
+    pub attempt: u32,
+    /// Wait before the next automatic retry; None once they are used up
+    pub delay_ms: Option<u32>,
+}
+
+impl PendingRetry {
+    /// The retry after the `attempt`th failed send
+    pub fn after_failure(message_id: &str, error: &str, attempt: u32, retry_delay: u32) -> Self {
+        let delay_ms = (attempt <= MAX_AUTO_RETRIES).then(|| {
+            retry_delay
+                .max(1)
+                .saturating_mul(2_u32.saturating_pow(attempt.saturating_sub(1)))
+                .min(MAX_RETRY_DELAY_MS)
+        });
+        Self {
+            message_id: message_id.to_string(),
+            error: error.to_string(),
+            attempt,
+            delay_ms,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_transient_errors_are_retried() {
+        assert!(is_transient(
+            "Network error - Check your internet connection and API key: TypeError: Failed to fetch"
+        ));
+        assert!(is_transient("API request failed with status 429: Too Many Requests"));
+        assert!(is_transient("API error 502: Bad Gateway"));
+        assert!(!is_transient("API error 400: max_tokens must be at most 500"));
+        assert!(!is_transient("API request failed with status 401: Invalid API key"));
+        assert!(!is_transient("Please configure your OpenAI API key in Settings"));
+    }
+
+    #[test]
+    fn retries_back_off_and_stop() {
+        let first = PendingRetry::after_failure("user_1", "timeout", 1, 1000);
+        assert_eq!(first.delay_ms, Some(1000));
+        assert_eq!(
+            PendingRetry::after_failure("user_1", "timeout", 3, 1000).delay_ms,
+            Some(4000)
+        );
+        assert_eq!(
+            PendingRetry::after_failure("user_1", "timeout", MAX_AUTO_RETRIES, 30_000).delay_ms,
+            Some(MAX_RETRY_DELAY_MS)
+        );
+        assert_eq!(
+            PendingRetry::after_failure("user_1", "timeout", MAX_AUTO_RETRIES + 1, 1000).delay_ms,
+            None
+        );
+    }
+
+    #[test]
+    fn a_rate_limited_send_costs_at_most_one_request_per_retry() {
+        // The chat sends once per attempt, so the queue's retries are the
+        // only requests a rate-limited key sees after the first one
+        let error = "API request failed with status 429: Rate limit exceeded";
+        let mut requests = 1;
+        while PendingRetry::after_failure("user_1", error, requests, 1000).delay_ms.is_some() {
+            requests += 1;
+        }
+        assert_eq!(requests, MAX_AUTO_RETRIES + 1);
+    }
+}
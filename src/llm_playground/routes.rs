@@ -0,0 +1,104 @@
+// Hash routes
+// The open session, view and settings panel are mirrored in the location hash
+// (`#/session/{id}`, `#/compare`, `#/settings/providers`, ...), so the back
+// button steps through them, a session can be linked to and a refresh comes
+// back to the same place. `#/view` is the read-only viewer for shared links.
+use yew_router::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Routable)]
+pub enum Route {
+    #[at("/")]
+    Home,
+    #[at("/session/:id")]
+    Session { id: String },
+    #[at("/session/:id/replay")]
+    Replay { id: String },
+    #[at("/session/:id/trace")]
+    Trace { id: String },
+    #[at("/session/:id/database")]
+    Database { id: String },
+    #[at("/settings")]
+    Settings,
+    /// Settings scrolled to one of `SETTINGS_SECTIONS`
+    #[at("/settings/:section")]
+    SettingsSection { section: String },
+    #[at("/compare")]
+    Compare,
+    #[at("/evals")]
+    Evals,
+    #[at("/ab-test")]
+    AbExperiment,
+    #[at("/knowledge")]
+    Knowledge,
+    #[at("/fine-tune")]
+    FineTune,
+    #[at("/diagnostics")]
+    Diagnostics,
+    #[at("/bookmarks")]
+    Bookmarks,
+    #[at("/view")]
+    View,
+    #[at("/view/:payload")]
+    SharedView { payload: String },
+    #[not_found]
+    #[at("/404")]
+    NotFound,
+}
+
+/// Sections of the settings panel that have a route; the panel gives each
+/// the element id `settings-{section}`
+pub const SETTINGS_SECTIONS: [&str; 6] = ["providers", "general", "system-prompt", "tools", "personas", "mcp"];
+
+impl Route {
+    /// Whether the route opens the settings panel
+    pub fn is_settings(&self) -> bool {
+        matches!(self, Route::Settings | Route::SettingsSection { .. })
+    }
+
+    /// Session the route points at
+    pub fn session_id(&self) -> Option<&str> {
+        match self {
+            Route::Session { id } | Route::Replay { id } | Route::Trace { id } | Route::Database { id } => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Settings section to scroll to, if it is a known one
+    pub fn settings_section(&self) -> Option<&'static str> {
+        match self {
+            Route::SettingsSection { section } => {
+                SETTINGS_SECTIONS.into_iter().find(|known| known == section)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_round_trip() {
+        for route in [
+            Route::Home,
+            Route::Session { id: "session_1700000000000".to_string() },
+            Route::Trace { id: "session_1".to_string() },
+            Route::Database { id: "session_1".to_string() },
+            Route::SettingsSection { section: "providers".to_string() },
+            Route::Compare,
+            Route::SharedView { payload: "q1Yq-_8".to_string() },
+        ] {
+            assert_eq!(Route::recognize(&route.to_path()), Some(route));
+        }
+    }
+
+    #[test]
+    fn routes_name_sessions_and_sections() {
+        assert_eq!(Route::Replay { id: "a".to_string() }.session_id(), Some("a"));
+        assert_eq!(Route::Compare.session_id(), None);
+        assert!(Route::Settings.is_settings());
+        assert_eq!(Route::SettingsSection { section: "mcp".to_string() }.settings_section(), Some("mcp"));
+        assert_eq!(Route::SettingsSection { section: "nope".to_string() }.settings_section(), None);
+    }
+}
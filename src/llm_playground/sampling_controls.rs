@@ -0,0 +1,277 @@
+// Stop sequences and logit bias
+// Both are sent with every request for experimenting with constrained
+// generation. Logit bias is keyed by token id, so words are looked up with the
+// tiktoken byte pair encoding OpenAI models use. The vocabulary, a few MB, is
+// downloaded from OpenAI's public encodings the first time a lookup is made
+// and kept for the rest of the page's life.
+use crate::llm_playground::{cors_proxy, logging};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// OpenAI accepts at most four stop sequences
+pub const MAX_STOP_SEQUENCES: usize = 4;
+pub const MIN_BIAS: i32 = -100;
+pub const MAX_BIAS: i32 = 100;
+
+/// Stop sequences as edited in settings: one per line, with `\n` and `\t`
+/// standing for a newline and a tab
+pub fn parse_stop_sequences(text: &str) -> Vec<String> {
+    text.lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.replace("\\n", "\n").replace("\\t", "\t"))
+        .collect()
+}
+
+/// The inverse of `parse_stop_sequences`
+pub fn format_stop_sequences(stop: &[String]) -> String {
+    stop.iter()
+        .map(|s| s.replace('\n', "\\n").replace('\t', "\\t"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LogitBiasEntry {
+    pub token_id: u32,
+    /// From -100 (never pick the token) to 100 (always pick it)
+    pub bias: i32,
+    /// The token's text, shown next to its id
+    #[serde(default)]
+    pub text: String,
+}
+
+/// The request's `logit_bias` object, None when no token is biased
+pub fn logit_bias_value(entries: &[LogitBiasEntry]) -> Option<Value> {
+    if entries.is_empty() {
+        return None;
+    }
+    let map: Map<String, Value> = entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.token_id.to_string(),
+                json!(entry.bias.clamp(MIN_BIAS, MAX_BIAS)),
+            )
+        })
+        .collect();
+    Some(Value::Object(map))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    /// GPT-4o, GPT-4.1, o-series and later
+    O200kBase,
+    /// GPT-4 and GPT-3.5
+    Cl100kBase,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 2] = [Encoding::O200kBase, Encoding::Cl100kBase];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::O200kBase => "o200k_base",
+            Encoding::Cl100kBase => "cl100k_base",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|encoding| encoding.name() == name)
+    }
+
+    fn url(&self) -> String {
+        format!(
+            "https://openaipublic.blob.core.windows.net/encodings/{}.tiktoken",
+            self.name()
+        )
+    }
+}
+
+/// A tiktoken vocabulary: every token's bytes and rank, which is also its id
+pub struct Vocabulary {
+    ranks: HashMap<Vec<u8>, u32>,
+    tokens: HashMap<u32, Vec<u8>>,
+}
+
+impl Vocabulary {
+    /// Read a `.tiktoken` file, one base64 token and its rank per line
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut ranks = HashMap::new();
+        let mut tokens = HashMap::new();
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (token, rank) = line
+                .split_once(' ')
+                .ok_or_else(|| format!("Line {} is not a token and a rank", number + 1))?;
+            let bytes = decode_base64(token)
+                .ok_or_else(|| format!("Line {} has invalid base64", number + 1))?;
+            let rank = rank
+                .trim()
+                .parse::<u32>()
+                .map_err(|e| format!("Line {} has an invalid rank: {}", number + 1, e))?;
+            tokens.insert(rank, bytes.clone());
+            ranks.insert(bytes, rank);
+        }
+        if ranks.is_empty() {
+            return Err("The vocabulary is empty".to_string());
+        }
+        Ok(Self { ranks, tokens })
+    }
+
+    /// Token ids of `text` encoded as one piece, the way a word and the space
+    /// before it are. Longer text is split differently by the model's own
+    /// pre-tokenizer, so ids of whole sentences are approximate.
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        let mut parts: Vec<Vec<u8>> = text.bytes().map(|byte| vec![byte]).collect();
+        // Merge the adjacent pair with the lowest rank until none is a token
+        loop {
+            let best = parts
+                .windows(2)
+                .enumerate()
+                .filter_map(|(index, pair)| {
+                    self.ranks
+                        .get(&[pair[0].as_slice(), pair[1].as_slice()].concat())
+                        .map(|rank| (*rank, index))
+                })
+                .min();
+            let Some((_, index)) = best else {
+                break;
+            };
+            let next = parts.remove(index + 1);
+            parts[index].extend(next);
+        }
+        parts
+            .iter()
+            .filter_map(|part| self.ranks.get(part).copied())
+            .collect()
+    }
+
+    /// The token's text, with bytes that are not valid UTF-8 on their own
+    /// replaced
+    pub fn decode(&self, token_id: u32) -> Option<String> {
+        self.tokens
+            .get(&token_id)
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+    }
+}
+
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    fn value(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a') as u32 + 26),
+            b'0'..=b'9' => Some((c - b'0') as u32 + 52),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let digits = text.trim_end_matches('=').as_bytes();
+    let mut bytes = Vec::with_capacity(digits.len() * 3 / 4);
+    for chunk in digits.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut buffer = 0;
+        for (index, c) in chunk.iter().enumerate() {
+            buffer |= value(*c)? << (18 - 6 * index);
+        }
+        let buffer = buffer.to_be_bytes();
+        bytes.extend_from_slice(&buffer[1..chunk.len()]);
+    }
+    Some(bytes)
+}
+
+thread_local! {
+    static VOCABULARIES: RefCell<HashMap<&'static str, Rc<Vocabulary>>> = RefCell::new(HashMap::new());
+}
+
+/// The encoding's vocabulary, downloaded on first use
+pub async fn load_vocabulary(
+    encoding: Encoding,
+    proxy: Option<&str>,
+) -> Result<Rc<Vocabulary>, String> {
+    if let Some(vocabulary) = VOCABULARIES.with(|v| v.borrow().get(encoding.name()).cloned()) {
+        return Ok(vocabulary);
+    }
+
+    logging::info!("Downloading the {} vocabulary", encoding.name());
+    let response = cors_proxy::get(&encoding.url(), proxy)
+        .send()
+        .await
+        .map_err(|e| {
+            format!(
+                "Failed to download the {} vocabulary: {}",
+                encoding.name(),
+                e
+            )
+        })?;
+    if !response.ok() {
+        return Err(format!(
+            "Failed to download the {} vocabulary: HTTP {}",
+            encoding.name(),
+            response.status()
+        ));
+    }
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read the {} vocabulary: {}", encoding.name(), e))?;
+    let vocabulary = Rc::new(Vocabulary::parse(&text)?);
+    VOCABULARIES.with(|v| {
+        v.borrow_mut()
+            .insert(encoding.name(), Rc::clone(&vocabulary))
+    });
+    Ok(vocabulary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stop_sequences_round_trip_through_the_settings_text() {
+        let stop = parse_stop_sequences("###\n\\n\\nUser:\n\nEND");
+        assert_eq!(stop, ["###", "\n\nUser:", "END"]);
+        assert_eq!(format_stop_sequences(&stop), "###\n\\n\\nUser:\nEND");
+
+        assert_eq!(logit_bias_value(&[]), None);
+        let entries = [
+            LogitBiasEntry {
+                token_id: 50256,
+                bias: -100,
+                text: String::new(),
+            },
+            LogitBiasEntry {
+                token_id: 12,
+                bias: 250,
+                text: String::new(),
+            },
+        ];
+        assert_eq!(
+            logit_bias_value(&entries),
+            Some(json!({ "50256": -100, "12": 100 }))
+        );
+    }
+
+    #[test]
+    fn words_are_encoded_by_merging_the_lowest_ranked_pairs() {
+        // "a"=0 "b"=1 " "=2 "ab"=3 " ab"=4 "bb"=5
+        let vocabulary =
+            Vocabulary::parse("YQ== 0\nYg== 1\nIA== 2\nYWI= 3\nIGFi 4\nYmI= 5\n").unwrap();
+        assert_eq!(vocabulary.encode(" ab"), [4]);
+        // "ab" (3) merges before "bb" (5)
+        assert_eq!(vocabulary.encode("abb"), [3, 1]);
+        assert_eq!(vocabulary.decode(4).as_deref(), Some(" ab"));
+        assert!(Vocabulary::parse("not a vocabulary").is_err());
+        assert_eq!(decode_base64("aGk="), Some(b"hi".to_vec()));
+    }
+}
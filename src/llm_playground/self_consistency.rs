@@ -0,0 +1,192 @@
+// Self-consistency voting
+// With the mode on, each prompt is answered several times and the reply is
+// the consensus: the answer most samples agree on, or one the model writes
+// after reading them all. The samples are kept on the reply, expandable under
+// it, so the vote can be checked.
+
+use crate::llm_playground::{
+    flexible_client::FlexibleLLMClient, provider_config::FlexibleApiConfig, Message, MessageRole,
+    MAX_CANDIDATES,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Characters of each sample given to the model when it aggregates
+const SAMPLE_CHARS: usize = 3000;
+
+pub const META_PROMPT: &str = "You aggregate several independent answers to the same question \
+into the consensus answer. Work out which final answer most of them reach, resolve \
+disagreements in favour of the best-supported reasoning, and write that answer as a complete \
+reply to the question. Don't mention that there were several answers.";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsensusMethod {
+    /// The sample whose final answer is most common
+    #[default]
+    Majority,
+    /// The model reads the samples and writes the consensus
+    Model,
+}
+
+impl ConsensusMethod {
+    pub const ALL: [ConsensusMethod; 2] = [ConsensusMethod::Majority, ConsensusMethod::Model];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConsensusMethod::Majority => "Majority vote",
+            ConsensusMethod::Model => "Model aggregation",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|method| method.label() == value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SelfConsistencySettings {
+    pub enabled: bool,
+    /// Completions sampled per prompt
+    pub samples: u32,
+    pub method: ConsensusMethod,
+}
+
+impl Default for SelfConsistencySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            samples: 3,
+            method: ConsensusMethod::Majority,
+        }
+    }
+}
+
+impl SelfConsistencySettings {
+    /// Samples per prompt, between 2 and MAX_CANDIDATES
+    pub fn samples(&self) -> u32 {
+        self.samples.clamp(2, MAX_CANDIDATES)
+    }
+
+    /// Have the request sample the completions to vote on
+    pub fn apply(&self, config: &mut FlexibleApiConfig) {
+        if self.enabled {
+            config.shared_settings.candidate_count = Some(self.samples());
+        }
+    }
+}
+
+/// How a reply's consensus was reached, kept on the message
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConsensusResult {
+    pub method: ConsensusMethod,
+    /// Samples sharing the most common final answer
+    pub votes: usize,
+}
+
+impl ConsensusResult {
+    /// "Majority vote · 2 of 3 agree"
+    pub fn summary(&self, samples: usize) -> String {
+        format!("{} · {} of {} agree", self.method.label(), self.votes, samples)
+    }
+}
+
+/// The final answer of a sample, normalized for comparison: the text after
+/// the last "answer:" if there is one, otherwise the last non-empty line
+pub fn final_answer(text: &str) -> String {
+    let lower = text.to_lowercase();
+    let answer = match lower.rfind("answer:") {
+        Some(at) => lower[at + "answer:".len()..].lines().next().unwrap_or_default(),
+        None => lower.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or_default(),
+    };
+    answer
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_string()
+}
+
+/// Index of the first sample with the most common final answer, and how many
+/// samples share it
+pub fn majority(samples: &[String]) -> Option<(usize, usize)> {
+    let mut groups: HashMap<String, (usize, usize)> = HashMap::new();
+    for (index, sample) in samples.iter().enumerate() {
+        groups.entry(final_answer(sample)).or_insert((index, 0)).1 += 1;
+    }
+    groups
+        .into_values()
+        .max_by(|(a_index, a_votes), (b_index, b_votes)| {
+            a_votes.cmp(b_votes).then_with(|| b_index.cmp(a_index))
+        })
+}
+
+/// The single user message asking for the consensus
+pub fn build_request(question: &str, samples: &[String]) -> String {
+    let answers: Vec<String> = samples
+        .iter()
+        .enumerate()
+        .map(|(index, sample)| {
+            let sample: String = sample.chars().take(SAMPLE_CHARS).collect();
+            format!("Answer {}:\n{}", index + 1, sample)
+        })
+        .collect();
+    format!(
+        "Question:\n{}\n\n{}\n\nWrite the consensus answer.",
+        question.trim(),
+        answers.join("\n\n")
+    )
+}
+
+/// Have the model write the consensus of the samples
+pub async fn aggregate(
+    client: &FlexibleLLMClient,
+    config: &FlexibleApiConfig,
+    question: &str,
+    samples: &[String],
+) -> Result<String, String> {
+    let mut config = config.clone();
+    config.system_prompt = META_PROMPT.to_string();
+    config.active_structured_output = None;
+    config.shared_settings.candidate_count = None;
+    for tool in &mut config.function_tools {
+        tool.enabled = false;
+    }
+
+    let message = Message {
+        id: format!("consensus_{}", js_sys::Date::now() as u64),
+        timestamp: js_sys::Date::now(),
+        ..Message::new(MessageRole::User, build_request(question, samples))
+    };
+    let response = client.send_message(&[message], &config).await?;
+    response
+        .content
+        .filter(|content| !content.trim().is_empty())
+        .ok_or_else(|| "The model returned no consensus".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|text| text.to_string()).collect()
+    }
+
+    #[test]
+    fn final_answers_are_normalized() {
+        assert_eq!(final_answer("Let me think.\nAnswer: 42."), "42");
+        assert_eq!(final_answer("So the ANSWER:  Paris  \nThanks"), "paris");
+        assert_eq!(final_answer("Step one\n\nThe total is 12\n\n"), "the total is 12");
+    }
+
+    #[test]
+    fn majority_prefers_the_earliest_of_the_largest_group() {
+        let votes = samples(&["Answer: 7", "Answer: 8", "answer: 8!", "Answer: 7"]);
+        assert_eq!(majority(&votes), Some((0, 2)));
+        let votes = samples(&["Answer: 3", "Answer: 5", "Answer: 5"]);
+        assert_eq!(majority(&votes), Some((1, 2)));
+        assert_eq!(majority(&[]), None);
+    }
+}
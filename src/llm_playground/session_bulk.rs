@@ -0,0 +1,148 @@
+// Bulk operations on sessions
+// In the sidebar's select mode one action is applied to every selected
+// session. Locked sessions are left out of deletes; tags and archiving only
+// organize sessions and apply to them too. Merging makes a new session from
+// the selected ones.
+use crate::llm_playground::session_merge::MergeOrder;
+use crate::llm_playground::session_pages;
+use crate::llm_playground::ChatSession;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BulkAction {
+    Delete,
+    Export,
+    Archive,
+    Tag(String),
+    Merge(MergeOrder),
+}
+
+/// The tag as stored: trimmed with inner whitespace collapsed, None if empty
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Archive or tag the selected sessions. Returns how many changed.
+pub fn apply(
+    sessions: &mut HashMap<String, ChatSession>,
+    ids: &[String],
+    action: &BulkAction,
+) -> usize {
+    let mut changed = 0;
+    for id in ids {
+        let Some(session) = sessions.get_mut(id) else {
+            continue;
+        };
+        match action {
+            BulkAction::Archive if !session.archived => session.archived = true,
+            BulkAction::Tag(tag) if !session.tags.contains(tag) => session.tags.push(tag.clone()),
+            _ => continue,
+        }
+        changed += 1;
+    }
+    changed
+}
+
+/// Selected sessions that may be deleted, and how many are locked
+pub fn deletable(sessions: &HashMap<String, ChatSession>, ids: &[String]) -> (Vec<String>, usize) {
+    let (locked, deletable): (Vec<&String>, Vec<&String>) = ids
+        .iter()
+        .filter(|id| sessions.contains_key(*id))
+        .partition(|id| sessions[*id].locked);
+    (deletable.into_iter().cloned().collect(), locked.len())
+}
+
+/// The selected sessions as a JSON array, newest first
+pub fn export_json(
+    sessions: &HashMap<String, ChatSession>,
+    ids: &[String],
+) -> Result<String, String> {
+    let mut selected: Vec<ChatSession> = ids
+        .iter()
+        .filter_map(|id| sessions.get(id))
+        .map(session_pages::hydrated)
+        .collect();
+    if selected.is_empty() {
+        return Err("No sessions selected".to_string());
+    }
+    selected.sort_by(|a, b| b.updated_at.total_cmp(&a.updated_at));
+    serde_json::to_string_pretty(&selected)
+        .map_err(|e| format!("Failed to serialize sessions: {}", e))
+}
+
+pub fn export_file_name(count: usize) -> String {
+    format!(
+        "llm-playground-{}-session{}.json",
+        count,
+        if count == 1 { "" } else { "s" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, updated_at: f64, locked: bool) -> ChatSession {
+        ChatSession {
+            id: id.to_string(),
+            title: format!("Session {}", id),
+            messages: Vec::new(),
+            created_at: 0.0,
+            updated_at,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    fn sessions() -> HashMap<String, ChatSession> {
+        [
+            session("a", 1.0, false),
+            session("b", 2.0, true),
+            session("c", 3.0, false),
+        ]
+        .into_iter()
+        .map(|s| (s.id.clone(), s))
+        .collect()
+    }
+
+    #[test]
+    fn tags_and_archiving_apply_once_per_session() {
+        let mut sessions = sessions();
+        let ids = vec!["a".to_string(), "b".to_string(), "gone".to_string()];
+        let tag = BulkAction::Tag(normalize_tag("  work   notes ").unwrap());
+        assert_eq!(apply(&mut sessions, &ids, &tag), 2);
+        assert_eq!(apply(&mut sessions, &ids, &tag), 0);
+        assert_eq!(sessions["b"].tags, ["work notes"]);
+        assert!(sessions["c"].tags.is_empty());
+
+        assert_eq!(apply(&mut sessions, &ids, &BulkAction::Archive), 2);
+        assert!(sessions["a"].archived && !sessions["c"].archived);
+        assert_eq!(normalize_tag("   "), None);
+    }
+
+    #[test]
+    fn locked_sessions_are_not_deleted_and_exports_are_newest_first() {
+        let sessions = sessions();
+        let ids = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let (ids_to_delete, locked) = deletable(&sessions, &ids);
+        assert_eq!(ids_to_delete, ["a", "c"]);
+        assert_eq!(locked, 1);
+
+        let exported: Vec<ChatSession> =
+            serde_json::from_str(&export_json(&sessions, &ids).unwrap()).unwrap();
+        let order: Vec<_> = exported.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(order, ["c", "b", "a"]);
+        assert!(export_json(&sessions, &[]).is_err());
+        assert_eq!(export_file_name(1), "llm-playground-1-session.json");
+    }
+}
@@ -0,0 +1,510 @@
+// Session databases
+// Each session can have its own SQLite database, run in the page with sql.js
+// and saved to IndexedDB after every change, in a database of its own with the
+// SQLite file's bytes under the session id. Incognito sessions' databases stay
+// in memory. CSV files are imported as tables from the session's database view,
+// and the `run_sql` tool lets the model query them; its results show in the
+// chat as tables.
+
+use crate::llm_playground::logging;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+pub const RUN_SQL_TOOL_NAME: &str = "run_sql";
+
+/// sql.js build loaded on first use; its .wasm file is fetched from beside it
+const SQL_JS_URL: &str = "https://cdn.jsdelivr.net/npm/sql.js@1.12.0/dist/sql-wasm.js";
+/// Rows of each result given to the model and shown in the chat
+pub const MAX_ROWS: usize = 200;
+
+/// Loads sql.js once per page and resolves with its module
+const LOAD_SQL_JS: &str = r#"
+if (!globalThis.__llmPlaygroundSqlJs) {
+    globalThis.__llmPlaygroundSqlJs = new Promise((resolve, reject) => {
+        const script = document.createElement("script");
+        script.src = url;
+        script.onload = () => resolve(initSqlJs({ locateFile: (file) => url.replace(/[^/]*$/, file) }));
+        script.onerror = () => reject(new Error("Couldn't load sql.js"));
+        document.head.appendChild(script);
+    }).catch((error) => {
+        delete globalThis.__llmPlaygroundSqlJs;
+        throw error;
+    });
+}
+return globalThis.__llmPlaygroundSqlJs;
+"#;
+
+/// Runs SQL and returns every statement's columns and rows as JSON text
+const EXEC: &str = r#"
+const results = db.exec(sql, params ? JSON.parse(params) : undefined).map(({ columns, values }) => ({
+    columns,
+    rows: values.map((row) => row.map((value) => value instanceof Uint8Array ? `<${value.length} byte blob>` : value)),
+}));
+return JSON.stringify({ results, changes: db.getRowsModified() });
+"#;
+
+/// Runs `get`, `put` or `delete` on the stored SQLite files and resolves with
+/// the request's result
+const STORAGE_REQUEST: &str = r#"
+return new Promise((resolve, reject) => {
+    const open = indexedDB.open("llm_playground_session_databases", 1);
+    open.onupgradeneeded = () => open.result.createObjectStore("databases");
+    open.onerror = () => reject(open.error);
+    open.onsuccess = () => {
+        const db = open.result;
+        const store = db.transaction("databases", action === "get" ? "readonly" : "readwrite").objectStore("databases");
+        const request = action === "put" ? store.put(bytes, key) : store[action](key);
+        request.onsuccess = () => { db.close(); resolve(request.result); };
+        request.onerror = () => { db.close(); reject(request.error); };
+    };
+});
+"#;
+
+thread_local! {
+    /// Open sql.js databases by session id
+    static DATABASES: RefCell<HashMap<String, JsValue>> = RefCell::new(HashMap::new());
+}
+
+/// One statement's result
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    /// Rows the statement returned, including ones cut from `rows`
+    #[serde(default)]
+    pub total_rows: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExecOutput {
+    results: Vec<QueryResult>,
+    changes: usize,
+}
+
+/// A table in a session's database
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableInfo {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub rows: usize,
+}
+
+/// Whether any statement in `sql` may change the database
+pub fn writes(sql: &str) -> bool {
+    sql.split(';').any(|statement| {
+        let keyword = statement
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        !keyword.is_empty() && !matches!(keyword.as_str(), "select" | "with" | "explain" | "values")
+    })
+}
+
+/// An identifier quoted for SQL
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Rows of a CSV file; tab-separated when the file name or first line says so
+pub fn parse_csv(file_name: &str, text: &str) -> Vec<Vec<String>> {
+    let text = text.trim_start_matches('\u{feff}');
+    let first_line = text.lines().next().unwrap_or_default();
+    let delimiter = if file_name.to_ascii_lowercase().ends_with(".tsv")
+        || (first_line.contains('\t') && !first_line.contains(','))
+    {
+        '\t'
+    } else {
+        ','
+    };
+
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            c if c == delimiter && !quoted => row.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows.retain(|row| row.iter().any(|field| !field.trim().is_empty()));
+    rows
+}
+
+/// A table name from a file name, not clashing with `taken`
+pub fn table_name(file_name: &str, taken: &[String]) -> String {
+    let stem = file_name.rsplit_once('.').map_or(file_name, |(stem, _)| stem);
+    let base = match identifier(stem) {
+        name if name.is_empty() => "data".to_string(),
+        name => name,
+    };
+    let mut name = base.clone();
+    let mut n = 2;
+    while taken.iter().any(|table| table.eq_ignore_ascii_case(&name)) {
+        name = format!("{}_{}", base, n);
+        n += 1;
+    }
+    name
+}
+
+/// Column names from a header row: lowercase identifiers, unique, none empty
+pub fn column_names(header: &[String]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (index, field) in header.iter().enumerate() {
+        let base = match identifier(field) {
+            name if name.is_empty() => format!("column_{}", index + 1),
+            name => name,
+        };
+        let mut name = base.clone();
+        let mut n = 2;
+        while names.contains(&name) {
+            name = format!("{}_{}", base, n);
+            n += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+/// Letters, digits and underscores, not starting with a digit
+fn identifier(text: &str) -> String {
+    let mut name = String::new();
+    for c in text.trim().chars() {
+        if c.is_alphanumeric() {
+            name.extend(c.to_lowercase());
+        } else if !name.is_empty() && !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    let name = name.trim_end_matches('_').to_string();
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// The SQLite type every non-empty value in a column fits
+pub fn column_type<'a>(values: impl Iterator<Item = &'a str>) -> &'static str {
+    let mut column_type = "INTEGER";
+    for value in values.map(str::trim).filter(|value| !value.is_empty()) {
+        if column_type == "INTEGER" && value.parse::<i64>().is_err() {
+            column_type = "REAL";
+        }
+        if column_type == "REAL" && value.parse::<f64>().is_err() {
+            return "TEXT";
+        }
+    }
+    column_type
+}
+
+/// A CSV value as an SQL parameter of its column's type; empty is NULL
+fn parameter(value: &str, column_type: &str) -> Value {
+    let trimmed = value.trim();
+    match column_type {
+        _ if trimmed.is_empty() => Value::Null,
+        "INTEGER" => trimmed.parse::<i64>().map_or(Value::Null, Value::from),
+        "REAL" => trimmed.parse::<f64>().map_or(Value::Null, Value::from),
+        _ => Value::from(value),
+    }
+}
+
+/// The statements creating a table for CSV rows: its definition, and the
+/// insert with the parameters of each row
+pub fn import_statements(table: &str, rows: &[Vec<String>]) -> Result<(String, String, Vec<Value>), String> {
+    let (header, records) = rows.split_first().ok_or("The file has no rows")?;
+    let columns = column_names(header);
+    let types: Vec<&str> = (0..columns.len())
+        .map(|index| column_type(records.iter().map(|row| row.get(index).map_or("", String::as_str))))
+        .collect();
+    let definitions: Vec<String> = columns
+        .iter()
+        .zip(&types)
+        .map(|(column, column_type)| format!("{} {}", quote_identifier(column), column_type))
+        .collect();
+    let create = format!("CREATE TABLE {} ({})", quote_identifier(table), definitions.join(", "));
+    let insert = format!(
+        "INSERT INTO {} VALUES ({})",
+        quote_identifier(table),
+        vec!["?"; columns.len()].join(", ")
+    );
+    let parameters = records
+        .iter()
+        .map(|row| {
+            Value::Array(
+                types
+                    .iter()
+                    .enumerate()
+                    .map(|(index, column_type)| parameter(row.get(index).map_or("", String::as_str), column_type))
+                    .collect(),
+            )
+        })
+        .collect();
+    Ok((create, insert, parameters))
+}
+
+/// The tool result: each statement's rows cut to MAX_ROWS
+pub fn to_tool_result(mut results: Vec<QueryResult>, changes: usize) -> Value {
+    for result in &mut results {
+        result.total_rows = result.rows.len();
+        result.rows.truncate(MAX_ROWS);
+    }
+    json!({ "results": results, "changes": changes })
+}
+
+/// The results recorded on a run_sql function response, for the chat's tables
+pub fn results_from_function_response(function_response: &Value) -> Option<Vec<QueryResult>> {
+    if function_response.get("name")?.as_str()? != RUN_SQL_TOOL_NAME {
+        return None;
+    }
+    serde_json::from_value(function_response.pointer("/response/results")?.clone()).ok()
+}
+
+fn js_error(error: JsValue) -> String {
+    error
+        .dyn_ref::<js_sys::Error>()
+        .map(|error| String::from(error.message()))
+        .unwrap_or_else(|| format!("{:?}", error))
+}
+
+/// Run a request on the stored SQLite files; `bytes` is only used by `put`
+async fn storage_request(action: &str, session_id: &str, bytes: &JsValue) -> Result<JsValue, String> {
+    let request = js_sys::Function::new_with_args("action, key, bytes", STORAGE_REQUEST);
+    let promise: js_sys::Promise = request
+        .call3(&JsValue::NULL, &action.into(), &session_id.into(), bytes)
+        .map_err(js_error)?
+        .into();
+    JsFuture::from(promise)
+        .await
+        .map_err(|e| format!("Session database storage failed: {}", js_error(e)))
+}
+
+/// The session's database, opened from storage (or empty) on first use;
+/// incognito sessions never read storage
+async fn database(session_id: &str, incognito: bool) -> Result<JsValue, String> {
+    if let Some(db) = DATABASES.with(|databases| databases.borrow().get(session_id).cloned()) {
+        return Ok(db);
+    }
+    let load = js_sys::Function::new_with_args("url", LOAD_SQL_JS);
+    let promise: js_sys::Promise = load.call1(&JsValue::NULL, &SQL_JS_URL.into()).map_err(js_error)?.into();
+    let sql = JsFuture::from(promise).await.map_err(js_error)?;
+    let bytes = if incognito {
+        None
+    } else {
+        storage_request("get", session_id, &JsValue::UNDEFINED)
+            .await?
+            .dyn_into::<js_sys::Uint8Array>()
+            .ok()
+    };
+
+    let constructor: js_sys::Function = js_sys::Reflect::get(&sql, &"Database".into())
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(|_| "sql.js has no Database".to_string())?;
+    let args = match bytes {
+        Some(bytes) => js_sys::Array::of1(&bytes),
+        None => js_sys::Array::new(),
+    };
+    let db: JsValue = js_sys::Reflect::construct(&constructor, &args).map_err(js_error)?;
+    DATABASES.with(|databases| databases.borrow_mut().insert(session_id.to_string(), db.clone()));
+    Ok(db)
+}
+
+fn exec(db: &JsValue, sql: &str, parameters: Option<&Value>) -> Result<ExecOutput, String> {
+    let exec = js_sys::Function::new_with_args("db, sql, params", EXEC);
+    let parameters = parameters.map_or(JsValue::UNDEFINED, |parameters| parameters.to_string().into());
+    let text = exec
+        .call3(&JsValue::NULL, db, &sql.into(), &parameters)
+        .map_err(js_error)?
+        .as_string()
+        .unwrap_or_default();
+    serde_json::from_str(&text).map_err(|e| format!("Unreadable query result: {}", e))
+}
+
+async fn save(session_id: &str, db: &JsValue) -> Result<(), String> {
+    let export: js_sys::Function = js_sys::Reflect::get(db, &"export".into())
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(|_| "sql.js database can't be exported".to_string())?;
+    let bytes: js_sys::Uint8Array = export.call0(db).map_err(js_error)?.unchecked_into();
+    storage_request("put", session_id, &bytes).await.map(|_| ())
+}
+
+/// Run the tool's query on the session's database; incognito sessions' changes
+/// aren't saved
+pub async fn run_sql(session_id: &str, incognito: bool, arguments: &Value) -> Result<Value, String> {
+    let query = arguments
+        .get("query")
+        .and_then(Value::as_str)
+        .filter(|query| !query.trim().is_empty())
+        .ok_or("Missing required parameter: query")?;
+    let db = database(session_id, incognito).await?;
+    let output = exec(&db, query, None)?;
+    if writes(query) && !incognito {
+        save(session_id, &db).await?;
+    }
+    Ok(to_tool_result(output.results, output.changes))
+}
+
+/// Import a CSV file as a new table; returns the table's name and row count
+pub async fn import_csv(
+    session_id: &str,
+    incognito: bool,
+    file_name: &str,
+    text: &str,
+) -> Result<(String, usize), String> {
+    let rows = parse_csv(file_name, text);
+    let db = database(session_id, incognito).await?;
+    let taken: Vec<String> = tables(session_id, incognito).await?.into_iter().map(|table| table.name).collect();
+    let table = table_name(file_name, &taken);
+    let (create, insert, parameters) = import_statements(&table, &rows)?;
+
+    exec(&db, "BEGIN", None)?;
+    let imported = (|| {
+        exec(&db, &create, None)?;
+        for row in &parameters {
+            exec(&db, &insert, Some(row))?;
+        }
+        exec(&db, "COMMIT", None)
+    })();
+    if let Err(error) = imported {
+        let _ = exec(&db, "ROLLBACK", None);
+        return Err(error);
+    }
+    if !incognito {
+        save(session_id, &db).await?;
+    }
+    Ok((table, parameters.len()))
+}
+
+/// The tables in the session's database with their columns and row counts
+pub async fn tables(session_id: &str, incognito: bool) -> Result<Vec<TableInfo>, String> {
+    let db = database(session_id, incognito).await?;
+    let names = exec(
+        &db,
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        None,
+    )?;
+    let names: Vec<String> = names
+        .results
+        .into_iter()
+        .flat_map(|result| result.rows)
+        .filter_map(|row| row.first().and_then(Value::as_str).map(str::to_string))
+        .collect();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let columns = exec(&db, &format!("SELECT name FROM pragma_table_info({})", sql_string(&name)), None)?
+                .results
+                .into_iter()
+                .flat_map(|result| result.rows)
+                .filter_map(|row| row.first().and_then(Value::as_str).map(str::to_string))
+                .collect();
+            let rows = exec(&db, &format!("SELECT COUNT(*) FROM {}", quote_identifier(&name)), None)?
+                .results
+                .first()
+                .and_then(|result| result.rows.first())
+                .and_then(|row| row.first())
+                .and_then(Value::as_u64)
+                .unwrap_or_default() as usize;
+            Ok(TableInfo { name, columns, rows })
+        })
+        .collect()
+}
+
+fn sql_string(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Drop the session's database, in memory and in storage
+pub fn delete(session_id: &str) {
+    if let Some(db) = DATABASES.with(|databases| databases.borrow_mut().remove(session_id)) {
+        let close = js_sys::Reflect::get(&db, &"close".into()).ok();
+        if let Some(close) = close.and_then(|close| close.dyn_into::<js_sys::Function>().ok()) {
+            let _ = close.call0(&db);
+        }
+    }
+    let session_id = session_id.to_string();
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(error) = storage_request("delete", &session_id, &JsValue::UNDEFINED).await {
+            logging::warn!("Couldn't delete the database of session {}: {}", session_id, error);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_and_quotes_are_read() {
+        let text = "\u{feff}Name,\"Note, with comma\",Score\r\nAda,\"said \"\"hi\"\"\nthen left\",3\r\n\r\nBob,,4.5\n";
+        assert_eq!(
+            parse_csv("people.csv", text),
+            vec![
+                vec!["Name", "Note, with comma", "Score"],
+                vec!["Ada", "said \"hi\"\nthen left", "3"],
+                vec!["Bob", "", "4.5"],
+            ]
+        );
+        assert_eq!(parse_csv("data.tsv", "a\tb\n1\t2"), vec![vec!["a", "b"], vec!["1", "2"]]);
+    }
+
+    #[test]
+    fn imports_get_typed_columns() {
+        let rows = parse_csv("2024 Sales (EU).csv", "Region,Units,Price,Units\nNorth,3,9.5,\nSouth,,10,x\n");
+        let table = table_name("2024 Sales (EU).csv", &["_2024_sales_eu".to_string()]);
+        assert_eq!(table, "_2024_sales_eu_2");
+
+        let (create, insert, parameters) = import_statements(&table, &rows).unwrap();
+        assert_eq!(
+            create,
+            "CREATE TABLE \"_2024_sales_eu_2\" (\"region\" TEXT, \"units\" INTEGER, \"price\" REAL, \"units_2\" TEXT)"
+        );
+        assert_eq!(insert, "INSERT INTO \"_2024_sales_eu_2\" VALUES (?, ?, ?, ?)");
+        assert_eq!(parameters, vec![json!(["North", 3, 9.5, null]), json!(["South", null, 10.0, "x"])]);
+        assert!(import_statements("empty", &[]).is_err());
+    }
+
+    #[test]
+    fn only_reads_skip_saving() {
+        assert!(!writes("SELECT 1; with t as (select 2) select * from t;"));
+        assert!(writes("select 1; DELETE FROM sales"));
+        assert!(writes("create table t (a)"));
+    }
+
+    #[test]
+    fn results_are_capped() {
+        let rows = (0..MAX_ROWS + 5).map(|n| vec![json!(n)]).collect();
+        let result = to_tool_result(
+            vec![QueryResult { columns: vec!["n".to_string()], rows, total_rows: 0 }],
+            0,
+        );
+        let function_response = json!({ "name": RUN_SQL_TOOL_NAME, "response": result });
+        let results = results_from_function_response(&function_response).unwrap();
+        assert_eq!(results[0].rows.len(), MAX_ROWS);
+        assert_eq!(results[0].total_rows, MAX_ROWS + 5);
+    }
+}
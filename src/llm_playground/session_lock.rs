@@ -0,0 +1,77 @@
+// Locked sessions
+// A locked session is kept as a reference conversation: the chat refuses to
+// send, and updates that would change its messages, title or other content
+// are dropped. Pinning, bookmarking, tagging, archiving and the lock itself
+// still work.
+use crate::llm_playground::ChatSession;
+
+/// Whether `updated` may replace `current`
+pub fn allows_update(current: &ChatSession, updated: &ChatSession) -> bool {
+    if !current.locked {
+        return true;
+    }
+    let mut allowed = current.clone();
+    allowed.locked = updated.locked;
+    allowed.pinned = updated.pinned;
+    allowed.pinned_messages = updated.pinned_messages.clone();
+    allowed.tags = updated.tags.clone();
+    allowed.archived = updated.archived;
+    allowed == *updated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::{Message, MessageRole};
+
+    fn session(locked: bool) -> ChatSession {
+        ChatSession {
+            id: "s".to_string(),
+            title: "Reference".to_string(),
+            messages: vec![Message {
+                id: "m1".to_string(),
+                timestamp: 1.0,
+                ..Message::new(MessageRole::User, "Hello")
+            }],
+            created_at: 0.0,
+            updated_at: 0.0,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    #[test]
+    fn locked_sessions_keep_their_content() {
+        let current = session(true);
+        let mut edited = current.clone();
+        edited.messages.clear();
+        assert!(!allows_update(&current, &edited));
+        let mut renamed = current.clone();
+        renamed.title = "Renamed".to_string();
+        assert!(!allows_update(&current, &renamed));
+
+        assert!(allows_update(&session(false), &edited));
+    }
+
+    #[test]
+    fn bookmarks_and_the_lock_still_change() {
+        let current = session(true);
+        let mut pinned = current.clone();
+        pinned.pinned_messages.push("m1".to_string());
+        pinned.pinned = true;
+        assert!(allows_update(&current, &pinned));
+
+        let mut unlocked = current.clone();
+        unlocked.locked = false;
+        assert!(allows_update(&current, &unlocked));
+    }
+}
@@ -0,0 +1,212 @@
+// Merging sessions
+// Sessions picked in the sidebar's select mode can be merged into a new one,
+// e.g. to keep what came out of a scratch session together with the main
+// conversation. The originals are left as they are. Tool results stay right
+// after the message that called the tool, whichever order is picked.
+use crate::llm_playground::{ChatSession, Message, MessageRole};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergeOrder {
+    /// Each session's messages after those of the session started before it
+    #[default]
+    Append,
+    /// Messages of all sessions ordered by when they were sent
+    Interleave,
+}
+
+impl MergeOrder {
+    pub const ALL: [MergeOrder; 2] = [MergeOrder::Append, MergeOrder::Interleave];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MergeOrder::Append => "Append",
+            MergeOrder::Interleave => "Interleave by time",
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            MergeOrder::Append => "append",
+            MergeOrder::Interleave => "interleave",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|order| order.key() == key)
+            .unwrap_or_default()
+    }
+}
+
+/// A message followed by the tool results that answer it
+fn blocks(messages: &[Message]) -> Vec<&[Message]> {
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    for (index, message) in messages.iter().enumerate().skip(1) {
+        if message.role != MessageRole::Function {
+            blocks.push(&messages[start..index]);
+            start = index;
+        }
+    }
+    if start < messages.len() {
+        blocks.push(&messages[start..]);
+    }
+    blocks
+}
+
+fn title(sources: &[&ChatSession]) -> String {
+    match sources {
+        [first, second] => format!("{} + {}", first.title, second.title),
+        [first, rest @ ..] => format!("{} + {} more", first.title, rest.len()),
+        [] => "Merged session".to_string(),
+    }
+}
+
+/// A new session with the messages of `sources`, which are ordered by when
+/// they were started. Message ids that clash get a suffix.
+pub fn merge(sources: &[&ChatSession], order: MergeOrder, id: String, now: f64) -> ChatSession {
+    let mut sources = sources.to_vec();
+    sources.sort_by(|a, b| a.created_at.total_cmp(&b.created_at));
+
+    // Ids are made unique per session first, so pinned messages can follow them
+    let mut taken = HashSet::new();
+    let mut renamed: Vec<Vec<Message>> = Vec::new();
+    let mut pinned_messages = Vec::new();
+    for session in &sources {
+        let mut new_ids = HashMap::new();
+        let messages = session
+            .messages
+            .iter()
+            .map(|message| {
+                let mut new_id = message.id.clone();
+                let mut suffix = 2;
+                while !taken.insert(new_id.clone()) {
+                    new_id = format!("{}_{}", message.id, suffix);
+                    suffix += 1;
+                }
+                new_ids.insert(message.id.clone(), new_id.clone());
+                Message {
+                    id: new_id,
+                    ..message.clone()
+                }
+            })
+            .collect();
+        pinned_messages.extend(session.pinned_messages.iter().filter_map(|id| new_ids.get(id).cloned()));
+        renamed.push(messages);
+    }
+
+    let messages = match order {
+        MergeOrder::Append => renamed.concat(),
+        MergeOrder::Interleave => {
+            let mut blocks: Vec<&[Message]> = renamed.iter().flat_map(|messages| blocks(messages)).collect();
+            // Stable, so blocks sent at the same time keep their session order
+            blocks.sort_by(|a, b| a[0].timestamp.total_cmp(&b[0].timestamp));
+            blocks.concat()
+        }
+    };
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in sources.iter().flat_map(|session| &session.tags) {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    let persona = sources[0].persona.clone().filter(|persona| {
+        sources.iter().all(|session| session.persona.as_ref() == Some(persona))
+    });
+
+    ChatSession {
+        id,
+        title: title(&sources),
+        messages,
+        created_at: now,
+        updated_at: now,
+        pinned: false,
+        pinned_messages,
+        locked: false,
+        tags,
+        archived: false,
+        todos: sources.iter().flat_map(|session| session.todos.clone()).collect(),
+        budget_since: 0.0,
+        dialogue: None,
+        persona,
+        incognito: sources.iter().any(|session| session.incognito),
+        older_pages: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str, role: MessageRole, timestamp: f64) -> Message {
+        Message {
+            id: id.to_string(),
+            timestamp,
+            ..Message::new(role, id)
+        }
+    }
+
+    fn session(title: &str, created_at: f64, messages: Vec<Message>) -> ChatSession {
+        ChatSession {
+            id: title.to_string(),
+            title: title.to_string(),
+            messages,
+            created_at,
+            updated_at: created_at,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: false,
+            tags: vec!["work".to_string()],
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    fn ids(session: &ChatSession) -> Vec<&str> {
+        session.messages.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn appends_in_the_order_sessions_were_started() {
+        let scratch = session("Scratch", 2.0, vec![message("u1", MessageRole::User, 10.0)]);
+        let mut main = session(
+            "Main",
+            1.0,
+            vec![message("u1", MessageRole::User, 5.0), message("a1", MessageRole::Assistant, 20.0)],
+        );
+        main.pinned_messages = vec!["a1".to_string()];
+        let merged = merge(&[&scratch, &main], MergeOrder::Append, "m".to_string(), 30.0);
+        assert_eq!(merged.title, "Main + Scratch");
+        assert_eq!(ids(&merged), ["u1", "a1", "u1_2"]);
+        assert_eq!(merged.pinned_messages, ["a1"]);
+        assert_eq!(merged.tags, ["work"]);
+    }
+
+    #[test]
+    fn interleaving_keeps_tool_results_with_their_call() {
+        let first = session(
+            "A",
+            1.0,
+            vec![
+                message("a_call", MessageRole::Assistant, 1.0),
+                message("a_result", MessageRole::Function, 4.0),
+            ],
+        );
+        let second = session(
+            "B",
+            2.0,
+            vec![message("b_user", MessageRole::User, 2.0), message("b_reply", MessageRole::Assistant, 3.0)],
+        );
+        let merged = merge(&[&first, &second], MergeOrder::Interleave, "m".to_string(), 5.0);
+        assert_eq!(ids(&merged), ["a_call", "a_result", "b_user", "b_reply"]);
+        assert_eq!(MergeOrder::from_key("interleave"), MergeOrder::Interleave);
+    }
+}
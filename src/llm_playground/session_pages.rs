@@ -0,0 +1,193 @@
+// Paged session storage
+// Long sessions are stored in pages of PAGE_SIZE messages, each under its own
+// key, with only the newest messages kept in the session list. Loading the app
+// then reads the list and nothing else, and opening a session reads just
+// enough pages for its most recent messages; older pages are read when the
+// conversation is scrolled up, or all at once when something needs the whole
+// history, such as sending the next message or exporting.
+//
+// A session's `older_pages` counts the pages still in storage before its
+// loaded messages. Those pages are never rewritten while unloaded, so loaded
+// messages are cut into pages starting after them.
+
+use crate::llm_playground::{logging, storage, ChatSession, Message};
+use gloo_storage::{LocalStorage, Storage};
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Session list, holding each session with its newest messages
+pub const SESSIONS_KEY: &str = "llm_playground_sessions";
+/// Messages per stored page
+pub const PAGE_SIZE: usize = 50;
+/// Messages loaded when a session is opened, at least
+pub const RECENT_MESSAGES: usize = 50;
+
+thread_local! {
+    /// Hash of what was last written under each page key, so unchanged pages
+    /// aren't written again on every save
+    static WRITTEN: RefCell<HashMap<String, u64>> = RefCell::new(HashMap::new());
+    /// Pages stored for each session, to remove those no longer needed
+    static PAGE_COUNTS: RefCell<HashMap<String, usize>> = RefCell::new(HashMap::new());
+}
+
+/// Start of every page key; the session id and page number follow
+pub const PAGE_KEY_PREFIX: &str = "llm_playground_session_page_";
+
+fn page_key(session_id: &str, page: usize) -> String {
+    format!("{}{}_{}", PAGE_KEY_PREFIX, session_id, page)
+}
+
+/// Pages to store for a session's loaded messages, numbered from
+/// `older_pages`, and the newest messages that don't fill a page
+pub fn split(messages: &[Message], older_pages: usize) -> (Vec<(usize, &[Message])>, &[Message]) {
+    let chunks = messages.chunks_exact(PAGE_SIZE);
+    let tail = chunks.remainder();
+    let pages = chunks.enumerate().map(|(index, page)| (older_pages + index, page)).collect();
+    (pages, tail)
+}
+
+/// Messages in the session, loaded or not
+pub fn message_count(session: &ChatSession) -> usize {
+    session.older_pages * PAGE_SIZE + session.messages.len()
+}
+
+/// Write the sessions; `sessions` may be partly loaded. Incognito sessions
+/// are left out, they only live in memory.
+pub fn save_all<'a>(sessions: impl IntoIterator<Item = &'a ChatSession>) {
+    let mut list = HashMap::new();
+    let mut page_counts = HashMap::new();
+    for session in sessions.into_iter().filter(|session| !session.incognito) {
+        let (pages, tail) = split(&session.messages, session.older_pages);
+        for (page, messages) in &pages {
+            write_page(&page_key(&session.id, *page), messages);
+        }
+        let stored_pages = session.older_pages + pages.len();
+        page_counts.insert(session.id.clone(), stored_pages);
+        list.insert(
+            session.id.clone(),
+            ChatSession {
+                messages: tail.to_vec(),
+                older_pages: stored_pages,
+                ..session.clone()
+            },
+        );
+    }
+
+    // Pages of removed sessions, and of messages that were deleted
+    let previous = PAGE_COUNTS.with(|counts| counts.replace(page_counts.clone()));
+    for (session_id, count) in previous {
+        let kept = page_counts.get(&session_id).copied().unwrap_or(0);
+        for page in kept..count {
+            let key = page_key(&session_id, page);
+            LocalStorage::delete(&key);
+            WRITTEN.with(|written| written.borrow_mut().remove(&key));
+        }
+    }
+
+    if let Ok(list) = storage::payload_json(&list) {
+        let _ = LocalStorage::set(SESSIONS_KEY, list);
+    }
+}
+
+fn write_page(key: &str, messages: &[Message]) {
+    let Ok(json) = storage::payload_json(&messages) else {
+        return;
+    };
+    let mut hasher = DefaultHasher::new();
+    json.hash(&mut hasher);
+    let hash = hasher.finish();
+    if WRITTEN.with(|written| written.borrow().get(key) == Some(&hash)) {
+        return;
+    }
+    if LocalStorage::set(key, json).is_ok() {
+        WRITTEN.with(|written| written.borrow_mut().insert(key.to_string(), hash));
+    }
+}
+
+/// The stored sessions with only their newest messages loaded. Sessions
+/// saved before paging are read whole, and paged on the next save.
+pub fn load_all() -> Option<HashMap<String, ChatSession>> {
+    let sessions: HashMap<String, ChatSession> = storage::load_payload(SESSIONS_KEY)?;
+    PAGE_COUNTS.with(|counts| {
+        *counts.borrow_mut() = sessions
+            .values()
+            .map(|session| (session.id.clone(), session.older_pages))
+            .collect()
+    });
+    Some(sessions)
+}
+
+/// Load the page just before the loaded messages; false when there is none
+/// or it can't be read. An unreadable page is quarantined, and the session
+/// keeps counting it, so the pages before it are neither lost nor rewritten.
+pub fn load_older(session: &mut ChatSession) -> bool {
+    if session.older_pages == 0 {
+        return false;
+    }
+    let page = session.older_pages - 1;
+    let Some(messages) = storage::load_payload::<Vec<Message>>(&page_key(&session.id, page)) else {
+        logging::warn!("Page {} of session {} couldn't be loaded", page, session.id);
+        return false;
+    };
+    session.older_pages = page;
+    session.messages.splice(0..0, messages);
+    true
+}
+
+/// Load pages until at least RECENT_MESSAGES are loaded; false when none were
+pub fn load_recent(session: &mut ChatSession) -> bool {
+    let mut loaded = false;
+    while session.messages.len() < RECENT_MESSAGES && load_older(session) {
+        loaded = true;
+    }
+    loaded
+}
+
+/// Load every page of the session
+pub fn hydrate(session: &mut ChatSession) {
+    while load_older(session) {}
+}
+
+/// The session with its whole history
+pub fn hydrated(session: &ChatSession) -> ChatSession {
+    let mut session = session.clone();
+    hydrate(&mut session);
+    session
+}
+
+/// Every session with its whole history
+pub fn hydrated_all<'a>(sessions: impl IntoIterator<Item = &'a ChatSession>) -> HashMap<String, ChatSession> {
+    sessions
+        .into_iter()
+        .map(|session| (session.id.clone(), hydrated(session)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(count: usize) -> Vec<Message> {
+        (0..count)
+            .map(|index| Message {
+                id: index.to_string(),
+                ..Message::new(crate::llm_playground::MessageRole::User, "")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn full_pages_are_cut_after_the_unloaded_ones() {
+        let messages = messages(PAGE_SIZE * 2 + 7);
+        let (pages, tail) = split(&messages, 3);
+        assert_eq!(pages.iter().map(|(page, _)| *page).collect::<Vec<_>>(), [3, 4]);
+        assert_eq!(pages[1].1[0].id, PAGE_SIZE.to_string());
+        assert_eq!(tail.len(), 7);
+
+        let (pages, tail) = split(&messages[..5], 0);
+        assert!(pages.is_empty());
+        assert_eq!(tail.len(), 5);
+    }
+}
@@ -0,0 +1,287 @@
+// Session recording and replay
+// While recording is on for a session, every model request with its reply and
+// every tool call with its result is stored in local storage next to the
+// session. A recording can be replayed step by step with its original timing,
+// or its requests can be sent again to another model to compare the replies.
+
+use crate::llm_playground::{
+    api_clients::{FunctionCallRequest, LLMResponse, TokenUsage},
+    flexible_client::FlexibleLLMClient,
+    provider_config::FlexibleApiConfig,
+    Message,
+};
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub(crate) const RECORDINGS_KEY: &str = "llm_playground_recordings";
+
+/// Longest pause between two replayed steps at normal speed
+pub const MAX_REPLAY_GAP_MS: f64 = 10_000.0;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub content: Option<String>,
+    #[serde(default)]
+    pub function_calls: Vec<FunctionCallRequest>,
+    pub finish_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<String>,
+    pub usage: Option<TokenUsage>,
+}
+
+impl From<&LLMResponse> for RecordedResponse {
+    fn from(response: &LLMResponse) -> Self {
+        Self {
+            content: response.content.clone(),
+            function_calls: response.function_calls.clone(),
+            finish_reason: response.finish_reason.clone(),
+            reasoning: response.reasoning.clone(),
+            citations: response.citations.clone(),
+            usage: response.usage.clone(),
+        }
+    }
+}
+
+/// One call to the model: what was sent and what came back
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub provider: String,
+    pub model: String,
+    pub messages: Vec<Message>,
+    pub started_at: f64,
+    pub latency_ms: f64,
+    /// None when the request failed
+    pub response: Option<RecordedResponse>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedToolCall {
+    pub call_id: String,
+    pub name: String,
+    pub arguments: Value,
+    pub result: Value,
+    pub started_at: f64,
+    pub duration_ms: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedStep {
+    Request(RecordedRequest),
+    ToolCall(RecordedToolCall),
+}
+
+impl RecordedStep {
+    pub fn started_at(&self) -> f64 {
+        match self {
+            RecordedStep::Request(request) => request.started_at,
+            RecordedStep::ToolCall(call) => call.started_at,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionRecording {
+    /// New steps are only added while this is set
+    pub active: bool,
+    pub steps: Vec<RecordedStep>,
+}
+
+impl SessionRecording {
+    pub fn push(&mut self, step: RecordedStep) {
+        if self.active {
+            self.steps.push(step);
+        }
+    }
+
+    /// Messages step `index` sent on top of the previous request, e.g. the
+    /// new user message or the tool results
+    pub fn new_messages(&self, index: usize) -> &[Message] {
+        let Some(RecordedStep::Request(request)) = self.steps.get(index) else {
+            return &[];
+        };
+        let previous = self.steps[..index]
+            .iter()
+            .rev()
+            .find_map(|step| match step {
+                RecordedStep::Request(previous) => Some(previous.messages.len()),
+                _ => None,
+            });
+        let already_sent = match previous {
+            Some(count) if count <= request.messages.len() => count,
+            _ => 0,
+        };
+        &request.messages[already_sent..]
+    }
+
+    /// How long to wait before showing step `index` when replaying at `speed`
+    pub fn replay_delay_ms(&self, index: usize, speed: f64) -> u32 {
+        if index == 0 || index >= self.steps.len() || speed <= 0.0 {
+            return 0;
+        }
+        let gap = self.steps[index].started_at() - self.steps[index - 1].started_at();
+        (gap.clamp(0.0, MAX_REPLAY_GAP_MS) / speed) as u32
+    }
+}
+
+pub fn load_recordings() -> HashMap<String, SessionRecording> {
+    LocalStorage::get(RECORDINGS_KEY).unwrap_or_default()
+}
+
+fn save_recordings(recordings: &HashMap<String, SessionRecording>) {
+    let _ = LocalStorage::set(RECORDINGS_KEY, recordings);
+}
+
+pub fn load_recording(session_id: &str) -> Option<SessionRecording> {
+    load_recordings().remove(session_id)
+}
+
+/// Start or stop recording; steps recorded so far are kept
+pub fn set_recording(session_id: &str, active: bool) {
+    let mut recordings = load_recordings();
+    recordings.entry(session_id.to_string()).or_default().active = active;
+    save_recordings(&recordings);
+}
+
+/// Add a step to the session's recording if recording is on
+pub fn record(session_id: &str, step: RecordedStep) {
+    let mut recordings = load_recordings();
+    if let Some(recording) = recordings.get_mut(session_id).filter(|r| r.active) {
+        recording.push(step);
+        save_recordings(&recordings);
+    }
+}
+
+pub fn delete_recording(session_id: &str) {
+    let mut recordings = load_recordings();
+    if recordings.remove(session_id).is_some() {
+        save_recordings(&recordings);
+    }
+}
+
+/// Record a finished model request
+pub fn record_request(
+    session_id: &str,
+    config: &FlexibleApiConfig,
+    messages: &[Message],
+    started_at: f64,
+    result: &Result<LLMResponse, String>,
+) {
+    let (provider, model) = config.get_current_provider_and_model();
+    record(
+        session_id,
+        RecordedStep::Request(RecordedRequest {
+            provider,
+            model,
+            messages: messages.to_vec(),
+            started_at,
+            latency_ms: js_sys::Date::now() - started_at,
+            response: result.as_ref().ok().map(RecordedResponse::from),
+            error: result.as_ref().err().cloned(),
+        }),
+    );
+}
+
+/// Send a recorded request again, to `provider` / `model`
+pub async fn rerun(
+    request: &RecordedRequest,
+    provider: &str,
+    model: &str,
+    llm_client: &FlexibleLLMClient,
+    config: &FlexibleApiConfig,
+) -> (Result<RecordedResponse, String>, f64) {
+    let mut config = config.clone();
+    config.set_session_provider(provider, model);
+    config.apply_model_defaults(provider, model);
+    let started = js_sys::Date::now();
+    let result = llm_client
+        .send_message(&request.messages, &config)
+        .await
+        .map(|response| RecordedResponse::from(&response));
+    (result, js_sys::Date::now() - started)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::MessageRole;
+
+    fn message(content: &str) -> Message {
+        Message {
+            id: content.to_string(),
+            ..Message::new(MessageRole::User, content)
+        }
+    }
+
+    fn request(contents: &[&str], started_at: f64) -> RecordedStep {
+        RecordedStep::Request(RecordedRequest {
+            provider: "mock".to_string(),
+            model: "scripted".to_string(),
+            messages: contents.iter().map(|c| message(c)).collect(),
+            started_at,
+            latency_ms: 100.0,
+            response: None,
+            error: None,
+        })
+    }
+
+    fn tool_call(started_at: f64) -> RecordedStep {
+        RecordedStep::ToolCall(RecordedToolCall {
+            call_id: "call_1".to_string(),
+            name: "get_weather".to_string(),
+            arguments: serde_json::json!({}),
+            result: serde_json::json!({ "temperature": 22 }),
+            started_at,
+            duration_ms: 5.0,
+        })
+    }
+
+    #[test]
+    fn steps_are_only_recorded_while_active() {
+        let mut recording = SessionRecording::default();
+        recording.push(request(&["Hi"], 0.0));
+        assert!(recording.steps.is_empty());
+
+        recording.active = true;
+        recording.push(request(&["Hi"], 0.0));
+        recording.push(tool_call(1_000.0));
+        assert_eq!(recording.steps.len(), 2);
+
+        let stored = serde_json::to_value(&recording).unwrap();
+        assert_eq!(stored["steps"][1]["type"], "tool_call");
+        let restored: SessionRecording = serde_json::from_value(stored).unwrap();
+        assert_eq!(restored, recording);
+    }
+
+    #[test]
+    fn replay_shows_new_messages_with_recorded_gaps() {
+        let recording = SessionRecording {
+            active: false,
+            steps: vec![
+                request(&["Hi"], 0.0),
+                tool_call(1_500.0),
+                request(&["Hi", "Reply", "Tool result"], 2_000.0),
+                request(&["Hi", "Reply", "Tool result", "More"], 60_000.0),
+            ],
+        };
+        assert_eq!(recording.new_messages(0).len(), 1);
+        assert!(recording.new_messages(1).is_empty());
+        let new: Vec<&str> = recording
+            .new_messages(2)
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect();
+        assert_eq!(new, ["Reply", "Tool result"]);
+
+        assert_eq!(recording.replay_delay_ms(0, 1.0), 0);
+        assert_eq!(recording.replay_delay_ms(1, 1.0), 1_500);
+        assert_eq!(recording.replay_delay_ms(2, 2.0), 250);
+        // Long pauses are capped
+        assert_eq!(recording.replay_delay_ms(3, 1.0), MAX_REPLAY_GAP_MS as u32);
+    }
+}
@@ -0,0 +1,239 @@
+// Session search for the sidebar
+// Keyword search matches every query term against session titles and message
+// text. Semantic search embeds the query and all chat messages (vectors are
+// cached in IndexedDB) and ranks sessions by their most similar message.
+
+use crate::llm_playground::{
+    api_clients::embeddings::{
+        cosine_similarity, create_embeddings_client, embed_all, EmbeddingsClient,
+    },
+    knowledge_store::{self, MessageEmbedding},
+    provider_config::FlexibleApiConfig,
+    ChatSession, MessageRole,
+};
+use std::collections::HashMap;
+
+pub const MAX_SEMANTIC_RESULTS: usize = 20;
+/// Message text beyond this many characters is not embedded
+const MAX_EMBEDDED_CHARS: usize = 2000;
+const SNIPPET_CHARS: usize = 80;
+
+/// A session matching a search, with the best-matching message excerpt
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionHit {
+    pub session_id: String,
+    pub snippet: Option<String>,
+    /// Cosine similarity for semantic hits, 1.0 for keyword hits
+    pub score: f32,
+}
+
+/// Sessions whose title or messages contain every query term, in the given order
+pub fn keyword_search(sessions: &[&ChatSession], query: &str) -> Vec<SessionHit> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| term.to_lowercase())
+        .collect();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    sessions
+        .iter()
+        .filter_map(|session| {
+            // Tags are searched along with the title
+            let title = format!("{} {}", session.title, session.tags.join(" ")).to_lowercase();
+            let contents: Vec<String> = session
+                .messages
+                .iter()
+                .map(|m| m.content.to_lowercase())
+                .collect();
+            let all_found = terms
+                .iter()
+                .all(|term| title.contains(term) || contents.iter().any(|c| c.contains(term)));
+            if !all_found {
+                return None;
+            }
+
+            let snippet = session
+                .messages
+                .iter()
+                .zip(&contents)
+                .find_map(|(message, lower)| {
+                    lower.find(&terms[0]).map(|index| {
+                        snippet_around(&message.content, lower[..index].chars().count())
+                    })
+                });
+            Some(SessionHit {
+                session_id: session.id.clone(),
+                snippet,
+                score: 1.0,
+            })
+        })
+        .collect()
+}
+
+/// Whether semantic search can run, i.e. an embeddings model with a key is set
+pub fn semantic_available(config: &FlexibleApiConfig) -> bool {
+    create_embeddings_client(config).is_ok()
+}
+
+/// Rank sessions by how similar their closest message is to the query.
+/// Messages without a cached vector are embedded first.
+pub async fn semantic_search(
+    sessions: &HashMap<String, ChatSession>,
+    query: &str,
+    config: &FlexibleApiConfig,
+) -> Result<Vec<SessionHit>, String> {
+    let client = create_embeddings_client(config)?;
+    let embeddings = message_embeddings(sessions, client.as_ref()).await?;
+    let query_embedding = embed_all(client.as_ref(), &[query.to_string()])
+        .await?
+        .pop()
+        .unwrap_or_default();
+
+    let mut hits: Vec<SessionHit> = sessions
+        .values()
+        .filter_map(|session| {
+            session
+                .messages
+                .iter()
+                .filter_map(|message| {
+                    embeddings
+                        .get(&message.id)
+                        .map(|vector| (cosine_similarity(&query_embedding, vector), message))
+                })
+                .max_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(score, message)| SessionHit {
+                    session_id: session.id.clone(),
+                    snippet: Some(snippet_around(&message.content, 0)),
+                    score,
+                })
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(MAX_SEMANTIC_RESULTS);
+    Ok(hits)
+}
+
+/// Vectors for every searchable message, keyed by message id
+async fn message_embeddings(
+    sessions: &HashMap<String, ChatSession>,
+    client: &dyn EmbeddingsClient,
+) -> Result<HashMap<String, Vec<f32>>, String> {
+    let mut cached: HashMap<String, Vec<f32>> =
+        knowledge_store::message_embeddings(client.model_id())
+            .await?
+            .into_iter()
+            .map(|entry| (entry.message_id, entry.embedding))
+            .collect();
+
+    let missing: Vec<(String, String)> = sessions
+        .values()
+        .flat_map(|session| &session.messages)
+        .filter(|m| matches!(m.role, MessageRole::User | MessageRole::Assistant))
+        .filter(|m| !m.content.trim().is_empty() && !cached.contains_key(&m.id))
+        .map(|m| {
+            (
+                m.id.clone(),
+                m.content.chars().take(MAX_EMBEDDED_CHARS).collect(),
+            )
+        })
+        .collect();
+    if missing.is_empty() {
+        return Ok(cached);
+    }
+
+    let texts: Vec<String> = missing.iter().map(|(_, text)| text.clone()).collect();
+    let vectors = embed_all(client, &texts).await?;
+    let new_entries: Vec<MessageEmbedding> = missing
+        .into_iter()
+        .zip(vectors)
+        .map(|((message_id, _), embedding)| MessageEmbedding {
+            message_id,
+            model: client.model_id().to_string(),
+            embedding,
+        })
+        .collect();
+    knowledge_store::put_message_embeddings(&new_entries).await?;
+    cached.extend(
+        new_entries
+            .into_iter()
+            .map(|entry| (entry.message_id, entry.embedding)),
+    );
+    Ok(cached)
+}
+
+/// About SNIPPET_CHARS characters of `text` around the char offset `at`, on one line
+fn snippet_around(text: &str, at: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let start = at.saturating_sub(SNIPPET_CHARS / 4).min(chars.len());
+    let end = (start + SNIPPET_CHARS).min(chars.len());
+    let excerpt: String = chars[start..end]
+        .iter()
+        .map(|&c| if c.is_whitespace() { ' ' } else { c })
+        .collect();
+    format!(
+        "{}{}{}",
+        if start > 0 { "…" } else { "" },
+        excerpt.trim(),
+        if end < chars.len() { "…" } else { "" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::Message;
+
+    fn session(id: &str, title: &str, contents: &[&str]) -> ChatSession {
+        ChatSession {
+            id: id.to_string(),
+            title: title.to_string(),
+            messages: contents
+                .iter()
+                .enumerate()
+                .map(|(i, content)| Message {
+                    id: format!("{}_{}", id, i),
+                    ..Message::new(MessageRole::User, *content)
+                })
+                .collect(),
+            created_at: 0.0,
+            updated_at: 0.0,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: false,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    #[test]
+    fn keyword_search_requires_every_term() {
+        let rust = session(
+            "a",
+            "Rust lifetimes",
+            &["Why does the borrow checker complain?"],
+        );
+        let cooking = session("b", "Dinner", &["A recipe for borrowed time pasta"]);
+        let sessions = [&rust, &cooking];
+
+        let hits = keyword_search(&sessions, "BORROW checker");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session_id, "a");
+        assert_eq!(
+            hits[0].snippet.as_deref(),
+            Some("Why does the borrow checker complain?")
+        );
+
+        let titles = keyword_search(&sessions, "dinner pasta");
+        assert_eq!(titles.len(), 1);
+        assert_eq!(titles[0].session_id, "b");
+        assert!(keyword_search(&sessions, "   ").is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+// Session snapshots
+// A copy of a session is kept in local storage every SNAPSHOT_INTERVAL
+// messages, before a change that removes messages (a clear or a shorter
+// branch), before a restore, and whenever the user asks for one. Each session
+// keeps its MAX_SNAPSHOTS newest snapshots; restoring one puts its messages
+// back in the same session.
+use crate::llm_playground::{session_pages, ChatSession};
+use gloo_storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub(crate) const SNAPSHOTS_KEY: &str = "llm_playground_snapshots";
+
+/// Messages between two automatic snapshots
+pub const SNAPSHOT_INTERVAL: usize = 20;
+/// Snapshots kept per session; the oldest go first
+pub const MAX_SNAPSHOTS: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotReason {
+    Autosave,
+    BeforeRemoval,
+    BeforeRestore,
+    Manual,
+}
+
+impl SnapshotReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SnapshotReason::Autosave => "Autosave",
+            SnapshotReason::BeforeRemoval => "Before messages were removed",
+            SnapshotReason::BeforeRestore => "Before a restore",
+            SnapshotReason::Manual => "Saved by you",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionSnapshot {
+    pub taken_at: f64,
+    pub reason: SnapshotReason,
+    pub session: ChatSession,
+}
+
+impl SessionSnapshot {
+    pub fn new(session: &ChatSession, reason: SnapshotReason, taken_at: f64) -> Self {
+        Self {
+            taken_at,
+            reason,
+            session: session.clone(),
+        }
+    }
+
+    /// The session as it was, keeping its id and marked as changed now
+    pub fn restore(&self, now: f64) -> ChatSession {
+        let mut session = self.session.clone();
+        session.updated_at = now;
+        session
+    }
+}
+
+/// Add a snapshot, dropping the oldest beyond MAX_SNAPSHOTS. A snapshot of
+/// the same messages as the newest one is not added twice.
+pub fn push(snapshots: &mut Vec<SessionSnapshot>, snapshot: SessionSnapshot) {
+    let unchanged = snapshots
+        .last()
+        .is_some_and(|last| last.session.messages == snapshot.session.messages);
+    if unchanged {
+        return;
+    }
+    snapshots.push(snapshot);
+    let excess = snapshots.len().saturating_sub(MAX_SNAPSHOTS);
+    snapshots.drain(..excess);
+}
+
+/// The snapshot to take, if any, when `current` is replaced by `updated`
+pub fn snapshot_reason(
+    snapshots: &[SessionSnapshot],
+    current: &ChatSession,
+    updated: &ChatSession,
+) -> Option<SnapshotReason> {
+    if updated.messages.len() < current.messages.len() {
+        return Some(SnapshotReason::BeforeRemoval);
+    }
+    let last_autosave = snapshots
+        .iter()
+        .rev()
+        .find(|s| s.reason == SnapshotReason::Autosave)
+        .map(|s| s.session.messages.len())
+        // Counting starts over after the session was cleared or cut short
+        .filter(|count| *count <= updated.messages.len())
+        .unwrap_or_default();
+    (updated.messages.len() >= last_autosave + SNAPSHOT_INTERVAL)
+        .then_some(SnapshotReason::Autosave)
+}
+
+fn load_all() -> HashMap<String, Vec<SessionSnapshot>> {
+    LocalStorage::get(SNAPSHOTS_KEY).unwrap_or_default()
+}
+
+fn save_all(snapshots: &HashMap<String, Vec<SessionSnapshot>>) {
+    let _ = LocalStorage::set(SNAPSHOTS_KEY, snapshots);
+}
+
+/// The session's snapshots, oldest first
+pub fn load_snapshots(session_id: &str) -> Vec<SessionSnapshot> {
+    load_all().remove(session_id).unwrap_or_default()
+}
+
+pub fn take_snapshot(session: &ChatSession, reason: SnapshotReason) {
+    if session.incognito {
+        return;
+    }
+    let mut all = load_all();
+    push(
+        all.entry(session.id.clone()).or_default(),
+        SessionSnapshot::new(&session_pages::hydrated(session), reason, js_sys::Date::now()),
+    );
+    save_all(&all);
+}
+
+/// Snapshot what `updated` is about to replace when it removes messages, or
+/// the update when enough messages were added since the last autosave
+pub fn autosave(current: &ChatSession, updated: &ChatSession) {
+    let snapshots = load_snapshots(&current.id);
+    match snapshot_reason(&snapshots, current, updated) {
+        Some(SnapshotReason::BeforeRemoval) => {
+            take_snapshot(current, SnapshotReason::BeforeRemoval)
+        }
+        Some(reason) => take_snapshot(updated, reason),
+        None => {}
+    }
+}
+
+pub fn delete_snapshots(session_id: &str) {
+    let mut all = load_all();
+    if all.remove(session_id).is_some() {
+        save_all(&all);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::{Message, MessageRole};
+
+    fn session(message_count: usize) -> ChatSession {
+        ChatSession {
+            id: "s".to_string(),
+            title: "Session".to_string(),
+            messages: (0..message_count)
+                .map(|i| Message {
+                    id: format!("m{}", i),
+                    timestamp: i as f64,
+                    ..Message::new(MessageRole::User, format!("Message {}", i))
+                })
+                .collect(),
+            created_at: 0.0,
+            updated_at: 0.0,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: false,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    #[test]
+    fn snapshots_are_taken_every_interval_and_before_removals() {
+        let mut snapshots = Vec::new();
+        assert_eq!(snapshot_reason(&snapshots, &session(3), &session(4)), None);
+        assert_eq!(
+            snapshot_reason(&snapshots, &session(19), &session(SNAPSHOT_INTERVAL)),
+            Some(SnapshotReason::Autosave)
+        );
+        push(
+            &mut snapshots,
+            SessionSnapshot::new(&session(SNAPSHOT_INTERVAL), SnapshotReason::Autosave, 1.0),
+        );
+        assert_eq!(
+            snapshot_reason(&snapshots, &session(20), &session(21)),
+            None
+        );
+        assert_eq!(
+            snapshot_reason(&snapshots, &session(39), &session(40)),
+            Some(SnapshotReason::Autosave)
+        );
+        // A clear is caught whatever the count
+        assert_eq!(
+            snapshot_reason(&snapshots, &session(5), &session(0)),
+            Some(SnapshotReason::BeforeRemoval)
+        );
+    }
+
+    #[test]
+    fn only_the_newest_distinct_snapshots_are_kept() {
+        let mut snapshots = Vec::new();
+        for count in 1..=MAX_SNAPSHOTS + 2 {
+            push(
+                &mut snapshots,
+                SessionSnapshot::new(&session(count), SnapshotReason::Manual, count as f64),
+            );
+        }
+        // The same messages again are skipped
+        push(
+            &mut snapshots,
+            SessionSnapshot::new(&session(MAX_SNAPSHOTS + 2), SnapshotReason::Manual, 99.0),
+        );
+        assert_eq!(snapshots.len(), MAX_SNAPSHOTS);
+        assert_eq!(snapshots[0].session.messages.len(), 3);
+
+        let restored = snapshots[0].restore(42.0);
+        assert_eq!(restored.id, "s");
+        assert_eq!(restored.messages.len(), 3);
+        assert_eq!(restored.updated_at, 42.0);
+    }
+}
@@ -0,0 +1,224 @@
+// Session store
+// All sessions live in one reducer. Each session sits behind its own Rc, so an
+// action copies the map of pointers and the one session it changes instead of
+// every message of every session. Sessions that didn't change keep their Rc,
+// which is also how two stores are compared cheaply.
+
+use crate::llm_playground::{ChatSession, Message};
+use std::collections::HashMap;
+use std::rc::Rc;
+use yew::prelude::*;
+
+#[derive(Clone, Default)]
+pub struct SessionStore {
+    sessions: HashMap<String, Rc<ChatSession>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SessionAction {
+    /// Replace every session, e.g. with those read from storage
+    Load(HashMap<String, ChatSession>),
+    /// Add a session, or replace the one with the same id
+    Put(ChatSession),
+    Remove(Vec<String>),
+    AppendMessage { session_id: String, message: Message },
+    /// Replace the loaded message with the same id
+    UpdateMessage { session_id: String, message: Message },
+    SetTitle { session_id: String, title: String },
+}
+
+impl PartialEq for SessionStore {
+    fn eq(&self, other: &Self) -> bool {
+        self.sessions.len() == other.sessions.len()
+            && self.sessions.iter().all(|(id, session)| {
+                other
+                    .sessions
+                    .get(id)
+                    .is_some_and(|other| Rc::ptr_eq(session, other))
+            })
+    }
+}
+
+impl SessionStore {
+    pub fn get(&self, id: &str) -> Option<&ChatSession> {
+        self.sessions.get(id).map(Rc::as_ref)
+    }
+
+    pub fn get_key_value(&self, id: &str) -> Option<(&String, &ChatSession)> {
+        self.sessions
+            .get_key_value(id)
+            .map(|(id, session)| (id, session.as_ref()))
+    }
+
+    pub fn contains_key(&self, id: &str) -> bool {
+        self.sessions.contains_key(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &ChatSession)> {
+        self.sessions.iter().map(|(id, session)| (id, session.as_ref()))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &ChatSession> {
+        self.sessions.values().map(Rc::as_ref)
+    }
+
+    /// The session to change in place; message and title changes leave
+    /// locked sessions as they are
+    fn unlocked(&mut self, id: &str) -> Option<&mut ChatSession> {
+        self.sessions
+            .get_mut(id)
+            .filter(|session| !session.locked)
+            .map(Rc::make_mut)
+    }
+
+    /// A copy of every session, for helpers that take a plain map
+    pub fn to_map(&self) -> HashMap<String, ChatSession> {
+        self.iter()
+            .map(|(id, session)| (id.clone(), session.clone()))
+            .collect()
+    }
+}
+
+impl Reducible for SessionStore {
+    type Action = SessionAction;
+
+    fn reduce(self: Rc<Self>, action: SessionAction) -> Rc<Self> {
+        let mut next = (*self).clone();
+        match action {
+            SessionAction::Load(sessions) => {
+                next.sessions = sessions
+                    .into_iter()
+                    .map(|(id, session)| (id, Rc::new(session)))
+                    .collect();
+            }
+            SessionAction::Put(session) => {
+                if self.get(&session.id) == Some(&session) {
+                    return self;
+                }
+                next.sessions.insert(session.id.clone(), Rc::new(session));
+            }
+            SessionAction::Remove(ids) => {
+                if !ids.iter().any(|id| self.contains_key(id)) {
+                    return self;
+                }
+                for id in &ids {
+                    next.sessions.remove(id);
+                }
+            }
+            SessionAction::AppendMessage { session_id, message } => {
+                let Some(session) = next.unlocked(&session_id) else {
+                    return self;
+                };
+                session.updated_at = session.updated_at.max(message.timestamp);
+                session.messages.push(message);
+            }
+            SessionAction::UpdateMessage { session_id, message } => {
+                let Some(session) = next.unlocked(&session_id) else {
+                    return self;
+                };
+                match session.messages.iter_mut().find(|m| m.id == message.id) {
+                    Some(current) if *current != message => *current = message,
+                    _ => return self,
+                }
+            }
+            SessionAction::SetTitle { session_id, title } => {
+                let Some(session) = next.unlocked(&session_id) else {
+                    return self;
+                };
+                if session.title == title {
+                    return self;
+                }
+                session.title = title;
+            }
+        }
+        Rc::new(next)
+    }
+}
+
+pub type SessionStoreHandle = UseReducerHandle<SessionStore>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::MessageRole;
+
+    fn message(id: &str, content: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            timestamp: 5.0,
+            ..Message::new(MessageRole::Assistant, content)
+        }
+    }
+
+    fn session(id: &str, locked: bool) -> ChatSession {
+        ChatSession {
+            id: id.to_string(),
+            title: id.to_string(),
+            messages: Vec::new(),
+            created_at: 0.0,
+            updated_at: 0.0,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    fn store() -> Rc<SessionStore> {
+        Rc::new(SessionStore::default()).reduce(SessionAction::Load(
+            [("a", false), ("b", false), ("locked", true)]
+                .into_iter()
+                .map(|(id, locked)| (id.to_string(), session(id, locked)))
+                .collect(),
+        ))
+    }
+
+    #[test]
+    fn only_the_changed_session_is_copied() {
+        let state = store();
+        let next = state.clone().reduce(SessionAction::AppendMessage {
+            session_id: "a".to_string(),
+            message: message("m1", "Hi"),
+        });
+        assert_eq!(next.get("a").unwrap().messages.len(), 1);
+        assert_eq!(next.get("a").unwrap().updated_at, 5.0);
+        assert!(Rc::ptr_eq(&state.sessions["b"], &next.sessions["b"]));
+        assert!(*state != *next);
+
+        let next = next.reduce(SessionAction::UpdateMessage {
+            session_id: "a".to_string(),
+            message: message("m1", "Hi there"),
+        });
+        assert_eq!(next.get("a").unwrap().messages[0].content, "Hi there");
+    }
+
+    #[test]
+    fn actions_that_change_nothing_keep_the_state() {
+        let state = store();
+        for action in [
+            SessionAction::SetTitle { session_id: "a".to_string(), title: "a".to_string() },
+            SessionAction::SetTitle { session_id: "locked".to_string(), title: "New".to_string() },
+            SessionAction::AppendMessage { session_id: "missing".to_string(), message: message("m1", "Hi") },
+            SessionAction::UpdateMessage { session_id: "a".to_string(), message: message("m1", "Hi") },
+            SessionAction::Put(session("b", false)),
+            SessionAction::Remove(vec!["missing".to_string()]),
+        ] {
+            assert!(Rc::ptr_eq(&state, &state.clone().reduce(action)));
+        }
+    }
+}
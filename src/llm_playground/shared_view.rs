@@ -0,0 +1,148 @@
+// Read-only viewer links
+// A conversation can be shared as a link to `#/view/<payload>`, where the
+// payload is the conversation as JSON, deflated and base64url-encoded. The
+// viewer renders it without any editing UI, provider or API key, so people
+// who never set the app up can read exported chats. Images, audio and
+// attachment data are left out to keep links short, and API keys are stripped
+// the same way as in HTML exports.
+
+use crate::llm_playground::{html_export, ChatSession, Message};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+/// Hash route of the viewer
+pub const VIEW_ROUTE: &str = "#/view";
+
+/// Format given to the browser's CompressionStream and DecompressionStream
+const COMPRESSION_FORMAT: &str = "deflate-raw";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SharedConversation {
+    pub title: String,
+    pub created_at: f64,
+    pub messages: Vec<Message>,
+}
+
+impl SharedConversation {
+    /// What of `session` goes into a link; `secrets` are stripped from the text
+    pub fn from_session(session: &ChatSession, secrets: &[String]) -> Self {
+        let messages = session
+            .messages
+            .iter()
+            .map(|message| Message {
+                content: html_export::strip_secrets(&message.content, secrets),
+                reasoning: message
+                    .reasoning
+                    .as_ref()
+                    .map(|reasoning| html_export::strip_secrets(reasoning, secrets)),
+                candidates: Vec::new(),
+                audio: None,
+                attachments: Vec::new(),
+                images: Vec::new(),
+                image_edit: None,
+                ..message.clone()
+            })
+            .collect();
+        Self {
+            title: html_export::strip_secrets(&session.title, secrets),
+            created_at: session.created_at,
+            messages,
+        }
+    }
+
+    /// A session to hand to the chat display; it is never stored
+    pub fn to_session(&self) -> ChatSession {
+        ChatSession {
+            id: "shared".to_string(),
+            title: self.title.clone(),
+            messages: self.messages.clone(),
+            created_at: self.created_at,
+            updated_at: self.created_at,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: true,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: true,
+            older_pages: 0,
+        }
+    }
+}
+
+/// The payload in a viewer link, a bare hash or a pasted payload on its own
+pub fn payload_from(text: &str) -> Option<&str> {
+    let text = text.trim();
+    let payload = match text.find(VIEW_ROUTE) {
+        Some(at) => text[at + VIEW_ROUTE.len()..].trim_start_matches('/'),
+        None => text,
+    };
+    Some(payload).filter(|payload| !payload.is_empty())
+}
+
+/// Run `data` through the browser's CompressionStream or DecompressionStream
+async fn through_stream(data: &[u8], stream: &str) -> Result<Vec<u8>, String> {
+    let error = |e: wasm_bindgen::JsValue| format!("{:?}", e);
+    let constructor = js_sys::Reflect::get(&js_sys::global(), &stream.into()).map_err(error)?;
+    if constructor.is_undefined() {
+        return Err(format!("This browser has no {}", stream));
+    }
+    let transform = js_sys::Reflect::construct(
+        constructor.unchecked_ref::<js_sys::Function>(),
+        &js_sys::Array::of1(&COMPRESSION_FORMAT.into()),
+    )
+    .map_err(error)?;
+    let mut bytes = data.to_vec();
+    let source = web_sys::Response::new_with_opt_u8_array(Some(&mut bytes)).map_err(error)?;
+    let body = source.body().ok_or("Empty payload")?;
+    let output = body.pipe_through(transform.unchecked_ref());
+    let response = web_sys::Response::new_with_opt_readable_stream(Some(&output)).map_err(error)?;
+    let buffer = JsFuture::from(response.array_buffer().map_err(error)?)
+        .await
+        .map_err(error)?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+/// The payload of a viewer link for `conversation`
+pub async fn encode(conversation: &SharedConversation) -> Result<String, String> {
+    let json = serde_json::to_vec(conversation).map_err(|e| e.to_string())?;
+    let compressed = through_stream(&json, "CompressionStream").await?;
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(compressed))
+}
+
+/// The conversation in a viewer link's payload
+pub async fn decode(payload: &str) -> Result<SharedConversation, String> {
+    let compressed = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .map_err(|_| "The link is incomplete or not a shared conversation".to_string())?;
+    let json = through_stream(&compressed, "DecompressionStream")
+        .await
+        .map_err(|_| "The link is incomplete or not a shared conversation".to_string())?;
+    serde_json::from_slice(&json).map_err(|e| format!("Unreadable conversation: {}", e))
+}
+
+/// Full link that opens `payload` in the viewer of this app
+pub fn view_url(payload: &str) -> Result<String, String> {
+    let location = web_sys::window().ok_or("No window available")?.location();
+    let origin = location.origin().map_err(|e| format!("{:?}", e))?;
+    let path = location.pathname().map_err(|e| format!("{:?}", e))?;
+    Ok(format!("{}{}{}/{}", origin, path, VIEW_ROUTE, payload))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_is_found_in_links_and_pastes() {
+        assert_eq!(payload_from("https://example.com/app/#/view/abc_-1"), Some("abc_-1"));
+        assert_eq!(payload_from("#/view/abc"), Some("abc"));
+        assert_eq!(payload_from("  abc \n"), Some("abc"));
+        assert_eq!(payload_from("#/view"), None);
+    }
+}
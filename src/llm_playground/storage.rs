@@ -1,9 +1,27 @@
 // Local storage utilities for LLM Playground
-use super::{ApiConfig, ChatSession};
+//
+// The stored config, session list and session pages each carry a schema
+// version, stored with the payload in one envelope so a payload and its version
+// are always written together. On load, payloads written by an older version
+// are run through the MIGRATIONS after it before anything parses them, and
+// each payload a migration changes is first copied to a backup key. Payloads
+// that still can't be read are quarantined; see `storage_recovery`.
+use super::{logging, session_pages, storage_recovery, ApiConfig, ChatSession, FlexibleApiConfig};
 use gloo_storage::{LocalStorage, Storage};
-use std::collections::HashMap;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
 
 const SESSIONS_KEY: &str = "llm_playground_sessions";
+/// Key of the FlexibleApiConfig
+pub const FLEXIBLE_CONFIG_KEY: &str = "llm_playground_flexible_config";
+/// Version of the stored config and sessions; bump it together with a new
+/// migration when a stored type changes in a way serde defaults don't cover
+pub const SCHEMA_VERSION: u32 = 1;
+/// Where one version for every payload was kept before payloads carried
+/// their own; payloads stored bare are at this version, or 0 without it
+const LEGACY_VERSION_KEY: &str = "llm_playground_schema_version";
+const BACKUP_PREFIX: &str = "llm_playground_backup_";
 const CONFIG_KEY: &str = "llm_playground_config";
 const CURRENT_SESSION_KEY: &str = "llm_playground_current_session";
 
@@ -75,3 +93,220 @@ impl StorageManager {
         Ok(())
     }
 }
+
+/// Stored payloads by key
+pub type Payloads = HashMap<String, Value>;
+
+/// Upgrades payloads from one schema version to the next
+type Migration = fn(&mut Payloads);
+
+/// `MIGRATIONS[n]` upgrades version n to n + 1; version 0 is everything
+/// stored before versions were kept
+const MIGRATIONS: [Migration; SCHEMA_VERSION as usize] = [fill_required_fields];
+
+/// Keys of the payloads migrations see: the config, the session list and
+/// every session page
+fn versioned_keys() -> Vec<String> {
+    let storage = LocalStorage::raw();
+    let count = storage.length().unwrap_or(0);
+    let pages = (0..count)
+        .filter_map(|index| storage.key(index).ok().flatten())
+        .filter(|key| key.starts_with(session_pages::PAGE_KEY_PREFIX));
+    [FLEXIBLE_CONFIG_KEY.to_string(), session_pages::SESSIONS_KEY.to_string()]
+        .into_iter()
+        .chain(pages)
+        .collect()
+}
+
+/// A payload as stored, with the version it was written by; the version
+/// comes first so current payloads are recognised without parsing them
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    schema_version: u32,
+    payload: &'a T,
+}
+
+/// The JSON text to store for `payload`: `{"schema_version": N, "payload": ...}`
+/// at SCHEMA_VERSION
+pub fn payload_json<T: Serialize>(payload: &T) -> serde_json::Result<String> {
+    serde_json::to_string(&Envelope { schema_version: SCHEMA_VERSION, payload })
+}
+
+/// The version and payload of stored JSON; no version for a bare payload,
+/// stored before payloads were enveloped
+pub fn open_envelope(value: Value) -> (Option<u32>, Value) {
+    match value {
+        Value::Object(mut fields) if fields.len() == 2 && fields.contains_key("payload") => {
+            match fields.get("schema_version").and_then(Value::as_u64) {
+                Some(version) => (Some(version as u32), fields.remove("payload").unwrap_or_default()),
+                None => (None, Value::Object(fields)),
+            }
+        }
+        value => (None, value),
+    }
+}
+
+/// Fields added before versions were kept without a serde default: the
+/// config's top-level settings come from the defaults, and sessions get
+/// `pinned` and `updated_at`
+fn fill_required_fields(payloads: &mut Payloads) {
+    if let (Some(Value::Object(config)), Ok(Value::Object(defaults))) = (
+        payloads.get_mut(FLEXIBLE_CONFIG_KEY),
+        serde_json::to_value(FlexibleApiConfig::default()),
+    ) {
+        for (field, value) in defaults {
+            config.entry(field).or_insert(value);
+        }
+    }
+    if let Some(Value::Object(sessions)) = payloads.get_mut(session_pages::SESSIONS_KEY) {
+        for session in sessions.values_mut().filter_map(Value::as_object_mut) {
+            session.entry("pinned").or_insert(Value::Bool(false));
+            let created_at = session.get("created_at").cloned().unwrap_or(Value::from(0.0));
+            session.entry("updated_at").or_insert(created_at);
+        }
+    }
+}
+
+/// Run the migrations after `version` over `payloads`
+pub fn migrate(payloads: &mut Payloads, version: u32) {
+    for migration in MIGRATIONS.iter().skip(version as usize) {
+        migration(payloads);
+    }
+}
+
+/// Bring the stored payloads up to SCHEMA_VERSION; call before loading any
+/// of them. Payloads a migration changes are backed up under
+/// `llm_playground_backup_v{version}_{key}` first, and each is rewritten with
+/// its new version in one write, so one already migrated is never migrated
+/// again. Data from a newer version is left alone.
+pub fn migrate_storage() {
+    let bare_version = LocalStorage::get::<u32>(LEGACY_VERSION_KEY).unwrap_or(0);
+    let mut raw = HashMap::new();
+    let mut stale: BTreeMap<u32, Payloads> = BTreeMap::new();
+    let current = format!("{{\"schema_version\":{},", SCHEMA_VERSION);
+    for key in versioned_keys() {
+        let Some(text) = LocalStorage::raw().get_item(&key).ok().flatten().map(|item| payload_text(&item)) else {
+            continue;
+        };
+        if text.starts_with(&current) {
+            continue;
+        }
+        // Unreadable payloads are left for load_payload to quarantine
+        let Ok(value) = serde_json::from_str(&text) else {
+            continue;
+        };
+        // Bare payloads are enveloped even when no migration changes them
+        let (version, payload) = match open_envelope(value) {
+            (Some(version), payload) if version < SCHEMA_VERSION => (version, payload),
+            (None, payload) if bare_version <= SCHEMA_VERSION => (bare_version, payload),
+            _ => continue,
+        };
+        stale.entry(version).or_default().insert(key.clone(), payload);
+        raw.insert(key, text);
+    }
+
+    let mut complete = true;
+    for (version, mut payloads) in stale {
+        let original = payloads.clone();
+        migrate(&mut payloads, version);
+        for (key, payload) in &payloads {
+            if original.get(key) != Some(payload) {
+                let backup_key = format!("{}v{}_{}", BACKUP_PREFIX, version, key);
+                if LocalStorage::set(&backup_key, &raw[key]).is_err() {
+                    // Without a backup the old payload stays as it is
+                    logging::warn!("Couldn't back up {} before migrating it; left unchanged", key);
+                    complete = false;
+                    continue;
+                }
+            }
+            let written = payload_json(payload).is_ok_and(|json| LocalStorage::set(key, json).is_ok());
+            complete &= written;
+        }
+        if version < SCHEMA_VERSION {
+            logging::info!("Stored data migrated from schema version {} to {}", version, SCHEMA_VERSION);
+        }
+    }
+    // Bare payloads left behind still need the old version
+    if complete {
+        LocalStorage::delete(LEGACY_VERSION_KEY);
+    }
+}
+
+/// The payload stored as JSON text under `key`. One that can't be read is
+/// moved to quarantine before None is returned, so writing defaults over it
+/// loses nothing. The raw item is read, so values that aren't even a JSON
+/// string are quarantined too rather than left in place.
+pub fn load_payload<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let raw = LocalStorage::raw().get_item(key).ok().flatten()?;
+    let text = payload_text(&raw);
+    let payload = serde_json::from_str(&text).and_then(|value| serde_json::from_value(open_envelope(value).1));
+    match payload {
+        Ok(payload) => Some(payload),
+        Err(error) => {
+            let quarantine_key = storage_recovery::quarantine(key, &text);
+            logging::warn!("Stored {} is unreadable ({}); moved it to {}", key, error, quarantine_key);
+            None
+        }
+    }
+}
+
+/// The payload's JSON text from a raw stored item. Payloads are saved as a
+/// JSON string holding that text; an item that isn't one is taken as it is.
+fn payload_text(raw: &str) -> String {
+    serde_json::from_str::<String>(raw).unwrap_or_else(|_| raw.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn unversioned_payloads_parse_after_migrating() {
+        let mut config = serde_json::to_value(FlexibleApiConfig::default()).unwrap();
+        let config_fields = config.as_object_mut().unwrap();
+        config_fields.remove("router");
+        config_fields.remove("mcp_config");
+        config_fields.insert("system_prompt".to_string(), json!("Be brief"));
+
+        let mut payloads = Payloads::from([
+            (FLEXIBLE_CONFIG_KEY.to_string(), config),
+            (
+                session_pages::SESSIONS_KEY.to_string(),
+                json!({ "s1": { "id": "s1", "title": "Old", "messages": [], "created_at": 3.0 } }),
+            ),
+        ]);
+        assert!(serde_json::from_value::<FlexibleApiConfig>(payloads[FLEXIBLE_CONFIG_KEY].clone()).is_err());
+
+        migrate(&mut payloads, 0);
+        let config: FlexibleApiConfig = serde_json::from_value(payloads[FLEXIBLE_CONFIG_KEY].clone()).unwrap();
+        assert_eq!(config.system_prompt, "Be brief");
+        let sessions: HashMap<String, ChatSession> =
+            serde_json::from_value(payloads[session_pages::SESSIONS_KEY].clone()).unwrap();
+        assert_eq!(sessions["s1"].updated_at, 3.0);
+        assert!(!sessions["s1"].pinned);
+    }
+
+    #[test]
+    fn raw_items_give_the_payload_text() {
+        assert_eq!(payload_text(r#""{\"a\": 1}""#), r#"{"a": 1}"#);
+        assert_eq!(payload_text("{\"a\": 1}"), r#"{"a": 1}"#);
+        assert_eq!(payload_text("not json"), "not json");
+    }
+
+    #[test]
+    fn envelopes_carry_the_version() {
+        let json = payload_json(&json!({ "a": 1 })).unwrap();
+        let value = serde_json::from_str(&json).unwrap();
+        assert_eq!(open_envelope(value), (Some(SCHEMA_VERSION), json!({ "a": 1 })));
+        assert_eq!(open_envelope(json!({ "a": 1 })), (None, json!({ "a": 1 })));
+        assert_eq!(open_envelope(json!([])), (None, json!([])));
+    }
+
+    #[test]
+    fn current_payloads_are_left_alone() {
+        let mut payloads = Payloads::from([(FLEXIBLE_CONFIG_KEY.to_string(), json!({ "custom": 1 }))]);
+        migrate(&mut payloads, SCHEMA_VERSION);
+        assert_eq!(payloads[FLEXIBLE_CONFIG_KEY], json!({ "custom": 1 }));
+    }
+}
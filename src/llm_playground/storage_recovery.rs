@@ -0,0 +1,320 @@
+// Recovering unreadable storage
+// A stored payload that doesn't parse is moved to a quarantine key instead of
+// being written over with defaults. The app then offers it in a recovery
+// dialog, where the raw text can be downloaded or imported with whatever
+// still reads: whole sessions, the messages of a broken session, and single
+// settings of a broken config.
+
+use crate::llm_playground::{session_pages, storage, ChatSession, FlexibleApiConfig, Message};
+use gloo_storage::{LocalStorage, Storage};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+pub const QUARANTINE_PREFIX: &str = "llm_playground_quarantine_";
+
+/// An unreadable payload and the key it was stored under
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuarantinedPayload {
+    /// Key it is kept under now
+    pub quarantine_key: String,
+    /// Key it was stored under
+    pub key: String,
+    pub text: String,
+}
+
+impl QuarantinedPayload {
+    pub fn label(&self) -> &'static str {
+        if self.key == storage::FLEXIBLE_CONFIG_KEY {
+            "Settings"
+        } else if self.key == session_pages::SESSIONS_KEY {
+            "Sessions"
+        } else if self.key.starts_with(session_pages::PAGE_KEY_PREFIX) {
+            "Session messages"
+        } else {
+            "Stored data"
+        }
+    }
+
+    pub fn file_name(&self) -> String {
+        format!("{}.json", self.quarantine_key)
+    }
+
+    /// Whether `salvage` knows how to read this payload
+    pub fn can_import(&self) -> bool {
+        self.key == storage::FLEXIBLE_CONFIG_KEY || self.key == session_pages::SESSIONS_KEY
+    }
+}
+
+/// What could be read from a quarantined payload
+#[derive(Clone, Debug, PartialEq)]
+pub enum Salvaged {
+    Config(Box<FlexibleApiConfig>),
+    Sessions(HashMap<String, ChatSession>),
+}
+
+/// Parts read and parts left out of a salvage
+#[derive(Clone, Debug, PartialEq)]
+pub struct Salvage {
+    pub salvaged: Salvaged,
+    pub recovered: usize,
+    pub skipped: usize,
+}
+
+impl Salvage {
+    pub fn summary(&self) -> String {
+        let what = match self.salvaged {
+            Salvaged::Config(_) => "settings",
+            Salvaged::Sessions(_) => "sessions",
+        };
+        if self.skipped == 0 {
+            format!("Imported {} {}", self.recovered, what)
+        } else {
+            format!("Imported {} {}; {} unreadable parts were left out", self.recovered, what, self.skipped)
+        }
+    }
+}
+
+/// Move `text`, found unreadable under `key`, to a quarantine key. A payload
+/// already quarantined for the same key is kept, and the new one gets a
+/// numbered key next to it.
+pub fn quarantine(key: &str, text: &str) -> String {
+    let base = format!("{}{}", QUARANTINE_PREFIX, key);
+    let mut quarantine_key = base.clone();
+    let mut number = 2;
+    while LocalStorage::raw().get_item(&quarantine_key).ok().flatten().is_some() {
+        quarantine_key = format!("{}#{}", base, number);
+        number += 1;
+    }
+    if LocalStorage::set(&quarantine_key, text).is_ok() {
+        LocalStorage::delete(key);
+    }
+    quarantine_key
+}
+
+/// The stored key a quarantine key was made for
+fn original_key(quarantine_key: &str) -> Option<&str> {
+    let key = quarantine_key.strip_prefix(QUARANTINE_PREFIX)?;
+    let numbered = |separator| match key.rsplit_once(separator) {
+        Some((key, number)) if number.parse::<u32>().is_ok() => Some(key),
+        _ => None,
+    };
+    // Numbered keys end in "#{n}", or "_{n}" when quarantined before page keys
+    // were, which end in a number of their own
+    Some(match numbered('#') {
+        Some(key) => key,
+        None if key.starts_with(session_pages::PAGE_KEY_PREFIX) => key,
+        None => numbered('_').unwrap_or(key),
+    })
+}
+
+/// Every payload waiting in quarantine
+pub fn quarantined() -> Vec<QuarantinedPayload> {
+    let storage = LocalStorage::raw();
+    let count = storage.length().unwrap_or(0);
+    let mut payloads: Vec<QuarantinedPayload> = (0..count)
+        .filter_map(|index| storage.key(index).ok().flatten())
+        .filter_map(|quarantine_key| {
+            let key = original_key(&quarantine_key)?.to_string();
+            let text = LocalStorage::get::<String>(&quarantine_key).ok()?;
+            Some(QuarantinedPayload { quarantine_key, key, text })
+        })
+        .collect();
+    payloads.sort_by(|a, b| a.quarantine_key.cmp(&b.quarantine_key));
+    payloads
+}
+
+pub fn discard(quarantine_key: &str) {
+    LocalStorage::delete(quarantine_key);
+}
+
+/// What can still be read from `payload`; None when nothing can
+pub fn salvage(payload: &QuarantinedPayload) -> Option<Salvage> {
+    let (_, value) = storage::open_envelope(serde_json::from_str(&payload.text).ok()?);
+    if payload.key == storage::FLEXIBLE_CONFIG_KEY {
+        salvage_config(value)
+    } else if payload.key == session_pages::SESSIONS_KEY {
+        salvage_sessions(value)
+    } else {
+        None
+    }
+}
+
+/// The default config with each stored setting that still reads
+fn salvage_config(value: Value) -> Option<Salvage> {
+    let Value::Object(stored) = value else {
+        return None;
+    };
+    let Ok(Value::Object(mut fields)) = serde_json::to_value(FlexibleApiConfig::default()) else {
+        return None;
+    };
+    let (mut recovered, mut skipped) = (0, 0);
+    for (name, setting) in stored {
+        let previous = fields.insert(name.clone(), setting);
+        if serde_json::from_value::<FlexibleApiConfig>(Value::Object(fields.clone())).is_ok() {
+            recovered += 1;
+        } else {
+            skipped += 1;
+            match previous {
+                Some(previous) => fields.insert(name, previous),
+                None => fields.remove(&name),
+            };
+        }
+    }
+    let config = serde_json::from_value(Value::Object(fields)).ok()?;
+    Some(Salvage {
+        salvaged: Salvaged::Config(Box::new(config)),
+        recovered,
+        skipped,
+    })
+}
+
+/// Sessions that read, and broken sessions with the messages that read
+fn salvage_sessions(value: Value) -> Option<Salvage> {
+    let Value::Object(stored) = value else {
+        return None;
+    };
+    let mut sessions = HashMap::new();
+    let mut skipped = 0;
+    for (id, session) in stored {
+        match salvage_session(&id, session) {
+            Some((session, dropped)) => {
+                skipped += dropped;
+                sessions.insert(id, session);
+            }
+            None => skipped += 1,
+        }
+    }
+    if sessions.is_empty() {
+        return None;
+    }
+    Some(Salvage {
+        recovered: sessions.len(),
+        salvaged: Salvaged::Sessions(sessions),
+        skipped,
+    })
+}
+
+/// A session and how many of its messages were left out
+fn salvage_session(id: &str, value: Value) -> Option<(ChatSession, usize)> {
+    if let Ok(session) = serde_json::from_value::<ChatSession>(value.clone()) {
+        return Some((session, 0));
+    }
+    let Value::Object(mut fields) = value else {
+        return None;
+    };
+    let messages = match fields.remove("messages") {
+        Some(Value::Array(messages)) => messages,
+        _ => Vec::new(),
+    };
+    let total = messages.len();
+    let messages: Vec<Message> = messages
+        .into_iter()
+        .filter_map(|message| serde_json::from_value(message).ok())
+        .collect();
+    let dropped = total - messages.len();
+    fields.insert("messages".to_string(), Value::Array(Vec::new()));
+    fill_session_fields(id, &mut fields);
+    let mut session: ChatSession = serde_json::from_value(Value::Object(fields)).ok()?;
+    session.messages = messages;
+    Some((session, dropped))
+}
+
+/// Stand-ins for the required session fields a broken session may lack
+fn fill_session_fields(id: &str, fields: &mut Map<String, Value>) {
+    fields.entry("id").or_insert(Value::from(id));
+    fields.entry("title").or_insert(Value::from("Recovered session"));
+    fields.entry("created_at").or_insert(Value::from(0.0));
+    let created_at = fields["created_at"].clone();
+    fields.entry("updated_at").or_insert(created_at);
+    fields.entry("pinned").or_insert(Value::Bool(false));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn payload(key: &str, text: &str) -> QuarantinedPayload {
+        QuarantinedPayload {
+            quarantine_key: format!("{}{}", QUARANTINE_PREFIX, key),
+            key: key.to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn quarantine_keys_name_the_stored_key() {
+        assert_eq!(original_key("llm_playground_quarantine_llm_playground_sessions"), Some("llm_playground_sessions"));
+        assert_eq!(original_key("llm_playground_quarantine_llm_playground_sessions_3"), Some("llm_playground_sessions"));
+        assert_eq!(original_key("llm_playground_quarantine_llm_playground_sessions#2"), Some("llm_playground_sessions"));
+        assert_eq!(
+            original_key("llm_playground_quarantine_llm_playground_session_page_s1_4"),
+            Some("llm_playground_session_page_s1_4")
+        );
+        assert_eq!(
+            original_key("llm_playground_quarantine_llm_playground_session_page_s1_4#3"),
+            Some("llm_playground_session_page_s1_4")
+        );
+        assert_eq!(original_key("llm_playground_sessions"), None);
+    }
+
+    #[test]
+    fn readable_settings_are_kept() {
+        let text = json!({ "system_prompt": "Be brief", "providers": 5, "hide_reasoning": true }).to_string();
+        let result = salvage(&payload(storage::FLEXIBLE_CONFIG_KEY, &text)).unwrap();
+        let Salvaged::Config(config) = &result.salvaged else {
+            panic!("expected a config");
+        };
+        assert_eq!(config.system_prompt, "Be brief");
+        assert!(config.hide_reasoning);
+        assert_eq!(config.providers, FlexibleApiConfig::default().providers);
+        assert_eq!((result.recovered, result.skipped), (2, 1));
+    }
+
+    #[test]
+    fn broken_sessions_keep_their_readable_messages() {
+        let text = json!({
+            "ok": { "id": "ok", "title": "Fine", "messages": [], "created_at": 1.0, "updated_at": 1.0, "pinned": false },
+            "broken": {
+                "id": "broken",
+                "created_at": "yesterday",
+                "messages": [
+                    { "id": "m1", "role": "User", "content": "Hi", "timestamp": 1.0, "function_call": null, "function_response": null },
+                    { "id": "m2", "role": "robot" }
+                ]
+            },
+            "lost": 7
+        })
+        .to_string();
+        let result = salvage(&payload(session_pages::SESSIONS_KEY, &text)).unwrap();
+        let Salvaged::Sessions(sessions) = &result.salvaged else {
+            panic!("expected sessions");
+        };
+        assert_eq!(sessions.len(), 1);
+        assert!(sessions.contains_key("ok"));
+        // "broken" has an unreadable created_at, so only "ok" survives
+        assert_eq!(result.skipped, 2);
+        assert!(salvage(&payload(session_pages::SESSIONS_KEY, "{\"a\": ")).is_none());
+    }
+
+    #[test]
+    fn sessions_missing_fields_are_filled_in() {
+        let text = json!({
+            "s1": {
+                "id": "s1",
+                "messages": [
+                    { "id": "m1", "role": "User", "content": "Hi", "timestamp": 1.0, "function_call": null, "function_response": null },
+                    { "id": "m2", "role": "robot" }
+                ]
+            }
+        })
+        .to_string();
+        let result = salvage(&payload(session_pages::SESSIONS_KEY, &text)).unwrap();
+        let Salvaged::Sessions(sessions) = &result.salvaged else {
+            panic!("expected sessions");
+        };
+        assert_eq!(sessions["s1"].title, "Recovered session");
+        assert_eq!(sessions["s1"].messages.len(), 1);
+        assert_eq!(result.skipped, 1);
+    }
+}
@@ -0,0 +1,217 @@
+// Structured outputs
+// Named JSON schemas a reply must follow. The visual editor works on a list of
+// fields (object fields carry their own child fields) that converts to and from
+// a JSON schema, and replies are validated against the schema they were
+// requested with.
+
+use crate::llm_playground::eval_suite::{strip_code_fence, validate_json_schema};
+use serde_json::{json, Value};
+
+pub const FIELD_TYPES: [&str; 6] = ["string", "number", "integer", "boolean", "array", "object"];
+
+/// One property of an object schema, as edited in the visual builder
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaField {
+    pub name: String,
+    pub field_type: String,
+    pub description: String,
+    pub required: bool,
+    /// Allowed values of a string field
+    pub enum_values: Vec<String>,
+    /// Element type of an array field
+    pub items_type: String,
+    /// Properties of an object field, or of the elements of an array of objects
+    pub children: Vec<SchemaField>,
+}
+
+impl Default for SchemaField {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            field_type: "string".to_string(),
+            description: String::new(),
+            required: true,
+            enum_values: Vec::new(),
+            items_type: "string".to_string(),
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Build an object schema from the builder's fields. Fields without a name are
+/// skipped; objects don't allow properties beyond the listed ones.
+pub fn fields_to_schema(fields: &[SchemaField]) -> Value {
+    let mut properties = serde_json::Map::new();
+    let mut required = Vec::new();
+    for field in fields.iter().filter(|f| !f.name.trim().is_empty()) {
+        let name = field.name.trim().to_string();
+        properties.insert(name.clone(), field_schema(field));
+        if field.required {
+            required.push(name);
+        }
+    }
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+        "additionalProperties": false
+    })
+}
+
+fn field_schema(field: &SchemaField) -> Value {
+    let mut schema = match field.field_type.as_str() {
+        "object" => fields_to_schema(&field.children),
+        "array" => {
+            let items = if field.items_type == "object" {
+                fields_to_schema(&field.children)
+            } else {
+                json!({ "type": field.items_type })
+            };
+            json!({ "type": "array", "items": items })
+        }
+        other => json!({ "type": other }),
+    };
+    if !field.description.trim().is_empty() {
+        schema["description"] = json!(field.description.trim());
+    }
+    if field.field_type == "string" && !field.enum_values.is_empty() {
+        schema["enum"] = json!(field.enum_values);
+    }
+    schema
+}
+
+/// Read an object schema back into builder fields. Constructs the builder can't
+/// express (e.g. `oneOf`) are dropped.
+pub fn schema_to_fields(schema: &Value) -> Vec<SchemaField> {
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(|r| r.as_array())
+        .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) else {
+        return Vec::new();
+    };
+
+    properties
+        .iter()
+        .map(|(name, property)| {
+            let field_type = property
+                .get("type")
+                .and_then(|t| t.as_str())
+                .filter(|t| FIELD_TYPES.contains(t))
+                .unwrap_or("string")
+                .to_string();
+            let items = property.get("items");
+            let items_type = items
+                .and_then(|i| i.get("type"))
+                .and_then(|t| t.as_str())
+                .filter(|t| FIELD_TYPES.contains(t) && *t != "array")
+                .unwrap_or("string")
+                .to_string();
+            let children = match field_type.as_str() {
+                "object" => schema_to_fields(property),
+                "array" if items_type == "object" => {
+                    items.map(schema_to_fields).unwrap_or_default()
+                }
+                _ => Vec::new(),
+            };
+            SchemaField {
+                name: name.clone(),
+                description: property
+                    .get("description")
+                    .and_then(|d| d.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                required: required.contains(&name.as_str()),
+                enum_values: property
+                    .get("enum")
+                    .and_then(|e| e.as_array())
+                    .map(|e| {
+                        e.iter()
+                            .filter_map(|v| v.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                field_type,
+                items_type,
+                children,
+            }
+        })
+        .collect()
+}
+
+/// Parse a reply as JSON (a surrounding code fence is allowed) and check it
+/// against the schema
+pub fn validate_reply(reply: &str, schema: &Value) -> Result<Value, String> {
+    let value = serde_json::from_str::<Value>(strip_code_fence(reply))
+        .map_err(|e| format!("Reply is not valid JSON: {}", e))?;
+    validate_json_schema(&value, schema, "$")?;
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_fields_round_trip_through_schema() {
+        let fields = vec![
+            SchemaField {
+                name: "sentiment".to_string(),
+                enum_values: vec!["positive".to_string(), "negative".to_string()],
+                ..Default::default()
+            },
+            SchemaField {
+                name: "tags".to_string(),
+                field_type: "array".to_string(),
+                required: false,
+                ..Default::default()
+            },
+            SchemaField {
+                name: "author".to_string(),
+                field_type: "object".to_string(),
+                description: "Who wrote it".to_string(),
+                children: vec![SchemaField {
+                    name: "age".to_string(),
+                    field_type: "integer".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            SchemaField::default(),
+        ];
+        let schema = fields_to_schema(&fields);
+        assert_eq!(schema["required"], json!(["sentiment", "author"]));
+        assert_eq!(schema["properties"]["tags"]["items"]["type"], "string");
+        assert_eq!(
+            schema["properties"]["author"]["properties"]["age"]["type"],
+            "integer"
+        );
+
+        let mut expected = fields[..3].to_vec();
+        expected.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut parsed = schema_to_fields(&schema);
+        parsed.sort_by(|a, b| a.name.cmp(&b.name));
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn validates_replies_against_schema() {
+        let schema = json!({
+            "type": "object",
+            "properties": { "score": { "type": "integer" } },
+            "required": ["score"],
+            "additionalProperties": false
+        });
+        assert_eq!(
+            validate_reply("```json\n{\"score\": 3}\n```", &schema).unwrap(),
+            json!({ "score": 3 })
+        );
+        assert!(validate_reply("{\"score\": \"high\"}", &schema)
+            .unwrap_err()
+            .contains("$.score"));
+        assert!(validate_reply("Sure! Here it is", &schema)
+            .unwrap_err()
+            .starts_with("Reply is not valid JSON"));
+    }
+}
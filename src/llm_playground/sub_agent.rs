@@ -0,0 +1,328 @@
+// Sub-agent execution for the Task tool
+// Runs a hidden child conversation with its own tool loop and reports every step
+// back into the parent session so the UI can show it as a nested panel.
+
+use crate::llm_playground::{
+    builtin_tools, event_bus::{EventBus, PlaygroundEvent}, flexible_client::FlexibleLLMClient, logging,
+    mcp_client::McpClient, provider_config::FlexibleApiConfig, session_store::SessionAction, tool_dependencies,
+    ChatSession, Message, MessageRole,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const TASK_TOOL_NAME: &str = "Task";
+
+/// Upper bound on model calls per task so a looping sub-agent can't run forever
+const MAX_SUB_AGENT_TURNS: usize = 10;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubAgentStatus {
+    #[default]
+    Running,
+    Completed,
+    Failed,
+}
+
+/// One entry in the sub-agent's transcript
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SubAgentStep {
+    Message { content: String },
+    ToolCall { name: String, arguments: Value },
+    ToolResult { name: String, result: Value },
+}
+
+/// Progress of a Task tool run, stored under `sub_agent` in the function response
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubAgentRun {
+    pub description: String,
+    pub agent_type: String,
+    /// "provider,model" the sub-agent ran on
+    pub model: String,
+    pub status: SubAgentStatus,
+    #[serde(default)]
+    pub steps: Vec<SubAgentStep>,
+}
+
+impl SubAgentRun {
+    /// Read the run recorded on a function response message, if any
+    pub fn from_function_response(function_response: &Value) -> Option<Self> {
+        serde_json::from_value(function_response.get("sub_agent")?.clone()).ok()
+    }
+}
+
+/// Run the Task tool for the call `call_id` and record it in `session`.
+///
+/// A function response message is added right away and updated (and published
+/// on `bus` as a session update) after every sub-agent step; once finished it
+/// holds the final answer as the tool result. Desktop tool approvals go through
+/// the same bus. Returns the updated session.
+pub async fn run_task_in_session(
+    mut session: ChatSession,
+    call_id: &str,
+    arguments: &Value,
+    llm_client: &FlexibleLLMClient,
+    config: &FlexibleApiConfig,
+    mcp_client: Option<&McpClient>,
+    bus: &EventBus,
+) -> ChatSession {
+    let argument = |key: &str| {
+        arguments
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let prompt = argument("prompt");
+    let mut run = SubAgentRun {
+        description: argument("description"),
+        agent_type: argument("subagent_type"),
+        ..Default::default()
+    };
+
+    let mut child_config = sub_agent_config(config, &run.agent_type);
+    let (provider_name, model_name) = child_config.get_current_provider_and_model();
+    run.model = format!("{},{}", provider_name, model_name);
+
+    let message_index = session.messages.len();
+    session.messages.push(Message {
+        id: format!("msg_fr_{}", js_sys::Date::now() as u64),
+        timestamp: js_sys::Date::now(),
+        function_response: Some(serde_json::json!({
+            "id": call_id,
+            "name": TASK_TOOL_NAME,
+            "sub_agent": run,
+        })),
+        ..Message::new(MessageRole::Function, format!("Function {} executed", TASK_TOOL_NAME))
+    });
+    session.updated_at = js_sys::Date::now();
+    bus.publish(PlaygroundEvent::SessionChanged(Box::new(SessionAction::AppendMessage {
+        session_id: session.id.clone(),
+        message: session.messages[message_index].clone(),
+    })));
+
+    // Progress replaces the one message rather than sending the whole session
+    let report = |session: &mut ChatSession, run: &SubAgentRun, response: Option<Value>| {
+        if let Some(message) = session.messages.get_mut(message_index) {
+            let mut function_response = serde_json::json!({
+                "id": call_id,
+                "name": TASK_TOOL_NAME,
+                "sub_agent": run,
+            });
+            if let Some(response) = response {
+                function_response["response"] = response;
+            }
+            message.function_response = Some(function_response);
+            bus.publish(PlaygroundEvent::SessionChanged(Box::new(SessionAction::UpdateMessage {
+                session_id: session.id.clone(),
+                message: message.clone(),
+            })));
+        }
+        session.updated_at = js_sys::Date::now();
+    };
+
+    let mut messages = vec![Message {
+        id: format!("task_prompt_{}", js_sys::Date::now() as u64),
+        timestamp: js_sys::Date::now(),
+        ..Message::new(MessageRole::User, prompt)
+    }];
+
+    for turn in 0..MAX_SUB_AGENT_TURNS {
+        logging::info!("Sub-agent turn {} for task: {}", turn + 1, run.description);
+        // Later turns answer tool results, so they mustn't be forced to call a tool
+        child_config.shared_settings.tool_choice = child_config.shared_settings.tool_choice.for_request(turn == 0);
+
+        let response = match llm_client.send_message(&messages, &child_config).await {
+            Ok(response) => response,
+            Err(error) => {
+                run.status = SubAgentStatus::Failed;
+                run.steps.push(SubAgentStep::Message {
+                    content: format!("Error: {}", error),
+                });
+                report(
+                    &mut session,
+                    &run,
+                    Some(serde_json::json!({ "error": error })),
+                );
+                return session;
+            }
+        };
+
+        let content = response.content.clone().unwrap_or_default();
+        if !content.trim().is_empty() {
+            run.steps.push(SubAgentStep::Message {
+                content: content.clone(),
+            });
+        }
+
+        if response.function_calls.is_empty() {
+            run.status = SubAgentStatus::Completed;
+            report(
+                &mut session,
+                &run,
+                Some(serde_json::json!({ "result": content, "turns": turn + 1 })),
+            );
+            return session;
+        }
+
+        let function_calls: Vec<Value> = response
+            .function_calls
+            .iter()
+            .map(
+                |fc| serde_json::json!({ "id": fc.id, "name": fc.name, "arguments": fc.arguments }),
+            )
+            .collect();
+        messages.push(Message {
+            id: format!("task_fc_{}_{}", turn, js_sys::Date::now() as u64),
+            timestamp: js_sys::Date::now(),
+            function_call: Some(Value::Array(function_calls)),
+            ..Message::new(MessageRole::Assistant, content)
+        });
+
+        for call in &response.function_calls {
+            run.steps.push(SubAgentStep::ToolCall {
+                name: call.name.clone(),
+                arguments: call.arguments.clone(),
+            });
+            report(&mut session, &run, None);
+
+            let result = match tool_dependencies::check(&child_config, &call.name, &call.arguments, &messages) {
+                Err(error) => serde_json::json!({ "error": error }),
+                Ok(()) => {
+                    // run_sql calls query the parent session's database
+                    builtin_tools::execute_function_tool(
+                        &child_config,
+                        &session,
+                        &call.name,
+                        &call.arguments,
+                        mcp_client,
+                        Some(bus),
+                    )
+                    .await
+                }
+            };
+
+            run.steps.push(SubAgentStep::ToolResult {
+                name: call.name.clone(),
+                result: result.clone(),
+            });
+            report(&mut session, &run, None);
+
+            messages.push(Message {
+                id: format!("task_fr_{}_{}", turn, js_sys::Date::now() as u64),
+                timestamp: js_sys::Date::now(),
+                function_response: Some(serde_json::json!({
+                    "id": call.id,
+                    "name": call.name,
+                    "response": result,
+                })),
+                ..Message::new(MessageRole::Function, format!("Function {} executed", call.name))
+            });
+        }
+    }
+
+    let error = format!(
+        "Sub-agent stopped after {} turns without a final answer",
+        MAX_SUB_AGENT_TURNS
+    );
+    run.status = SubAgentStatus::Failed;
+    run.steps.push(SubAgentStep::Message {
+        content: error.clone(),
+    });
+    report(
+        &mut session,
+        &run,
+        Some(serde_json::json!({ "error": error })),
+    );
+    session
+}
+
+/// Config for the child conversation: the configured sub-agent model (or the
+/// chat's model), its own system prompt, and no nested Task tool
+fn sub_agent_config(config: &FlexibleApiConfig, agent_type: &str) -> FlexibleApiConfig {
+    let mut child_config = config.clone();
+
+    if let Some((provider, model)) = config
+        .sub_agent_model
+        .as_deref()
+        .and_then(|value| value.split_once(','))
+        .filter(|(provider, model)| {
+            config
+                .get_provider(provider)
+                .is_some_and(|p| p.models.iter().any(|m| m == model))
+        })
+    {
+        child_config.set_session_provider(provider, model);
+        child_config.apply_model_defaults(provider, model);
+    }
+
+    child_config.system_prompt = format!(
+        "You are a {} sub-agent working on a task delegated by another assistant. \
+         Work autonomously using the available tools, then reply with a concise, \
+         self-contained final answer. Your final reply is returned to the delegating \
+         assistant as the task result.",
+        if agent_type.is_empty() {
+            "general-purpose"
+        } else {
+            agent_type
+        }
+    );
+    child_config
+        .function_tools
+        .retain(|tool| tool.name != TASK_TOOL_NAME);
+    child_config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn the_sub_agent_has_its_own_prompt_and_no_task_tool() {
+        let config = FlexibleApiConfig::default();
+        assert!(config.function_tools.iter().any(|tool| tool.name == TASK_TOOL_NAME));
+
+        let child = sub_agent_config(&config, "research");
+        assert!(child.function_tools.iter().all(|tool| tool.name != TASK_TOOL_NAME));
+        assert!(child.system_prompt.starts_with("You are a research sub-agent"));
+        assert!(sub_agent_config(&config, "").system_prompt.contains("general-purpose sub-agent"));
+        assert_eq!(child.get_current_provider_and_model(), config.get_current_provider_and_model());
+    }
+
+    #[test]
+    fn the_sub_agent_runs_on_its_configured_model_if_it_exists() {
+        let mut config = FlexibleApiConfig::default();
+        let provider = config.providers[0].name.clone();
+        let model = config.providers[0].models.last().unwrap().clone();
+
+        config.sub_agent_model = Some(format!("{},{}", provider, model));
+        assert_eq!(sub_agent_config(&config, "").get_current_provider_and_model(), (provider.clone(), model));
+
+        config.sub_agent_model = Some(format!("{},no-such-model", provider));
+        assert_eq!(
+            sub_agent_config(&config, "").get_current_provider_and_model(),
+            config.get_current_provider_and_model()
+        );
+    }
+
+    #[test]
+    fn a_run_is_read_back_from_its_function_response() {
+        let run = SubAgentRun {
+            description: "Find the config loader".to_string(),
+            agent_type: "explore".to_string(),
+            model: "openai,gpt-4o".to_string(),
+            status: SubAgentStatus::Completed,
+            steps: vec![
+                SubAgentStep::ToolCall { name: "Grep".to_string(), arguments: json!({ "pattern": "load" }) },
+                SubAgentStep::ToolResult { name: "Grep".to_string(), result: json!("src/storage.rs") },
+                SubAgentStep::Message { content: "It is in storage.rs".to_string() },
+            ],
+        };
+        let response = json!({ "result": "It is in storage.rs", "sub_agent": run });
+        assert_eq!(response["sub_agent"]["steps"][0]["type"], "tool_call");
+        assert_eq!(SubAgentRun::from_function_response(&response), Some(run));
+        assert_eq!(SubAgentRun::from_function_response(&json!({ "result": "done" })), None);
+    }
+}
@@ -0,0 +1,111 @@
+// Tab title and favicon status
+// While a reply is generated or tools run, the document title gets a busy
+// marker and the favicon turns into a spinner; a run that finishes while the
+// tab is in the background leaves a "done" dot until the tab is looked at.
+
+use gloo::events::EventListener;
+use wasm_bindgen::JsCast;
+use web_sys::{Document, HtmlLinkElement};
+
+const FAVICON_ID: &str = "llm-playground-status-icon";
+const BUSY_PREFIX: &str = "⏳ ";
+const DONE_PREFIX: &str = "● ";
+
+const BUSY_ICON: &str = "<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 32 32'>\
+<circle cx='16' cy='16' r='12' fill='none' stroke='%23e5e7eb' stroke-width='5'/>\
+<path d='M16 4a12 12 0 0 1 12 12' fill='none' stroke='%23f59e0b' stroke-width='5' stroke-linecap='round'>\
+<animateTransform attributeName='transform' type='rotate' from='0 16 16' to='360 16 16' dur='1s' repeatCount='indefinite'/>\
+</path></svg>";
+const DONE_ICON: &str = "<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 32 32'>\
+<circle cx='16' cy='16' r='12' fill='%2322c55e'/></svg>";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TabStatus {
+    Idle,
+    /// A reply is being generated or tools are running
+    Busy,
+    /// A run finished while the tab was in the background
+    Done,
+}
+
+/// The title for `status`, from the current one with any marker removed
+pub fn title_for(status: TabStatus, current: &str) -> String {
+    let base = current
+        .strip_prefix(BUSY_PREFIX)
+        .or_else(|| current.strip_prefix(DONE_PREFIX))
+        .unwrap_or(current);
+    match status {
+        TabStatus::Idle => base.to_string(),
+        TabStatus::Busy => format!("{}{}", BUSY_PREFIX, base),
+        TabStatus::Done => format!("{}{}", DONE_PREFIX, base),
+    }
+}
+
+fn document() -> Option<Document> {
+    web_sys::window().and_then(|window| window.document())
+}
+
+pub fn tab_hidden() -> bool {
+    document().is_some_and(|document| document.hidden())
+}
+
+/// Show `status` in the tab's title and favicon
+pub fn set(status: TabStatus) {
+    let Some(document) = document() else {
+        return;
+    };
+    document.set_title(&title_for(status, &document.title()));
+
+    let icon = match status {
+        TabStatus::Idle => None,
+        TabStatus::Busy => Some(BUSY_ICON),
+        TabStatus::Done => Some(DONE_ICON),
+    };
+    let existing = document
+        .get_element_by_id(FAVICON_ID)
+        .and_then(|element| element.dyn_into::<HtmlLinkElement>().ok());
+    match (icon, existing) {
+        // Removing the link brings the site's own favicon back
+        (None, Some(link)) => link.remove(),
+        (None, None) => {}
+        (Some(svg), existing) => {
+            let link = existing.or_else(|| {
+                let link = document.create_element("link").ok()?.dyn_into::<HtmlLinkElement>().ok()?;
+                link.set_id(FAVICON_ID);
+                link.set_rel("icon");
+                link.set_type("image/svg+xml");
+                document.head()?.append_child(&link).ok()?;
+                Some(link)
+            });
+            if let Some(link) = link {
+                link.set_href(&format!("data:image/svg+xml,{}", svg));
+            }
+        }
+    }
+}
+
+/// Go back to idle once the tab is shown after a run finished in the
+/// background; the listener stops when dropped
+pub fn clear_done_when_shown() -> Option<EventListener> {
+    let document = document()?;
+    Some(EventListener::new(&document.clone(), "visibilitychange", move |_| {
+        if !document.hidden() && document.title().starts_with(DONE_PREFIX) {
+            set(TabStatus::Idle);
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markers_replace_each_other() {
+        let busy = title_for(TabStatus::Busy, "LLM API Playground");
+        assert_eq!(busy, "⏳ LLM API Playground");
+        let done = title_for(TabStatus::Done, &busy);
+        assert_eq!(done, "● LLM API Playground");
+        assert_eq!(title_for(TabStatus::Idle, &done), "LLM API Playground");
+        assert_eq!(title_for(TabStatus::Busy, &busy), busy);
+    }
+}
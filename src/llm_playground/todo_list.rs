@@ -0,0 +1,142 @@
+// TodoWrite tool state
+// The model's todo list is stored on the session; changes the user makes in the
+// task panel are reported back to the model as a TodoWrite tool result.
+
+use crate::llm_playground::{ChatSession, Message, MessageRole, TodoItem, TodoStatus};
+use serde_json::Value;
+
+pub const TODO_WRITE_TOOL_NAME: &str = "TodoWrite";
+
+/// Read the `todos` argument of a TodoWrite call
+pub fn parse_todos(arguments: &Value) -> Result<Vec<TodoItem>, String> {
+    let todos = arguments
+        .get("todos")
+        .ok_or_else(|| "Missing 'todos' argument".to_string())?;
+    let mut items: Vec<TodoItem> = serde_json::from_value(todos.clone())
+        .map_err(|e| format!("Invalid 'todos' argument: {}", e))?;
+
+    for (index, item) in items.iter_mut().enumerate() {
+        if item.content.trim().is_empty() {
+            return Err(format!("Todo {} has empty content", index + 1));
+        }
+        if item.id.is_empty() {
+            item.id = (index + 1).to_string();
+        }
+    }
+    Ok(items)
+}
+
+/// Counts per status, as returned to the model
+pub fn todo_summary(todos: &[TodoItem]) -> Value {
+    let count = |status: TodoStatus| todos.iter().filter(|t| t.status == status).count();
+    serde_json::json!({
+        "total_todos": todos.len(),
+        "pending": count(TodoStatus::Pending),
+        "in_progress": count(TodoStatus::InProgress),
+        "completed": count(TodoStatus::Completed),
+    })
+}
+
+/// Handle a TodoWrite call from the model: replace the session's list and
+/// return the tool result
+pub fn apply_todo_write(session: &mut ChatSession, arguments: &Value) -> Value {
+    match parse_todos(arguments) {
+        Ok(todos) => {
+            let mut result = todo_summary(&todos);
+            result["status"] = "updated".into();
+            session.todos = todos;
+            result
+        }
+        Err(error) => serde_json::json!({ "error": error }),
+    }
+}
+
+/// Check off (or reopen) a todo from the task panel and tell the model about it.
+///
+/// The change is recorded as a TodoWrite call/result pair so the model sees the
+/// updated list on its next turn. Returns false if there is no such todo.
+pub fn toggle_todo_by_user(session: &mut ChatSession, todo_id: &str) -> bool {
+    let Some(item) = session.todos.iter_mut().find(|t| t.id == todo_id) else {
+        return false;
+    };
+    item.status = if item.status == TodoStatus::Completed {
+        TodoStatus::Pending
+    } else {
+        TodoStatus::Completed
+    };
+    let change = format!(
+        "The user marked \"{}\" as {}",
+        item.content,
+        if item.status == TodoStatus::Completed {
+            "completed"
+        } else {
+            "pending"
+        }
+    );
+
+    let now = js_sys::Date::now();
+    let call_id = format!("todo_user_{}", now as u64);
+    let arguments = serde_json::json!({ "todos": session.todos });
+    let mut result = todo_summary(&session.todos);
+    result["status"] = "updated_by_user".into();
+    result["change"] = change.into();
+
+    session.messages.push(Message {
+        id: format!("msg_fc_{}", now as u64),
+        timestamp: now,
+        function_call: Some(serde_json::json!([{
+            "id": call_id,
+            "name": TODO_WRITE_TOOL_NAME,
+            "arguments": arguments,
+        }])),
+        ..Message::new(MessageRole::Assistant, "")
+    });
+    session.messages.push(Message {
+        id: format!("msg_fr_{}", now as u64),
+        timestamp: now,
+        function_response: Some(serde_json::json!({
+            "id": call_id,
+            "name": TODO_WRITE_TOOL_NAME,
+            "response": result,
+        })),
+        ..Message::new(MessageRole::Function, format!("Function {} executed", TODO_WRITE_TOOL_NAME))
+    });
+    session.updated_at = now;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_todos_and_fills_missing_ids() {
+        let arguments = serde_json::json!({
+            "todos": [
+                { "id": "a", "content": "Write parser", "status": "in_progress", "priority": "high" },
+                { "id": "", "content": "Add tests", "status": "pending", "priority": "low" },
+            ]
+        });
+        let todos = parse_todos(&arguments).unwrap();
+        assert_eq!(todos[0].status, TodoStatus::InProgress);
+        assert_eq!(todos[1].id, "2");
+
+        let summary = todo_summary(&todos);
+        assert_eq!(summary["total_todos"], 2);
+        assert_eq!(summary["in_progress"], 1);
+        assert_eq!(summary["pending"], 1);
+    }
+
+    #[test]
+    fn rejects_invalid_todos() {
+        assert!(parse_todos(&serde_json::json!({})).is_err());
+        assert!(parse_todos(&serde_json::json!({
+            "todos": [{ "id": "1", "content": " ", "status": "pending", "priority": "low" }]
+        }))
+        .is_err());
+        assert!(parse_todos(&serde_json::json!({
+            "todos": [{ "id": "1", "content": "x", "status": "done", "priority": "low" }]
+        }))
+        .is_err());
+    }
+}
@@ -0,0 +1,207 @@
+// Tool dependencies
+// A tool can declare calls it needs before it, such as Edit needing a Read of
+// the same file. The agent loops check each call against the conversation so
+// far and answer one that skips a step with an error naming the call to make
+// first, instead of running it. The built-in tools declare the rules their
+// descriptions already ask models to follow.
+
+use crate::llm_playground::{
+    provider_config::FlexibleApiConfig, FunctionTool, Message, MessageRole, ToolDependency,
+};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// The tool's dependencies; tools saved before they could be declared get
+/// those of the default tool with the same name
+pub fn dependencies_of(tool: &FunctionTool) -> Vec<ToolDependency> {
+    match &tool.requires {
+        Some(requires) => requires.clone(),
+        None => FlexibleApiConfig::get_default_function_tools()
+            .into_iter()
+            .find(|default| default.name == tool.name)
+            .and_then(|default| default.requires)
+            .unwrap_or_default(),
+    }
+}
+
+/// Tool calls in `messages` whose result wasn't an error, as name and arguments
+fn successful_calls(messages: &[Message]) -> Vec<(&str, &Value)> {
+    let succeeded: HashSet<&str> = messages
+        .iter()
+        .filter_map(|message| {
+            let function_response = message.function_response.as_ref()?;
+            let failed = function_response
+                .get("response")
+                .is_some_and(|response| response.get("error").is_some());
+            if failed {
+                None
+            } else {
+                function_response.get("id")?.as_str()
+            }
+        })
+        .collect();
+    messages
+        .iter()
+        .filter(|message| message.role == MessageRole::Assistant)
+        .filter_map(|message| match &message.function_call {
+            Some(Value::Array(calls)) => Some(calls),
+            _ => None,
+        })
+        .flatten()
+        .filter(|call| {
+            call.get("id")
+                .and_then(Value::as_str)
+                .is_some_and(|id| succeeded.contains(id))
+        })
+        .filter_map(|call| {
+            let name = call.get("name")?.as_str()?;
+            Some((name, call.get("arguments").unwrap_or(&Value::Null)))
+        })
+        .collect()
+}
+
+/// Check a call of `tool_name` against the calls already made in `messages`.
+/// The error tells the model which call to make first.
+pub fn check(
+    config: &FlexibleApiConfig,
+    tool_name: &str,
+    arguments: &Value,
+    messages: &[Message],
+) -> Result<(), String> {
+    let Some(tool) = config.function_tools.iter().find(|tool| tool.name == tool_name) else {
+        return Ok(());
+    };
+    let dependencies = dependencies_of(tool);
+    if dependencies.is_empty() {
+        return Ok(());
+    }
+    let calls = successful_calls(messages);
+    for dependency in dependencies {
+        // Without the argument the call fails on its own, with a better error
+        let value = match &dependency.same_argument {
+            Some(argument) => match arguments.get(argument) {
+                Some(value) => Some((argument, value)),
+                None => continue,
+            },
+            None => None,
+        };
+        let met = calls.iter().any(|(name, call_arguments)| {
+            *name == dependency.tool
+                && value.is_none_or(|(argument, value)| call_arguments.get(argument) == Some(value))
+        });
+        if !met {
+            return Err(match value {
+                Some((argument, value)) => format!(
+                    "{} requires a successful {} call with {} {} earlier in the conversation. Call {} with {} {} first, then retry {}.",
+                    tool_name, dependency.tool, argument, value, dependency.tool, argument, value, tool_name
+                ),
+                None => format!(
+                    "{} requires a successful {} call earlier in the conversation. Call {} first, then retry {}.",
+                    tool_name, dependency.tool, dependency.tool, tool_name
+                ),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Dependencies as typed in the tool editor: `Read(file_path), Search`
+pub fn format_list(dependencies: &[ToolDependency]) -> String {
+    dependencies
+        .iter()
+        .map(|dependency| match &dependency.same_argument {
+            Some(argument) => format!("{}({})", dependency.tool, argument),
+            None => dependency.tool.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Read the tool editor's dependency list, skipping empty entries
+pub fn parse_list(text: &str) -> Vec<ToolDependency> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once('(') {
+            Some((tool, argument)) => ToolDependency {
+                tool: tool.trim().to_string(),
+                same_argument: Some(argument.trim_end_matches(')').trim().to_string())
+                    .filter(|argument| !argument.is_empty()),
+            },
+            None => ToolDependency {
+                tool: entry.to_string(),
+                same_argument: None,
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(role: MessageRole) -> Message {
+        Message::new(role, "")
+    }
+
+    fn call(id: &str, name: &str, arguments: Value) -> Message {
+        let mut message = message(MessageRole::Assistant);
+        message.function_call = Some(json!([{ "id": id, "name": name, "arguments": arguments }]));
+        message
+    }
+
+    fn result(id: &str, response: Value) -> Message {
+        let mut message = message(MessageRole::Function);
+        message.function_response = Some(json!({ "id": id, "name": "tool", "response": response }));
+        message
+    }
+
+    #[test]
+    fn edit_needs_a_read_of_the_same_file() {
+        let config = FlexibleApiConfig::default();
+        let edit = json!({ "file_path": "/src/main.rs", "old_string": "a", "new_string": "b" });
+        let mut messages = vec![
+            call("1", "Read", json!({ "file_path": "/src/lib.rs" })),
+            result("1", json!({ "content": "..." })),
+            call("2", "Read", json!({ "file_path": "/src/main.rs" })),
+            result("2", json!({ "error": "No such file" })),
+        ];
+        assert_eq!(
+            check(&config, "Edit", &edit, &messages).unwrap_err(),
+            "Edit requires a successful Read call with file_path \"/src/main.rs\" earlier in the conversation. \
+             Call Read with file_path \"/src/main.rs\" first, then retry Edit."
+        );
+
+        messages.push(call("3", "Read", json!({ "file_path": "/src/main.rs" })));
+        messages.push(result("3", json!({ "content": "fn main() {}" })));
+        assert!(check(&config, "Edit", &edit, &messages).is_ok());
+        assert!(check(&config, "Write", &edit, &[]).is_ok());
+    }
+
+    #[test]
+    fn saved_tools_fall_back_to_the_defaults() {
+        let mut config = FlexibleApiConfig::default();
+        let edit = config.function_tools.iter_mut().find(|tool| tool.name == "MultiEdit").unwrap();
+        edit.requires = None;
+        assert!(check(&config, "MultiEdit", &json!({ "file_path": "/a" }), &[]).is_err());
+
+        let edit = config.function_tools.iter_mut().find(|tool| tool.name == "MultiEdit").unwrap();
+        edit.requires = Some(Vec::new());
+        assert!(check(&config, "MultiEdit", &json!({ "file_path": "/a" }), &[]).is_ok());
+    }
+
+    #[test]
+    fn editor_lists_round_trip() {
+        let dependencies = parse_list(" Read(file_path),, Search ,Fetch()");
+        assert_eq!(
+            dependencies,
+            vec![
+                ToolDependency { tool: "Read".to_string(), same_argument: Some("file_path".to_string()) },
+                ToolDependency { tool: "Search".to_string(), same_argument: None },
+                ToolDependency { tool: "Fetch".to_string(), same_argument: None },
+            ]
+        );
+        assert_eq!(format_list(&dependencies), "Read(file_path), Search, Fetch");
+    }
+}
@@ -0,0 +1,181 @@
+// Tool pre-selection
+// With many function tools enabled, a model (the session's or a cheaper one
+// picked in settings) first reads the user's message and the tools' names and
+// descriptions and picks the relevant ones. Only those are sent with the
+// request, which saves their definitions' tokens and keeps the model from
+// reaching for the wrong tool. The pick holds for the whole turn, tool calls
+// included.
+
+use crate::llm_playground::{
+    flexible_client::FlexibleLLMClient, provider_config::FlexibleApiConfig, FunctionTool, Message,
+    MessageRole,
+};
+use serde::{Deserialize, Serialize};
+
+/// Characters of the user's message given to the model
+const MESSAGE_CHARS: usize = 4000;
+/// Characters of each tool's description listed
+const DESCRIPTION_CHARS: usize = 300;
+
+pub const META_PROMPT: &str = "You decide which tools an AI assistant may need to answer a user's \
+message. Given the message and a list of tools with their descriptions, reply with the names of \
+the tools that could help, one per line, exactly as listed. If none of them is needed, reply \
+with NONE. Reply with nothing else.";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolSelectionSettings {
+    pub enabled: bool,
+    /// "provider,model" that picks the tools; empty uses the session's model
+    pub model: String,
+    /// Enabled tools needed before a pick is asked for
+    pub min_tools: usize,
+}
+
+impl Default for ToolSelectionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            model: String::new(),
+            min_tools: 5,
+        }
+    }
+}
+
+impl ToolSelectionSettings {
+    /// Whether a request with this config gets its tools picked
+    pub fn applies(&self, config: &FlexibleApiConfig) -> bool {
+        self.enabled && config.get_enabled_function_tools().len() >= self.min_tools.max(1)
+    }
+
+    /// The config the pick is requested with
+    pub fn config_for(&self, session_config: &FlexibleApiConfig) -> FlexibleApiConfig {
+        let mut config = session_config.clone();
+        if let Some((provider, model)) = self.model.split_once(',') {
+            config.set_session_provider(provider, model);
+            config.apply_model_defaults(provider, model);
+        }
+        config
+    }
+}
+
+/// The single user message asking for the relevant tools
+pub fn build_request(message: &str, tools: &[&FunctionTool]) -> String {
+    let message: String = message.chars().take(MESSAGE_CHARS).collect();
+    let listed: Vec<String> = tools
+        .iter()
+        .map(|tool| {
+            let description: String = tool.description.chars().take(DESCRIPTION_CHARS).collect();
+            format!("- {}: {}", tool.name, description)
+        })
+        .collect();
+    format!(
+        "Message:\n{}\n\nTools:\n{}\n\nWhich tools could help?",
+        message.trim(),
+        listed.join("\n")
+    )
+}
+
+/// The listed tool names the reply mentions; unknown names are dropped
+pub fn parse_selection(reply: &str, tools: &[&FunctionTool]) -> Vec<String> {
+    // A name as written, or after a list marker like "-" or "2."
+    let picked: Vec<&str> = reply
+        .split(['\n', ','])
+        .flat_map(|line| {
+            let line = line.trim();
+            let unlisted = line
+                .trim_start_matches(|c: char| c == '-' || c == '*' || c.is_ascii_digit() || c == '.');
+            [unquote(line), unquote(unlisted)]
+        })
+        .collect();
+    tools
+        .iter()
+        .filter(|tool| picked.contains(&tool.name.as_str()))
+        .map(|tool| tool.name.clone())
+        .collect()
+}
+
+fn unquote(name: &str) -> &str {
+    name.trim().trim_matches(['`', '"', '\''])
+}
+
+/// Leave only the picked tools enabled
+pub fn restrict(config: &mut FlexibleApiConfig, picked: &[String]) {
+    for tool in &mut config.function_tools {
+        tool.enabled = tool.enabled && picked.contains(&tool.name);
+    }
+}
+
+/// Ask which of the session's enabled tools the message needs
+pub async fn select(
+    client: &FlexibleLLMClient,
+    config: &FlexibleApiConfig,
+    session_config: &FlexibleApiConfig,
+    user_message: &str,
+) -> Result<Vec<String>, String> {
+    let tools = session_config.get_enabled_function_tools();
+    let mut config = config.clone();
+    config.system_prompt = META_PROMPT.to_string();
+    config.active_structured_output = None;
+    config.shared_settings.candidate_count = None;
+    for tool in &mut config.function_tools {
+        tool.enabled = false;
+    }
+
+    let message = Message {
+        id: format!("tool_selection_{}", js_sys::Date::now() as u64),
+        timestamp: js_sys::Date::now(),
+        ..Message::new(MessageRole::User, build_request(user_message, &tools))
+    };
+    let response = client.send_message(&[message], &config).await?;
+    let reply = response.content.unwrap_or_default();
+    if reply.trim().is_empty() {
+        return Err("The model returned no tool selection".to_string());
+    }
+    Ok(parse_selection(&reply, &tools))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, enabled: bool) -> FunctionTool {
+        FunctionTool {
+            name: name.to_string(),
+            description: format!("Does {}", name),
+            parameters: serde_json::json!({"type": "object", "properties": {}}),
+            mock_response: String::new(),
+            enabled,
+            category: "Test".to_string(),
+            is_builtin: false,
+            requires: None,
+        }
+    }
+
+    #[test]
+    fn selection_keeps_only_listed_names() {
+        let tools = [tool("get_weather", true), tool("search", true), tool("calc", true)];
+        let listed: Vec<&FunctionTool> = tools.iter().collect();
+        assert_eq!(
+            parse_selection("- `search`\n2. get_weather\nweather_tool", &listed),
+            vec!["get_weather", "search"]
+        );
+        assert_eq!(parse_selection("calc, search", &listed), vec!["search", "calc"]);
+        assert!(parse_selection("NONE", &listed).is_empty());
+    }
+
+    #[test]
+    fn restricting_never_enables_a_tool() {
+        let mut config = FlexibleApiConfig {
+            function_tools: vec![tool("a", true), tool("b", true), tool("c", false)],
+            ..Default::default()
+        };
+        restrict(&mut config, &["b".to_string(), "c".to_string()]);
+        let enabled: Vec<&str> = config
+            .get_enabled_function_tools()
+            .iter()
+            .map(|tool| tool.name.as_str())
+            .collect();
+        assert_eq!(enabled, vec!["b"]);
+    }
+}
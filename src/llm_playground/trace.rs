@@ -0,0 +1,236 @@
+// Tool call trace
+// Lays out every tool call in a session by the model turn that asked for it:
+// arguments, how long the call ran and whether it succeeded. Built from the
+// session's messages, with the exact durations of a session recording when
+// one covers the call, so multi-step agent runs can be followed at a glance.
+
+use crate::llm_playground::{
+    session_recording::{RecordedStep, SessionRecording},
+    sub_agent::{SubAgentRun, SubAgentStatus},
+    Message, MessageRole,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Characters of an argument summary
+const SUMMARY_CHARS: usize = 80;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TraceStatus {
+    Ok,
+    Error,
+    /// No result yet, or the sub-agent is still running
+    Pending,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceSpan {
+    pub call_id: String,
+    pub name: String,
+    pub arguments: Value,
+    pub result: Option<Value>,
+    pub started_at: f64,
+    /// None while running, or when the session doesn't tell
+    pub duration_ms: Option<f64>,
+    pub status: TraceStatus,
+}
+
+/// A model reply that called tools
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceTurn {
+    /// Position among the session's assistant replies, from 1
+    pub number: usize,
+    pub model: Option<String>,
+    pub started_at: f64,
+    /// How long the model took to ask for the calls
+    pub latency_ms: Option<f64>,
+    pub spans: Vec<TraceSpan>,
+}
+
+impl TraceTurn {
+    /// When the turn's last call finished, or its reply arrived if none did
+    pub fn ended_at(&self) -> f64 {
+        self.spans
+            .iter()
+            .filter_map(|span| span.duration_ms.map(|duration| span.started_at + duration))
+            .fold(self.started_at, f64::max)
+    }
+}
+
+/// "city: Paris, units: metric", cut to a line
+pub fn summarize_arguments(arguments: &Value) -> String {
+    let summary = match arguments {
+        Value::Object(fields) => fields
+            .iter()
+            .map(|(key, value)| match value {
+                Value::String(text) => format!("{}: {}", key, text),
+                other => format!("{}: {}", key, other),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    };
+    let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+    if summary.chars().count() > SUMMARY_CHARS {
+        format!("{}…", summary.chars().take(SUMMARY_CHARS).collect::<String>())
+    } else {
+        summary
+    }
+}
+
+fn status_of(function_response: &Value) -> TraceStatus {
+    if let Some(run) = SubAgentRun::from_function_response(function_response) {
+        return match run.status {
+            SubAgentStatus::Running => TraceStatus::Pending,
+            SubAgentStatus::Completed => TraceStatus::Ok,
+            SubAgentStatus::Failed => TraceStatus::Error,
+        };
+    }
+    let failed = function_response
+        .get("response")
+        .is_some_and(|response| response.get("error").is_some());
+    if failed {
+        TraceStatus::Error
+    } else {
+        TraceStatus::Ok
+    }
+}
+
+/// The session's tool calls by turn. Without a recording, a call is taken to
+/// start when the one before it in its turn finished.
+pub fn build(messages: &[Message], recording: Option<&SessionRecording>) -> Vec<TraceTurn> {
+    let recorded: HashMap<&str, (f64, f64)> = recording
+        .map(|recording| {
+            recording
+                .steps
+                .iter()
+                .filter_map(|step| match step {
+                    RecordedStep::ToolCall(call) => {
+                        Some((call.call_id.as_str(), (call.started_at, call.duration_ms)))
+                    }
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let responses: HashMap<&str, &Message> = messages
+        .iter()
+        .filter_map(|message| {
+            let id = message.function_response.as_ref()?.get("id")?.as_str()?;
+            Some((id, message))
+        })
+        .collect();
+
+    let mut turns = Vec::new();
+    let replies = messages.iter().filter(|m| m.role == MessageRole::Assistant);
+    for (index, message) in replies.enumerate() {
+        let Some(Value::Array(calls)) = &message.function_call else {
+            continue;
+        };
+        let mut previous_end = message.timestamp;
+        let spans = calls
+            .iter()
+            .map(|call| {
+                let call_id = call.get("id").and_then(|id| id.as_str()).unwrap_or_default();
+                let response = responses.get(call_id);
+                let is_task = SubAgentRun::from_function_response(
+                    response.and_then(|r| r.function_response.as_ref()).unwrap_or(&Value::Null),
+                )
+                .is_some();
+                let (started_at, duration_ms) = match (recorded.get(call_id), response) {
+                    (Some((started_at, duration)), _) => (*started_at, Some(*duration)),
+                    // A Task's message is added when it starts and kept updated
+                    (None, Some(response)) if is_task => (response.timestamp, None),
+                    (None, Some(response)) => {
+                        (previous_end, Some((response.timestamp - previous_end).max(0.0)))
+                    }
+                    (None, None) => (previous_end, None),
+                };
+                if let Some(duration) = duration_ms {
+                    previous_end = started_at + duration;
+                }
+                let function_response = response.and_then(|r| r.function_response.as_ref());
+                TraceSpan {
+                    call_id: call_id.to_string(),
+                    name: call
+                        .get("name")
+                        .and_then(|name| name.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    arguments: call.get("arguments").cloned().unwrap_or(Value::Null),
+                    result: function_response.map(|r| {
+                        r.get("response").or_else(|| r.get("sub_agent")).cloned().unwrap_or(Value::Null)
+                    }),
+                    started_at,
+                    duration_ms,
+                    status: function_response.map_or(TraceStatus::Pending, status_of),
+                }
+            })
+            .collect();
+        turns.push(TraceTurn {
+            number: index + 1,
+            model: message.metrics.as_ref().and_then(|m| m.model.clone()),
+            started_at: message.timestamp,
+            latency_ms: message.metrics.as_ref().map(|m| m.latency_ms),
+            spans,
+        });
+    }
+    turns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(role: MessageRole, timestamp: f64) -> Message {
+        Message {
+            timestamp,
+            ..Message::new(role, "")
+        }
+    }
+
+    fn result(id: &str, response: Value, timestamp: f64) -> Message {
+        let mut message = message(MessageRole::Function, timestamp);
+        message.function_response = Some(json!({"id": id, "name": "tool", "response": response}));
+        message
+    }
+
+    #[test]
+    fn calls_are_timed_one_after_another() {
+        let mut call = message(MessageRole::Assistant, 1000.0);
+        call.function_call = Some(json!([
+            {"id": "a", "name": "get_weather", "arguments": {"city": "Paris"}},
+            {"id": "b", "name": "search", "arguments": {"query": "news"}},
+            {"id": "c", "name": "calc", "arguments": {}},
+        ]));
+        let messages = vec![
+            message(MessageRole::User, 900.0),
+            message(MessageRole::Assistant, 950.0),
+            call,
+            result("a", json!({"temp": 20}), 1300.0),
+            result("b", json!({"error": "offline"}), 1350.0),
+        ];
+
+        let turns = build(&messages, None);
+        assert_eq!(turns.len(), 1);
+        assert_eq!(turns[0].number, 2);
+        let spans = &turns[0].spans;
+        assert_eq!((spans[0].started_at, spans[0].duration_ms), (1000.0, Some(300.0)));
+        assert_eq!((spans[1].started_at, spans[1].duration_ms), (1300.0, Some(50.0)));
+        assert_eq!(spans[1].status, TraceStatus::Error);
+        assert_eq!(spans[2].status, TraceStatus::Pending);
+        assert_eq!(turns[0].ended_at(), 1350.0);
+    }
+
+    #[test]
+    fn arguments_are_summarized_on_one_line() {
+        assert_eq!(
+            summarize_arguments(&json!({"city": "Paris", "days": 3})),
+            "city: Paris, days: 3"
+        );
+        let long = summarize_arguments(&json!({"text": "word\n".repeat(40)}));
+        assert!(long.ends_with('…') && !long.contains('\n'));
+    }
+}
@@ -0,0 +1,272 @@
+// Trace export in OpenTelemetry's OTLP JSON format
+// A session becomes one trace: a root span for the conversation, a span per
+// model reply with its model and token counts, and a span per tool call under
+// the reply that asked for it. Attribute names follow the OpenTelemetry GenAI
+// conventions, so the file can be loaded into LLM observability tools.
+
+use crate::llm_playground::{
+    html_export,
+    session_recording::SessionRecording,
+    trace::{self, TraceStatus},
+    ChatSession, MessageRole,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+const SERVICE_NAME: &str = "llm-playground";
+/// OTLP span kinds
+const KIND_INTERNAL: u8 = 1;
+const KIND_CLIENT: u8 = 3;
+/// OTLP status codes
+const STATUS_OK: u8 = 1;
+const STATUS_ERROR: u8 = 2;
+
+/// FNV-1a, so a session always exports with the same ids
+fn hash(seed: u64, text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325 ^ seed, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn trace_id(session_id: &str) -> String {
+    format!("{:016x}{:016x}", hash(1, session_id), hash(2, session_id))
+}
+
+fn span_id(session_id: &str, key: &str) -> String {
+    format!("{:016x}", hash(3, &format!("{}/{}", session_id, key)))
+}
+
+fn unix_nanos(millis: f64) -> String {
+    ((millis.max(0.0) * 1_000_000.0) as u64).to_string()
+}
+
+fn attribute(key: &str, value: Value) -> Option<Value> {
+    let value = match value {
+        Value::String(text) => json!({ "stringValue": text }),
+        Value::Number(number) if number.is_f64() => json!({ "doubleValue": number }),
+        // int64 values are strings in OTLP JSON
+        Value::Number(number) => json!({ "intValue": number.to_string() }),
+        Value::Bool(flag) => json!({ "boolValue": flag }),
+        Value::Null => return None,
+        other => json!({ "stringValue": other.to_string() }),
+    };
+    Some(json!({ "key": key, "value": value }))
+}
+
+fn attributes(pairs: Vec<(&str, Value)>) -> Vec<Value> {
+    pairs
+        .into_iter()
+        .filter_map(|(key, value)| attribute(key, value))
+        .collect()
+}
+
+/// A finished span; its status is OK until set otherwise
+fn span(
+    session_id: &str,
+    key: &str,
+    parent: Option<&str>,
+    name: String,
+    kind: u8,
+    (start, end): (f64, f64),
+    attributes: Vec<Value>,
+) -> Value {
+    json!({
+        "traceId": trace_id(session_id),
+        "spanId": span_id(session_id, key),
+        "parentSpanId": parent.map(|parent| span_id(session_id, parent)).unwrap_or_default(),
+        "name": name,
+        "kind": kind,
+        "startTimeUnixNano": unix_nanos(start),
+        "endTimeUnixNano": unix_nanos(end.max(start)),
+        "attributes": attributes,
+        "status": { "code": STATUS_OK },
+    })
+}
+
+/// The session as an OTLP `ExportTraceServiceRequest`
+pub fn to_otlp(session: &ChatSession, recording: Option<&SessionRecording>) -> Value {
+    let id = session.id.as_str();
+    let mut spans = Vec::new();
+    let mut ended_at = session.created_at;
+    // Reply spans by the reply's position among assistant messages, from 1
+    let mut replies: HashMap<usize, String> = HashMap::new();
+
+    let assistant_messages = session
+        .messages
+        .iter()
+        .filter(|m| m.role == MessageRole::Assistant);
+    for (index, message) in assistant_messages.enumerate() {
+        let key = format!("reply/{}", message.id);
+        let metrics = message.metrics.as_ref();
+        let latency = metrics.map_or(0.0, |m| m.latency_ms);
+        let model = metrics.and_then(|m| m.model.clone());
+        let tool_calls = match &message.function_call {
+            Some(Value::Array(calls)) => calls.len(),
+            _ => 0,
+        };
+        ended_at = ended_at.max(message.timestamp);
+        spans.push(span(
+            id,
+            &key,
+            Some("session"),
+            format!("chat {}", model.as_deref().unwrap_or("model")),
+            KIND_CLIENT,
+            (message.timestamp - latency, message.timestamp),
+            attributes(vec![
+                ("gen_ai.operation.name", json!("chat")),
+                ("gen_ai.response.model", json!(model)),
+                ("gen_ai.usage.input_tokens", json!(metrics.and_then(|m| m.input_tokens))),
+                ("gen_ai.usage.output_tokens", json!(metrics.and_then(|m| m.output_tokens))),
+                ("gen_ai.usage.cached_tokens", json!(metrics.and_then(|m| m.cached_tokens))),
+                ("gen_ai.response.finish_reasons", json!(message.finish_reason)),
+                ("gen_ai.response.tool_calls", json!(tool_calls)),
+                ("llm.time_to_headers_ms", json!(metrics.and_then(|m| m.headers_ms))),
+                ("llm.cost", json!(metrics.and_then(|m| m.cost))),
+            ]),
+        ));
+        replies.insert(index + 1, key);
+    }
+
+    for turn in trace::build(&session.messages, recording) {
+        let parent = replies.get(&turn.number).map(String::as_str);
+        for call in turn.spans {
+            let end = call.started_at + call.duration_ms.unwrap_or(0.0);
+            ended_at = ended_at.max(end);
+            let error = (call.status == TraceStatus::Error).then(|| {
+                call.result
+                    .as_ref()
+                    .and_then(|result| result.get("error"))
+                    .map(|error| error.as_str().map_or_else(|| error.to_string(), str::to_string))
+                    .unwrap_or_else(|| "Tool call failed".to_string())
+            });
+            let mut tool_span = span(
+                id,
+                &format!("tool/{}", call.call_id),
+                parent.or(Some("session")),
+                format!("execute_tool {}", call.name),
+                KIND_INTERNAL,
+                (call.started_at, end),
+                attributes(vec![
+                    ("gen_ai.operation.name", json!("execute_tool")),
+                    ("gen_ai.tool.name", json!(call.name)),
+                    ("gen_ai.tool.call.id", json!(call.call_id)),
+                    ("gen_ai.tool.call.arguments", json!(call.arguments.to_string())),
+                    ("gen_ai.tool.call.result", json!(call.result.map(|result| result.to_string()))),
+                    ("tool.pending", json!(call.status == TraceStatus::Pending)),
+                ]),
+            );
+            if let Some(message) = error {
+                tool_span["status"] = json!({ "code": STATUS_ERROR, "message": message });
+            }
+            spans.push(tool_span);
+        }
+    }
+
+    let started_at = session
+        .messages
+        .first()
+        .map_or(session.created_at, |m| m.timestamp.min(session.created_at));
+    spans.insert(
+        0,
+        span(
+            id,
+            "session",
+            None,
+            format!("session {}", session.title),
+            KIND_INTERNAL,
+            (started_at, ended_at),
+            attributes(vec![
+                ("gen_ai.operation.name", json!("invoke_agent")),
+                ("gen_ai.conversation.id", json!(session.id)),
+                ("session.messages", json!(session.messages.len())),
+            ]),
+        ),
+    );
+
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": attributes(vec![("service.name", json!(SERVICE_NAME))]),
+            },
+            "scopeSpans": [{
+                "scope": { "name": SERVICE_NAME, "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans,
+            }],
+        }],
+    })
+}
+
+/// "my-session.trace.json"
+pub fn file_name(session: &ChatSession) -> String {
+    let html = html_export::export_file_name(session);
+    format!("{}.trace.json", html.trim_end_matches(".html"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm_playground::{Message, ResponseMetrics};
+
+    fn message(id: &str, role: MessageRole, timestamp: f64) -> Message {
+        Message {
+            id: id.to_string(),
+            timestamp,
+            ..Message::new(role, "")
+        }
+    }
+
+    #[test]
+    fn tool_spans_hang_off_the_reply_that_called_them() {
+        let mut reply = message("r1", MessageRole::Assistant, 2000.0);
+        reply.function_call = Some(json!([{ "id": "c1", "name": "search", "arguments": {} }]));
+        reply.metrics = Some(ResponseMetrics {
+            headers_ms: None,
+            latency_ms: 500.0,
+            output_tokens: Some(12),
+            input_tokens: Some(340),
+            cached_tokens: None,
+            queue_ms: None,
+            generation_ms: None,
+            cost: None,
+            model: Some("gpt-4o".to_string()),
+        });
+        let mut result = message("f1", MessageRole::Function, 2300.0);
+        result.function_response =
+            Some(json!({ "id": "c1", "name": "search", "response": { "error": "offline" } }));
+        let session = ChatSession {
+            id: "s1".to_string(),
+            title: "Test".to_string(),
+            messages: vec![message("u1", MessageRole::User, 1000.0), reply, result],
+            created_at: 1000.0,
+            updated_at: 2300.0,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: false,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        };
+
+        let otlp = to_otlp(&session, None);
+        let spans = otlp["resourceSpans"][0]["scopeSpans"][0]["spans"].as_array().unwrap();
+        assert_eq!(spans.len(), 3);
+        let (root, chat, tool) = (&spans[0], &spans[1], &spans[2]);
+        assert_eq!(root["parentSpanId"], "");
+        assert_eq!(root["endTimeUnixNano"], "2300000000");
+        assert_eq!(chat["parentSpanId"], root["spanId"]);
+        assert_eq!(chat["name"], "chat gpt-4o");
+        assert_eq!(chat["startTimeUnixNano"], "1500000000");
+        assert!(chat["attributes"]
+            .as_array()
+            .unwrap()
+            .contains(&json!({ "key": "gen_ai.usage.input_tokens", "value": { "intValue": "340" } })));
+        assert_eq!(tool["parentSpanId"], chat["spanId"]);
+        assert_eq!(tool["status"], json!({ "code": STATUS_ERROR, "message": "offline" }));
+        assert!(spans.iter().all(|span| span["traceId"] == root["traceId"]));
+    }
+}
@@ -1,5 +1,16 @@
 // Type definitions for LLM Playground
+use crate::llm_playground::api_clients::{GeminiAuth, ResponseTiming, TokenUsage};
+use crate::llm_playground::attachments::Attachment;
+use crate::llm_playground::audio_output::{AudioOutputSettings, MessageAudio};
+use crate::llm_playground::dialogue::DialogueConfig;
+use crate::llm_playground::guardrails::GuardrailViolation;
+use crate::llm_playground::image_generation::{GeneratedImage, ImageEdit};
 use crate::llm_playground::mcp_client::McpConfig;
+use crate::llm_playground::markdown::MarkdownCache;
+use crate::llm_playground::moderation::ModerationResult;
+use crate::llm_playground::native_tools::{NativeToolCall, NativeToolSettings};
+use crate::llm_playground::sampling_controls::{LogitBiasEntry, MAX_STOP_SEQUENCES};
+use crate::llm_playground::self_consistency::ConsensusResult;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -16,6 +27,7 @@ pub struct ApiConfig {
     pub shared_settings: SharedSettings,
     pub system_prompt: String,
     pub function_tools: Vec<FunctionTool>,
+    // Clients constrain replies to the first entry's schema
     pub structured_outputs: Vec<StructuredOutput>,
     pub mcp_config: McpConfig,
 }
@@ -25,6 +37,8 @@ pub struct GeminiConfig {
     pub api_key: String,
     pub model: String,
     pub base_url: String,
+    #[serde(default)]
+    pub auth: GeminiAuth,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -39,6 +53,190 @@ pub struct SharedSettings {
     pub temperature: f32,
     pub max_tokens: u32,
     pub retry_delay: u32,
+    // Optional CORS proxy all HTTP calls are routed through
+    #[serde(default)]
+    pub cors_proxy: Option<String>,
+    // Correction turns sent when a structured reply or tool arguments are
+    // invalid; None uses DEFAULT_JSON_REPAIR_RETRIES
+    #[serde(default)]
+    pub json_repair_retries: Option<u32>,
+    // Lifetime of Gemini context caches for large system prompts and tools;
+    // None sends the full context with every request
+    #[serde(default)]
+    pub context_cache_ttl_minutes: Option<u32>,
+    // Strings that end the reply when generated
+    #[serde(default)]
+    pub stop_sequences: Vec<String>,
+    // Token likelihood adjustments, sent to OpenAI-compatible APIs
+    #[serde(default)]
+    pub logit_bias: Vec<LogitBiasEntry>,
+    // Whether the model may, must or must not call tools
+    #[serde(default)]
+    pub tool_choice: ToolChoice,
+    // Functions Gemini may pick from when it must call a tool; empty allows
+    // every enabled one
+    #[serde(default)]
+    pub allowed_functions: Vec<String>,
+    // Web search and code interpreter run by OpenAI through the Responses API
+    #[serde(default)]
+    pub native_tools: NativeToolSettings,
+    // Spoken replies from audio-capable OpenAI models
+    #[serde(default)]
+    pub audio_output: AudioOutputSettings,
+    // Replies sampled per prompt to pick from; None asks for one
+    #[serde(default)]
+    pub candidate_count: Option<u32>,
+    // Whether reasoning models are asked to return their thinking
+    #[serde(default)]
+    pub reasoning: ReasoningVisibility,
+}
+
+pub const DEFAULT_JSON_REPAIR_RETRIES: u32 = 2;
+pub const MAX_CANDIDATES: u32 = 5;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolChoice {
+    /// The model decides
+    #[default]
+    Auto,
+    None,
+    /// The model must call a tool
+    Required,
+}
+
+impl ToolChoice {
+    pub const ALL: [ToolChoice; 3] = [ToolChoice::Auto, ToolChoice::None, ToolChoice::Required];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ToolChoice::Auto => "Auto",
+            ToolChoice::None => "No tools",
+            ToolChoice::Required => "Must call a tool",
+        }
+    }
+
+    /// OpenAI's `tool_choice` value
+    pub fn openai_value(&self) -> &'static str {
+        match self {
+            ToolChoice::Auto => "auto",
+            ToolChoice::None => "none",
+            ToolChoice::Required => "required",
+        }
+    }
+
+    /// Gemini's `functionCallingConfig.mode`
+    pub fn gemini_mode(&self) -> &'static str {
+        match self {
+            ToolChoice::Auto => "AUTO",
+            ToolChoice::None => "NONE",
+            ToolChoice::Required => "ANY",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|choice| choice.openai_value() == value)
+    }
+
+    /// The choice for one request of a run. Requests that answer tool results
+    /// fall back to Auto, or a model that must call a tool could never reply
+    /// in text and the run would never end.
+    pub fn for_request(self, follows_user_message: bool) -> Self {
+        if self == ToolChoice::Required && !follows_user_message {
+            ToolChoice::Auto
+        } else {
+            self
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasoningVisibility {
+    /// Whatever the provider does by default
+    #[default]
+    Default,
+    /// Ask for the thinking where the API can return it
+    Visible,
+    /// Ask for the answer only, and drop thinking returned anyway
+    Hidden,
+}
+
+impl ReasoningVisibility {
+    pub const ALL: [ReasoningVisibility; 3] = [
+        ReasoningVisibility::Default,
+        ReasoningVisibility::Visible,
+        ReasoningVisibility::Hidden,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReasoningVisibility::Default => "Provider default",
+            ReasoningVisibility::Visible => "Return reasoning",
+            ReasoningVisibility::Hidden => "Hide reasoning",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|visibility| visibility.label() == value)
+    }
+
+    /// Value of the request's include-reasoning flag; None leaves it out
+    pub fn include_reasoning(&self) -> Option<bool> {
+        match self {
+            ReasoningVisibility::Default => None,
+            ReasoningVisibility::Visible => Some(true),
+            ReasoningVisibility::Hidden => Some(false),
+        }
+    }
+
+    /// Thinking from a response, unless it is to be hidden
+    pub fn keep(&self, reasoning: Option<String>) -> Option<String> {
+        reasoning.filter(|reasoning| {
+            *self != ReasoningVisibility::Hidden && !reasoning.trim().is_empty()
+        })
+    }
+}
+
+impl SharedSettings {
+    /// The configured CORS proxy, if any
+    pub fn cors_proxy(&self) -> Option<&str> {
+        self.cors_proxy.as_deref().filter(|p| !p.trim().is_empty())
+    }
+
+    /// How many times an invalid structured reply or tool call is sent back for repair
+    pub fn json_repair_retries(&self) -> u32 {
+        self.json_repair_retries.unwrap_or(DEFAULT_JSON_REPAIR_RETRIES)
+    }
+
+    /// How many replies to sample per prompt, between 1 and MAX_CANDIDATES
+    pub fn candidate_count(&self) -> u32 {
+        self.candidate_count.unwrap_or(1).clamp(1, MAX_CANDIDATES)
+    }
+
+    /// Stop sequences to send, capped at what OpenAI accepts
+    pub fn stop_sequences(&self) -> Vec<String> {
+        self.stop_sequences
+            .iter()
+            .filter(|s| !s.is_empty())
+            .take(MAX_STOP_SEQUENCES)
+            .cloned()
+            .collect()
+    }
+
+    /// Context cache lifetime in minutes, if caching is on
+    pub fn context_cache_ttl(&self) -> Option<u32> {
+        self.context_cache_ttl_minutes.filter(|minutes| *minutes > 0)
+    }
+
+    /// Add a function to the ones Gemini may pick from, or remove it
+    pub fn toggle_allowed_function(&mut self, name: &str) {
+        if let Some(index) = self.allowed_functions.iter().position(|allowed| allowed == name) {
+            self.allowed_functions.remove(index);
+        } else {
+            self.allowed_functions.push(name.to_string());
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -50,6 +248,27 @@ pub struct FunctionTool {
     pub enabled: bool,
     pub category: String,
     pub is_builtin: bool,
+    /// Tools that must have succeeded earlier in the conversation before this
+    /// one runs; None for tools saved before these could be declared, which
+    /// then get the dependencies of the default tool with their name
+    #[serde(default)]
+    pub requires: Option<Vec<ToolDependency>>,
+}
+
+impl FunctionTool {
+    /// The MCP server this tool comes from, read from its category
+    pub fn mcp_server(&self) -> Option<&str> {
+        self.category.strip_prefix("MCP (")?.strip_suffix(')')
+    }
+}
+
+/// A call a tool needs before it, e.g. a Read of the file an Edit changes
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ToolDependency {
+    pub tool: String,
+    /// Argument the earlier call must have had the same value for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub same_argument: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -66,11 +285,187 @@ pub struct Message {
     pub timestamp: f64,
     pub function_call: Option<serde_json::Value>,
     pub function_response: Option<serde_json::Value>,
+    #[serde(default)]
+    pub metrics: Option<ResponseMetrics>,
+    /// Schema the reply was requested to follow, so it can be validated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_output: Option<StructuredOutput>,
+    /// Guardrail rules the reply broke
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub guardrail_violations: Vec<GuardrailViolation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub moderation: Option<ModerationResult>,
+    /// Why the model stopped, as the provider reported it, e.g. "stop",
+    /// "length" or "MAX_TOKENS"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<String>,
+    /// Thinking the model returned separately from its answer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    /// Source URLs the answer's [1], [2], ... markers refer to
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<String>,
+    /// Replies sampled for the same prompt, the shown one included; empty
+    /// unless several were requested
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candidates: Vec<String>,
+    /// How the content was chosen from the candidates in self-consistency mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consensus: Option<ConsensusResult>,
+    /// Web searches and code runs a provider did on its own for the reply
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub native_tool_calls: Vec<NativeToolCall>,
+    /// Spoken version of the reply, when audio output was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio: Option<MessageAudio>,
+    /// Files sent with a user message
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    /// Images an image model replied with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<GeneratedImage>,
+    /// Earlier image a user message asks to have edited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_edit: Option<ImageEdit>,
+    /// Blocks the content was last parsed into for display
+    #[serde(skip)]
+    pub markdown: MarkdownCache,
+}
+
+/// Sent as a user turn to have a cut-off reply resumed
+pub const CONTINUE_PROMPT: &str =
+    "Your last reply was cut off. Continue exactly where you stopped, without repeating anything.";
+
+impl Message {
+    /// A message with just a role and content: no id, a zero timestamp and
+    /// nothing attached. Set the rest with struct update syntax.
+    pub fn new(role: MessageRole, content: impl Into<String>) -> Self {
+        Self {
+            id: String::new(),
+            role,
+            content: content.into(),
+            timestamp: 0.0,
+            function_call: None,
+            function_response: None,
+            metrics: None,
+            structured_output: None,
+            guardrail_violations: Vec::new(),
+            moderation: None,
+            finish_reason: None,
+            reasoning: None,
+            citations: Vec::new(),
+            candidates: Vec::new(),
+            consensus: None,
+            native_tool_calls: Vec::new(),
+            audio: None,
+            attachments: Vec::new(),
+            images: Vec::new(),
+            image_edit: None,
+            markdown: Default::default(),
+        }
+    }
+
+    /// Whether the reply was cut off by the max tokens limit
+    pub fn hit_token_limit(&self) -> bool {
+        matches!(
+            self.finish_reason.as_deref(),
+            Some("length" | "max_tokens" | "MAX_TOKENS")
+        )
+    }
+}
+
+/// A message with an id, shared by the unit tests
+#[cfg(test)]
+pub fn test_message(id: &str, role: MessageRole, content: &str) -> Message {
+    Message {
+        id: id.to_string(),
+        ..Message::new(role, content)
+    }
+}
+
+/// Timing and throughput of the request that produced an assistant message
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResponseMetrics {
+    /// Time until the response headers arrived. Replies aren't streamed when
+    /// metrics are taken, so this is not a time to the first token.
+    #[serde(default, alias = "ttfb_ms")]
+    pub headers_ms: Option<f64>,
+    /// Time until the full response was parsed
+    pub latency_ms: f64,
+    #[serde(default)]
+    pub output_tokens: Option<u32>,
+    #[serde(default)]
+    pub input_tokens: Option<u32>,
+    /// Input tokens read from a context cache
+    #[serde(default)]
+    pub cached_tokens: Option<u32>,
+    /// Time the request waited in the provider's queue, if it reports it
+    #[serde(default)]
+    pub queue_ms: Option<f64>,
+    /// Time the provider spent generating the output, if it reports it
+    #[serde(default)]
+    pub generation_ms: Option<f64>,
+    /// Estimated from the model's prices, if they are set
+    #[serde(default)]
+    pub cost: Option<f64>,
+    /// Model that produced the reply
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl ResponseMetrics {
+    pub fn new(timing: ResponseTiming, usage: Option<&TokenUsage>) -> Self {
+        let millis = |seconds: Option<f64>| seconds.map(|s| s * 1000.0);
+        Self {
+            headers_ms: Some(timing.headers_ms),
+            latency_ms: timing.latency_ms,
+            output_tokens: usage.map(|u| u.completion_tokens),
+            input_tokens: usage.map(|u| u.prompt_tokens),
+            cached_tokens: usage.map(|u| u.cached_tokens),
+            queue_ms: millis(usage.and_then(|u| u.queue_time)),
+            generation_ms: millis(usage.and_then(|u| u.completion_time)),
+            cost: None,
+            model: None,
+        }
+    }
+
+    /// Output tokens over the whole request, network and queueing included,
+    /// as the user experienced it
+    pub fn end_to_end_tokens_per_second(&self) -> Option<f64> {
+        let tokens = self.output_tokens?;
+        (self.latency_ms > 0.0).then(|| tokens as f64 / (self.latency_ms / 1000.0))
+    }
+
+    /// Output tokens over the provider's own generation time, without
+    /// network and queueing
+    pub fn generation_tokens_per_second(&self) -> Option<f64> {
+        let tokens = self.output_tokens?;
+        let generation_ms = self.generation_ms.filter(|ms| *ms > 0.0)?;
+        Some(tokens as f64 / (generation_ms / 1000.0))
+    }
+}
+
+/// Averages over all assistant messages in a session that carry metrics
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionStats {
+    pub requests: usize,
+    pub avg_headers_ms: Option<f64>,
+    pub avg_latency_ms: f64,
+    pub avg_end_to_end_tokens_per_second: Option<f64>,
+    pub avg_generation_tokens_per_second: Option<f64>,
+    pub avg_queue_ms: Option<f64>,
+    pub total_output_tokens: u32,
+    pub total_input_tokens: u32,
+    pub total_cached_tokens: u32,
+    /// None when no reply has a cost
+    pub total_cost: Option<f64>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum MessageRole {
     System,
+    /// Instructions from the app developer, sent as OpenAI's developer role
+    Developer,
     User,
     Assistant,
     Function,
@@ -84,6 +479,99 @@ pub struct ChatSession {
     pub created_at: f64,
     pub updated_at: f64,
     pub pinned: bool,
+    /// Ids of messages pinned as bookmarks
+    #[serde(default)]
+    pub pinned_messages: Vec<String>,
+    /// Read-only: nothing can be sent, changed or deleted until unlocked
+    #[serde(default)]
+    pub locked: bool,
+    /// Labels for finding and grouping sessions
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Kept out of the main session list
+    #[serde(default)]
+    pub archived: bool,
+    /// Task list maintained through the TodoWrite tool
+    #[serde(default)]
+    pub todos: Vec<TodoItem>,
+    /// Usage before this time no longer counts against the session budget;
+    /// moved forward when the user lets a paused agent continue
+    #[serde(default)]
+    pub budget_since: f64,
+    /// Set for two-model dialogue sessions
+    #[serde(default)]
+    pub dialogue: Option<DialogueConfig>,
+    /// Name of the persona the session was started from
+    #[serde(default)]
+    pub persona: Option<String>,
+    /// Never written to storage; gone when the page is closed or reloaded
+    #[serde(default)]
+    pub incognito: bool,
+    /// Pages of older messages still in storage, before `messages`; see
+    /// `session_pages`
+    #[serde(default)]
+    pub older_pages: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoStatus {
+    #[default]
+    Pending,
+    InProgress,
+    Completed,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TodoItem {
+    pub id: String,
+    pub content: String,
+    #[serde(default)]
+    pub status: TodoStatus,
+    #[serde(default)]
+    pub priority: String,
+}
+
+impl ChatSession {
+    /// Aggregate response metrics, or None when no message has any
+    pub fn stats(&self) -> Option<SessionStats> {
+        let metrics: Vec<&ResponseMetrics> = self
+            .messages
+            .iter()
+            .filter_map(|m| m.metrics.as_ref())
+            .collect();
+        if metrics.is_empty() {
+            return None;
+        }
+
+        let average = |values: Vec<f64>| {
+            (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+        };
+
+        Some(SessionStats {
+            requests: metrics.len(),
+            avg_headers_ms: average(metrics.iter().filter_map(|m| m.headers_ms).collect()),
+            avg_latency_ms: average(metrics.iter().map(|m| m.latency_ms).collect())
+                .unwrap_or_default(),
+            avg_end_to_end_tokens_per_second: average(
+                metrics.iter().filter_map(|m| m.end_to_end_tokens_per_second()).collect(),
+            ),
+            avg_generation_tokens_per_second: average(
+                metrics
+                    .iter()
+                    .filter_map(|m| m.generation_tokens_per_second())
+                    .collect(),
+            ),
+            avg_queue_ms: average(metrics.iter().filter_map(|m| m.queue_ms).collect()),
+            total_output_tokens: metrics.iter().filter_map(|m| m.output_tokens).sum(),
+            total_input_tokens: metrics.iter().filter_map(|m| m.input_tokens).sum(),
+            total_cached_tokens: metrics.iter().filter_map(|m| m.cached_tokens).sum(),
+            total_cost: metrics
+                .iter()
+                .filter_map(|m| m.cost)
+                .reduce(|total, cost| total + cost),
+        })
+    }
 }
 
 impl Default for ApiConfig {
@@ -93,6 +581,7 @@ impl Default for ApiConfig {
                 api_key: String::new(),
                 model: "gemini-2.5-flash-lite-preview-06-17".to_string(),
                 base_url: "https://generativelanguage.googleapis.com/v1beta/models".to_string(),
+                auth: Default::default(),
             },
             openai: OpenAIConfig {
                 base_url: "https://api.openai.com/v1".to_string(),
@@ -104,6 +593,17 @@ impl Default for ApiConfig {
                 temperature: 0.7,
                 max_tokens: 2048,
                 retry_delay: 2000,
+                cors_proxy: None,
+                json_repair_retries: None,
+                context_cache_ttl_minutes: None,
+                stop_sequences: Vec::new(),
+                logit_bias: Vec::new(),
+                tool_choice: ToolChoice::Auto,
+                allowed_functions: Vec::new(),
+                native_tools: NativeToolSettings::default(),
+                audio_output: AudioOutputSettings::default(),
+                candidate_count: None,
+                reasoning: ReasoningVisibility::Default,
             },
             system_prompt: "You are a helpful assistant that responds in markdown format. Always be concise and to the point.".to_string(),
             function_tools: Self::get_default_function_tools(),
@@ -120,7 +620,7 @@ impl ApiConfig {
             // Built-in Fetch Tool
             FunctionTool {
                 name: "fetch".to_string(),
-                description: "A tool for making HTTP requests. Supports GET, POST, PUT, DELETE, and other HTTP methods with custom headers and payload.".to_string(),
+                description: "A tool for making HTTP requests. Supports GET, POST, PUT, DELETE, and other HTTP methods with custom headers and payload. Web pages come back as markdown of their main content, JSON parsed.".to_string(),
                 parameters: serde_json::json!({
                     "type": "object",
                     "properties": {
@@ -139,14 +639,205 @@ impl ApiConfig {
                         "payload": {
                             "type": "string",
                             "description": "Request body payload (for POST, PUT, PATCH methods)"
+                        },
+                        "format": {
+                            "type": "string",
+                            "enum": ["auto", "text", "json", "markdown"],
+                            "description": "How to return the body: auto picks by content type, markdown converts an HTML page's main content, text is the raw body"
+                        },
+                        "max_bytes": {
+                            "type": "integer",
+                            "description": "Cut the body to this many bytes; the settings' limit applies when it is lower"
                         }
                     },
                     "required": ["url"]
                 }),
-                mock_response: r#"{"status": 200, "headers": {"content-type": "application/json"}, "body": "{\"message\": \"success\"}"}"#.to_string(),
+                mock_response: r#"{"status": 200, "headers": {"content-type": "application/json"}, "format": "json", "body": {"message": "success"}, "truncated": false, "total_bytes": 22}"#.to_string(),
                 enabled: true,
                 category: "HTTP".to_string(),
                 is_builtin: true,
+                requires: None,
+            },
+
+            // Built-in Knowledge Base Search Tool
+            FunctionTool {
+                name: "search_documents".to_string(),
+                description: "Search the user's uploaded documents for passages relevant to a query. Returns the most similar text chunks with their document name and similarity score. Use it whenever the answer may be in the user's documents.".to_string(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "What to look for, phrased as a question or description of the needed information"
+                        },
+                        "top_k": {
+                            "type": "integer",
+                            "description": "Number of chunks to return (1-10, default 4)"
+                        }
+                    },
+                    "required": ["query"]
+                }),
+                mock_response: r#"{"query": "example", "results": [{"document": "notes.md", "chunk": 0, "score": 0.82, "text": "Example passage"}]}"#.to_string(),
+                enabled: false,
+                category: "Knowledge".to_string(),
+                is_builtin: true,
+                requires: None,
+            },
+
+            // Built-in Code Execution Tools
+            FunctionTool {
+                name: "run_python".to_string(),
+                description: "Run Python 3 code in a sandbox (Pyodide) and get its printed output, the value of its last expression, or the error with its traceback. Use it to calculate, analyze data or check code instead of working it out by hand. The standard library and common packages like numpy and pandas can be imported; there is no network or file access, and runs are stopped after a time limit.".to_string(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "code": {
+                            "type": "string",
+                            "description": "The Python code to run. Print what you want to see, or end with an expression."
+                        }
+                    },
+                    "required": ["code"]
+                }),
+                mock_response: r#"{"ok": true, "stdout": "3.141592653589793", "stderr": "", "duration_ms": 4}"#.to_string(),
+                enabled: false,
+                category: "Code".to_string(),
+                is_builtin: true,
+                requires: None,
+            },
+            FunctionTool {
+                name: "run_js".to_string(),
+                description: "Run JavaScript in a sandboxed Web Worker and get what it logs with console.log, the value it returns, or the error it throws. The code is the body of an async function, so use `return` for a result and `await` freely. There is no DOM, network or storage access, and runs are stopped after a time limit.".to_string(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "code": {
+                            "type": "string",
+                            "description": "The JavaScript to run, as the body of an async function"
+                        }
+                    },
+                    "required": ["code"]
+                }),
+                mock_response: r#"{"ok": true, "stdout": "", "stderr": "", "result": "55", "duration_ms": 1}"#.to_string(),
+                enabled: false,
+                category: "Code".to_string(),
+                is_builtin: true,
+                requires: None,
+            },
+
+            // Built-in Session Database Tool
+            FunctionTool {
+                name: "run_sql".to_string(),
+                description: "Run SQLite SQL on this conversation's database, which holds the tables the user imported from CSV files and any you create. Returns each statement's columns and rows (up to 200 per statement) and how many rows were changed. List the tables with `SELECT name, sql FROM sqlite_master WHERE type = 'table'` before querying them.".to_string(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "One or more SQLite statements separated by semicolons"
+                        }
+                    },
+                    "required": ["query"]
+                }),
+                mock_response: r#"{"results": [{"columns": ["region", "total"], "rows": [["North", 120], ["South", 95]], "total_rows": 2}], "changes": 0}"#.to_string(),
+                enabled: false,
+                category: "Data".to_string(),
+                is_builtin: true,
+                requires: None,
+            },
+
+            // Built-in Chart Tool
+            FunctionTool {
+                name: "render_chart".to_string(),
+                description: "Show the user a bar, line, area or pie chart, drawn in the chat under this call. Use it to visualize numbers you got from other tools. Give one label per category and one value per label in each series; null leaves a gap. A pie chart takes a single series of non-negative values.".to_string(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "type": {
+                            "type": "string",
+                            "enum": ["bar", "line", "area", "pie"],
+                            "description": "The kind of chart"
+                        },
+                        "title": {
+                            "type": "string",
+                            "description": "Title shown above the chart"
+                        },
+                        "labels": {
+                            "type": "array",
+                            "items": {"type": "string"},
+                            "description": "Category labels along the x axis, or the pie's slices"
+                        },
+                        "series": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "name": {"type": "string", "description": "Shown in the legend"},
+                                    "data": {"type": "array", "items": {"type": ["number", "null"]}}
+                                },
+                                "required": ["data"]
+                            },
+                            "description": "Up to 12 series of values, one value per label"
+                        },
+                        "x_label": {
+                            "type": "string",
+                            "description": "Caption under the x axis"
+                        },
+                        "y_label": {
+                            "type": "string",
+                            "description": "Caption beside the value axis"
+                        }
+                    },
+                    "required": ["type", "labels", "series"]
+                }),
+                mock_response: r#"{"result": "The chart is shown to the user below this tool call", "chart": {"type": "bar", "labels": ["North", "South"], "series": [{"name": "total", "data": [120, 95]}]}}"#.to_string(),
+                enabled: false,
+                category: "Data".to_string(),
+                is_builtin: true,
+                requires: None,
+            },
+
+            // Built-in Handoff Tools
+            FunctionTool {
+                name: "copy_to_clipboard".to_string(),
+                description: "Put text on the user's clipboard, e.g. a finished email, command or snippet they asked for. The user is asked to allow each call.".to_string(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "content": {
+                            "type": "string",
+                            "description": "The exact text to copy"
+                        }
+                    },
+                    "required": ["content"]
+                }),
+                mock_response: r#"{"copied": true, "characters": 42}"#.to_string(),
+                enabled: false,
+                category: "Output".to_string(),
+                is_builtin: true,
+                requires: None,
+            },
+            FunctionTool {
+                name: "save_file".to_string(),
+                description: "Give the user a text file, such as a report, CSV export or source file, as a download. The user is asked to allow each call. The file type follows the name's extension.".to_string(),
+                parameters: serde_json::json!({
+                    "type": "object",
+                    "properties": {
+                        "file_name": {
+                            "type": "string",
+                            "description": "Name to save the file as, with an extension, e.g. report.md"
+                        },
+                        "content": {
+                            "type": "string",
+                            "description": "The file's full text"
+                        }
+                    },
+                    "required": ["file_name", "content"]
+                }),
+                mock_response: r#"{"saved": true, "file_name": "report.md", "bytes": 1024}"#.to_string(),
+                enabled: false,
+                category: "Output".to_string(),
+                is_builtin: true,
+                requires: None,
             },
 
             // Task Agent Tool
@@ -177,6 +868,7 @@ impl ApiConfig {
                 enabled: true,
                 category: "Agent".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // Bash Tool
@@ -207,6 +899,7 @@ impl ApiConfig {
                 enabled: true,
                 category: "System".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // Glob Tool
@@ -233,6 +926,7 @@ impl ApiConfig {
                 enabled: true,
                 category: "File System".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // Grep Tool
@@ -300,6 +994,7 @@ impl ApiConfig {
                 enabled: true,
                 category: "Search".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // LS Tool
@@ -329,6 +1024,7 @@ impl ApiConfig {
                 enabled: true,
                 category: "File System".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // Read Tool
@@ -359,6 +1055,7 @@ impl ApiConfig {
                 enabled: true,
                 category: "File System".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // Edit Tool
@@ -394,6 +1091,10 @@ impl ApiConfig {
                 enabled: true,
                 category: "File System".to_string(),
                 is_builtin: false,
+                requires: Some(vec![ToolDependency {
+                    tool: "Read".to_string(),
+                    same_argument: Some("file_path".to_string()),
+                }]),
             },
 
             // Write Tool
@@ -420,6 +1121,7 @@ impl ApiConfig {
                 enabled: true,
                 category: "File System".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // MultiEdit Tool
@@ -467,6 +1169,10 @@ impl ApiConfig {
                 enabled: true,
                 category: "File System".to_string(),
                 is_builtin: false,
+                requires: Some(vec![ToolDependency {
+                    tool: "Read".to_string(),
+                    same_argument: Some("file_path".to_string()),
+                }]),
             },
 
             // ExitPlanMode Tool
@@ -489,6 +1195,7 @@ impl ApiConfig {
                 enabled: false,
                 category: "Planning".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // TodoWrite Tool
@@ -533,6 +1240,7 @@ impl ApiConfig {
                 enabled: false,
                 category: "Planning".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // WebFetch Tool
@@ -556,10 +1264,11 @@ impl ApiConfig {
                     "additionalProperties": false,
                     "$schema": "http://json-schema.org/draft-07/schema#"
                 }),
-                mock_response: "{\"title\": \"Example Page\", \"content\": \"# Example\\n\\nThis is example content from the webpage.\", \"summary\": \"A webpage about examples\"}".to_string(),
+                mock_response: r#"{"url": "https://example.com", "title": "Example Domain", "result": "The page says the domain is reserved for use in documentation examples."}"#.to_string(),
                 enabled: true,
                 category: "Web".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // WebSearch Tool
@@ -597,6 +1306,7 @@ impl ApiConfig {
                 enabled: true,
                 category: "Web".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // NotebookEdit Tool
@@ -637,6 +1347,7 @@ impl ApiConfig {
                 enabled: false,
                 category: "IDE".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // Weather Tool (Enhanced)
@@ -664,6 +1375,7 @@ impl ApiConfig {
                 enabled: true,
                 category: "Weather".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // IDE Diagnostics Tool
@@ -685,6 +1397,7 @@ impl ApiConfig {
                 enabled: false,
                 category: "IDE".to_string(),
                 is_builtin: false,
+                requires: None,
             },
 
             // Execute Code Tool
@@ -707,6 +1420,7 @@ impl ApiConfig {
                 enabled: false,
                 category: "IDE".to_string(),
                 is_builtin: false,
+                requires: None,
             },
         ]
     }
@@ -789,3 +1503,154 @@ impl ApiConfig {
         self.mcp_config = config;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_choices_round_trip_through_the_settings_select() {
+        for choice in ToolChoice::ALL {
+            assert_eq!(ToolChoice::parse(choice.openai_value()), Some(choice));
+        }
+        assert_eq!(ToolChoice::parse("ANY"), None);
+        assert_eq!(ToolChoice::Required.gemini_mode(), "ANY");
+    }
+
+    #[test]
+    fn required_tool_choice_holds_only_for_the_first_request() {
+        assert_eq!(ToolChoice::Required.for_request(true), ToolChoice::Required);
+        assert_eq!(ToolChoice::Required.for_request(false), ToolChoice::Auto);
+        assert_eq!(ToolChoice::None.for_request(false), ToolChoice::None);
+    }
+
+    #[test]
+    fn allowed_functions_toggle() {
+        let mut settings = ApiConfig::default().shared_settings;
+        settings.toggle_allowed_function("Read");
+        settings.toggle_allowed_function("Grep");
+        assert_eq!(settings.allowed_functions, vec!["Read", "Grep"]);
+        settings.toggle_allowed_function("Read");
+        assert_eq!(settings.allowed_functions, vec!["Grep"]);
+    }
+
+    fn session_with(messages: Vec<Message>) -> ChatSession {
+        ChatSession {
+            id: String::new(),
+            title: String::new(),
+            messages,
+            created_at: 0.0,
+            updated_at: 0.0,
+            pinned: false,
+            pinned_messages: Vec::new(),
+            locked: false,
+            tags: Vec::new(),
+            archived: false,
+            todos: Vec::new(),
+            budget_since: 0.0,
+            dialogue: None,
+            persona: None,
+            incognito: false,
+            older_pages: 0,
+        }
+    }
+
+    fn timed_reply(id: &str, headers_ms: f64, latency_ms: f64, output_tokens: u32) -> Message {
+        let mut message = test_message(id, MessageRole::Assistant, "reply");
+        let usage = TokenUsage { completion_tokens: output_tokens, ..Default::default() };
+        message.metrics = Some(ResponseMetrics::new(ResponseTiming { headers_ms, latency_ms }, Some(&usage)));
+        message
+    }
+
+    #[test]
+    fn reply_metrics_give_end_to_end_throughput() {
+        let metrics = timed_reply("a", 300.0, 2000.0, 100).metrics.unwrap();
+        assert_eq!(metrics.headers_ms, Some(300.0));
+        assert_eq!(metrics.end_to_end_tokens_per_second(), Some(50.0));
+
+        let untimed = ResponseMetrics { output_tokens: Some(10), ..Default::default() };
+        assert_eq!(untimed.end_to_end_tokens_per_second(), None);
+        assert_eq!(ResponseMetrics::new(ResponseTiming::default(), None).output_tokens, None);
+    }
+
+    #[test]
+    fn session_stats_average_the_timed_replies() {
+        let session = session_with(vec![
+            test_message("q", MessageRole::User, "question"),
+            timed_reply("a", 200.0, 1000.0, 100),
+            timed_reply("b", 400.0, 4000.0, 200),
+        ]);
+        let stats = session.stats().unwrap();
+        assert_eq!(stats.requests, 2);
+        assert_eq!(stats.avg_headers_ms, Some(300.0));
+        assert_eq!(stats.avg_latency_ms, 2500.0);
+        assert_eq!(stats.avg_end_to_end_tokens_per_second, Some(75.0));
+        assert_eq!(stats.total_output_tokens, 300);
+
+        assert!(session_with(vec![test_message("q", MessageRole::User, "question")]).stats().is_none());
+    }
+
+    #[test]
+    fn only_replies_stopped_by_the_token_limit_offer_continue() {
+        let mut reply = test_message("a", MessageRole::Assistant, "cut");
+        assert!(!reply.hit_token_limit());
+        for reason in ["length", "max_tokens", "MAX_TOKENS"] {
+            reply.finish_reason = Some(reason.to_string());
+            assert!(reply.hit_token_limit(), "{}", reason);
+        }
+        for reason in ["stop", "STOP", "tool_calls", "SAFETY"] {
+            reply.finish_reason = Some(reason.to_string());
+            assert!(!reply.hit_token_limit(), "{}", reason);
+        }
+    }
+
+    #[test]
+    fn provider_timings_give_queue_time_and_generation_speed() {
+        let usage = TokenUsage {
+            completion_tokens: 250,
+            queue_time: Some(0.02),
+            completion_time: Some(0.5),
+            ..Default::default()
+        };
+        let timing = ResponseTiming { headers_ms: 100.0, latency_ms: 1000.0 };
+        let metrics = ResponseMetrics::new(timing, Some(&usage));
+        assert_eq!(metrics.queue_ms, Some(20.0));
+        assert_eq!(metrics.generation_tokens_per_second(), Some(500.0));
+        assert_eq!(metrics.end_to_end_tokens_per_second(), Some(250.0));
+
+        let usage = TokenUsage { completion_tokens: 250, ..Default::default() };
+        let unreported = ResponseMetrics::new(timing, Some(&usage));
+        assert_eq!(unreported.queue_ms, None);
+        assert_eq!(unreported.generation_tokens_per_second(), None);
+    }
+
+    #[test]
+    fn reasoning_visibility_controls_the_request_flag_and_the_reply() {
+        for visibility in ReasoningVisibility::ALL {
+            assert_eq!(ReasoningVisibility::parse(visibility.label()), Some(visibility));
+        }
+        assert_eq!(ReasoningVisibility::Default.include_reasoning(), None);
+        assert_eq!(ReasoningVisibility::Visible.include_reasoning(), Some(true));
+        assert_eq!(ReasoningVisibility::Hidden.include_reasoning(), Some(false));
+
+        let thinking = || Some("Let me think.".to_string());
+        assert_eq!(ReasoningVisibility::Default.keep(thinking()), thinking());
+        assert_eq!(ReasoningVisibility::Visible.keep(thinking()), thinking());
+        assert_eq!(ReasoningVisibility::Hidden.keep(thinking()), None);
+        assert_eq!(ReasoningVisibility::Visible.keep(Some(" \n".to_string())), None);
+    }
+
+    #[test]
+    fn sessions_stored_before_incognito_mode_are_regular() {
+        let stored = serde_json::json!({
+            "id": "s1",
+            "title": "Old",
+            "messages": [],
+            "created_at": 1.0,
+            "updated_at": 2.0,
+            "pinned": false
+        });
+        let session: ChatSession = serde_json::from_value(stored).unwrap();
+        assert!(!session.incognito);
+    }
+}
@@ -0,0 +1,174 @@
+// WebFetch tool
+// Fetches a page through the CORS proxy setting, turns it into markdown and has
+// a model (a cheap one picked in settings, or the session's) answer the call's
+// prompt about it. The calling model gets that answer instead of the whole
+// page, so reading documentation doesn't fill its context window.
+
+use crate::llm_playground::{
+    builtin_tools,
+    fetch_tool::{FetchFormat, FetchToolSettings},
+    flexible_client::FlexibleLLMClient,
+    provider_config::FlexibleApiConfig,
+    Message, MessageRole,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+pub const WEB_FETCH_TOOL_NAME: &str = "WebFetch";
+
+pub const META_PROMPT: &str = "You read a web page for another AI assistant. Answer the \
+assistant's prompt using only the page content given, quoting it where exact wording matters. \
+If the page doesn't contain the answer, say so. Be concise.";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebFetchSettings {
+    /// "provider,model" that reads the page; empty uses the session's model
+    pub model: String,
+    /// Bytes of the page's markdown given to the model; 0 for no limit
+    pub max_bytes: usize,
+}
+
+impl Default for WebFetchSettings {
+    fn default() -> Self {
+        Self {
+            model: String::new(),
+            max_bytes: 100_000,
+        }
+    }
+}
+
+impl WebFetchSettings {
+    /// The config the page is read with
+    pub fn config_for(&self, session_config: &FlexibleApiConfig) -> FlexibleApiConfig {
+        let mut config = session_config.clone();
+        if let Some((provider, model)) = self.model.split_once(',') {
+            config.set_session_provider(provider, model);
+            config.apply_model_defaults(provider, model);
+        }
+        config.system_prompt = META_PROMPT.to_string();
+        config.active_structured_output = None;
+        config.shared_settings.candidate_count = None;
+        for tool in &mut config.function_tools {
+            tool.enabled = false;
+        }
+        config
+    }
+}
+
+/// The single user message with the page and the prompt to answer
+pub fn build_request(url: &str, page: &Value, prompt: &str) -> String {
+    let title = page
+        .get("title")
+        .and_then(Value::as_str)
+        .map(|title| format!("Title: {}\n", title))
+        .unwrap_or_default();
+    let body = match page.get("body") {
+        Some(Value::String(body)) => body.clone(),
+        Some(body) => body.to_string(),
+        None => String::new(),
+    };
+    let truncated = if page.get("truncated").and_then(Value::as_bool) == Some(true) {
+        "\n\n[The page was cut short here]"
+    } else {
+        ""
+    };
+    format!(
+        "Page: {}\n{}\n{}{}\n\nPrompt: {}",
+        url,
+        title,
+        body.trim(),
+        truncated,
+        prompt.trim()
+    )
+}
+
+/// Fetch the call's URL and answer its prompt about the page
+pub async fn run(config: &FlexibleApiConfig, arguments: &Value) -> Result<Value, String> {
+    let url = arguments
+        .get("url")
+        .and_then(Value::as_str)
+        .ok_or("Missing required parameter: url")?;
+    let prompt = arguments
+        .get("prompt")
+        .and_then(Value::as_str)
+        .ok_or("Missing required parameter: prompt")?;
+
+    let fetch_settings = FetchToolSettings {
+        format: FetchFormat::Auto,
+        max_bytes: config.web_fetch.max_bytes,
+    };
+    let page = builtin_tools::execute_builtin_tool(
+        "fetch",
+        &json!({ "url": url }),
+        None,
+        config.shared_settings.cors_proxy(),
+        &fetch_settings,
+    )
+    .await?;
+    let status = page.get("status").and_then(Value::as_u64).unwrap_or(0);
+    if !(200..300).contains(&status) {
+        return Err(format!("Fetching {} failed with status {}", url, status));
+    }
+
+    let reader_config = config.web_fetch.config_for(config);
+    let message = Message {
+        id: format!("web_fetch_{}", js_sys::Date::now() as u64),
+        timestamp: js_sys::Date::now(),
+        ..Message::new(MessageRole::User, build_request(url, &page, prompt))
+    };
+    let response = FlexibleLLMClient::new().send_message(&[message], &reader_config).await?;
+    let answer = response.content.unwrap_or_default();
+    if answer.trim().is_empty() {
+        return Err("The model returned no answer about the page".to_string());
+    }
+
+    let (provider, model) = reader_config.get_current_provider_and_model();
+    let mut result = json!({
+        "url": url,
+        "model": format!("{},{}", provider, model),
+        "result": answer.trim(),
+    });
+    if let (Some(fields), Some(title)) = (result.as_object_mut(), page.get("title")) {
+        fields.insert("title".to_string(), title.clone());
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_holds_the_page_and_prompt() {
+        let page = json!({
+            "title": "Docs",
+            "body": "# Install\n\nRun `cargo add`.\n",
+            "truncated": true,
+        });
+        let request = build_request("https://example.com/docs", &page, " How is it installed? ");
+        assert_eq!(
+            request,
+            "Page: https://example.com/docs\nTitle: Docs\n\n# Install\n\nRun `cargo add`.\n\n\
+             [The page was cut short here]\n\nPrompt: How is it installed?"
+        );
+
+        let json_page = json!({ "body": { "name": "crate" }, "truncated": false });
+        let request = build_request("https://example.com/api", &json_page, "Name?");
+        assert_eq!(request, "Page: https://example.com/api\n\n{\"name\":\"crate\"}\n\nPrompt: Name?");
+    }
+
+    #[test]
+    fn reader_runs_without_tools() {
+        let mut config = FlexibleApiConfig::default();
+        config.function_tools.iter_mut().for_each(|tool| tool.enabled = true);
+        let settings = WebFetchSettings::default();
+        let reader = settings.config_for(&config);
+        assert_eq!(reader.system_prompt, META_PROMPT);
+        assert!(reader.get_enabled_function_tools().is_empty());
+        assert_eq!(
+            reader.get_current_provider_and_model(),
+            config.get_current_provider_and_model()
+        );
+    }
+}
@@ -0,0 +1,379 @@
+// WebSearch tool
+// Runs the tool's query against a search API picked in settings (Brave Search,
+// SerpAPI or Tavily, each with its own key) and returns the results as title,
+// URL and snippet. The call's allowed and blocked domains go to the backend as
+// far as it understands them, and results are filtered on them again after.
+// The chat shows the results of a WebSearch call as a list of sources.
+
+use crate::llm_playground::cors_proxy;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+pub const WEB_SEARCH_TOOL_NAME: &str = "WebSearch";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchBackend {
+    #[default]
+    Brave,
+    SerpApi,
+    Tavily,
+}
+
+impl SearchBackend {
+    pub const ALL: [SearchBackend; 3] = [SearchBackend::Brave, SearchBackend::SerpApi, SearchBackend::Tavily];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            SearchBackend::Brave => "brave",
+            SearchBackend::SerpApi => "serp_api",
+            SearchBackend::Tavily => "tavily",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SearchBackend::Brave => "Brave Search",
+            SearchBackend::SerpApi => "SerpAPI",
+            SearchBackend::Tavily => "Tavily",
+        }
+    }
+
+    pub fn parse(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|backend| backend.id() == id)
+    }
+
+    /// Where an API key for the backend is created
+    pub fn key_url(&self) -> &'static str {
+        match self {
+            SearchBackend::Brave => "https://api-dashboard.search.brave.com/app/keys",
+            SearchBackend::SerpApi => "https://serpapi.com/manage-api-key",
+            SearchBackend::Tavily => "https://app.tavily.com/home",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebSearchSettings {
+    pub backend: SearchBackend,
+    pub api_key: String,
+    /// Results asked for per search
+    pub max_results: usize,
+}
+
+impl Default for WebSearchSettings {
+    fn default() -> Self {
+        Self {
+            backend: SearchBackend::Brave,
+            api_key: String::new(),
+            max_results: 5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    #[serde(default)]
+    pub snippet: String,
+}
+
+/// The domains a call limits its results to or leaves out
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DomainFilter {
+    pub allowed: Vec<String>,
+    pub blocked: Vec<String>,
+}
+
+impl DomainFilter {
+    pub fn from_arguments(arguments: &Value) -> Self {
+        let domains = |key: &str| -> Vec<String> {
+            arguments
+                .get(key)
+                .and_then(Value::as_array)
+                .map(|domains| {
+                    domains
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .map(normalize_domain)
+                        .filter(|domain| !domain.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Self {
+            allowed: domains("allowed_domains"),
+            blocked: domains("blocked_domains"),
+        }
+    }
+
+    /// Whether a result's URL passes the filter; subdomains count as their domain
+    pub fn allows(&self, url: &str) -> bool {
+        let Some(host) = host(url) else {
+            return false;
+        };
+        let matches = |domain: &String| host == *domain || host.ends_with(&format!(".{}", domain));
+        (self.allowed.is_empty() || self.allowed.iter().any(matches)) && !self.blocked.iter().any(matches)
+    }
+
+    /// `site:` operators for backends that take them in the query
+    fn query_operators(&self) -> String {
+        let allowed: Vec<String> = self.allowed.iter().map(|domain| format!("site:{}", domain)).collect();
+        let blocked = self.blocked.iter().map(|domain| format!("-site:{}", domain));
+        let mut operators = Vec::new();
+        if !allowed.is_empty() {
+            operators.push(format!("({})", allowed.join(" OR ")));
+        }
+        operators.extend(blocked);
+        operators.join(" ")
+    }
+}
+
+/// "https://www.Example.com/path" or "example.com." as "example.com"
+fn normalize_domain(domain: &str) -> String {
+    let domain = domain.trim();
+    let domain = domain.split_once("://").map_or(domain, |(_, rest)| rest);
+    let domain = domain.split('/').next().unwrap_or_default();
+    domain.trim_start_matches("www.").trim_end_matches('.').to_lowercase()
+}
+
+fn host(url: &str) -> Option<String> {
+    let (_, rest) = url.split_once("://")?;
+    let host = rest.split(['/', '?', '#']).next()?;
+    let host = host.rsplit('@').next()?.split(':').next()?;
+    Some(normalize_domain(host)).filter(|host| !host.is_empty())
+}
+
+/// An HTTP request to a backend
+#[derive(Debug, PartialEq)]
+pub struct SearchRequest {
+    pub url: String,
+    pub headers: Vec<(&'static str, String)>,
+    /// JSON body for a POST; GET when None
+    pub body: Option<Value>,
+}
+
+/// The request searching `query` with these settings
+pub fn build_request(settings: &WebSearchSettings, query: &str, filter: &DomainFilter) -> SearchRequest {
+    let count = settings.max_results.clamp(1, 20);
+    let with_operators = match filter.query_operators() {
+        operators if operators.is_empty() => query.trim().to_string(),
+        operators => format!("{} {}", query.trim(), operators),
+    };
+    match settings.backend {
+        SearchBackend::Brave => SearchRequest {
+            url: format!(
+                "https://api.search.brave.com/res/v1/web/search?q={}&count={}",
+                cors_proxy::encode_uri_component(&with_operators),
+                count
+            ),
+            headers: vec![
+                ("Accept", "application/json".to_string()),
+                ("X-Subscription-Token", settings.api_key.clone()),
+            ],
+            body: None,
+        },
+        SearchBackend::SerpApi => SearchRequest {
+            url: format!(
+                "https://serpapi.com/search.json?engine=google&q={}&num={}&api_key={}",
+                cors_proxy::encode_uri_component(&with_operators),
+                count,
+                cors_proxy::encode_uri_component(&settings.api_key)
+            ),
+            headers: Vec::new(),
+            body: None,
+        },
+        // Tavily takes the domains as fields
+        SearchBackend::Tavily => SearchRequest {
+            url: "https://api.tavily.com/search".to_string(),
+            headers: vec![
+                ("Content-Type", "application/json".to_string()),
+                ("Authorization", format!("Bearer {}", settings.api_key)),
+            ],
+            body: Some(json!({
+                "query": query.trim(),
+                "max_results": count,
+                "include_domains": filter.allowed,
+                "exclude_domains": filter.blocked,
+            })),
+        },
+    }
+}
+
+/// The results in a backend's response body
+pub fn parse_response(backend: SearchBackend, body: &Value) -> Vec<SearchResult> {
+    let (list, url_key, snippet_key) = match backend {
+        SearchBackend::Brave => (body.pointer("/web/results"), "url", "description"),
+        SearchBackend::SerpApi => (body.get("organic_results"), "link", "snippet"),
+        SearchBackend::Tavily => (body.get("results"), "url", "content"),
+    };
+    list.and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| {
+                    let url = item.get(url_key)?.as_str()?.to_string();
+                    let text = |key: &str| item.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+                    Some(SearchResult {
+                        title: text("title"),
+                        snippet: strip_tags(&text(snippet_key)),
+                        url,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Brave marks matched words in snippets with <strong>
+fn strip_tags(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => plain.push(c),
+            _ => {}
+        }
+    }
+    plain
+}
+
+/// The results recorded on a WebSearch function response, for the source list
+pub fn results_from_function_response(function_response: &Value) -> Option<Vec<SearchResult>> {
+    if function_response.get("name")?.as_str()? != WEB_SEARCH_TOOL_NAME {
+        return None;
+    }
+    let results = function_response.pointer("/response/results")?.clone();
+    serde_json::from_value(results).ok()
+}
+
+/// Search the call's query on the configured backend
+pub async fn run(settings: &WebSearchSettings, proxy: Option<&str>, arguments: &Value) -> Result<Value, String> {
+    let query = arguments
+        .get("query")
+        .and_then(Value::as_str)
+        .filter(|query| !query.trim().is_empty())
+        .ok_or("Missing required parameter: query")?;
+    if settings.api_key.trim().is_empty() {
+        return Err(format!(
+            "No {} API key is set; add one under WebSearch Tool in settings",
+            settings.backend.label()
+        ));
+    }
+
+    let filter = DomainFilter::from_arguments(arguments);
+    let request = build_request(settings, query, &filter);
+    let builder = match &request.body {
+        Some(_) => cors_proxy::post(&request.url, proxy),
+        None => cors_proxy::get(&request.url, proxy),
+    };
+    let builder = request
+        .headers
+        .iter()
+        .fold(builder, |builder, (key, value)| builder.header(key, value));
+    let response = match &request.body {
+        Some(body) => builder
+            .json(body)
+            .map_err(|e| format!("Failed to create request: {}", e))?
+            .send()
+            .await,
+        None => builder.send().await,
+    }
+    .map_err(|e| format!("Network error: {}", e))?;
+    if !response.ok() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "{} returned status {}: {}",
+            settings.backend.label(),
+            response.status(),
+            text.chars().take(300).collect::<String>()
+        ));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid {} response: {}", settings.backend.label(), e))?;
+
+    let results: Vec<SearchResult> = parse_response(settings.backend, &body)
+        .into_iter()
+        .filter(|result| filter.allows(&result.url))
+        .collect();
+    Ok(json!({
+        "query": query,
+        "backend": settings.backend.id(),
+        "total": results.len(),
+        "results": results,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn domain_filter_matches_subdomains() {
+        let filter = DomainFilter::from_arguments(&json!({
+            "allowed_domains": ["https://www.rust-lang.org/", "docs.rs"],
+            "blocked_domains": ["blog.rust-lang.org"],
+        }));
+        assert_eq!(filter.allowed, ["rust-lang.org", "docs.rs"]);
+        assert!(filter.allows("https://doc.rust-lang.org/book/"));
+        assert!(filter.allows("https://docs.rs/serde"));
+        assert!(!filter.allows("https://blog.rust-lang.org/2024/"));
+        assert!(!filter.allows("https://notrust-lang.org/"));
+        assert!(!filter.allows("not a url"));
+        assert!(DomainFilter::default().allows("https://example.com"));
+    }
+
+    #[test]
+    fn requests_carry_the_domains() {
+        let filter = DomainFilter {
+            allowed: vec!["a.com".to_string(), "b.com".to_string()],
+            blocked: vec!["c.com".to_string()],
+        };
+        let mut settings = WebSearchSettings {
+            api_key: "key".to_string(),
+            ..Default::default()
+        };
+        let brave = build_request(&settings, "rust", &filter);
+        assert_eq!(
+            brave.url,
+            "https://api.search.brave.com/res/v1/web/search?q=rust%20(site%3Aa.com%20OR%20site%3Ab.com)%20-site%3Ac.com&count=5"
+        );
+        assert!(brave.body.is_none());
+
+        settings.backend = SearchBackend::Tavily;
+        let tavily = build_request(&settings, "rust", &filter);
+        assert_eq!(tavily.body.unwrap()["exclude_domains"], json!(["c.com"]));
+        assert!(tavily.headers.contains(&("Authorization", "Bearer key".to_string())));
+    }
+
+    #[test]
+    fn backend_responses_are_read() {
+        let brave = json!({"web": {"results": [
+            {"title": "Rust", "url": "https://rust-lang.org", "description": "A <strong>language</strong>"}
+        ]}});
+        let serp = json!({"organic_results": [
+            {"title": "Rust", "link": "https://rust-lang.org", "snippet": "A language"},
+            {"title": "No link"}
+        ]});
+        let expected = vec![SearchResult {
+            title: "Rust".to_string(),
+            url: "https://rust-lang.org".to_string(),
+            snippet: "A language".to_string(),
+        }];
+        assert_eq!(parse_response(SearchBackend::Brave, &brave), expected);
+        assert_eq!(parse_response(SearchBackend::SerpApi, &serp), expected);
+        assert!(parse_response(SearchBackend::Tavily, &serp).is_empty());
+
+        let function_response = json!({
+            "name": WEB_SEARCH_TOOL_NAME,
+            "response": {"results": expected},
+        });
+        assert_eq!(results_from_function_response(&function_response), Some(expected));
+    }
+}
@@ -0,0 +1,234 @@
+// Completion webhook
+// When a reply finishes or fails, its session, model and final message can be
+// posted to a URL set in settings, e.g. a spreadsheet's Apps Script endpoint.
+// The body is a template with {{placeholders}}; the default sends JSON.
+
+use crate::llm_playground::{
+    components::notification::{NotificationMessage, NotificationType},
+    provider_config::FlexibleApiConfig,
+    ChatSession,
+};
+use gloo_net::http::Request;
+use serde::{Deserialize, Serialize};
+use yew::Callback;
+
+pub const DEFAULT_TEMPLATE: &str = r#"{
+  "event": "{{event}}",
+  "session_id": "{{session_id}}",
+  "session_title": "{{session_title}}",
+  "provider": "{{provider}}",
+  "model": "{{model}}",
+  "message": "{{message}}",
+  "timestamp": "{{timestamp}}"
+}"#;
+
+/// Placeholders a template can use
+pub const PLACEHOLDERS: [&str; 7] = [
+    "event",
+    "session_id",
+    "session_title",
+    "provider",
+    "model",
+    "message",
+    "timestamp",
+];
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub enabled: bool,
+    pub url: String,
+    /// Request body; empty uses DEFAULT_TEMPLATE
+    pub template: String,
+    pub on_completion: bool,
+    pub on_failure: bool,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            template: String::new(),
+            on_completion: true,
+            on_failure: true,
+        }
+    }
+}
+
+impl WebhookSettings {
+    pub fn template(&self) -> &str {
+        if self.template.trim().is_empty() {
+            DEFAULT_TEMPLATE
+        } else {
+            &self.template
+        }
+    }
+
+    pub fn fires_on(&self, event: WebhookEvent) -> bool {
+        self.enabled
+            && !self.url.trim().is_empty()
+            && match event {
+                WebhookEvent::Completed => self.on_completion,
+                WebhookEvent::Failed => self.on_failure,
+            }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WebhookEvent {
+    Completed,
+    Failed,
+}
+
+impl WebhookEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WebhookEvent::Completed => "generation.completed",
+            WebhookEvent::Failed => "generation.failed",
+        }
+    }
+}
+
+/// What a webhook call reports
+#[derive(Clone, Debug, PartialEq)]
+pub struct WebhookPayload {
+    pub event: WebhookEvent,
+    pub session_id: String,
+    pub session_title: String,
+    pub provider: String,
+    pub model: String,
+    /// The final reply, or the error
+    pub message: String,
+    /// ISO 8601
+    pub timestamp: String,
+}
+
+impl WebhookPayload {
+    pub fn new(
+        event: WebhookEvent,
+        session: &ChatSession,
+        config: &FlexibleApiConfig,
+        message: &str,
+    ) -> Self {
+        let (provider, model) = config.get_current_provider_and_model();
+        Self {
+            event,
+            session_id: session.id.clone(),
+            session_title: session.title.clone(),
+            provider,
+            model,
+            message: message.to_string(),
+            timestamp: js_sys::Date::new_0().to_iso_string().into(),
+        }
+    }
+
+    fn value(&self, placeholder: &str) -> &str {
+        match placeholder {
+            "event" => self.event.name(),
+            "session_id" => &self.session_id,
+            "session_title" => &self.session_title,
+            "provider" => &self.provider,
+            "model" => &self.model,
+            "message" => &self.message,
+            "timestamp" => &self.timestamp,
+            _ => "",
+        }
+    }
+}
+
+/// Fill in the template's placeholders. Values are escaped as JSON string
+/// contents when the template is JSON, so they can go between quotes.
+pub fn render(template: &str, payload: &WebhookPayload) -> String {
+    let start = template.trim_start();
+    let is_json = start.starts_with('[') || (start.starts_with('{') && !start.starts_with("{{"));
+    PLACEHOLDERS.iter().fold(template.to_string(), |body, placeholder| {
+        let value = payload.value(placeholder);
+        let value = if is_json {
+            let quoted = serde_json::to_string(value).unwrap_or_default();
+            quoted[1..quoted.len() - 1].to_string()
+        } else {
+            value.to_string()
+        };
+        body.replace(&format!("{{{{{}}}}}", placeholder), &value)
+    })
+}
+
+/// Post the payload to the webhook
+pub async fn send(settings: &WebhookSettings, payload: &WebhookPayload) -> Result<(), String> {
+    let body = render(settings.template(), payload);
+    let content_type = if serde_json::from_str::<serde_json::Value>(&body).is_ok() {
+        "application/json"
+    } else {
+        "text/plain"
+    };
+    let response = Request::post(settings.url.trim())
+        .header("Content-Type", content_type)
+        .body(body)
+        .map_err(|e| format!("Failed to build the request: {:?}", e))?
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {:?}", e))?;
+    if response.ok() {
+        Ok(())
+    } else {
+        Err(format!("HTTP {}", response.status()))
+    }
+}
+
+/// Fire the webhook in the background if it is set up for the event; a
+/// failure is only reported as a warning
+pub fn fire(
+    config: &FlexibleApiConfig,
+    event: WebhookEvent,
+    session: &ChatSession,
+    message: &str,
+    on_notification: &Callback<NotificationMessage>,
+) {
+    if !config.webhook.fires_on(event) {
+        return;
+    }
+    let settings = config.webhook.clone();
+    let payload = WebhookPayload::new(event, session, config, message);
+    let on_notification = on_notification.clone();
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(error) = send(&settings, &payload).await {
+            on_notification.emit(NotificationMessage::new(
+                format!("Webhook failed: {}", error),
+                NotificationType::Warning,
+            ));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(message: &str) -> WebhookPayload {
+        WebhookPayload {
+            event: WebhookEvent::Completed,
+            session_id: "session_1".to_string(),
+            session_title: "Notes".to_string(),
+            provider: "OpenAI".to_string(),
+            model: "gpt-4o".to_string(),
+            message: message.to_string(),
+            timestamp: "2024-01-01T00:00:00.000Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn json_templates_get_escaped_values() {
+        let body = render(DEFAULT_TEMPLATE, &payload("He said \"hi\"\nthen left"));
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["message"], "He said \"hi\"\nthen left");
+        assert_eq!(parsed["event"], "generation.completed");
+        assert_eq!(parsed["model"], "gpt-4o");
+    }
+
+    #[test]
+    fn plain_templates_get_raw_values() {
+        let body = render("{{session_title}} via {{model}}: {{message}}", &payload("a \"b\""));
+        assert_eq!(body, "Notes via gpt-4o: a \"b\"");
+    }
+}
@@ -0,0 +1,176 @@
+// Word-level text diff
+// Used by the response diff viewer to compare two assistant messages.
+
+use serde::{Deserialize, Serialize};
+
+/// One run of the diff. Whitespace is kept in the runs so joining them
+/// reproduces the original texts.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DiffOp {
+    Equal(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Past this many token pairs the LCS table gets too large for the browser,
+/// so the differing middle section is reported as a whole replacement
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Diff two texts word by word
+pub fn diff_words(old: &str, new: &str) -> Vec<DiffOp> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+
+    // Common prefix and suffix don't need the LCS table
+    let prefix = old_tokens
+        .iter()
+        .zip(new_tokens.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_tokens[prefix..]
+        .iter()
+        .rev()
+        .zip(new_tokens[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_middle = &old_tokens[prefix..old_tokens.len() - suffix];
+    let new_middle = &new_tokens[prefix..new_tokens.len() - suffix];
+
+    let mut ops = Vec::new();
+    push_ops(&mut ops, &old_tokens[..prefix], DiffOp::Equal);
+    if old_middle.len() * new_middle.len() > MAX_LCS_CELLS {
+        push_ops(&mut ops, old_middle, DiffOp::Removed);
+        push_ops(&mut ops, new_middle, DiffOp::Added);
+    } else {
+        diff_lcs(&mut ops, old_middle, new_middle);
+    }
+    push_ops(
+        &mut ops,
+        &old_tokens[old_tokens.len() - suffix..],
+        DiffOp::Equal,
+    );
+    ops
+}
+
+/// Count the words (non-whitespace tokens) removed and added
+pub fn diff_stats(ops: &[DiffOp]) -> (usize, usize) {
+    ops.iter().fold((0, 0), |(removed, added), op| match op {
+        DiffOp::Removed(text) => (removed + text.split_whitespace().count(), added),
+        DiffOp::Added(text) => (removed, added + text.split_whitespace().count()),
+        DiffOp::Equal(_) => (removed, added),
+    })
+}
+
+/// Split into alternating word and whitespace tokens
+fn tokenize(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut in_whitespace = None;
+
+    for (i, ch) in text.char_indices() {
+        let is_whitespace = ch.is_whitespace();
+        if in_whitespace.is_some_and(|ws| ws != is_whitespace) {
+            tokens.push(&text[start..i]);
+            start = i;
+        }
+        in_whitespace = Some(is_whitespace);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+fn diff_lcs(ops: &mut Vec<DiffOp>, old: &[&str], new: &[&str]) {
+    let (n, m) = (old.len(), new.len());
+    // lengths[i][j] = LCS length of old[i..] and new[j..]
+    let mut lengths = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[at(i, j)] = if old[i] == new[j] {
+                lengths[at(i + 1, j + 1)] + 1
+            } else {
+                lengths[at(i + 1, j)].max(lengths[at(i, j + 1)])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            push_op(ops, DiffOp::Equal(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lengths[at(i, j + 1)] >= lengths[at(i + 1, j)]) {
+            push_op(ops, DiffOp::Added(new[j].to_string()));
+            j += 1;
+        } else {
+            push_op(ops, DiffOp::Removed(old[i].to_string()));
+            i += 1;
+        }
+    }
+}
+
+fn push_ops(ops: &mut Vec<DiffOp>, tokens: &[&str], make: fn(String) -> DiffOp) {
+    if !tokens.is_empty() {
+        push_op(ops, make(tokens.concat()));
+    }
+}
+
+/// Append an op, merging it into the previous one when they are the same kind
+fn push_op(ops: &mut Vec<DiffOp>, op: DiffOp) {
+    match (ops.last_mut(), op) {
+        (Some(DiffOp::Equal(last)), DiffOp::Equal(text))
+        | (Some(DiffOp::Removed(last)), DiffOp::Removed(text))
+        | (Some(DiffOp::Added(last)), DiffOp::Added(text)) => last.push_str(&text),
+        (_, op) => ops.push(op),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rebuild(ops: &[DiffOp]) -> (String, String) {
+        let mut old = String::new();
+        let mut new = String::new();
+        for op in ops {
+            match op {
+                DiffOp::Equal(text) => {
+                    old.push_str(text);
+                    new.push_str(text);
+                }
+                DiffOp::Removed(text) => old.push_str(text),
+                DiffOp::Added(text) => new.push_str(text),
+            }
+        }
+        (old, new)
+    }
+
+    #[test]
+    fn test_diff_words_replacement() {
+        let old = "The quick brown fox jumps";
+        let new = "The quick red fox leaps";
+        let ops = diff_words(old, new);
+
+        assert_eq!(rebuild(&ops), (old.to_string(), new.to_string()));
+        assert!(ops.contains(&DiffOp::Removed("brown".to_string())));
+        assert!(ops.contains(&DiffOp::Added("red".to_string())));
+        assert_eq!(diff_stats(&ops), (2, 2));
+    }
+
+    #[test]
+    fn test_diff_words_identical_and_empty() {
+        assert_eq!(
+            diff_words("same text", "same text"),
+            vec![DiffOp::Equal("same text".to_string())]
+        );
+        assert_eq!(
+            diff_words("", "new words"),
+            vec![DiffOp::Added("new words".to_string())]
+        );
+        assert!(diff_words("", "").is_empty());
+    }
+}
@@ -1,13 +1,27 @@
 use yew::prelude::*;
+use yew_router::prelude::*;
 
-mod llm_playground;
-use llm_playground::FlexibleLLMPlayground;
+use llm_playground_rs::llm_playground::{routes::Route, ConversationViewer, FlexibleLLMPlayground};
+
+fn switch(route: Route) -> Html {
+    match route {
+        // Shared conversations open in the read-only viewer, which needs no setup
+        Route::View => html! { <ConversationViewer /> },
+        Route::SharedView { payload } => html! { <ConversationViewer payload={Some(payload)} /> },
+        // The playground reads the rest of the route itself
+        _ => html! {
+            // <LLMPlayground />
+            <FlexibleLLMPlayground />
+        },
+    }
+}
 
 #[function_component(App)]
 fn app() -> Html {
     html! {
-        // <LLMPlayground />
-        <FlexibleLLMPlayground />
+        <HashRouter>
+            <Switch<Route> render={switch} />
+        </HashRouter>
     }
 }
 
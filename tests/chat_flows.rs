@@ -0,0 +1,163 @@
+// Full chat turns against a mocked `fetch`: the user asks, the model calls a
+// tool, the tool result goes back and the model answers, for both wire
+// formats. The recorded requests are what the request builders produced.
+#![cfg(target_arch = "wasm32")]
+
+mod common;
+
+use common::{config_for, message, session, MockFetch};
+use llm_playground_rs::llm_playground::{
+    builtin_tools, flexible_client::FlexibleLLMClient, FlexibleApiConfig, LLMResponse, Message, MessageRole,
+};
+use serde_json::{json, Value};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// The messages the app adds after a reply with tool calls: the assistant
+/// turn with the calls, then one result per call
+async fn tool_turn(config: &FlexibleApiConfig, response: &LLMResponse) -> Vec<Message> {
+    let calls: Vec<Value> = response
+        .function_calls
+        .iter()
+        .map(|call| json!({ "id": call.id, "name": call.name, "arguments": call.arguments }))
+        .collect();
+    let mut messages = vec![Message {
+        function_call: Some(Value::Array(calls)),
+        ..message("a1", MessageRole::Assistant, "")
+    }];
+    for call in &response.function_calls {
+        let result =
+            builtin_tools::execute_function_tool(config, &session("s1"), &call.name, &call.arguments, None, None).await;
+        messages.push(Message {
+            function_response: Some(json!({ "id": call.id, "name": call.name, "response": result })),
+            ..message("f1", MessageRole::Function, "Function get_weather executed")
+        });
+    }
+    messages
+}
+
+#[wasm_bindgen_test]
+async fn openai_tool_call_round_trip() {
+    let fetch = MockFetch::install();
+    let config = config_for("openrouter");
+    let client = FlexibleLLMClient::new();
+    let mut messages = vec![message("u1", MessageRole::User, "Weather in Oslo?")];
+
+    fetch.reply_json(json!({
+        "choices": [{
+            "message": {
+                "role": "assistant",
+                "content": null,
+                "tool_calls": [{
+                    "id": "call_1",
+                    "type": "function",
+                    "function": { "name": "get_weather", "arguments": "{\"city\":\"Oslo\"}" }
+                }]
+            },
+            "finish_reason": "tool_calls"
+        }]
+    }));
+    let response = client.send_message(&messages, &config).await.unwrap();
+    assert_eq!(response.function_calls.len(), 1);
+    assert_eq!(response.function_calls[0].name, "get_weather");
+    assert_eq!(response.function_calls[0].arguments, json!({ "city": "Oslo" }));
+
+    messages.extend(tool_turn(&config, &response).await);
+    fetch.reply_json(json!({
+        "choices": [{
+            "message": { "role": "assistant", "content": "It is 21°C in Oslo." },
+            "finish_reason": "stop"
+        }]
+    }));
+    let response = client.send_message(&messages, &config).await.unwrap();
+    assert_eq!(response.content.as_deref(), Some("It is 21°C in Oslo."));
+    assert!(response.function_calls.is_empty());
+
+    let calls = fetch.calls();
+    assert_eq!(calls.len(), 2);
+    assert!(calls[0].url.ends_with("/chat/completions"));
+    assert_eq!(calls[0].method, "POST");
+    assert_eq!(calls[0].headers["authorization"], "Bearer test-key");
+    assert_eq!(calls[0].json()["tools"][0]["function"]["name"], "get_weather");
+
+    let sent = calls[1].json()["messages"].as_array().unwrap().clone();
+    let result = &sent[sent.len() - 1];
+    assert_eq!(result["role"], "tool");
+    assert_eq!(result["tool_call_id"], "call_1");
+    assert!(result["content"].as_str().unwrap().contains("21"));
+    assert_eq!(sent[sent.len() - 2]["tool_calls"][0]["id"], "call_1");
+}
+
+#[wasm_bindgen_test]
+async fn gemini_tool_call_round_trip() {
+    let fetch = MockFetch::install();
+    let config = config_for("gemini");
+    let client = FlexibleLLMClient::new();
+    let mut messages = vec![message("u1", MessageRole::User, "Weather in Oslo?")];
+
+    fetch.reply_json(json!({
+        "candidates": [{
+            "content": {
+                "role": "model",
+                "parts": [{ "functionCall": { "name": "get_weather", "args": { "city": "Oslo" } } }]
+            },
+            "finishReason": "STOP"
+        }]
+    }));
+    let response = client.send_message(&messages, &config).await.unwrap();
+    assert_eq!(response.function_calls.len(), 1);
+    assert_eq!(response.function_calls[0].arguments, json!({ "city": "Oslo" }));
+
+    messages.extend(tool_turn(&config, &response).await);
+    fetch.reply_json(json!({
+        "candidates": [{
+            "content": { "role": "model", "parts": [{ "text": "It is 21°C in Oslo." }] },
+            "finishReason": "STOP"
+        }]
+    }));
+    let response = client.send_message(&messages, &config).await.unwrap();
+    assert_eq!(response.content.as_deref(), Some("It is 21°C in Oslo."));
+
+    let calls = fetch.calls();
+    assert_eq!(calls.len(), 2);
+    assert!(calls[0].url.contains("gemini-2.5-flash:generateContent"));
+    assert_eq!(
+        calls[0].json()["tools"][0]["functionDeclarations"][0]["name"],
+        "get_weather"
+    );
+
+    let contents = calls[1].json()["contents"].as_array().unwrap().clone();
+    let result = &contents[contents.len() - 1]["parts"][0]["functionResponse"];
+    assert_eq!(result["name"], "get_weather");
+    assert_eq!(result["response"]["temperature"], 21);
+    assert!(contents[contents.len() - 2]["parts"][0]["functionCall"].is_object());
+}
+
+#[wasm_bindgen_test]
+async fn model_listing_keeps_chat_models() {
+    let fetch = MockFetch::install();
+    let config = config_for("openrouter");
+    fetch.reply_json(json!({
+        "data": [
+            { "id": "gpt-4o", "object": "model" },
+            { "id": "whisper-1", "object": "model" },
+            { "id": "meta-llama/llama-3.1-8b-instruct", "object": "model" }
+        ]
+    }));
+    let models = FlexibleLLMClient::new().get_available_models(&config).await.unwrap();
+    assert_eq!(models, ["gpt-4o", "meta-llama/llama-3.1-8b-instruct"]);
+    assert!(fetch.calls()[0].url.ends_with("/models"));
+}
+
+#[wasm_bindgen_test]
+async fn api_errors_reach_the_caller() {
+    let fetch = MockFetch::install();
+    fetch.reply(401, &[], r#"{"error": "bad key"}"#);
+    let error = FlexibleLLMClient::new()
+        .send_message(&[message("u1", MessageRole::User, "Hi")], &config_for("openrouter"))
+        .await
+        .unwrap_err();
+    assert!(error.starts_with("Invalid OpenAI API key"), "{}", error);
+    assert!(error.contains("bad key"));
+}
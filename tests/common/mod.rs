@@ -0,0 +1,142 @@
+// Shared helpers for the browser tests
+// `MockFetch` swaps the page's `fetch` for one that records each request and
+// answers with the replies queued by the test, in order. Every client goes
+// through `fetch` (gloo-net and web_sys alike), so whole flows run without a
+// network. The original `fetch` is put back when the mock is dropped.
+#![allow(dead_code)]
+
+use llm_playground_rs::llm_playground::{ChatSession, FlexibleApiConfig, FunctionTool, Message, MessageRole};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use wasm_bindgen::JsValue;
+
+const INSTALL: &str = r#"
+const state = { calls: [], replies: [], original: globalThis.fetch };
+globalThis.fetch = async (input, init) => {
+    const request = new Request(input, init);
+    state.calls.push({
+        url: request.url,
+        method: request.method,
+        headers: Object.fromEntries(request.headers),
+        body: await request.text(),
+    });
+    const reply = state.replies.shift()
+        ?? { status: 599, headers: {}, body: "No mock reply queued for " + request.url };
+    return new Response(reply.body, { status: reply.status, headers: reply.headers });
+};
+return state;
+"#;
+
+/// A request the app made
+#[derive(Debug, Deserialize)]
+pub struct RecordedCall {
+    pub url: String,
+    pub method: String,
+    pub headers: serde_json::Map<String, Value>,
+    pub body: String,
+}
+
+impl RecordedCall {
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.body).unwrap_or(Value::Null)
+    }
+}
+
+pub struct MockFetch {
+    state: JsValue,
+}
+
+impl MockFetch {
+    pub fn install() -> Self {
+        let state = js_sys::Function::new_no_args(INSTALL)
+            .call0(&JsValue::NULL)
+            .expect("fetch mock installs");
+        Self { state }
+    }
+
+    /// Queue a reply with the given status, headers and body
+    pub fn reply(&self, status: u16, headers: &[(&str, &str)], body: &str) {
+        let headers: serde_json::Map<String, Value> = headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), json!(value)))
+            .collect();
+        let reply = json!({ "status": status, "headers": headers, "body": body });
+        let reply = js_sys::JSON::parse(&reply.to_string()).unwrap();
+        let replies: js_sys::Array = js_sys::Reflect::get(&self.state, &"replies".into())
+            .unwrap()
+            .into();
+        replies.push(&reply);
+    }
+
+    /// Queue a 200 reply with a JSON body
+    pub fn reply_json(&self, body: Value) {
+        self.reply(200, &[("content-type", "application/json")], &body.to_string());
+    }
+
+    /// Requests made so far, oldest first
+    pub fn calls(&self) -> Vec<RecordedCall> {
+        let calls = js_sys::Reflect::get(&self.state, &"calls".into()).unwrap();
+        let json = js_sys::JSON::stringify(&calls).unwrap();
+        serde_json::from_str(&String::from(json)).unwrap()
+    }
+}
+
+impl Drop for MockFetch {
+    fn drop(&mut self) {
+        if let Ok(original) = js_sys::Reflect::get(&self.state, &"original".into()) {
+            let _ = js_sys::Reflect::set(&js_sys::global(), &"fetch".into(), &original);
+        }
+    }
+}
+
+pub fn message(id: &str, role: MessageRole, content: &str) -> Message {
+    Message {
+        id: id.to_string(),
+        ..Message::new(role, content)
+    }
+}
+
+/// An empty session, with every optional field left at its default
+pub fn session(id: &str) -> ChatSession {
+    serde_json::from_value(json!({
+        "id": id,
+        "title": "Test",
+        "messages": [],
+        "created_at": 0.0,
+        "updated_at": 0.0,
+        "pinned": false,
+    }))
+    .expect("session deserializes")
+}
+
+pub fn weather_tool() -> FunctionTool {
+    FunctionTool {
+        name: "get_weather".to_string(),
+        description: "Current weather for a city".to_string(),
+        parameters: json!({"type": "object", "properties": {"city": {"type": "string"}}}),
+        mock_response: r#"{"city": "Oslo", "temperature": 21}"#.to_string(),
+        enabled: true,
+        category: "Custom".to_string(),
+        is_builtin: false,
+        requires: None,
+    }
+}
+
+/// Default config on `provider`'s first model, with a key and only the weather tool
+pub fn config_for(provider: &str) -> FlexibleApiConfig {
+    let mut config = FlexibleApiConfig {
+        function_tools: vec![weather_tool()],
+        ..FlexibleApiConfig::default()
+    };
+    let model = {
+        let provider = config
+            .providers
+            .iter_mut()
+            .find(|candidate| candidate.name == provider)
+            .expect("provider is in the default config");
+        provider.api_key = "test-key".to_string();
+        provider.models[0].clone()
+    };
+    config.set_session_provider(provider, &model);
+    config
+}
@@ -0,0 +1,130 @@
+// MCP discovery against a mocked server: initialize, list tools, then call
+// one with the session id the server handed out
+#![cfg(target_arch = "wasm32")]
+
+mod common;
+
+use common::MockFetch;
+use llm_playground_rs::llm_playground::mcp_client::{McpClient, McpConfig, McpServerConfig};
+use serde_json::json;
+use std::collections::HashMap;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const SERVER_URL: &str = "https://mcp.example.com/mcp";
+
+fn client() -> McpClient {
+    let server = McpServerConfig {
+        name: "Docs".to_string(),
+        server_type: "http".to_string(),
+        url: Some(SERVER_URL.to_string()),
+        headers: Some(HashMap::from([("Authorization".to_string(), "Bearer mcp-token".to_string())])),
+        enabled: true,
+        tool_prefix: None,
+    };
+    McpClient::new(McpConfig {
+        servers: HashMap::from([("docs".to_string(), server)]),
+    })
+}
+
+#[wasm_bindgen_test]
+async fn tools_are_discovered_and_called_in_the_session() {
+    let fetch = MockFetch::install();
+    fetch.reply(
+        200,
+        &[("content-type", "application/json"), ("mcp-session-id", "session-42")],
+        &json!({ "jsonrpc": "2.0", "id": "1", "result": { "protocolVersion": "2024-11-05" } }).to_string(),
+    );
+    fetch.reply_json(json!({
+        "jsonrpc": "2.0",
+        "id": "2",
+        "result": { "tools": [{
+            "name": "search-docs",
+            "description": "Search the docs",
+            "inputSchema": { "type": "object", "properties": { "query": { "type": "string" } } }
+        }] }
+    }));
+
+    let mut client = client();
+    client.initialize().await.unwrap();
+
+    let tools = client.get_function_tools();
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0].name, "mcp_docs_search-docs");
+    assert_eq!(tools[0].parameters["properties"]["query"]["type"], "string");
+    assert!(client.is_mcp_tool("mcp_docs_search-docs"));
+
+    fetch.reply_json(json!({
+        "jsonrpc": "2.0",
+        "id": "3",
+        "result": { "content": [{ "type": "text", "text": "Found 2 pages" }] }
+    }));
+    let result = client
+        .call_tool("mcp_docs_search-docs", &json!({ "query": "install" }))
+        .await
+        .unwrap();
+    assert_eq!(result["content"][0]["text"], "Found 2 pages");
+
+    let calls = fetch.calls();
+    let methods: Vec<_> = calls.iter().map(|call| call.json()["method"].clone()).collect();
+    assert_eq!(methods, ["initialize", "tools/list", "tools/call"]);
+    assert!(calls.iter().all(|call| call.url == SERVER_URL));
+    assert!(calls.iter().all(|call| call.headers["authorization"] == "Bearer mcp-token"));
+    assert!(calls[0].headers.get("mcp-session-id").is_none());
+    assert_eq!(calls[1].headers["mcp-session-id"], "session-42");
+    assert_eq!(calls[2].headers["mcp-session-id"], "session-42");
+    assert_eq!(calls[2].json()["params"]["name"], "search-docs");
+}
+
+#[wasm_bindgen_test]
+async fn unprefixed_tools_with_the_same_name_are_renamed() {
+    let fetch = MockFetch::install();
+    let mut servers = HashMap::new();
+    for name in ["docs", "wiki"] {
+        let server = McpServerConfig {
+            name: name.to_string(),
+            server_type: "http".to_string(),
+            url: Some(format!("https://{}.example.com/mcp", name)),
+            headers: None,
+            enabled: true,
+            tool_prefix: Some(String::new()),
+        };
+        servers.insert(name.to_string(), server);
+        fetch.reply_json(json!({ "jsonrpc": "2.0", "id": "1", "result": {} }));
+        fetch.reply_json(json!({
+            "jsonrpc": "2.0",
+            "id": "2",
+            "result": { "tools": [{ "name": "search", "inputSchema": { "type": "object" } }] }
+        }));
+    }
+
+    let mut client = McpClient::new(McpConfig { servers });
+    client.initialize().await.unwrap();
+
+    // Servers connect in name order, so the second one's tool is renamed
+    let tools = client.get_function_tools();
+    let names: Vec<_> = tools.iter().map(|tool| tool.name.as_str()).collect();
+    assert_eq!(names, ["search", "search_2"]);
+    assert_eq!(client.get_available_tools()["search"].server_name, "docs");
+    assert_eq!(client.get_available_tools()["search_2"].server_name, "wiki");
+
+    fetch.reply_json(json!({ "jsonrpc": "2.0", "id": "3", "result": { "content": [] } }));
+    client.call_tool("search_2", &json!({})).await.unwrap();
+    let calls = fetch.calls();
+    let last = calls.last().unwrap();
+    assert_eq!(last.url, "https://wiki.example.com/mcp");
+    assert_eq!(last.json()["params"]["name"], "search");
+}
+
+#[wasm_bindgen_test]
+async fn a_failing_server_leaves_no_tools() {
+    let fetch = MockFetch::install();
+    fetch.reply(500, &[], "Internal Server Error");
+
+    let mut client = client();
+    // Failures are logged per server; the other servers still connect
+    client.initialize().await.unwrap();
+    assert!(client.get_function_tools().is_empty());
+    assert_eq!(fetch.calls().len(), 1);
+}
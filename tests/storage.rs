@@ -0,0 +1,201 @@
+// Storage round-trips through the browser's localStorage
+#![cfg(target_arch = "wasm32")]
+
+mod common;
+
+use common::{config_for, message};
+use gloo_storage::{LocalStorage, Storage};
+use llm_playground_rs::llm_playground::{components::confirm_dialog, session_pages, storage, storage_recovery, ChatSession, ConfirmAction, FlexibleApiConfig, MessageRole};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn session(id: &str, message_count: usize) -> ChatSession {
+    ChatSession {
+        id: id.to_string(),
+        title: id.to_string(),
+        messages: (0..message_count)
+            .map(|index| message(&format!("m{}", index), MessageRole::User, &format!("Message {}", index)))
+            .collect(),
+        created_at: 1.0,
+        updated_at: 2.0,
+        pinned: false,
+        pinned_messages: Vec::new(),
+        locked: false,
+        tags: vec!["work".to_string()],
+        archived: false,
+        todos: Vec::new(),
+        budget_since: 0.0,
+        dialogue: None,
+        persona: None,
+        incognito: false,
+        older_pages: 0,
+    }
+}
+
+#[wasm_bindgen_test]
+fn paged_sessions_come_back_whole() {
+    LocalStorage::clear();
+    let long = session("long", session_pages::PAGE_SIZE * 2 + 3);
+    let short = session("short", 2);
+    session_pages::save_all([&long, &short]);
+
+    let mut loaded = session_pages::load_all().unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded["short"], short);
+
+    let stored = loaded.remove("long").unwrap();
+    assert_eq!(stored.older_pages, 2);
+    assert_eq!(stored.messages.len(), 3);
+    assert_eq!(session_pages::message_count(&stored), long.messages.len());
+
+    let mut opened = stored.clone();
+    assert!(session_pages::load_recent(&mut opened));
+    assert_eq!(opened.messages.len(), session_pages::PAGE_SIZE + 3);
+
+    assert_eq!(session_pages::hydrated(&stored).messages, long.messages);
+}
+
+#[wasm_bindgen_test]
+fn removed_sessions_leave_no_pages() {
+    LocalStorage::clear();
+    // Its own id, so no page matches one already written by another test
+    let removed = session("removed", session_pages::PAGE_SIZE + 1);
+    session_pages::save_all([&removed]);
+    assert_eq!(LocalStorage::length(), 2);
+
+    session_pages::save_all(std::iter::empty());
+    assert_eq!(LocalStorage::length(), 1);
+    assert!(session_pages::load_all().unwrap().is_empty());
+}
+
+#[wasm_bindgen_test]
+fn provider_config_round_trips() {
+    let config = config_for("gemini");
+    let json = serde_json::to_string(&config).unwrap();
+    LocalStorage::set(storage::FLEXIBLE_CONFIG_KEY, json).unwrap();
+
+    let loaded: FlexibleApiConfig = storage::load_payload(storage::FLEXIBLE_CONFIG_KEY).unwrap();
+    assert_eq!(loaded, config);
+    assert_eq!(loaded.get_current_provider_and_model().0, "gemini");
+}
+
+#[wasm_bindgen_test]
+fn unversioned_sessions_are_migrated_with_a_backup() {
+    LocalStorage::clear();
+    let list = r#"{"s1": {"id": "s1", "title": "Old", "messages": [], "created_at": 3.0}}"#;
+    LocalStorage::set(session_pages::SESSIONS_KEY, list).unwrap();
+
+    storage::migrate_storage();
+    let stored: String = LocalStorage::get(session_pages::SESSIONS_KEY).unwrap();
+    let (version, _) = storage::open_envelope(serde_json::from_str(&stored).unwrap());
+    assert_eq!(version, Some(storage::SCHEMA_VERSION));
+    let backup: String =
+        LocalStorage::get(format!("llm_playground_backup_v0_{}", session_pages::SESSIONS_KEY)).unwrap();
+    assert_eq!(backup, list);
+    assert_eq!(session_pages::load_all().unwrap()["s1"].updated_at, 3.0);
+
+    // Nothing runs a second time
+    storage::migrate_storage();
+    assert_eq!(LocalStorage::length(), 2);
+}
+
+#[wasm_bindgen_test]
+fn payloads_migrated_before_an_interruption_are_not_migrated_again() {
+    LocalStorage::clear();
+    // The session list was rewritten, then the page wasn't
+    let list = r#"{"s1": {"id": "s1", "title": "Old", "messages": [], "created_at": 3.0, "older_pages": 1}}"#;
+    let migrated = storage::payload_json(&serde_json::from_str::<serde_json::Value>(list).unwrap()).unwrap();
+    LocalStorage::set(session_pages::SESSIONS_KEY, &migrated).unwrap();
+    let page_key = format!("{}s1_0", session_pages::PAGE_KEY_PREFIX);
+    LocalStorage::set(&page_key, "[]").unwrap();
+
+    storage::migrate_storage();
+    assert_eq!(LocalStorage::get::<String>(session_pages::SESSIONS_KEY).unwrap(), migrated);
+    let page: String = LocalStorage::get(&page_key).unwrap();
+    assert_eq!(storage::open_envelope(serde_json::from_str(&page).unwrap()).0, Some(storage::SCHEMA_VERSION));
+    assert_eq!(LocalStorage::length(), 2);
+}
+
+#[wasm_bindgen_test]
+fn unreadable_config_is_quarantined() {
+    LocalStorage::clear();
+    LocalStorage::set(storage::FLEXIBLE_CONFIG_KEY, r#"{"providers": 5}"#).unwrap();
+    assert!(storage::load_payload::<FlexibleApiConfig>(storage::FLEXIBLE_CONFIG_KEY).is_none());
+    assert!(LocalStorage::get::<String>(storage::FLEXIBLE_CONFIG_KEY).is_err());
+
+    // A second unreadable config doesn't replace the first
+    LocalStorage::set(storage::FLEXIBLE_CONFIG_KEY, "{").unwrap();
+    assert!(storage::load_payload::<FlexibleApiConfig>(storage::FLEXIBLE_CONFIG_KEY).is_none());
+
+    let quarantined = storage_recovery::quarantined();
+    assert_eq!(quarantined.len(), 2);
+    assert!(quarantined.iter().all(|payload| payload.key == storage::FLEXIBLE_CONFIG_KEY));
+    assert_eq!(quarantined[0].text, r#"{"providers": 5}"#);
+    assert_eq!(quarantined[1].text, "{");
+
+    let salvage = storage_recovery::salvage(&quarantined[0]).unwrap();
+    assert_eq!(salvage.skipped, 1);
+    storage_recovery::discard(&quarantined[0].quarantine_key);
+    assert_eq!(storage_recovery::quarantined().len(), 1);
+}
+
+#[wasm_bindgen_test]
+fn values_that_are_not_json_are_quarantined() {
+    LocalStorage::clear();
+    LocalStorage::raw().set_item(storage::FLEXIBLE_CONFIG_KEY, "not json").unwrap();
+    assert!(storage::load_payload::<FlexibleApiConfig>(storage::FLEXIBLE_CONFIG_KEY).is_none());
+    assert!(LocalStorage::raw().get_item(storage::FLEXIBLE_CONFIG_KEY).unwrap().is_none());
+
+    let quarantined = storage_recovery::quarantined();
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].text, "not json");
+}
+
+#[wasm_bindgen_test]
+fn unreadable_pages_are_quarantined_and_kept_counted() {
+    LocalStorage::clear();
+    let long = session("broken", session_pages::PAGE_SIZE * 2 + 3);
+    session_pages::save_all([&long]);
+    let page_key = format!("{}broken_1", session_pages::PAGE_KEY_PREFIX);
+    LocalStorage::raw().set_item(&page_key, "[{").unwrap();
+
+    let mut stored = session_pages::load_all().unwrap().remove("broken").unwrap();
+    assert!(!session_pages::load_older(&mut stored));
+    assert_eq!(stored.older_pages, 2);
+    assert_eq!(stored.messages.len(), 3);
+
+    let quarantined = storage_recovery::quarantined();
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].key, page_key);
+    assert_eq!(quarantined[0].label(), "Session messages");
+}
+
+#[wasm_bindgen_test]
+fn skipped_confirmations_are_remembered_per_action() {
+    LocalStorage::clear();
+    assert!(!confirm_dialog::has_skipped_confirmations());
+
+    ConfirmAction::DeleteSession.skip_from_now_on();
+    assert!(ConfirmAction::DeleteSession.is_skipped());
+    assert!(!ConfirmAction::DeleteProvider.is_skipped());
+    assert!(confirm_dialog::has_skipped_confirmations());
+
+    confirm_dialog::reset_skipped_confirmations();
+    assert!(!ConfirmAction::DeleteSession.is_skipped());
+}
+
+#[wasm_bindgen_test]
+fn incognito_sessions_are_never_written() {
+    LocalStorage::clear();
+    let kept = session("kept", 2);
+    let mut incognito = session("incognito", session_pages::PAGE_SIZE + 1);
+    incognito.incognito = true;
+    session_pages::save_all([&kept, &incognito]);
+
+    let stored = session_pages::load_all().unwrap();
+    assert!(stored.contains_key("kept"));
+    assert!(!stored.contains_key("incognito"));
+    let page_key = format!("{}incognito_0", session_pages::PAGE_KEY_PREFIX);
+    assert!(LocalStorage::raw().get_item(&page_key).unwrap().is_none());
+}